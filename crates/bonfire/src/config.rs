@@ -16,6 +16,7 @@ pub struct ProtocolConfiguration {
     protocol_version: i32,
     format: ProtocolFormat,
     session_token: Option<String>,
+    last_event: Option<String>,
 }
 
 impl ProtocolConfiguration {
@@ -24,11 +25,13 @@ impl ProtocolConfiguration {
         protocol_version: i32,
         format: ProtocolFormat,
         session_token: Option<String>,
+        last_event: Option<String>,
     ) -> Self {
         Self {
             protocol_version,
             format,
             session_token,
+            last_event,
         }
     }
 
@@ -74,6 +77,11 @@ impl ProtocolConfiguration {
         &self.session_token
     }
 
+    /// Get the id of the last event seen before reconnecting
+    pub fn get_last_event(&self) -> &Option<String> {
+        &self.last_event
+    }
+
     /// Get the protocol version specified
     pub fn get_protocol_version(&self) -> i32 {
         self.protocol_version
@@ -112,6 +120,7 @@ impl handshake::server::Callback for WebsocketHandshakeCallback {
         let mut protocol_version = 1;
         let mut format = ProtocolFormat::Json;
         let mut session_token = None;
+        let mut last_event = None;
 
         // Parse and map parameters from key-value to known variables.
         for (key, value) in params {
@@ -127,6 +136,7 @@ impl handshake::server::Callback for WebsocketHandshakeCallback {
                     _ => {}
                 },
                 "token" => session_token = Some(value.into()),
+                "last_event" => last_event = Some(value.into()),
                 _ => {}
             }
         }
@@ -139,6 +149,7 @@ impl handshake::server::Callback for WebsocketHandshakeCallback {
                 protocol_version,
                 format,
                 session_token,
+                last_event,
             })
             .is_ok()
         {
@@ -9,6 +9,7 @@ extern crate log;
 pub mod config;
 
 mod database;
+mod replay;
 mod websocket;
 
 #[async_std::main]
@@ -20,6 +21,14 @@ async fn main() {
     // Clean up the current region information.
     clear_region(None).await;
 
+    // Record recent events so reconnecting clients can catch up.
+    async_std::task::spawn(async {
+        loop {
+            replay::record().await;
+            async_std::task::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    });
+
     // Setup a TCP listener to accept WebSocket connections on.
     // By default, we bind to port 9000 on all interfaces.
     let bind = env::var("HOST").unwrap_or_else(|_| "0.0.0.0:9000".into());
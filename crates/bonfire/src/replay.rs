@@ -0,0 +1,373 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    env,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use fred::{
+    interfaces::{ClientLike, EventInterface, PubsubInterface},
+    types::RedisConfig,
+};
+use once_cell::sync::Lazy;
+use onechatsocial_quark::{
+    events::client::{EventV1, PublishedEvent},
+    redis_kiss::REDIS_URI,
+};
+use ulid::Ulid;
+
+use crate::websocket::decode_event;
+
+/// Maximum number of events kept for each channel
+static REPLAY_BUFFER_SIZE: Lazy<usize> = Lazy::new(|| {
+    env::var("REPLAY_BUFFER_SIZE")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(100)
+});
+
+/// How long events are kept for (in seconds)
+static REPLAY_BUFFER_TTL: Lazy<u64> = Lazy::new(|| {
+    env::var("REPLAY_BUFFER_TTL")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(300)
+});
+
+/// Buffer of recent events shared by all clients on this node
+pub static REPLAY_BUFFER: Lazy<ReplayBuffer> =
+    Lazy::new(|| ReplayBuffer::new(*REPLAY_BUFFER_SIZE, *REPLAY_BUFFER_TTL * 1000));
+
+/// Result of asking for events missed since some point
+pub enum Backfill {
+    /// Events the client missed, oldest first
+    Events(Vec<PublishedEvent>),
+    /// Some events are no longer available, client must refetch state
+    Resync,
+}
+
+/// Recent events of a single channel
+#[derive(Default)]
+struct ChannelEvents {
+    /// Buffered events, oldest first
+    events: VecDeque<(Ulid, PublishedEvent)>,
+    /// Id of the newest event dropped to make space
+    evicted: Option<Ulid>,
+}
+
+/// Bounded per-channel buffer of message events
+///
+/// Events are keyed by the ULID assigned when they were published, which
+/// is the same on every node, so clients can replay from the id of the
+/// last event they received regardless of which node they reconnect to.
+pub struct ReplayBuffer {
+    size: usize,
+    ttl: u64,
+    started: AtomicU64,
+    channels: Mutex<HashMap<String, ChannelEvents>>,
+    swept: AtomicU64,
+}
+
+impl ReplayBuffer {
+    /// Create a new buffer holding `size` events per channel for `ttl` milliseconds
+    pub fn new(size: usize, ttl: u64) -> Self {
+        Self {
+            size,
+            ttl,
+            started: AtomicU64::new(Ulid::new().timestamp_ms()),
+            channels: Mutex::new(HashMap::new()),
+            swept: AtomicU64::new(0),
+        }
+    }
+
+    /// Record an event if it should be replayed to reconnecting clients
+    pub fn record(&self, event: &PublishedEvent) {
+        let Some(channel) = replay_channel(&event.event) else {
+            return;
+        };
+
+        let Some(id) = event
+            .event_id
+            .as_deref()
+            .and_then(|id| Ulid::from_string(id).ok())
+        else {
+            return;
+        };
+
+        let now = Ulid::new().timestamp_ms();
+        let cutoff = now.saturating_sub(self.ttl);
+
+        let mut channels = self
+            .channels
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        // Periodically forget channels which have not seen any events recently
+        if self.swept.load(Ordering::Relaxed) < cutoff {
+            self.swept.store(now, Ordering::Relaxed);
+            channels.retain(|_, buffer| {
+                buffer
+                    .events
+                    .back()
+                    .map_or(false, |(id, _)| id.timestamp_ms() >= cutoff)
+            });
+        }
+
+        let buffer = channels.entry(channel.to_string()).or_default();
+        buffer.events.push_back((id, event.clone()));
+
+        while buffer.events.len() > self.size {
+            if let Some((id, _)) = buffer.events.pop_front() {
+                buffer.evicted = buffer.evicted.max(Some(id));
+            }
+        }
+    }
+
+    /// Forget all buffered events, such as after missing some events
+    pub fn reset(&self) {
+        let mut channels = self
+            .channels
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        channels.clear();
+        self.started
+            .store(Ulid::new().timestamp_ms(), Ordering::Relaxed);
+    }
+
+    /// Find all events after the given ULID in channels accepted by `filter`
+    pub fn backfill(&self, last_event: &str, filter: impl Fn(&str) -> bool) -> Backfill {
+        let Ok(last_event) = Ulid::from_string(last_event) else {
+            return Backfill::Resync;
+        };
+
+        // Anything from before we started recording or outside
+        // the retention window may have been missed entirely
+        let cutoff = Ulid::new().timestamp_ms().saturating_sub(self.ttl);
+        let started = self.started.load(Ordering::Relaxed);
+        if last_event.timestamp_ms() < started.max(cutoff) {
+            return Backfill::Resync;
+        }
+
+        let channels = self
+            .channels
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let mut events = vec![];
+        for (channel, buffer) in channels.iter() {
+            if !filter(channel) {
+                continue;
+            }
+
+            if buffer.evicted.map_or(false, |evicted| last_event < evicted) {
+                return Backfill::Resync;
+            }
+
+            events.extend(
+                buffer
+                    .events
+                    .iter()
+                    .filter(|(id, _)| id > &last_event)
+                    .cloned(),
+            );
+        }
+
+        events.sort_by_key(|(id, _)| *id);
+        Backfill::Events(events.into_iter().map(|(_, event)| event).collect())
+    }
+}
+
+/// Find the channel a message event belongs to, if it may be replayed
+fn replay_channel(event: &EventV1) -> Option<&str> {
+    match event {
+        EventV1::Message(message) => Some(&message.channel),
+        EventV1::MessageUpdate { channel, .. }
+        | EventV1::MessageAppend { channel, .. }
+        | EventV1::MessageDelete { channel, .. }
+        | EventV1::BulkMessageDelete { channel, .. }
+        | EventV1::MessageReact {
+            channel_id: channel,
+            ..
+        }
+        | EventV1::MessageUnreact {
+            channel_id: channel,
+            ..
+        }
+        | EventV1::MessageRemoveReaction {
+            channel_id: channel,
+            ..
+        }
+        | EventV1::MessageReactionRemoveAll {
+            channel_id: channel,
+            ..
+        }
+        | EventV1::MessageReactionBulk {
+            channel_id: channel,
+            ..
+        } => Some(channel),
+        _ => None,
+    }
+}
+
+/// Record all published events into the replay buffer
+///
+/// Returns if the subscription fails, after which the buffer
+/// must be considered incomplete and is reset.
+pub async fn record() {
+    let redis_config = RedisConfig::from_url(&REDIS_URI).unwrap();
+    let Ok(subscriber) = fred::types::Builder::from_config(redis_config).build_subscriber_client()
+    else {
+        return;
+    };
+    if subscriber.init().await.is_err() || subscriber.psubscribe("*").await.is_err() {
+        return;
+    }
+
+    REPLAY_BUFFER.reset();
+
+    let mut message_rx = subscriber.message_rx();
+    loop {
+        let message = match message_rx.recv().await {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Error while recording events for replay: {e:?}");
+                REPLAY_BUFFER.reset();
+                return;
+            }
+        };
+
+        if let Some(event) = decode_event(&message) {
+            REPLAY_BUFFER.record(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use onechatsocial_quark::events::client::{EventV1, PublishedEvent};
+    use ulid::Ulid;
+
+    use super::{Backfill, ReplayBuffer};
+
+    fn publish(channel: &str, id: Ulid) -> PublishedEvent {
+        PublishedEvent {
+            event_id: Some(id.to_string()),
+            event: EventV1::MessageDelete {
+                id: Ulid::new().to_string(),
+                channel: channel.to_string(),
+            },
+        }
+    }
+
+    /// Create an id for an event published `n` events after another
+    fn after(id: Ulid, n: u128) -> Ulid {
+        Ulid(id.0 + n)
+    }
+
+    /// Create an id for an event published some time ago
+    fn published_ago(ago: Duration) -> Ulid {
+        let timestamp = Ulid::new().timestamp_ms() - ago.as_millis() as u64;
+        Ulid((timestamp as u128) << 80)
+    }
+
+    fn event_ids(backfill: Backfill) -> Vec<String> {
+        match backfill {
+            Backfill::Events(events) => events
+                .into_iter()
+                .map(|event| event.event_id.unwrap())
+                .collect(),
+            Backfill::Resync => panic!("expected events"),
+        }
+    }
+
+    #[test]
+    fn replays_newer_events_in_visible_channels() {
+        let buffer = ReplayBuffer::new(10, 60_000);
+        let last_event = Ulid::new();
+
+        let first = publish("a", after(last_event, 1));
+        let hidden = publish("b", after(last_event, 2));
+        let second = publish("a", after(last_event, 3));
+        for event in [&first, &hidden, &second] {
+            buffer.record(event);
+        }
+
+        // Events without an id are never recorded
+        buffer.record(&PublishedEvent {
+            event_id: None,
+            ..publish("a", Ulid::new())
+        });
+
+        assert_eq!(
+            event_ids(buffer.backfill(&last_event.to_string(), |channel| channel == "a")),
+            vec![first.event_id.clone().unwrap(), second.event_id.unwrap()]
+        );
+
+        // Events up to and including the last event are not replayed again
+        assert_eq!(
+            event_ids(buffer.backfill(&first.event_id.unwrap(), |channel| channel == "a")).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn resync_after_eviction() {
+        let buffer = ReplayBuffer::new(2, 60_000);
+        let last_event = Ulid::new();
+
+        let events: Vec<PublishedEvent> = (1..=3)
+            .map(|n| publish("a", after(last_event, n)))
+            .collect();
+        for event in &events {
+            buffer.record(event);
+        }
+
+        // The oldest event was dropped, so it can not be replayed
+        assert!(matches!(
+            buffer.backfill(&last_event.to_string(), |_| true),
+            Backfill::Resync
+        ));
+
+        // Clients which already received the dropped event can still catch up
+        let evicted = events[0].event_id.clone().unwrap();
+        assert_eq!(event_ids(buffer.backfill(&evicted, |_| true)).len(), 2);
+
+        // Eviction only affects the channel it happened in
+        assert!(
+            event_ids(buffer.backfill(&last_event.to_string(), |channel| channel == "b"))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn resync_outside_of_ttl() {
+        let buffer = ReplayBuffer::new(10, 1_000);
+        buffer.record(&publish("a", Ulid::new()));
+
+        // Events from before the retention window may have been missed
+        let expired = published_ago(Duration::from_secs(5));
+        assert!(matches!(
+            buffer.backfill(&expired.to_string(), |_| true),
+            Backfill::Resync
+        ));
+
+        // As may events from before the buffer started recording
+        buffer.reset();
+        let before_reset = published_ago(Duration::from_millis(500));
+        assert!(matches!(
+            buffer.backfill(&before_reset.to_string(), |_| true),
+            Backfill::Resync
+        ));
+
+        // Ids which are not ULIDs can not be replayed from
+        assert!(matches!(
+            buffer.backfill("not an id", |_| true),
+            Backfill::Resync
+        ));
+    }
+}
@@ -1,20 +1,21 @@
-use std::net::SocketAddr;
+use std::{collections::HashSet, net::SocketAddr};
 
 use async_tungstenite::WebSocketStream;
 use fred::{
     interfaces::{ClientLike, EventInterface, PubsubInterface},
-    types::RedisConfig,
+    types::{Message, RedisConfig},
 };
 use futures::{
-    channel::oneshot,
+    channel::{mpsc, oneshot},
     pin_mut, select,
     stream::{SplitSink, SplitStream},
     FutureExt, SinkExt, StreamExt, TryStreamExt,
 };
 use onechatsocial_presence::{create_session, delete_session};
 use onechatsocial_quark::{
+    authifier::AuthifierEvent,
     events::{
-        client::EventV1,
+        client::{EventV1, PublishedEvent},
         server::ClientMessage,
         state::{State, SubscriptionStateChange},
     },
@@ -25,11 +26,17 @@ use onechatsocial_quark::{
 
 use async_std::{net::TcpStream, sync::Mutex};
 
-use crate::config::{ProtocolConfiguration, WebsocketHandshakeCallback};
+use crate::{
+    config::{ProtocolConfiguration, WebsocketHandshakeCallback},
+    replay::{Backfill, REPLAY_BUFFER},
+};
 
 type WsReader = SplitStream<WebSocketStream<TcpStream>>;
 type WsWriter = SplitSink<WebSocketStream<TcpStream>, async_tungstenite::tungstenite::Message>;
 
+/// Request to watch presence for a set of server members
+type PresenceRequest = (String, Vec<String>);
+
 /// Start a new WebSocket client worker given access to the database,
 /// the relevant TCP stream and the remote address of the client.
 pub async fn client(db: &'static Database, stream: TcpStream, addr: SocketAddr) {
@@ -115,10 +122,12 @@ pub async fn client(db: &'static Database, stream: TcpStream, addr: SocketAddr)
 
     {
         let write = Mutex::new(write);
+        // Forward presence subscription requests from the reader to the listener.
+        let (presence_tx, presence_rx) = mpsc::unbounded();
         // Create a PubSub connection to poll on.
-        let listener = listener(db, &mut state, addr, &config, &write).fuse();
+        let listener = listener(db, &mut state, addr, &config, &write, presence_rx).fuse();
         // Read from WebSocket stream.
-        let worker = worker(addr, user_id.clone(), &config, read, &write).fuse();
+        let worker = worker(addr, user_id.clone(), &config, read, &write, presence_tx).fuse();
 
         // Pin both tasks.
         pin_mut!(listener, worker);
@@ -144,6 +153,7 @@ async fn listener(
     addr: SocketAddr,
     config: &ProtocolConfiguration,
     write: &Mutex<WsWriter>,
+    mut presence_rx: mpsc::UnboundedReceiver<PresenceRequest>,
 ) {
     let redis_config = RedisConfig::from_url(&REDIS_URI).unwrap();
     let Ok(subscriber) = fred::types::Builder::from_config(redis_config).build_subscriber_client()
@@ -154,6 +164,11 @@ async fn listener(
         return;
     };
     let mut message_rx = subscriber.message_rx();
+
+    // Client may ask to replay events it missed since it was last connected,
+    // we only do so once subscribed so no events are lost in between.
+    let mut last_event = config.get_last_event().clone();
+    let mut replayed = HashSet::new();
     loop {
         // Check for state changes for subscriptions.
         match state.apply_state() {
@@ -184,37 +199,91 @@ async fn listener(
             SubscriptionStateChange::None => {}
         }
 
+        if let Some(last_event) = last_event.take() {
+            let backfill = REPLAY_BUFFER.backfill(&last_event, |channel| {
+                state.cache.channels.contains_key(channel)
+            });
+
+            let events = match backfill {
+                Backfill::Events(events) => events,
+                Backfill::Resync => vec![PublishedEvent {
+                    event_id: None,
+                    event: EventV1::ResyncRequired,
+                }],
+            };
+
+            let mut write = write.lock().await;
+            for event in events {
+                // Remember what was replayed, these may also arrive through our subscription
+                if let Some(id) = &event.event_id {
+                    replayed.insert(id.clone());
+                }
+
+                if write.send(config.encode(&event)).await.is_err() {
+                    return;
+                }
+            }
+        }
+
+        // Handle presence subscription requests, re-applying state afterwards.
+        let message = select! {
+            message = message_rx.recv().fuse() => message,
+            request = presence_rx.next() => {
+                if let Some((server, users)) = request {
+                    if let Err(err) = state.subscribe_presence(db, &server, users).await {
+                        write.lock().await.send(config.encode(&err)).await.ok();
+                    }
+                }
+
+                continue;
+            }
+        };
+
         // Handle incoming events.
-        let Ok(message) = message_rx.recv().await.map_err(|e| {
+        let Ok(message) = message.map_err(|e| {
             warn!("Error while consuming pub/sub messages: {e:?}");
             sentry::capture_error(&e);
         }) else {
             return;
         };
-        let event = match *REDIS_PAYLOAD_TYPE {
-            PayloadType::Json => message
-                .value
-                .as_str()
-                .and_then(|s| serde_json::from_str::<EventV1>(s.as_ref()).ok()),
-            PayloadType::Msgpack => message
-                .value
-                .as_bytes()
-                .and_then(|b| rmp_serde::from_slice::<EventV1>(b).ok()),
-            PayloadType::Bincode => message
-                .value
-                .as_bytes()
-                .and_then(|b| bincode::deserialize::<EventV1>(b).ok()),
-        };
-        let Some(mut event) = event else {
+        let Some(PublishedEvent {
+            event_id,
+            mut event,
+        }) = decode_event(&message)
+        else {
             warn!("Failed to deserialise an event for {}!", message.channel);
             return;
         };
+
+        // Skip events which the client has already been sent through replay.
+        if event_id.as_ref().is_some_and(|id| replayed.remove(id)) {
+            continue;
+        }
+
+        // Disconnect if the session used by this connection was revoked.
+        if let EventV1::Auth(
+            AuthifierEvent::DeleteSession { .. } | AuthifierEvent::DeleteAllSessions { .. },
+        ) = &event
+        {
+            let Some(token) = config.get_session_token() else {
+                return;
+            };
+
+            if let Err(err) = User::from_token(db, token, UserHint::Any).await {
+                write.lock().await.send(config.encode(&err)).await.ok();
+                return;
+            }
+        }
         let should_send = state.handle_incoming_event_v1(db, &mut event).await;
         if !should_send {
             continue;
         }
 
-        let result = write.lock().await.send(config.encode(&event)).await;
+        let result = write
+            .lock()
+            .await
+            .send(config.encode(&PublishedEvent { event_id, event }))
+            .await;
         if let Err(e) = result {
             use async_tungstenite::tungstenite::Error;
             if !matches!(e, Error::AlreadyClosed | Error::ConnectionClosed) {
@@ -225,12 +294,31 @@ async fn listener(
     }
 }
 
+/// Decode an event received through Redis pub/sub
+pub fn decode_event(message: &Message) -> Option<PublishedEvent> {
+    match *REDIS_PAYLOAD_TYPE {
+        PayloadType::Json => message
+            .value
+            .as_str()
+            .and_then(|s| serde_json::from_str::<PublishedEvent>(s.as_ref()).ok()),
+        PayloadType::Msgpack => message
+            .value
+            .as_bytes()
+            .and_then(|b| rmp_serde::from_slice::<PublishedEvent>(b).ok()),
+        PayloadType::Bincode => message
+            .value
+            .as_bytes()
+            .and_then(|b| bincode::deserialize::<PublishedEvent>(b).ok()),
+    }
+}
+
 async fn worker(
     addr: SocketAddr,
     user_id: String,
     config: &ProtocolConfiguration,
     mut read: WsReader,
     write: &Mutex<WsWriter>,
+    presence_tx: mpsc::UnboundedSender<PresenceRequest>,
 ) {
     loop {
         let result = read.try_next().await;
@@ -266,6 +354,9 @@ async fn worker(
                 .p(channel.clone())
                 .await;
             }
+            ClientMessage::SubscribePresence { server, users } => {
+                presence_tx.unbounded_send((server, users)).ok();
+            }
             ClientMessage::Ping { data, responded } => {
                 if responded.is_none() {
                     write
@@ -85,6 +85,7 @@ pub struct ApiSecurity {
 #[derive(Deserialize, Debug, Clone)]
 pub struct ApiWorkers {
     pub max_concurrent_connections: usize,
+    pub max_concurrent_attachments: usize,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -103,14 +104,24 @@ pub struct FeaturesLimits {
     pub group_size: usize,
     pub bots: usize,
     pub message_length: usize,
+    #[serde(default)]
+    pub message_length_overrides: HashMap<String, usize>,
     pub message_replies: usize,
+    pub message_reply_mentions: usize,
+    pub message_mentions: usize,
     pub message_attachments: usize,
     pub message_embeds: usize,
+    pub message_embed_length: usize,
+    pub message_embed_description_length: usize,
+    pub message_stickers: usize,
     pub message_reactions: usize,
+    pub message_reactions_per_user: usize,
+    pub channel_pins: usize,
     pub servers: usize,
     pub server_emoji: usize,
     pub server_roles: usize,
     pub server_channels: usize,
+    pub server_categories: usize,
 
     pub attachment_size: usize,
     pub avatar_size: usize,
@@ -118,6 +129,21 @@ pub struct FeaturesLimits {
     pub icon_size: usize,
     pub banner_size: usize,
     pub emoji_size: usize,
+    pub role_icon_size: usize,
+}
+
+impl FeaturesLimits {
+    /// Get the maximum message length for a given channel
+    ///
+    /// Overrides may be keyed by channel ID or by channel type,
+    /// falling back to the default message length otherwise.
+    pub fn message_length_for(&self, channel_id: &str, channel_type: &str) -> usize {
+        self.message_length_overrides
+            .get(channel_id)
+            .or_else(|| self.message_length_overrides.get(channel_type))
+            .copied()
+            .unwrap_or(self.message_length)
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -128,9 +154,50 @@ pub struct FeaturesLimitsCollection {
     pub roles: HashMap<String, FeaturesLimits>,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeaturesServerTier {
+    pub flag: u32,
+    pub limits: String,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Features {
     pub limits: FeaturesLimitsCollection,
+    pub reaction_summary_threshold: usize,
+    pub suppress_push_for_online: bool,
+    pub push_collapse_window: u64,
+    pub push_mention_only_threshold: usize,
+    pub message_delete_window: u64,
+    pub message_tombstone_retention: u64,
+    pub audit_log_retention: u64,
+    pub message_edit_history: usize,
+    pub reply_snapshots: bool,
+    pub reply_snapshot_length: usize,
+    pub message_idempotency_window: u64,
+    pub role_colour_gradients: bool,
+    pub reaction_rate_limit: u32,
+    pub reaction_rate_limit_period: u64,
+    pub reaction_batch_window: u64,
+    #[serde(default)]
+    pub reaction_batch_channels: Vec<String>,
+    pub permission_cache_ttl: u64,
+    pub thread_archive_after: u64,
+    #[serde(default)]
+    pub server_tiers: Vec<FeaturesServerTier>,
+}
+
+impl Features {
+    /// Get the effective limits for a server with the given flags
+    ///
+    /// The first configured tier whose flag is set on the server is used,
+    /// falling back to the default limits otherwise.
+    pub fn server_limits(&self, flags: u32) -> &FeaturesLimits {
+        self.server_tiers
+            .iter()
+            .filter(|tier| tier.flag != 0 && flags & tier.flag == tier.flag)
+            .find_map(|tier| self.limits.roles.get(&tier.limits))
+            .unwrap_or(&self.limits.default)
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -160,10 +227,36 @@ pub async fn config() -> Settings {
 #[cfg(feature = "test")]
 #[cfg(test)]
 mod tests {
-    use crate::init;
+    use crate::{config, init, FeaturesServerTier};
 
     #[async_std::test]
     async fn it_works() {
         init().await;
     }
+
+    #[async_std::test]
+    async fn server_tiers() {
+        let mut features = config().await.features;
+        let default = features.limits.default.server_emoji;
+
+        let mut boosted = features.limits.default.clone();
+        boosted.server_emoji = default + 100;
+        features.limits.roles.insert("boosted".to_string(), boosted);
+        features.server_tiers = vec![
+            FeaturesServerTier {
+                flag: 0,
+                limits: "boosted".to_string(),
+            },
+            FeaturesServerTier {
+                flag: 4,
+                limits: "boosted".to_string(),
+            },
+        ];
+
+        // Tiers only apply once their flag is set, whatever other flags there are
+        assert_eq!(features.server_limits(0).server_emoji, default);
+        assert_eq!(features.server_limits(2).server_emoji, default);
+        assert_eq!(features.server_limits(4).server_emoji, default + 100);
+        assert_eq!(features.server_limits(4 | 1).server_emoji, default + 100);
+    }
 }
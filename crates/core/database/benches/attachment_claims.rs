@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use futures::{stream, StreamExt, TryStreamExt};
+use onechatsocial_database::{AbstractAttachments, Database, File, ReferenceDb};
+use onechatsocial_result::Result;
+
+/// Number of attachments sent with the message
+const ATTACHMENTS: usize = 10;
+
+/// Number of attachments claimed at once, matches the default configuration
+const MAX_CONCURRENT_ATTACHMENTS: usize = 5;
+
+/// Simulated round-trip time to the database
+const ROUND_TRIP: Duration = Duration::from_millis(1);
+
+/// Create a database with unclaimed attachments
+async fn setup() -> (Database, Vec<String>) {
+    let db = Database::Reference(ReferenceDb::default());
+
+    let mut ids = vec![];
+    for i in 0..ATTACHMENTS {
+        let file = File {
+            id: ulid::Ulid::new().to_string(),
+            tag: "attachments".to_string(),
+            filename: format!("file{i}.png"),
+            metadata: Default::default(),
+            content_type: "image/png".to_string(),
+            size: 1024,
+            description: None,
+            is_voice: false,
+            waveform: None,
+            duration: None,
+            deleted: None,
+            reported: None,
+            message_id: None,
+            user_id: None,
+            server_id: None,
+            object_id: None,
+        };
+
+        db.insert_attachment(&file).await.unwrap();
+        ids.push(file.id);
+    }
+
+    (db, ids)
+}
+
+/// Claim a single attachment for a message
+async fn claim(db: &Database, id: &str) -> Result<File> {
+    async_std::task::sleep(ROUND_TRIP).await;
+    db.find_and_use_attachment(id, "attachments", "message", "message")
+        .await
+}
+
+/// Previous behaviour, each attachment is claimed after the last
+async fn claim_sequentially(db: &Database, ids: &[String]) -> Vec<File> {
+    let mut attachments = vec![];
+    for id in ids {
+        attachments.push(claim(db, id).await.unwrap());
+    }
+
+    attachments
+}
+
+/// Current behaviour, attachments are claimed concurrently while keeping their order
+async fn claim_buffered(db: &Database, ids: &[String]) -> Vec<File> {
+    stream::iter(ids)
+        .map(|id| claim(db, id))
+        .buffered(MAX_CONCURRENT_ATTACHMENTS)
+        .try_collect()
+        .await
+        .unwrap()
+}
+
+fn attachment_claims(c: &mut Criterion) {
+    let mut group = c.benchmark_group("attachment_claims");
+    group.bench_function("sequential", |b| {
+        b.iter_batched(
+            || async_std::task::block_on(setup()),
+            |(db, ids)| async_std::task::block_on(claim_sequentially(&db, &ids)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("buffered", |b| {
+        b.iter_batched(
+            || async_std::task::block_on(setup()),
+            |(db, ids)| async_std::task::block_on(claim_buffered(&db, &ids)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, attachment_claims);
+criterion_main!(benches);
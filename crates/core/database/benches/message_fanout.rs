@@ -0,0 +1,145 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use onechatsocial_database::{File, Message};
+use onechatsocial_models::v0::{self, Embed, MessageAuthor, PushNotification, Text};
+
+/// Allocator which counts the number of allocations made
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Message with the maximum number of attachments and embeds
+fn large_message() -> Message {
+    Message {
+        id: ulid::Ulid::new().to_string(),
+        channel: ulid::Ulid::new().to_string(),
+        author: ulid::Ulid::new().to_string(),
+        content: Some("a".repeat(2000)),
+        attachments: Some(
+            (0..10)
+                .map(|i| File {
+                    id: format!("file{i}"),
+                    tag: "attachments".to_string(),
+                    filename: format!("file{i}.png"),
+                    metadata: Default::default(),
+                    content_type: "image/png".to_string(),
+                    size: 1024,
+                    description: None,
+                    is_voice: false,
+                    waveform: None,
+                    duration: None,
+                    deleted: None,
+                    reported: None,
+                    message_id: None,
+                    user_id: None,
+                    server_id: None,
+                    object_id: None,
+                })
+                .collect(),
+        ),
+        embeds: Some(
+            (0..10)
+                .map(|i| {
+                    Embed::Text(Text {
+                        icon_url: None,
+                        url: Some(format!("https://example.com/{i}")),
+                        title: Some(format!("Embed {i}")),
+                        description: Some("b".repeat(500)),
+                        media: None,
+                        colour: None,
+                    })
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    }
+}
+
+/// Previous behaviour, the message is converted separately for the event and the notification
+async fn convert_twice(message: &Message) -> (v0::Message, PushNotification) {
+    let event: v0::Message = message.clone().into();
+    let notification = PushNotification::from(
+        &message.clone().into(),
+        Some(MessageAuthor::System {
+            username: "Author",
+            avatar: None,
+            by: None,
+        }),
+        &message.channel,
+        None,
+        None,
+        None,
+    )
+    .await;
+
+    (event, notification)
+}
+
+/// Current behaviour, the notification borrows the message converted for the event
+async fn convert_once(message: &Message) -> (v0::Message, PushNotification) {
+    let event: v0::Message = message.clone().into();
+    let notification = PushNotification::from(
+        &event,
+        Some(MessageAuthor::System {
+            username: "Author",
+            avatar: None,
+            by: None,
+        }),
+        &message.channel,
+        None,
+        None,
+        None,
+    )
+    .await;
+
+    (event, notification)
+}
+
+/// Count allocations made while running the given future
+fn count_allocations<F: std::future::Future>(future: F) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(async_std::task::block_on(future));
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn fanout(c: &mut Criterion) {
+    let message = large_message();
+
+    // Warm up the configuration so it is not counted
+    async_std::task::block_on(onechatsocial_config::config());
+
+    let twice = count_allocations(convert_twice(&message));
+    let once = count_allocations(convert_once(&message));
+    println!("allocations per send: {twice} converting twice, {once} converting once");
+    assert!(once < twice);
+
+    let mut group = c.benchmark_group("message_fanout");
+    group.bench_function("convert_twice", |b| {
+        b.iter(|| async_std::task::block_on(convert_twice(black_box(&message))))
+    });
+    group.bench_function("convert_once", |b| {
+        b.iter(|| async_std::task::block_on(convert_once(black_box(&message))))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, fanout);
+criterion_main!(benches);
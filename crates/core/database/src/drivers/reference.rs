@@ -1,29 +1,43 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::SystemTime};
 
 use futures::lock::Mutex;
 
 use crate::{
-    Bot, Channel, ChannelCompositeKey, ChannelUnread, Emoji, File, Invite, Member,
-    MemberCompositeKey, Message, RatelimitEvent, Server, ServerBan, User, UserSettings, Webhook,
+    AuditLogEntry, Bot, Channel, ChannelCompositeKey, ChannelFollow, ChannelNotificationPreference,
+    ChannelUnread, Emoji, File, Invite, Member, MemberCompositeKey, Message, MessageTombstone,
+    PendingMessageDeletion, RatelimitEvent, ReactionRole, ScheduledMessage, Server, ServerBan,
+    ServerNameFilter, ServerTemplate, Sticker, User, UserSettings, Webhook,
 };
 
 database_derived!(
     /// Reference implementation
     #[derive(Default)]
     pub struct ReferenceDb {
+        pub audit_log: Arc<Mutex<HashMap<String, AuditLogEntry>>>,
         pub bots: Arc<Mutex<HashMap<String, Bot>>>,
         pub channels: Arc<Mutex<HashMap<String, Channel>>>,
+        pub channel_follows: Arc<Mutex<HashMap<String, ChannelFollow>>>,
         pub channel_invites: Arc<Mutex<HashMap<String, Invite>>>,
+        pub channel_notification_preferences:
+            Arc<Mutex<HashMap<ChannelCompositeKey, ChannelNotificationPreference>>>,
         pub channel_unreads: Arc<Mutex<HashMap<ChannelCompositeKey, ChannelUnread>>>,
         pub channel_webhooks: Arc<Mutex<HashMap<String, Webhook>>>,
         pub emojis: Arc<Mutex<HashMap<String, Emoji>>>,
         pub files: Arc<Mutex<HashMap<String, File>>>,
         pub messages: Arc<Mutex<HashMap<String, Message>>>,
+        pub message_tombstones: Arc<Mutex<HashMap<String, MessageTombstone>>>,
+        pub pending_message_deletions: Arc<Mutex<HashMap<String, PendingMessageDeletion>>>,
         pub ratelimit_events: Arc<Mutex<HashMap<String, RatelimitEvent>>>,
+        pub reaction_cooldowns: Arc<Mutex<HashMap<String, SystemTime>>>,
+        pub reaction_roles: Arc<Mutex<HashMap<String, ReactionRole>>>,
+        pub scheduled_messages: Arc<Mutex<HashMap<String, ScheduledMessage>>>,
+        pub stickers: Arc<Mutex<HashMap<String, Sticker>>>,
         pub user_settings: Arc<Mutex<HashMap<String, UserSettings>>>,
         pub users: Arc<Mutex<HashMap<String, User>>>,
         pub server_bans: Arc<Mutex<HashMap<MemberCompositeKey, ServerBan>>>,
         pub server_members: Arc<Mutex<HashMap<MemberCompositeKey, Member>>>,
+        pub server_name_filters: Arc<Mutex<HashMap<String, ServerNameFilter>>>,
+        pub server_templates: Arc<Mutex<HashMap<String, ServerTemplate>>>,
         pub servers: Arc<Mutex<HashMap<String, Server>>>,
         pub safety_reports: Arc<Mutex<HashMap<String, ()>>>,
         pub safety_snapshots: Arc<Mutex<HashMap<String, ()>>>,
@@ -1,15 +1,17 @@
 use authifier::AuthifierEvent;
+use iso8601_timestamp::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use onechatsocial_models::v0::{
-    AppendMessage, Channel, Emoji, FieldsChannel, FieldsMember, FieldsRole, FieldsServer,
-    FieldsUser, FieldsWebhook, MemberCompositeKey, Message, PartialChannel, PartialMember,
-    PartialMessage, PartialRole, PartialServer, PartialUser, PartialWebhook, Server, User,
-    UserSettings, Webhook,
+    AppendMessage, Channel, Emoji, FieldsChannel, FieldsMember, FieldsMessage, FieldsRole,
+    FieldsServer, FieldsUser, FieldsWebhook, MemberCompositeKey, Message, PartialChannel,
+    PartialMember, PartialMessage, PartialRole, PartialServer, PartialUser, PartialWebhook,
+    ReactionDelta, Server, User, UserSettings, Webhook,
 };
 use onechatsocial_result::Error;
+use ulid::Ulid;
 
-use crate::Database;
+use crate::{util::permission_cache::invalidate_server, Database};
 
 /// WebSocket Client Errors
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -67,6 +69,7 @@ pub enum EventV1 {
         id: String,
         channel: String,
         data: PartialMessage,
+        clear: Vec<FieldsMessage>,
     },
 
     /// Append information to existing message
@@ -102,6 +105,16 @@ pub enum EventV1 {
         emoji_id: String,
     },
 
+    /// Remove all reactions from message
+    MessageReactionRemoveAll { id: String, channel_id: String },
+
+    /// Batch of reaction changes to a message
+    MessageReactionBulk {
+        id: String,
+        channel_id: String,
+        changes: Vec<ReactionDelta>,
+    },
+
     /// Bulk delete messages
     BulkMessageDelete { channel: String, ids: Vec<String> },
 
@@ -136,6 +149,9 @@ pub enum EventV1 {
     /// User left server
     ServerMemberLeave { id: String, user: String },
 
+    /// User was unbanned from server
+    ServerMemberUnban { id: String, user: String },
+
     /// Server role created or updated
     ServerRoleUpdate {
         id: String,
@@ -210,6 +226,13 @@ pub enum EventV1 {
         message_id: String,
     },
 
+    /// Messages pinned in a channel changed
+    ChannelPinsUpdate {
+        id: String,
+        pin_count: usize,
+        last_pinned_at: Option<Timestamp>,
+    },
+
     /// New webhook
     WebhookCreate(Webhook),
 
@@ -227,17 +250,41 @@ pub enum EventV1 {
     Auth(AuthifierEvent),
 }
 
+/// Event as published to subscribers
+///
+/// Every published event is given a ULID, which clients can pass back
+/// as `last_event` when reconnecting to replay the events they missed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublishedEvent {
+    /// Unique id assigned when the event was published
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<String>,
+    /// Published event
+    #[serde(flatten)]
+    pub event: EventV1,
+}
+
 impl EventV1 {
     /// Publish helper wrapper
     pub async fn p(self, channel: String) {
+        // Permissions calculated for this server may no longer be valid
+        if let Some(server) = self.permission_server() {
+            invalidate_server(server).await;
+        }
+
+        let event = PublishedEvent {
+            event_id: Some(Ulid::new().to_string()),
+            event: self,
+        };
+
         #[cfg(not(debug_assertions))]
-        redis_kiss::p(channel, self).await;
+        redis_kiss::p(channel, event).await;
 
         #[cfg(debug_assertions)]
-        info!("Publishing event to {channel}: {self:?}");
+        info!("Publishing event to {channel}: {event:?}");
 
         #[cfg(debug_assertions)]
-        redis_kiss::publish(channel, self).await.unwrap();
+        redis_kiss::publish(channel, event).await.unwrap();
     }
 
     /// Publish user event
@@ -261,4 +308,18 @@ impl EventV1 {
     pub async fn global(self) {
         self.p("global".to_string()).await;
     }
+
+    /// Find the server whose permissions may be affected by this event
+    fn permission_server(&self) -> Option<&str> {
+        match self {
+            EventV1::ServerUpdate { id, .. }
+            | EventV1::ServerDelete { id }
+            | EventV1::ServerMemberJoin { id, .. }
+            | EventV1::ServerMemberLeave { id, .. }
+            | EventV1::ServerRoleUpdate { id, .. }
+            | EventV1::ServerRoleDelete { id, .. } => Some(id),
+            EventV1::ServerMemberUpdate { id, .. } => Some(&id.server),
+            _ => None,
+        }
+    }
 }
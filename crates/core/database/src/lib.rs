@@ -87,3 +87,8 @@ pub mod tasks;
 pub fn if_false(t: &bool) -> bool {
     !t
 }
+
+/// Utility function to check if an u32 is zero
+pub fn if_zero_u32(t: &u32) -> bool {
+    t == &0
+}
@@ -80,6 +80,46 @@ pub async fn create_database(db: &MongoDb) {
         .await
         .expect("Failed to create ratelimit_events collection.");
 
+    db.create_collection("channel_follows", None)
+        .await
+        .expect("Failed to create channel_follows collection.");
+
+    db.create_collection("scheduled_messages", None)
+        .await
+        .expect("Failed to create scheduled_messages collection.");
+
+    db.create_collection("message_tombstones", None)
+        .await
+        .expect("Failed to create message_tombstones collection.");
+
+    db.create_collection("pending_message_deletions", None)
+        .await
+        .expect("Failed to create pending_message_deletions collection.");
+
+    db.create_collection("reaction_roles", None)
+        .await
+        .expect("Failed to create reaction_roles collection.");
+
+    db.create_collection("server_name_filters", None)
+        .await
+        .expect("Failed to create server_name_filters collection.");
+
+    db.create_collection("stickers", None)
+        .await
+        .expect("Failed to create stickers collection.");
+
+    db.create_collection("channel_notification_preferences", None)
+        .await
+        .expect("Failed to create channel_notification_preferences collection.");
+
+    db.create_collection("server_templates", None)
+        .await
+        .expect("Failed to create server_templates collection.");
+
+    db.create_collection("audit_log", None)
+        .await
+        .expect("Failed to create audit_log collection.");
+
     db.create_collection(
         "pubsub",
         CreateCollectionOptions::builder()
@@ -146,6 +186,33 @@ pub async fn create_database(db: &MongoDb) {
                         "author": 1_i32
                     },
                     "name": "author"
+                },
+                {
+                    "key": {
+                        "mentions": 1_i32,
+                        "_id": -1_i32
+                    },
+                    "name": "mentions_id_compound",
+                    "sparse": true
+                },
+                {
+                    "key": {
+                        "expires_at": 1_i32
+                    },
+                    "name": "expires_at",
+                    "sparse": true
+                },
+                {
+                    "key": {
+                        "replies": 1_i32,
+                        "author": 1_i32
+                    },
+                    "name": "replies_author",
+                    "partialFilterExpression": {
+                        "replies": {
+                            "$exists": true
+                        }
+                    }
                 }
             ]
         },
@@ -154,6 +221,31 @@ pub async fn create_database(db: &MongoDb) {
     .await
     .expect("Failed to create message index.");
 
+    db.run_command(
+        doc! {
+            "createIndexes": "channels",
+            "indexes": [
+                {
+                    "key": {
+                        "root_message": 1_i32
+                    },
+                    "name": "root_message",
+                    "sparse": true
+                },
+                {
+                    "key": {
+                        "parent": 1_i32
+                    },
+                    "name": "parent",
+                    "sparse": true
+                }
+            ]
+        },
+        None,
+    )
+    .await
+    .expect("Failed to create channels index.");
+
     db.run_command(
         doc! {
             "createIndexes": "channel_unreads",
@@ -194,6 +286,24 @@ pub async fn create_database(db: &MongoDb) {
                         "_id.user": 1_i32,
                     },
                     "name": "user_id"
+                },
+                {
+                    "key": {
+                        "_id.server": 1_i32,
+                        "nickname": 1_i32,
+                    },
+                    "name": "server_nickname",
+                    "collation": {
+                        "locale": "en",
+                        "strength": 2_i32
+                    }
+                },
+                {
+                    "key": {
+                        "timed_roles.expires_at": 1_i32,
+                    },
+                    "name": "timed_roles_expires_at",
+                    "sparse": true
                 }
             ]
         },
@@ -202,6 +312,24 @@ pub async fn create_database(db: &MongoDb) {
     .await
     .expect("Failed to create server_members index.");
 
+    db.run_command(
+        doc! {
+            "createIndexes": "server_bans",
+            "indexes": [
+                {
+                    "key": {
+                        "expires_at": 1_i32,
+                    },
+                    "name": "expires_at",
+                    "sparse": true
+                }
+            ]
+        },
+        None,
+    )
+    .await
+    .expect("Failed to create server_bans index.");
+
     db.collection("migrations")
         .insert_one(
             doc! {
@@ -232,5 +360,168 @@ pub async fn create_database(db: &MongoDb) {
     .await
     .expect("Failed to create ratelimit_events index.");
 
+    db.run_command(
+        doc! {
+            "createIndexes": "reaction_roles",
+            "indexes": [
+                {
+                    "key": {
+                        "message": 1_i32,
+                        "emoji": 1_i32,
+                    },
+                    "name": "message_emoji",
+                    "unique": true
+                }
+            ]
+        },
+        None,
+    )
+    .await
+    .expect("Failed to create reaction_roles index.");
+
+    db.run_command(
+        doc! {
+            "createIndexes": "stickers",
+            "indexes": [
+                {
+                    "key": {
+                        "server": 1_i32,
+                    },
+                    "name": "server"
+                }
+            ]
+        },
+        None,
+    )
+    .await
+    .expect("Failed to create stickers index.");
+
+    db.run_command(
+        doc! {
+            "createIndexes": "message_tombstones",
+            "indexes": [
+                {
+                    "key": {
+                        "channel": 1_i32,
+                        "_id": -1_i32,
+                    },
+                    "name": "channel_id"
+                },
+                {
+                    "key": {
+                        "message": 1_i32,
+                    },
+                    "name": "message"
+                }
+            ]
+        },
+        None,
+    )
+    .await
+    .expect("Failed to create message_tombstones index.");
+
+    db.run_command(
+        doc! {
+            "createIndexes": "channel_follows",
+            "indexes": [
+                {
+                    "key": {
+                        "channel": 1_i32,
+                        "follower": 1_i32,
+                    },
+                    "name": "channel_follower",
+                    "unique": true
+                },
+                {
+                    "key": {
+                        "follower": 1_i32,
+                    },
+                    "name": "follower"
+                }
+            ]
+        },
+        None,
+    )
+    .await
+    .expect("Failed to create channel_follows index.");
+
+    db.run_command(
+        doc! {
+            "createIndexes": "scheduled_messages",
+            "indexes": [
+                {
+                    "key": {
+                        "send_at": 1_i32,
+                    },
+                    "name": "send_at"
+                },
+                {
+                    "key": {
+                        "channel": 1_i32,
+                        "send_at": 1_i32,
+                    },
+                    "name": "channel_send_at"
+                }
+            ]
+        },
+        None,
+    )
+    .await
+    .expect("Failed to create scheduled_messages index.");
+
+    db.run_command(
+        doc! {
+            "createIndexes": "channel_notification_preferences",
+            "indexes": [
+                {
+                    "key": {
+                        "_id.channel": 1_i32,
+                        "_id.user": 1_i32,
+                    },
+                    "name": "compound_id",
+                    "unique": true
+                }
+            ]
+        },
+        None,
+    )
+    .await
+    .expect("Failed to create channel_notification_preferences index.");
+
+    db.run_command(
+        doc! {
+            "createIndexes": "audit_log",
+            "indexes": [
+                {
+                    "key": {
+                        "server": 1_i32,
+                        "_id": -1_i32,
+                    },
+                    "name": "server_id"
+                }
+            ]
+        },
+        None,
+    )
+    .await
+    .expect("Failed to create audit_log index.");
+
+    db.run_command(
+        doc! {
+            "createIndexes": "pending_message_deletions",
+            "indexes": [
+                {
+                    "key": {
+                        "finalise_at": 1_i32,
+                    },
+                    "name": "finalise_at"
+                }
+            ]
+        },
+        None,
+    )
+    .await
+    .expect("Failed to create pending_message_deletions index.");
+
     info!("Created database.");
 }
@@ -19,7 +19,7 @@ struct MigrationInfo {
     revision: i32,
 }
 
-pub const LATEST_REVISION: i32 = 26;
+pub const LATEST_REVISION: i32 = 45;
 
 pub async fn migrate_database(db: &MongoDb) {
     let migrations = db.col::<Document>("migrations");
@@ -983,6 +983,524 @@ pub async fn run_migrations(db: &MongoDb, revision: i32) -> i32 {
             .expect("Failed to create ratelimit_events index.");
     }
 
+    if revision <= 26 {
+        info!("Running migration [revision 26 / 16-10-2026]: Add collection `reaction_roles` with index.");
+
+        db.db()
+            .create_collection("reaction_roles", None)
+            .await
+            .ok();
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "reaction_roles",
+                    "indexes": [
+                        {
+                            "key": {
+                                "message": 1_i32,
+                                "emoji": 1_i32,
+                            },
+                            "name": "message_emoji",
+                            "unique": true
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create reaction_roles index.");
+    }
+
+    if revision <= 27 {
+        info!("Running migration [revision 27 / 16-10-2026]: Add collection `server_name_filters`.");
+
+        db.db()
+            .create_collection("server_name_filters", None)
+            .await
+            .ok();
+    }
+
+    if revision <= 28 {
+        info!("Running migration [revision 28 / 16-10-2026]: Add nickname index to `server_members`.");
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "server_members",
+                    "indexes": [
+                        {
+                            "key": {
+                                "_id.server": 1_i32,
+                                "nickname": 1_i32
+                            },
+                            "name": "server_nickname",
+                            "collation": {
+                                "locale": "en",
+                                "strength": 2_i32
+                            }
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create server_members nickname index.");
+    }
+
+    if revision <= 29 {
+        info!("Running migration [revision 29 / 16-10-2026]: Add collection `message_tombstones` with index.");
+
+        db.db()
+            .create_collection("message_tombstones", None)
+            .await
+            .ok();
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "message_tombstones",
+                    "indexes": [
+                        {
+                            "key": {
+                                "channel": 1_i32,
+                                "_id": -1_i32,
+                            },
+                            "name": "channel_id"
+                        },
+                        {
+                            "key": {
+                                "message": 1_i32,
+                            },
+                            "name": "message"
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create message_tombstones index.");
+    }
+
+    if revision <= 30 {
+        info!("Running migration [revision 30 / 16-10-2026]: Add collection `channel_follows` with index.");
+
+        db.db()
+            .create_collection("channel_follows", None)
+            .await
+            .ok();
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "channel_follows",
+                    "indexes": [
+                        {
+                            "key": {
+                                "channel": 1_i32,
+                                "follower": 1_i32,
+                            },
+                            "name": "channel_follower",
+                            "unique": true
+                        },
+                        {
+                            "key": {
+                                "follower": 1_i32,
+                            },
+                            "name": "follower"
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create channel_follows index.");
+    }
+
+    if revision <= 31 {
+        info!("Running migration [revision 31 / 16-10-2026]: Add index for mentions on messages.");
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "messages",
+                    "indexes": [
+                        {
+                            "key": {
+                                "mentions": 1_i32,
+                                "_id": -1_i32,
+                            },
+                            "name": "mentions_id_compound",
+                            "sparse": true
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create messages mentions index.");
+    }
+
+    if revision <= 32 {
+        info!("Running migration [revision 32 / 16-10-2026]: Add collection `scheduled_messages` with index.");
+
+        db.db()
+            .create_collection("scheduled_messages", None)
+            .await
+            .ok();
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "scheduled_messages",
+                    "indexes": [
+                        {
+                            "key": {
+                                "send_at": 1_i32,
+                            },
+                            "name": "send_at"
+                        },
+                        {
+                            "key": {
+                                "channel": 1_i32,
+                                "send_at": 1_i32,
+                            },
+                            "name": "channel_send_at"
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create scheduled_messages index.");
+    }
+
+    if revision <= 33 {
+        info!("Running migration [revision 33 / 16-10-2026]: Add index for expiring messages.");
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "messages",
+                    "indexes": [
+                        {
+                            "key": {
+                                "expires_at": 1_i32,
+                            },
+                            "name": "expires_at",
+                            "sparse": true
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create messages expires_at index.");
+    }
+
+    if revision <= 34 {
+        info!("Running migration [revision 34 / 16-10-2026]: Add collection `stickers` with index.");
+
+        db.db()
+            .create_collection("stickers", None)
+            .await
+            .ok();
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "stickers",
+                    "indexes": [
+                        {
+                            "key": {
+                                "server": 1_i32,
+                            },
+                            "name": "server"
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create stickers index.");
+    }
+
+    if revision <= 35 {
+        info!("Running migration [revision 35 / 16-10-2026]: Add index for timed member roles.");
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "server_members",
+                    "indexes": [
+                        {
+                            "key": {
+                                "timed_roles.expires_at": 1_i32,
+                            },
+                            "name": "timed_roles_expires_at",
+                            "sparse": true
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create server_members timed_roles index.");
+    }
+
+    if revision <= 36 {
+        info!("Running migration [revision 36 / 16-10-2026]: Add collection `channel_notification_preferences` with index.");
+
+        db.db()
+            .create_collection("channel_notification_preferences", None)
+            .await
+            .ok();
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "channel_notification_preferences",
+                    "indexes": [
+                        {
+                            "key": {
+                                "_id.channel": 1_i32,
+                                "_id.user": 1_i32,
+                            },
+                            "name": "compound_id",
+                            "unique": true
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create channel_notification_preferences index.");
+    }
+
+    if revision <= 37 {
+        info!("Running migration [revision 37 / 16-10-2026]: Add indexes for threads.");
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "channels",
+                    "indexes": [
+                        {
+                            "key": {
+                                "root_message": 1_i32
+                            },
+                            "name": "root_message",
+                            "sparse": true
+                        },
+                        {
+                            "key": {
+                                "parent": 1_i32
+                            },
+                            "name": "parent",
+                            "sparse": true
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create channels thread indexes.");
+    }
+
+    if revision <= 38 {
+        info!("Running migration [revision 38 / 16-10-2026]: Add collection `server_templates`.");
+
+        db.db()
+            .create_collection("server_templates", None)
+            .await
+            .ok();
+    }
+
+    if revision <= 39 {
+        info!("Running migration [revision 39 / 16-10-2026]: Add default role to servers.");
+
+        // Existing servers keep their current default permissions
+        db.col::<Document>("servers")
+            .update_many(
+                doc! { "roles.default": { "$exists": false } },
+                vec![doc! {
+                    "$set": {
+                        "roles.default": {
+                            "name": "Default",
+                            "permissions": {
+                                "a": "$default_permissions",
+                                "d": 0_i64
+                            },
+                            "rank": i64::MAX
+                        }
+                    }
+                }],
+                None,
+            )
+            .await
+            .expect("Failed to add default role to servers.");
+    }
+
+    if revision <= 40 {
+        info!("Running migration [revision 40 / 16-10-2026]: Add index for expiring bans.");
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "server_bans",
+                    "indexes": [
+                        {
+                            "key": {
+                                "expires_at": 1_i32,
+                            },
+                            "name": "expires_at",
+                            "sparse": true
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create server_bans expires_at index.");
+    }
+
+    if revision <= 41 {
+        info!("Running migration [revision 41 / 16-10-2026]: Add audit log collection.");
+
+        db.db().create_collection("audit_log", None).await.ok();
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "audit_log",
+                    "indexes": [
+                        {
+                            "key": {
+                                "server": 1_i32,
+                                "_id": -1_i32,
+                            },
+                            "name": "server_id"
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create audit_log index.");
+    }
+
+    if revision <= 42 {
+        info!("Running migration [revision 42 / 16-10-2026]: Add pending message deletions collection.");
+
+        db.db()
+            .create_collection("pending_message_deletions", None)
+            .await
+            .ok();
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "pending_message_deletions",
+                    "indexes": [
+                        {
+                            "key": {
+                                "finalise_at": 1_i32,
+                            },
+                            "name": "finalise_at"
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create pending_message_deletions index.");
+    }
+
+    if revision <= 43 {
+        info!("Running migration [revision 43 / 16-10-2026]: Count pinned messages on channels.");
+
+        let mut cursor = db
+            .col::<Document>("messages")
+            .aggregate(
+                vec![
+                    doc! {
+                        "$match": {
+                            "pinned": true
+                        }
+                    },
+                    doc! {
+                        "$group": {
+                            "_id": "$channel",
+                            "count": {
+                                "$sum": 1_i64
+                            },
+                            "last_pinned_at": {
+                                "$max": "$pinned_at"
+                            }
+                        }
+                    },
+                ],
+                None,
+            )
+            .await
+            .expect("Failed to count pinned messages.");
+
+        while let Some(Ok(document)) = cursor.next().await {
+            let mut pins = doc! {
+                "count": document.get("count").cloned().unwrap_or(Bson::Int64(0))
+            };
+
+            if let Some(last_pinned_at) = document.get("last_pinned_at") {
+                if !matches!(last_pinned_at, Bson::Null) {
+                    pins.insert("last_pinned_at", last_pinned_at.clone());
+                }
+            }
+
+            db.col::<Document>("channels")
+                .update_one(
+                    doc! {
+                        "_id": document.get("_id").cloned().unwrap_or(Bson::Null)
+                    },
+                    doc! {
+                        "$set": {
+                            "pins": pins
+                        }
+                    },
+                    None,
+                )
+                .await
+                .expect("Failed to update channel pins.");
+        }
+    }
+
+    if revision <= 44 {
+        info!("Running migration [revision 44 / 16-10-2026]: Add index for replies by author.");
+
+        db.db()
+            .run_command(
+                doc! {
+                    "createIndexes": "messages",
+                    "indexes": [
+                        {
+                            "key": {
+                                "replies": 1_i32,
+                                "author": 1_i32
+                            },
+                            "name": "replies_author",
+                            "partialFilterExpression": {
+                                "replies": {
+                                    "$exists": true
+                                }
+                            }
+                        }
+                    ]
+                },
+                None,
+            )
+            .await
+            .expect("Failed to create message replies index.");
+    }
+
     // Need to migrate fields on attachments, change `user_id`, `object_id`, etc to `parent`.
 
     // Reminder to update LATEST_REVISION when adding new migrations.
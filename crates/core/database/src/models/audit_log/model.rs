@@ -0,0 +1,240 @@
+use std::{collections::BTreeSet, time::Duration};
+
+use iso8601_timestamp::Timestamp;
+use onechatsocial_config::config;
+use onechatsocial_result::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{util::ulid_generator, Database};
+
+auto_derived!(
+    /// Record of a moderation or configuration action taken in a server
+    ///
+    /// Entries are never modified once written.
+    pub struct AuditLogEntry {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the server the action was taken in
+        pub server: String,
+        /// Id of the user who took the action
+        pub actor: String,
+        /// Type of action taken
+        pub action: AuditLogAction,
+        /// Id of the role, channel, category or user the action was taken on
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub target: Option<String>,
+        /// Values changed by the action
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub changes: Vec<AuditLogChange>,
+        /// Time at which the action was taken
+        pub created_at: Timestamp,
+    }
+
+    /// Type of action recorded in the audit log
+    pub enum AuditLogAction {
+        RoleCreate,
+        RoleUpdate,
+        RoleDelete,
+        RolePermissionsUpdate,
+        DefaultPermissionsUpdate,
+        ChannelUpdate,
+        ChannelPermissionsUpdate,
+        CategoryPermissionsUpdate,
+        MemberKick,
+        MemberBan,
+        MemberUnban,
+    }
+
+    /// Change to a single value, with both sides encoded as JSON
+    pub struct AuditLogChange {
+        /// Name of the changed value
+        pub key: String,
+        /// Value before the action, if it was set
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub before: Option<String>,
+        /// Value after the action, if it is set
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub after: Option<String>,
+    }
+);
+
+/// Encode a value as JSON, treating null as not being set
+fn encode(value: Option<&Value>) -> Option<String> {
+    value
+        .filter(|value| !value.is_null())
+        .map(|value| value.to_string())
+}
+
+impl AuditLogChange {
+    /// Record a change to a single value
+    pub fn new<T: Serialize>(key: &str, before: &T, after: &T) -> AuditLogChange {
+        AuditLogChange {
+            key: key.to_string(),
+            before: encode(serde_json::to_value(before).ok().as_ref()),
+            after: encode(serde_json::to_value(after).ok().as_ref()),
+        }
+    }
+
+    /// Find the fields which differ between two versions of an object
+    pub fn diff<T: Serialize>(before: &T, after: &T) -> Vec<AuditLogChange> {
+        let (Ok(Value::Object(before)), Ok(Value::Object(after))) =
+            (serde_json::to_value(before), serde_json::to_value(after))
+        else {
+            return vec![];
+        };
+
+        before
+            .keys()
+            .chain(after.keys())
+            .collect::<BTreeSet<&String>>()
+            .into_iter()
+            .filter(|key| before.get(*key) != after.get(*key))
+            .map(|key| AuditLogChange {
+                key: key.to_string(),
+                before: encode(before.get(key)),
+                after: encode(after.get(key)),
+            })
+            .filter(|change| change.before != change.after)
+            .collect()
+    }
+}
+
+impl AuditLogEntry {
+    /// Retention period for audit log entries, if they expire
+    pub async fn retention() -> Option<Duration> {
+        match config().await.features.audit_log_retention {
+            0 => None,
+            seconds => Some(Duration::from_secs(seconds)),
+        }
+    }
+
+    /// Record an action taken in a server
+    pub async fn record(
+        db: &Database,
+        server: &str,
+        actor: &str,
+        action: AuditLogAction,
+        target: Option<&str>,
+        changes: Vec<AuditLogChange>,
+    ) -> Result<()> {
+        db.insert_audit_log_entry(&AuditLogEntry {
+            id: ulid_generator::generate().to_string(),
+            server: server.to_string(),
+            actor: actor.to_string(),
+            action,
+            target: target.map(str::to_string),
+            changes,
+            created_at: Timestamp::now_utc(),
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use onechatsocial_permissions::OverrideField;
+
+    use crate::{AuditLogAction, AuditLogChange, AuditLogEntry, Role};
+
+    #[async_std::test]
+    async fn record_and_filter() {
+        database_test!(|db| async move {
+            let server = ulid::Ulid::new().to_string();
+            let moderator = ulid::Ulid::new().to_string();
+            let admin = ulid::Ulid::new().to_string();
+
+            let before = OverrideField { a: 1, d: 0 };
+            let after = OverrideField { a: 1, d: 2 };
+            AuditLogEntry::record(
+                &db,
+                &server,
+                &moderator,
+                AuditLogAction::RolePermissionsUpdate,
+                Some("role"),
+                vec![AuditLogChange::new("permissions", &before, &after)],
+            )
+            .await
+            .unwrap();
+
+            AuditLogEntry::record(
+                &db,
+                &server,
+                &admin,
+                AuditLogAction::MemberKick,
+                Some(&moderator),
+                vec![],
+            )
+            .await
+            .unwrap();
+
+            // Entries are returned newest first
+            let entries = db
+                .fetch_audit_log_entries(&server, None, None, None, 50)
+                .await
+                .unwrap();
+            assert_eq!(entries.len(), 2);
+            assert_eq!(entries[0].action, AuditLogAction::MemberKick);
+
+            // Permission changes keep both override values
+            let entries = db
+                .fetch_audit_log_entries(
+                    &server,
+                    Some(&AuditLogAction::RolePermissionsUpdate),
+                    Some(&moderator),
+                    None,
+                    50,
+                )
+                .await
+                .unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(
+                entries[0].changes[0].before.as_deref(),
+                Some(r#"{"a":1,"d":0}"#)
+            );
+            assert_eq!(
+                entries[0].changes[0].after.as_deref(),
+                Some(r#"{"a":1,"d":2}"#)
+            );
+
+            let entries = db
+                .fetch_audit_log_entries(&server, None, Some(&admin), Some(&entries[0].id), 50)
+                .await
+                .unwrap();
+            assert!(entries.is_empty());
+        });
+    }
+
+    #[test]
+    fn diff() {
+        let before = Role {
+            name: "Moderator".to_string(),
+            colour: Some("red".to_string()),
+            ..Role::default_role(0)
+        };
+
+        let after = Role {
+            name: "Admin".to_string(),
+            colour: None,
+            ..before.clone()
+        };
+
+        let changes = AuditLogChange::diff(&before, &after);
+        assert_eq!(
+            changes,
+            vec![
+                AuditLogChange {
+                    key: "colour".to_string(),
+                    before: Some(r#""red""#.to_string()),
+                    after: None,
+                },
+                AuditLogChange {
+                    key: "name".to_string(),
+                    before: Some(r#""Moderator""#.to_string()),
+                    after: Some(r#""Admin""#.to_string()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use onechatsocial_result::Result;
+
+use crate::{AuditLogAction, AuditLogEntry};
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractAuditLog: Sync + Send {
+    /// Insert a new audit log entry into the database
+    async fn insert_audit_log_entry(&self, entry: &AuditLogEntry) -> Result<()>;
+
+    /// Fetch audit log entries in a server, newest first
+    async fn fetch_audit_log_entries(
+        &self,
+        server: &str,
+        action: Option<&AuditLogAction>,
+        actor: Option<&str>,
+        before: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<AuditLogEntry>>;
+
+    /// Delete all audit log entries older than the given period
+    async fn delete_expired_audit_log_entries(&self, period: Duration) -> Result<()>;
+}
@@ -0,0 +1,79 @@
+use std::time::{Duration, SystemTime};
+
+use bson::to_bson;
+use mongodb::options::FindOptions;
+use onechatsocial_result::Result;
+use ulid::Ulid;
+
+use crate::MongoDb;
+use crate::{AuditLogAction, AuditLogEntry};
+
+use super::AbstractAuditLog;
+
+static COL: &str = "audit_log";
+
+#[async_trait]
+impl AbstractAuditLog for MongoDb {
+    /// Insert a new audit log entry into the database
+    async fn insert_audit_log_entry(&self, entry: &AuditLogEntry) -> Result<()> {
+        query!(self, insert_one, COL, &entry).map(|_| ())
+    }
+
+    /// Fetch audit log entries in a server, newest first
+    async fn fetch_audit_log_entries(
+        &self,
+        server: &str,
+        action: Option<&AuditLogAction>,
+        actor: Option<&str>,
+        before: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<AuditLogEntry>> {
+        let mut filter = doc! {
+            "server": server
+        };
+
+        if let Some(action) = action {
+            filter.insert(
+                "action",
+                to_bson(action).map_err(|_| create_database_error!("to_bson", "action"))?,
+            );
+        }
+
+        if let Some(actor) = actor {
+            filter.insert("actor", actor);
+        }
+
+        if let Some(before) = before {
+            filter.insert("_id", doc! { "$lt": before });
+        }
+
+        query!(
+            self,
+            find_with_options,
+            COL,
+            filter,
+            FindOptions::builder()
+                .sort(doc! {
+                    "_id": -1_i32
+                })
+                .limit(limit)
+                .build()
+        )
+    }
+
+    /// Delete all audit log entries older than the given period
+    async fn delete_expired_audit_log_entries(&self, period: Duration) -> Result<()> {
+        self.col::<AuditLogEntry>(COL)
+            .delete_many(
+                doc! {
+                    "_id": {
+                        "$lt": Ulid::from_datetime(SystemTime::now() - period).to_string()
+                    }
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("delete_many", COL))
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::{Duration, SystemTime};
+
+use onechatsocial_result::Result;
+use ulid::Ulid;
+
+use crate::ReferenceDb;
+use crate::{AuditLogAction, AuditLogEntry};
+
+use super::AbstractAuditLog;
+
+#[async_trait]
+impl AbstractAuditLog for ReferenceDb {
+    /// Insert a new audit log entry into the database
+    async fn insert_audit_log_entry(&self, entry: &AuditLogEntry) -> Result<()> {
+        let mut audit_log = self.audit_log.lock().await;
+        if audit_log.contains_key(&entry.id) {
+            Err(create_database_error!("insert", "audit_log"))
+        } else {
+            audit_log.insert(entry.id.to_string(), entry.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch audit log entries in a server, newest first
+    async fn fetch_audit_log_entries(
+        &self,
+        server: &str,
+        action: Option<&AuditLogAction>,
+        actor: Option<&str>,
+        before: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<AuditLogEntry>> {
+        let audit_log = self.audit_log.lock().await;
+        let mut entries: Vec<AuditLogEntry> = audit_log
+            .values()
+            .filter(|entry| {
+                entry.server == server
+                    && action.map_or(true, |action| &entry.action == action)
+                    && actor.map_or(true, |actor| entry.actor == actor)
+                    && before.map_or(true, |before| entry.id.as_str() < before)
+            })
+            .cloned()
+            .collect();
+
+        entries.sort_by(|a, b| b.id.cmp(&a.id));
+        entries.truncate(limit as usize);
+        Ok(entries)
+    }
+
+    /// Delete all audit log entries older than the given period
+    async fn delete_expired_audit_log_entries(&self, period: Duration) -> Result<()> {
+        let mut audit_log = self.audit_log.lock().await;
+        let gte_cmp_id = Ulid::from_datetime(SystemTime::now() - period).to_string();
+        audit_log.retain(|id, _| *id >= gte_cmp_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use onechatsocial_result::Result;
+
+use crate::{util::ulid_generator, Database};
+
+auto_derived!(
+    /// Follow relationship where new messages in one channel are cross-posted into another
+    pub struct ChannelFollow {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the channel being followed
+        pub channel: String,
+        /// Id of the channel receiving cross-posted messages
+        pub follower: String,
+        /// Id of the user who created this follow
+        pub creator: String,
+    }
+);
+
+impl ChannelFollow {
+    /// Have a channel follow another channel
+    pub async fn create(
+        db: &Database,
+        channel: String,
+        follower: String,
+        creator: String,
+    ) -> Result<ChannelFollow> {
+        if channel == follower || db.fetch_channel_follow(&channel, &follower).await.is_ok() {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        let follow = ChannelFollow {
+            id: ulid_generator::generate().to_string(),
+            channel,
+            follower,
+            creator,
+        };
+
+        db.insert_channel_follow(&follow).await?;
+        Ok(follow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use onechatsocial_result::ErrorType;
+
+    use crate::ChannelFollow;
+
+    #[async_std::test]
+    async fn follow_and_cleanup() {
+        database_test!(|db| async move {
+            let announcements = ulid::Ulid::new().to_string();
+            let follower = ulid::Ulid::new().to_string();
+            let creator = ulid::Ulid::new().to_string();
+
+            let follow = ChannelFollow::create(
+                &db,
+                announcements.to_string(),
+                follower.to_string(),
+                creator.to_string(),
+            )
+            .await
+            .unwrap();
+
+            // Channels cannot follow each other twice or follow themselves
+            for (channel, follower) in [(&announcements, &follower), (&follower, &follower)] {
+                let error = ChannelFollow::create(
+                    &db,
+                    channel.to_string(),
+                    follower.to_string(),
+                    creator.to_string(),
+                )
+                .await
+                .unwrap_err();
+                assert!(matches!(error.error_type, ErrorType::InvalidOperation));
+            }
+
+            assert_eq!(
+                db.fetch_channel_followers(&announcements).await.unwrap(),
+                vec![follow]
+            );
+
+            // Deleting the follower removes the follow
+            db.delete_channel_follows(&follower).await.unwrap();
+            assert!(db
+                .fetch_channel_followers(&announcements)
+                .await
+                .unwrap()
+                .is_empty());
+        });
+    }
+}
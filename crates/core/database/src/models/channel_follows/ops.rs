@@ -0,0 +1,24 @@
+use onechatsocial_result::Result;
+
+use crate::ChannelFollow;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractChannelFollows: Sync + Send {
+    /// Insert a new channel follow into the database
+    async fn insert_channel_follow(&self, follow: &ChannelFollow) -> Result<()>;
+
+    /// Fetch a channel follow by the followed and follower channel ids
+    async fn fetch_channel_follow(&self, channel: &str, follower: &str) -> Result<ChannelFollow>;
+
+    /// Fetch all follows of a given channel
+    async fn fetch_channel_followers(&self, channel: &str) -> Result<Vec<ChannelFollow>>;
+
+    /// Delete a channel follow from the database by its id
+    async fn delete_channel_follow(&self, id: &str) -> Result<()>;
+
+    /// Delete all follows which a given channel is either side of
+    async fn delete_channel_follows(&self, channel: &str) -> Result<()>;
+}
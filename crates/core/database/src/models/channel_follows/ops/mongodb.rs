@@ -0,0 +1,64 @@
+use onechatsocial_result::Result;
+
+use crate::ChannelFollow;
+use crate::MongoDb;
+
+use super::AbstractChannelFollows;
+
+static COL: &str = "channel_follows";
+
+#[async_trait]
+impl AbstractChannelFollows for MongoDb {
+    /// Insert a new channel follow into the database
+    async fn insert_channel_follow(&self, follow: &ChannelFollow) -> Result<()> {
+        query!(self, insert_one, COL, &follow).map(|_| ())
+    }
+
+    /// Fetch a channel follow by the followed and follower channel ids
+    async fn fetch_channel_follow(&self, channel: &str, follower: &str) -> Result<ChannelFollow> {
+        query!(
+            self,
+            find_one,
+            COL,
+            doc! {
+                "channel": channel,
+                "follower": follower
+            }
+        )?
+        .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all follows of a given channel
+    async fn fetch_channel_followers(&self, channel: &str) -> Result<Vec<ChannelFollow>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "channel": channel
+            }
+        )
+    }
+
+    /// Delete a channel follow from the database by its id
+    async fn delete_channel_follow(&self, id: &str) -> Result<()> {
+        query!(self, delete_one_by_id, COL, id).map(|_| ())
+    }
+
+    /// Delete all follows which a given channel is either side of
+    async fn delete_channel_follows(&self, channel: &str) -> Result<()> {
+        self.col::<ChannelFollow>(COL)
+            .delete_many(
+                doc! {
+                    "$or": [
+                        { "channel": channel },
+                        { "follower": channel }
+                    ]
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("delete_many", COL))
+    }
+}
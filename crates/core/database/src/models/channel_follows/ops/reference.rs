@@ -0,0 +1,57 @@
+use onechatsocial_result::Result;
+
+use crate::ChannelFollow;
+use crate::ReferenceDb;
+
+use super::AbstractChannelFollows;
+
+#[async_trait]
+impl AbstractChannelFollows for ReferenceDb {
+    /// Insert a new channel follow into the database
+    async fn insert_channel_follow(&self, follow: &ChannelFollow) -> Result<()> {
+        let mut channel_follows = self.channel_follows.lock().await;
+        if channel_follows.contains_key(&follow.id) {
+            Err(create_database_error!("insert", "channel_follow"))
+        } else {
+            channel_follows.insert(follow.id.to_string(), follow.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a channel follow by the followed and follower channel ids
+    async fn fetch_channel_follow(&self, channel: &str, follower: &str) -> Result<ChannelFollow> {
+        let channel_follows = self.channel_follows.lock().await;
+        channel_follows
+            .values()
+            .find(|follow| follow.channel == channel && follow.follower == follower)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all follows of a given channel
+    async fn fetch_channel_followers(&self, channel: &str) -> Result<Vec<ChannelFollow>> {
+        let channel_follows = self.channel_follows.lock().await;
+        Ok(channel_follows
+            .values()
+            .filter(|follow| follow.channel == channel)
+            .cloned()
+            .collect())
+    }
+
+    /// Delete a channel follow from the database by its id
+    async fn delete_channel_follow(&self, id: &str) -> Result<()> {
+        let mut channel_follows = self.channel_follows.lock().await;
+        if channel_follows.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Delete all follows which a given channel is either side of
+    async fn delete_channel_follows(&self, channel: &str) -> Result<()> {
+        let mut channel_follows = self.channel_follows.lock().await;
+        channel_follows.retain(|_, follow| follow.channel != channel && follow.follower != channel);
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use onechatsocial_result::{Error, ErrorType, Result};
+
+use crate::{Channel, ChannelCompositeKey, Database};
+
+auto_derived!(
+    /// Notification preference of a user for a channel
+    pub struct ChannelNotificationPreference {
+        /// Composite key pointing to a user's view of a channel
+        #[serde(rename = "_id")]
+        pub id: ChannelCompositeKey,
+        /// Which messages the user is notified of
+        pub level: NotificationLevel,
+    }
+
+    /// Which messages in a channel a user is notified of
+    #[derive(Copy)]
+    pub enum NotificationLevel {
+        /// Push every message
+        All,
+        /// Only push messages which mention the user
+        Mentions,
+        /// Never push messages, mentions are still tracked
+        Muted,
+        /// Never push messages or track mentions
+        Nothing,
+    }
+);
+
+impl NotificationLevel {
+    /// Level used for a channel when the user has not set one
+    ///
+    /// Direct messages and groups notify of every message, server channels only of mentions.
+    pub fn default_for(channel: &Channel) -> NotificationLevel {
+        match channel {
+            Channel::DirectMessage { .. } | Channel::Group { .. } => NotificationLevel::All,
+            _ => NotificationLevel::Mentions,
+        }
+    }
+
+    /// Whether a message should be pushed at this level
+    pub fn should_push(&self, mentioned: bool) -> bool {
+        match self {
+            NotificationLevel::All => true,
+            NotificationLevel::Mentions => mentioned,
+            NotificationLevel::Muted | NotificationLevel::Nothing => false,
+        }
+    }
+}
+
+impl ChannelNotificationPreference {
+    /// Fetch the level a user is notified at in a channel
+    pub async fn fetch_level(
+        db: &Database,
+        channel: &Channel,
+        user_id: &str,
+    ) -> Result<NotificationLevel> {
+        match db
+            .fetch_channel_notification_preference(&channel.id(), user_id)
+            .await
+        {
+            Ok(preference) => Ok(preference.level),
+            Err(Error {
+                error_type: ErrorType::NotFound,
+                ..
+            }) => Ok(NotificationLevel::default_for(channel)),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Fetch the levels users have explicitly set in a channel
+    pub async fn fetch_levels(
+        db: &Database,
+        channel_id: &str,
+    ) -> Result<HashMap<String, NotificationLevel>> {
+        Ok(db
+            .fetch_channel_notification_preferences(channel_id)
+            .await?
+            .into_iter()
+            .map(|preference| (preference.id.user, preference.level))
+            .collect())
+    }
+
+    /// Set the level a user is notified at in a channel
+    ///
+    /// Preferences matching the channel's default are not stored.
+    pub async fn set_level(
+        db: &Database,
+        channel: &Channel,
+        user_id: &str,
+        level: NotificationLevel,
+    ) -> Result<()> {
+        if level == NotificationLevel::default_for(channel) {
+            db.delete_channel_notification_preference(&channel.id(), user_id)
+                .await
+        } else {
+            db.upsert_channel_notification_preference(&ChannelNotificationPreference {
+                id: ChannelCompositeKey {
+                    channel: channel.id(),
+                    user: user_id.to_string(),
+                },
+                level,
+            })
+            .await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fixture, ChannelNotificationPreference, NotificationLevel};
+
+    #[async_std::test]
+    async fn group_default_level() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                member user 1
+                group channel 3);
+
+            // Groups notify of every message by default
+            assert_eq!(
+                NotificationLevel::All,
+                ChannelNotificationPreference::fetch_level(&db, &group, &member.id)
+                    .await
+                    .unwrap()
+            );
+        });
+    }
+
+    #[async_std::test]
+    async fn set_level() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                user user 2
+                channel channel 3);
+
+            // Server channels only notify of mentions by default
+            assert_eq!(
+                NotificationLevel::Mentions,
+                ChannelNotificationPreference::fetch_level(&db, &channel, &user.id)
+                    .await
+                    .unwrap()
+            );
+
+            ChannelNotificationPreference::set_level(
+                &db,
+                &channel,
+                &user.id,
+                NotificationLevel::Muted,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(
+                NotificationLevel::Muted,
+                ChannelNotificationPreference::fetch_level(&db, &channel, &user.id)
+                    .await
+                    .unwrap()
+            );
+
+            // Returning to the default removes the stored preference
+            ChannelNotificationPreference::set_level(
+                &db,
+                &channel,
+                &user.id,
+                NotificationLevel::Mentions,
+            )
+            .await
+            .unwrap();
+
+            assert!(
+                ChannelNotificationPreference::fetch_levels(&db, &channel.id())
+                    .await
+                    .unwrap()
+                    .is_empty()
+            );
+
+            // Deleting the channel removes any preferences set for it
+            ChannelNotificationPreference::set_level(
+                &db,
+                &channel,
+                &user.id,
+                NotificationLevel::Muted,
+            )
+            .await
+            .unwrap();
+
+            channel.delete(&db).await.unwrap();
+            assert!(
+                ChannelNotificationPreference::fetch_levels(&db, &channel.id())
+                    .await
+                    .unwrap()
+                    .is_empty()
+            );
+        });
+    }
+}
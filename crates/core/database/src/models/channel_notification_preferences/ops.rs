@@ -0,0 +1,38 @@
+use onechatsocial_result::Result;
+
+use crate::ChannelNotificationPreference;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractChannelNotificationPreferences: Sync + Send {
+    /// Fetch a user's notification preference for a channel
+    async fn fetch_channel_notification_preference(
+        &self,
+        channel_id: &str,
+        user_id: &str,
+    ) -> Result<ChannelNotificationPreference>;
+
+    /// Fetch all notification preferences set for a channel
+    async fn fetch_channel_notification_preferences(
+        &self,
+        channel_id: &str,
+    ) -> Result<Vec<ChannelNotificationPreference>>;
+
+    /// Insert or replace a user's notification preference for a channel
+    async fn upsert_channel_notification_preference(
+        &self,
+        preference: &ChannelNotificationPreference,
+    ) -> Result<()>;
+
+    /// Delete a user's notification preference for a channel
+    async fn delete_channel_notification_preference(
+        &self,
+        channel_id: &str,
+        user_id: &str,
+    ) -> Result<()>;
+
+    /// Delete all notification preferences set for a channel
+    async fn delete_channel_notification_preferences(&self, channel_id: &str) -> Result<()>;
+}
@@ -0,0 +1,96 @@
+use mongodb::options::ReplaceOptions;
+use onechatsocial_result::Result;
+
+use crate::ChannelNotificationPreference;
+use crate::MongoDb;
+
+use super::AbstractChannelNotificationPreferences;
+
+static COL: &str = "channel_notification_preferences";
+
+#[async_trait]
+impl AbstractChannelNotificationPreferences for MongoDb {
+    /// Fetch a user's notification preference for a channel
+    async fn fetch_channel_notification_preference(
+        &self,
+        channel_id: &str,
+        user_id: &str,
+    ) -> Result<ChannelNotificationPreference> {
+        query!(
+            self,
+            find_one,
+            COL,
+            doc! {
+                "_id.channel": channel_id,
+                "_id.user": user_id
+            }
+        )?
+        .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all notification preferences set for a channel
+    async fn fetch_channel_notification_preferences(
+        &self,
+        channel_id: &str,
+    ) -> Result<Vec<ChannelNotificationPreference>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "_id.channel": channel_id
+            }
+        )
+    }
+
+    /// Insert or replace a user's notification preference for a channel
+    async fn upsert_channel_notification_preference(
+        &self,
+        preference: &ChannelNotificationPreference,
+    ) -> Result<()> {
+        self.col::<ChannelNotificationPreference>(COL)
+            .replace_one(
+                doc! {
+                    "_id.channel": &preference.id.channel,
+                    "_id.user": &preference.id.user
+                },
+                preference,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("replace_one", COL))
+    }
+
+    /// Delete a user's notification preference for a channel
+    async fn delete_channel_notification_preference(
+        &self,
+        channel_id: &str,
+        user_id: &str,
+    ) -> Result<()> {
+        query!(
+            self,
+            delete_one,
+            COL,
+            doc! {
+                "_id.channel": channel_id,
+                "_id.user": user_id
+            }
+        )
+        .map(|_| ())
+    }
+
+    /// Delete all notification preferences set for a channel
+    async fn delete_channel_notification_preferences(&self, channel_id: &str) -> Result<()> {
+        self.col::<ChannelNotificationPreference>(COL)
+            .delete_many(
+                doc! {
+                    "_id.channel": channel_id
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("delete_many", COL))
+    }
+}
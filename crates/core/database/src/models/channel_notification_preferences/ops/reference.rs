@@ -0,0 +1,68 @@
+use onechatsocial_result::Result;
+
+use crate::{ChannelCompositeKey, ChannelNotificationPreference, ReferenceDb};
+
+use super::AbstractChannelNotificationPreferences;
+
+#[async_trait]
+impl AbstractChannelNotificationPreferences for ReferenceDb {
+    /// Fetch a user's notification preference for a channel
+    async fn fetch_channel_notification_preference(
+        &self,
+        channel_id: &str,
+        user_id: &str,
+    ) -> Result<ChannelNotificationPreference> {
+        let preferences = self.channel_notification_preferences.lock().await;
+        preferences
+            .get(&ChannelCompositeKey {
+                channel: channel_id.to_string(),
+                user: user_id.to_string(),
+            })
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all notification preferences set for a channel
+    async fn fetch_channel_notification_preferences(
+        &self,
+        channel_id: &str,
+    ) -> Result<Vec<ChannelNotificationPreference>> {
+        let preferences = self.channel_notification_preferences.lock().await;
+        Ok(preferences
+            .values()
+            .filter(|preference| preference.id.channel == channel_id)
+            .cloned()
+            .collect())
+    }
+
+    /// Insert or replace a user's notification preference for a channel
+    async fn upsert_channel_notification_preference(
+        &self,
+        preference: &ChannelNotificationPreference,
+    ) -> Result<()> {
+        let mut preferences = self.channel_notification_preferences.lock().await;
+        preferences.insert(preference.id.clone(), preference.clone());
+        Ok(())
+    }
+
+    /// Delete a user's notification preference for a channel
+    async fn delete_channel_notification_preference(
+        &self,
+        channel_id: &str,
+        user_id: &str,
+    ) -> Result<()> {
+        let mut preferences = self.channel_notification_preferences.lock().await;
+        preferences.remove(&ChannelCompositeKey {
+            channel: channel_id.to_string(),
+            user: user_id.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Delete all notification preferences set for a channel
+    async fn delete_channel_notification_preferences(&self, channel_id: &str) -> Result<()> {
+        let mut preferences = self.channel_notification_preferences.lock().await;
+        preferences.retain(|id, _| id.channel != channel_id);
+        Ok(())
+    }
+}
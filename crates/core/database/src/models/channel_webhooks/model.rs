@@ -1,4 +1,8 @@
+use hmac::{Hmac, Mac};
+use onechatsocial_models::v0::{DataMessageSend, SendableEmbed};
 use onechatsocial_result::Result;
+use serde_json::Value;
+use sha2::Sha256;
 
 use crate::events::client::EventV1;
 use crate::{Database, File};
@@ -25,6 +29,14 @@ auto_derived_partial!(
 
         /// The private token for the webhook
         pub token: Option<String>,
+
+        /// Secret used to sign incoming requests
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub secret: Option<String>,
+
+        /// Template used to transform incoming payloads into messages
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub template: Option<WebhookTemplate>,
     },
     "PartialWebhook"
 );
@@ -33,6 +45,32 @@ auto_derived!(
     /// Optional fields on webhook object
     pub enum FieldsWebhook {
         Avatar,
+        Template,
+    }
+
+    /// Template used to transform incoming payloads into messages
+    ///
+    /// Each field may reference values from the payload using `{{ path.to.field }}`
+    #[derive(Default)]
+    pub struct WebhookTemplate {
+        /// Message content
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub content: Option<String>,
+        /// Embed title
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub title: Option<String>,
+        /// Embed description
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub description: Option<String>,
+        /// Embed URL
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub url: Option<String>,
+        /// Embed icon URL
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub icon_url: Option<String>,
+        /// Embed colour
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub colour: Option<String>,
     }
 );
 
@@ -46,6 +84,8 @@ impl Default for Webhook {
             channel_id: Default::default(),
             permissions: Default::default(),
             token: Default::default(),
+            secret: None,
+            template: None,
         }
     }
 }
@@ -74,6 +114,40 @@ impl Webhook {
         }
     }
 
+    /// Verify the signature of an incoming payload
+    ///
+    /// Signatures take the form `sha256=<hex digest>` and are only
+    /// required if this webhook has a secret configured.
+    pub fn assert_signature(&self, payload: &[u8], signature: Option<&str>) -> Result<()> {
+        let Some(secret) = &self.secret else {
+            return Ok(());
+        };
+
+        let digest = signature
+            .and_then(|signature| signature.strip_prefix("sha256="))
+            .and_then(|digest| hex::decode(digest).ok())
+            .ok_or_else(|| create_error!(InvalidCredentials))?;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .map_err(|_| create_error!(InternalError))?;
+
+        mac.update(payload);
+        mac.verify_slice(&digest)
+            .map_err(|_| create_error!(InvalidCredentials))
+    }
+
+    /// Sign an outgoing payload using the same scheme as incoming payloads
+    pub fn sign_payload(secret: &str, payload: &[u8]) -> Result<String> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .map_err(|_| create_error!(InternalError))?;
+
+        mac.update(payload);
+        Ok(format!(
+            "sha256={}",
+            hex::encode(mac.finalize().into_bytes())
+        ))
+    }
+
     pub async fn update(
         &mut self,
         db: &Database,
@@ -104,6 +178,7 @@ impl Webhook {
     pub fn remove_field(&mut self, field: &FieldsWebhook) {
         match field {
             FieldsWebhook::Avatar => self.avatar = None,
+            FieldsWebhook::Template => self.template = None,
         }
     }
 
@@ -120,9 +195,151 @@ impl Webhook {
     }
 }
 
+impl WebhookTemplate {
+    /// Render this template against an incoming payload
+    pub fn render(&self, payload: &Value) -> DataMessageSend {
+        let render = |template: &Option<String>| {
+            template
+                .as_deref()
+                .map(|template| render_template(template, payload))
+                .filter(|output| !output.is_empty())
+        };
+
+        let embed = SendableEmbed {
+            icon_url: render(&self.icon_url),
+            url: render(&self.url),
+            title: render(&self.title),
+            description: render(&self.description),
+            colour: render(&self.colour),
+            ..Default::default()
+        };
+
+        let has_embed = embed.icon_url.is_some()
+            || embed.url.is_some()
+            || embed.title.is_some()
+            || embed.description.is_some();
+
+        DataMessageSend {
+            nonce: None,
+            content: render(&self.content),
+            attachments: None,
+            attachment_descriptions: None,
+            stickers: None,
+            replies: None,
+            embeds: if has_embed { Some(vec![embed]) } else { None },
+            masquerade: None,
+            interactions: None,
+            quote: None,
+            language: None,
+            if_last_message: None,
+            send_at: None,
+            expires_in: None,
+            allowed_mentions: None,
+            voice: None,
+        }
+    }
+}
+
+/// Substitute every `{{ path.to.field }}` in the template with the value found in the payload
+///
+/// Missing fields are replaced with nothing, no other expressions are evaluated.
+fn render_template(template: &str, payload: &Value) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+
+        let expression = &rest[start + 2..];
+        let Some(end) = expression.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+
+        match extract_field(payload, expression[..end].trim()) {
+            Some(Value::Null) | None => {}
+            Some(Value::String(value)) => output.push_str(value),
+            Some(value) => output.push_str(&value.to_string()),
+        }
+
+        rest = &expression[end + 2..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Find a value by its dot-separated path, array elements are referenced by index
+fn extract_field<'a>(payload: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|key| !key.is_empty())
+        .try_fold(payload, |value, key| match value {
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|index| items.get(index)),
+            Value::Object(fields) => fields.get(key),
+            _ => None,
+        })
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{FieldsWebhook, PartialWebhook, Webhook};
+    use serde_json::json;
+
+    use crate::{FieldsWebhook, PartialWebhook, Webhook, WebhookTemplate};
+
+    #[test]
+    fn template() {
+        let template = WebhookTemplate {
+            content: Some("{{ sender.login }} pushed {{ commits.0.id }} {{ missing }}".to_string()),
+            title: Some("{{repository.name}}".to_string()),
+            description: Some("{{ commits.1.id }}".to_string()),
+            ..Default::default()
+        };
+
+        let data = template.render(&json!({
+            "sender": { "login": "insert" },
+            "repository": { "name": "backend" },
+            "commits": [{ "id": 1234 }]
+        }));
+
+        assert_eq!(data.content.as_deref(), Some("insert pushed 1234 "));
+
+        let embeds = data.embeds.unwrap();
+        assert_eq!(embeds[0].title.as_deref(), Some("backend"));
+        assert!(embeds[0].description.is_none());
+
+        let data = WebhookTemplate {
+            content: Some("{{ unterminated".to_string()),
+            ..Default::default()
+        }
+        .render(&json!({}));
+
+        assert_eq!(data.content.as_deref(), Some("{{ unterminated"));
+        assert!(data.embeds.is_none());
+    }
+
+    #[test]
+    fn signature() {
+        let webhook = Webhook {
+            secret: Some("It's a Secret to Everybody".to_string()),
+            ..Default::default()
+        };
+
+        // Example from GitHub's webhook documentation
+        let payload = b"Hello, World!";
+        let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+
+        assert!(webhook.assert_signature(payload, Some(signature)).is_ok());
+        assert!(webhook
+            .assert_signature(b"Hello, Mars!", Some(signature))
+            .is_err());
+        assert!(webhook.assert_signature(payload, None).is_err());
+        assert!(Webhook::default().assert_signature(payload, None).is_ok());
+
+        assert_eq!(
+            Webhook::sign_payload("It's a Secret to Everybody", payload).unwrap(),
+            signature
+        );
+    }
 
     #[async_std::test]
     async fn crud() {
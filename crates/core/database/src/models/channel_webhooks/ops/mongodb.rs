@@ -72,6 +72,7 @@ impl IntoDocumentPath for FieldsWebhook {
     fn as_path(&self) -> Option<&'static str> {
         Some(match self {
             FieldsWebhook::Avatar => "avatar",
+            FieldsWebhook::Template => "template",
         })
     }
 }
@@ -1,5 +1,11 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
 
+use async_std::sync::Mutex;
+use once_cell::sync::Lazy;
 use onechatsocial_config::config;
 use onechatsocial_models::v0::{self, MessageAuthor};
 use onechatsocial_permissions::OverrideField;
@@ -7,10 +13,17 @@ use onechatsocial_result::Result;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    events::client::EventV1, Database, File, IntoDocumentPath, PartialServer, Server,
-    SystemMessage, User,
+    events::client::EventV1, ChannelPins, Database, File, IntoDocumentPath, Member, Message,
+    MessageFilter, MessageQuery, MessageTimePeriod, PartialServer, Server, SystemMessage, User,
 };
 
+/// How long a server's member count is trusted for when deciding whether to only push mentions
+const MEMBER_COUNT_TTL: Duration = Duration::from_secs(300);
+
+/// Member counts of servers recently checked for mention-only push notifications
+static MEMBER_COUNTS: Lazy<Mutex<lru::LruCache<String, (usize, Instant)>>> =
+    Lazy::new(|| Mutex::new(lru::LruCache::new(NonZeroUsize::new(1000).unwrap())));
+
 auto_derived!(
     #[serde(tag = "channel_type")]
     pub enum Channel {
@@ -21,6 +34,10 @@ auto_derived!(
             id: String,
             /// Id of the user this channel belongs to
             user: String,
+
+            /// Summary of the messages pinned in this channel
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pins: Option<ChannelPins>,
         },
         /// Direct message channel between two users
         DirectMessage {
@@ -35,6 +52,10 @@ auto_derived!(
             /// Id of the last message sent in this channel
             #[serde(skip_serializing_if = "Option::is_none")]
             last_message_id: Option<String>,
+
+            /// Summary of the messages pinned in this channel
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pins: Option<ChannelPins>,
         },
         /// Group channel between 1 or more participants
         Group {
@@ -67,6 +88,10 @@ auto_derived!(
             /// Whether this group is marked as not safe for work
             #[serde(skip_serializing_if = "crate::if_false", default)]
             nsfw: bool,
+
+            /// Summary of the messages pinned in this channel
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pins: Option<ChannelPins>,
         },
         /// Text channel belonging to a server
         TextChannel {
@@ -102,6 +127,51 @@ auto_derived!(
             /// Whether this channel is marked as not safe for work
             #[serde(skip_serializing_if = "crate::if_false", default)]
             nsfw: bool,
+
+            /// Minimum number of seconds between messages from each member
+            #[serde(skip_serializing_if = "Option::is_none")]
+            slowmode_seconds: Option<u16>,
+            /// Whether slowmode also applies to webhooks and system messages
+            #[serde(skip_serializing_if = "crate::if_false", default)]
+            slowmode_applies_to_bots: bool,
+            /// Ids of roles whose members are exempt from slowmode
+            #[serde(skip_serializing_if = "Vec::is_empty", default)]
+            slowmode_exempt_roles: Vec<String>,
+
+            /// Whether only members who can manage messages may send messages,
+            /// everyone else may only read and react
+            #[serde(skip_serializing_if = "crate::if_false", default)]
+            reactions_only: bool,
+
+            /// Whether link previews should not be generated for messages in this channel
+            #[serde(skip_serializing_if = "crate::if_false", default)]
+            disable_embeds: bool,
+
+            /// Whether other channels may follow this channel to have its messages cross-posted
+            #[serde(skip_serializing_if = "crate::if_false", default)]
+            announcement: bool,
+
+            /// Id of the channel this thread was started in
+            #[serde(skip_serializing_if = "Option::is_none")]
+            parent: Option<String>,
+            /// Id of the message this thread was started from
+            #[serde(skip_serializing_if = "Option::is_none")]
+            root_message: Option<String>,
+            /// Whether this thread was archived after going idle
+            #[serde(skip_serializing_if = "crate::if_false", default)]
+            archived: bool,
+
+            /// Ids of pinned messages in their manually arranged order,
+            /// pinned messages are listed by pin time if empty
+            #[serde(skip_serializing_if = "Vec::is_empty", default)]
+            pin_order: Vec<String>,
+            /// Id of the last message sent in any thread started in this channel
+            #[serde(skip_serializing_if = "Option::is_none")]
+            last_thread_message_id: Option<String>,
+
+            /// Summary of the messages pinned in this channel
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pins: Option<ChannelPins>,
         },
         /// Voice channel belonging to a server
         VoiceChannel {
@@ -133,6 +203,10 @@ auto_derived!(
             /// Whether this channel is marked as not safe for work
             #[serde(skip_serializing_if = "crate::if_false", default)]
             nsfw: bool,
+
+            /// Summary of the messages pinned in this channel
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pins: Option<ChannelPins>,
         },
     }
 );
@@ -160,6 +234,24 @@ auto_derived!(
         pub default_permissions: Option<OverrideField>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub last_message_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub slowmode_seconds: Option<u16>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub slowmode_applies_to_bots: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub slowmode_exempt_roles: Option<Vec<String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub reactions_only: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub disable_embeds: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub announcement: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub archived: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub pin_order: Option<Vec<String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub last_thread_message_id: Option<String>,
     }
 
     /// Optional fields on channel object
@@ -167,6 +259,7 @@ auto_derived!(
         Description,
         Icon,
         DefaultPermissions,
+        SlowmodeSeconds,
     }
 );
 
@@ -200,13 +293,24 @@ impl Channel {
         update_server: bool,
     ) -> Result<Channel> {
         let config = config().await;
-        if server.channels.len() > config.features.limits.default.server_channels {
+        let limits = config
+            .features
+            .server_limits(server.flags.unwrap_or_default() as u32);
+
+        if server.channels.len() >= limits.server_channels {
             return Err(create_error!(TooManyChannels {
-                max: config.features.limits.default.server_channels,
+                max: limits.server_channels,
             }));
         };
 
         let id = ulid::Ulid::new().to_string();
+        let role_permissions: HashMap<String, OverrideField> = data
+            .role_permissions
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(role_id, permissions)| (role_id, permissions.into()))
+            .collect();
+
         let channel = match data.channel_type {
             v0::LegacyServerChannelType::Text => Channel::TextChannel {
                 id: id.clone(),
@@ -216,8 +320,20 @@ impl Channel {
                 icon: None,
                 last_message_id: None,
                 default_permissions: None,
-                role_permissions: HashMap::new(),
+                role_permissions,
                 nsfw: data.nsfw.unwrap_or(false),
+                slowmode_seconds: None,
+                slowmode_applies_to_bots: false,
+                slowmode_exempt_roles: vec![],
+                reactions_only: false,
+                disable_embeds: false,
+                announcement: false,
+                parent: None,
+                root_message: None,
+                archived: false,
+                pin_order: vec![],
+                last_thread_message_id: None,
+                pins: None,
             },
             v0::LegacyServerChannelType::Voice => Channel::VoiceChannel {
                 id: id.clone(),
@@ -226,8 +342,9 @@ impl Channel {
                 description: data.description,
                 icon: None,
                 default_permissions: None,
-                role_permissions: HashMap::new(),
+                role_permissions,
                 nsfw: data.nsfw.unwrap_or(false),
+                pins: None,
             },
         };
 
@@ -253,21 +370,105 @@ impl Channel {
         Ok(channel)
     }
 
+    /// Start a new thread from a message in this channel
+    ///
+    /// Threads are not listed in the server's channels, they take their
+    /// permissions from this channel and are referenced by a system message in it.
+    pub async fn create_thread(
+        &self,
+        db: &Database,
+        message: &Message,
+        name: String,
+        by: &str,
+    ) -> Result<Channel> {
+        let Channel::TextChannel {
+            id: parent_id,
+            server,
+            nsfw,
+            slowmode_seconds,
+            slowmode_applies_to_bots,
+            slowmode_exempt_roles,
+            reactions_only,
+            disable_embeds,
+            parent: None,
+            ..
+        } = self
+        else {
+            return Err(create_error!(InvalidOperation));
+        };
+
+        if &message.channel != parent_id || message.system.is_some() {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        if db
+            .fetch_thread_by_root_message(&message.id)
+            .await?
+            .is_some()
+        {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        let channel = Channel::TextChannel {
+            id: ulid::Ulid::new().to_string(),
+            server: server.clone(),
+            name: name.clone(),
+            description: None,
+            icon: None,
+            last_message_id: None,
+            default_permissions: None,
+            role_permissions: HashMap::new(),
+            nsfw: *nsfw,
+            slowmode_seconds: *slowmode_seconds,
+            slowmode_applies_to_bots: *slowmode_applies_to_bots,
+            slowmode_exempt_roles: slowmode_exempt_roles.clone(),
+            reactions_only: *reactions_only,
+            disable_embeds: *disable_embeds,
+            announcement: false,
+            parent: Some(parent_id.clone()),
+            root_message: Some(message.id.clone()),
+            archived: false,
+            pin_order: vec![],
+            last_thread_message_id: None,
+            pins: None,
+        };
+
+        db.insert_channel(&channel).await?;
+
+        EventV1::ChannelCreate(channel.clone().into())
+            .p(server.clone())
+            .await;
+
+        SystemMessage::ThreadCreated {
+            id: channel.id(),
+            name,
+            by: by.to_string(),
+        }
+        .into_message(parent_id.clone())
+        .send_without_notifications(db, false, false)
+        .await?;
+
+        Ok(channel)
+    }
+
     /// Create a group
     pub async fn create_group(
         db: &Database,
         mut data: v0::DataCreateGroup,
         owner_id: String,
     ) -> Result<Channel> {
-        data.users.insert(owner_id.to_string());
+        // Count the owner the same way as adding a recipient to an existing group
+        data.users.remove(&owner_id);
 
         let config = config().await;
-        if data.users.len() > config.features.limits.default.group_size {
+        if data.users.len() >= config.features.limits.default.group_size {
             return Err(create_error!(GroupTooLarge {
                 max: config.features.limits.default.group_size,
             }));
         }
 
+        data.users.insert(owner_id.to_string());
+
         let recipients = data.users.into_iter().collect::<Vec<String>>();
         let channel = Channel::Group {
             id: ulid::Ulid::new().to_string(),
@@ -283,6 +484,7 @@ impl Channel {
             permissions: None,
 
             nsfw: data.nsfw.unwrap_or(false),
+            pins: None,
         };
 
         db.insert_channel(&channel).await?;
@@ -307,6 +509,13 @@ impl Channel {
                 return Err(create_error!(AlreadyInGroup));
             }
 
+            let config = config().await;
+            if recipients.len() >= config.features.limits.default.group_size {
+                return Err(create_error!(GroupTooLarge {
+                    max: config.features.limits.default.group_size,
+                }));
+            }
+
             recipients.push(String::from(&user.id));
         }
 
@@ -321,6 +530,8 @@ impl Channel {
                 .p(id.to_string())
                 .await;
 
+                let by = db.fetch_user(by_id).await.ok().map(|by| by.username);
+
                 SystemMessage::UserAdded {
                     id: user.id.to_string(),
                     by: by_id.to_string(),
@@ -331,6 +542,7 @@ impl Channel {
                     MessageAuthor::System {
                         username: &user.username,
                         avatar: user.avatar.as_ref().map(|file| file.id.as_ref()),
+                        by,
                     },
                     self,
                     false,
@@ -369,6 +581,77 @@ impl Channel {
         }
     }
 
+    /// Check whether only mentions should produce push notifications in this channel
+    ///
+    /// Text channels in servers with at least `push_mention_only_threshold` members
+    /// never push non-mention messages. This takes priority over any per-user
+    /// notification overrides, mentions are always pushed.
+    pub async fn is_push_mention_only(&self, db: &Database) -> bool {
+        match self {
+            Channel::TextChannel { server, .. } => {
+                let config = config().await;
+                Channel::fetch_member_count(db, server)
+                    .await
+                    .map(|count| count >= config.features.push_mention_only_threshold)
+                    .unwrap_or(true)
+            }
+            _ => false,
+        }
+    }
+
+    /// Fetch the number of members in a server, reusing a recently fetched count
+    ///
+    /// Counts only need to be roughly up to date, so they are not invalidated as members come and go.
+    async fn fetch_member_count(db: &Database, server: &str) -> Result<usize> {
+        if let Some((count, fetched_at)) = MEMBER_COUNTS.lock().await.get(server) {
+            if fetched_at.elapsed() < MEMBER_COUNT_TTL {
+                return Ok(*count);
+            }
+        }
+
+        let count = db.fetch_member_count(server).await?;
+        MEMBER_COUNTS
+            .lock()
+            .await
+            .put(server.to_string(), (count, Instant::now()));
+
+        Ok(count)
+    }
+
+    /// Replace the cached member count of a server
+    #[cfg(test)]
+    pub(crate) async fn set_cached_member_count(server: &str, count: usize) {
+        MEMBER_COUNTS
+            .lock()
+            .await
+            .put(server.to_string(), (count, Instant::now()));
+    }
+
+    /// Check whether a member holds a role which exempts them from slowmode in this channel
+    pub fn is_slowmode_exempt(&self, member: &Member) -> bool {
+        match self {
+            Channel::TextChannel {
+                slowmode_exempt_roles,
+                ..
+            } => member
+                .roles
+                .iter()
+                .any(|role| slowmode_exempt_roles.contains(role)),
+            _ => false,
+        }
+    }
+
+    /// Check whether this channel only allows reactions from regular members
+    pub fn is_reactions_only(&self) -> bool {
+        matches!(
+            self,
+            Channel::TextChannel {
+                reactions_only: true,
+                ..
+            }
+        )
+    }
+
     /// Clone this channel's id
     pub fn id(&self) -> String {
         match self {
@@ -380,6 +663,39 @@ impl Channel {
         }
     }
 
+    /// Get the name of this channel's type
+    pub fn channel_type(&self) -> &'static str {
+        match self {
+            Channel::SavedMessages { .. } => "SavedMessages",
+            Channel::DirectMessage { .. } => "DirectMessage",
+            Channel::Group { .. } => "Group",
+            Channel::TextChannel { .. } => "TextChannel",
+            Channel::VoiceChannel { .. } => "VoiceChannel",
+        }
+    }
+
+    /// Get a summary of the messages pinned in this channel
+    pub fn pins(&self) -> ChannelPins {
+        match self {
+            Channel::DirectMessage { pins, .. }
+            | Channel::Group { pins, .. }
+            | Channel::SavedMessages { pins, .. }
+            | Channel::TextChannel { pins, .. }
+            | Channel::VoiceChannel { pins, .. } => pins.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Get a mutable reference to the summary of pinned messages
+    pub(crate) fn pins_mut(&mut self) -> &mut Option<ChannelPins> {
+        match self {
+            Channel::DirectMessage { pins, .. }
+            | Channel::Group { pins, .. }
+            | Channel::SavedMessages { pins, .. }
+            | Channel::TextChannel { pins, .. }
+            | Channel::VoiceChannel { pins, .. } => pins,
+        }
+    }
+
     /// Set role permission on a channel
     pub async fn set_role_permission(
         &mut self,
@@ -423,6 +739,85 @@ impl Channel {
         }
     }
 
+    /// Arrange pinned messages in the order they should be listed
+    ///
+    /// Messages are listed newest pin first, unless the channel has a manual
+    /// pin order in which case messages pinned since it was set come first.
+    pub fn sort_pins(&self, messages: &mut [Message]) {
+        messages.sort_by(|a, b| {
+            b.pinned_at
+                .map(|t| *t)
+                .cmp(&a.pinned_at.map(|t| *t))
+                .then_with(|| b.id.cmp(&a.id))
+        });
+
+        if let Channel::TextChannel { pin_order, .. } = self {
+            if !pin_order.is_empty() {
+                messages.sort_by_key(|message| pin_order.iter().position(|id| id == &message.id));
+            }
+        }
+    }
+
+    /// Set the manual order of pinned messages in this channel
+    ///
+    /// The order must list every pinned message exactly once, an empty order
+    /// reverts to listing pinned messages by pin time.
+    pub async fn set_pin_order(&mut self, db: &Database, order: Vec<String>) -> Result<()> {
+        if !matches!(self, Channel::TextChannel { .. }) {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        if !order.is_empty() {
+            let mut pinned: Vec<String> = db
+                .fetch_messages(MessageQuery {
+                    limit: Some(config().await.features.limits.default.channel_pins as i64),
+                    filter: MessageFilter {
+                        channel: Some(self.id()),
+                        pinned: Some(true),
+                        ..Default::default()
+                    },
+                    time_period: MessageTimePeriod::Absolute {
+                        before: None,
+                        after: None,
+                        sort: Some(v0::MessageSort::Latest),
+                    },
+                })
+                .await?
+                .into_iter()
+                .map(|message| message.id)
+                .collect();
+
+            let mut sorted = order.clone();
+            sorted.sort();
+            pinned.sort();
+
+            if sorted != pinned {
+                return Err(create_error!(InvalidProperty));
+            }
+        }
+
+        self.update(
+            db,
+            PartialChannel {
+                pin_order: Some(order),
+                ..Default::default()
+            },
+            vec![],
+        )
+        .await?;
+
+        let pins = self.pins();
+        EventV1::ChannelPinsUpdate {
+            id: self.id(),
+            pin_count: pins.count,
+            last_pinned_at: pins.last_pinned_at,
+        }
+        .p(self.id())
+        .await;
+
+        Ok(())
+    }
+
     /// Update channel data
     pub async fn update(
         &mut self,
@@ -485,9 +880,55 @@ impl Channel {
                 }
                 _ => {}
             },
+            FieldsChannel::SlowmodeSeconds => {
+                if let Self::TextChannel {
+                    slowmode_seconds, ..
+                } = self
+                {
+                    slowmode_seconds.take();
+                }
+            }
         }
     }
 
+    /// Check whether link previews are disabled in this channel
+    pub fn embeds_disabled(&self) -> bool {
+        matches!(
+            self,
+            Channel::TextChannel {
+                disable_embeds: true,
+                ..
+            }
+        )
+    }
+
+    /// Get the id of the channel this thread was started in, if this is a thread
+    pub fn parent(&self) -> Option<&str> {
+        match self {
+            Channel::TextChannel {
+                parent: Some(parent),
+                ..
+            } => Some(parent),
+            _ => None,
+        }
+    }
+
+    /// Check whether this channel is a thread which was archived after going idle
+    pub fn is_archived(&self) -> bool {
+        matches!(self, Channel::TextChannel { archived: true, .. })
+    }
+
+    /// Check whether this channel is an announcement channel which can be followed
+    pub fn is_announcement(&self) -> bool {
+        matches!(
+            self,
+            Channel::TextChannel {
+                announcement: true,
+                ..
+            }
+        )
+    }
+
     /// Remove multiple fields from Channel object
     pub fn remove_fields(&mut self, partial: Vec<FieldsChannel>) {
         for field in partial {
@@ -497,6 +938,71 @@ impl Channel {
 
     /// Apply partial channel to channel
     pub fn apply_options(&mut self, partial: PartialChannel) {
+        if let Self::TextChannel {
+            slowmode_seconds,
+            slowmode_applies_to_bots,
+            ..
+        } = self
+        {
+            if let Some(v) = partial.slowmode_seconds {
+                slowmode_seconds.replace(v);
+            }
+
+            if let Some(v) = partial.slowmode_applies_to_bots {
+                *slowmode_applies_to_bots = v;
+            }
+        }
+
+        if let Self::TextChannel {
+            slowmode_exempt_roles,
+            ..
+        } = self
+        {
+            if let Some(v) = partial.slowmode_exempt_roles {
+                *slowmode_exempt_roles = v;
+            }
+        }
+
+        if let Self::TextChannel { reactions_only, .. } = self {
+            if let Some(v) = partial.reactions_only {
+                *reactions_only = v;
+            }
+        }
+
+        if let Self::TextChannel { disable_embeds, .. } = self {
+            if let Some(v) = partial.disable_embeds {
+                *disable_embeds = v;
+            }
+        }
+
+        if let Self::TextChannel { announcement, .. } = self {
+            if let Some(v) = partial.announcement {
+                *announcement = v;
+            }
+        }
+
+        if let Self::TextChannel { archived, .. } = self {
+            if let Some(v) = partial.archived {
+                *archived = v;
+            }
+        }
+
+        if let Self::TextChannel { pin_order, .. } = self {
+            if let Some(v) = partial.pin_order {
+                *pin_order = v;
+            }
+        }
+
+        if let Self::TextChannel {
+            last_thread_message_id,
+            ..
+        } = self
+        {
+            if let Some(v) = partial.last_thread_message_id {
+                last_thread_message_id.replace(v);
+            }
+        }
+
         match self {
             Self::SavedMessages { .. } => {}
             Self::DirectMessage { active, .. } => {
@@ -620,6 +1126,7 @@ impl Channel {
                             MessageAuthor::System {
                                 username: name,
                                 avatar: None,
+                                by: None,
                             },
                             self,
                             false,
@@ -640,6 +1147,11 @@ impl Channel {
                 .await;
 
                 if !silent {
+                    let by_name = match by_id {
+                        Some(by_id) => db.fetch_user(by_id).await.ok().map(|by| by.username),
+                        None => None,
+                    };
+
                     if let Some(by) = by_id {
                         SystemMessage::UserRemove {
                             id: user.id.to_string(),
@@ -656,6 +1168,7 @@ impl Channel {
                         MessageAuthor::System {
                             username: &user.username,
                             avatar: user.avatar.as_ref().map(|file| file.id.as_ref()),
+                            by: by_name,
                         },
                         self,
                         false,
@@ -671,9 +1184,23 @@ impl Channel {
         }
     }
 
-    /// Delete a channel
+    /// Delete a channel along with any threads started in it
+    ///
+    /// Threads take their permissions from the channel they were started in,
+    /// so they must not outlive it.
     pub async fn delete(&self, db: &Database) -> Result<()> {
+        for thread in db.fetch_threads(&self.id()).await? {
+            thread.delete_single(db).await?;
+        }
+
+        self.delete_single(db).await
+    }
+
+    /// Delete only this channel
+    async fn delete_single(&self, db: &Database) -> Result<()> {
         let id = self.id().to_string();
+        db.delete_channel_follows(&id).await?;
+        db.delete_channel_notification_preferences(&id).await?;
         EventV1::ChannelDelete { id: id.clone() }.p(id).await;
         db.delete_channel(self).await
     }
@@ -685,15 +1212,118 @@ impl IntoDocumentPath for FieldsChannel {
             FieldsChannel::Description => "description",
             FieldsChannel::Icon => "icon",
             FieldsChannel::DefaultPermissions => "default_permissions",
+            FieldsChannel::SlowmodeSeconds => "slowmode_seconds",
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+    use onechatsocial_config::config;
+    use onechatsocial_models::v0;
+    use onechatsocial_permissions::{
+        calculate_channel_permissions, ChannelPermission, PermissionValue,
+    };
+    use onechatsocial_result::ErrorType;
+
+    use crate::{
+        fixture, util::permissions::DatabasePermissionQuery, Channel, Message, MessageFilter,
+        MessageQuery, MessageTimePeriod, PartialChannel, SystemMessage,
+    };
+
+    #[async_std::test]
+    async fn group_size_limit() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                member2 user 2);
+
+            let config = config().await;
+            let mut recipients = vec![owner.id.clone()];
+            while recipients.len() < config.features.limits.default.group_size {
+                recipients.push(ulid::Ulid::new().to_string());
+            }
 
-    use crate::{fixture, util::permissions::DatabasePermissionQuery};
+            let mut channel = Channel::Group {
+                id: ulid::Ulid::new().to_string(),
+                name: "Full Group".to_string(),
+                owner: owner.id.clone(),
+                description: None,
+                recipients,
+                icon: None,
+                last_message_id: None,
+                permissions: None,
+                nsfw: false,
+                pins: None,
+            };
+
+            db.insert_channel(&channel).await.unwrap();
+
+            let error = channel
+                .add_user_to_group(&db, &member2, &owner.id)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(error.error_type, ErrorType::GroupTooLarge { .. }));
+            assert!(!db
+                .fetch_channel(&channel.id())
+                .await
+                .unwrap()
+                .contains_user(&member2.id));
+        });
+    }
+
+    #[async_std::test]
+    async fn group_size_limit_on_create() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0);
+
+            let config = config().await;
+            let mut users = std::collections::HashSet::new();
+            while users.len() + 1 < config.features.limits.default.group_size {
+                users.insert(ulid::Ulid::new().to_string());
+            }
+
+            // Exactly at the limit once the owner is included
+            let channel = Channel::create_group(
+                &db,
+                v0::DataCreateGroup {
+                    name: "Full Group".to_string(),
+                    description: None,
+                    icon: None,
+                    users: users.clone(),
+                    nsfw: None,
+                },
+                owner.id.clone(),
+            )
+            .await
+            .unwrap();
+
+            if let Channel::Group { recipients, .. } = &channel {
+                assert_eq!(recipients.len(), config.features.limits.default.group_size);
+            } else {
+                unreachable!()
+            }
+
+            users.insert(ulid::Ulid::new().to_string());
+            let error = Channel::create_group(
+                &db,
+                v0::DataCreateGroup {
+                    name: "Oversized Group".to_string(),
+                    description: None,
+                    icon: None,
+                    users,
+                    nsfw: None,
+                },
+                owner.id.clone(),
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(error.error_type, ErrorType::GroupTooLarge { .. }));
+        });
+    }
 
     #[async_std::test]
     async fn permissions_group_channel() {
@@ -746,4 +1376,298 @@ mod tests {
                 .has_channel_permission(ChannelPermission::SendMessage));
         });
     }
+
+    #[async_std::test]
+    async fn slowmode_exempt_roles() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                moderator user 1
+                user user 2
+                server server 4
+                channel channel 3);
+
+            let moderator = db.fetch_member(&server.id, &moderator.id).await.unwrap();
+            let user = db.fetch_member(&server.id, &user.id).await.unwrap();
+
+            let mut channel = channel;
+            assert!(!channel.is_slowmode_exempt(&moderator));
+
+            channel.apply_options(PartialChannel {
+                slowmode_exempt_roles: Some(moderator.roles.clone()),
+                ..Default::default()
+            });
+
+            assert!(channel.is_slowmode_exempt(&moderator));
+            assert!(!channel.is_slowmode_exempt(&user));
+        });
+    }
+
+    #[async_std::test]
+    async fn server_channel_limit() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                server server 4);
+
+            let config = config().await;
+            let limit = config.features.limits.default.server_channels;
+
+            let mut server = server;
+            while server.channels.len() < limit {
+                server.channels.push(ulid::Ulid::new().to_string());
+            }
+
+            let error = Channel::create_server_channel(
+                &db,
+                &mut server,
+                v0::DataCreateServerChannel {
+                    name: "One Too Many".to_string(),
+                    ..Default::default()
+                },
+                false,
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(
+                error.error_type,
+                ErrorType::TooManyChannels { max } if max == limit
+            ));
+        });
+    }
+
+    #[async_std::test]
+    async fn reactions_only() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                moderator user 1
+                channel channel 3);
+
+            let mut channel = channel;
+            assert!(!channel.is_reactions_only());
+
+            channel.apply_options(PartialChannel {
+                reactions_only: Some(true),
+                ..Default::default()
+            });
+
+            assert!(channel.is_reactions_only());
+
+            // Reacting is checked on its own, moderators may still post
+            let mut query = DatabasePermissionQuery::new(&db, &moderator).channel(&channel);
+            let permissions = calculate_channel_permissions(&mut query).await;
+            assert!(permissions.has_channel_permission(ChannelPermission::React));
+            assert!(permissions.has_channel_permission(ChannelPermission::ManageMessages));
+        });
+    }
+
+    #[async_std::test]
+    async fn disable_embeds() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                channel channel 3);
+
+            let mut channel = channel;
+            assert!(!channel.embeds_disabled());
+
+            channel.apply_options(PartialChannel {
+                disable_embeds: Some(true),
+                ..Default::default()
+            });
+
+            assert!(channel.embeds_disabled());
+        });
+    }
+
+    #[async_std::test]
+    async fn announcement() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                channel channel 3);
+
+            let mut channel = channel;
+            assert!(!channel.is_announcement());
+
+            channel.apply_options(PartialChannel {
+                announcement: Some(true),
+                ..Default::default()
+            });
+
+            assert!(channel.is_announcement());
+        });
+    }
+
+    #[async_std::test]
+    async fn threads() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                owner user 0
+                moderator user 1
+                user user 2
+                channel channel 3);
+
+            let message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: owner.id.to_string(),
+                content: Some("Start a thread here".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&message).await.unwrap();
+
+            let thread = channel
+                .create_thread(&db, &message, "Thread".to_string(), &owner.id)
+                .await
+                .unwrap();
+
+            assert_eq!(Some(channel.id().as_str()), thread.parent());
+            assert!(matches!(
+                &thread,
+                Channel::TextChannel { root_message: Some(root_message), .. } if root_message == &message.id
+            ));
+
+            // Only one thread may be started from each message
+            let error = channel
+                .create_thread(&db, &message, "Again".to_string(), &owner.id)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(error.error_type, ErrorType::InvalidOperation));
+
+            // Threads take their permissions from the parent channel
+            let mut query = DatabasePermissionQuery::new(&db, &moderator).channel(&thread);
+            assert!(calculate_channel_permissions(&mut query)
+                .await
+                .has_channel_permission(ChannelPermission::SendMessage));
+
+            let mut query = DatabasePermissionQuery::new(&db, &user).channel(&thread);
+            assert!(!calculate_channel_permissions(&mut query)
+                .await
+                .has_channel_permission(ChannelPermission::SendMessage));
+
+            // Thread creation is announced in the parent channel
+            let announcements = db
+                .fetch_messages(MessageQuery {
+                    limit: Some(50),
+                    filter: MessageFilter {
+                        channel: Some(channel.id()),
+                        ..Default::default()
+                    },
+                    time_period: MessageTimePeriod::Absolute {
+                        before: None,
+                        after: None,
+                        sort: None,
+                    },
+                })
+                .await
+                .unwrap();
+
+            assert!(announcements.iter().any(|announcement| matches!(
+                &announcement.system,
+                Some(SystemMessage::ThreadCreated { id, by, .. }) if id == &thread.id() && by == &owner.id
+            )));
+
+            // Idle threads are archived until someone speaks in them again
+            let cutoff = ulid::Ulid::new().to_string();
+            let idle = db.fetch_idle_threads(&cutoff, 100).await.unwrap();
+            assert!(idle.iter().any(|idle| idle.id() == thread.id()));
+
+            let mut thread = thread;
+            thread
+                .update(
+                    &db,
+                    PartialChannel {
+                        archived: Some(true),
+                        ..Default::default()
+                    },
+                    vec![],
+                )
+                .await
+                .unwrap();
+
+            assert!(db.fetch_channel(&thread.id()).await.unwrap().is_archived());
+
+            let idle = db.fetch_idle_threads(&cutoff, 100).await.unwrap();
+            assert!(!idle.iter().any(|idle| idle.id() == thread.id()));
+
+            // Threads are deleted along with the channel they were started in
+            channel.delete(&db).await.unwrap();
+            assert!(db.fetch_channel(&thread.id()).await.is_err());
+
+            // Threads left without a parent channel grant nothing, even to the owner
+            db.insert_channel(&thread).await.unwrap();
+            let mut query = DatabasePermissionQuery::new(&db, &owner).channel(&thread);
+            assert!(!calculate_channel_permissions(&mut query)
+                .await
+                .has_channel_permission(ChannelPermission::ViewChannel));
+        });
+    }
+
+    #[async_std::test]
+    async fn pin_order() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                owner user 0
+                channel channel 3);
+
+            let mut channel = channel;
+            let permissions = PermissionValue::from(ChannelPermission::ManageMessages);
+
+            let mut messages = vec![];
+            for content in ["First", "Second"] {
+                let mut message = Message {
+                    id: ulid::Ulid::new().to_string(),
+                    channel: channel.id(),
+                    author: owner.id.to_string(),
+                    content: Some(content.to_string()),
+                    ..Default::default()
+                };
+
+                db.insert_message(&message).await.unwrap();
+                message
+                    .set_pinned(&db, &channel, &permissions, true, &owner.id)
+                    .await
+                    .unwrap();
+
+                messages.push(message);
+            }
+
+            let ids = |messages: &[Message]| {
+                messages
+                    .iter()
+                    .map(|message| message.id.clone())
+                    .collect::<Vec<String>>()
+            };
+
+            let (first, second) = (messages[0].id.clone(), messages[1].id.clone());
+
+            // Most recently pinned comes first by default
+            channel.sort_pins(&mut messages);
+            assert_eq!(ids(&messages), vec![second.clone(), first.clone()]);
+
+            // The order must list every pinned message exactly once
+            for order in [vec![first.clone()], vec![first.clone(), first.clone()]] {
+                let error = channel.set_pin_order(&db, order).await.unwrap_err();
+                assert!(matches!(error.error_type, ErrorType::InvalidProperty));
+            }
+
+            channel
+                .set_pin_order(&db, vec![first.clone(), second.clone()])
+                .await
+                .unwrap();
+
+            channel.sort_pins(&mut messages);
+            assert_eq!(ids(&messages), vec![first.clone(), second.clone()]);
+
+            // The order is persisted on the channel
+            let mut fetched = db.fetch_channel(&channel.id()).await.unwrap();
+            fetched.sort_pins(&mut messages);
+            assert_eq!(ids(&messages), vec![first.clone(), second.clone()]);
+
+            // Clearing the order goes back to pin time
+            fetched.set_pin_order(&db, vec![]).await.unwrap();
+            fetched.sort_pins(&mut messages);
+            assert_eq!(ids(&messages), vec![second, first]);
+        });
+    }
 }
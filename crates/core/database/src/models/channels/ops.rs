@@ -1,4 +1,5 @@
 use crate::{onechatsocial_result::Result, Channel, FieldsChannel, PartialChannel};
+use iso8601_timestamp::Timestamp;
 use onechatsocial_permissions::OverrideField;
 mod mongodb;
 mod reference;
@@ -23,6 +24,15 @@ pub trait AbstractChannels: Sync + Send {
     // Fetch direct message channel (DM or Saved Messages)
     async fn find_direct_message_channel(&self, user_a: &str, user_b: &str) -> Result<Channel>;
 
+    /// Fetch the thread started from a message
+    async fn fetch_thread_by_root_message(&self, message_id: &str) -> Result<Option<Channel>>;
+
+    /// Fetch all threads started in a channel
+    async fn fetch_threads(&self, parent_id: &str) -> Result<Vec<Channel>>;
+
+    /// Fetch threads which are not archived and have had no messages since the given id
+    async fn fetch_idle_threads(&self, before: &str, limit: i64) -> Result<Vec<Channel>>;
+
     /// Insert a user to a group
     async fn add_user_to_group(&self, channel_id: &str, user_id: &str) -> Result<()>;
 
@@ -45,6 +55,19 @@ pub trait AbstractChannels: Sync + Send {
     // Remove a user from a group
     async fn remove_user_from_group(&self, channel_id: &str, user_id: &str) -> Result<()>;
 
+    /// Count a newly pinned message in a channel
+    ///
+    /// Returns false without counting it if the channel already has `max` pinned messages.
+    async fn add_channel_pin(
+        &self,
+        channel_id: &str,
+        pinned_at: Timestamp,
+        max: usize,
+    ) -> Result<bool>;
+
+    /// Stop counting messages which are no longer pinned in a channel
+    async fn remove_channel_pins(&self, channel_id: &str, count: usize) -> Result<()>;
+
     // Delete a channel
     async fn delete_channel(&self, channel_id: &Channel) -> Result<()>;
 }
@@ -1,7 +1,9 @@
 use super::AbstractChannels;
 use crate::{Channel, FieldsChannel, IntoDocumentPath, MongoDb, PartialChannel};
-use bson::Document;
+use ::mongodb::options::FindOptions;
+use bson::{to_bson, Document};
 use futures::StreamExt;
+use iso8601_timestamp::Timestamp;
 use onechatsocial_permissions::OverrideField;
 use onechatsocial_result::Result;
 
@@ -107,6 +109,66 @@ impl AbstractChannels for MongoDb {
         query!(self, find_one, COL, doc)?.ok_or_else(|| create_error!(NotFound))
     }
 
+    /// Fetch the thread started from a message
+    async fn fetch_thread_by_root_message(&self, message_id: &str) -> Result<Option<Channel>> {
+        query!(
+            self,
+            find_one,
+            COL,
+            doc! {
+                "channel_type": "TextChannel",
+                "root_message": message_id
+            }
+        )
+    }
+
+    /// Fetch all threads started in a channel
+    async fn fetch_threads(&self, parent_id: &str) -> Result<Vec<Channel>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "channel_type": "TextChannel",
+                "parent": parent_id
+            }
+        )
+    }
+
+    /// Fetch threads which are not archived and have had no messages since the given id
+    async fn fetch_idle_threads(&self, before: &str, limit: i64) -> Result<Vec<Channel>> {
+        query!(
+            self,
+            find_with_options,
+            COL,
+            doc! {
+                "channel_type": "TextChannel",
+                "parent": {
+                    "$exists": true
+                },
+                "archived": {
+                    "$ne": true
+                },
+                "$or": [
+                    {
+                        "last_message_id": {
+                            "$lt": before
+                        }
+                    },
+                    {
+                        "last_message_id": {
+                            "$exists": false
+                        },
+                        "_id": {
+                            "$lt": before
+                        }
+                    }
+                ]
+            },
+            FindOptions::builder().limit(limit).build()
+        )
+    }
+
     /// Insert a user to a group
     async fn add_user_to_group(&self, channel: &str, user: &str) -> Result<()> {
         self.col::<Document>(COL)
@@ -186,6 +248,68 @@ impl AbstractChannels for MongoDb {
             .map_err(|_| create_database_error!("update_one", "channels"))
     }
 
+    /// Count a newly pinned message in a channel
+    async fn add_channel_pin(
+        &self,
+        channel: &str,
+        pinned_at: Timestamp,
+        max: usize,
+    ) -> Result<bool> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": channel,
+                    "pins.count": {
+                        "$not": {
+                            "$gte": i64::try_from(max).unwrap_or(i64::MAX)
+                        }
+                    }
+                },
+                doc! {
+                    "$inc": {
+                        "pins.count": 1_i64
+                    },
+                    "$max": {
+                        "pins.last_pinned_at": to_bson(&pinned_at)
+                            .map_err(|_| create_database_error!("to_bson", "pinned_at"))?
+                    }
+                },
+                None,
+            )
+            .await
+            .map(|result| result.matched_count == 1)
+            .map_err(|_| create_database_error!("update_one", "channels"))
+    }
+
+    /// Stop counting messages which are no longer pinned in a channel
+    async fn remove_channel_pins(&self, channel: &str, count: usize) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": channel
+                },
+                vec![doc! {
+                    "$set": {
+                        "pins.count": {
+                            "$max": [
+                                0_i64,
+                                {
+                                    "$subtract": [
+                                        { "$ifNull": ["$pins.count", 0_i64] },
+                                        count as i64
+                                    ]
+                                }
+                            ]
+                        }
+                    }
+                }],
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", "channels"))
+    }
+
     // Delete a channel
     async fn delete_channel(&self, channel: &Channel) -> Result<()> {
         query!(self, delete_one_by_id, COL, &channel.id()).map(|_| ())
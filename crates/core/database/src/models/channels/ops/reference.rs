@@ -3,6 +3,7 @@ use std::collections::hash_map::Entry;
 use super::AbstractChannels;
 use crate::ReferenceDb;
 use crate::{Channel, FieldsChannel, PartialChannel};
+use iso8601_timestamp::Timestamp;
 use onechatsocial_permissions::OverrideField;
 use onechatsocial_result::Result;
 
@@ -70,6 +71,62 @@ impl AbstractChannels for ReferenceDb {
         }
         Err(create_error!(NotFound))
     }
+
+    /// Fetch the thread started from a message
+    async fn fetch_thread_by_root_message(&self, message_id: &str) -> Result<Option<Channel>> {
+        let channels = self.channels.lock().await;
+        Ok(channels
+            .values()
+            .find(|channel| {
+                matches!(
+                    channel,
+                    Channel::TextChannel {
+                        root_message: Some(root_message),
+                        ..
+                    } if root_message == message_id
+                )
+            })
+            .cloned())
+    }
+
+    /// Fetch all threads started in a channel
+    async fn fetch_threads(&self, parent_id: &str) -> Result<Vec<Channel>> {
+        let channels = self.channels.lock().await;
+        Ok(channels
+            .values()
+            .filter(|channel| {
+                matches!(
+                    channel,
+                    Channel::TextChannel {
+                        parent: Some(parent),
+                        ..
+                    } if parent == parent_id
+                )
+            })
+            .cloned()
+            .collect())
+    }
+
+    /// Fetch threads which are not archived and have had no messages since the given id
+    async fn fetch_idle_threads(&self, before: &str, limit: i64) -> Result<Vec<Channel>> {
+        let channels = self.channels.lock().await;
+        Ok(channels
+            .values()
+            .filter(|channel| match channel {
+                Channel::TextChannel {
+                    id,
+                    parent: Some(_),
+                    archived: false,
+                    last_message_id,
+                    ..
+                } => last_message_id.as_deref().unwrap_or(id) < before,
+                _ => false,
+            })
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
     /// Insert a user to a group
     async fn add_user_to_group(&self, channel_id: &str, user_id: &str) -> Result<()> {
         let mut channels = self.channels.lock().await;
@@ -145,6 +202,42 @@ impl AbstractChannels for ReferenceDb {
         Err(create_error!(NotFound))
     }
 
+    /// Count a newly pinned message in a channel
+    async fn add_channel_pin(
+        &self,
+        channel: &str,
+        pinned_at: Timestamp,
+        max: usize,
+    ) -> Result<bool> {
+        let mut channels = self.channels.lock().await;
+        let pins = channels
+            .get_mut(channel)
+            .ok_or_else(|| create_error!(NotFound))?
+            .pins_mut()
+            .get_or_insert_with(Default::default);
+
+        if pins.count >= max {
+            return Ok(false);
+        }
+
+        pins.count += 1;
+        pins.last_pinned_at = pins.last_pinned_at.max(Some(pinned_at));
+        Ok(true)
+    }
+
+    /// Stop counting messages which are no longer pinned in a channel
+    async fn remove_channel_pins(&self, channel: &str, count: usize) -> Result<()> {
+        let mut channels = self.channels.lock().await;
+        let pins = channels
+            .get_mut(channel)
+            .ok_or_else(|| create_error!(NotFound))?
+            .pins_mut()
+            .get_or_insert_with(Default::default);
+
+        pins.count = pins.count.saturating_sub(count);
+        Ok(())
+    }
+
     // Delete a channel
     async fn delete_channel(&self, channel: &Channel) -> Result<()> {
         let mut channels = self.channels.lock().await;
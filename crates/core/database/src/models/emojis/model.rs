@@ -2,7 +2,7 @@ use std::collections::HashSet;
 use std::str::FromStr;
 
 use once_cell::sync::Lazy;
-use onechatsocial_result::Result;
+use onechatsocial_result::{ErrorType, Result};
 use ulid::Ulid;
 
 use crate::events::client::EventV1;
@@ -75,13 +75,145 @@ impl Emoji {
         db.detach_emoji(&self).await
     }
 
-    /// Check whether we can use a given emoji
-    pub async fn can_use(db: &Database, emoji: &str) -> Result<bool> {
+    /// Delete many emoji belonging to a server at once
+    ///
+    /// Returns the ids of the emoji which were deleted, any other ids
+    /// were either not found or do not belong to the given server.
+    pub async fn delete_many(
+        db: &Database,
+        server_id: &str,
+        ids: &[String],
+    ) -> Result<Vec<String>> {
+        let emojis = db.fetch_emoji_by_parent_id(server_id).await?;
+
+        let mut deleted = vec![];
+        for emoji in emojis.into_iter().filter(|emoji| ids.contains(&emoji.id)) {
+            let id = emoji.id.clone();
+            if emoji.delete(db).await.is_ok() {
+                deleted.push(id);
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// Check whether a user can use a given emoji
+    ///
+    /// Custom emoji may only be used by members of the server they belong to.
+    pub async fn can_use(db: &Database, user_id: &str, emoji: &str) -> Result<bool> {
         if Ulid::from_str(emoji).is_ok() {
-            db.fetch_emoji(emoji).await?;
-            Ok(true)
+            match db.fetch_emoji(emoji).await?.parent {
+                EmojiParent::Server { id } => match db.fetch_member(&id, user_id).await {
+                    Ok(_) => Ok(true),
+                    Err(error) if matches!(error.error_type, ErrorType::NotFound) => Ok(false),
+                    Err(error) => Err(error),
+                },
+                EmojiParent::Detached => Ok(false),
+            }
         } else {
             Ok(PERMISSIBLE_EMOJIS.contains(emoji))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use onechatsocial_result::ErrorType;
+
+    use crate::{fixture, Emoji, EmojiParent, Message};
+
+    #[async_std::test]
+    async fn delete_many() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                owner user 0
+                server server 4);
+
+            let create = |parent: EmojiParent| Emoji {
+                id: ulid::Ulid::new().to_string(),
+                parent,
+                creator_id: owner.id.clone(),
+                name: "emoji".to_string(),
+                animated: false,
+                nsfw: false,
+            };
+
+            let ours = create(EmojiParent::Server {
+                id: server.id.clone(),
+            });
+            let other = create(EmojiParent::Server {
+                id: ulid::Ulid::new().to_string(),
+            });
+
+            db.insert_emoji(&ours).await.unwrap();
+            db.insert_emoji(&other).await.unwrap();
+
+            let missing = ulid::Ulid::new().to_string();
+            let deleted = Emoji::delete_many(
+                &db,
+                &server.id,
+                &[ours.id.clone(), other.id.clone(), missing],
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(vec![ours.id.clone()], deleted);
+            assert_eq!(
+                EmojiParent::Detached,
+                db.fetch_emoji(&ours.id).await.unwrap().parent
+            );
+            assert_ne!(
+                EmojiParent::Detached,
+                db.fetch_emoji(&other.id).await.unwrap().parent
+            );
+        });
+    }
+
+    #[async_std::test]
+    async fn cross_server_emoji() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                user user 2
+                channel channel 3
+                server server 4);
+
+            let create = |server: String| Emoji {
+                id: ulid::Ulid::new().to_string(),
+                parent: EmojiParent::Server { id: server },
+                creator_id: user.id.clone(),
+                name: "emoji".to_string(),
+                animated: false,
+                nsfw: false,
+            };
+
+            let ours = create(server.id.clone());
+            let other = create(ulid::Ulid::new().to_string());
+            db.insert_emoji(&ours).await.unwrap();
+            db.insert_emoji(&other).await.unwrap();
+
+            assert!(Emoji::can_use(&db, &user.id, &ours.id).await.unwrap());
+            assert!(!Emoji::can_use(&db, &user.id, &other.id).await.unwrap());
+            assert!(Emoji::can_use(&db, &user.id, "👍").await.unwrap());
+            assert!(!Emoji::can_use(&db, &user.id, "not an emoji").await.unwrap());
+
+            // Reacting with emoji from a server we are not in is refused
+            let message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: user.id.clone(),
+                content: Some("React to me!".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&message).await.unwrap();
+
+            let error = message
+                .add_reaction(&db, &user.id, &other.id)
+                .await
+                .unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::InvalidOperation));
+
+            message.add_reaction(&db, &user.id, &ours.id).await.unwrap();
+        });
+    }
+}
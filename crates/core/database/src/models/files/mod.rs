@@ -1,5 +1,7 @@
 mod model;
 mod ops;
+mod scanner;
 
 pub use model::*;
 pub use ops::*;
+pub use scanner::*;
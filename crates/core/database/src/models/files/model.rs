@@ -18,6 +18,18 @@ auto_derived_partial!(
         pub content_type: String,
         /// Size of this file (in bytes)
         pub size: isize,
+        /// Alternative text describing this file
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub description: Option<String>,
+        /// Whether this file is a recorded voice message
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub is_voice: bool,
+        /// Waveform of a voice message, one amplitude sample per byte
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub waveform: Option<Vec<u8>>,
+        /// Length of a voice message in milliseconds
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub duration: Option<u32>,
 
         /// Whether this file was deleted
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -63,6 +75,11 @@ auto_derived!(
 );
 
 impl File {
+    /// Whether this file was marked as a spoiler by its uploader
+    pub fn is_spoiler(&self) -> bool {
+        self.filename.starts_with("SPOILER_")
+    }
+
     /// Use a file for a message attachment
     pub async fn use_attachment(db: &Database, id: &str, parent: &str) -> Result<File> {
         db.find_and_use_attachment(id, "attachments", "message", parent)
@@ -99,6 +116,12 @@ impl File {
             .await
     }
 
+    /// Use a file for a role icon
+    pub async fn use_role_icon(db: &Database, id: &str, parent: &str) -> Result<File> {
+        db.find_and_use_attachment(id, "icons", "object", parent)
+            .await
+    }
+
     /// Use a file for an emoji
     pub async fn use_emoji(db: &Database, id: &str, parent: &str) -> Result<File> {
         db.find_and_use_attachment(id, "emojis", "object", parent)
@@ -1,4 +1,5 @@
 use bson::Document;
+use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
 use onechatsocial_result::Result;
 
 use crate::File;
@@ -15,6 +16,20 @@ impl AbstractAttachments for MongoDb {
         query!(self, insert_one, COL, &attachment).map(|_| ())
     }
 
+    /// Fetch an attachment by its id and tag.
+    async fn fetch_attachment(&self, tag: &str, file_id: &str) -> Result<File> {
+        query!(
+            self,
+            find_one,
+            COL,
+            doc! {
+                "_id": file_id,
+                "tag": tag
+            }
+        )?
+        .ok_or_else(|| create_error!(NotFound))
+    }
+
     /// Find an attachment by its details and mark it as used by a given parent.
     async fn find_and_use_attachment(
         &self,
@@ -24,36 +39,27 @@ impl AbstractAttachments for MongoDb {
         parent_id: &str,
     ) -> Result<File> {
         let key = format!("{parent_type}_id");
-        let file = query!(
-            self,
-            find_one,
-            COL,
-            doc! {
-                "_id": id,
-                "tag": tag,
-                &key: {
-                    "$exists": false
-                }
-            }
-        )?
-        .ok_or_else(|| create_error!(NotFound))?;
-
-        self.col::<Document>(COL)
-            .update_one(
+        self.col::<File>(COL)
+            .find_one_and_update(
                 doc! {
-                    "_id": id
+                    "_id": id,
+                    "tag": tag,
+                    &key: {
+                        "$exists": false
+                    }
                 },
                 doc! {
                     "$set": {
-                        key: parent_id
+                        &key: parent_id
                     }
                 },
-                None,
+                FindOneAndUpdateOptions::builder()
+                    .return_document(ReturnDocument::After)
+                    .build(),
             )
             .await
-            .map_err(|_| create_database_error!("update_one", COL))?;
-
-        Ok(file)
+            .map_err(|_| create_database_error!("find_one_and_update", COL))?
+            .ok_or_else(|| create_error!(NotFound))
     }
 
     /// Mark an attachment as having been reported.
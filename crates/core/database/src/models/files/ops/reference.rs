@@ -18,6 +18,16 @@ impl AbstractAttachments for ReferenceDb {
         }
     }
 
+    /// Fetch an attachment by its id and tag.
+    async fn fetch_attachment(&self, tag: &str, file_id: &str) -> Result<File> {
+        let files = self.files.lock().await;
+        files
+            .get(file_id)
+            .filter(|file| file.tag == tag)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
     /// Find an attachment by its details and mark it as used by a given parent.
     async fn find_and_use_attachment(
         &self,
@@ -28,15 +38,16 @@ impl AbstractAttachments for ReferenceDb {
     ) -> Result<File> {
         let mut files = self.files.lock().await;
         if let Some(file) = files.get_mut(id) {
-            if file.tag == tag {
-                match parent_type {
-                    "message" => file.message_id = Some(parent_id.to_owned()),
-                    "user" => file.user_id = Some(parent_id.to_owned()),
-                    "object" => file.object_id = Some(parent_id.to_owned()),
-                    "server" => file.server_id = Some(parent_id.to_owned()),
-                    _ => unreachable!(),
-                }
+            let parent = match parent_type {
+                "message" => &mut file.message_id,
+                "user" => &mut file.user_id,
+                "object" => &mut file.object_id,
+                "server" => &mut file.server_id,
+                _ => unreachable!(),
+            };
 
+            if file.tag == tag && parent.is_none() {
+                parent.replace(parent_id.to_owned());
                 Ok(file.clone())
             } else {
                 Err(create_error!(NotFound))
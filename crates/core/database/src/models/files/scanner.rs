@@ -0,0 +1,52 @@
+use once_cell::sync::OnceCell;
+
+use crate::File;
+
+/// Result of scanning an attachment
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ScanVerdict {
+    /// Attachment is safe to use
+    Clean,
+    /// Attachment should not be used
+    Quarantine {
+        /// Reason given by the scanner
+        reason: String,
+    },
+}
+
+/// Scanner invoked before an attachment is used in a message
+///
+/// Self-hosters may provide their own implementation (e.g. backed by ClamAV)
+/// using [`set_attachment_scanner`] before starting the server.
+#[async_trait]
+pub trait AttachmentScanner: Sync + Send {
+    /// Scan a given attachment
+    async fn scan(&self, file: &File) -> ScanVerdict;
+}
+
+/// Scanner which considers every attachment to be clean
+pub struct NoopAttachmentScanner;
+
+#[async_trait]
+impl AttachmentScanner for NoopAttachmentScanner {
+    /// Scan a given attachment
+    async fn scan(&self, _file: &File) -> ScanVerdict {
+        ScanVerdict::Clean
+    }
+}
+
+static SCANNER: OnceCell<Box<dyn AttachmentScanner>> = OnceCell::new();
+
+/// Set the attachment scanner to use, this can only be done once
+///
+/// Returns false if a scanner has already been set.
+pub fn set_attachment_scanner<S: AttachmentScanner + 'static>(scanner: S) -> bool {
+    SCANNER.set(Box::new(scanner)).is_ok()
+}
+
+/// Get the attachment scanner in use
+pub fn attachment_scanner() -> &'static dyn AttachmentScanner {
+    SCANNER
+        .get_or_init(|| Box::new(NoopAttachmentScanner))
+        .as_ref()
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use iso8601_timestamp::Timestamp;
+use onechatsocial_config::config;
+use onechatsocial_result::Result;
+
+use crate::{util::ulid_generator, Database, Message};
+
+auto_derived!(
+    /// Record of a deleted message kept for moderation audit
+    ///
+    /// Does not include any of the message content.
+    pub struct MessageTombstone {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the deleted message
+        pub message: String,
+        /// Id of the channel the message was sent in
+        pub channel: String,
+        /// Id of the user or webhook that sent the message
+        pub author: String,
+        /// Id of the user that deleted the message
+        pub deleted_by: String,
+        /// Time at which the message was deleted
+        pub deleted_at: Timestamp,
+    }
+);
+
+impl MessageTombstone {
+    /// Retention period for tombstones, if enabled
+    pub async fn retention() -> Option<Duration> {
+        match config().await.features.message_tombstone_retention {
+            0 => None,
+            seconds => Some(Duration::from_secs(seconds)),
+        }
+    }
+
+    /// Record that a message has been deleted
+    pub async fn create(db: &Database, message: &Message, deleted_by: &str) -> Result<()> {
+        MessageTombstone::create_many(
+            db,
+            &message.channel,
+            &[(message.id.to_string(), message.author.to_string())],
+            deleted_by,
+        )
+        .await
+    }
+
+    /// Record that several messages in a channel have been deleted
+    ///
+    /// Messages are given as pairs of message id and author id.
+    pub async fn create_many(
+        db: &Database,
+        channel: &str,
+        messages: &[(String, String)],
+        deleted_by: &str,
+    ) -> Result<()> {
+        let Some(retention) = MessageTombstone::retention().await else {
+            return Ok(());
+        };
+
+        let deleted_at = Timestamp::now_utc();
+        for (message, author) in messages {
+            db.insert_message_tombstone(&MessageTombstone {
+                id: ulid_generator::generate().to_string(),
+                message: message.to_string(),
+                channel: channel.to_string(),
+                author: author.to_string(),
+                deleted_by: deleted_by.to_string(),
+                deleted_at,
+            })
+            .await?;
+        }
+
+        // Opportunistically clean up tombstones which have expired
+        db.delete_expired_message_tombstones(retention).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{Message, MessageTombstone};
+
+    #[async_std::test]
+    async fn record_and_restore() {
+        database_test!(|db| async move {
+            let channel = ulid::Ulid::new().to_string();
+            let message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.to_string(),
+                author: ulid::Ulid::new().to_string(),
+                content: Some("Secret".to_string()),
+                ..Default::default()
+            };
+
+            let moderator = ulid::Ulid::new().to_string();
+            MessageTombstone::create(&db, &message, &moderator)
+                .await
+                .unwrap();
+
+            let period = MessageTombstone::retention().await.unwrap();
+            let tombstones = db
+                .fetch_message_tombstones(&channel, period, 50)
+                .await
+                .unwrap();
+
+            assert_eq!(tombstones.len(), 1);
+            assert_eq!(tombstones[0].message, message.id);
+            assert_eq!(tombstones[0].author, message.author);
+            assert_eq!(tombstones[0].deleted_by, moderator);
+
+            // Tombstones outside of the period are not returned
+            async_std::task::sleep(Duration::from_millis(5)).await;
+            assert!(db
+                .fetch_message_tombstones(&channel, Duration::from_millis(1), 50)
+                .await
+                .unwrap()
+                .is_empty());
+
+            db.delete_message_tombstone(&message.id).await.unwrap();
+            assert!(db
+                .fetch_message_tombstones(&channel, period, 50)
+                .await
+                .unwrap()
+                .is_empty());
+        });
+    }
+}
@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use onechatsocial_result::Result;
+
+use crate::MessageTombstone;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractMessageTombstones: Sync + Send {
+    /// Insert a new message tombstone into the database
+    async fn insert_message_tombstone(&self, tombstone: &MessageTombstone) -> Result<()>;
+
+    /// Fetch tombstones in a channel created within the given period, newest first
+    async fn fetch_message_tombstones(
+        &self,
+        channel: &str,
+        period: Duration,
+        limit: i64,
+    ) -> Result<Vec<MessageTombstone>>;
+
+    /// Delete the tombstone for a message which has been restored
+    async fn delete_message_tombstone(&self, message: &str) -> Result<()>;
+
+    /// Delete all tombstones older than the given period
+    async fn delete_expired_message_tombstones(&self, period: Duration) -> Result<()>;
+}
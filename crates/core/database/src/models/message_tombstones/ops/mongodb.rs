@@ -0,0 +1,76 @@
+use std::time::{Duration, SystemTime};
+
+use mongodb::options::FindOptions;
+use onechatsocial_result::Result;
+use ulid::Ulid;
+
+use crate::MessageTombstone;
+use crate::MongoDb;
+
+use super::AbstractMessageTombstones;
+
+static COL: &str = "message_tombstones";
+
+#[async_trait]
+impl AbstractMessageTombstones for MongoDb {
+    /// Insert a new message tombstone into the database
+    async fn insert_message_tombstone(&self, tombstone: &MessageTombstone) -> Result<()> {
+        query!(self, insert_one, COL, &tombstone).map(|_| ())
+    }
+
+    /// Fetch tombstones in a channel created within the given period, newest first
+    async fn fetch_message_tombstones(
+        &self,
+        channel: &str,
+        period: Duration,
+        limit: i64,
+    ) -> Result<Vec<MessageTombstone>> {
+        query!(
+            self,
+            find_with_options,
+            COL,
+            doc! {
+                "_id": {
+                    "$gte": Ulid::from_datetime(SystemTime::now() - period).to_string()
+                },
+                "channel": channel
+            },
+            FindOptions::builder()
+                .sort(doc! {
+                    "_id": -1_i32
+                })
+                .limit(limit)
+                .build()
+        )
+    }
+
+    /// Delete the tombstone for a message which has been restored
+    async fn delete_message_tombstone(&self, message: &str) -> Result<()> {
+        self.col::<MessageTombstone>(COL)
+            .delete_many(
+                doc! {
+                    "message": message
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("delete_many", COL))
+    }
+
+    /// Delete all tombstones older than the given period
+    async fn delete_expired_message_tombstones(&self, period: Duration) -> Result<()> {
+        self.col::<MessageTombstone>(COL)
+            .delete_many(
+                doc! {
+                    "_id": {
+                        "$lt": Ulid::from_datetime(SystemTime::now() - period).to_string()
+                    }
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("delete_many", COL))
+    }
+}
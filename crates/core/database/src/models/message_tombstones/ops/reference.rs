@@ -0,0 +1,59 @@
+use std::time::{Duration, SystemTime};
+
+use onechatsocial_result::Result;
+use ulid::Ulid;
+
+use crate::MessageTombstone;
+use crate::ReferenceDb;
+
+use super::AbstractMessageTombstones;
+
+#[async_trait]
+impl AbstractMessageTombstones for ReferenceDb {
+    /// Insert a new message tombstone into the database
+    async fn insert_message_tombstone(&self, tombstone: &MessageTombstone) -> Result<()> {
+        let mut message_tombstones = self.message_tombstones.lock().await;
+        if message_tombstones.contains_key(&tombstone.id) {
+            Err(create_database_error!("insert", "message_tombstone"))
+        } else {
+            message_tombstones.insert(tombstone.id.to_string(), tombstone.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch tombstones in a channel created within the given period, newest first
+    async fn fetch_message_tombstones(
+        &self,
+        channel: &str,
+        period: Duration,
+        limit: i64,
+    ) -> Result<Vec<MessageTombstone>> {
+        let message_tombstones = self.message_tombstones.lock().await;
+        let gte_cmp_id = Ulid::from_datetime(SystemTime::now() - period).to_string();
+
+        let mut tombstones: Vec<MessageTombstone> = message_tombstones
+            .values()
+            .filter(|tombstone| tombstone.id >= gte_cmp_id && tombstone.channel == channel)
+            .cloned()
+            .collect();
+
+        tombstones.sort_by(|a, b| b.id.cmp(&a.id));
+        tombstones.truncate(limit as usize);
+        Ok(tombstones)
+    }
+
+    /// Delete the tombstone for a message which has been restored
+    async fn delete_message_tombstone(&self, message: &str) -> Result<()> {
+        let mut message_tombstones = self.message_tombstones.lock().await;
+        message_tombstones.retain(|_, tombstone| tombstone.message != message);
+        Ok(())
+    }
+
+    /// Delete all tombstones older than the given period
+    async fn delete_expired_message_tombstones(&self, period: Duration) -> Result<()> {
+        let mut message_tombstones = self.message_tombstones.lock().await;
+        let gte_cmp_id = Ulid::from_datetime(SystemTime::now() - period).to_string();
+        message_tombstones.retain(|id, _| *id >= gte_cmp_id);
+        Ok(())
+    }
+}
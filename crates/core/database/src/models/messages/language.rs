@@ -0,0 +1,39 @@
+use once_cell::sync::OnceCell;
+
+/// Detector invoked when a message is sent without a language tag
+///
+/// Self-hosters may provide their own implementation (e.g. backed by a
+/// language identification model) using [`set_language_detector`]
+/// before starting the server.
+#[async_trait]
+pub trait LanguageDetector: Sync + Send {
+    /// Detect the language of some content, returning a BCP-47 tag
+    async fn detect(&self, content: &str) -> Option<String>;
+}
+
+/// Detector which never detects a language
+pub struct NoopLanguageDetector;
+
+#[async_trait]
+impl LanguageDetector for NoopLanguageDetector {
+    /// Detect the language of some content
+    async fn detect(&self, _content: &str) -> Option<String> {
+        None
+    }
+}
+
+static DETECTOR: OnceCell<Box<dyn LanguageDetector>> = OnceCell::new();
+
+/// Set the language detector to use, this can only be done once
+///
+/// Returns false if a detector has already been set.
+pub fn set_language_detector<D: LanguageDetector + 'static>(detector: D) -> bool {
+    DETECTOR.set(Box::new(detector)).is_ok()
+}
+
+/// Get the language detector in use
+pub fn language_detector() -> &'static dyn LanguageDetector {
+    DETECTOR
+        .get_or_init(|| Box::new(NoopLanguageDetector))
+        .as_ref()
+}
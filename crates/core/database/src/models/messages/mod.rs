@@ -1,5 +1,7 @@
+mod language;
 mod model;
 mod ops;
 
+pub use language::*;
 pub use model::*;
 pub use ops::*;
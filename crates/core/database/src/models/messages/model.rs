@@ -1,23 +1,58 @@
-use std::collections::HashSet;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
+use futures::{stream, StreamExt, TryStreamExt};
 use indexmap::{IndexMap, IndexSet};
 use iso8601_timestamp::Timestamp;
+use once_cell::sync::Lazy;
 use onechatsocial_config::config;
 use onechatsocial_models::v0::{
-    self, DataMessageSend, Embed, MessageAuthor, MessageSort, MessageWebhook, PushNotification,
-    ReplyIntent, SendableEmbed, RE_MENTION,
+    self, AllowedMentions, DataMessageSend, Embed, MessageAuthor, MessageFlags, MessageSort,
+    MessageWebhook, PushNotification, PushNotificationReply, ReplyIntent, SendableEmbed,
+    RE_LANGUAGE,
+};
+use onechatsocial_permissions::{
+    calculate_channel_permissions, ChannelPermission, PermissionValue,
 };
-use onechatsocial_permissions::{ChannelPermission, PermissionValue};
+use onechatsocial_presence::filter_online;
 use onechatsocial_result::Result;
 use ulid::Ulid;
 
 use crate::{
+    attachment_scanner,
     events::client::EventV1,
-    tasks::{self, ack::AckEvent},
-    util::idempotency::IdempotencyKey,
-    Channel, Database, Emoji, File,
+    language_detector,
+    tasks::{self, ack::AckEvent, reaction_batch},
+    util::{
+        colour::is_valid_colour, idempotency::IdempotencyKey, permissions::DatabasePermissionQuery,
+        token_bucket::TokenBuckets, ulid_generator,
+    },
+    AttachmentScanner, Channel, ChannelNotificationPreference, Database, Emoji, File, Member,
+    MessageTombstone, Metadata, NotificationLevel, PartialChannel, PendingMessageDeletion,
+    ScanVerdict, ScheduledMessage, Sticker,
 };
 
+/// Reactions recently added by each user
+static REACTION_BUCKETS: Lazy<TokenBuckets> = Lazy::new(|| TokenBuckets::new(10_000));
+
+/// Longest cooldown which may be placed on a reaction
+const MAX_REACTION_COOLDOWN: u64 = 604_800;
+
+/// Longest alternative text which may be given for an attachment
+const MAX_ATTACHMENT_DESCRIPTION_LENGTH: usize = 1000;
+
+/// Furthest ahead a message may be scheduled to be sent
+const MAX_SCHEDULE_AHEAD: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Oldest a message may be to be deleted in bulk
+const MAX_BULK_DELETE_AGE: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+/// Number of members checked for presence at a time when resolving @here
+const MASS_MENTION_PAGE_SIZE: usize = 1000;
+
 auto_derived_partial!(
     /// Message
     pub struct Message {
@@ -43,18 +78,30 @@ auto_derived_partial!(
         /// Array of attachments
         #[serde(skip_serializing_if = "Option::is_none")]
         pub attachments: Option<Vec<File>>,
+        /// Array of sticker ids included in this message
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub stickers: Option<Vec<String>>,
         /// Time at which this message was last edited
         #[serde(skip_serializing_if = "Option::is_none")]
         pub edited: Option<Timestamp>,
+        /// Id of the user who last edited this message, if not the author
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub edited_by: Option<String>,
         /// Attached embeds to this message
         #[serde(skip_serializing_if = "Option::is_none")]
         pub embeds: Option<Vec<Embed>>,
         /// Array of user ids mentioned in this message
         #[serde(skip_serializing_if = "Option::is_none")]
         pub mentions: Option<Vec<String>>,
+        /// Array of role ids mentioned in this message
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub role_mentions: Option<Vec<String>>,
         /// Array of message ids this message is replying to
         #[serde(skip_serializing_if = "Option::is_none")]
         pub replies: Option<Vec<String>>,
+        /// Copies of the replied messages taken when this message was sent
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub reply_snapshots: Option<Vec<MessageSnapshot>>,
         /// Hashmap of emoji IDs to array of user IDs
         #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
         pub reactions: IndexMap<String, IndexSet<String>>,
@@ -64,11 +111,49 @@ auto_derived_partial!(
         /// Name and / or avatar overrides for this message
         #[serde(skip_serializing_if = "Option::is_none")]
         pub masquerade: Option<Masquerade>,
+        /// Whether this message contains spoilers in its content or attachments
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub has_spoilers: bool,
+        /// BCP-47 language tag of this message's content, if known
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub language: Option<String>,
+        /// Number of replies to this message
+        #[serde(skip_serializing_if = "crate::if_zero_u32", default)]
+        pub reply_count: u32,
+        /// Number of distinct users who replied to this message
+        #[serde(skip_serializing_if = "crate::if_zero_u32", default)]
+        pub thread_participant_count: u32,
+        /// Whether this message is pinned in its channel
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub pinned: Option<bool>,
+        /// Time at which this message was last pinned
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub pinned_at: Option<Timestamp>,
+        /// Previous versions of this message, oldest first
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub edit_history: Option<Vec<MessageRevision>>,
+        /// Poll attached to this message
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub poll: Option<Poll>,
+        /// Time at which this message will be deleted
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub expires_at: Option<Timestamp>,
+        /// Message this message was forwarded from
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub forwarded_from: Option<MessageReference>,
+        /// Bitfield of message flags
+        #[serde(skip_serializing_if = "crate::if_zero_u32", default)]
+        pub flags: u32,
     },
     "PartialMessage"
 );
 
 auto_derived!(
+    /// Optional fields on message object
+    pub enum FieldsMessage {
+        EditedBy,
+    }
+
     /// System Event
     #[serde(tag = "type")]
     pub enum SystemMessage {
@@ -83,9 +168,17 @@ auto_derived!(
         #[serde(rename = "user_left")]
         UserLeft { id: String },
         #[serde(rename = "user_kicked")]
-        UserKicked { id: String },
+        UserKicked {
+            id: String,
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            by: Option<String>,
+        },
         #[serde(rename = "user_banned")]
-        UserBanned { id: String },
+        UserBanned {
+            id: String,
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            by: Option<String>,
+        },
         #[serde(rename = "channel_renamed")]
         ChannelRenamed { name: String, by: String },
         #[serde(rename = "channel_description_changed")]
@@ -94,6 +187,24 @@ auto_derived!(
         ChannelIconChanged { by: String },
         #[serde(rename = "channel_ownership_changed")]
         ChannelOwnershipChanged { from: String, to: String },
+        #[serde(rename = "message_pinned")]
+        MessagePinned { id: String, by: String },
+        #[serde(rename = "message_unpinned")]
+        MessageUnpinned { id: String, by: String },
+        #[serde(rename = "poll")]
+        Poll {
+            question: String,
+            options: Vec<String>,
+            by: String,
+        },
+        #[serde(rename = "role_expired")]
+        RoleExpired { id: String, role: String },
+        #[serde(rename = "thread_created")]
+        ThreadCreated {
+            id: String,
+            name: String,
+            by: String,
+        },
     }
 
     /// Name and / or avatar override information
@@ -122,6 +233,63 @@ auto_derived!(
         /// Can only be set to true if reactions list is of at least length 1
         #[serde(skip_serializing_if = "crate::if_false", default)]
         pub restrict_reactions: bool,
+        /// Seconds a user must wait before adding a given reaction again
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        pub reaction_cooldowns: Option<IndexMap<String, u64>>,
+    }
+
+    /// Previous version of an edited message
+    pub struct MessageRevision {
+        /// Message content
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub content: Option<String>,
+        /// Attached embeds
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub embeds: Option<Vec<Embed>>,
+        /// Time at which this version was replaced by an edit
+        pub edited_at: Timestamp,
+    }
+
+    /// Reference to a message in another channel
+    pub struct MessageReference {
+        /// Id of the channel the message was sent in
+        pub channel: String,
+        /// Id of the message
+        pub message: String,
+        /// Id of the user or webhook that sent the message
+        pub author: String,
+    }
+
+    /// Copy of a replied message taken when the reply was sent
+    pub struct MessageSnapshot {
+        /// Id of the message
+        pub message: String,
+        /// Id of the user or webhook that sent the message
+        pub author: String,
+        /// Message content, truncated to the configured length
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub content: Option<String>,
+    }
+
+    /// Poll which users can vote on
+    pub struct Poll {
+        /// Options which can be voted for
+        pub options: Vec<PollOption>,
+        /// Whether users may vote for more than one option
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub multi_select: bool,
+        /// Time after which votes are no longer accepted
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub closes_at: Option<Timestamp>,
+    }
+
+    /// Option of a poll
+    pub struct PollOption {
+        /// Name of this option
+        pub name: String,
+        /// Ids of users who voted for this option
+        #[serde(default)]
+        pub voters: IndexSet<String>,
     }
 
     /// Appended Information
@@ -158,10 +326,18 @@ auto_derived!(
     pub struct MessageFilter {
         /// Parent channel ID
         pub channel: Option<String>,
+        /// Parent channel IDs, matching messages in any of them
+        pub channels: Option<Vec<String>>,
         /// Message author ID
         pub author: Option<String>,
+        /// Mentioned user ID
+        pub mentions: Option<String>,
         /// Search query
         pub query: Option<String>,
+        /// Whether the message is pinned
+        pub pinned: Option<bool>,
+        /// Content the message must have
+        pub has: Option<Vec<v0::MessageHas>>,
     }
 
     /// Message Query
@@ -177,6 +353,26 @@ auto_derived!(
         #[serde(flatten)]
         pub time_period: MessageTimePeriod,
     }
+
+    /// Summary of the messages pinned in a channel
+    #[derive(Default)]
+    pub struct ChannelPins {
+        /// Number of pinned messages
+        pub count: usize,
+        /// Time at which the most recently pinned message was pinned
+        pub last_pinned_at: Option<Timestamp>,
+    }
+
+    /// Number of reactions made using an emoji
+    pub struct EmojiReactionCount {
+        /// Emoji id
+        #[serde(rename = "_id")]
+        pub emoji: String,
+        /// Total number of reactions
+        pub count: usize,
+        /// Number of distinct users who reacted
+        pub users: usize,
+    }
 );
 
 #[allow(clippy::derivable_impls)]
@@ -191,13 +387,28 @@ impl Default for Message {
             content: None,
             system: None,
             attachments: None,
+            stickers: None,
             edited: None,
+            edited_by: None,
             embeds: None,
             mentions: None,
+            role_mentions: None,
             replies: None,
+            reply_snapshots: None,
             reactions: Default::default(),
             interactions: Default::default(),
             masquerade: None,
+            has_spoilers: false,
+            language: None,
+            reply_count: 0,
+            thread_participant_count: 0,
+            pinned: None,
+            pinned_at: None,
+            edit_history: None,
+            poll: None,
+            expires_at: None,
+            forwarded_from: None,
+            flags: 0,
         }
     }
 }
@@ -216,25 +427,99 @@ impl Message {
     ) -> Result<Message> {
         let config = config().await;
 
+        let (author_id, webhook) = match &author {
+            MessageAuthor::User(user) => (user.id.clone(), None),
+            MessageAuthor::Webhook(webhook) => (webhook.id.clone(), Some((*webhook).clone())),
+            MessageAuthor::System { .. } => ("00000000000000000000000000".to_string(), None),
+        };
+
+        // Check the message is not empty
+        if !Message::data_has_content(&data) {
+            return Err(create_error!(EmptyMessage));
+        }
+
+        // Identical retries are given the original message instead of sending it again
+        let fingerprint = {
+            let mut hasher = DefaultHasher::new();
+            channel.id().hash(&mut hasher);
+            serde_json::to_vec(&data)
+                .unwrap_or_default()
+                .hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let (claim, existing) = idempotency
+            .claim_message(data.nonce.clone(), &author_id, fingerprint)
+            .await?;
+
+        if let Some(id) = existing {
+            return Message::fetch_including_scheduled(db, &id).await;
+        }
+
+        // Use limits for the server's tier and its embed defaults, if any
+        let server = match &channel {
+            Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => {
+                Some(db.fetch_server(server).await?)
+            }
+            _ => None,
+        };
+
+        let (flags, embed_colour) = server
+            .as_ref()
+            .map(|server| {
+                (
+                    server.flags.unwrap_or_default() as u32,
+                    server.embed_colour.clone(),
+                )
+            })
+            .unwrap_or_default();
+
+        let limits = config.features.server_limits(flags);
+
         Message::validate_sum(
             &data.content,
             data.embeds.as_deref().unwrap_or_default(),
-            config.features.limits.default.message_length,
+            limits.message_length_for(&channel.id(), channel.channel_type()),
+            limits.message_embed_length,
+            limits.message_embed_description_length,
         )?;
 
-        idempotency
-            .consume_nonce(data.nonce)
-            .await
-            .map_err(|_| create_error!(InvalidOperation))?;
+        if let Some(masquerade) = &data.masquerade {
+            Message::validate_masquerade(masquerade, config.features.role_colour_gradients)?;
+        }
 
-        // Check the message is not empty
-        if (data.content.as_ref().map_or(true, |v| v.is_empty()))
-            && (data.attachments.as_ref().map_or(true, |v| v.is_empty()))
-            && (data.embeds.as_ref().map_or(true, |v| v.is_empty()))
-        {
-            return Err(create_error!(EmptyMessage));
+        // Messages scheduled in the past are sent immediately
+        let now = Timestamp::now_utc();
+        let send_at = data.send_at.filter(|send_at| **send_at > *now);
+        if send_at.is_some_and(|send_at| *send_at > *now + MAX_SCHEDULE_AHEAD) {
+            return Err(create_error!(InvalidProperty));
+        }
+
+        // Only post if nothing newer was sent since the client last looked,
+        // the channel's last_message_id is debounced so ask for the newest message
+        if let Some(expected) = &data.if_last_message {
+            let latest = db
+                .fetch_messages(MessageQuery {
+                    limit: Some(1),
+                    filter: MessageFilter {
+                        channel: Some(channel.id()),
+                        ..Default::default()
+                    },
+                    time_period: MessageTimePeriod::Absolute {
+                        before: None,
+                        after: None,
+                        sort: Some(MessageSort::Latest),
+                    },
+                })
+                .await?;
+
+            if latest.first().map(|message| message.id.as_str()) != Some(expected.as_str()) {
+                return Err(create_error!(LastMessageMismatch));
+            }
         }
 
+        Message::check_slowmode(db, &channel, &author).await?;
+
         // Ensure restrict_reactions is not specified without reactions list
         if let Some(interactions) = &data.interactions {
             if interactions.restrict_reactions {
@@ -250,14 +535,8 @@ impl Message {
             }
         }
 
-        let (author_id, webhook) = match &author {
-            MessageAuthor::User(user) => (user.id.clone(), None),
-            MessageAuthor::Webhook(webhook) => (webhook.id.clone(), Some((*webhook).clone())),
-            MessageAuthor::System { .. } => ("00000000000000000000000000".to_string(), None),
-        };
-
         // Start constructing the message
-        let message_id = Ulid::new().to_string();
+        let message_id = ulid_generator::generate().to_string();
         let mut message = Message {
             id: message_id.clone(),
             channel: channel.id(),
@@ -271,13 +550,65 @@ impl Message {
             ..Default::default()
         };
 
-        // Parse mentions in message.
+        // Interaction-defined reactions should always appear, so show them before anyone reacts
+        message.reactions = message
+            .interactions
+            .default_reactions()
+            .into_iter()
+            .map(|emoji| (emoji, IndexSet::new()))
+            .collect();
+
+        // Parse mentions in message, content is always kept verbatim.
+        let allowed_mentions = data.allowed_mentions.unwrap_or_else(AllowedMentions::all);
+
+        if allowed_mentions.is_ambiguous() {
+            return Err(create_error!(InvalidOperation));
+        }
+
         let mut mentions = HashSet::new();
         if allow_mentions {
             if let Some(content) = &data.content {
-                for capture in RE_MENTION.captures_iter(content) {
-                    if let Some(mention) = capture.get(1) {
-                        mentions.insert(mention.as_str().to_string());
+                mentions.extend(
+                    v0::parse_mentions(content)
+                        .filter(|id| allowed_mentions.allows_user(id))
+                        .map(|id| id.to_string()),
+                );
+
+                // Resolve role and mass mentions into the members they notify
+                let mut roles: Vec<String> = v0::parse_role_mentions(content)
+                    .filter(|id| allowed_mentions.allows_role(id))
+                    .map(|id| id.to_string())
+                    .collect();
+
+                roles.sort();
+                roles.dedup();
+
+                let (mut everyone, mut here) = (false, false);
+                if allowed_mentions.allows_everyone() {
+                    for mention in v0::parse_mass_mentions(content) {
+                        match mention {
+                            "everyone" => everyone = true,
+                            _ => here = true,
+                        }
+                    }
+                }
+
+                if (!roles.is_empty() || everyone || here)
+                    && Message::can_mention_everyone(db, &channel, &author).await?
+                {
+                    let (roles, users) = Message::resolve_mass_mentions(
+                        db,
+                        &channel,
+                        roles,
+                        everyone,
+                        here,
+                        limits.message_mentions,
+                    )
+                    .await?;
+
+                    mentions.extend(users.into_iter().filter(|id| id != author.id()));
+                    if !roles.is_empty() {
+                        message.role_mentions = Some(roles);
                     }
                 }
             }
@@ -286,21 +617,57 @@ impl Message {
         // Verify replies are valid.
         let mut replies = HashSet::new();
         if let Some(entries) = data.replies {
-            if entries.len() > config.features.limits.default.message_replies {
+            if entries.len() > limits.message_replies {
                 return Err(create_error!(TooManyReplies {
-                    max: config.features.limits.default.message_replies,
+                    max: limits.message_replies,
                 }));
             }
 
+            // Only a limited number of replies may notify their authors,
+            // any further replies are kept but remain silent.
+            let mut reply_mentions = 0;
+            let mut reply_snapshots = vec![];
             for ReplyIntent { id, mention } in entries {
                 let message = db.fetch_message(&id).await?;
 
-                if mention && allow_mentions {
-                    mentions.insert(message.author.to_owned());
+                if mention
+                    && allow_mentions
+                    && reply_mentions < limits.message_reply_mentions
+                    && mentions.insert(message.author.to_owned())
+                {
+                    reply_mentions += 1;
+                }
+
+                // Keep a copy in case the replied message is later deleted,
+                // only for messages the author could already see here
+                if config.features.reply_snapshots
+                    && message.channel == channel.id()
+                    && !replies.contains(&message.id)
+                {
+                    reply_snapshots.push(MessageSnapshot {
+                        message: message.id.clone(),
+                        author: message.author.clone(),
+                        content: message.content.as_ref().map(|content| {
+                            content
+                                .chars()
+                                .take(config.features.reply_snapshot_length)
+                                .collect()
+                        }),
+                    });
                 }
 
                 replies.insert(message.id);
             }
+
+            if !reply_snapshots.is_empty() {
+                message.reply_snapshots = Some(reply_snapshots);
+            }
+        }
+
+        if mentions.len() > limits.message_mentions {
+            return Err(create_error!(TooManyMentions {
+                max: limits.message_mentions
+            }));
         }
 
         if !mentions.is_empty() {
@@ -314,262 +681,5634 @@ impl Message {
         }
 
         // Add attachments to message.
-        let mut attachments = vec![];
         if data
             .attachments
             .as_ref()
-            .is_some_and(|v| v.len() > config.features.limits.default.message_attachments)
+            .is_some_and(|v| v.len() > limits.message_attachments)
         {
             return Err(create_error!(TooManyAttachments {
-                max: config.features.limits.default.message_attachments,
+                max: limits.message_attachments,
             }));
         }
 
-        if data
-            .embeds
-            .as_ref()
-            .is_some_and(|v| v.len() > config.features.limits.default.message_embeds)
+        // Each attachment can only be claimed once
+        let attachment_ids = data.attachments.as_deref().unwrap_or_default();
+        if attachment_ids.iter().collect::<HashSet<_>>().len() != attachment_ids.len() {
+            return Err(create_error!(InvalidProperty));
+        }
+
+        // Alternative text is given for each attachment in order
+        let descriptions = data.attachment_descriptions.unwrap_or_default();
+        if descriptions.len() > data.attachments.as_ref().map_or(0, |v| v.len())
+            || descriptions
+                .iter()
+                .flatten()
+                .any(|description| description.chars().count() > MAX_ATTACHMENT_DESCRIPTION_LENGTH)
+        {
+            return Err(create_error!(InvalidProperty));
+        }
+
+        // Voice messages consist of a single recording without any text
+        if data.voice.is_some()
+            && (data
+                .content
+                .as_ref()
+                .is_some_and(|content| !content.is_empty())
+                || data.attachments.as_ref().map_or(0, |v| v.len()) != 1)
+        {
+            return Err(create_error!(InvalidProperty));
+        }
+
+        // Quotes are included as an additional embed
+        if data.embeds.as_ref().map_or(0, |v| v.len()) + data.quote.is_some() as usize
+            > limits.message_embeds
         {
             return Err(create_error!(TooManyEmbeds {
-                max: config.features.limits.default.message_embeds,
+                max: limits.message_embeds,
             }));
         }
 
+        // Stickers must exist and come from a sticker pack the author can use
+        if let Some(stickers) = data.stickers {
+            let stickers: Vec<String> = stickers
+                .into_iter()
+                .collect::<IndexSet<String>>()
+                .into_iter()
+                .collect();
+
+            if stickers.len() > limits.message_stickers {
+                return Err(create_error!(TooManyStickers {
+                    max: limits.message_stickers,
+                }));
+            }
+
+            let server_id = match &channel {
+                Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => {
+                    Some(server.as_str())
+                }
+                _ => None,
+            };
+
+            Sticker::can_use_all(db, &stickers, &message.author, server_id).await?;
+
+            if !stickers.is_empty() {
+                message.stickers.replace(stickers);
+            }
+        }
+
+        // Scan attachments before any of them are claimed
         for attachment_id in data.attachments.as_deref().unwrap_or_default() {
-            attachments.push(
-                db.find_and_use_attachment(attachment_id, "attachments", "message", &message_id)
-                    .await?,
-            );
+            let file = db.fetch_attachment("attachments", attachment_id).await?;
+            if data.voice.is_some() && !matches!(file.metadata, Metadata::Audio) {
+                return Err(create_error!(InvalidProperty));
+            }
+
+            Message::scan_attachment(&file, attachment_scanner()).await?;
         }
 
+        // Claim attachments concurrently, keeping the order they were given in
+        let attachments: Vec<File> = stream::iter(data.attachments.as_deref().unwrap_or_default())
+            .map(|attachment_id| {
+                db.find_and_use_attachment(attachment_id, "attachments", "message", &message_id)
+            })
+            .buffered(config.api.workers.max_concurrent_attachments.max(1))
+            .try_collect()
+            .await?;
+
         if !attachments.is_empty() {
             message.attachments.replace(attachments);
         }
 
+        for (attachment, description) in message.attachments.iter_mut().flatten().zip(descriptions)
+        {
+            attachment.description = description.filter(|description| !description.is_empty());
+        }
+
+        if let Some(voice) = data.voice {
+            for attachment in message.attachments.iter_mut().flatten() {
+                attachment.is_voice = true;
+                attachment.waveform = Some(voice.waveform.clone());
+                attachment.duration = Some(voice.duration);
+            }
+        }
+
         // Process included embeds.
-        for sendable_embed in data.embeds.unwrap_or_default() {
+        for mut sendable_embed in data.embeds.unwrap_or_default() {
+            if sendable_embed.colour.is_none() {
+                sendable_embed.colour = embed_colour.clone();
+            }
+
             message.attach_sendable_embed(db, sendable_embed).await?;
         }
 
+        // Include a snapshot of the quoted message.
+        if let Some(id) = data.quote {
+            let quoted = db.fetch_message(&id).await?;
+
+            // Users must be able to read the quoted message, even within this channel,
+            // webhooks and the system may only quote within this channel.
+            if let MessageAuthor::User(user) = &author {
+                let user = db.fetch_user(&user.id).await?;
+                let quoted_channel = if quoted.channel == message.channel {
+                    None
+                } else {
+                    Some(db.fetch_channel(&quoted.channel).await?)
+                };
+
+                let mut query = DatabasePermissionQuery::new(db, &user)
+                    .channel(quoted_channel.as_ref().unwrap_or(&channel));
+                let permissions = calculate_channel_permissions(&mut query).await;
+
+                if !permissions.has_channel_permission(ChannelPermission::ViewChannel)
+                    || !permissions.has_channel_permission(ChannelPermission::ReadMessageHistory)
+                {
+                    return Err(create_error!(NotFound));
+                }
+            } else if quoted.channel != message.channel {
+                return Err(create_error!(NotFound));
+            }
+
+            let title = quoted.author_name(db).await;
+            message
+                .attach_sendable_embed(
+                    db,
+                    SendableEmbed {
+                        title: title.map(|title| title.chars().take(100).collect()),
+                        description: quoted
+                            .content
+                            .filter(|content| !content.is_empty())
+                            .map(|content| content.chars().take(2000).collect()),
+                        url: Some(format!(
+                            "{}/channel/{}/{}",
+                            config.hosts.app, quoted.channel, quoted.id
+                        )),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+        }
+
         // Set content
         message.content = data.content;
 
+        // Flag spoilers so clients don't need to parse content
+        message.has_spoilers = message
+            .attachments
+            .as_ref()
+            .is_some_and(|files| files.iter().any(File::is_spoiler));
+
+        if let Some(content) = &message.content {
+            message.has_spoilers |= Message::detect_spoilers(content)?;
+        }
+
+        // Tag the language, detecting it if the client didn't provide one
+        message.language = match data.language {
+            Some(language) => Some(language),
+            None => match message.content.as_deref() {
+                Some(content) if !content.is_empty() => language_detector()
+                    .detect(content)
+                    .await
+                    .filter(|language| RE_LANGUAGE.is_match(language)),
+                _ => None,
+            },
+        };
+
         // Pass-through nonce value for clients
         message.nonce = Some(idempotency.into_key());
 
+        // Count the time to live from when the message is actually sent
+        message.expires_at = data.expires_in.map(|expires_in| {
+            Timestamp::from(*send_at.unwrap_or(now) + Duration::from_secs(expires_in as u64))
+        });
+
+        // Hold on to the message until it is due to be sent
+        if let Some(send_at) = send_at {
+            db.insert_scheduled_message(&ScheduledMessage {
+                id: message.id.to_string(),
+                channel: message.channel.to_string(),
+                send_at,
+                generate_embeds,
+                message: message.clone(),
+            })
+            .await?;
+
+            claim.complete(&message.id);
+            return Ok(message);
+        }
+
         // Send the message
         message.send(db, author, &channel, generate_embeds).await?;
 
+        claim.complete(&message.id);
         Ok(message)
     }
 
-    /// Send a message without any notifications
-    pub async fn send_without_notifications(
-        &mut self,
-        db: &Database,
-        is_dm: bool,
-        generate_embeds: bool,
-    ) -> Result<()> {
-        db.insert_message(self).await?;
-
-        // Fan out events
-        EventV1::Message(self.clone().into())
-            .p(self.channel.to_string())
-            .await;
+    /// Fetch a message by its id, including messages which are yet to be sent
+    pub async fn fetch_including_scheduled(db: &Database, id: &str) -> Result<Message> {
+        match db.fetch_message(id).await {
+            Ok(message) => Ok(message),
+            Err(error) => db
+                .fetch_scheduled_message(id)
+                .await
+                .map(|scheduled| scheduled.message)
+                .map_err(|_| error),
+        }
+    }
 
-        // Update last_message_id
-        tasks::last_message_id::queue(self.channel.to_string(), self.id.to_string(), is_dm).await;
+    /// Check whether content contains spoilers (`||text||`)
+    ///
+    /// Markers inside code blocks and inline code are ignored,
+    /// any other marker must be closed.
+    pub fn detect_spoilers(content: &str) -> Result<bool> {
+        let markers: usize = content
+            .split("```")
+            .step_by(2)
+            .flat_map(|text| text.split('`').step_by(2))
+            .map(|text| text.matches("||").count())
+            .sum();
 
-        // Add mentions for affected users
-        if let Some(mentions) = &self.mentions {
-            for user in mentions {
-                tasks::ack::queue(
-                    self.channel.to_string(),
-                    user.to_string(),
-                    AckEvent::AddMention {
-                        ids: vec![self.id.to_string()],
-                    },
-                )
-                .await;
-            }
+        if markers % 2 == 0 {
+            Ok(markers > 0)
+        } else {
+            Err(create_error!(InvalidProperty))
         }
+    }
 
-        // Generate embeds
-        if generate_embeds {
-            if let Some(content) = &self.content {
-                tasks::process_embeds::queue(
-                    self.channel.to_string(),
-                    self.id.to_string(),
-                    content.clone(),
-                )
-                .await;
-            }
+    /// Reject an attachment if the given scanner quarantines it
+    async fn scan_attachment(file: &File, scanner: &dyn AttachmentScanner) -> Result<()> {
+        if let ScanVerdict::Quarantine { reason } = scanner.scan(file).await {
+            info!("Rejected attachment {}: {reason}", file.id);
+            return Err(create_error!(AttachmentQuarantined));
         }
 
         Ok(())
     }
 
-    /// Send a message
-    pub async fn send(
-        &mut self,
+    /// Check the author isn't sending messages faster than the channel's slowmode allows
+    async fn check_slowmode(
         db: &Database,
-        author: MessageAuthor<'_>,
         channel: &Channel,
-        generate_embeds: bool,
+        author: &MessageAuthor<'_>,
     ) -> Result<()> {
-        self.send_without_notifications(
-            db,
-            matches!(channel, Channel::DirectMessage { .. }),
-            generate_embeds,
-        )
-        .await?;
+        let Channel::TextChannel {
+            server,
+            slowmode_seconds: Some(interval),
+            slowmode_applies_to_bots,
+            ..
+        } = channel
+        else {
+            return Ok(());
+        };
 
-        // Push out Web Push notifications
-        crate::tasks::web_push::queue(
-            {
-                match channel {
-                    Channel::DirectMessage { recipients, .. }
-                    | Channel::Group { recipients, .. } => recipients.clone(),
-                    Channel::TextChannel { .. } => self.mentions.clone().unwrap_or_default(),
-                    _ => vec![],
+        if *interval == 0 {
+            return Ok(());
+        }
+
+        let author_id = match author {
+            MessageAuthor::User(user) => {
+                // Exempt roles and members who can manage messages bypass slowmode
+                if let Ok(member) = db.fetch_member(server, &user.id).await {
+                    if channel.is_slowmode_exempt(&member) {
+                        return Ok(());
+                    }
                 }
-            },
-            PushNotification::from(self.clone().into(), Some(author), &channel.id()).await,
-        )
-        .await;
 
-        Ok(())
-    }
+                let user = db.fetch_user(&user.id).await?;
+                let mut query = DatabasePermissionQuery::new(db, &user).channel(channel);
+                if calculate_channel_permissions(&mut query)
+                    .await
+                    .has_channel_permission(ChannelPermission::ManageMessages)
+                {
+                    return Ok(());
+                }
 
-    /// Append content to message
-    pub async fn append(
-        db: &Database,
-        id: String,
-        channel: String,
-        append: AppendMessage,
-    ) -> Result<()> {
-        db.append_message(&id, &append).await?;
+                user.id
+            }
+            MessageAuthor::Webhook(webhook) if *slowmode_applies_to_bots => webhook.id.clone(),
+            MessageAuthor::System { .. } if *slowmode_applies_to_bots => {
+                "00000000000000000000000000".to_string()
+            }
+            _ => return Ok(()),
+        };
 
-        EventV1::MessageAppend {
-            id,
-            channel: channel.to_string(),
-            append: append.into(),
+        let last_message = db
+            .fetch_messages(MessageQuery {
+                limit: Some(1),
+                filter: MessageFilter {
+                    channel: Some(channel.id()),
+                    author: Some(author_id),
+                    ..Default::default()
+                },
+                time_period: MessageTimePeriod::Absolute {
+                    before: None,
+                    after: None,
+                    sort: Some(MessageSort::Latest),
+                },
+            })
+            .await?
+            .into_iter()
+            .map(|message| message.id)
+            .max();
+
+        if let Some(sent_at) = last_message
+            .and_then(|id| Ulid::from_string(&id).ok())
+            .map(|id| id.timestamp_ms())
+        {
+            let elapsed = Ulid::new().timestamp_ms().saturating_sub(sent_at);
+            let interval = *interval as u64 * 1000;
+            if elapsed < interval {
+                return Err(create_error!(RateLimited {
+                    retry_after: interval - elapsed
+                }));
+            }
         }
-        .p(channel)
-        .await;
 
         Ok(())
     }
 
-    /// Convert sendable embed to text embed and attach to message
-    pub async fn attach_sendable_embed(
-        &mut self,
+    /// Check whether the author may mention roles, everyone or everyone online
+    async fn can_mention_everyone(
         db: &Database,
-        embed: v0::SendableEmbed,
-    ) -> Result<()> {
-        let media: Option<v0::File> = if let Some(id) = embed.media {
-            Some(
-                db.find_and_use_attachment(&id, "attachments", "message", &self.id)
-                    .await?
-                    .into(),
-            )
-        } else {
-            None
+        channel: &Channel,
+        author: &MessageAuthor<'_>,
+    ) -> Result<bool> {
+        let MessageAuthor::User(user) = author else {
+            return Ok(false);
         };
 
-        let embed = v0::Embed::Text(v0::Text {
-            icon_url: embed.icon_url,
-            url: embed.url,
-            title: embed.title,
-            description: embed.description,
-            media,
-            colour: embed.colour,
-        });
+        let user = db.fetch_user(&user.id).await?;
+        let mut query = DatabasePermissionQuery::new(db, &user).channel(channel);
+        Ok(calculate_channel_permissions(&mut query)
+            .await
+            .has_channel_permission(ChannelPermission::MentionEveryone))
+    }
 
-        if let Some(embeds) = &mut self.embeds {
-            embeds.push(embed);
+    /// Find the members notified by role and mass mentions in a channel
+    ///
+    /// Returns the roles which exist in the channel's server alongside the
+    /// ids of the members who can see the channel. With `here` only members
+    /// who are currently online are included, unless they have a mentioned role.
+    async fn resolve_mass_mentions(
+        db: &Database,
+        channel: &Channel,
+        roles: Vec<String>,
+        everyone: bool,
+        here: bool,
+        max: usize,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let too_many = || create_error!(TooManyMentions { max });
+
+        match channel {
+            Channel::DirectMessage { recipients, .. } | Channel::Group { recipients, .. } => {
+                let online = if here && !everyone {
+                    filter_online(recipients).await
+                } else {
+                    HashSet::new()
+                };
+
+                let users: Vec<String> = recipients
+                    .iter()
+                    .filter(|id| everyone || online.contains(*id))
+                    .cloned()
+                    .collect();
+
+                if users.len() > max {
+                    return Err(too_many());
+                }
+
+                Ok((vec![], users))
+            }
+            Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => {
+                let server = db.fetch_server(server).await?;
+                let roles: Vec<String> = roles
+                    .into_iter()
+                    .filter(|role| server.roles.contains_key(role))
+                    .collect();
+
+                // Only ever fetch one more member than the limit allows,
+                // so large servers are refused without loading every member
+                let mut found: IndexMap<String, Member> = IndexMap::new();
+                if everyone {
+                    if db.fetch_member_count(&server.id).await? > max {
+                        return Err(too_many());
+                    }
+
+                    for member in db
+                        .fetch_members_page(&server.id, None, None, Some(max + 1))
+                        .await?
+                    {
+                        found.insert(member.id.user.to_string(), member);
+                    }
+                } else {
+                    for role in &roles {
+                        for member in db
+                            .fetch_members_page(&server.id, None, Some(role), Some(max + 1))
+                            .await?
+                        {
+                            found.insert(member.id.user.to_string(), member);
+                        }
+
+                        if found.len() > max {
+                            return Err(too_many());
+                        }
+                    }
+
+                    // Online members are found a page at a time, stopping once over the limit
+                    if here {
+                        let mut after: Option<String> = None;
+                        loop {
+                            let page = db
+                                .fetch_members_page(
+                                    &server.id,
+                                    after.as_deref(),
+                                    None,
+                                    Some(MASS_MENTION_PAGE_SIZE),
+                                )
+                                .await?;
+
+                            let Some(last) = page.last() else {
+                                break;
+                            };
+
+                            after = Some(last.id.user.to_string());
+                            let ids: Vec<String> = page
+                                .iter()
+                                .map(|member| member.id.user.to_string())
+                                .collect();
+
+                            let online = filter_online(&ids).await;
+                            for member in page {
+                                if online.contains(&member.id.user) {
+                                    found.insert(member.id.user.to_string(), member);
+                                }
+                            }
+
+                            if found.len() > max {
+                                return Err(too_many());
+                            }
+                        }
+                    }
+                }
+
+                // Check the limit before looking any further into the members
+                if found.len() > max {
+                    return Err(too_many());
+                }
+
+                let members: Vec<Member> = found.into_values().collect();
+
+                let ids: Vec<String> = members
+                    .iter()
+                    .map(|member| member.id.user.to_string())
+                    .collect();
+
+                let users = db.fetch_users(&ids).await?;
+
+                // Only notify members who can actually see the channel
+                let mut visible = Vec::with_capacity(members.len());
+                for member in &members {
+                    if let Some(user) = users.iter().find(|user| user.id == member.id.user) {
+                        let mut query = DatabasePermissionQuery::new(db, user)
+                            .channel(channel)
+                            .server(&server)
+                            .member(member);
+
+                        if calculate_channel_permissions(&mut query)
+                            .await
+                            .has_channel_permission(ChannelPermission::ViewChannel)
+                        {
+                            visible.push(member.id.user.to_string());
+                        }
+                    }
+                }
+
+                Ok((roles, visible))
+            }
+            _ => Ok((vec![], vec![])),
+        }
+    }
+
+    /// Resolve the name this message is displayed under
+    pub async fn author_name(&self, db: &Database) -> Option<String> {
+        if let Some(name) = self
+            .masquerade
+            .as_ref()
+            .and_then(|masquerade| masquerade.name.clone())
+        {
+            Some(name)
+        } else if let Ok(user) = db.fetch_user(&self.author).await {
+            Some(user.display_name.unwrap_or(user.username))
         } else {
-            self.embeds = Some(vec![embed]);
+            db.fetch_webhook(&self.author)
+                .await
+                .ok()
+                .map(|webhook| webhook.name)
         }
+    }
 
-        Ok(())
+    /// Check whether this message has any content to display
+    pub fn has_content(&self) -> bool {
+        self.content.as_ref().is_some_and(|v| !v.is_empty())
+            || self.attachments.as_ref().is_some_and(|v| !v.is_empty())
+            || self.stickers.as_ref().is_some_and(|v| !v.is_empty())
+            || self.embeds.as_ref().is_some_and(|v| !v.is_empty())
+            || self.system.is_some()
     }
 
-    /// Validate the sum of content of a message is under threshold
-    pub fn validate_sum(
-        content: &Option<String>,
-        embeds: &[SendableEmbed],
-        max_length: usize,
-    ) -> Result<()> {
-        let mut running_total = 0;
-        if let Some(content) = content {
-            running_total += content.len();
+    /// Check whether this message is empty
+    pub fn is_empty(&self) -> bool {
+        !self.has_content()
+    }
+
+    /// Check whether a message sent from this data would have any content to display
+    pub fn data_has_content(data: &DataMessageSend) -> bool {
+        data.content.as_ref().is_some_and(|v| !v.is_empty())
+            || data.attachments.as_ref().is_some_and(|v| !v.is_empty())
+            || data.stickers.as_ref().is_some_and(|v| !v.is_empty())
+            || data.embeds.as_ref().is_some_and(|v| !v.is_empty())
+            || data.quote.is_some()
+    }
+
+    /// Remove a field from Message object
+    pub fn remove_field(&mut self, field: &FieldsMessage) {
+        match field {
+            FieldsMessage::EditedBy => self.edited_by = None,
         }
+    }
 
-        for embed in embeds {
-            if let Some(desc) = &embed.description {
-                running_total += desc.len();
-            }
+    /// Check whether link previews are suppressed on this message
+    pub fn embeds_suppressed(&self) -> bool {
+        self.flags & MessageFlags::SuppressEmbeds as u32 != 0
+    }
+
+    /// Find the icon of the author's hoisted role to show alongside this message
+    ///
+    /// Icons are resolved when the message is read so they follow changes to the author's roles.
+    pub async fn fetch_role_icon(&self, db: &Database, channel: &Channel) -> Option<String> {
+        if self.webhook.is_some() || self.masquerade.is_some() {
+            return None;
         }
 
-        if running_total <= max_length {
-            Ok(())
+        let server = match channel {
+            Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => {
+                db.fetch_server(server).await.ok()?
+            }
+            _ => return None,
+        };
+
+        let member = db.fetch_member(&server.id, &self.author).await.ok()?;
+        member.get_role_icon(&server).map(|icon| icon.id.clone())
+    }
+
+    /// Get the embeds which should be shown for this message
+    ///
+    /// Only embeds sent with the message are shown while link previews are suppressed.
+    pub fn visible_embeds(&self) -> Option<Vec<Embed>> {
+        if self.embeds_suppressed() {
+            self.embeds.as_ref().map(|embeds| {
+                embeds
+                    .iter()
+                    .filter(|embed| matches!(embed, Embed::Text(_)))
+                    .cloned()
+                    .collect()
+            })
         } else {
-            Err(create_error!(PayloadTooLarge))
+            self.embeds.clone()
         }
     }
-}
 
-impl SystemMessage {
-    pub fn into_message(self, channel: String) -> Message {
-        Message {
-            id: Ulid::new().to_string(),
-            channel,
-            author: "00000000000000000000000000".to_string(),
-            system: Some(self),
+    /// Set the flags on this message
+    ///
+    /// Users other than the author need `ManageMessages`. Lifting the suppression
+    /// of link previews discards the old previews and generates them again.
+    pub async fn set_flags(
+        &mut self,
+        db: &Database,
+        channel: &Channel,
+        permissions: &PermissionValue,
+        flags: u32,
+        by: &str,
+    ) -> Result<()> {
+        if self.author != by {
+            permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+        }
+
+        if flags & !(MessageFlags::SuppressEmbeds as u32) != 0 {
+            return Err(create_error!(InvalidProperty));
+        }
 
+        let was_suppressed = self.embeds_suppressed();
+        self.flags = flags;
+
+        let mut partial = PartialMessage {
+            flags: Some(flags),
             ..Default::default()
+        };
+
+        let unsuppressed = was_suppressed && !self.embeds_suppressed();
+        if unsuppressed {
+            self.embeds = self.visible_embeds();
+            partial.embeds = Some(self.embeds.clone().unwrap_or_default());
         }
-    }
-}
 
-impl Interactions {
-    /// Validate interactions info is correct
-    pub async fn validate(&self, db: &Database, permissions: &PermissionValue) -> Result<()> {
-        let config = config().await;
+        db.update_message(&self.id, &partial, vec![]).await?;
 
-        if let Some(reactions) = &self.reactions {
-            permissions.throw_if_lacking_channel_permission(ChannelPermission::React)?;
+        // Clients only receive the embeds they should now show
+        if was_suppressed != self.embeds_suppressed() {
+            partial.embeds = Some(self.visible_embeds().unwrap_or_default());
+        }
 
-            if reactions.len() > config.features.limits.default.message_reactions {
-                return Err(create_error!(InvalidOperation));
+        EventV1::MessageUpdate {
+            id: self.id.to_string(),
+            channel: self.channel.to_string(),
+            data: partial.into(),
+            clear: vec![],
+        }
+        .p(self.channel.to_string())
+        .await;
+
+        if unsuppressed && !channel.embeds_disabled() {
+            if let Some(content) = &self.content {
+                tasks::process_embeds::queue(
+                    self.channel.to_string(),
+                    self.id.to_string(),
+                    content.clone(),
+                )
+                .await;
             }
+        }
 
-            for reaction in reactions {
-                if !Emoji::can_use(db, reaction).await? {
-                    return Err(create_error!(InvalidOperation));
+        Ok(())
+    }
+
+    /// Send a message without any notifications
+    pub async fn send_without_notifications(
+        &mut self,
+        db: &Database,
+        is_dm: bool,
+        generate_embeds: bool,
+    ) -> Result<()> {
+        let message = self.clone().into();
+        self.fan_out(db, message, None, is_dm, generate_embeds)
+            .await
+    }
+
+    /// Insert this message and fan out events to clients
+    ///
+    /// Takes the already converted message and notification levels so callers
+    /// which also need them for notifications only have to fetch them once.
+    async fn fan_out(
+        &mut self,
+        db: &Database,
+        message: v0::Message,
+        levels: Option<&HashMap<String, NotificationLevel>>,
+        is_dm: bool,
+        generate_embeds: bool,
+    ) -> Result<()> {
+        db.insert_message(self).await?;
+        self.count_reply(db).await;
+
+        // Fan out events
+        EventV1::Message(message).p(self.channel.to_string()).await;
+
+        // Update last_message_id
+        tasks::last_message_id::queue(self.channel.to_string(), self.id.to_string(), is_dm).await;
+
+        // Add mentions for affected users, unless they opted out of them entirely
+        if let Some(mentions) = &self.mentions {
+            let fetched;
+            let levels = match levels {
+                Some(levels) => levels,
+                None => {
+                    fetched = ChannelNotificationPreference::fetch_levels(db, &self.channel)
+                        .await
+                        .unwrap_or_default();
+                    &fetched
+                }
+            };
+
+            for user in mentions {
+                if levels.get(user) == Some(&NotificationLevel::Nothing) {
+                    continue;
                 }
+
+                tasks::ack::queue(
+                    self.channel.to_string(),
+                    user.to_string(),
+                    AckEvent::AddMention {
+                        ids: vec![self.id.to_string()],
+                    },
+                )
+                .await;
+            }
+        }
+
+        // Generate embeds
+        if generate_embeds && !self.embeds_suppressed() {
+            if let Some(content) = &self.content {
+                tasks::process_embeds::queue(
+                    self.channel.to_string(),
+                    self.id.to_string(),
+                    content.clone(),
+                )
+                .await;
             }
         }
 
         Ok(())
     }
 
-    /// Check if we can use a given emoji to react
-    pub fn can_use(&self, emoji: &str) -> bool {
-        if self.restrict_reactions {
-            if let Some(reactions) = &self.reactions {
-                reactions.contains(emoji)
-            } else {
-                false
+    /// Send a message
+    pub async fn send(
+        &mut self,
+        db: &Database,
+        author: MessageAuthor<'_>,
+        channel: &Channel,
+        generate_embeds: bool,
+    ) -> Result<()> {
+        // Convert once, the notification borrows the message before it is moved into the event
+        let message: v0::Message = self.clone().into();
+        let levels = ChannelNotificationPreference::fetch_levels(db, &channel.id())
+            .await
+            .unwrap_or_default();
+
+        let notification = self
+            .push_notification(db, &message, author, channel, &levels)
+            .await;
+
+        self.fan_out(
+            db,
+            message,
+            Some(&levels),
+            matches!(channel, Channel::DirectMessage { .. }),
+            generate_embeds && !channel.embeds_disabled(),
+        )
+        .await?;
+
+        // Record activity in threads on the parent channel without
+        // changing the parent's own last message
+        if let Some(parent) = channel.parent() {
+            tasks::last_message_id::queue_thread(parent.to_string(), self.id.to_string()).await;
+        }
+
+        // Sending a message brings an archived thread back
+        if channel.is_archived() {
+            channel
+                .clone()
+                .update(
+                    db,
+                    PartialChannel {
+                        archived: Some(false),
+                        last_message_id: Some(self.id.to_string()),
+                        ..Default::default()
+                    },
+                    vec![],
+                )
+                .await?;
+        }
+
+        // Cross-post into any channels following this one
+        if channel.is_announcement() {
+            tasks::crosspost::queue(self.clone()).await;
+        }
+
+        // Push out Web Push notifications
+        if let Some((recipients, notification)) = notification {
+            crate::tasks::web_push::queue(recipients, notification).await;
+        }
+
+        Ok(())
+    }
+
+    /// Build the push notification for this message and the users who should receive it
+    async fn push_notification(
+        &self,
+        db: &Database,
+        message: &v0::Message,
+        author: MessageAuthor<'_>,
+        channel: &Channel,
+        levels: &HashMap<String, NotificationLevel>,
+    ) -> Option<(Vec<String>, PushNotification)> {
+        let mentions = self.mentions.clone().unwrap_or_default();
+
+        // Figure out who should be notified of this message
+        let recipients = match channel {
+            Channel::DirectMessage { recipients, .. } | Channel::Group { recipients, .. } => {
+                recipients.clone()
+            }
+            Channel::TextChannel { server, .. } => {
+                let mut recipients = mentions.clone();
+
+                // Users who asked for every message, unless the channel is too large
+                if !channel.is_push_mention_only(db).await {
+                    let ids: Vec<String> = levels
+                        .iter()
+                        .filter(|(id, level)| {
+                            **level == NotificationLevel::All
+                                && **id != self.author
+                                && !mentions.contains(id)
+                        })
+                        .map(|(id, _)| id.to_string())
+                        .collect();
+
+                    if !ids.is_empty() {
+                        recipients.extend(Self::filter_visible(db, channel, server, &ids).await);
+                    }
+                }
+
+                recipients
+            }
+            _ => vec![],
+        };
+
+        // Respect each user's notification preference for this channel
+        let default = NotificationLevel::default_for(channel);
+        let recipients: Vec<String> = recipients
+            .into_iter()
+            .filter(|id| {
+                levels
+                    .get(id)
+                    .copied()
+                    .unwrap_or(default)
+                    .should_push(mentions.contains(id))
+            })
+            .collect();
+
+        if recipients.is_empty() {
+            return None;
+        }
+
+        // Describe where this message was sent for notifications
+        let (channel_name, server_name) = match channel {
+            Channel::TextChannel { name, server, .. }
+            | Channel::VoiceChannel { name, server, .. } => (
+                Some(name.clone()),
+                db.fetch_server(server).await.ok().map(|server| server.name),
+            ),
+            Channel::Group { name, .. } => (Some(name.clone()), None),
+            _ => (None, None),
+        };
+
+        // Include context for the earliest message being replied to, only from
+        // this channel as recipients may not be able to read anywhere else
+        let mut reply_ids: Vec<&String> = self.replies.iter().flatten().collect();
+        reply_ids.sort();
+
+        let mut reply = None;
+        for id in reply_ids {
+            if let Ok(message) = db.fetch_message(id).await {
+                if message.channel == self.channel {
+                    reply = message
+                        .author_name(db)
+                        .await
+                        .map(|author| PushNotificationReply {
+                            author,
+                            snippet: message.content.unwrap_or_default(),
+                        });
+
+                    break;
+                }
             }
-        } else {
-            true
         }
+
+        let notification = PushNotification::from(
+            message,
+            Some(author),
+            &channel.id(),
+            channel_name,
+            server_name,
+            reply,
+        )
+        .await;
+
+        Some((recipients, notification))
     }
 
-    /// Check if default initialisation of fields
-    pub fn is_default(&self) -> bool {
-        !self.restrict_reactions && self.reactions.is_none()
+    /// Find which of the given users are members who can see a server channel
+    async fn filter_visible(
+        db: &Database,
+        channel: &Channel,
+        server_id: &str,
+        ids: &[String],
+    ) -> Vec<String> {
+        let Ok(server) = db.fetch_server(server_id).await else {
+            return vec![];
+        };
+
+        let (Ok(members), Ok(users)) = (
+            db.fetch_members(server_id, ids).await,
+            db.fetch_users(ids).await,
+        ) else {
+            return vec![];
+        };
+
+        let mut visible = Vec::with_capacity(members.len());
+        for member in &members {
+            if let Some(user) = users.iter().find(|user| user.id == member.id.user) {
+                let mut query = DatabasePermissionQuery::new(db, user)
+                    .channel(channel)
+                    .server(&server)
+                    .member(member);
+
+                if calculate_channel_permissions(&mut query)
+                    .await
+                    .has_channel_permission(ChannelPermission::ViewChannel)
+                {
+                    visible.push(member.id.user.to_string());
+                }
+            }
+        }
+
+        visible
+    }
+
+    /// Delete this message
+    ///
+    /// The message is immediately hidden but may be restored by the user who
+    /// deleted it until the configured undo window has passed.
+    pub async fn delete(self, db: &Database, deleted_by: &str) -> Result<()> {
+        let window = config().await.features.message_delete_window;
+        if window == 0 {
+            return self.delete_now(db, deleted_by).await;
+        }
+
+        // Record the deletion first so a failure here leaves the message untouched
+        MessageTombstone::create(db, &self, deleted_by).await?;
+
+        // Keep a copy of the message before removing it so the deletion can be undone
+        db.insert_pending_message_deletion(&PendingMessageDeletion {
+            id: self.id.to_string(),
+            channel: self.channel.to_string(),
+            deleted_by: deleted_by.to_string(),
+            finalise_at: Timestamp::from(SystemTime::now() + Duration::from_secs(window)),
+            message: self.clone(),
+        })
+        .await?;
+
+        db.delete_message(&self.id).await?;
+        self.discount_reply(db).await;
+        self.discount_pin(db).await;
+        Ok(())
+    }
+
+    /// Delete this message straight away, without allowing it to be undone
+    ///
+    /// Used where nobody could undo the deletion, such as when a webhook deletes its own message.
+    pub async fn delete_now(self, db: &Database, deleted_by: &str) -> Result<()> {
+        MessageTombstone::create(db, &self, deleted_by).await?;
+        db.delete_message(&self.id).await?;
+        self.discount_reply(db).await;
+        self.discount_pin(db).await;
+        self.finalise_delete(db).await
+    }
+
+    /// Clean up after this message once it has expired
+    ///
+    /// Expired messages can not be restored, the message must already have been
+    /// removed by claiming it through `claim_expired_message`.
+    pub async fn expire(self, db: &Database) -> Result<()> {
+        self.discount_reply(db).await;
+        self.discount_pin(db).await;
+        self.finalise_delete(db).await
+    }
+
+    /// Delete many messages in a channel at once
+    ///
+    /// All messages must belong to the channel and have been sent within the past two weeks.
+    pub async fn bulk_delete(
+        db: &Database,
+        channel: &str,
+        ids: Vec<String>,
+        deleted_by: &str,
+    ) -> Result<()> {
+        // Message ids encode the time they were sent at
+        let cutoff = Ulid::from_datetime(SystemTime::now() - MAX_BULK_DELETE_AGE).to_string();
+        let too_old: Vec<String> = ids.iter().filter(|id| **id < cutoff).cloned().collect();
+        if !too_old.is_empty() {
+            return Err(create_error!(MessagesTooOld { ids: too_old }));
+        }
+
+        let mut messages = Vec::with_capacity(ids.len());
+        for id in &ids {
+            let message = db.fetch_message(id).await?;
+            if message.channel != channel {
+                return Err(create_error!(NotFound));
+            }
+
+            messages.push(message);
+        }
+
+        let deleted: Vec<(String, String)> = messages
+            .iter()
+            .map(|message| (message.id.to_string(), message.author.to_string()))
+            .collect();
+
+        MessageTombstone::create_many(db, channel, &deleted, deleted_by).await?;
+        db.delete_messages(channel, &ids).await?;
+
+        // Release attachments and update the threads the messages replied to,
+        // forwarded messages only borrow the attachments of the original message
+        let file_ids: Vec<String> = messages
+            .iter()
+            .filter(|message| message.forwarded_from.is_none())
+            .flat_map(|message| message.attachments.iter().flatten())
+            .map(|file| file.id.to_string())
+            .collect();
+
+        if !file_ids.is_empty() {
+            db.mark_attachments_as_deleted(&file_ids).await?;
+        }
+
+        for message in &messages {
+            message.discount_reply(db).await;
+        }
+
+        let pinned = messages
+            .iter()
+            .filter(|message| message.pinned.unwrap_or_default())
+            .count();
+
+        if pinned > 0 {
+            db.remove_channel_pins(channel, pinned).await.ok();
+        }
+
+        EventV1::BulkMessageDelete {
+            channel: channel.to_string(),
+            ids,
+        }
+        .p(channel.to_string())
+        .await;
+
+        Ok(())
+    }
+
+    /// Restore a message which is still within its undo window
+    pub async fn undo_delete(
+        db: &Database,
+        channel: &str,
+        id: &str,
+        user_id: &str,
+    ) -> Result<Message> {
+        let message = db
+            .take_pending_message_deletion(channel, id, user_id)
+            .await?
+            .message;
+
+        db.insert_message(&message).await?;
+        db.delete_message_tombstone(&message.id).await?;
+        message.count_reply(db).await;
+
+        // Restored pins count towards the channel again, even if that takes it over the limit
+        if let Some(pinned_at) = message
+            .pinned_at
+            .filter(|_| message.pinned.unwrap_or_default())
+        {
+            db.add_channel_pin(&message.channel, pinned_at, usize::MAX)
+                .await
+                .ok();
+        }
+
+        Ok(message)
+    }
+
+    /// Count this message in the thread summaries of the messages it replies to
+    pub async fn count_reply(&self, db: &Database) {
+        for id in self.replies.iter().flatten() {
+            db.add_thread_reply(id, &self.author).await.ok();
+        }
+    }
+
+    /// Discount this message from the thread summaries of the messages it replies to
+    ///
+    /// Must be called after the message has been removed from the database.
+    pub async fn discount_reply(&self, db: &Database) {
+        for id in self.replies.iter().flatten() {
+            db.remove_thread_reply(id, &self.author).await.ok();
+        }
+    }
+
+    /// Discount this message from the pins of its channel
+    ///
+    /// Must be called after the message has been removed from the database.
+    pub async fn discount_pin(&self, db: &Database) {
+        if self.pinned.unwrap_or_default() {
+            db.remove_channel_pins(&self.channel, 1).await.ok();
+        }
+    }
+
+    /// Finalise deletion of this message, removing attachments and notifying clients
+    pub async fn finalise_delete(self, db: &Database) -> Result<()> {
+        let file_ids: Vec<String> = self
+            .attachments
+            .filter(|_| self.forwarded_from.is_none())
+            .map(|files| files.iter().map(|file| file.id.to_string()).collect())
+            .unwrap_or_default();
+
+        if !file_ids.is_empty() {
+            db.mark_attachments_as_deleted(&file_ids).await?;
+        }
+
+        EventV1::MessageDelete {
+            id: self.id,
+            channel: self.channel.clone(),
+        }
+        .p(self.channel)
+        .await;
+
+        Ok(())
+    }
+
+    /// Add a reaction to this message
+    pub async fn add_reaction(&self, db: &Database, user_id: &str, emoji: &str) -> Result<()> {
+        // Check how many distinct reactions are already on the message
+        let config = config().await;
+        let limits = &config.features.limits.default;
+        if self.reactions.len() >= limits.message_reactions && !self.reactions.contains_key(emoji) {
+            return Err(create_error!(TooManyReactions {
+                max: limits.message_reactions
+            }));
+        }
+
+        // Check how many reactions the user has already added to the message
+        let reacted = self
+            .reactions
+            .values()
+            .filter(|users| users.contains(user_id))
+            .count();
+
+        if reacted >= limits.message_reactions_per_user
+            && !self
+                .reactions
+                .get(emoji)
+                .is_some_and(|users| users.contains(user_id))
+        {
+            return Err(create_error!(TooManyReactions {
+                max: limits.message_reactions_per_user
+            }));
+        }
+
+        // Check if the emoji is whitelisted
+        if !self.interactions.can_use(emoji) {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        // Check if the emoji is usable by us
+        if !Emoji::can_use(db, user_id, emoji).await? {
+            return Err(create_error!(InvalidOperation));
+        }
+
+        // Rate limits are only consumed once the reaction is otherwise allowed,
+        // first check the user isn't reacting too quickly across all channels
+        if config.features.reaction_rate_limit > 0 {
+            REACTION_BUCKETS
+                .take(
+                    user_id,
+                    config.features.reaction_rate_limit,
+                    Duration::from_secs(config.features.reaction_rate_limit_period),
+                )
+                .map_err(|retry_after| {
+                    create_error!(RateLimited {
+                        retry_after: retry_after.as_millis() as u64
+                    })
+                })?;
+        }
+
+        // Then check the user hasn't added this reaction within its cooldown,
+        // which is recorded on the message so that it holds across every node
+        if let Some(cooldown) = self.interactions.cooldown(emoji) {
+            if let Some(retry_after) = db
+                .claim_reaction_cooldown(&self.id, emoji, user_id, cooldown)
+                .await?
+            {
+                return Err(create_error!(RateLimited {
+                    retry_after: retry_after.as_millis() as u64
+                }));
+            }
+        }
+
+        // Send reaction event
+        self.publish_reaction_event(EventV1::MessageReact {
+            id: self.id.to_string(),
+            channel_id: self.channel.to_string(),
+            user_id: user_id.to_string(),
+            emoji_id: emoji.to_string(),
+        })
+        .await;
+
+        // Add emoji
+        db.add_reaction(&self.id, emoji, user_id).await?;
+
+        // Grant any role attached to this reaction
+        self.apply_reaction_role(db, emoji, &[user_id.to_string()], true)
+            .await;
+
+        Ok(())
+    }
+
+    /// Remove a reaction from this message
+    pub async fn remove_reaction(&self, db: &Database, user_id: &str, emoji: &str) -> Result<()> {
+        // Check if it actually exists
+        let empty = if let Some(users) = self.reactions.get(emoji) {
+            if !users.contains(user_id) {
+                return Err(create_error!(NotFound));
+            }
+
+            users.len() == 1
+        } else {
+            return Err(create_error!(NotFound));
+        };
+
+        // Send reaction event
+        self.publish_reaction_event(EventV1::MessageUnreact {
+            id: self.id.to_string(),
+            channel_id: self.channel.to_string(),
+            user_id: user_id.to_string(),
+            emoji_id: emoji.to_string(),
+        })
+        .await;
+
+        if empty && !self.interactions.is_default_reaction(emoji) {
+            // If empty, remove the reaction entirely
+            db.clear_reaction(&self.id, emoji).await?;
+        } else {
+            // Otherwise only remove that one reaction
+            db.remove_reaction(&self.id, emoji, user_id).await?;
+        }
+
+        // Revoke any role attached to this reaction
+        self.apply_reaction_role(db, emoji, &[user_id.to_string()], false)
+            .await;
+
+        Ok(())
+    }
+
+    /// Remove all of a given reaction from this message
+    pub async fn clear_reaction(&self, db: &Database, emoji: &str) -> Result<()> {
+        // Send reaction event
+        self.publish_reaction_event(EventV1::MessageRemoveReaction {
+            id: self.id.to_string(),
+            channel_id: self.channel.to_string(),
+            emoji_id: emoji.to_string(),
+        })
+        .await;
+
+        // Write to database, interaction-defined reactions stay without any users
+        if self.interactions.is_default_reaction(emoji) {
+            db.reset_reaction(&self.id, emoji).await?;
+        } else {
+            db.clear_reaction(&self.id, emoji).await?;
+        }
+
+        // Revoke any role attached to this reaction
+        if let Some(users) = self.reactions.get(emoji) {
+            let users: Vec<String> = users.iter().cloned().collect();
+            self.apply_reaction_role(db, emoji, &users, false).await;
+        }
+
+        Ok(())
+    }
+
+    /// Remove all reactions from this message
+    pub async fn remove_all_reactions(
+        &mut self,
+        db: &Database,
+        permissions: &PermissionValue,
+    ) -> Result<()> {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+        // Interaction-defined reactions stay without any users
+        let keep = self.interactions.default_reactions();
+        db.clear_all_reactions(&self.id, &keep).await?;
+
+        self.publish_reaction_event(EventV1::MessageReactionRemoveAll {
+            id: self.id.to_string(),
+            channel_id: self.channel.to_string(),
+        })
+        .await;
+
+        // Revoke any roles attached to the reactions
+        let reactions = std::mem::replace(
+            &mut self.reactions,
+            keep.into_iter()
+                .map(|emoji| (emoji, IndexSet::new()))
+                .collect(),
+        );
+
+        for (emoji, users) in reactions {
+            let users: Vec<String> = users.into_iter().collect();
+            self.apply_reaction_role(db, &emoji, &users, false).await;
+        }
+
+        Ok(())
+    }
+
+    /// Publish a reaction event, batching it if enabled for this channel
+    ///
+    /// Other events are kept in order with any batched reaction changes.
+    async fn publish_reaction_event(&self, event: EventV1) {
+        if !reaction_batch::is_batched(&self.channel).await {
+            event.p(self.channel.to_string()).await;
+            return;
+        }
+
+        match event {
+            EventV1::MessageReact {
+                user_id, emoji_id, ..
+            } => {
+                reaction_batch::queue_change(
+                    self.id.to_string(),
+                    self.channel.to_string(),
+                    user_id,
+                    emoji_id,
+                    true,
+                )
+                .await
+            }
+            EventV1::MessageUnreact {
+                user_id, emoji_id, ..
+            } => {
+                reaction_batch::queue_change(
+                    self.id.to_string(),
+                    self.channel.to_string(),
+                    user_id,
+                    emoji_id,
+                    false,
+                )
+                .await
+            }
+            event => {
+                reaction_batch::queue_event(self.id.to_string(), self.channel.to_string(), event)
+                    .await
+            }
+        }
+    }
+
+    /// Grant or revoke the role attached to a reaction, if there is one
+    async fn apply_reaction_role(&self, db: &Database, emoji: &str, users: &[String], grant: bool) {
+        if let Ok(reaction_role) = db.fetch_reaction_role(&self.id, emoji).await {
+            for user_id in users {
+                if let Err(err) = reaction_role.apply(db, user_id, grant).await {
+                    error!(
+                        "Failed to apply reaction role {} to {user_id}! {err:?}",
+                        reaction_role.id
+                    );
+                }
+            }
+        }
+    }
+
+    /// Pin or unpin this message in its channel
+    ///
+    /// Announces the change in the channel with a system message.
+    pub async fn set_pinned(
+        &mut self,
+        db: &Database,
+        channel: &Channel,
+        permissions: &PermissionValue,
+        pinned: bool,
+        by: &str,
+    ) -> Result<()> {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+        if self.pinned.unwrap_or_default() == pinned {
+            return Ok(());
+        }
+
+        let partial = PartialMessage {
+            pinned: Some(pinned),
+            pinned_at: pinned.then(Timestamp::now_utc),
+            ..Default::default()
+        };
+
+        // Pins are counted on the channel, which also enforces the limit
+        if let Some(pinned_at) = partial.pinned_at {
+            let max = config().await.features.limits.default.channel_pins;
+            if !db.add_channel_pin(&self.channel, pinned_at, max).await? {
+                return Err(create_error!(TooManyPins { max }));
+            }
+        } else {
+            db.remove_channel_pins(&self.channel, 1).await?;
+        }
+
+        db.update_message(&self.id, &partial, vec![]).await?;
+        self.pinned = Some(pinned);
+        if partial.pinned_at.is_some() {
+            self.pinned_at = partial.pinned_at;
+        }
+
+        EventV1::MessageUpdate {
+            id: self.id.to_string(),
+            channel: self.channel.to_string(),
+            data: partial.into(),
+            clear: vec![],
+        }
+        .p(self.channel.to_string())
+        .await;
+
+        // Let clients update their pin indicators without fetching the pins
+        let pins = db.fetch_channel(&self.channel).await?.pins();
+        EventV1::ChannelPinsUpdate {
+            id: self.channel.to_string(),
+            pin_count: pins.count,
+            last_pinned_at: pins.last_pinned_at,
+        }
+        .p(self.channel.to_string())
+        .await;
+
+        let system = if pinned {
+            SystemMessage::MessagePinned {
+                id: self.id.to_string(),
+                by: by.to_string(),
+            }
+        } else {
+            SystemMessage::MessageUnpinned {
+                id: self.id.to_string(),
+                by: by.to_string(),
+            }
+        };
+
+        system
+            .into_message(self.channel.to_string())
+            .send_without_notifications(db, matches!(channel, Channel::DirectMessage { .. }), false)
+            .await
+    }
+
+    /// Keep a copy of this message's current content before it is edited
+    ///
+    /// Only the most recent revisions up to the configured limit are kept.
+    pub async fn record_revision(&self, db: &Database) -> Result<()> {
+        let limit = config().await.features.message_edit_history;
+        if limit == 0 {
+            return Ok(());
+        }
+
+        db.push_message_revision(
+            &self.id,
+            &MessageRevision {
+                content: self.content.clone(),
+                embeds: self.embeds.clone(),
+                edited_at: Timestamp::now_utc(),
+            },
+            limit,
+        )
+        .await
+    }
+
+    /// Edit the content and embeds of this message
+    ///
+    /// The previous version is kept in the edit history. Link previews are
+    /// generated again for the new content if `generate_embeds` is set.
+    pub async fn edit(
+        &mut self,
+        db: &Database,
+        channel: &Channel,
+        data: v0::DataEditMessage,
+        editor: &str,
+        generate_embeds: bool,
+    ) -> Result<()> {
+        let flags = match channel {
+            Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => {
+                db.fetch_server(server).await?.flags.unwrap_or_default() as u32
+            }
+            _ => 0,
+        };
+
+        let config = config().await;
+        let limits = config.features.server_limits(flags);
+        Message::validate_sum(
+            &data.content,
+            data.embeds.as_deref().unwrap_or_default(),
+            limits.message_length_for(&channel.id(), channel.channel_type()),
+            limits.message_embed_length,
+            limits.message_embed_description_length,
+        )?;
+
+        if data
+            .embeds
+            .as_ref()
+            .is_some_and(|v| v.len() > limits.message_embeds)
+        {
+            return Err(create_error!(TooManyEmbeds {
+                max: limits.message_embeds,
+            }));
+        }
+
+        let previous = self.clone();
+        self.edited = Some(Timestamp::now_utc());
+        let mut partial = PartialMessage {
+            edited: self.edited,
+            ..Default::default()
+        };
+
+        // Moderators may edit messages on behalf of other users,
+        // the author editing it again clears who it was last edited by
+        let mut remove = vec![];
+        if editor != self.author {
+            self.edited_by = Some(editor.to_string());
+            partial.edited_by = self.edited_by.clone();
+        } else if self.edited_by.is_some() {
+            self.remove_field(&FieldsMessage::EditedBy);
+            remove.push(FieldsMessage::EditedBy);
+        }
+
+        if let Some(content) = &data.content {
+            self.has_spoilers = Message::detect_spoilers(content)?
+                || self
+                    .attachments
+                    .as_ref()
+                    .is_some_and(|files| files.iter().any(File::is_spoiler));
+
+            self.content = Some(content.clone());
+            partial.content = self.content.clone();
+            partial.has_spoilers = Some(self.has_spoilers);
+        }
+
+        // Attachments may only be reordered, not added or removed
+        if let Some(order) = data.attachments {
+            let mut attachments = self.attachments.clone().unwrap_or_default();
+            if order.len() != attachments.len() {
+                return Err(create_error!(InvalidOperation));
+            }
+
+            let mut reordered = Vec::with_capacity(attachments.len());
+            for id in order {
+                let index = attachments
+                    .iter()
+                    .position(|file| file.id == id)
+                    .ok_or_else(|| create_error!(InvalidOperation))?;
+
+                reordered.push(attachments.remove(index));
+            }
+
+            self.attachments = Some(reordered);
+            partial.attachments = self.attachments.clone();
+        }
+
+        // Generated link previews no longer apply, sent embeds are kept unless replaced
+        if data.content.is_some() || data.embeds.is_some() {
+            self.embeds = self.embeds.take().map(|embeds| {
+                embeds
+                    .into_iter()
+                    .filter(|embed| matches!(embed, Embed::Text(_)))
+                    .collect()
+            });
+
+            if let Some(embeds) = data.embeds {
+                self.embeds = None;
+                for embed in embeds {
+                    self.attach_sendable_embed(db, embed).await?;
+                }
+            }
+
+            partial.embeds = Some(self.embeds.clone().unwrap_or_default());
+        }
+
+        if self.is_empty() {
+            return Err(create_error!(EmptyMessage));
+        }
+
+        // Keep the previous version around for moderators to review
+        previous.record_revision(db).await?;
+        db.update_message(&self.id, &partial, remove.clone())
+            .await?;
+
+        EventV1::MessageUpdate {
+            id: self.id.to_string(),
+            channel: self.channel.to_string(),
+            data: partial.into(),
+            clear: remove.into_iter().map(|v| v.into()).collect(),
+        }
+        .p(self.channel.to_string())
+        .await;
+
+        if generate_embeds && !self.embeds_suppressed() && !channel.embeds_disabled() {
+            if let Some(content) = data.content {
+                tasks::process_embeds::queue(
+                    self.channel.to_string(),
+                    self.id.to_string(),
+                    content,
+                )
+                .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Start a new poll in a channel
+    ///
+    /// The poll is sent as a system message which users can then vote on.
+    pub async fn create_poll(
+        db: &Database,
+        channel: &Channel,
+        data: v0::DataCreatePoll,
+        by: &str,
+    ) -> Result<Message> {
+        if data
+            .options
+            .iter()
+            .any(|option| option.is_empty() || option.len() > 100)
+        {
+            return Err(create_error!(FailedValidation {
+                error: "poll options must be between 1 and 100 characters".to_string()
+            }));
+        }
+
+        if data
+            .closes_at
+            .is_some_and(|closes_at| closes_at <= Timestamp::now_utc())
+        {
+            return Err(create_error!(InvalidProperty));
+        }
+
+        let mut message = SystemMessage::Poll {
+            question: data.question,
+            options: data.options.clone(),
+            by: by.to_string(),
+        }
+        .into_message(channel.id());
+
+        message.poll = Some(Poll {
+            options: data
+                .options
+                .into_iter()
+                .map(|name| PollOption {
+                    name,
+                    voters: IndexSet::new(),
+                })
+                .collect(),
+            multi_select: data.multi_select,
+            closes_at: data.closes_at,
+        });
+
+        message
+            .send_without_notifications(db, matches!(channel, Channel::DirectMessage { .. }), false)
+            .await?;
+
+        Ok(message)
+    }
+
+    /// Reference to the message this one was originally sent as
+    ///
+    /// Messages which were themselves forwarded point back to their original.
+    pub fn original_reference(&self) -> MessageReference {
+        self.forwarded_from
+            .clone()
+            .unwrap_or_else(|| MessageReference {
+                channel: self.channel.to_string(),
+                message: self.id.to_string(),
+                author: self.author.to_string(),
+            })
+    }
+
+    /// Forward this message into another channel
+    ///
+    /// Content, attachments and embeds are copied over as they are, the attachments
+    /// remain owned by the original message. The copy is sent by the forwarding user
+    /// and records the original message and its author.
+    ///
+    /// The copy is held to the same limits and slowmode as a normal send in the target channel.
+    pub async fn forward(
+        &self,
+        db: &Database,
+        target: &Channel,
+        user: &v0::User,
+    ) -> Result<Message> {
+        let config = config().await;
+        let flags = match target {
+            Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => {
+                db.fetch_server(server).await?.flags.unwrap_or_default() as u32
+            }
+            _ => 0,
+        };
+
+        let limits = config.features.server_limits(flags);
+
+        Message::validate_sum(
+            &self.content,
+            &[],
+            limits.message_length_for(&target.id(), target.channel_type()),
+            limits.message_embed_length,
+            limits.message_embed_description_length,
+        )?;
+
+        if self
+            .attachments
+            .as_ref()
+            .is_some_and(|v| v.len() > limits.message_attachments)
+        {
+            return Err(create_error!(TooManyAttachments {
+                max: limits.message_attachments,
+            }));
+        }
+
+        if self
+            .embeds
+            .as_ref()
+            .is_some_and(|v| v.len() > limits.message_embeds)
+        {
+            return Err(create_error!(TooManyEmbeds {
+                max: limits.message_embeds,
+            }));
+        }
+
+        Message::check_slowmode(db, target, &MessageAuthor::User(user)).await?;
+
+        let forwarded_from = self.original_reference();
+
+        let mut message = Message {
+            id: ulid_generator::generate().to_string(),
+            channel: target.id(),
+            author: user.id.to_string(),
+            content: self.content.clone(),
+            attachments: self.attachments.clone(),
+            embeds: self.embeds.clone(),
+            has_spoilers: self.has_spoilers,
+            language: self.language.clone(),
+            forwarded_from: Some(forwarded_from),
+            ..Default::default()
+        };
+
+        if message.is_empty() {
+            return Err(create_error!(EmptyMessage));
+        }
+
+        message
+            .send(db, MessageAuthor::User(user), target, false)
+            .await?;
+
+        Ok(message)
+    }
+
+    /// Record or toggle a user's vote on this message's poll
+    ///
+    /// Voting for an option the user already picked removes their vote,
+    /// otherwise single-select polls replace any vote for another option.
+    pub async fn vote_poll(&mut self, db: &Database, option: usize, user: &str) -> Result<()> {
+        let poll = self.poll.as_ref().ok_or_else(|| create_error!(NotFound))?;
+
+        if poll
+            .closes_at
+            .is_some_and(|closes_at| closes_at <= Timestamp::now_utc())
+        {
+            return Err(create_error!(PollClosed));
+        }
+
+        let selected = poll
+            .options
+            .get(option)
+            .ok_or_else(|| create_error!(InvalidProperty))?;
+
+        if selected.voters.contains(user) {
+            db.remove_poll_vote(&self.id, option, user).await?;
+        } else {
+            db.add_poll_vote(&self.id, option, user, !poll.multi_select)
+                .await?;
+        }
+
+        // Fetch the new tallies, other users may have voted in the meantime
+        self.poll = db.fetch_message(&self.id).await?.poll;
+
+        EventV1::MessageUpdate {
+            id: self.id.to_string(),
+            channel: self.channel.to_string(),
+            data: PartialMessage {
+                poll: self.poll.clone(),
+                ..Default::default()
+            }
+            .into(),
+            clear: vec![],
+        }
+        .p(self.channel.to_string())
+        .await;
+
+        Ok(())
+    }
+
+    /// Append content to message
+    pub async fn append(
+        db: &Database,
+        id: String,
+        channel: String,
+        append: AppendMessage,
+    ) -> Result<()> {
+        db.append_message(&id, &append).await?;
+
+        EventV1::MessageAppend {
+            id,
+            channel: channel.to_string(),
+            append: append.into(),
+        }
+        .p(channel)
+        .await;
+
+        Ok(())
+    }
+
+    /// Convert sendable embed to text embed and attach to message
+    pub async fn attach_sendable_embed(
+        &mut self,
+        db: &Database,
+        embed: v0::SendableEmbed,
+    ) -> Result<()> {
+        if let Some(colour) = &embed.colour {
+            Message::validate_embed_colour(colour)?;
+        }
+
+        let media: Option<v0::File> = if let Some(id) = embed.media {
+            Some(
+                db.find_and_use_attachment(&id, "attachments", "message", &self.id)
+                    .await?
+                    .into(),
+            )
+        } else {
+            None
+        };
+
+        let embed = v0::Embed::Text(v0::Text {
+            icon_url: embed.icon_url,
+            url: embed.url,
+            title: embed.title,
+            description: embed.description,
+            media,
+            colour: embed.colour,
+        });
+
+        if let Some(embeds) = &mut self.embeds {
+            embeds.push(embed);
+        } else {
+            self.embeds = Some(vec![embed]);
+        }
+
+        Ok(())
+    }
+
+    /// Ensure an embed colour is a solid CSS colour which clients can render
+    pub fn validate_embed_colour(colour: &str) -> Result<()> {
+        if is_valid_colour(colour, false) {
+            Ok(())
+        } else {
+            Err(create_error!(InvalidProperty))
+        }
+    }
+
+    /// Ensure masquerade overrides are safe for clients to display
+    ///
+    /// Avatars must be http(s) URLs and colours must be valid CSS colours.
+    pub fn validate_masquerade(masquerade: &v0::Masquerade, allow_gradients: bool) -> Result<()> {
+        if let Some(avatar) = &masquerade.avatar {
+            let avatar = avatar.to_lowercase();
+            if !avatar.starts_with("https://") && !avatar.starts_with("http://") {
+                return Err(create_error!(InvalidProperty));
+            }
+        }
+
+        if let Some(colour) = &masquerade.colour {
+            if !is_valid_colour(colour, allow_gradients) {
+                return Err(create_error!(InvalidProperty));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate the sum of content of a message is under threshold
+    ///
+    /// Attachments and stickers do not count towards the total. Embeds count towards
+    /// the total as well as their own separate total, and each description is capped.
+    pub fn validate_sum(
+        content: &Option<String>,
+        embeds: &[SendableEmbed],
+        max_length: usize,
+        max_embed_length: usize,
+        max_description_length: usize,
+    ) -> Result<()> {
+        let mut running_total = 0;
+        if let Some(content) = content {
+            running_total += content.len();
+        }
+
+        let mut embed_total = 0;
+        for embed in embeds {
+            if let Some(desc) = &embed.description {
+                if desc.len() > max_description_length {
+                    return Err(create_error!(PayloadTooLarge));
+                }
+            }
+
+            embed_total += Message::embed_length(embed);
+        }
+
+        running_total += embed_total;
+        if running_total <= max_length && embed_total <= max_embed_length {
+            Ok(())
+        } else {
+            Err(create_error!(PayloadTooLarge))
+        }
+    }
+
+    /// Count the length of all text shown in an embed
+    fn embed_length(embed: &SendableEmbed) -> usize {
+        // Destructured so that any new fields have to be accounted for here
+        let SendableEmbed {
+            icon_url,
+            url,
+            title,
+            description,
+            // Attachment id, validated separately
+            media: _,
+            // Not shown as text
+            colour: _,
+        } = embed;
+
+        [icon_url, url, title, description]
+            .into_iter()
+            .flatten()
+            .map(String::len)
+            .sum()
+    }
+}
+
+impl SystemMessage {
+    pub fn into_message(self, channel: String) -> Message {
+        Message {
+            id: ulid_generator::generate().to_string(),
+            channel,
+            author: "00000000000000000000000000".to_string(),
+            system: Some(self),
+
+            ..Default::default()
+        }
+    }
+}
+
+impl MessageTimePeriod {
+    /// Create an absolute time period covering messages sent from `from_time` (inclusive)
+    /// up to `to_time` (exclusive), alongside any given message id bounds
+    ///
+    /// Message ids are ULIDs which encode the time they were created at,
+    /// so the times are translated into the equivalent id bounds.
+    pub fn from_time_range(
+        before: Option<String>,
+        after: Option<String>,
+        from_time: Option<SystemTime>,
+        to_time: Option<SystemTime>,
+        sort: Option<MessageSort>,
+    ) -> MessageTimePeriod {
+        let as_millis = |time: SystemTime| {
+            time.duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as u64)
+                .unwrap_or_default()
+        };
+
+        // Last possible id in the millisecond before `from_time`
+        let from_id = from_time
+            .map(as_millis)
+            .filter(|ms| *ms > 0)
+            .map(|ms| Ulid::from_parts(ms - 1, u128::MAX).to_string());
+
+        // First possible id in the millisecond of `to_time`
+        let to_id = to_time.map(|time| Ulid::from_parts(as_millis(time), 0).to_string());
+
+        MessageTimePeriod::Absolute {
+            before: match (before, to_id) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            },
+            after: match (after, from_id) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            },
+            sort,
+        }
+    }
+}
+
+impl Interactions {
+    /// Validate interactions info is correct
+    pub async fn validate(
+        &self,
+        db: &Database,
+        user_id: &str,
+        permissions: &PermissionValue,
+    ) -> Result<()> {
+        let config = config().await;
+
+        if let Some(reactions) = &self.reactions {
+            permissions.throw_if_lacking_channel_permission(ChannelPermission::React)?;
+
+            if reactions.len() > config.features.limits.default.message_reactions {
+                return Err(create_error!(InvalidOperation));
+            }
+
+            for reaction in reactions {
+                if !Emoji::can_use(db, user_id, reaction).await? {
+                    return Err(create_error!(InvalidOperation));
+                }
+            }
+        }
+
+        if let Some(cooldowns) = &self.reaction_cooldowns {
+            if cooldowns.len() > config.features.limits.default.message_reactions
+                || cooldowns
+                    .values()
+                    .any(|cooldown| !(1..=MAX_REACTION_COOLDOWN).contains(cooldown))
+            {
+                return Err(create_error!(InvalidProperty));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check if we can use a given emoji to react
+    pub fn can_use(&self, emoji: &str) -> bool {
+        if self.restrict_reactions {
+            if let Some(reactions) = &self.reactions {
+                reactions.contains(emoji)
+            } else {
+                false
+            }
+        } else {
+            true
+        }
+    }
+
+    /// Get the reactions which should always appear on the message
+    pub fn default_reactions(&self) -> Vec<String> {
+        self.reactions
+            .as_ref()
+            .map(|reactions| reactions.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Check if a given emoji is one of the reactions which should always appear
+    pub fn is_default_reaction(&self, emoji: &str) -> bool {
+        self.reactions
+            .as_ref()
+            .is_some_and(|reactions| reactions.contains(emoji))
+    }
+
+    /// Get the cooldown between adding a given reaction, if any
+    pub fn cooldown(&self, emoji: &str) -> Option<Duration> {
+        self.reaction_cooldowns
+            .as_ref()?
+            .get(emoji)
+            .map(|seconds| Duration::from_secs(*seconds))
+    }
+
+    /// Check if default initialisation of fields
+    pub fn is_default(&self) -> bool {
+        !self.restrict_reactions && self.reactions.is_none() && self.reaction_cooldowns.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use indexmap::{IndexMap, IndexSet};
+    use iso8601_timestamp::Timestamp;
+    use onechatsocial_config::config;
+    use onechatsocial_models::v0::{
+        self, AllowedMentions, DataMessageSend, Embed, MentionType, MessageAuthor, MessageHas,
+        PushNotification, ReplyIntent, RE_LANGUAGE,
+    };
+    use onechatsocial_permissions::{ChannelPermission, OverrideField, PermissionValue};
+    use onechatsocial_result::ErrorType;
+
+    use crate::{
+        fixture, util::idempotency::IdempotencyKey, AttachmentScanner, Channel,
+        ChannelNotificationPreference, FieldsRole, File, Interactions, Member, Message,
+        MessageFilter, MessageQuery, MessageReference, MessageTimePeriod, Metadata,
+        NoopAttachmentScanner, NotificationLevel, PartialChannel, PartialRole, PartialServer,
+        ScanVerdict, Sticker, SystemMessage, User,
+    };
+
+    #[test]
+    fn empty_message() {
+        let message = Message::default();
+        assert!(message.is_empty());
+
+        let message = Message {
+            content: Some(String::new()),
+            attachments: Some(vec![]),
+            embeds: Some(vec![]),
+            ..Default::default()
+        };
+        assert!(message.is_empty());
+    }
+
+    #[test]
+    fn message_with_content() {
+        let message = Message {
+            content: Some("Hello!".to_string()),
+            ..Default::default()
+        };
+        assert!(message.has_content());
+    }
+
+    #[test]
+    fn message_with_attachments() {
+        let message = Message {
+            attachments: Some(vec![File {
+                id: "file".to_string(),
+                tag: "attachments".to_string(),
+                filename: "file.txt".to_string(),
+                metadata: Default::default(),
+                content_type: "text/plain".to_string(),
+                size: 0,
+                description: None,
+                is_voice: false,
+                waveform: None,
+                duration: None,
+                deleted: None,
+                reported: None,
+                message_id: None,
+                user_id: None,
+                server_id: None,
+                object_id: None,
+            }]),
+            ..Default::default()
+        };
+        assert!(message.has_content());
+    }
+
+    /// Scanner which quarantines executables
+    struct ExecutableScanner;
+
+    #[async_trait]
+    impl AttachmentScanner for ExecutableScanner {
+        async fn scan(&self, file: &File) -> ScanVerdict {
+            if file.filename.ends_with(".exe") {
+                ScanVerdict::Quarantine {
+                    reason: "executable".to_string(),
+                }
+            } else {
+                ScanVerdict::Clean
+            }
+        }
+    }
+
+    #[async_std::test]
+    async fn quarantined_attachments() {
+        let file = |filename: &str| File {
+            id: "file".to_string(),
+            tag: "attachments".to_string(),
+            filename: filename.to_string(),
+            metadata: Default::default(),
+            content_type: "application/octet-stream".to_string(),
+            size: 0,
+            description: None,
+            is_voice: false,
+            waveform: None,
+            duration: None,
+            deleted: None,
+            reported: None,
+            message_id: None,
+            user_id: None,
+            server_id: None,
+            object_id: None,
+        };
+
+        assert!(
+            Message::scan_attachment(&file("notes.txt"), &ExecutableScanner)
+                .await
+                .is_ok()
+        );
+
+        let error = Message::scan_attachment(&file("setup.exe"), &ExecutableScanner)
+            .await
+            .unwrap_err();
+        assert!(matches!(error.error_type, ErrorType::AttachmentQuarantined));
+
+        // Nothing is quarantined by default
+        assert!(
+            Message::scan_attachment(&file("setup.exe"), &NoopAttachmentScanner)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn message_with_embeds() {
+        let message = Message {
+            embeds: Some(vec![Embed::None]),
+            ..Default::default()
+        };
+        assert!(message.has_content());
+    }
+
+    #[test]
+    fn system_message() {
+        let message = SystemMessage::Text {
+            content: "Hello!".to_string(),
+        }
+        .into_message("channel".to_string());
+        assert!(message.has_content());
+    }
+
+    #[test]
+    fn data_with_content() {
+        let data = DataMessageSend {
+            nonce: None,
+            content: None,
+            attachments: Some(vec![]),
+            attachment_descriptions: None,
+            stickers: None,
+            replies: None,
+            embeds: Some(vec![]),
+            masquerade: None,
+            interactions: None,
+            quote: None,
+            language: None,
+            if_last_message: None,
+            send_at: None,
+            expires_in: None,
+            allowed_mentions: None,
+            voice: None,
+        };
+        assert!(!Message::data_has_content(&data));
+
+        let data = DataMessageSend {
+            content: Some("Hello!".to_string()),
+            ..data
+        };
+        assert!(Message::data_has_content(&data));
+
+        // Quotes are sent as an embed
+        let data = DataMessageSend {
+            content: None,
+            quote: Some("message".to_string()),
+            ..data
+        };
+        assert!(Message::data_has_content(&data));
+    }
+
+    #[test]
+    fn language_tags() {
+        for tag in ["en", "en-GB", "zh-Hant-TW", "es-419", "de-CH-1996"] {
+            assert!(RE_LANGUAGE.is_match(tag), "{tag}");
+        }
+
+        for tag in ["e", "english", "en_GB", "en-", "en-GB-x"] {
+            assert!(!RE_LANGUAGE.is_match(tag), "{tag}");
+        }
+    }
+
+    #[test]
+    fn embed_length() {
+        let embed = |title: &str, description: &str| v0::SendableEmbed {
+            title: Some(title.to_string()),
+            description: Some(description.to_string()),
+            ..Default::default()
+        };
+
+        let validate = |content: &str, embeds: &[v0::SendableEmbed]| {
+            Message::validate_sum(&Some(content.to_string()), embeds, 2000, 1000, 500)
+        };
+
+        assert!(validate("Hello!", &[embed("Title", "Description")]).is_ok());
+
+        // Titles count towards both totals
+        for (content, title) in [
+            ("Hello!".to_string(), "a".repeat(1500)),
+            ("a".repeat(1500), "a".repeat(600)),
+        ] {
+            assert!(matches!(
+                validate(&content, &[embed(&title, "Description")])
+                    .unwrap_err()
+                    .error_type,
+                ErrorType::PayloadTooLarge
+            ));
+        }
+
+        // Each description is capped on its own
+        assert!(validate("Hello!", &[embed("Title", &"a".repeat(501))]).is_err());
+
+        // Embeds together may not exceed their own total
+        let embeds: Vec<v0::SendableEmbed> =
+            (0..3).map(|_| embed("Title", &"a".repeat(400))).collect();
+
+        assert!(validate("Hello!", &embeds[..2]).is_ok());
+        assert!(validate("Hello!", &embeds).is_err());
+    }
+
+    #[test]
+    fn spoiler_markers() {
+        assert!(!Message::detect_spoilers("Hello!").unwrap());
+        assert!(Message::detect_spoilers("The ||butler|| did it").unwrap());
+        assert!(Message::detect_spoilers("||one|| and ||two||").unwrap());
+        assert!(Message::detect_spoilers("||unclosed").is_err());
+        assert!(Message::detect_spoilers("||one|| and ||unclosed").is_err());
+        assert!(!Message::detect_spoilers("`a || b`").unwrap());
+        assert!(!Message::detect_spoilers("```\nif a || b {}\n```").unwrap());
+        assert!(Message::detect_spoilers("||`code`|| `||`").unwrap());
+    }
+
+    #[test]
+    fn time_range_maps_to_id_bounds() {
+        let time = SystemTime::now();
+        let MessageTimePeriod::Absolute {
+            before: Some(before),
+            after: Some(after),
+            ..
+        } = MessageTimePeriod::from_time_range(
+            None,
+            None,
+            Some(time),
+            Some(time + Duration::from_secs(60)),
+            None,
+        )
+        else {
+            panic!("expected both bounds");
+        };
+
+        let id_at = |time: SystemTime| ulid::Ulid::from_datetime(time).to_string();
+
+        // Messages sent within the range are included, from_time is inclusive
+        assert!(id_at(time) > after && id_at(time) < before);
+        assert!(id_at(time + Duration::from_secs(30)) > after);
+        assert!(id_at(time + Duration::from_secs(30)) < before);
+
+        // Messages sent outside of the range are excluded, to_time is exclusive
+        assert!(id_at(time - Duration::from_millis(1)) < after);
+        assert!(id_at(time + Duration::from_secs(60)) > before);
+    }
+
+    #[test]
+    fn time_range_keeps_narrower_ids() {
+        let time = SystemTime::now();
+        let inner_after = ulid::Ulid::from_datetime(time + Duration::from_secs(10)).to_string();
+        let inner_before = ulid::Ulid::from_datetime(time + Duration::from_secs(20)).to_string();
+
+        assert_eq!(
+            MessageTimePeriod::from_time_range(
+                Some(inner_before.clone()),
+                Some(inner_after.clone()),
+                Some(time),
+                Some(time + Duration::from_secs(60)),
+                None,
+            ),
+            MessageTimePeriod::Absolute {
+                before: Some(inner_before),
+                after: Some(inner_after),
+                sort: None,
+            }
+        );
+    }
+
+    #[test]
+    fn display_author() {
+        let message = Message {
+            webhook: Some(v0::MessageWebhook {
+                name: "Webhook".to_string(),
+                avatar: Some("avatar".to_string()),
+            }),
+            ..Default::default()
+        };
+
+        let resolved: v0::Message = message.clone().into();
+        assert_eq!(Some("Webhook".to_string()), resolved.display_author.name);
+        assert_eq!(
+            Some(v0::DisplayAvatar::File {
+                id: "avatar".to_string()
+            }),
+            resolved.display_author.avatar
+        );
+
+        // Masquerades take priority over the webhook
+        let message = Message {
+            masquerade: Some(crate::Masquerade {
+                name: Some("Masquerade".to_string()),
+                avatar: None,
+                colour: None,
+            }),
+            ..message
+        };
+
+        let resolved: v0::Message = message.into();
+        assert_eq!(Some("Masquerade".to_string()), resolved.display_author.name);
+        assert!(matches!(
+            resolved.display_author.avatar,
+            Some(v0::DisplayAvatar::File { .. })
+        ));
+
+        // Users without a masquerade use their own profile
+        let resolved: v0::Message = Message::default().into();
+        assert_eq!(v0::DisplayAuthor::default(), resolved.display_author);
+    }
+
+    #[test]
+    fn masquerade_validation() {
+        let masquerade = |avatar: Option<&str>, colour: Option<&str>| v0::Masquerade {
+            name: None,
+            avatar: avatar.map(|avatar| avatar.to_string()),
+            colour: colour.map(|colour| colour.to_string()),
+        };
+
+        for (avatar, colour) in [
+            (Some("https://example.com/avatar.png"), None),
+            (Some("HTTP://example.com/avatar.png"), Some("#ff0000")),
+            (None, Some("rgb(255, 0, 0)")),
+            (None, Some("rebeccapurple")),
+            (None, Some("linear-gradient(90deg, red, #00f 50%)")),
+        ] {
+            assert!(Message::validate_masquerade(&masquerade(avatar, colour), true).is_ok());
+        }
+
+        // Gradients are only accepted where enabled
+        let gradient = masquerade(None, Some("conic-gradient(from 45deg, red, orange)"));
+        assert!(Message::validate_masquerade(&gradient, true).is_ok());
+        assert!(Message::validate_masquerade(&gradient, false).is_err());
+
+        for (avatar, colour) in [
+            (Some("javascript:alert(1)"), None),
+            (Some("data:image/png;base64,AAAA"), None),
+            (Some("//example.com/avatar.png"), None),
+            (None, Some("#12345")),
+            (None, Some("#ggg")),
+            (None, Some("#ff0000; background: url(x)")),
+            (None, Some("linear-gradient(90deg, red, nope)")),
+        ] {
+            assert!(Message::validate_masquerade(&masquerade(avatar, colour), true).is_err());
+        }
+    }
+
+    #[async_std::test]
+    async fn escaped_mentions() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                member user 1
+                channel channel 3);
+
+            let mention = format!("<@{}>", member.id);
+            assert_eq!(
+                vec![member.id.as_str()],
+                v0::parse_mentions(&mention).collect::<Vec<&str>>()
+            );
+
+            let escaped = v0::escape_mentions(&mention);
+            assert_eq!(format!("\\{mention}"), escaped);
+            assert_eq!(0, v0::parse_mentions(&escaped).count());
+
+            let author = owner.clone().into_self().await;
+            let send = |content: String| DataMessageSend {
+                nonce: None,
+                content: Some(content),
+                attachments: None,
+                attachment_descriptions: None,
+                stickers: None,
+                replies: None,
+                embeds: None,
+                masquerade: None,
+                interactions: None,
+                quote: None,
+                language: None,
+                if_last_message: None,
+                send_at: None,
+                expires_in: None,
+                allowed_mentions: None,
+                voice: None,
+            };
+
+            // Nothing is parsed when mentions are not allowed
+            let content = format!("Hello {mention}!");
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(content.clone()),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("no_mentions".to_string()),
+                false,
+                false,
+            )
+            .await
+            .unwrap();
+
+            assert!(message.mentions.is_none());
+            assert_eq!(Some(content), message.content);
+
+            // Escaped mentions are kept verbatim but never parsed
+            let content = format!("Hello {escaped}!");
+            let message = Message::create_from_api(
+                &db,
+                channel,
+                send(content.clone()),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("escaped_mentions".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert!(message.mentions.is_none());
+            assert_eq!(Some(content), message.content);
+        });
+    }
+
+    #[async_std::test]
+    async fn allowed_mentions() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                member user 1
+                channel channel 3);
+
+            let author = owner.clone().into_self().await;
+            let send = |allowed_mentions: AllowedMentions| DataMessageSend {
+                nonce: None,
+                content: Some(format!("Hello <@{}> and <@{}>!", owner.id, member.id)),
+                attachments: None,
+                attachment_descriptions: None,
+                stickers: None,
+                replies: None,
+                embeds: None,
+                masquerade: None,
+                interactions: None,
+                quote: None,
+                language: None,
+                if_last_message: None,
+                send_at: None,
+                expires_in: None,
+                allowed_mentions: Some(allowed_mentions),
+                voice: None,
+            };
+
+            // Only users in the allowlist are mentioned
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(AllowedMentions {
+                    users: vec![member.id.clone()],
+                    ..Default::default()
+                }),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("allowed_mentions_users".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(Some(vec![member.id.clone()]), message.mentions);
+
+            // Nothing is mentioned unless users are parsed or listed
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(AllowedMentions::default()),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("allowed_mentions_none".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert!(message.mentions.is_none());
+
+            // Users can not be parsed and listed at the same time
+            let error = Message::create_from_api(
+                &db,
+                channel,
+                send(AllowedMentions {
+                    parse: vec![MentionType::Users],
+                    users: vec![member.id.clone()],
+                    ..Default::default()
+                }),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("allowed_mentions_ambiguous".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(error.error_type, ErrorType::InvalidOperation));
+        });
+    }
+
+    #[async_std::test]
+    async fn role_and_mass_mentions() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                owner user 0
+                moderator user 1
+                server server 4
+                channel channel 3);
+
+            let role = server
+                .roles
+                .iter()
+                .find(|(_, role)| role.name == "Moderator")
+                .map(|(id, _)| id.to_string())
+                .unwrap();
+
+            let send = |content: String| DataMessageSend {
+                nonce: None,
+                content: Some(content),
+                attachments: None,
+                attachment_descriptions: None,
+                stickers: None,
+                replies: None,
+                embeds: None,
+                masquerade: None,
+                interactions: None,
+                quote: None,
+                language: None,
+                if_last_message: None,
+                send_at: None,
+                expires_in: None,
+                allowed_mentions: None,
+                voice: None,
+            };
+
+            // Everyone who can see the channel is mentioned, except the author
+            let author = owner.clone().into_self().await;
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send("Hello @everyone!".to_string()),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("mention_everyone".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(Some(vec![moderator.id.clone()]), message.mentions);
+
+            // Role mentions are stored and notify members with the role
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(format!("Hello <%{role}>!")),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("mention_role".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(Some(vec![role.clone()]), message.role_mentions);
+            assert_eq!(Some(vec![moderator.id.clone()]), message.mentions);
+
+            // Mentioning everyone requires permission to do so
+            let author = moderator.clone().into_self().await;
+            let message = Message::create_from_api(
+                &db,
+                channel,
+                send(format!("Hello @everyone and <%{role}>!")),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("mention_everyone_denied".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert!(message.mentions.is_none());
+            assert!(message.role_mentions.is_none());
+        });
+    }
+
+    #[async_std::test]
+    async fn mass_mentions_are_bounded() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                moderator user 1
+                server server 4
+                channel channel 3);
+
+            let role = server
+                .roles
+                .iter()
+                .find(|(_, role)| role.name == "Moderator")
+                .map(|(id, _)| id.to_string())
+                .unwrap();
+
+            // Servers with more members than the limit are refused outright
+            let error = Message::resolve_mass_mentions(&db, &channel, vec![], true, false, 2)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                error.error_type,
+                ErrorType::TooManyMentions { max: 2 }
+            ));
+
+            // Roles only fetch their own members
+            let (roles, users) =
+                Message::resolve_mass_mentions(&db, &channel, vec![role.clone()], false, false, 1)
+                    .await
+                    .unwrap();
+
+            assert_eq!(vec![role], roles);
+            assert_eq!(vec![moderator.id.to_string()], users);
+        });
+    }
+
+    #[async_std::test]
+    async fn role_icon() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                owner user 0
+                moderator user 1
+                server server 4
+                channel channel 3);
+
+            let role = server
+                .roles
+                .iter()
+                .find(|(_, role)| role.name == "Moderator")
+                .map(|(id, _)| id.to_string())
+                .unwrap();
+
+            db.update_role(
+                &server.id,
+                &role,
+                &PartialRole {
+                    hoist: Some(true),
+                    icon: Some(File {
+                        id: "icon".to_string(),
+                        tag: "icons".to_string(),
+                        filename: "icon.png".to_string(),
+                        metadata: Default::default(),
+                        content_type: "image/png".to_string(),
+                        size: 0,
+                        description: None,
+                        is_voice: false,
+                        waveform: None,
+                        duration: None,
+                        deleted: None,
+                        reported: None,
+                        message_id: None,
+                        user_id: None,
+                        server_id: None,
+                        object_id: Some(role.clone()),
+                    }),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+            let send = |masquerade: Option<v0::Masquerade>| DataMessageSend {
+                nonce: None,
+                content: Some("Hello!".to_string()),
+                attachments: None,
+                attachment_descriptions: None,
+                stickers: None,
+                replies: None,
+                embeds: None,
+                masquerade,
+                interactions: None,
+                quote: None,
+                language: None,
+                if_last_message: None,
+                send_at: None,
+                expires_in: None,
+                allowed_mentions: None,
+                voice: None,
+            };
+
+            // Members with a hoisted role show its icon
+            let author = moderator.clone().into_self().await;
+            let sent = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(None),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("role_icon".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(
+                Some("icon".to_string()),
+                sent.fetch_role_icon(&db, &channel).await
+            );
+
+            // Masquerades hide the author's role icon
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(Some(v0::Masquerade {
+                    name: Some("Masquerade".to_string()),
+                    avatar: None,
+                    colour: None,
+                })),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("role_icon_masquerade".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert!(message.fetch_role_icon(&db, &channel).await.is_none());
+
+            // Members without a hoisted role with an icon show nothing
+            let author = owner.clone().into_self().await;
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(None),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("role_icon_none".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert!(message.fetch_role_icon(&db, &channel).await.is_none());
+
+            // Icons follow changes to the role after the message was sent
+            db.update_role(
+                &server.id,
+                &role,
+                &PartialRole::default(),
+                vec![FieldsRole::Icon],
+            )
+            .await
+            .unwrap();
+
+            assert!(sent.fetch_role_icon(&db, &channel).await.is_none());
+        });
+    }
+
+    #[async_std::test]
+    async fn slowmode() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                moderator user 1
+                user user 2
+                channel channel 3);
+
+            let mut channel = channel;
+            channel.apply_options(PartialChannel {
+                slowmode_seconds: Some(60),
+                ..Default::default()
+            });
+
+            let send = || DataMessageSend {
+                nonce: None,
+                content: Some("Hello!".to_string()),
+                attachments: None,
+                attachment_descriptions: None,
+                stickers: None,
+                replies: None,
+                embeds: None,
+                masquerade: None,
+                interactions: None,
+                quote: None,
+                language: None,
+                if_last_message: None,
+                send_at: None,
+                expires_in: None,
+                allowed_mentions: None,
+                voice: None,
+            };
+
+            // Members may only send one message per interval
+            let author = user.clone().into_self().await;
+            for attempt in 0..2 {
+                let result = Message::create_from_api(
+                    &db,
+                    channel.clone(),
+                    send(),
+                    MessageAuthor::User(&author),
+                    IdempotencyKey::unchecked_from_string(format!("slowmode_{attempt}")),
+                    false,
+                    true,
+                )
+                .await;
+
+                if attempt == 0 {
+                    result.unwrap();
+                } else {
+                    assert!(matches!(
+                        result.unwrap_err().error_type,
+                        ErrorType::RateLimited { retry_after } if retry_after > 0
+                    ));
+                }
+            }
+
+            // Members who can manage messages are not limited
+            let author = moderator.clone().into_self().await;
+            for attempt in 0..2 {
+                Message::create_from_api(
+                    &db,
+                    channel.clone(),
+                    send(),
+                    MessageAuthor::User(&author),
+                    IdempotencyKey::unchecked_from_string(format!("slowmode_mod_{attempt}")),
+                    false,
+                    true,
+                )
+                .await
+                .unwrap();
+            }
+        });
+    }
+
+    #[async_std::test]
+    async fn reply_snapshots() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                channel channel 3);
+
+            let config = config().await;
+            let length = config.features.reply_snapshot_length;
+
+            let original = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: owner.id.clone(),
+                content: Some("a".repeat(length + 10)),
+                ..Default::default()
+            };
+
+            db.insert_message(&original).await.unwrap();
+
+            let author = owner.clone().into_self().await;
+            let reply = |key: &str| {
+                Message::create_from_api(
+                    &db,
+                    channel.clone(),
+                    DataMessageSend {
+                        nonce: None,
+                        content: Some("Replying".to_string()),
+                        attachments: None,
+                        attachment_descriptions: None,
+                        stickers: None,
+                        replies: Some(vec![ReplyIntent {
+                            id: original.id.clone(),
+                            mention: false,
+                        }]),
+                        embeds: None,
+                        masquerade: None,
+                        interactions: None,
+                        quote: None,
+                        language: None,
+                        if_last_message: None,
+                        send_at: None,
+                        expires_in: None,
+                        allowed_mentions: None,
+                        voice: None,
+                    },
+                    MessageAuthor::User(&author),
+                    IdempotencyKey::unchecked_from_string(key.to_string()),
+                    false,
+                    true,
+                )
+            };
+
+            let message = reply("reply_snapshot").await.unwrap();
+
+            // The preview survives the original being deleted
+            db.delete_message(&original.id).await.unwrap();
+
+            let snapshots = db
+                .fetch_message(&message.id)
+                .await
+                .unwrap()
+                .reply_snapshots
+                .unwrap();
+
+            assert_eq!(1, snapshots.len());
+            assert_eq!(original.id, snapshots[0].message);
+            assert_eq!(owner.id, snapshots[0].author);
+            assert_eq!(Some("a".repeat(length)), snapshots[0].content);
+
+            // Replying to a deleted message still fails
+            assert!(matches!(
+                reply("reply_snapshot_deleted")
+                    .await
+                    .unwrap_err()
+                    .error_type,
+                ErrorType::NotFound
+            ));
+        });
+    }
+
+    #[async_std::test]
+    async fn reply_mentions_are_bounded() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                channel channel 3);
+
+            let config = config().await;
+            let limits = config.features.limits.default;
+
+            let mut replies = vec![];
+            for _ in 0..limits.message_replies {
+                let message = Message {
+                    id: ulid::Ulid::new().to_string(),
+                    channel: channel.id(),
+                    author: ulid::Ulid::new().to_string(),
+                    content: Some("Hello!".to_string()),
+                    ..Default::default()
+                };
+
+                db.insert_message(&message).await.unwrap();
+                replies.push(ReplyIntent {
+                    id: message.id,
+                    mention: true,
+                });
+            }
+
+            let author = owner.clone().into_self().await;
+            let message = Message::create_from_api(
+                &db,
+                channel,
+                DataMessageSend {
+                    nonce: None,
+                    content: Some("Replying to everyone".to_string()),
+                    attachments: None,
+                    attachment_descriptions: None,
+                    stickers: None,
+                    replies: Some(replies),
+                    embeds: None,
+                    masquerade: None,
+                    interactions: None,
+                    quote: None,
+                    language: None,
+                    if_last_message: None,
+                    send_at: None,
+                    expires_in: None,
+                    allowed_mentions: None,
+                    voice: None,
+                },
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("reply_mentions".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(
+                limits.message_replies,
+                message.replies.unwrap_or_default().len()
+            );
+            assert_eq!(
+                limits.message_reply_mentions.min(limits.message_replies),
+                message.mentions.unwrap_or_default().len()
+            );
+        });
+    }
+
+    #[async_std::test]
+    async fn attachments_keep_order() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                channel channel 3);
+
+            let mut ids = vec![];
+            for i in 0..10 {
+                let file = File {
+                    id: ulid::Ulid::new().to_string(),
+                    tag: "attachments".to_string(),
+                    filename: format!("{i}.txt"),
+                    metadata: Default::default(),
+                    content_type: "text/plain".to_string(),
+                    size: i,
+                    description: None,
+                    is_voice: false,
+                    waveform: None,
+                    duration: None,
+                    deleted: None,
+                    reported: None,
+                    message_id: None,
+                    user_id: None,
+                    server_id: None,
+                    object_id: None,
+                };
+
+                db.insert_attachment(&file).await.unwrap();
+                ids.push(file.id);
+            }
+
+            ids.reverse();
+
+            let author = owner.clone().into_self().await;
+            let message = Message::create_from_api(
+                &db,
+                channel,
+                DataMessageSend {
+                    nonce: None,
+                    content: None,
+                    attachments: Some(ids.clone()),
+                    attachment_descriptions: None,
+                    stickers: None,
+                    replies: None,
+                    embeds: None,
+                    masquerade: None,
+                    interactions: None,
+                    quote: None,
+                    language: None,
+                    if_last_message: None,
+                    send_at: None,
+                    expires_in: None,
+                    allowed_mentions: None,
+                    voice: None,
+                },
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("attachments".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            let attachments = message.attachments.unwrap_or_default();
+            assert_eq!(
+                ids,
+                attachments
+                    .iter()
+                    .map(|file| file.id.clone())
+                    .collect::<Vec<String>>()
+            );
+            assert!(attachments
+                .iter()
+                .all(|file| file.message_id.as_deref() == Some(message.id.as_str())));
+        });
+    }
+
+    #[async_std::test]
+    async fn duplicate_attachments() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                channel channel 3);
+
+            let file = File {
+                id: ulid::Ulid::new().to_string(),
+                tag: "attachments".to_string(),
+                filename: "file.txt".to_string(),
+                metadata: Default::default(),
+                content_type: "text/plain".to_string(),
+                size: 1,
+                description: None,
+                is_voice: false,
+                waveform: None,
+                duration: None,
+                deleted: None,
+                reported: None,
+                message_id: None,
+                user_id: None,
+                server_id: None,
+                object_id: None,
+            };
+
+            db.insert_attachment(&file).await.unwrap();
+
+            let author = owner.clone().into_self().await;
+            let error = Message::create_from_api(
+                &db,
+                channel,
+                DataMessageSend {
+                    nonce: None,
+                    content: None,
+                    attachments: Some(vec![file.id.clone(), file.id.clone()]),
+                    attachment_descriptions: None,
+                    stickers: None,
+                    replies: None,
+                    embeds: None,
+                    masquerade: None,
+                    interactions: None,
+                    quote: None,
+                    language: None,
+                    if_last_message: None,
+                    send_at: None,
+                    expires_in: None,
+                    allowed_mentions: None,
+                    voice: None,
+                },
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("duplicate_attachments".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(error.error_type, ErrorType::InvalidProperty));
+
+            // Attachments can only ever be claimed by a single parent
+            db.find_and_use_attachment(&file.id, "attachments", "message", "first")
+                .await
+                .unwrap();
+
+            assert!(db
+                .find_and_use_attachment(&file.id, "attachments", "message", "second")
+                .await
+                .is_err());
+        });
+    }
+
+    #[async_std::test]
+    async fn attachment_descriptions() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                channel channel 3);
+
+            let mut ids = vec![];
+            for i in 0..2 {
+                let file = File {
+                    id: ulid::Ulid::new().to_string(),
+                    tag: "attachments".to_string(),
+                    filename: format!("{i}.png"),
+                    metadata: Default::default(),
+                    content_type: "image/png".to_string(),
+                    size: i,
+                    description: None,
+                    is_voice: false,
+                    waveform: None,
+                    duration: None,
+                    deleted: None,
+                    reported: None,
+                    message_id: None,
+                    user_id: None,
+                    server_id: None,
+                    object_id: None,
+                };
+
+                db.insert_attachment(&file).await.unwrap();
+                ids.push(file.id);
+            }
+
+            let author = owner.clone().into_self().await;
+            let send = |descriptions: Vec<Option<String>>| DataMessageSend {
+                nonce: None,
+                content: None,
+                attachments: Some(ids.clone()),
+                attachment_descriptions: Some(descriptions),
+                stickers: None,
+                replies: None,
+                embeds: None,
+                masquerade: None,
+                interactions: None,
+                quote: None,
+                language: None,
+                if_last_message: None,
+                send_at: None,
+                expires_in: None,
+                allowed_mentions: None,
+                voice: None,
+            };
+
+            // Descriptions must each belong to an attachment
+            assert!(Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(vec![None, None, Some("Extra".to_string())]),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("extra".to_string()),
+                false,
+                true,
+            )
+            .await
+            .is_err());
+
+            assert!(Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(vec![Some(
+                    "a".repeat(MAX_ATTACHMENT_DESCRIPTION_LENGTH + 1)
+                )]),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("long".to_string()),
+                false,
+                true,
+            )
+            .await
+            .is_err());
+
+            let message = Message::create_from_api(
+                &db,
+                channel,
+                send(vec![Some("A cat asleep on a keyboard".to_string())]),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("descriptions".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            let attachments: Vec<v0::File> = db
+                .fetch_message(&message.id)
+                .await
+                .unwrap()
+                .attachments
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect();
+
+            assert_eq!(
+                vec![Some("A cat asleep on a keyboard".to_string()), None],
+                attachments
+                    .into_iter()
+                    .map(|file| file.description)
+                    .collect::<Vec<Option<String>>>()
+            );
+        });
+    }
+
+    #[async_std::test]
+    async fn voice_messages() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                channel channel 3);
+
+            let mut ids = vec![];
+            for metadata in [Metadata::Audio, Metadata::File] {
+                let file = File {
+                    id: ulid::Ulid::new().to_string(),
+                    tag: "attachments".to_string(),
+                    filename: "recording.ogg".to_string(),
+                    metadata,
+                    content_type: "audio/ogg".to_string(),
+                    size: 1,
+                    description: None,
+                    is_voice: false,
+                    waveform: None,
+                    duration: None,
+                    deleted: None,
+                    reported: None,
+                    message_id: None,
+                    user_id: None,
+                    server_id: None,
+                    object_id: None,
+                };
+
+                db.insert_attachment(&file).await.unwrap();
+                ids.push(file.id);
+            }
+
+            let author = owner.clone().into_self().await;
+            let send = |content: Option<&str>, attachments: Vec<String>| DataMessageSend {
+                nonce: None,
+                content: content.map(ToString::to_string),
+                attachments: Some(attachments),
+                attachment_descriptions: None,
+                stickers: None,
+                replies: None,
+                embeds: None,
+                masquerade: None,
+                interactions: None,
+                quote: None,
+                language: None,
+                if_last_message: None,
+                send_at: None,
+                expires_in: None,
+                allowed_mentions: None,
+                voice: Some(v0::VoiceMessage {
+                    waveform: vec![0, 128, 255],
+                    duration: 1500,
+                }),
+            };
+
+            // Voice messages must be a single audio attachment without any text
+            for (i, data) in [
+                send(Some("Hello"), vec![ids[0].clone()]),
+                send(None, ids.clone()),
+                send(None, vec![ids[1].clone()]),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                assert!(Message::create_from_api(
+                    &db,
+                    channel.clone(),
+                    data,
+                    MessageAuthor::User(&author),
+                    IdempotencyKey::unchecked_from_string(format!("voice{i}")),
+                    false,
+                    true,
+                )
+                .await
+                .is_err());
+            }
+
+            let message = Message::create_from_api(
+                &db,
+                channel,
+                send(Some(""), vec![ids[0].clone()]),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("voice".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            let attachment: v0::File = db
+                .fetch_message(&message.id)
+                .await
+                .unwrap()
+                .attachments
+                .unwrap_or_default()
+                .remove(0)
+                .into();
+
+            assert!(attachment.is_voice);
+            assert_eq!(Some(vec![0, 128, 255]), attachment.waveform);
+            assert_eq!(Some(1500), attachment.duration);
+        });
+    }
+
+    #[async_std::test]
+    async fn stickers() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                owner user 0
+                server server 4
+                channel channel 3);
+
+            let create = |server: String| Sticker {
+                id: ulid::Ulid::new().to_string(),
+                server,
+                creator_id: owner.id.clone(),
+                name: "sticker".to_string(),
+                animated: false,
+            };
+
+            let ours = create(server.id.clone());
+            let other = create(ulid::Ulid::new().to_string());
+            db.insert_sticker(&ours).await.unwrap();
+            db.insert_sticker(&other).await.unwrap();
+
+            let author = owner.clone().into_self().await;
+            let send = |stickers: Vec<String>| DataMessageSend {
+                nonce: None,
+                content: None,
+                attachments: None,
+                attachment_descriptions: None,
+                stickers: Some(stickers),
+                replies: None,
+                embeds: None,
+                masquerade: None,
+                interactions: None,
+                quote: None,
+                language: None,
+                if_last_message: None,
+                send_at: None,
+                expires_in: None,
+                allowed_mentions: None,
+                voice: None,
+            };
+
+            // Stickers must exist and belong to a server the author is in
+            for (i, stickers) in [vec![ulid::Ulid::new().to_string()], vec![other.id.clone()]]
+                .into_iter()
+                .enumerate()
+            {
+                assert!(matches!(
+                    Message::create_from_api(
+                        &db,
+                        channel.clone(),
+                        send(stickers),
+                        MessageAuthor::User(&author),
+                        IdempotencyKey::unchecked_from_string(format!("sticker{i}")),
+                        false,
+                        true,
+                    )
+                    .await
+                    .unwrap_err()
+                    .error_type,
+                    ErrorType::UnknownSticker
+                ));
+            }
+
+            let max = config().await.features.limits.default.message_stickers;
+            assert!(matches!(
+                Message::create_from_api(
+                    &db,
+                    channel.clone(),
+                    send((0..=max).map(|_| ulid::Ulid::new().to_string()).collect()),
+                    MessageAuthor::User(&author),
+                    IdempotencyKey::unchecked_from_string("stickers".to_string()),
+                    false,
+                    true,
+                )
+                .await
+                .unwrap_err()
+                .error_type,
+                ErrorType::TooManyStickers { max: limit } if limit == max
+            ));
+
+            // A message may consist of only stickers
+            let message = Message::create_from_api(
+                &db,
+                channel,
+                send(vec![ours.id.clone(), ours.id.clone()]),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("sticker".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(Some(vec![ours.id.clone()]), message.stickers);
+        });
+    }
+
+    #[async_std::test]
+    async fn suppress_embeds() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                member user 1
+                channel channel 3);
+
+            let text = Embed::Text(v0::Text {
+                icon_url: None,
+                url: None,
+                title: Some("Sent with the message".to_string()),
+                description: None,
+                media: None,
+                colour: None,
+            });
+
+            let preview = Embed::Image(v0::Image {
+                url: "https://example.com/cat.png".to_string(),
+                width: 1,
+                height: 1,
+                size: v0::ImageSize::Large,
+            });
+
+            let mut message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: owner.id.to_string(),
+                content: Some("https://example.com/cat.png".to_string()),
+                embeds: Some(vec![text.clone(), preview.clone()]),
+                ..Default::default()
+            };
+
+            db.insert_message(&message).await.unwrap();
+
+            let suppress = v0::MessageFlags::SuppressEmbeds as u32;
+            let permissions = PermissionValue::from(ChannelPermission::ViewChannel);
+
+            // Only the author may change flags without permission to manage messages
+            let error = message
+                .set_flags(&db, &channel, &permissions, suppress, &member.id)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                error.error_type,
+                ErrorType::MissingPermission { .. }
+            ));
+
+            let error = message
+                .set_flags(&db, &channel, &permissions, 2, &owner.id)
+                .await
+                .unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::InvalidProperty));
+
+            // Link previews are hidden but kept while suppressed
+            message
+                .set_flags(&db, &channel, &permissions, suppress, &owner.id)
+                .await
+                .unwrap();
+
+            let stored = db.fetch_message(&message.id).await.unwrap();
+            assert_eq!(suppress, stored.flags);
+            assert_eq!(Some(vec![text.clone(), preview]), stored.embeds);
+            assert_eq!(Some(vec![text.clone()]), v0::Message::from(stored).embeds);
+
+            // Lifting the suppression discards the old previews
+            message
+                .set_flags(&db, &channel, &permissions, 0, &owner.id)
+                .await
+                .unwrap();
+
+            let stored = db.fetch_message(&message.id).await.unwrap();
+            assert_eq!(0, stored.flags);
+            assert_eq!(Some(vec![text]), stored.embeds);
+        });
+    }
+
+    #[async_std::test]
+    async fn forward_message() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                member user 1
+                channel channel 3);
+
+            let file = File {
+                id: ulid::Ulid::new().to_string(),
+                tag: "attachments".to_string(),
+                filename: "cat.png".to_string(),
+                metadata: Default::default(),
+                content_type: "image/png".to_string(),
+                size: 1,
+                description: None,
+                is_voice: false,
+                waveform: None,
+                duration: None,
+                deleted: None,
+                reported: None,
+                message_id: None,
+                user_id: None,
+                server_id: None,
+                object_id: None,
+            };
+
+            db.insert_attachment(&file).await.unwrap();
+
+            let original = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: ulid::Ulid::new().to_string(),
+                author: owner.id.to_string(),
+                content: Some("Look at this cat".to_string()),
+                attachments: Some(vec![file.clone()]),
+                ..Default::default()
+            };
+
+            db.insert_message(&original).await.unwrap();
+
+            let reference = MessageReference {
+                channel: original.channel.to_string(),
+                message: original.id.to_string(),
+                author: owner.id.to_string(),
+            };
+
+            // Forwarded messages are sent by the forwarding user but credit the original author
+            let author = member.clone().into_self().await;
+            let forwarded = original.forward(&db, &channel, &author).await.unwrap();
+            assert_eq!(member.id, forwarded.author);
+            assert_eq!(original.content, forwarded.content);
+            assert_eq!(Some(reference.clone()), forwarded.forwarded_from);
+            assert_eq!(
+                vec![file.id.clone()],
+                forwarded
+                    .attachments
+                    .iter()
+                    .flatten()
+                    .map(|file| file.id.to_string())
+                    .collect::<Vec<String>>()
+            );
+
+            // Forwarding a forwarded message still credits the original
+            let again = forwarded.forward(&db, &channel, &author).await.unwrap();
+            assert_eq!(Some(reference), again.forwarded_from);
+
+            // Attachments stay with the original message
+            assert!(db.claim_expired_message(&forwarded.id).await.unwrap());
+            forwarded.expire(&db).await.unwrap();
+            assert!(db
+                .fetch_attachment("attachments", &file.id)
+                .await
+                .unwrap()
+                .deleted
+                .is_none());
+
+            // Messages without anything to show can not be forwarded
+            let empty = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: ulid::Ulid::new().to_string(),
+                author: owner.id.to_string(),
+                ..Default::default()
+            };
+
+            let error = empty.forward(&db, &channel, &author).await.unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::EmptyMessage));
+        });
+    }
+
+    #[async_std::test]
+    async fn forward_follows_target_limits() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                owner user 0
+                user user 2
+                channel channel 3);
+
+            let mut channel = channel;
+            channel.apply_options(PartialChannel {
+                slowmode_seconds: Some(60),
+                ..Default::default()
+            });
+
+            let original = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: ulid::Ulid::new().to_string(),
+                author: owner.id.to_string(),
+                content: Some("Forward me".to_string()),
+                ..Default::default()
+            };
+
+            // Forwards count towards slowmode like any other message
+            let author = user.clone().into_self().await;
+            original.forward(&db, &channel, &author).await.unwrap();
+
+            let error = original.forward(&db, &channel, &author).await.unwrap_err();
+            assert!(matches!(
+                error.error_type,
+                ErrorType::RateLimited { retry_after } if retry_after > 0
+            ));
+
+            // Content must fit within the target channel's length limit
+            let length = config()
+                .await
+                .features
+                .server_limits(0)
+                .message_length_for(&channel.id(), "TextChannel");
+
+            let long = Message {
+                content: Some("a".repeat(length + 1)),
+                ..original
+            };
+
+            let author = owner.clone().into_self().await;
+            let error = long.forward(&db, &channel, &author).await.unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::PayloadTooLarge));
+        });
+    }
+
+    #[async_std::test]
+    async fn quote_requires_read_access() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                channel channel 3);
+
+            let quoted = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: owner.id.to_string(),
+                content: Some("Quote me".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&quoted).await.unwrap();
+
+            let direct_message = Channel::DirectMessage {
+                id: ulid::Ulid::new().to_string(),
+                active: true,
+                recipients: vec![ulid::Ulid::new().to_string(), ulid::Ulid::new().to_string()],
+                last_message_id: None,
+                pins: None,
+            };
+
+            db.insert_channel(&direct_message).await.unwrap();
+
+            let hidden = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: direct_message.id(),
+                author: ulid::Ulid::new().to_string(),
+                content: Some("Secret".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&hidden).await.unwrap();
+
+            let author = owner.clone().into_self().await;
+            let send = |quote: &str| DataMessageSend {
+                nonce: None,
+                content: None,
+                attachments: None,
+                attachment_descriptions: None,
+                stickers: None,
+                replies: None,
+                embeds: None,
+                masquerade: None,
+                interactions: None,
+                quote: Some(quote.to_string()),
+                language: None,
+                if_last_message: None,
+                send_at: None,
+                expires_in: None,
+                allowed_mentions: None,
+                voice: None,
+            };
+
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(&quoted.id),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("quote".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            let Some(Embed::Text(embed)) = message.embeds.unwrap_or_default().pop() else {
+                panic!("expected quote embed");
+            };
+
+            assert_eq!(embed.description, Some("Quote me".to_string()));
+            assert!(embed.url.is_some_and(|url| url.ends_with(&format!(
+                "/{}/{}",
+                channel.id(),
+                quoted.id
+            ))));
+
+            assert!(Message::create_from_api(
+                &db,
+                channel,
+                send(&hidden.id),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("hidden_quote".to_string()),
+                false,
+                true,
+            )
+            .await
+            .is_err());
+        });
+    }
+
+    #[async_std::test]
+    async fn quote_requires_read_history() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                owner user 0
+                user user 2
+                channel channel 3);
+
+            let quoted = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: owner.id.to_string(),
+                content: Some("Earlier message".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&quoted).await.unwrap();
+
+            // Members can see the channel but not its history
+            let mut channel = channel;
+            channel.apply_options(PartialChannel {
+                default_permissions: Some(OverrideField {
+                    a: 0,
+                    d: ChannelPermission::ReadMessageHistory as i64,
+                }),
+                ..Default::default()
+            });
+
+            let author = user.clone().into_self().await;
+            let error = Message::create_from_api(
+                &db,
+                channel,
+                DataMessageSend {
+                    nonce: None,
+                    content: None,
+                    attachments: None,
+                    attachment_descriptions: None,
+                    stickers: None,
+                    replies: None,
+                    embeds: None,
+                    masquerade: None,
+                    interactions: None,
+                    quote: Some(quoted.id.to_string()),
+                    language: None,
+                    if_last_message: None,
+                    send_at: None,
+                    expires_in: None,
+                    allowed_mentions: None,
+                    voice: None,
+                },
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("history_quote".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(error.error_type, ErrorType::NotFound));
+        });
+    }
+
+    #[async_std::test]
+    async fn embed_colour() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                owner user 0
+                channel channel 3
+                server server 4);
+
+            let mut server = server;
+            server
+                .update(
+                    &db,
+                    PartialServer {
+                        embed_colour: Some("#ff0000".to_string()),
+                        ..Default::default()
+                    },
+                    vec![],
+                )
+                .await
+                .unwrap();
+
+            let author = owner.clone().into_self().await;
+            let send = |colour: Option<&str>| DataMessageSend {
+                nonce: None,
+                content: None,
+                attachments: None,
+                attachment_descriptions: None,
+                stickers: None,
+                replies: None,
+                embeds: Some(vec![v0::SendableEmbed {
+                    title: Some("Embed".to_string()),
+                    colour: colour.map(|colour| colour.to_string()),
+                    ..Default::default()
+                }]),
+                masquerade: None,
+                interactions: None,
+                quote: None,
+                language: None,
+                if_last_message: None,
+                send_at: None,
+                expires_in: None,
+                allowed_mentions: None,
+                voice: None,
+            };
+
+            // Server default applies unless the embed sets its own colour
+            let cases = [(None, "#ff0000"), (Some("var(--accent)"), "var(--accent)")];
+            for (colour, expected) in cases {
+                let message = Message::create_from_api(
+                    &db,
+                    channel.clone(),
+                    send(colour),
+                    MessageAuthor::User(&author),
+                    IdempotencyKey::unchecked_from_string(expected.to_string()),
+                    false,
+                    true,
+                )
+                .await
+                .unwrap();
+
+                let Some(Embed::Text(embed)) = message.embeds.unwrap_or_default().pop() else {
+                    panic!("expected text embed");
+                };
+
+                assert_eq!(embed.colour, Some(expected.to_string()));
+            }
+
+            let error = Message::create_from_api(
+                &db,
+                channel,
+                send(Some("red; background: url(x)")),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("invalid".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(error.error_type, ErrorType::InvalidProperty));
+        });
+    }
+
+    #[async_std::test]
+    async fn if_last_message() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                channel channel 3);
+
+            let author = owner.clone().into_self().await;
+            let send = |if_last_message: &str| DataMessageSend {
+                nonce: None,
+                content: Some("Status".to_string()),
+                attachments: None,
+                attachment_descriptions: None,
+                stickers: None,
+                replies: None,
+                embeds: None,
+                masquerade: None,
+                interactions: None,
+                quote: None,
+                language: None,
+                if_last_message: Some(if_last_message.to_string()),
+                send_at: None,
+                expires_in: None,
+                allowed_mentions: None,
+                voice: None,
+            };
+
+            let previous = ulid::Ulid::new().to_string();
+
+            // Nothing has been sent to the channel yet
+            let error = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(&previous),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("empty".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(error.error_type, ErrorType::LastMessageMismatch));
+
+            db.insert_message(&Message {
+                id: previous.clone(),
+                channel: channel.id(),
+                author: owner.id.clone(),
+                content: Some("Earlier".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(&previous),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("matching".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(message.content, Some("Status".to_string()));
+
+            // The channel object still names the old message as the latest
+            let mut channel = channel;
+            if let Channel::Group {
+                last_message_id, ..
+            } = &mut channel
+            {
+                last_message_id.replace(previous.clone());
+            }
+
+            let error = Message::create_from_api(
+                &db,
+                channel,
+                send(&previous),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("stale".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(error.error_type, ErrorType::LastMessageMismatch));
+        });
+    }
+
+    #[async_std::test]
+    async fn bulk_delete() {
+        database_test!(|db| async move {
+            let channel = ulid::Ulid::new().to_string();
+            let message = |channel: &str| Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.to_string(),
+                author: ulid::Ulid::new().to_string(),
+                content: Some("Spam".to_string()),
+                ..Default::default()
+            };
+
+            let first = message(&channel);
+            let second = message(&channel);
+            let elsewhere = message(&ulid::Ulid::new().to_string());
+            for message in [&first, &second, &elsewhere] {
+                db.insert_message(message).await.unwrap();
+            }
+
+            // Messages older than two weeks are listed in the error
+            let sent_at = SystemTime::now() - Duration::from_secs(15 * 24 * 60 * 60);
+            let old = ulid::Ulid::from_datetime(sent_at).to_string();
+
+            let error = Message::bulk_delete(
+                &db,
+                &channel,
+                vec![first.id.to_string(), old.to_string()],
+                "moderator",
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(
+                error.error_type,
+                ErrorType::MessagesTooOld { ids } if ids == vec![old]
+            ));
+
+            // Messages must all belong to the channel
+            let error = Message::bulk_delete(
+                &db,
+                &channel,
+                vec![first.id.to_string(), elsewhere.id.to_string()],
+                "moderator",
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(error.error_type, ErrorType::NotFound));
+            assert!(db.fetch_message(&first.id).await.is_ok());
+
+            Message::bulk_delete(
+                &db,
+                &channel,
+                vec![first.id.to_string(), second.id.to_string()],
+                "moderator",
+            )
+            .await
+            .unwrap();
+
+            assert!(db.fetch_message(&first.id).await.is_err());
+            assert!(db.fetch_message(&second.id).await.is_err());
+            assert!(db.fetch_message(&elsewhere.id).await.is_ok());
+        });
+    }
+
+    #[async_std::test]
+    async fn undo_delete() {
+        database_test!(|db| async move {
+            let message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: ulid::Ulid::new().to_string(),
+                author: ulid::Ulid::new().to_string(),
+                content: Some("Oops".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&message).await.unwrap();
+            message.clone().delete(&db, "user").await.unwrap();
+            assert!(db.fetch_message(&message.id).await.is_err());
+
+            // Only the user who deleted the message may restore it
+            assert!(
+                Message::undo_delete(&db, &message.channel, &message.id, "someone else")
+                    .await
+                    .is_err()
+            );
+
+            let restored = Message::undo_delete(&db, &message.channel, &message.id, "user")
+                .await
+                .unwrap();
+
+            assert_eq!(restored, message);
+            assert!(db.fetch_message(&message.id).await.is_ok());
+
+            // Once claimed for finalisation the deletion can no longer be undone
+            message.clone().delete(&db, "user").await.unwrap();
+            let later = Timestamp::from(SystemTime::now() + Duration::from_secs(60 * 60));
+            let due = db
+                .fetch_due_pending_message_deletions(later, 100)
+                .await
+                .unwrap();
+
+            assert_eq!(due.len(), 1);
+            assert!(db
+                .claim_pending_message_deletion(&message.id)
+                .await
+                .unwrap());
+            assert!(!db
+                .claim_pending_message_deletion(&message.id)
+                .await
+                .unwrap());
+            assert!(
+                Message::undo_delete(&db, &message.channel, &message.id, "user")
+                    .await
+                    .is_err()
+            );
+        });
+    }
+
+    #[async_std::test]
+    async fn edit_history() {
+        database_test!(|db| async move {
+            let mut message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: ulid::Ulid::new().to_string(),
+                author: ulid::Ulid::new().to_string(),
+                content: Some("Version 0".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&message).await.unwrap();
+
+            let limit = config().await.features.message_edit_history;
+            for version in 1..=limit + 2 {
+                message.record_revision(&db).await.unwrap();
+                message.content = Some(format!("Version {version}"));
+            }
+
+            // Only the latest revisions are kept, oldest first
+            let history = db.fetch_message(&message.id).await.unwrap().edit_history;
+            let contents: Vec<String> = history
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|revision| revision.content)
+                .collect();
+
+            assert_eq!(
+                (2..limit + 2)
+                    .map(|version| format!("Version {version}"))
+                    .collect::<Vec<String>>(),
+                contents
+            );
+        });
+    }
+
+    #[async_std::test]
+    async fn edit_message() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                channel channel 3);
+
+            let mut message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: owner.id.clone(),
+                content: Some("Original".to_string()),
+                embeds: Some(vec![Embed::None]),
+                ..Default::default()
+            };
+
+            db.insert_message(&message).await.unwrap();
+
+            message
+                .edit(
+                    &db,
+                    &channel,
+                    v0::DataEditMessage {
+                        content: Some("Edited ||spoiler||".to_string()),
+                        embeds: None,
+                        attachments: None,
+                    },
+                    &owner.id,
+                    false,
+                )
+                .await
+                .unwrap();
+
+            // Generated previews are dropped and the original is kept as a revision
+            let stored = db.fetch_message(&message.id).await.unwrap();
+            assert_eq!(Some("Edited ||spoiler||".to_string()), stored.content);
+            assert!(stored.has_spoilers);
+            assert!(stored.edited.is_some());
+            assert_eq!(Some(vec![]), stored.embeds);
+            assert_eq!(
+                Some("Original".to_string()),
+                stored.edit_history.unwrap_or_default()[0].content
+            );
+
+            // Edits may not leave the message empty
+            let mut message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: owner.id.clone(),
+                embeds: Some(vec![Embed::None]),
+                ..Default::default()
+            };
+
+            db.insert_message(&message).await.unwrap();
+
+            assert!(matches!(
+                message
+                    .edit(
+                        &db,
+                        &channel,
+                        v0::DataEditMessage {
+                            content: None,
+                            embeds: Some(vec![]),
+                            attachments: None,
+                        },
+                        &owner.id,
+                        false,
+                    )
+                    .await
+                    .unwrap_err()
+                    .error_type,
+                ErrorType::EmptyMessage
+            ));
+
+            // Replacement embeds are capped like new messages
+            let max = config().await.features.server_limits(0).message_embeds;
+            assert!(matches!(
+                message
+                    .edit(
+                        &db,
+                        &channel,
+                        v0::DataEditMessage {
+                            content: None,
+                            embeds: Some(vec![
+                                v0::SendableEmbed {
+                                    title: Some("Title".to_string()),
+                                    ..Default::default()
+                                };
+                                max + 1
+                            ]),
+                            attachments: None,
+                        },
+                        &owner.id,
+                        false,
+                    )
+                    .await
+                    .unwrap_err()
+                    .error_type,
+                ErrorType::TooManyEmbeds { .. }
+            ));
+        });
+    }
+
+    #[async_std::test]
+    async fn scheduled_messages() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                member user 1
+                channel channel 3);
+
+            let author = owner.clone().into_self().await;
+            let send = |send_at: SystemTime| DataMessageSend {
+                nonce: None,
+                content: Some("Event starting soon!".to_string()),
+                attachments: None,
+                attachment_descriptions: None,
+                stickers: None,
+                replies: None,
+                embeds: None,
+                masquerade: None,
+                interactions: None,
+                quote: None,
+                language: None,
+                if_last_message: None,
+                send_at: Some(Timestamp::from(send_at)),
+                expires_in: None,
+                allowed_mentions: None,
+                voice: None,
+            };
+
+            let hour = Duration::from_secs(60 * 60);
+
+            // Messages may not be scheduled too far ahead
+            let error = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(SystemTime::now() + hour * 24 * 31),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("scheduled_too_late".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap_err();
+
+            assert!(matches!(error.error_type, ErrorType::InvalidProperty));
+
+            // Messages scheduled in the past are sent immediately
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(SystemTime::now() - hour),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("scheduled_in_past".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert!(db.fetch_message(&message.id).await.is_ok());
+
+            // Messages scheduled in the future are held back until they are due
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(SystemTime::now() + hour),
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("scheduled_in_future".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            assert!(db.fetch_message(&message.id).await.is_err());
+            assert_eq!(
+                Message::fetch_including_scheduled(&db, &message.id)
+                    .await
+                    .unwrap()
+                    .id,
+                message.id
+            );
+
+            let scheduled = db.fetch_scheduled_messages(&channel.id()).await.unwrap();
+            assert_eq!(scheduled.len(), 1);
+            assert_eq!(scheduled[0].message.id, message.id);
+
+            assert!(db
+                .fetch_due_scheduled_messages(Timestamp::now_utc(), 100)
+                .await
+                .unwrap()
+                .is_empty());
+
+            let due = db
+                .fetch_due_scheduled_messages(Timestamp::from(SystemTime::now() + hour * 2), 100)
+                .await
+                .unwrap();
+
+            assert_eq!(due.len(), 1);
+
+            // Each message may only be claimed once
+            assert!(db.claim_scheduled_message(&message.id).await.unwrap());
+            assert!(!db.claim_scheduled_message(&message.id).await.unwrap());
+
+            // Sent messages sort by when they were actually sent
+            let sent = due.into_iter().next().unwrap().send(&db).await.unwrap();
+            assert!(sent.id > message.id);
+            assert!(db.fetch_message(&sent.id).await.is_ok());
+            assert!(db.fetch_message(&message.id).await.is_err());
+            assert!(db
+                .fetch_scheduled_messages(&channel.id())
+                .await
+                .unwrap()
+                .is_empty());
+
+            // Messages are dropped if their author has since left the channel
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                send(SystemTime::now() + hour),
+                MessageAuthor::User(&member.clone().into_self().await),
+                IdempotencyKey::unchecked_from_string("scheduled_then_left".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            db.remove_user_from_group(&channel.id(), &member.id)
+                .await
+                .unwrap();
+
+            let scheduled = db.fetch_scheduled_message(&message.id).await.unwrap();
+            assert!(db.claim_scheduled_message(&message.id).await.unwrap());
+            assert!(scheduled.send(&db).await.is_err());
+            assert!(db
+                .fetch_messages(MessageQuery {
+                    limit: Some(50),
+                    filter: MessageFilter {
+                        channel: Some(channel.id()),
+                        author: Some(member.id.clone()),
+                        ..Default::default()
+                    },
+                    time_period: MessageTimePeriod::Absolute {
+                        before: None,
+                        after: None,
+                        sort: None,
+                    },
+                })
+                .await
+                .unwrap()
+                .is_empty());
+        });
+    }
+
+    #[async_std::test]
+    async fn mentions_filter() {
+        database_test!(|db| async move {
+            let channel = ulid::Ulid::new().to_string();
+            let user = ulid::Ulid::new().to_string();
+
+            for mentions in [Some(vec![user.clone()]), None] {
+                db.insert_message(&Message {
+                    id: ulid::Ulid::new().to_string(),
+                    channel: channel.clone(),
+                    author: ulid::Ulid::new().to_string(),
+                    mentions,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            }
+
+            let messages = db
+                .fetch_messages(MessageQuery {
+                    limit: Some(50),
+                    filter: MessageFilter {
+                        channels: Some(vec![channel]),
+                        mentions: Some(user.clone()),
+                        ..Default::default()
+                    },
+                    time_period: MessageTimePeriod::Absolute {
+                        before: None,
+                        after: None,
+                        sort: None,
+                    },
+                })
+                .await
+                .unwrap();
+
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].mentions, Some(vec![user]));
+        });
+    }
+
+    #[async_std::test]
+    async fn has_filter() {
+        database_test!(|db| async move {
+            let channel = ulid::Ulid::new().to_string();
+            let attachment = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.clone(),
+                attachments: Some(vec![File {
+                    id: ulid::Ulid::new().to_string(),
+                    tag: "attachments".to_string(),
+                    filename: "file.txt".to_string(),
+                    metadata: Default::default(),
+                    content_type: "text/plain".to_string(),
+                    size: 0,
+                    description: None,
+                    is_voice: false,
+                    waveform: None,
+                    duration: None,
+                    deleted: None,
+                    reported: None,
+                    message_id: None,
+                    user_id: None,
+                    server_id: None,
+                    object_id: None,
+                }]),
+                ..Default::default()
+            };
+
+            let link = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.clone(),
+                content: Some("see https://example.com".to_string()),
+                embeds: Some(vec![Embed::None]),
+                ..Default::default()
+            };
+
+            let reaction = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.clone(),
+                content: Some("no links here".to_string()),
+                reactions: IndexMap::from([("👍".to_string(), IndexSet::from(["a".to_string()]))]),
+                pinned: Some(true),
+                ..Default::default()
+            };
+
+            for message in [&attachment, &link, &reaction] {
+                db.insert_message(message).await.unwrap();
+            }
+
+            let query = |has: Option<Vec<MessageHas>>, pinned: Option<bool>| MessageQuery {
+                limit: Some(50),
+                filter: MessageFilter {
+                    channel: Some(channel.clone()),
+                    has,
+                    pinned,
+                    ..Default::default()
+                },
+                time_period: MessageTimePeriod::Absolute {
+                    before: None,
+                    after: None,
+                    sort: None,
+                },
+            };
+
+            let messages = db
+                .fetch_messages(query(Some(vec![MessageHas::Attachment]), None))
+                .await
+                .unwrap();
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].id, attachment.id);
+
+            // All requested content must be present
+            let messages = db
+                .fetch_messages(query(Some(vec![MessageHas::Embed, MessageHas::Link]), None))
+                .await
+                .unwrap();
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].id, link.id);
+
+            let messages = db
+                .fetch_messages(query(
+                    Some(vec![MessageHas::Attachment, MessageHas::Link]),
+                    None,
+                ))
+                .await
+                .unwrap();
+            assert!(messages.is_empty());
+
+            let messages = db
+                .fetch_messages(query(Some(vec![MessageHas::Reaction]), Some(true)))
+                .await
+                .unwrap();
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].id, reaction.id);
+
+            let messages = db.fetch_messages(query(None, Some(false))).await.unwrap();
+            assert_eq!(messages.len(), 2);
+        });
+    }
+
+    #[async_std::test]
+    async fn reaction_leaderboard() {
+        database_test!(|db| async move {
+            let channel = ulid::Ulid::new().to_string();
+            let reactions = [
+                ("👍", vec!["a", "b", "c"]),
+                ("🎉", vec!["a"]),
+                ("👍", vec!["a", "d"]),
+            ];
+
+            for (emoji, users) in reactions {
+                let message = Message {
+                    id: ulid::Ulid::new().to_string(),
+                    channel: channel.to_string(),
+                    author: ulid::Ulid::new().to_string(),
+                    reactions: IndexMap::from([(
+                        emoji.to_string(),
+                        users.into_iter().map(|user| user.to_string()).collect(),
+                    )]),
+                    ..Default::default()
+                };
+
+                db.insert_message(&message).await.unwrap();
+            }
+
+            let since = SystemTime::now() - Duration::from_secs(60);
+            let leaderboard = db
+                .fetch_reaction_leaderboard(&[channel], since, 100, 10)
+                .await
+                .unwrap();
+
+            assert_eq!(leaderboard.len(), 2);
+            assert_eq!(leaderboard[0].emoji, "👍");
+            assert_eq!(leaderboard[0].count, 5);
+            assert_eq!(leaderboard[0].users, 4);
+            assert_eq!(leaderboard[1].emoji, "🎉");
+            assert_eq!(leaderboard[1].count, 1);
+        });
+    }
+
+    #[async_std::test]
+    async fn message_context() {
+        database_test!(|db| async move {
+            let channel = ulid::Ulid::new().to_string();
+
+            let mut ids = vec![];
+            for _ in 0..7 {
+                let message = Message {
+                    id: ulid::Ulid::new().to_string(),
+                    channel: channel.to_string(),
+                    author: ulid::Ulid::new().to_string(),
+                    content: Some("Hello!".to_string()),
+                    ..Default::default()
+                };
+
+                db.insert_message(&message).await.unwrap();
+                ids.push(message.id);
+            }
+
+            // Ids generated within the same millisecond are not ordered
+            ids.sort();
+
+            let context = db
+                .fetch_message_context(&channel, &ids[3], 2)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|message| message.id)
+                .collect::<Vec<String>>();
+
+            assert_eq!(context, ids[1..6]);
+
+            // Messages near the start of the channel have less context
+            let context = db
+                .fetch_message_context(&channel, &ids[0], 2)
+                .await
+                .unwrap();
+            assert_eq!(context.len(), 3);
+            assert_eq!(context[0].id, ids[0]);
+
+            // Messages from other channels are not found
+            assert!(db
+                .fetch_message_context(&ulid::Ulid::new().to_string(), &ids[0], 2)
+                .await
+                .is_err());
+        });
+    }
+
+    #[async_std::test]
+    async fn thread_summary() {
+        database_test!(|db| async move {
+            let root = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: "channel".to_string(),
+                author: "author".to_string(),
+                content: Some("Root".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&root).await.unwrap();
+
+            let mut replies = vec![];
+            for author in ["alice", "bob", "alice"] {
+                let reply = Message {
+                    id: ulid::Ulid::new().to_string(),
+                    channel: "channel".to_string(),
+                    author: author.to_string(),
+                    content: Some("Reply".to_string()),
+                    replies: Some(vec![root.id.clone()]),
+                    ..Default::default()
+                };
+
+                db.insert_message(&reply).await.unwrap();
+                reply.count_reply(&db).await;
+                replies.push(reply);
+            }
+
+            let message = db.fetch_message(&root.id).await.unwrap();
+            assert_eq!(message.reply_count, 3);
+            assert_eq!(message.thread_participant_count, 2);
+
+            // Alice still has a reply left
+            db.delete_message(&replies[0].id).await.unwrap();
+            replies[0].discount_reply(&db).await;
+
+            let message = db.fetch_message(&root.id).await.unwrap();
+            assert_eq!(message.reply_count, 2);
+            assert_eq!(message.thread_participant_count, 2);
+
+            // Bob no longer participates
+            db.delete_message(&replies[1].id).await.unwrap();
+            replies[1].discount_reply(&db).await;
+
+            let message = db.fetch_message(&root.id).await.unwrap();
+            assert_eq!(message.reply_count, 1);
+            assert_eq!(message.thread_participant_count, 1);
+        });
+    }
+
+    #[async_std::test]
+    async fn system_push_notification() {
+        let message: v0::Message = Message {
+            id: ulid::Ulid::new().to_string(),
+            channel: "channel".to_string(),
+            author: "00000000000000000000000000".to_string(),
+            system: Some(SystemMessage::UserBanned {
+                id: "alice".to_string(),
+                by: Some("bob".to_string()),
+            }),
+            ..Default::default()
+        }
+        .into();
+
+        let notification = PushNotification::from(
+            &message,
+            Some(MessageAuthor::System {
+                username: "Alice",
+                avatar: None,
+                by: Some("Bob".to_string()),
+            }),
+            "channel",
+            None,
+            None,
+            None,
+        )
+        .await;
+
+        assert_eq!(notification.author, "System");
+        assert_eq!(notification.body, "Alice was banned by Bob");
+    }
+
+    #[async_std::test]
+    async fn idempotent_retries() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                channel channel 3);
+
+            let author = owner.clone().into_self().await;
+            let send = |content: &str, key: &str| {
+                Message::create_from_api(
+                    &db,
+                    channel.clone(),
+                    DataMessageSend {
+                        nonce: None,
+                        content: Some(content.to_string()),
+                        attachments: None,
+                        attachment_descriptions: None,
+                        stickers: None,
+                        replies: None,
+                        embeds: None,
+                        masquerade: None,
+                        interactions: None,
+                        quote: None,
+                        language: None,
+                        if_last_message: None,
+                        send_at: None,
+                        expires_in: None,
+                        allowed_mentions: None,
+                        voice: None,
+                    },
+                    MessageAuthor::User(&author),
+                    IdempotencyKey::from_string(key.to_string()),
+                    false,
+                    true,
+                )
+            };
+
+            // Racing duplicates only send the message once
+            let (first, second) = futures::join!(
+                send("Hello!", "idempotent_retry"),
+                send("Hello!", "idempotent_retry")
+            );
+
+            let first = first.unwrap();
+            assert_eq!(first.id, second.unwrap().id);
+
+            // Later retries are given the original message
+            let retry = send("Hello!", "idempotent_retry").await.unwrap();
+            assert_eq!(first.id, retry.id);
+
+            let messages = db
+                .fetch_messages(MessageQuery {
+                    limit: None,
+                    filter: MessageFilter {
+                        channel: Some(channel.id()),
+                        ..Default::default()
+                    },
+                    time_period: MessageTimePeriod::Absolute {
+                        before: None,
+                        after: None,
+                        sort: None,
+                    },
+                })
+                .await
+                .unwrap();
+
+            assert_eq!(1, messages.len());
+
+            // Reusing the key for a different message is a conflict
+            assert!(matches!(
+                send("Goodbye!", "idempotent_retry")
+                    .await
+                    .unwrap_err()
+                    .error_type,
+                ErrorType::DuplicateNonce
+            ));
+
+            // Failed sends release the key so they can be retried
+            assert!(send(&"a".repeat(10_000), "idempotent_failed")
+                .await
+                .is_err());
+            assert!(send("Not too long", "idempotent_failed").await.is_ok());
+        });
+    }
+
+    #[async_std::test]
+    async fn notification_levels() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                member user 1
+                other user 2
+                channel channel 3);
+
+            ChannelNotificationPreference::set_level(
+                &db,
+                &channel,
+                &member.id,
+                NotificationLevel::Muted,
+            )
+            .await
+            .unwrap();
+
+            ChannelNotificationPreference::set_level(
+                &db,
+                &channel,
+                &other.id,
+                NotificationLevel::Nothing,
+            )
+            .await
+            .unwrap();
+
+            let message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: owner.id.clone(),
+                content: Some("Hello!".to_string()),
+                mentions: Some(vec![member.id.clone(), other.id.clone()]),
+                ..Default::default()
+            };
+
+            let author = owner.clone().into_self().await;
+            let converted: v0::Message = message.clone().into();
+
+            // Neither muted users nor users who opted out are pushed, even when mentioned
+            let (recipients, _) = message
+                .push_notification(
+                    &db,
+                    &converted,
+                    MessageAuthor::User(&author),
+                    &channel,
+                    &ChannelNotificationPreference::fetch_levels(&db, &channel.id())
+                        .await
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert!(recipients.contains(&owner.id));
+            assert!(!recipients.contains(&member.id));
+            assert!(!recipients.contains(&other.id));
+        });
+    }
+
+    #[async_std::test]
+    async fn push_reply_snippet() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                member user 1
+                channel channel 3);
+
+            let elsewhere = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: ulid::Ulid::new().to_string(),
+                author: owner.id.clone(),
+                content: Some("Private".to_string()),
+                ..Default::default()
+            };
+
+            let here = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: owner.id.clone(),
+                content: Some("Public".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&elsewhere).await.unwrap();
+            db.insert_message(&here).await.unwrap();
+
+            // Replies to messages in other channels never leak into the snippet
+            let message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: member.id.clone(),
+                content: Some("Replying".to_string()),
+                replies: Some(vec![here.id.clone(), elsewhere.id.clone()]),
+                ..Default::default()
+            };
+
+            let author = member.clone().into_self().await;
+            let converted: v0::Message = message.clone().into();
+            let (_, notification) = message
+                .push_notification(
+                    &db,
+                    &converted,
+                    MessageAuthor::User(&author),
+                    &channel,
+                    &ChannelNotificationPreference::fetch_levels(&db, &channel.id())
+                        .await
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(
+                notification.reply.map(|reply| reply.snippet),
+                Some("Public".to_string())
+            );
+        });
+    }
+
+    #[async_std::test]
+    async fn push_mention_only() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                owner user 0
+                moderator user 1
+                server server 4
+                channel channel 3);
+
+            ChannelNotificationPreference::set_level(
+                &db,
+                &channel,
+                &moderator.id,
+                NotificationLevel::All,
+            )
+            .await
+            .unwrap();
+
+            let author = owner.clone().into_self().await;
+            let message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: owner.id.clone(),
+                content: Some("Hello!".to_string()),
+                ..Default::default()
+            };
+
+            let converted: v0::Message = message.clone().into();
+            let (recipients, _) = message
+                .push_notification(
+                    &db,
+                    &converted,
+                    MessageAuthor::User(&author),
+                    &channel,
+                    &ChannelNotificationPreference::fetch_levels(&db, &channel.id())
+                        .await
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert!(recipients.contains(&moderator.id));
+
+            // Reaching the threshold overrides the moderator's preference
+            let threshold = config().await.features.push_mention_only_threshold;
+            Channel::set_cached_member_count(&server.id, threshold).await;
+            assert!(channel.is_push_mention_only(&db).await);
+            assert!(message
+                .push_notification(
+                    &db,
+                    &converted,
+                    MessageAuthor::User(&author),
+                    &channel,
+                    &ChannelNotificationPreference::fetch_levels(&db, &channel.id())
+                        .await
+                        .unwrap(),
+                )
+                .await
+                .is_none());
+
+            // Mentions are still pushed
+            let message = Message {
+                id: ulid::Ulid::new().to_string(),
+                mentions: Some(vec![moderator.id.clone()]),
+                ..message
+            };
+
+            let converted: v0::Message = message.clone().into();
+            let (recipients, _) = message
+                .push_notification(
+                    &db,
+                    &converted,
+                    MessageAuthor::User(&author),
+                    &channel,
+                    &ChannelNotificationPreference::fetch_levels(&db, &channel.id())
+                        .await
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(recipients, vec![moderator.id.clone()]);
+        });
+    }
+
+    #[async_std::test]
+    async fn reaction_rate_limit() {
+        database_test!(|db| async move {
+            let message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: ulid::Ulid::new().to_string(),
+                author: ulid::Ulid::new().to_string(),
+                content: Some("React quickly!".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&message).await.unwrap();
+
+            let user = ulid::Ulid::new().to_string();
+            let limit = config().await.features.reaction_rate_limit;
+            for _ in 0..limit {
+                message.add_reaction(&db, &user, "👍").await.unwrap();
+            }
+
+            let error = message.add_reaction(&db, &user, "👍").await.unwrap_err();
+            assert!(matches!(
+                error.error_type,
+                ErrorType::RateLimited { retry_after } if retry_after > 0
+            ));
+
+            // Other users are not affected
+            message
+                .add_reaction(&db, &ulid::Ulid::new().to_string(), "👍")
+                .await
+                .unwrap();
+        });
+    }
+
+    #[async_std::test]
+    async fn reaction_limits() {
+        database_test!(|db| async move {
+            let limits = config().await.features.limits.default;
+            let user = ulid::Ulid::new().to_string();
+            let create = |reactions: IndexMap<String, IndexSet<String>>| Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: ulid::Ulid::new().to_string(),
+                author: ulid::Ulid::new().to_string(),
+                content: Some("React to me!".to_string()),
+                reactions,
+                ..Default::default()
+            };
+
+            // Users may only add so many reactions to a message
+            let mut message = create(
+                (0..limits.message_reactions_per_user)
+                    .map(|i| (format!("emoji{i}"), IndexSet::from([user.clone()])))
+                    .collect(),
+            );
+
+            db.insert_message(&message).await.unwrap();
+
+            let error = message.add_reaction(&db, &user, "👍").await.unwrap_err();
+            assert!(matches!(
+                error.error_type,
+                ErrorType::TooManyReactions { max } if max == limits.message_reactions_per_user
+            ));
+
+            message
+                .add_reaction(&db, &ulid::Ulid::new().to_string(), "👍")
+                .await
+                .unwrap();
+
+            // Messages may only have so many distinct reactions
+            let full = create(
+                (0..limits.message_reactions)
+                    .map(|i| {
+                        (
+                            format!("emoji{i}"),
+                            IndexSet::from([ulid::Ulid::new().to_string()]),
+                        )
+                    })
+                    .collect(),
+            );
+
+            let error = full
+                .add_reaction(&db, &ulid::Ulid::new().to_string(), "👍")
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                error.error_type,
+                ErrorType::TooManyReactions { max } if max == limits.message_reactions
+            ));
+
+            // Removing all reactions requires permission to manage messages
+            let error = message
+                .remove_all_reactions(&db, &PermissionValue::from(ChannelPermission::ViewChannel))
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                error.error_type,
+                ErrorType::MissingPermission { .. }
+            ));
+
+            message
+                .remove_all_reactions(
+                    &db,
+                    &PermissionValue::from(ChannelPermission::ManageMessages),
+                )
+                .await
+                .unwrap();
+
+            assert!(message.reactions.is_empty());
+            assert!(db
+                .fetch_message(&message.id)
+                .await
+                .unwrap()
+                .reactions
+                .is_empty());
+        });
+    }
+
+    #[async_std::test]
+    async fn restricted_reactions() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                member user 1
+                channel channel 3);
+
+            let author = owner.clone().into_self().await;
+            let message = Message::create_from_api(
+                &db,
+                channel,
+                DataMessageSend {
+                    nonce: None,
+                    content: Some("Yes or no?".to_string()),
+                    attachments: None,
+                    attachment_descriptions: None,
+                    stickers: None,
+                    replies: None,
+                    embeds: None,
+                    masquerade: None,
+                    interactions: Some(v0::Interactions {
+                        reactions: Some(IndexSet::from(["👍".to_string(), "👎".to_string()])),
+                        restrict_reactions: true,
+                        reaction_cooldowns: None,
+                    }),
+                    quote: None,
+                    language: None,
+                    if_last_message: None,
+                    send_at: None,
+                    expires_in: None,
+                    allowed_mentions: None,
+                    voice: None,
+                },
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("restricted_reactions".to_string()),
+                false,
+                false,
+            )
+            .await
+            .unwrap();
+
+            // Interaction-defined reactions are shown before anyone reacts
+            assert_eq!(
+                vec!["👍", "👎"],
+                message
+                    .reactions
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<&str>>()
+            );
+            assert!(message.reactions.values().all(IndexSet::is_empty));
+
+            // Only the defined reactions may be added
+            let error = message
+                .add_reaction(&db, &member.id, "🎉")
+                .await
+                .unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::InvalidOperation));
+
+            message.add_reaction(&db, &member.id, "👍").await.unwrap();
+            assert!(
+                db.fetch_message(&message.id).await.unwrap().reactions["👍"].contains(&member.id)
+            );
+
+            // Interaction-defined reactions stay once their last user is removed
+            let message = db.fetch_message(&message.id).await.unwrap();
+            message
+                .remove_reaction(&db, &member.id, "👍")
+                .await
+                .unwrap();
+
+            let mut message = db.fetch_message(&message.id).await.unwrap();
+            assert!(message.reactions["👍"].is_empty());
+
+            message.add_reaction(&db, &member.id, "👎").await.unwrap();
+            message
+                .remove_all_reactions(
+                    &db,
+                    &PermissionValue::from(ChannelPermission::ManageMessages),
+                )
+                .await
+                .unwrap();
+
+            let message = db.fetch_message(&message.id).await.unwrap();
+            assert_eq!(
+                vec!["👍", "👎"],
+                message
+                    .reactions
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<&str>>()
+            );
+            assert!(message.reactions.values().all(IndexSet::is_empty));
+        });
+    }
+
+    #[async_std::test]
+    async fn reaction_cooldown() {
+        database_test!(|db| async move {
+            let message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: ulid::Ulid::new().to_string(),
+                author: ulid::Ulid::new().to_string(),
+                content: Some("Vote once per minute!".to_string()),
+                interactions: Interactions {
+                    reaction_cooldowns: Some(IndexMap::from([("👍".to_string(), 60)])),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            db.insert_message(&message).await.unwrap();
+
+            let user = ulid::Ulid::new().to_string();
+            message.add_reaction(&db, &user, "👍").await.unwrap();
+            message.remove_reaction(&db, &user, "👍").await.unwrap();
+
+            let error = message.add_reaction(&db, &user, "👍").await.unwrap_err();
+            assert!(matches!(
+                error.error_type,
+                ErrorType::RateLimited { retry_after } if retry_after > 0
+            ));
+
+            // The cooldown is recorded in the database so every node sees it
+            assert!(db
+                .claim_reaction_cooldown(&message.id, "👍", &user, Duration::from_secs(60))
+                .await
+                .unwrap()
+                .is_some());
+
+            // Reactions without a cooldown are unaffected
+            message.add_reaction(&db, &user, "👎").await.unwrap();
+        });
+    }
+
+    #[async_std::test]
+    async fn pin_limit() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                channel channel 3);
+
+            let mut message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: owner.id.to_string(),
+                content: Some("Pin me".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&message).await.unwrap();
+
+            // Pinning requires permission to manage messages
+            let error = message
+                .set_pinned(
+                    &db,
+                    &channel,
+                    &PermissionValue::from(ChannelPermission::ViewChannel),
+                    true,
+                    &owner.id,
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                error.error_type,
+                ErrorType::MissingPermission { .. }
+            ));
+
+            let permissions = PermissionValue::from(ChannelPermission::ManageMessages);
+            message
+                .set_pinned(&db, &channel, &permissions, true, &owner.id)
+                .await
+                .unwrap();
+
+            assert_eq!(
+                Some(true),
+                db.fetch_message(&message.id).await.unwrap().pinned
+            );
+
+            // Pins are counted on the channel
+            let pins = db.fetch_channel(&channel.id()).await.unwrap().pins();
+            assert_eq!(pins.count, 1);
+            assert_eq!(pins.last_pinned_at, message.pinned_at);
+            assert!(pins.last_pinned_at.is_some());
+
+            // Pin changes are announced in the channel
+            let announcements = db
+                .fetch_messages(MessageQuery {
+                    limit: Some(50),
+                    filter: MessageFilter {
+                        channel: Some(channel.id()),
+                        ..Default::default()
+                    },
+                    time_period: MessageTimePeriod::Absolute {
+                        before: None,
+                        after: None,
+                        sort: None,
+                    },
+                })
+                .await
+                .unwrap();
+
+            assert!(announcements.iter().any(|announcement| matches!(
+                &announcement.system,
+                Some(SystemMessage::MessagePinned { id, by }) if id == &message.id && by == &owner.id
+            )));
+
+            // Fill up the remaining pins
+            let max = config().await.features.limits.default.channel_pins;
+            for _ in 1..max {
+                let pinned_at = Timestamp::now_utc();
+                db.insert_message(&Message {
+                    id: ulid::Ulid::new().to_string(),
+                    channel: channel.id(),
+                    author: owner.id.to_string(),
+                    pinned: Some(true),
+                    pinned_at: Some(pinned_at),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+
+                assert!(db
+                    .add_channel_pin(&channel.id(), pinned_at, max)
+                    .await
+                    .unwrap());
+            }
+
+            let mut another = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: owner.id.to_string(),
+                content: Some("One too many".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&another).await.unwrap();
+
+            let error = another
+                .set_pinned(&db, &channel, &permissions, true, &owner.id)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                error.error_type,
+                ErrorType::TooManyPins { max: limit } if limit == max
+            ));
+
+            // Unpinning frees up space for another pin
+            message
+                .set_pinned(&db, &channel, &permissions, false, &owner.id)
+                .await
+                .unwrap();
+
+            another
+                .set_pinned(&db, &channel, &permissions, true, &owner.id)
+                .await
+                .unwrap();
+
+            assert_eq!(
+                max,
+                db.fetch_channel(&channel.id()).await.unwrap().pins().count
+            );
+
+            // Deleted pins no longer count towards the channel
+            another.delete_now(&db, &owner.id).await.unwrap();
+            assert_eq!(
+                max - 1,
+                db.fetch_channel(&channel.id()).await.unwrap().pins().count
+            );
+        });
+    }
+
+    #[async_std::test]
+    async fn poll_votes() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                member user 1
+                channel channel 3);
+
+            let mut message = Message::create_poll(
+                &db,
+                &channel,
+                v0::DataCreatePoll {
+                    question: "Best colour?".to_string(),
+                    options: vec!["Red".to_string(), "Blue".to_string()],
+                    multi_select: false,
+                    closes_at: None,
+                },
+                &owner.id,
+            )
+            .await
+            .unwrap();
+
+            assert!(matches!(
+                &message.system,
+                Some(SystemMessage::Poll { by, .. }) if by == &owner.id
+            ));
+
+            let voters = |message: &Message, option: usize| {
+                message.poll.as_ref().unwrap().options[option]
+                    .voters
+                    .clone()
+            };
+
+            message.vote_poll(&db, 0, &owner.id).await.unwrap();
+            message.vote_poll(&db, 0, &member.id).await.unwrap();
+            assert_eq!(
+                IndexSet::from([owner.id.clone(), member.id.clone()]),
+                voters(&message, 0)
+            );
+
+            // Single-select polls move the vote to the new option
+            message.vote_poll(&db, 1, &member.id).await.unwrap();
+            assert_eq!(IndexSet::from([owner.id.clone()]), voters(&message, 0));
+            assert_eq!(IndexSet::from([member.id.clone()]), voters(&message, 1));
+
+            // Voting for the same option again removes the vote
+            message.vote_poll(&db, 0, &owner.id).await.unwrap();
+            assert!(voters(&message, 0).is_empty());
+
+            let stored = db.fetch_message(&message.id).await.unwrap();
+            assert_eq!(IndexSet::from([member.id.clone()]), voters(&stored, 1));
+
+            let error = message.vote_poll(&db, 2, &owner.id).await.unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::InvalidProperty));
+
+            // Votes are rejected once the poll has closed
+            let closes_at = Timestamp::from(SystemTime::now() - Duration::from_secs(1));
+            message.poll.as_mut().unwrap().closes_at = Some(closes_at);
+
+            let error = message.vote_poll(&db, 1, &owner.id).await.unwrap_err();
+            assert!(matches!(error.error_type, ErrorType::PollClosed));
+        });
+    }
+
+    #[async_std::test]
+    async fn expiring_messages() {
+        database_test!(|db| async move {
+            fixture!(db, "group_with_members",
+                owner user 0
+                channel channel 3);
+
+            let author = owner.clone().into_self().await;
+            let message = Message::create_from_api(
+                &db,
+                channel.clone(),
+                DataMessageSend {
+                    nonce: None,
+                    content: Some("This message will self-destruct".to_string()),
+                    attachments: None,
+                    attachment_descriptions: None,
+                    stickers: None,
+                    replies: None,
+                    embeds: None,
+                    masquerade: None,
+                    interactions: None,
+                    quote: None,
+                    language: None,
+                    if_last_message: None,
+                    send_at: None,
+                    expires_in: Some(60),
+                    allowed_mentions: None,
+                    voice: None,
+                },
+                MessageAuthor::User(&author),
+                IdempotencyKey::unchecked_from_string("expiring_message".to_string()),
+                false,
+                true,
+            )
+            .await
+            .unwrap();
+
+            let expires_at = db.fetch_message(&message.id).await.unwrap().expires_at;
+            assert!(expires_at.is_some_and(|expires_at| {
+                expires_at > Timestamp::now_utc()
+                    && expires_at <= Timestamp::from(SystemTime::now() + Duration::from_secs(60))
+            }));
+
+            // Nothing has expired yet
+            assert!(db
+                .fetch_expired_messages(Timestamp::now_utc(), 10)
+                .await
+                .unwrap()
+                .is_empty());
+
+            let later = Timestamp::from(SystemTime::now() + Duration::from_secs(61));
+            let expired = db.fetch_expired_messages(later, 10).await.unwrap();
+            assert_eq!(1, expired.len());
+
+            for message in expired {
+                assert!(db.claim_expired_message(&message.id).await.unwrap());
+                message.expire(&db).await.unwrap();
+            }
+
+            assert!(db.fetch_message(&message.id).await.is_err());
+
+            // Only one worker may claim an expired message
+            assert!(!db.claim_expired_message(&message.id).await.unwrap());
+        });
     }
 }
@@ -1,6 +1,12 @@
+use std::time::{Duration, SystemTime};
+
+use iso8601_timestamp::Timestamp;
 use onechatsocial_result::Result;
 
-use crate::{AppendMessage, Message, MessageQuery, PartialMessage};
+use crate::{
+    AppendMessage, EmojiReactionCount, FieldsMessage, Message, MessageQuery, MessageRevision,
+    PartialMessage,
+};
 
 mod mongodb;
 mod reference;
@@ -16,8 +22,37 @@ pub trait AbstractMessages: Sync + Send {
     /// Fetch multiple messages by given query
     async fn fetch_messages(&self, query: MessageQuery) -> Result<Vec<Message>>;
 
+    /// Fetch a message along with up to `radius` messages either side of it
+    async fn fetch_message_context(
+        &self,
+        channel: &str,
+        id: &str,
+        radius: i64,
+    ) -> Result<Vec<Message>>;
+
+    /// Fetch messages which expired at or before the given time
+    async fn fetch_expired_messages(&self, now: Timestamp, limit: i64) -> Result<Vec<Message>>;
+
+    /// Claim an expired message by removing it, returns whether this call removed it
+    async fn claim_expired_message(&self, id: &str) -> Result<bool>;
+
     /// Update a given message with new information
-    async fn update_message(&self, id: &str, message: &PartialMessage) -> Result<()>;
+    async fn update_message(
+        &self,
+        id: &str,
+        message: &PartialMessage,
+        remove: Vec<FieldsMessage>,
+    ) -> Result<()>;
+
+    /// Add a previous version to a message's edit history
+    ///
+    /// Only the latest `limit` revisions are kept, the oldest are dropped first.
+    async fn push_message_revision(
+        &self,
+        id: &str,
+        revision: &MessageRevision,
+        limit: usize,
+    ) -> Result<()>;
 
     /// Append information to a given message
     async fn append_message(&self, id: &str, append: &AppendMessage) -> Result<()>;
@@ -28,12 +63,64 @@ pub trait AbstractMessages: Sync + Send {
     /// Remove a reaction from a message
     async fn remove_reaction(&self, id: &str, emoji: &str, user: &str) -> Result<()>;
 
+    /// Record that a user is adding a reaction which has a cooldown
+    ///
+    /// Returns how long is left instead if the user last added it within the cooldown.
+    async fn claim_reaction_cooldown(
+        &self,
+        id: &str,
+        emoji: &str,
+        user: &str,
+        cooldown: Duration,
+    ) -> Result<Option<Duration>>;
+
     /// Remove reaction from a message
     async fn clear_reaction(&self, id: &str, emoji: &str) -> Result<()>;
 
+    /// Remove all users from a reaction while keeping it on a message
+    async fn reset_reaction(&self, id: &str, emoji: &str) -> Result<()>;
+
+    /// Remove all reactions from a message
+    ///
+    /// Any emoji in `keep` are left on the message without any users.
+    async fn clear_all_reactions(&self, id: &str, keep: &[String]) -> Result<()>;
+
+    /// Add a user's vote to an option of a message's poll
+    ///
+    /// If `exclusive` is set, the user's votes for any other option are removed in the same update.
+    async fn add_poll_vote(
+        &self,
+        id: &str,
+        option: usize,
+        user: &str,
+        exclusive: bool,
+    ) -> Result<()>;
+
+    /// Remove a user's vote from an option of a message's poll
+    async fn remove_poll_vote(&self, id: &str, option: usize, user: &str) -> Result<()>;
+
+    /// Count a new reply to a message in its thread summary
+    async fn add_thread_reply(&self, id: &str, author: &str) -> Result<()>;
+
+    /// Discount a deleted reply from a message's thread summary
+    ///
+    /// The author stops counting as a participant once none of their replies remain.
+    async fn remove_thread_reply(&self, id: &str, author: &str) -> Result<()>;
+
     /// Delete a message from the database by its id
     async fn delete_message(&self, id: &str) -> Result<()>;
 
     /// Delete messages from a channel by their ids and corresponding channel id
     async fn delete_messages(&self, channel: &str, ids: &[String]) -> Result<()>;
+
+    /// Count the most used reactions on recent messages in the given channels
+    ///
+    /// Only the latest `scan_limit` messages sent since the given time are considered.
+    async fn fetch_reaction_leaderboard(
+        &self,
+        channels: &[String],
+        since: SystemTime,
+        scan_limit: i64,
+        limit: i64,
+    ) -> Result<Vec<EmojiReactionCount>>;
 }
@@ -1,10 +1,17 @@
+use std::time::{Duration, SystemTime};
+
 use bson::{to_bson, Document};
-use futures::try_join;
+use futures::{try_join, StreamExt};
+use iso8601_timestamp::Timestamp;
 use mongodb::options::FindOptions;
-use onechatsocial_models::v0::MessageSort;
+use onechatsocial_models::v0::{MessageHas, MessageSort};
 use onechatsocial_result::Result;
+use ulid::Ulid;
 
-use crate::{AppendMessage, Message, MessageQuery, MessageTimePeriod, MongoDb, PartialMessage};
+use crate::{
+    AppendMessage, EmojiReactionCount, FieldsMessage, IntoDocumentPath, Message, MessageQuery,
+    MessageRevision, MessageTimePeriod, MongoDb, PartialMessage,
+};
 
 use super::AbstractMessages;
 
@@ -29,12 +36,76 @@ impl AbstractMessages for MongoDb {
         // 1. Apply message filters
         if let Some(channel) = query.filter.channel {
             filter.insert("channel", channel);
+        } else if let Some(channels) = query.filter.channels {
+            filter.insert(
+                "channel",
+                doc! {
+                    "$in": channels
+                },
+            );
         }
 
         if let Some(author) = query.filter.author {
             filter.insert("author", author);
         }
 
+        if let Some(user) = query.filter.mentions {
+            filter.insert("mentions", user);
+        }
+
+        if let Some(pinned) = query.filter.pinned {
+            if pinned {
+                filter.insert("pinned", true);
+            } else {
+                filter.insert(
+                    "pinned",
+                    doc! {
+                        "$ne": true
+                    },
+                );
+            }
+        }
+
+        if let Some(has) = query.filter.has {
+            for has in has {
+                match has {
+                    MessageHas::Attachment => {
+                        filter.insert(
+                            "attachments.0",
+                            doc! {
+                                "$exists": true
+                            },
+                        );
+                    }
+                    MessageHas::Embed => {
+                        filter.insert(
+                            "embeds.0",
+                            doc! {
+                                "$exists": true
+                            },
+                        );
+                    }
+                    MessageHas::Link => {
+                        filter.insert(
+                            "content",
+                            doc! {
+                                "$regex": "https?://"
+                            },
+                        );
+                    }
+                    MessageHas::Reaction => {
+                        filter.insert(
+                            "reactions",
+                            doc! {
+                                "$exists": true,
+                                "$ne": {}
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
         let is_search_query = if let Some(query) = query.filter.query {
             filter.insert(
                 "$text",
@@ -159,9 +230,138 @@ impl AbstractMessages for MongoDb {
         }
     }
 
+    /// Fetch a message along with up to `radius` messages either side of it
+    async fn fetch_message_context(
+        &self,
+        channel: &str,
+        id: &str,
+        radius: i64,
+    ) -> Result<Vec<Message>> {
+        let (target, older, newer) = try_join!(
+            self.find_one::<Message>(
+                COL,
+                doc! {
+                    "_id": id,
+                    "channel": channel
+                }
+            ),
+            self.find_with_options::<_, Message>(
+                COL,
+                doc! {
+                    "channel": channel,
+                    "_id": {
+                        "$lt": id
+                    }
+                },
+                FindOptions::builder()
+                    .limit(radius)
+                    .sort(doc! {
+                        "_id": -1_i32
+                    })
+                    .build(),
+            ),
+            self.find_with_options::<_, Message>(
+                COL,
+                doc! {
+                    "channel": channel,
+                    "_id": {
+                        "$gt": id
+                    }
+                },
+                FindOptions::builder()
+                    .limit(radius)
+                    .sort(doc! {
+                        "_id": 1_i32
+                    })
+                    .build(),
+            )
+        )
+        .map_err(|_| create_database_error!("find", COL))?;
+
+        let target = target.ok_or_else(|| create_error!(NotFound))?;
+        Ok(older
+            .into_iter()
+            .rev()
+            .chain(std::iter::once(target))
+            .chain(newer)
+            .collect())
+    }
+
+    /// Fetch messages which expired at or before the given time
+    async fn fetch_expired_messages(&self, now: Timestamp, limit: i64) -> Result<Vec<Message>> {
+        query!(
+            self,
+            find_with_options,
+            COL,
+            doc! {
+                "expires_at": {
+                    "$lte": to_bson(&now)
+                        .map_err(|_| create_database_error!("to_bson", "expires_at"))?
+                }
+            },
+            FindOptions::builder()
+                .sort(doc! {
+                    "expires_at": 1_i32
+                })
+                .limit(limit)
+                .build()
+        )
+    }
+
+    /// Claim an expired message by removing it, returns whether this call removed it
+    async fn claim_expired_message(&self, id: &str) -> Result<bool> {
+        query!(self, delete_one_by_id, COL, id).map(|result| result.deleted_count == 1)
+    }
+
     /// Update a given message with new information
-    async fn update_message(&self, id: &str, message: &PartialMessage) -> Result<()> {
-        query!(self, update_one_by_id, COL, id, message, vec![], None).map(|_| ())
+    async fn update_message(
+        &self,
+        id: &str,
+        message: &PartialMessage,
+        remove: Vec<FieldsMessage>,
+    ) -> Result<()> {
+        query!(
+            self,
+            update_one_by_id,
+            COL,
+            id,
+            message,
+            remove.iter().map(|x| x as &dyn IntoDocumentPath).collect(),
+            None
+        )
+        .map(|_| ())
+    }
+
+    /// Add a previous version to a message's edit history
+    ///
+    /// Only the latest `limit` revisions are kept, the oldest are dropped first.
+    async fn push_message_revision(
+        &self,
+        id: &str,
+        revision: &MessageRevision,
+        limit: usize,
+    ) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$push": {
+                        "edit_history": {
+                            "$each": [
+                                to_bson(revision)
+                                    .map_err(|_| create_database_error!("to_bson", "revision"))?
+                            ],
+                            "$slice": -(limit as i64)
+                        }
+                    }
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
     }
 
     /// Append information to a given message
@@ -237,6 +437,63 @@ impl AbstractMessages for MongoDb {
             .map_err(|_| create_database_error!("update_one", COL))
     }
 
+    /// Record that a user is adding a reaction which has a cooldown
+    async fn claim_reaction_cooldown(
+        &self,
+        id: &str,
+        emoji: &str,
+        user: &str,
+        cooldown: Duration,
+    ) -> Result<Option<Duration>> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+
+        let path = format!("reacted_at.{emoji}.{user}");
+
+        // Only claim the cooldown if it has not been claimed within the period
+        let result = self
+            .col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id,
+                    "$or": [
+                        { &path: { "$exists": false } },
+                        { &path: { "$lte": now - cooldown.as_millis() as i64 } }
+                    ]
+                },
+                doc! {
+                    "$set": {
+                        &path: now
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|_| create_database_error!("update_one", COL))?;
+
+        if result.matched_count == 1 {
+            return Ok(None);
+        }
+
+        let last = self
+            .col::<Document>(COL)
+            .find_one(doc! { "_id": id }, None)
+            .await
+            .map_err(|_| create_database_error!("find_one", COL))?
+            .ok_or_else(|| create_error!(NotFound))?
+            .get_document("reacted_at")
+            .ok()
+            .and_then(|cooldowns| cooldowns.get_document(emoji).ok())
+            .and_then(|users| users.get_i64(user).ok())
+            .unwrap_or(now);
+
+        Ok(Some(Duration::from_millis(
+            (last + cooldown.as_millis() as i64 - now).max(0) as u64,
+        )))
+    }
+
     /// Remove reaction from a message
     async fn clear_reaction(&self, id: &str, emoji: &str) -> Result<()> {
         self.col::<Document>(COL)
@@ -256,6 +513,256 @@ impl AbstractMessages for MongoDb {
             .map_err(|_| create_database_error!("update_one", COL))
     }
 
+    /// Remove all users from a reaction while keeping it on a message
+    async fn reset_reaction(&self, id: &str, emoji: &str) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$set": {
+                        format!("reactions.{emoji}"): []
+                    }
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Remove all reactions from a message
+    ///
+    /// Any emoji in `keep` are left on the message without any users.
+    async fn clear_all_reactions(&self, id: &str, keep: &[String]) -> Result<()> {
+        let update = if keep.is_empty() {
+            doc! {
+                "$unset": {
+                    "reactions": 1
+                }
+            }
+        } else {
+            let mut reactions = Document::new();
+            for emoji in keep {
+                reactions.insert(emoji, bson::Array::new());
+            }
+
+            doc! {
+                "$set": {
+                    "reactions": reactions
+                }
+            }
+        };
+
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                update,
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Add a user's vote to an option of a message's poll
+    ///
+    /// If `exclusive` is set, the user's votes for any other option are removed in the same update.
+    async fn add_poll_vote(
+        &self,
+        id: &str,
+        option: usize,
+        user: &str,
+        exclusive: bool,
+    ) -> Result<()> {
+        let filter = doc! {
+            "_id": id,
+            "poll": {
+                "$exists": true
+            }
+        };
+
+        let result = if exclusive {
+            // Rebuild the options in a single pipeline update, as pulling from
+            // every option while adding to one of them would conflict
+            let voters = doc! {
+                "$cond": [
+                    { "$eq": ["$$index", option as i64] },
+                    { "$setUnion": ["$$option.voters", [user]] },
+                    { "$setDifference": ["$$option.voters", [user]] }
+                ]
+            };
+
+            let options = doc! {
+                "$map": {
+                    "input": { "$range": [0, { "$size": "$poll.options" }] },
+                    "as": "index",
+                    "in": {
+                        "$let": {
+                            "vars": {
+                                "option": { "$arrayElemAt": ["$poll.options", "$$index"] }
+                            },
+                            "in": {
+                                "$mergeObjects": ["$$option", { "voters": voters }]
+                            }
+                        }
+                    }
+                }
+            };
+
+            self.col::<Document>(COL)
+                .update_one(
+                    filter,
+                    vec![doc! {
+                        "$set": {
+                            "poll.options": options
+                        }
+                    }],
+                    None,
+                )
+                .await
+        } else {
+            self.col::<Document>(COL)
+                .update_one(
+                    filter,
+                    doc! {
+                        "$addToSet": {
+                            format!("poll.options.{option}.voters"): user
+                        }
+                    },
+                    None,
+                )
+                .await
+        };
+
+        result
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Remove a user's vote from an option of a message's poll
+    async fn remove_poll_vote(&self, id: &str, option: usize, user: &str) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$pull": {
+                        format!("poll.options.{option}.voters"): user
+                    }
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Count a new reply to a message in its thread summary
+    async fn add_thread_reply(&self, id: &str, author: &str) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                doc! {
+                    "$inc": {
+                        "reply_count": 1_i32
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|_| create_database_error!("update_one", COL))?;
+
+        // Only count each participant once
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id,
+                    "thread_participants": {
+                        "$ne": author
+                    }
+                },
+                doc! {
+                    "$push": {
+                        "thread_participants": author
+                    },
+                    "$inc": {
+                        "thread_participant_count": 1_i32
+                    }
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
+    /// Discount a deleted reply from a message's thread summary
+    ///
+    /// The author stops counting as a participant once none of their replies remain.
+    async fn remove_thread_reply(&self, id: &str, author: &str) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id,
+                    "reply_count": {
+                        "$gt": 0_i32
+                    }
+                },
+                doc! {
+                    "$inc": {
+                        "reply_count": -1_i32
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|_| create_database_error!("update_one", COL))?;
+
+        // Served by the `replies_author` index
+        let remaining = self
+            .col::<Document>(COL)
+            .count_documents(
+                doc! {
+                    "replies": id,
+                    "author": author
+                },
+                None,
+            )
+            .await
+            .map_err(|_| create_database_error!("count_documents", COL))?;
+
+        if remaining > 0 {
+            return Ok(());
+        }
+
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id,
+                    "thread_participants": author
+                },
+                doc! {
+                    "$pull": {
+                        "thread_participants": author
+                    },
+                    "$inc": {
+                        "thread_participant_count": -1_i32
+                    }
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
     /// Delete a message from the database by its id
     async fn delete_message(&self, id: &str) -> Result<()> {
         query!(self, delete_one_by_id, COL, id).map(|_| ())
@@ -277,4 +784,99 @@ impl AbstractMessages for MongoDb {
             .map(|_| ())
             .map_err(|_| create_database_error!("delete_many", COL))
     }
+
+    /// Count the most used reactions on recent messages in the given channels
+    ///
+    /// Only the latest `scan_limit` messages sent since the given time are considered.
+    async fn fetch_reaction_leaderboard(
+        &self,
+        channels: &[String],
+        since: SystemTime,
+        scan_limit: i64,
+        limit: i64,
+    ) -> Result<Vec<EmojiReactionCount>> {
+        let after = Ulid::from_datetime(since).to_string();
+        Ok(self
+            .col::<Document>(COL)
+            .aggregate(
+                vec![
+                    doc! {
+                        "$match": {
+                            "channel": {
+                                "$in": channels
+                            },
+                            "_id": {
+                                "$gte": after
+                            },
+                            "reactions": {
+                                "$exists": true
+                            }
+                        }
+                    },
+                    doc! {
+                        "$sort": {
+                            "_id": -1_i32
+                        }
+                    },
+                    doc! {
+                        "$limit": scan_limit
+                    },
+                    doc! {
+                        "$project": {
+                            "reactions": {
+                                "$objectToArray": "$reactions"
+                            }
+                        }
+                    },
+                    doc! {
+                        "$unwind": "$reactions"
+                    },
+                    doc! {
+                        "$unwind": "$reactions.v"
+                    },
+                    doc! {
+                        "$group": {
+                            "_id": "$reactions.k",
+                            "count": {
+                                "$sum": 1_i32
+                            },
+                            "users": {
+                                "$addToSet": "$reactions.v"
+                            }
+                        }
+                    },
+                    doc! {
+                        "$project": {
+                            "count": 1_i32,
+                            "users": {
+                                "$size": "$users"
+                            }
+                        }
+                    },
+                    doc! {
+                        "$sort": {
+                            "count": -1_i32
+                        }
+                    },
+                    doc! {
+                        "$limit": limit
+                    },
+                ],
+                None,
+            )
+            .await
+            .map_err(|_| create_database_error!("aggregate", COL))?
+            .filter_map(|s| async { s.ok() })
+            .filter_map(|doc| async move { bson::from_document(doc).ok() })
+            .collect()
+            .await)
+    }
+}
+
+impl IntoDocumentPath for FieldsMessage {
+    fn as_path(&self) -> Option<&'static str> {
+        Some(match self {
+            FieldsMessage::EditedBy => "edited_by",
+        })
+    }
 }
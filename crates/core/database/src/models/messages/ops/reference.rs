@@ -1,7 +1,18 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, SystemTime},
+};
+
 use indexmap::IndexSet;
+use iso8601_timestamp::Timestamp;
+use onechatsocial_models::v0::MessageHas;
 use onechatsocial_result::Result;
+use ulid::Ulid;
 
-use crate::{AppendMessage, Message, MessageQuery, PartialMessage, ReferenceDb};
+use crate::{
+    AppendMessage, EmojiReactionCount, FieldsMessage, Message, MessageQuery, MessageRevision,
+    PartialMessage, ReferenceDb,
+};
 
 use super::AbstractMessages;
 
@@ -37,6 +48,10 @@ impl AbstractMessages for ReferenceDb {
                     if &message.channel != channel {
                         return false;
                     }
+                } else if let Some(channels) = &query.filter.channels {
+                    if !channels.contains(&message.channel) {
+                        return false;
+                    }
                 }
 
                 if let Some(author) = &query.filter.author {
@@ -45,6 +60,45 @@ impl AbstractMessages for ReferenceDb {
                     }
                 }
 
+                if let Some(user) = &query.filter.mentions {
+                    if !message
+                        .mentions
+                        .as_ref()
+                        .is_some_and(|mentions| mentions.contains(user))
+                    {
+                        return false;
+                    }
+                }
+
+                if let Some(pinned) = query.filter.pinned {
+                    if message.pinned.unwrap_or_default() != pinned {
+                        return false;
+                    }
+                }
+
+                if let Some(has) = &query.filter.has {
+                    for has in has {
+                        let matches = match has {
+                            MessageHas::Attachment => message
+                                .attachments
+                                .as_ref()
+                                .is_some_and(|attachments| !attachments.is_empty()),
+                            MessageHas::Embed => message
+                                .embeds
+                                .as_ref()
+                                .is_some_and(|embeds| !embeds.is_empty()),
+                            MessageHas::Link => message.content.as_ref().is_some_and(|content| {
+                                content.contains("http://") || content.contains("https://")
+                            }),
+                            MessageHas::Reaction => !message.reactions.is_empty(),
+                        };
+
+                        if !matches {
+                            return false;
+                        }
+                    }
+                }
+
                 if let Some(query) = &query.filter.query {
                     if let Some(content) = &message.content {
                         if !content.to_lowercase().contains(query) {
@@ -176,10 +230,79 @@ impl AbstractMessages for ReferenceDb {
         }*/
     }
 
+    /// Fetch a message along with up to `radius` messages either side of it
+    async fn fetch_message_context(
+        &self,
+        channel: &str,
+        id: &str,
+        radius: i64,
+    ) -> Result<Vec<Message>> {
+        let messages = self.messages.lock().await;
+        if !messages
+            .get(id)
+            .is_some_and(|message| message.channel == channel)
+        {
+            return Err(create_error!(NotFound));
+        }
+
+        let mut in_channel: Vec<&Message> = messages
+            .values()
+            .filter(|message| message.channel == channel)
+            .collect();
+
+        in_channel.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let index = in_channel
+            .iter()
+            .position(|message| message.id == id)
+            .expect("target is in channel");
+
+        let radius = radius as usize;
+        Ok(
+            in_channel[index.saturating_sub(radius)..(index + radius + 1).min(in_channel.len())]
+                .iter()
+                .map(|message| (*message).clone())
+                .collect(),
+        )
+    }
+
+    /// Fetch messages which expired at or before the given time
+    async fn fetch_expired_messages(&self, now: Timestamp, limit: i64) -> Result<Vec<Message>> {
+        let messages = self.messages.lock().await;
+        let mut expired: Vec<Message> = messages
+            .values()
+            .filter(|message| {
+                message
+                    .expires_at
+                    .is_some_and(|expires_at| *expires_at <= *now)
+            })
+            .cloned()
+            .collect();
+
+        expired.sort_by_key(|message| message.expires_at.map(|expires_at| *expires_at));
+        expired.truncate(limit as usize);
+        Ok(expired)
+    }
+
+    /// Claim an expired message by removing it, returns whether this call removed it
+    async fn claim_expired_message(&self, id: &str) -> Result<bool> {
+        let mut messages = self.messages.lock().await;
+        Ok(messages.remove(id).is_some())
+    }
+
     /// Update a given message with new information
-    async fn update_message(&self, id: &str, message: &PartialMessage) -> Result<()> {
+    async fn update_message(
+        &self,
+        id: &str,
+        message: &PartialMessage,
+        remove: Vec<FieldsMessage>,
+    ) -> Result<()> {
         let mut messages = self.messages.lock().await;
         if let Some(message_data) = messages.get_mut(id) {
+            for field in remove {
+                message_data.remove_field(&field);
+            }
+
             message_data.apply_options(message.to_owned());
             Ok(())
         } else {
@@ -187,6 +310,29 @@ impl AbstractMessages for ReferenceDb {
         }
     }
 
+    /// Add a previous version to a message's edit history
+    ///
+    /// Only the latest `limit` revisions are kept, the oldest are dropped first.
+    async fn push_message_revision(
+        &self,
+        id: &str,
+        revision: &MessageRevision,
+        limit: usize,
+    ) -> Result<()> {
+        let mut messages = self.messages.lock().await;
+        if let Some(message_data) = messages.get_mut(id) {
+            let history = message_data.edit_history.get_or_insert_with(Vec::new);
+            history.push(revision.clone());
+            if history.len() > limit {
+                history.drain(..history.len() - limit);
+            }
+
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
     /// Append information to a given message
     async fn append_message(&self, id: &str, append: &AppendMessage) -> Result<()> {
         let mut messages = self.messages.lock().await;
@@ -239,6 +385,33 @@ impl AbstractMessages for ReferenceDb {
         }
     }
 
+    /// Record that a user is adding a reaction which has a cooldown
+    async fn claim_reaction_cooldown(
+        &self,
+        id: &str,
+        emoji: &str,
+        user: &str,
+        cooldown: Duration,
+    ) -> Result<Option<Duration>> {
+        if !self.messages.lock().await.contains_key(id) {
+            return Err(create_error!(NotFound));
+        }
+
+        let now = SystemTime::now();
+        let mut cooldowns = self.reaction_cooldowns.lock().await;
+        let last = cooldowns
+            .entry(format!("{id}:{emoji}:{user}"))
+            .or_insert(SystemTime::UNIX_EPOCH);
+
+        let elapsed = now.duration_since(*last).unwrap_or_default();
+        if elapsed < cooldown {
+            return Ok(Some(cooldown - elapsed));
+        }
+
+        *last = now;
+        Ok(None)
+    }
+
     /// Remove reaction from a message
     async fn clear_reaction(&self, id: &str, emoji: &str) -> Result<()> {
         let mut messages = self.messages.lock().await;
@@ -250,6 +423,103 @@ impl AbstractMessages for ReferenceDb {
         }
     }
 
+    /// Remove all users from a reaction while keeping it on a message
+    async fn reset_reaction(&self, id: &str, emoji: &str) -> Result<()> {
+        let mut messages = self.messages.lock().await;
+        if let Some(message) = messages.get_mut(id) {
+            message.reactions.insert(emoji.to_string(), IndexSet::new());
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Remove all reactions from a message
+    ///
+    /// Any emoji in `keep` are left on the message without any users.
+    async fn clear_all_reactions(&self, id: &str, keep: &[String]) -> Result<()> {
+        let mut messages = self.messages.lock().await;
+        if let Some(message) = messages.get_mut(id) {
+            message.reactions = keep
+                .iter()
+                .map(|emoji| (emoji.clone(), IndexSet::new()))
+                .collect();
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Add a user's vote to an option of a message's poll
+    ///
+    /// If `exclusive` is set, the user's votes for any other option are removed in the same update.
+    async fn add_poll_vote(
+        &self,
+        id: &str,
+        option: usize,
+        user: &str,
+        exclusive: bool,
+    ) -> Result<()> {
+        let mut messages = self.messages.lock().await;
+        let poll = messages
+            .get_mut(id)
+            .and_then(|message| message.poll.as_mut())
+            .ok_or_else(|| create_error!(NotFound))?;
+
+        for (index, poll_option) in poll.options.iter_mut().enumerate() {
+            if index == option {
+                poll_option.voters.insert(user.to_string());
+            } else if exclusive {
+                poll_option.voters.shift_remove(user);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove a user's vote from an option of a message's poll
+    async fn remove_poll_vote(&self, id: &str, option: usize, user: &str) -> Result<()> {
+        let mut messages = self.messages.lock().await;
+        let poll = messages
+            .get_mut(id)
+            .and_then(|message| message.poll.as_mut())
+            .ok_or_else(|| create_error!(NotFound))?;
+
+        if let Some(poll_option) = poll.options.get_mut(option) {
+            poll_option.voters.shift_remove(user);
+        }
+
+        Ok(())
+    }
+
+    /// Count a new reply to a message in its thread summary
+    async fn add_thread_reply(&self, id: &str, _author: &str) -> Result<()> {
+        let mut messages = self.messages.lock().await;
+        let participants = thread_participants(&messages, id);
+        if let Some(message) = messages.get_mut(id) {
+            message.reply_count += 1;
+            message.thread_participant_count = participants;
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
+    /// Discount a deleted reply from a message's thread summary
+    ///
+    /// The author stops counting as a participant once none of their replies remain.
+    async fn remove_thread_reply(&self, id: &str, _author: &str) -> Result<()> {
+        let mut messages = self.messages.lock().await;
+        let participants = thread_participants(&messages, id);
+        if let Some(message) = messages.get_mut(id) {
+            message.reply_count = message.reply_count.saturating_sub(1);
+            message.thread_participant_count = participants;
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
     /// Delete a message from the database by its id
     async fn delete_message(&self, id: &str) -> Result<()> {
         let mut messages = self.messages.lock().await;
@@ -265,8 +535,66 @@ impl AbstractMessages for ReferenceDb {
         self.messages
             .lock()
             .await
-            .retain(|id, message| message.channel != channel && !ids.contains(id));
+            .retain(|id, message| message.channel != channel || !ids.contains(id));
 
         Ok(())
     }
+
+    /// Count the most used reactions on recent messages in the given channels
+    ///
+    /// Only the latest `scan_limit` messages sent since the given time are considered.
+    async fn fetch_reaction_leaderboard(
+        &self,
+        channels: &[String],
+        since: SystemTime,
+        scan_limit: i64,
+        limit: i64,
+    ) -> Result<Vec<EmojiReactionCount>> {
+        let after = Ulid::from_datetime(since).to_string();
+        let messages = self.messages.lock().await;
+        let mut recent: Vec<&Message> = messages
+            .values()
+            .filter(|message| channels.contains(&message.channel) && message.id >= after)
+            .collect();
+
+        recent.sort_by(|a, b| b.id.cmp(&a.id));
+        recent.truncate(scan_limit as usize);
+
+        let mut counts: HashMap<&String, (usize, HashSet<&String>)> = HashMap::new();
+        for message in recent {
+            for (emoji, users) in &message.reactions {
+                let entry = counts.entry(emoji).or_default();
+                entry.0 += users.len();
+                entry.1.extend(users.iter());
+            }
+        }
+
+        let mut leaderboard: Vec<EmojiReactionCount> = counts
+            .into_iter()
+            .map(|(emoji, (count, users))| EmojiReactionCount {
+                emoji: emoji.to_string(),
+                count,
+                users: users.len(),
+            })
+            .collect();
+
+        leaderboard.sort_by(|a, b| b.count.cmp(&a.count));
+        leaderboard.truncate(limit as usize);
+        Ok(leaderboard)
+    }
+}
+
+/// Count the distinct authors of replies to a message
+fn thread_participants(messages: &HashMap<String, Message>, id: &str) -> u32 {
+    messages
+        .values()
+        .filter(|message| {
+            message
+                .replies
+                .as_ref()
+                .map_or(false, |replies| replies.iter().any(|reply| reply == id))
+        })
+        .map(|message| &message.author)
+        .collect::<HashSet<_>>()
+        .len() as u32
 }
@@ -1,32 +1,52 @@
 mod admin_migrations;
+mod audit_log;
 mod bots;
+mod channel_follows;
 mod channel_invites;
+mod channel_notification_preferences;
 mod channel_unreads;
 mod channel_webhooks;
 mod channels;
 mod emojis;
 mod files;
+mod message_tombstones;
 mod messages;
+mod pending_message_deletions;
 mod ratelimit_events;
+mod reaction_roles;
+mod scheduled_messages;
 mod server_bans;
 mod server_members;
+mod server_name_filters;
+mod server_templates;
 mod servers;
+mod stickers;
 mod user_settings;
 mod users;
 
 pub use admin_migrations::*;
+pub use audit_log::*;
 pub use bots::*;
+pub use channel_follows::*;
 pub use channel_invites::*;
+pub use channel_notification_preferences::*;
 pub use channel_unreads::*;
 pub use channel_webhooks::*;
 pub use channels::*;
 pub use emojis::*;
 pub use files::*;
+pub use message_tombstones::*;
 pub use messages::*;
+pub use pending_message_deletions::*;
 pub use ratelimit_events::*;
+pub use reaction_roles::*;
+pub use scheduled_messages::*;
 pub use server_bans::*;
 pub use server_members::*;
+pub use server_name_filters::*;
+pub use server_templates::*;
 pub use servers::*;
+pub use stickers::*;
 pub use user_settings::*;
 pub use users::*;
 
@@ -36,18 +56,28 @@ pub trait AbstractDatabase:
     Sync
     + Send
     + admin_migrations::AbstractMigrations
+    + audit_log::AbstractAuditLog
     + bots::AbstractBots
     + channels::AbstractChannels
+    + channel_follows::AbstractChannelFollows
     + channel_invites::AbstractChannelInvites
+    + channel_notification_preferences::AbstractChannelNotificationPreferences
     + channel_unreads::AbstractChannelUnreads
     + channel_webhooks::AbstractWebhooks
     + emojis::AbstractEmojis
     + files::AbstractAttachments
+    + message_tombstones::AbstractMessageTombstones
     + messages::AbstractMessages
+    + pending_message_deletions::AbstractPendingMessageDeletions
     + ratelimit_events::AbstractRatelimitEvents
+    + reaction_roles::AbstractReactionRoles
+    + scheduled_messages::AbstractScheduledMessages
     + server_bans::AbstractServerBans
     + server_members::AbstractServerMembers
+    + server_name_filters::AbstractServerNameFilters
+    + server_templates::AbstractServerTemplates
     + servers::AbstractServers
+    + stickers::AbstractStickers
     + user_settings::AbstractUserSettings
     + users::AbstractUsers
 {
@@ -0,0 +1,20 @@
+use iso8601_timestamp::Timestamp;
+
+use crate::Message;
+
+auto_derived!(
+    /// Deleted message which may still be restored by the user who deleted it
+    pub struct PendingMessageDeletion {
+        /// Id of the deleted message
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the channel the message was sent in
+        pub channel: String,
+        /// Id of the user that deleted the message
+        pub deleted_by: String,
+        /// Time at which the deletion should be finalised
+        pub finalise_at: Timestamp,
+        /// Deleted message
+        pub message: Message,
+    }
+);
@@ -0,0 +1,36 @@
+use iso8601_timestamp::Timestamp;
+use onechatsocial_result::Result;
+
+use crate::PendingMessageDeletion;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractPendingMessageDeletions: Sync + Send {
+    /// Insert a new pending message deletion into the database
+    async fn insert_pending_message_deletion(
+        &self,
+        deletion: &PendingMessageDeletion,
+    ) -> Result<()>;
+
+    /// Fetch deletions which should be finalised at or before the given time
+    async fn fetch_due_pending_message_deletions(
+        &self,
+        now: Timestamp,
+        limit: i64,
+    ) -> Result<Vec<PendingMessageDeletion>>;
+
+    /// Remove a pending deletion so that it can be finalised
+    ///
+    /// Returns whether it was removed, only the caller which removed it may finalise it.
+    async fn claim_pending_message_deletion(&self, id: &str) -> Result<bool>;
+
+    /// Remove and return a pending deletion made by the given user so that it can be undone
+    async fn take_pending_message_deletion(
+        &self,
+        channel: &str,
+        id: &str,
+        deleted_by: &str,
+    ) -> Result<PendingMessageDeletion>;
+}
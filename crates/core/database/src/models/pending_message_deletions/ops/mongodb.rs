@@ -0,0 +1,75 @@
+use bson::to_bson;
+use iso8601_timestamp::Timestamp;
+use mongodb::options::FindOptions;
+use onechatsocial_result::Result;
+
+use crate::MongoDb;
+use crate::PendingMessageDeletion;
+
+use super::AbstractPendingMessageDeletions;
+
+static COL: &str = "pending_message_deletions";
+
+#[async_trait]
+impl AbstractPendingMessageDeletions for MongoDb {
+    /// Insert a new pending message deletion into the database
+    async fn insert_pending_message_deletion(
+        &self,
+        deletion: &PendingMessageDeletion,
+    ) -> Result<()> {
+        query!(self, insert_one, COL, &deletion).map(|_| ())
+    }
+
+    /// Fetch deletions which should be finalised at or before the given time
+    async fn fetch_due_pending_message_deletions(
+        &self,
+        now: Timestamp,
+        limit: i64,
+    ) -> Result<Vec<PendingMessageDeletion>> {
+        query!(
+            self,
+            find_with_options,
+            COL,
+            doc! {
+                "finalise_at": {
+                    "$lte": to_bson(&now)
+                        .map_err(|_| create_database_error!("to_bson", "finalise_at"))?
+                }
+            },
+            FindOptions::builder()
+                .sort(doc! {
+                    "finalise_at": 1_i32
+                })
+                .limit(limit)
+                .build()
+        )
+    }
+
+    /// Remove a pending deletion so that it can be finalised
+    ///
+    /// Returns whether it was removed, only the caller which removed it may finalise it.
+    async fn claim_pending_message_deletion(&self, id: &str) -> Result<bool> {
+        query!(self, delete_one_by_id, COL, id).map(|result| result.deleted_count == 1)
+    }
+
+    /// Remove and return a pending deletion made by the given user so that it can be undone
+    async fn take_pending_message_deletion(
+        &self,
+        channel: &str,
+        id: &str,
+        deleted_by: &str,
+    ) -> Result<PendingMessageDeletion> {
+        self.col::<PendingMessageDeletion>(COL)
+            .find_one_and_delete(
+                doc! {
+                    "_id": id,
+                    "channel": channel,
+                    "deleted_by": deleted_by
+                },
+                None,
+            )
+            .await
+            .map_err(|_| create_database_error!("find_one_and_delete", COL))?
+            .ok_or_else(|| create_error!(NotFound))
+    }
+}
@@ -0,0 +1,69 @@
+use iso8601_timestamp::Timestamp;
+use onechatsocial_result::Result;
+
+use crate::PendingMessageDeletion;
+use crate::ReferenceDb;
+
+use super::AbstractPendingMessageDeletions;
+
+#[async_trait]
+impl AbstractPendingMessageDeletions for ReferenceDb {
+    /// Insert a new pending message deletion into the database
+    async fn insert_pending_message_deletion(
+        &self,
+        deletion: &PendingMessageDeletion,
+    ) -> Result<()> {
+        let mut pending_message_deletions = self.pending_message_deletions.lock().await;
+        if pending_message_deletions.contains_key(&deletion.id) {
+            Err(create_database_error!("insert", "pending_message_deletion"))
+        } else {
+            pending_message_deletions.insert(deletion.id.to_string(), deletion.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch deletions which should be finalised at or before the given time
+    async fn fetch_due_pending_message_deletions(
+        &self,
+        now: Timestamp,
+        limit: i64,
+    ) -> Result<Vec<PendingMessageDeletion>> {
+        let pending_message_deletions = self.pending_message_deletions.lock().await;
+        let mut due: Vec<PendingMessageDeletion> = pending_message_deletions
+            .values()
+            .filter(|deletion| *deletion.finalise_at <= *now)
+            .cloned()
+            .collect();
+
+        due.sort_by_key(|deletion| *deletion.finalise_at);
+        due.truncate(limit as usize);
+        Ok(due)
+    }
+
+    /// Remove a pending deletion so that it can be finalised
+    ///
+    /// Returns whether it was removed, only the caller which removed it may finalise it.
+    async fn claim_pending_message_deletion(&self, id: &str) -> Result<bool> {
+        let mut pending_message_deletions = self.pending_message_deletions.lock().await;
+        Ok(pending_message_deletions.remove(id).is_some())
+    }
+
+    /// Remove and return a pending deletion made by the given user so that it can be undone
+    async fn take_pending_message_deletion(
+        &self,
+        channel: &str,
+        id: &str,
+        deleted_by: &str,
+    ) -> Result<PendingMessageDeletion> {
+        let mut pending_message_deletions = self.pending_message_deletions.lock().await;
+        if pending_message_deletions.get(id).is_some_and(|deletion| {
+            deletion.channel == channel && deletion.deleted_by == deleted_by
+        }) {
+            pending_message_deletions
+                .remove(id)
+                .ok_or_else(|| create_error!(NotFound))
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
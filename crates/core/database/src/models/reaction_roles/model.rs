@@ -0,0 +1,107 @@
+use onechatsocial_result::Result;
+
+use crate::{Database, PartialMember};
+
+auto_derived!(
+    /// Role granted to users who react to a message with a given emoji
+    pub struct ReactionRole {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the server the role belongs to
+        pub server: String,
+        /// Id of the channel the message was sent in
+        pub channel: String,
+        /// Id of the message to react to
+        pub message: String,
+        /// Emoji which grants the role
+        pub emoji: String,
+        /// Id of the role to grant
+        pub role: String,
+    }
+);
+
+impl ReactionRole {
+    /// Grant or revoke this role for a given user
+    pub async fn apply(&self, db: &Database, user_id: &str, grant: bool) -> Result<()> {
+        // Ignore roles which have since been deleted
+        let server = db.fetch_server(&self.server).await?;
+        if !server.roles.contains_key(&self.role) {
+            return Ok(());
+        }
+
+        let mut member = db.fetch_member(&self.server, user_id).await?;
+        if member.roles.contains(&self.role) == grant {
+            return Ok(());
+        }
+
+        let mut roles = member.roles.clone();
+        if grant {
+            roles.push(self.role.to_string());
+        } else {
+            roles.retain(|role| role != &self.role);
+        }
+
+        member
+            .update(
+                db,
+                PartialMember {
+                    roles: Some(roles),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fixture, Message, ReactionRole};
+
+    #[async_std::test]
+    async fn reaction_grants_role() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                user user 2
+                server server 4
+                channel channel 3);
+
+            let role = server
+                .roles
+                .iter()
+                .find(|(_, role)| role.name == "Moderator")
+                .map(|(id, _)| id.to_string())
+                .unwrap();
+
+            let message = Message {
+                id: ulid::Ulid::new().to_string(),
+                channel: channel.id(),
+                author: ulid::Ulid::new().to_string(),
+                content: Some("React for a role!".to_string()),
+                ..Default::default()
+            };
+
+            db.insert_message(&message).await.unwrap();
+            db.insert_reaction_role(&ReactionRole {
+                id: ulid::Ulid::new().to_string(),
+                server: server.id.to_string(),
+                channel: channel.id(),
+                message: message.id.to_string(),
+                emoji: "👍".to_string(),
+                role: role.to_string(),
+            })
+            .await
+            .unwrap();
+
+            message.add_reaction(&db, &user.id, "👍").await.unwrap();
+            let member = db.fetch_member(&server.id, &user.id).await.unwrap();
+            assert!(member.roles.contains(&role));
+
+            let message = db.fetch_message(&message.id).await.unwrap();
+            message.remove_reaction(&db, &user.id, "👍").await.unwrap();
+            let member = db.fetch_member(&server.id, &user.id).await.unwrap();
+            assert!(!member.roles.contains(&role));
+        });
+    }
+}
@@ -0,0 +1,21 @@
+use onechatsocial_result::Result;
+
+use crate::ReactionRole;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractReactionRoles: Sync + Send {
+    /// Insert a new reaction role into the database
+    async fn insert_reaction_role(&self, reaction_role: &ReactionRole) -> Result<()>;
+
+    /// Fetch a reaction role by message id and emoji
+    async fn fetch_reaction_role(&self, message_id: &str, emoji: &str) -> Result<ReactionRole>;
+
+    /// Fetch all reaction roles on a message
+    async fn fetch_reaction_roles(&self, message_id: &str) -> Result<Vec<ReactionRole>>;
+
+    /// Delete a reaction role from the database by its id
+    async fn delete_reaction_role(&self, id: &str) -> Result<()>;
+}
@@ -0,0 +1,47 @@
+use onechatsocial_result::Result;
+
+use crate::MongoDb;
+use crate::ReactionRole;
+
+use super::AbstractReactionRoles;
+
+static COL: &str = "reaction_roles";
+
+#[async_trait]
+impl AbstractReactionRoles for MongoDb {
+    /// Insert a new reaction role into the database
+    async fn insert_reaction_role(&self, reaction_role: &ReactionRole) -> Result<()> {
+        query!(self, insert_one, COL, &reaction_role).map(|_| ())
+    }
+
+    /// Fetch a reaction role by message id and emoji
+    async fn fetch_reaction_role(&self, message_id: &str, emoji: &str) -> Result<ReactionRole> {
+        query!(
+            self,
+            find_one,
+            COL,
+            doc! {
+                "message": message_id,
+                "emoji": emoji
+            }
+        )?
+        .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all reaction roles on a message
+    async fn fetch_reaction_roles(&self, message_id: &str) -> Result<Vec<ReactionRole>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "message": message_id
+            }
+        )
+    }
+
+    /// Delete a reaction role from the database by its id
+    async fn delete_reaction_role(&self, id: &str) -> Result<()> {
+        query!(self, delete_one_by_id, COL, id).map(|_| ())
+    }
+}
@@ -0,0 +1,52 @@
+use onechatsocial_result::Result;
+
+use crate::ReactionRole;
+use crate::ReferenceDb;
+
+use super::AbstractReactionRoles;
+
+#[async_trait]
+impl AbstractReactionRoles for ReferenceDb {
+    /// Insert a new reaction role into the database
+    async fn insert_reaction_role(&self, reaction_role: &ReactionRole) -> Result<()> {
+        let mut reaction_roles = self.reaction_roles.lock().await;
+        if reaction_roles.contains_key(&reaction_role.id) {
+            Err(create_database_error!("insert", "reaction_role"))
+        } else {
+            reaction_roles.insert(reaction_role.id.to_string(), reaction_role.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a reaction role by message id and emoji
+    async fn fetch_reaction_role(&self, message_id: &str, emoji: &str) -> Result<ReactionRole> {
+        let reaction_roles = self.reaction_roles.lock().await;
+        reaction_roles
+            .values()
+            .find(|reaction_role| {
+                reaction_role.message == message_id && reaction_role.emoji == emoji
+            })
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all reaction roles on a message
+    async fn fetch_reaction_roles(&self, message_id: &str) -> Result<Vec<ReactionRole>> {
+        let reaction_roles = self.reaction_roles.lock().await;
+        Ok(reaction_roles
+            .values()
+            .filter(|reaction_role| reaction_role.message == message_id)
+            .cloned()
+            .collect())
+    }
+
+    /// Delete a reaction role from the database by its id
+    async fn delete_reaction_role(&self, id: &str) -> Result<()> {
+        let mut reaction_roles = self.reaction_roles.lock().await;
+        if reaction_roles.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use iso8601_timestamp::Timestamp;
+use onechatsocial_models::v0::{self, MessageAuthor};
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+
+use crate::{
+    util::{permissions::DatabasePermissionQuery, ulid_generator},
+    Database, Message,
+};
+
+auto_derived!(
+    /// Message which will be sent into its channel at a later time
+    pub struct ScheduledMessage {
+        /// Id of the message
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the channel the message will be sent in
+        pub channel: String,
+        /// Time at which the message should be sent
+        pub send_at: Timestamp,
+        /// Whether embeds should be generated once the message is sent
+        pub generate_embeds: bool,
+        /// Message to send
+        pub message: Message,
+    }
+);
+
+impl ScheduledMessage {
+    /// Send this message into its channel as its original author
+    ///
+    /// The message is given a new id so it sorts by when it was actually sent,
+    /// and is dropped if its author may no longer send messages in the channel.
+    ///
+    /// Must only be called once the message has been claimed.
+    pub async fn send(self, db: &Database) -> Result<Message> {
+        let channel = db.fetch_channel(&self.channel).await?;
+        let mut message = self.message;
+        message.id = ulid_generator::generate().to_string();
+
+        if message.webhook.is_some() {
+            let webhook: v0::Webhook = db.fetch_webhook(&message.author).await?.into();
+            if webhook.channel_id != channel.id() {
+                return Err(create_error!(NotFound));
+            }
+
+            message
+                .send(
+                    db,
+                    MessageAuthor::Webhook(&webhook),
+                    &channel,
+                    self.generate_embeds,
+                )
+                .await?;
+        } else {
+            let user = db.fetch_user(&message.author).await?;
+
+            // The author may have left or lost permission since scheduling the message
+            let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+            let permissions = calculate_channel_permissions(&mut query).await;
+            permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+            permissions.throw_if_lacking_channel_permission(ChannelPermission::SendMessage)?;
+
+            let author: v0::User = user.clone().into(db, Some(&user)).await;
+            message
+                .send(
+                    db,
+                    MessageAuthor::User(&author),
+                    &channel,
+                    self.generate_embeds,
+                )
+                .await?;
+        }
+
+        Ok(message)
+    }
+}
@@ -0,0 +1,31 @@
+use iso8601_timestamp::Timestamp;
+use onechatsocial_result::Result;
+
+use crate::ScheduledMessage;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractScheduledMessages: Sync + Send {
+    /// Insert a new scheduled message into the database
+    async fn insert_scheduled_message(&self, scheduled: &ScheduledMessage) -> Result<()>;
+
+    /// Fetch a scheduled message by its id
+    async fn fetch_scheduled_message(&self, id: &str) -> Result<ScheduledMessage>;
+
+    /// Fetch all messages scheduled in a channel, soonest first
+    async fn fetch_scheduled_messages(&self, channel: &str) -> Result<Vec<ScheduledMessage>>;
+
+    /// Fetch messages which were scheduled to be sent at or before the given time
+    async fn fetch_due_scheduled_messages(
+        &self,
+        now: Timestamp,
+        limit: i64,
+    ) -> Result<Vec<ScheduledMessage>>;
+
+    /// Remove a scheduled message so that it can be sent
+    ///
+    /// Returns whether it was removed, only the caller which removed it may send it.
+    async fn claim_scheduled_message(&self, id: &str) -> Result<bool>;
+}
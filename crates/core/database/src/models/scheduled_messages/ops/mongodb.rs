@@ -0,0 +1,73 @@
+use bson::to_bson;
+use iso8601_timestamp::Timestamp;
+use mongodb::options::FindOptions;
+use onechatsocial_result::Result;
+
+use crate::MongoDb;
+use crate::ScheduledMessage;
+
+use super::AbstractScheduledMessages;
+
+static COL: &str = "scheduled_messages";
+
+#[async_trait]
+impl AbstractScheduledMessages for MongoDb {
+    /// Insert a new scheduled message into the database
+    async fn insert_scheduled_message(&self, scheduled: &ScheduledMessage) -> Result<()> {
+        query!(self, insert_one, COL, &scheduled).map(|_| ())
+    }
+
+    /// Fetch a scheduled message by its id
+    async fn fetch_scheduled_message(&self, id: &str) -> Result<ScheduledMessage> {
+        query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all messages scheduled in a channel, soonest first
+    async fn fetch_scheduled_messages(&self, channel: &str) -> Result<Vec<ScheduledMessage>> {
+        query!(
+            self,
+            find_with_options,
+            COL,
+            doc! {
+                "channel": channel
+            },
+            FindOptions::builder()
+                .sort(doc! {
+                    "send_at": 1_i32
+                })
+                .build()
+        )
+    }
+
+    /// Fetch messages which were scheduled to be sent at or before the given time
+    async fn fetch_due_scheduled_messages(
+        &self,
+        now: Timestamp,
+        limit: i64,
+    ) -> Result<Vec<ScheduledMessage>> {
+        query!(
+            self,
+            find_with_options,
+            COL,
+            doc! {
+                "send_at": {
+                    "$lte": to_bson(&now)
+                        .map_err(|_| create_database_error!("to_bson", "send_at"))?
+                }
+            },
+            FindOptions::builder()
+                .sort(doc! {
+                    "send_at": 1_i32
+                })
+                .limit(limit)
+                .build()
+        )
+    }
+
+    /// Remove a scheduled message so that it can be sent
+    ///
+    /// Returns whether it was removed, only the caller which removed it may send it.
+    async fn claim_scheduled_message(&self, id: &str) -> Result<bool> {
+        query!(self, delete_one_by_id, COL, id).map(|result| result.deleted_count == 1)
+    }
+}
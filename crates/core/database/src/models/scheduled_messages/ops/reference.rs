@@ -0,0 +1,69 @@
+use iso8601_timestamp::Timestamp;
+use onechatsocial_result::Result;
+
+use crate::ReferenceDb;
+use crate::ScheduledMessage;
+
+use super::AbstractScheduledMessages;
+
+#[async_trait]
+impl AbstractScheduledMessages for ReferenceDb {
+    /// Insert a new scheduled message into the database
+    async fn insert_scheduled_message(&self, scheduled: &ScheduledMessage) -> Result<()> {
+        let mut scheduled_messages = self.scheduled_messages.lock().await;
+        if scheduled_messages.contains_key(&scheduled.id) {
+            Err(create_database_error!("insert", "scheduled_message"))
+        } else {
+            scheduled_messages.insert(scheduled.id.to_string(), scheduled.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a scheduled message by its id
+    async fn fetch_scheduled_message(&self, id: &str) -> Result<ScheduledMessage> {
+        let scheduled_messages = self.scheduled_messages.lock().await;
+        scheduled_messages
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch all messages scheduled in a channel, soonest first
+    async fn fetch_scheduled_messages(&self, channel: &str) -> Result<Vec<ScheduledMessage>> {
+        let scheduled_messages = self.scheduled_messages.lock().await;
+        let mut scheduled: Vec<ScheduledMessage> = scheduled_messages
+            .values()
+            .filter(|scheduled| scheduled.channel == channel)
+            .cloned()
+            .collect();
+
+        scheduled.sort_by_key(|scheduled| *scheduled.send_at);
+        Ok(scheduled)
+    }
+
+    /// Fetch messages which were scheduled to be sent at or before the given time
+    async fn fetch_due_scheduled_messages(
+        &self,
+        now: Timestamp,
+        limit: i64,
+    ) -> Result<Vec<ScheduledMessage>> {
+        let scheduled_messages = self.scheduled_messages.lock().await;
+        let mut scheduled: Vec<ScheduledMessage> = scheduled_messages
+            .values()
+            .filter(|scheduled| *scheduled.send_at <= *now)
+            .cloned()
+            .collect();
+
+        scheduled.sort_by_key(|scheduled| *scheduled.send_at);
+        scheduled.truncate(limit as usize);
+        Ok(scheduled)
+    }
+
+    /// Remove a scheduled message so that it can be sent
+    ///
+    /// Returns whether it was removed, only the caller which removed it may send it.
+    async fn claim_scheduled_message(&self, id: &str) -> Result<bool> {
+        let mut scheduled_messages = self.scheduled_messages.lock().await;
+        Ok(scheduled_messages.remove(id).is_some())
+    }
+}
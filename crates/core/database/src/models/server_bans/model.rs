@@ -1,4 +1,7 @@
-use crate::MemberCompositeKey;
+use iso8601_timestamp::Timestamp;
+use onechatsocial_result::Result;
+
+use crate::{events::client::EventV1, Database, MemberCompositeKey};
 
 auto_derived!(
     /// Server Ban
@@ -8,5 +11,179 @@ auto_derived!(
         pub id: MemberCompositeKey,
         /// Reason for ban creation
         pub reason: Option<String>,
+        /// Id of the user who issued the ban
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub moderator: Option<String>,
+        /// Time at which the ban is lifted
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub expires_at: Option<Timestamp>,
     }
 );
+
+impl ServerBan {
+    /// Lift this ban, allowing the user to join the server again
+    pub async fn lift(self, db: &Database) -> Result<()> {
+        db.delete_ban(&self.id).await?;
+        self.publish_unban().await;
+        Ok(())
+    }
+
+    /// Lift this ban if it has expired by the given time
+    ///
+    /// Returns whether the ban was lifted, it may have been replaced since it was fetched.
+    pub async fn lift_expired(self, db: &Database, now: Timestamp) -> Result<bool> {
+        if !db.delete_expired_ban(&self.id, now).await? {
+            return Ok(false);
+        }
+
+        self.publish_unban().await;
+        Ok(true)
+    }
+
+    /// Let everyone in the server know the user is no longer banned
+    async fn publish_unban(self) {
+        EventV1::ServerMemberUnban {
+            id: self.id.server.clone(),
+            user: self.id.user,
+        }
+        .p(self.id.server)
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use iso8601_timestamp::Timestamp;
+
+    use crate::{fixture, MemberCompositeKey, ServerBan};
+
+    #[async_std::test]
+    async fn expire_bans() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                user user 2
+                server server 4);
+
+            let ban = ServerBan {
+                id: MemberCompositeKey {
+                    server: server.id.to_string(),
+                    user: user.id.to_string(),
+                },
+                reason: Some("spam".to_string()),
+                moderator: Some(server.owner.to_string()),
+                expires_at: Some(Timestamp::from(SystemTime::now() + Duration::from_secs(60))),
+            };
+
+            db.insert_ban(&ban).await.unwrap();
+
+            let now = Timestamp::now_utc();
+            assert!(db.fetch_expired_bans(now, 10).await.unwrap().is_empty());
+
+            // Later, the ban has run out
+            let later = Timestamp::from(SystemTime::now() + Duration::from_secs(120));
+            let expired = db.fetch_expired_bans(later, 10).await.unwrap();
+            assert_eq!(expired, vec![ban.clone()]);
+
+            // Bans replaced since they were fetched are kept
+            let extended = ServerBan {
+                expires_at: Some(Timestamp::from(
+                    SystemTime::now() + Duration::from_secs(600),
+                )),
+                ..ban
+            };
+
+            db.delete_ban(&extended.id).await.unwrap();
+            db.insert_ban(&extended).await.unwrap();
+            assert!(!expired[0].clone().lift_expired(&db, later).await.unwrap());
+            assert_eq!(db.fetch_ban(&server.id, &user.id).await.unwrap(), extended);
+
+            let much_later = Timestamp::from(SystemTime::now() + Duration::from_secs(1200));
+            for ban in db.fetch_expired_bans(much_later, 10).await.unwrap() {
+                assert!(ban.lift_expired(&db, much_later).await.unwrap());
+            }
+
+            assert!(db.fetch_ban(&server.id, &user.id).await.is_err());
+        });
+    }
+
+    #[async_std::test]
+    async fn paginate_bans() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                moderator user 1
+                user user 2
+                server server 4);
+
+            for (banned, reason) in [(&moderator, Some("spam")), (&user, None)] {
+                db.insert_ban(&ServerBan {
+                    id: MemberCompositeKey {
+                        server: server.id.to_string(),
+                        user: banned.id.to_string(),
+                    },
+                    reason: reason.map(str::to_string),
+                    moderator: None,
+                    expires_at: None,
+                })
+                .await
+                .unwrap();
+            }
+
+            let mut ids = vec![moderator.id.to_string(), user.id.to_string()];
+            ids.sort();
+
+            let page = |bans: Vec<ServerBan>| {
+                bans.into_iter()
+                    .map(|ban| ban.id.user)
+                    .collect::<Vec<String>>()
+            };
+
+            let bans = db
+                .fetch_bans_page(&server.id, 10, None, None, None)
+                .await
+                .unwrap();
+            assert_eq!(page(bans), ids);
+
+            // Cursors page in either direction over the user ids
+            let bans = db
+                .fetch_bans_page(&server.id, 1, None, Some(&ids[0]), None)
+                .await
+                .unwrap();
+            assert_eq!(page(bans), vec![ids[1].clone()]);
+
+            let bans = db
+                .fetch_bans_page(&server.id, 1, Some(&ids[1]), None, None)
+                .await
+                .unwrap();
+            assert_eq!(page(bans), vec![ids[0].clone()]);
+
+            // Queries match the start of either the reason or the username
+            let bans = db
+                .fetch_bans_page(&server.id, 10, None, None, Some("SPAM"))
+                .await
+                .unwrap();
+            assert_eq!(page(bans), vec![moderator.id.to_string()]);
+
+            let bans = db
+                .fetch_bans_page(&server.id, 10, None, None, Some("user"))
+                .await
+                .unwrap();
+            assert_eq!(page(bans), vec![user.id.to_string()]);
+
+            // Text in the middle of the username is not matched
+            let bans = db
+                .fetch_bans_page(&server.id, 10, None, None, Some("ser"))
+                .await
+                .unwrap();
+            assert!(bans.is_empty());
+
+            assert_eq!(2, db.count_bans(&server.id, None, 100).await.unwrap());
+            assert_eq!(1, db.count_bans(&server.id, None, 1).await.unwrap());
+            assert_eq!(
+                1,
+                db.count_bans(&server.id, Some("user"), 100).await.unwrap()
+            );
+        });
+    }
+}
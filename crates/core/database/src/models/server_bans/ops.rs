@@ -1,3 +1,4 @@
+use iso8601_timestamp::Timestamp;
 use onechatsocial_result::Result;
 
 use crate::{MemberCompositeKey, ServerBan};
@@ -16,6 +17,29 @@ pub trait AbstractServerBans: Sync + Send {
     /// Fetch all bans in a server
     async fn fetch_bans(&self, server_id: &str) -> Result<Vec<ServerBan>>;
 
+    /// Fetch a page of bans in a server, ordered by the banned user's id
+    ///
+    /// Bans are optionally filtered by a query matching the start of the banned user's username or the reason.
+    async fn fetch_bans_page(
+        &self,
+        server_id: &str,
+        limit: i64,
+        before: Option<&str>,
+        after: Option<&str>,
+        query: Option<&str>,
+    ) -> Result<Vec<ServerBan>>;
+
+    /// Count bans in a server, optionally matching a query, up to a maximum
+    async fn count_bans(&self, server_id: &str, query: Option<&str>, max: u64) -> Result<usize>;
+
+    /// Fetch bans which have expired by the given time
+    async fn fetch_expired_bans(&self, now: Timestamp, limit: i64) -> Result<Vec<ServerBan>>;
+
     /// Delete a ban from the database
     async fn delete_ban(&self, id: &MemberCompositeKey) -> Result<()>;
+
+    /// Delete a ban from the database if it has expired by the given time
+    ///
+    /// Returns whether the ban was deleted.
+    async fn delete_expired_ban(&self, id: &MemberCompositeKey, now: Timestamp) -> Result<bool>;
 }
@@ -1,3 +1,7 @@
+use ::mongodb::options::{Collation, CollationStrength, CountOptions, FindOptions};
+use bson::{to_bson, Document};
+use futures::StreamExt;
+use iso8601_timestamp::Timestamp;
 use onechatsocial_result::Result;
 
 use crate::MongoDb;
@@ -7,6 +11,67 @@ use super::AbstractServerBans;
 
 static COL: &str = "server_bans";
 
+/// Most users matched by username when searching bans
+const USERNAME_SEARCH_SCAN: i64 = 1000;
+
+impl MongoDb {
+    /// Build a filter for bans in a server, optionally matching a query
+    ///
+    /// Queries match the start of the banned user's username, found through
+    /// the collated username index, or the start of the reason.
+    async fn ban_filter(&self, server_id: &str, query: Option<&str>) -> Result<Document> {
+        let mut filter = doc! {
+            "_id.server": server_id
+        };
+
+        let Some(query) = query else {
+            return Ok(filter);
+        };
+
+        let user_ids: Vec<String> = self
+            .col::<Document>("users")
+            .find(
+                doc! {
+                    "username": {
+                        "$gte": query,
+                        "$lt": format!("{query}\u{10FFFF}")
+                    }
+                },
+                FindOptions::builder()
+                    .collation(
+                        Collation::builder()
+                            .locale("en")
+                            .strength(CollationStrength::Secondary)
+                            .build(),
+                    )
+                    .projection(doc! { "_id": 1_i32 })
+                    .limit(USERNAME_SEARCH_SCAN)
+                    .build(),
+            )
+            .await
+            .map_err(|_| create_database_error!("find", "users"))?
+            .filter_map(|s| async { s.ok() })
+            .filter_map(|doc| async move { doc.get_str("_id").ok().map(|id| id.to_string()) })
+            .collect()
+            .await;
+
+        filter.insert(
+            "$or",
+            vec![
+                doc! { "_id.user": { "$in": user_ids } },
+                doc! {
+                    "reason": {
+                        "$regex": format!("^{}", regex::escape(query)),
+                        "$options": "i"
+                    }
+                },
+            ],
+        );
+
+        Ok(filter)
+    }
+}
+
 #[async_trait]
 impl AbstractServerBans for MongoDb {
     /// Insert new ban into database
@@ -40,6 +105,79 @@ impl AbstractServerBans for MongoDb {
         )
     }
 
+    /// Fetch a page of bans in a server, ordered by the banned user's id
+    async fn fetch_bans_page(
+        &self,
+        server_id: &str,
+        limit: i64,
+        before: Option<&str>,
+        after: Option<&str>,
+        query: Option<&str>,
+    ) -> Result<Vec<ServerBan>> {
+        let mut filter = self.ban_filter(server_id, query).await?;
+
+        let mut cursor = doc! {};
+        if let Some(before) = before {
+            cursor.insert("$lt", before);
+        }
+
+        if let Some(after) = after {
+            cursor.insert("$gt", after);
+        }
+
+        if !cursor.is_empty() {
+            filter.insert("_id.user", cursor);
+        }
+
+        // Paging backwards takes the bans closest to the cursor
+        let reverse = before.is_some() && after.is_none();
+
+        let mut bans: Vec<ServerBan> = query!(
+            self,
+            find_with_options,
+            COL,
+            filter,
+            FindOptions::builder()
+                .sort(doc! {
+                    "_id.user": if reverse { -1_i32 } else { 1_i32 }
+                })
+                .limit(limit)
+                .build()
+        )?;
+
+        if reverse {
+            bans.reverse();
+        }
+
+        Ok(bans)
+    }
+
+    /// Count bans in a server, optionally matching a query, up to a maximum
+    async fn count_bans(&self, server_id: &str, query: Option<&str>, max: u64) -> Result<usize> {
+        let filter = self.ban_filter(server_id, query).await?;
+        self.col::<Document>(COL)
+            .count_documents(filter, CountOptions::builder().limit(max).build())
+            .await
+            .map(|count| count as usize)
+            .map_err(|_| create_database_error!("count_documents", COL))
+    }
+
+    /// Fetch bans which have expired by the given time
+    async fn fetch_expired_bans(&self, now: Timestamp, limit: i64) -> Result<Vec<ServerBan>> {
+        query!(
+            self,
+            find_with_options,
+            COL,
+            doc! {
+                "expires_at": {
+                    "$lte": to_bson(&now)
+                        .map_err(|_| create_database_error!("to_bson", "expires_at"))?
+                }
+            },
+            FindOptions::builder().limit(limit).build()
+        )
+    }
+
     /// Delete a ban from the database
     async fn delete_ban(&self, id: &MemberCompositeKey) -> Result<()> {
         query!(
@@ -53,4 +191,24 @@ impl AbstractServerBans for MongoDb {
         )
         .map(|_| ())
     }
+
+    /// Delete a ban from the database if it has expired by the given time
+    ///
+    /// Returns whether the ban was deleted.
+    async fn delete_expired_ban(&self, id: &MemberCompositeKey, now: Timestamp) -> Result<bool> {
+        query!(
+            self,
+            delete_one,
+            COL,
+            doc! {
+                "_id.server": &id.server,
+                "_id.user": &id.user,
+                "expires_at": {
+                    "$lte": to_bson(&now)
+                        .map_err(|_| create_database_error!("to_bson", "expires_at"))?
+                }
+            }
+        )
+        .map(|result| result.deleted_count == 1)
+    }
 }
@@ -1,10 +1,27 @@
+use std::collections::HashMap;
+
+use iso8601_timestamp::Timestamp;
 use onechatsocial_result::Result;
 
 use crate::ReferenceDb;
-use crate::{MemberCompositeKey, ServerBan};
+use crate::{MemberCompositeKey, ServerBan, User};
 
 use super::AbstractServerBans;
 
+/// Check whether the banned user's username or the reason start with a query
+fn matches_query(ban: &ServerBan, users: &HashMap<String, User>, query: Option<&str>) -> bool {
+    let Some(query) = query.map(str::to_lowercase) else {
+        return true;
+    };
+
+    ban.reason
+        .as_ref()
+        .is_some_and(|reason| reason.to_lowercase().starts_with(&query))
+        || users
+            .get(&ban.id.user)
+            .is_some_and(|user| user.username.to_lowercase().starts_with(&query))
+}
+
 #[async_trait]
 impl AbstractServerBans for ReferenceDb {
     /// Insert new ban into database
@@ -40,6 +57,63 @@ impl AbstractServerBans for ReferenceDb {
             .collect())
     }
 
+    /// Fetch a page of bans in a server, ordered by the banned user's id
+    async fn fetch_bans_page(
+        &self,
+        server_id: &str,
+        limit: i64,
+        before: Option<&str>,
+        after: Option<&str>,
+        query: Option<&str>,
+    ) -> Result<Vec<ServerBan>> {
+        let server_bans = self.server_bans.lock().await;
+        let users = self.users.lock().await;
+
+        let mut bans: Vec<ServerBan> = server_bans
+            .values()
+            .filter(|ban| ban.id.server == server_id)
+            .filter(|ban| before.map_or(true, |before| ban.id.user.as_str() < before))
+            .filter(|ban| after.map_or(true, |after| ban.id.user.as_str() > after))
+            .filter(|ban| matches_query(ban, &users, query))
+            .cloned()
+            .collect();
+
+        bans.sort_by(|a, b| a.id.user.cmp(&b.id.user));
+
+        // Paging backwards takes the bans closest to the cursor
+        let limit = limit as usize;
+        if before.is_some() && after.is_none() {
+            Ok(bans.split_off(bans.len().saturating_sub(limit)))
+        } else {
+            bans.truncate(limit);
+            Ok(bans)
+        }
+    }
+
+    /// Count bans in a server, optionally matching a query, up to a maximum
+    async fn count_bans(&self, server_id: &str, query: Option<&str>, max: u64) -> Result<usize> {
+        let server_bans = self.server_bans.lock().await;
+        let users = self.users.lock().await;
+
+        Ok(server_bans
+            .values()
+            .filter(|ban| ban.id.server == server_id)
+            .filter(|ban| matches_query(ban, &users, query))
+            .take(max as usize)
+            .count())
+    }
+
+    /// Fetch bans which have expired by the given time
+    async fn fetch_expired_bans(&self, now: Timestamp, limit: i64) -> Result<Vec<ServerBan>> {
+        let server_bans = self.server_bans.lock().await;
+        Ok(server_bans
+            .values()
+            .filter(|ban| ban.expires_at.is_some_and(|expires_at| *expires_at <= *now))
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
     /// Delete a ban from the database
     async fn delete_ban(&self, id: &MemberCompositeKey) -> Result<()> {
         let mut server_bans = self.server_bans.lock().await;
@@ -49,4 +123,20 @@ impl AbstractServerBans for ReferenceDb {
             Err(create_error!(NotFound))
         }
     }
+
+    /// Delete a ban from the database if it has expired by the given time
+    ///
+    /// Returns whether the ban was deleted.
+    async fn delete_expired_ban(&self, id: &MemberCompositeKey, now: Timestamp) -> Result<bool> {
+        let mut server_bans = self.server_bans.lock().await;
+        if server_bans
+            .get(id)
+            .is_some_and(|ban| ban.expires_at.is_some_and(|expires_at| *expires_at <= *now))
+        {
+            server_bans.remove(id);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
 }
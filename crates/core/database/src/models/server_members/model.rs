@@ -1,4 +1,5 @@
 use iso8601_timestamp::Timestamp;
+use onechatsocial_models::v0::ServerWebhookEvent;
 use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
 use onechatsocial_result::{create_error, Result};
 
@@ -30,6 +31,9 @@ auto_derived_partial!(
         /// Timestamp this member is timed out until
         #[serde(skip_serializing_if = "Option::is_none")]
         pub timeout: Option<Timestamp>,
+        /// Roles which are removed from this member once they expire
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub timed_roles: Option<Vec<TimedRole>>,
     },
     "PartialMember"
 );
@@ -44,12 +48,21 @@ auto_derived!(
         pub user: String,
     }
 
+    /// Role assigned to a member until a given time
+    pub struct TimedRole {
+        /// Role id
+        pub role: String,
+        /// Time at which the role is removed
+        pub expires_at: Timestamp,
+    }
+
     /// Optional fields on server member object
     pub enum FieldsMember {
         Nickname,
         Avatar,
         Roles,
         Timeout,
+        TimedRoles,
     }
 
     /// Member removal intention
@@ -69,6 +82,7 @@ impl Default for Member {
             avatar: None,
             roles: vec![],
             timeout: None,
+            timed_roles: None,
         }
     }
 }
@@ -141,6 +155,17 @@ impl Member {
         .private(user.id.clone())
         .await;
 
+        if let Some(webhook) = &server.event_webhook {
+            crate::tasks::server_webhook::queue(
+                webhook.clone(),
+                ServerWebhookEvent::MemberJoin {
+                    server: server.id.clone(),
+                    user: user.id.clone(),
+                },
+            )
+            .await;
+        }
+
         if let Some(id) = server
             .system_messages
             .as_ref()
@@ -190,6 +215,7 @@ impl Member {
             FieldsMember::Nickname => self.nickname = None,
             FieldsMember::Roles => self.roles.clear(),
             FieldsMember::Timeout => self.timeout = None,
+            FieldsMember::TimedRoles => self.timed_roles = None,
         }
     }
 
@@ -207,6 +233,16 @@ impl Member {
         value
     }
 
+    /// Get the icon of this member's highest ranking hoisted role which has one
+    pub fn get_role_icon<'a>(&self, server: &'a Server) -> Option<&'a File> {
+        self.roles
+            .iter()
+            .filter_map(|role| server.roles.get(role))
+            .filter(|role| role.hoist && role.icon.is_some())
+            .min_by_key(|role| role.rank)
+            .and_then(|role| role.icon.as_ref())
+    }
+
     /// Check whether this member is in timeout
     pub fn in_timeout(&self) -> bool {
         if let Some(timeout) = self.timeout {
@@ -215,4 +251,280 @@ impl Member {
             false
         }
     }
+
+    /// Remove any timed roles which have expired by the given time
+    pub async fn expire_roles(&mut self, db: &Database, now: Timestamp) -> Result<()> {
+        let expired: Vec<TimedRole> = self
+            .timed_roles
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|timed_role| *timed_role.expires_at <= *now)
+            .collect();
+
+        if expired.is_empty() {
+            return Ok(());
+        }
+
+        // Roles may have since been taken away by other means
+        let removed: Vec<String> = expired
+            .into_iter()
+            .map(|timed_role| timed_role.role)
+            .filter(|role| self.roles.contains(role))
+            .collect();
+
+        // Only pull the expired roles so concurrent role changes are kept
+        db.remove_expired_member_roles(&self.id, &removed, now)
+            .await?;
+
+        // Announce the roles as they are now rather than as they were fetched
+        *self = db.fetch_member(&self.id.server, &self.id.user).await?;
+
+        let mut partial = PartialMember::default();
+        let mut remove = vec![];
+
+        if !removed.is_empty() {
+            partial.roles = Some(self.roles.clone());
+        }
+
+        match &self.timed_roles {
+            Some(timed_roles) if !timed_roles.is_empty() => {
+                partial.timed_roles = Some(timed_roles.clone());
+            }
+            _ => remove.push(FieldsMember::TimedRoles),
+        }
+
+        // Publishing the update invalidates any permissions calculated with these roles
+        EventV1::ServerMemberUpdate {
+            id: self.id.clone().into(),
+            data: partial.into(),
+            clear: remove.into_iter().map(|field| field.into()).collect(),
+        }
+        .p(self.id.server.clone())
+        .await;
+
+        if removed.is_empty() {
+            return Ok(());
+        }
+
+        let server = db.fetch_server(&self.id.server).await?;
+        if let Some(id) = server
+            .system_messages
+            .as_ref()
+            .and_then(|x| x.role_expired.as_ref())
+        {
+            for role in removed {
+                SystemMessage::RoleExpired {
+                    id: self.id.user.clone(),
+                    role,
+                }
+                .into_message(id.to_string())
+                .send_without_notifications(db, false, false)
+                .await
+                .ok();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use iso8601_timestamp::Timestamp;
+
+    use crate::{
+        fixture, MemberCompositeKey, MessageFilter, MessageQuery, MessageTimePeriod, PartialMember,
+        PartialServer, SystemMessage, SystemMessageChannels, TimedRole,
+    };
+
+    #[async_std::test]
+    async fn search_by_prefix() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                moderator user 1
+                user user 2
+                server server 4);
+
+            db.update_member(
+                &MemberCompositeKey {
+                    server: server.id.clone(),
+                    user: user.id.clone(),
+                },
+                &PartialMember {
+                    nickname: Some("Modest".to_string()),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+            let members = db.search_members(&server.id, "mod", 10).await.unwrap();
+            assert_eq!(members.len(), 2);
+
+            // Nickname matches come first
+            assert_eq!(members[0].id.user, user.id);
+            assert_eq!(members[1].id.user, moderator.id);
+
+            let members = db.search_members(&server.id, "MOD", 1).await.unwrap();
+            assert_eq!(members.len(), 1);
+
+            let members = db.search_members(&server.id, "nobody", 10).await.unwrap();
+            assert!(members.is_empty());
+        });
+    }
+
+    #[async_std::test]
+    async fn paginate_members() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                moderator user 1
+                server server 4);
+
+            let all = db
+                .fetch_members_page(&server.id, None, None, None)
+                .await
+                .unwrap();
+
+            assert_eq!(all.len(), 3);
+            assert!(all.windows(2).all(|pair| pair[0].id.user < pair[1].id.user));
+
+            let first = db
+                .fetch_members_page(&server.id, None, None, Some(2))
+                .await
+                .unwrap();
+
+            let rest = db
+                .fetch_members_page(&server.id, Some(&first[1].id.user), None, Some(2))
+                .await
+                .unwrap();
+
+            assert_eq!(first.len(), 2);
+            assert_eq!(rest.len(), 1);
+            assert_eq!([first, rest].concat(), all);
+
+            let role = all
+                .iter()
+                .find(|member| member.id.user == moderator.id)
+                .unwrap()
+                .roles[0]
+                .clone();
+
+            let members = db
+                .fetch_members_page(&server.id, None, Some(&role), None)
+                .await
+                .unwrap();
+
+            assert_eq!(members.len(), 1);
+            assert_eq!(members[0].id.user, moderator.id);
+        });
+    }
+
+    #[async_std::test]
+    async fn expire_roles() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                moderator user 1
+                server server 4
+                channel channel 3);
+
+            db.update_server(
+                &server.id,
+                &PartialServer {
+                    system_messages: Some(SystemMessageChannels {
+                        user_joined: None,
+                        user_left: None,
+                        user_kicked: None,
+                        user_banned: None,
+                        role_expired: Some(channel.id()),
+                    }),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+            let id = MemberCompositeKey {
+                server: server.id.clone(),
+                user: moderator.id.clone(),
+            };
+
+            let role = db.fetch_member(&id.server, &id.user).await.unwrap().roles[0].clone();
+            let now = Timestamp::now_utc();
+            let later = TimedRole {
+                role: "other".to_string(),
+                expires_at: Timestamp::from(SystemTime::now() + Duration::from_secs(60)),
+            };
+
+            db.update_member(
+                &id,
+                &PartialMember {
+                    timed_roles: Some(vec![
+                        TimedRole {
+                            role: role.clone(),
+                            expires_at: Timestamp::from(SystemTime::now() - Duration::from_secs(1)),
+                        },
+                        later.clone(),
+                    ]),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+            let mut members = db.fetch_members_with_expired_roles(now, 10).await.unwrap();
+            assert_eq!(members.len(), 1);
+
+            // Roles granted after the member was fetched are kept
+            db.update_member(
+                &id,
+                &PartialMember {
+                    roles: Some(vec![role.clone(), "granted".to_string()]),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+            // Only the expired role is removed
+            members[0].expire_roles(&db, now).await.unwrap();
+
+            let member = db.fetch_member(&id.server, &id.user).await.unwrap();
+            assert_eq!(vec!["granted".to_string()], member.roles);
+            assert_eq!(Some(vec![later]), member.timed_roles);
+            assert!(db
+                .fetch_members_with_expired_roles(now, 10)
+                .await
+                .unwrap()
+                .is_empty());
+
+            // The removal is announced in the server
+            let messages = db
+                .fetch_messages(MessageQuery {
+                    limit: Some(50),
+                    filter: MessageFilter {
+                        channel: Some(channel.id()),
+                        ..Default::default()
+                    },
+                    time_period: MessageTimePeriod::Absolute {
+                        before: None,
+                        after: None,
+                        sort: None,
+                    },
+                })
+                .await
+                .unwrap();
+
+            assert!(messages.iter().any(|message| matches!(
+                &message.system,
+                Some(SystemMessage::RoleExpired { id, role: expired }) if id == &moderator.id && expired == &role
+            )));
+        });
+    }
 }
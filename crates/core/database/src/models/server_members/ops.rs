@@ -1,3 +1,4 @@
+use iso8601_timestamp::Timestamp;
 use onechatsocial_result::Result;
 
 use crate::{FieldsMember, Member, MemberCompositeKey, PartialMember};
@@ -16,12 +17,38 @@ pub trait AbstractServerMembers: Sync + Send {
     /// Fetch all members in a server
     async fn fetch_all_members<'a>(&self, server_id: &str) -> Result<Vec<Member>>;
 
+    /// Fetch members of a server ordered by user id
+    ///
+    /// Starts after the given user id and optionally only includes members with a given role.
+    async fn fetch_members_page(
+        &self,
+        server_id: &str,
+        after: Option<&str>,
+        role: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Member>>;
+
     /// Fetch all memberships for a user
     async fn fetch_all_memberships<'a>(&self, user_id: &str) -> Result<Vec<Member>>;
 
     /// Fetch multiple members by their ids
     async fn fetch_members<'a>(&self, server_id: &str, ids: &'a [String]) -> Result<Vec<Member>>;
 
+    /// Search for members of a server whose nickname or username starts with a given prefix
+    async fn search_members(
+        &self,
+        server_id: &str,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Member>>;
+
+    /// Fetch members with timed roles which have expired by the given time
+    async fn fetch_members_with_expired_roles(
+        &self,
+        now: Timestamp,
+        limit: i64,
+    ) -> Result<Vec<Member>>;
+
     /// Fetch member count of a server
     async fn fetch_member_count(&self, server_id: &str) -> Result<usize>;
 
@@ -36,6 +63,15 @@ pub trait AbstractServerMembers: Sync + Send {
         remove: Vec<FieldsMember>,
     ) -> Result<()>;
 
+    /// Remove timed roles which expired by the given time from a server member,
+    /// along with the given roles they granted
+    async fn remove_expired_member_roles(
+        &self,
+        id: &MemberCompositeKey,
+        roles: &[String],
+        now: Timestamp,
+    ) -> Result<()>;
+
     /// Delete a server member by their id
     async fn delete_member(&self, id: &MemberCompositeKey) -> Result<()>;
 }
@@ -1,4 +1,7 @@
+use ::mongodb::options::{Collation, CollationStrength, FindOptions};
+use bson::{to_bson, Document};
 use futures::StreamExt;
+use iso8601_timestamp::Timestamp;
 use onechatsocial_result::Result;
 
 use crate::{FieldsMember, Member, MemberCompositeKey, PartialMember};
@@ -8,6 +11,9 @@ use super::AbstractServerMembers;
 
 static COL: &str = "server_members";
 
+/// Most users matched by username while searching for members of a server
+const USERNAME_SEARCH_SCAN: i64 = 1000;
+
 #[async_trait]
 impl AbstractServerMembers for MongoDb {
     /// Insert a new server member into the database
@@ -52,6 +58,57 @@ impl AbstractServerMembers for MongoDb {
             .await)
     }
 
+    /// Fetch members of a server ordered by user id
+    ///
+    /// Starts after the given user id and optionally only includes members with a given role.
+    async fn fetch_members_page(
+        &self,
+        server_id: &str,
+        after: Option<&str>,
+        role: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Member>> {
+        let mut filter = doc! {
+            "_id.server": server_id
+        };
+
+        if let Some(after) = after {
+            filter.insert(
+                "_id.user",
+                doc! {
+                    "$gt": after
+                },
+            );
+        }
+
+        if let Some(role) = role {
+            filter.insert("roles", role);
+        }
+
+        Ok(self
+            .col::<Member>(COL)
+            .find(
+                filter,
+                FindOptions::builder()
+                    .sort(doc! {
+                        "_id.user": 1_i32
+                    })
+                    .limit(limit.map(|limit| limit as i64))
+                    .build(),
+            )
+            .await
+            .map_err(|_| create_database_error!("find", COL))?
+            .filter_map(|s| async {
+                if cfg!(debug_assertions) {
+                    Some(s.unwrap())
+                } else {
+                    s.ok()
+                }
+            })
+            .collect()
+            .await)
+    }
+
     /// Fetch all memberships for a user
     async fn fetch_all_memberships<'a>(&self, user_id: &str) -> Result<Vec<Member>> {
         Ok(self
@@ -101,6 +158,121 @@ impl AbstractServerMembers for MongoDb {
             .await)
     }
 
+    /// Search for members of a server whose nickname or username starts with a given prefix
+    async fn search_members(
+        &self,
+        server_id: &str,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Member>> {
+        // Case-insensitive range queries, served by the collated indexes
+        let collation = Collation::builder()
+            .locale("en")
+            .strength(CollationStrength::Secondary)
+            .build();
+
+        let upper_bound = format!("{prefix}\u{10FFFF}");
+
+        let mut members: Vec<Member> = self
+            .col::<Member>(COL)
+            .find(
+                doc! {
+                    "_id.server": server_id,
+                    "nickname": {
+                        "$gte": prefix,
+                        "$lt": &upper_bound
+                    }
+                },
+                FindOptions::builder()
+                    .collation(collation.clone())
+                    .limit(limit as i64)
+                    .build(),
+            )
+            .await
+            .map_err(|_| create_database_error!("find", COL))?
+            .filter_map(|s| async { s.ok() })
+            .collect()
+            .await;
+
+        if members.len() >= limit {
+            return Ok(members);
+        }
+
+        // Find users by username through the collated index, then keep those who are members
+        let found: Vec<String> = members
+            .iter()
+            .map(|member| member.id.user.to_string())
+            .collect();
+
+        let user_ids: Vec<String> = self
+            .col::<Document>("users")
+            .find(
+                doc! {
+                    "_id": {
+                        "$nin": found
+                    },
+                    "username": {
+                        "$gte": prefix,
+                        "$lt": &upper_bound
+                    }
+                },
+                FindOptions::builder()
+                    .collation(collation)
+                    .projection(doc! { "_id": 1_i32 })
+                    .limit(USERNAME_SEARCH_SCAN)
+                    .build(),
+            )
+            .await
+            .map_err(|_| create_database_error!("find", "users"))?
+            .filter_map(|s| async { s.ok() })
+            .filter_map(|doc| async move { doc.get_str("_id").ok().map(|id| id.to_string()) })
+            .collect()
+            .await;
+
+        let by_username: Vec<Member> = self
+            .col::<Member>(COL)
+            .find(
+                doc! {
+                    "_id.server": server_id,
+                    "_id.user": {
+                        "$in": user_ids
+                    }
+                },
+                FindOptions::builder()
+                    .limit((limit - members.len()) as i64)
+                    .build(),
+            )
+            .await
+            .map_err(|_| create_database_error!("find", COL))?
+            .filter_map(|s| async { s.ok() })
+            .collect()
+            .await;
+
+        members.extend(by_username);
+        members.truncate(limit);
+        Ok(members)
+    }
+
+    /// Fetch members with timed roles which have expired by the given time
+    async fn fetch_members_with_expired_roles(
+        &self,
+        now: Timestamp,
+        limit: i64,
+    ) -> Result<Vec<Member>> {
+        query!(
+            self,
+            find_with_options,
+            COL,
+            doc! {
+                "timed_roles.expires_at": {
+                    "$lte": to_bson(&now)
+                        .map_err(|_| create_database_error!("to_bson", "expires_at"))?
+                }
+            },
+            FindOptions::builder().limit(limit).build()
+        )
+    }
+
     /// Fetch member count of a server
     async fn fetch_member_count(&self, server_id: &str) -> Result<usize> {
         self.col::<Member>(COL)
@@ -151,6 +323,40 @@ impl AbstractServerMembers for MongoDb {
         .map(|_| ())
     }
 
+    /// Remove timed roles which expired by the given time from a server member,
+    /// along with the given roles they granted
+    async fn remove_expired_member_roles(
+        &self,
+        id: &MemberCompositeKey,
+        roles: &[String],
+        now: Timestamp,
+    ) -> Result<()> {
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id.server": &id.server,
+                    "_id.user": &id.user
+                },
+                doc! {
+                    "$pull": {
+                        "roles": {
+                            "$in": roles
+                        },
+                        "timed_roles": {
+                            "expires_at": {
+                                "$lte": to_bson(&now)
+                                    .map_err(|_| create_database_error!("to_bson", "expires_at"))?
+                            }
+                        }
+                    }
+                },
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", COL))
+    }
+
     /// Delete a server member by their id
     async fn delete_member(&self, id: &MemberCompositeKey) -> Result<()> {
         query!(
@@ -173,6 +379,7 @@ impl IntoDocumentPath for FieldsMember {
             FieldsMember::Nickname => "nickname",
             FieldsMember::Roles => "roles",
             FieldsMember::Timeout => "timeout",
+            FieldsMember::TimedRoles => "timed_roles",
         })
     }
 }
@@ -1,3 +1,4 @@
+use iso8601_timestamp::Timestamp;
 use onechatsocial_result::Result;
 
 use crate::ReferenceDb;
@@ -40,6 +41,30 @@ impl AbstractServerMembers for ReferenceDb {
             .collect())
     }
 
+    /// Fetch members of a server ordered by user id
+    ///
+    /// Starts after the given user id and optionally only includes members with a given role.
+    async fn fetch_members_page(
+        &self,
+        server_id: &str,
+        after: Option<&str>,
+        role: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Member>> {
+        let server_members = self.server_members.lock().await;
+        let mut members: Vec<Member> = server_members
+            .values()
+            .filter(|member| member.id.server == server_id)
+            .filter(|member| after.map_or(true, |after| member.id.user.as_str() > after))
+            .filter(|member| role.map_or(true, |role| member.roles.iter().any(|r| r == role)))
+            .cloned()
+            .collect();
+
+        members.sort_by(|a, b| a.id.user.cmp(&b.id.user));
+        members.truncate(limit.unwrap_or(members.len()));
+        Ok(members)
+    }
+
     /// Fetch all memberships for a user
     async fn fetch_all_memberships<'a>(&self, user_id: &str) -> Result<Vec<Member>> {
         let server_members = self.server_members.lock().await;
@@ -66,6 +91,61 @@ impl AbstractServerMembers for ReferenceDb {
             .collect()
     }
 
+    /// Search for members of a server whose nickname or username starts with a given prefix
+    async fn search_members(
+        &self,
+        server_id: &str,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Member>> {
+        let prefix = prefix.to_lowercase();
+        let server_members = self.server_members.lock().await;
+        let users = self.users.lock().await;
+
+        let (mut by_nickname, by_username): (Vec<&Member>, Vec<&Member>) = server_members
+            .values()
+            .filter(|member| member.id.server == server_id)
+            .filter(|member| {
+                member
+                    .nickname
+                    .as_ref()
+                    .is_some_and(|nickname| nickname.to_lowercase().starts_with(&prefix))
+                    || users
+                        .get(&member.id.user)
+                        .is_some_and(|user| user.username.to_lowercase().starts_with(&prefix))
+            })
+            .partition(|member| {
+                member
+                    .nickname
+                    .as_ref()
+                    .is_some_and(|nickname| nickname.to_lowercase().starts_with(&prefix))
+            });
+
+        by_nickname.extend(by_username);
+        Ok(by_nickname.into_iter().take(limit).cloned().collect())
+    }
+
+    /// Fetch members with timed roles which have expired by the given time
+    async fn fetch_members_with_expired_roles(
+        &self,
+        now: Timestamp,
+        limit: i64,
+    ) -> Result<Vec<Member>> {
+        let server_members = self.server_members.lock().await;
+        Ok(server_members
+            .values()
+            .filter(|member| {
+                member.timed_roles.as_ref().is_some_and(|timed_roles| {
+                    timed_roles
+                        .iter()
+                        .any(|timed_role| *timed_role.expires_at <= *now)
+                })
+            })
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
     /// Fetch member count of a server
     async fn fetch_member_count(&self, server_id: &str) -> Result<usize> {
         let server_members = self.server_members.lock().await;
@@ -105,6 +185,27 @@ impl AbstractServerMembers for ReferenceDb {
         }
     }
 
+    /// Remove timed roles which expired by the given time from a server member,
+    /// along with the given roles they granted
+    async fn remove_expired_member_roles(
+        &self,
+        id: &MemberCompositeKey,
+        roles: &[String],
+        now: Timestamp,
+    ) -> Result<()> {
+        let mut server_members = self.server_members.lock().await;
+        if let Some(member) = server_members.get_mut(id) {
+            member.roles.retain(|role| !roles.contains(role));
+            if let Some(timed_roles) = member.timed_roles.as_mut() {
+                timed_roles.retain(|timed_role| *timed_role.expires_at > *now);
+            }
+
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
     /// Delete a server member by their id
     async fn delete_member(&self, id: &MemberCompositeKey) -> Result<()> {
         let mut server_members = self.server_members.lock().await;
@@ -0,0 +1,109 @@
+use onechatsocial_result::{Error, ErrorType, Result};
+
+use crate::Database;
+
+auto_derived!(
+    /// Words which may not appear in channel names, role names or nicknames within a server
+    pub struct ServerNameFilter {
+        /// Server Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Disallowed words
+        pub words: Vec<String>,
+    }
+);
+
+impl ServerNameFilter {
+    /// Check whether a name contains any disallowed word
+    pub fn matches(&self, name: &str) -> bool {
+        let name = normalise_name(name);
+        self.words
+            .iter()
+            .map(|word| normalise_name(word))
+            .any(|word| !word.is_empty() && name.contains(&word))
+    }
+
+    /// Ensure a name is allowed by a server's filter, if it has one
+    pub async fn validate_name(db: &Database, server_id: &str, name: &str) -> Result<()> {
+        match db.fetch_server_name_filter(server_id).await {
+            Ok(filter) => {
+                if filter.matches(name) {
+                    Err(create_error!(InvalidProperty))
+                } else {
+                    Ok(())
+                }
+            }
+            Err(Error {
+                error_type: ErrorType::NotFound,
+                ..
+            }) => Ok(()),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+/// Reduce a name to a comparable form
+///
+/// Confusable characters and diacritics are folded to ASCII, common
+/// leetspeak substitutions are reversed and anything other than letters
+/// is dropped so that separators cannot be used to split up a word.
+pub fn normalise_name(name: &str) -> String {
+    decancer::cure(name)
+        .into_str()
+        .chars()
+        .filter_map(|c| match c {
+            '0' => Some('o'),
+            '1' | '!' | '|' => Some('i'),
+            '3' => Some('e'),
+            '4' | '@' => Some('a'),
+            '5' | '$' => Some('s'),
+            '7' => Some('t'),
+            '8' => Some('b'),
+            '9' => Some('g'),
+            c if c.is_alphabetic() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ServerNameFilter;
+
+    #[test]
+    fn matches_evasions() {
+        let filter = ServerNameFilter {
+            id: "server".to_string(),
+            words: vec!["Spam".to_string()],
+        };
+
+        assert!(filter.matches("spam-channel"));
+        assert!(filter.matches("$P4M"));
+        assert!(filter.matches("s.p.a.m"));
+        assert!(filter.matches("špàm"));
+        assert!(!filter.matches("general"));
+    }
+
+    #[async_std::test]
+    async fn validate_name() {
+        database_test!(|db| async move {
+            assert!(ServerNameFilter::validate_name(&db, "server", "spam")
+                .await
+                .is_ok());
+
+            db.upsert_server_name_filter(&ServerNameFilter {
+                id: "server".to_string(),
+                words: vec!["spam".to_string()],
+            })
+            .await
+            .unwrap();
+
+            assert!(ServerNameFilter::validate_name(&db, "server", "sp4m")
+                .await
+                .is_err());
+            assert!(ServerNameFilter::validate_name(&db, "server", "general")
+                .await
+                .is_ok());
+        });
+    }
+}
@@ -0,0 +1,18 @@
+use onechatsocial_result::Result;
+
+use crate::ServerNameFilter;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractServerNameFilters: Sync + Send {
+    /// Fetch the name filter for a server
+    async fn fetch_server_name_filter(&self, server_id: &str) -> Result<ServerNameFilter>;
+
+    /// Insert or replace the name filter for a server
+    async fn upsert_server_name_filter(&self, filter: &ServerNameFilter) -> Result<()>;
+
+    /// Delete the name filter for a server
+    async fn delete_server_name_filter(&self, server_id: &str) -> Result<()>;
+}
@@ -0,0 +1,37 @@
+use mongodb::options::ReplaceOptions;
+use onechatsocial_result::Result;
+
+use crate::MongoDb;
+use crate::ServerNameFilter;
+
+use super::AbstractServerNameFilters;
+
+static COL: &str = "server_name_filters";
+
+#[async_trait]
+impl AbstractServerNameFilters for MongoDb {
+    /// Fetch the name filter for a server
+    async fn fetch_server_name_filter(&self, server_id: &str) -> Result<ServerNameFilter> {
+        query!(self, find_one_by_id, COL, server_id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Insert or replace the name filter for a server
+    async fn upsert_server_name_filter(&self, filter: &ServerNameFilter) -> Result<()> {
+        self.col::<ServerNameFilter>(COL)
+            .replace_one(
+                doc! {
+                    "_id": &filter.id
+                },
+                filter,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("replace_one", COL))
+    }
+
+    /// Delete the name filter for a server
+    async fn delete_server_name_filter(&self, server_id: &str) -> Result<()> {
+        query!(self, delete_one_by_id, COL, server_id).map(|_| ())
+    }
+}
@@ -0,0 +1,32 @@
+use onechatsocial_result::Result;
+
+use crate::ReferenceDb;
+use crate::ServerNameFilter;
+
+use super::AbstractServerNameFilters;
+
+#[async_trait]
+impl AbstractServerNameFilters for ReferenceDb {
+    /// Fetch the name filter for a server
+    async fn fetch_server_name_filter(&self, server_id: &str) -> Result<ServerNameFilter> {
+        let server_name_filters = self.server_name_filters.lock().await;
+        server_name_filters
+            .get(server_id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Insert or replace the name filter for a server
+    async fn upsert_server_name_filter(&self, filter: &ServerNameFilter) -> Result<()> {
+        let mut server_name_filters = self.server_name_filters.lock().await;
+        server_name_filters.insert(filter.id.to_string(), filter.clone());
+        Ok(())
+    }
+
+    /// Delete the name filter for a server
+    async fn delete_server_name_filter(&self, server_id: &str) -> Result<()> {
+        let mut server_name_filters = self.server_name_filters.lock().await;
+        server_name_filters.remove(server_id);
+        Ok(())
+    }
+}
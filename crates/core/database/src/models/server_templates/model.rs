@@ -0,0 +1,406 @@
+use std::collections::{HashMap, HashSet};
+
+use onechatsocial_config::config;
+use onechatsocial_models::v0;
+use onechatsocial_permissions::OverrideField;
+use onechatsocial_result::Result;
+use ulid::Ulid;
+
+use crate::{Category, Channel, Database, PartialServer, Role, Server, User, DEFAULT_ROLE_ID};
+
+static ALPHABET: [char; 54] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'J',
+    'K', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'V', 'W', 'X', 'Y', 'Z', 'a', 'b', 'c', 'd', 'e', 'f',
+    'g', 'h', 'j', 'k', 'm', 'n', 'p', 'q', 'r', 's', 't', 'v', 'w', 'x', 'y', 'z',
+];
+
+auto_derived!(
+    /// Snapshot of a server's structure which new servers can be created from
+    pub struct ServerTemplate {
+        /// Code used to share this template
+        #[serde(rename = "_id")]
+        pub code: String,
+        /// Id of the server this template was taken from
+        pub server: String,
+        /// Id of the user who created this template
+        pub creator: String,
+        /// Name of the server when this template was taken
+        pub name: String,
+        /// Default set of server and channel permissions
+        pub default_permissions: i64,
+        /// Roles, keyed by their id in the template
+        #[serde(
+            default = "HashMap::<String, Role>::new",
+            skip_serializing_if = "HashMap::<String, Role>::is_empty"
+        )]
+        pub roles: HashMap<String, Role>,
+        /// Channels, in the order they appeared in the server
+        pub channels: Vec<TemplateChannel>,
+        /// Categories, referring to channels by their id in the template
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub categories: Vec<Category>,
+    }
+
+    /// Channel within a server template
+    pub struct TemplateChannel {
+        /// Id of the channel in the template
+        pub id: String,
+        /// Channel type
+        #[serde(rename = "type")]
+        pub channel_type: TemplateChannelType,
+        /// Display name of the channel
+        pub name: String,
+        /// Channel description
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub description: Option<String>,
+        /// Whether this channel is marked as not safe for work
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub nsfw: bool,
+        /// Default permissions assigned to users in this channel
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub default_permissions: Option<OverrideField>,
+        /// Permissions assigned to roles in this channel, keyed by their id in the template
+        #[serde(
+            default = "HashMap::<String, OverrideField>::new",
+            skip_serializing_if = "HashMap::<String, OverrideField>::is_empty"
+        )]
+        pub role_permissions: HashMap<String, OverrideField>,
+    }
+
+    /// Type of channel within a server template
+    pub enum TemplateChannelType {
+        Text,
+        Voice,
+    }
+);
+
+impl ServerTemplate {
+    /// Take a snapshot of a server's channels, categories, roles and default permissions
+    pub async fn create(db: &Database, server: &Server, creator: &str) -> Result<ServerTemplate> {
+        let channels: Vec<TemplateChannel> = db
+            .fetch_channels(&server.channels)
+            .await?
+            .into_iter()
+            .filter_map(|channel| match channel {
+                Channel::TextChannel {
+                    id,
+                    name,
+                    description,
+                    nsfw,
+                    default_permissions,
+                    role_permissions,
+                    ..
+                } => Some(TemplateChannel {
+                    id,
+                    channel_type: TemplateChannelType::Text,
+                    name,
+                    description,
+                    nsfw,
+                    default_permissions,
+                    role_permissions,
+                }),
+                Channel::VoiceChannel {
+                    id,
+                    name,
+                    description,
+                    nsfw,
+                    default_permissions,
+                    role_permissions,
+                    ..
+                } => Some(TemplateChannel {
+                    id,
+                    channel_type: TemplateChannelType::Voice,
+                    name,
+                    description,
+                    nsfw,
+                    default_permissions,
+                    role_permissions,
+                }),
+                _ => None,
+            })
+            .collect();
+
+        let template = ServerTemplate {
+            code: nanoid::nanoid!(8, &ALPHABET),
+            server: server.id.to_string(),
+            creator: creator.to_string(),
+            name: server.name.to_string(),
+            default_permissions: server.default_permissions,
+            // Role icons belong to the original server
+            roles: server
+                .roles
+                .iter()
+                .map(|(id, role)| {
+                    (
+                        id.clone(),
+                        Role {
+                            icon: None,
+                            ..role.clone()
+                        },
+                    )
+                })
+                .collect(),
+            channels,
+            categories: server.categories.clone().unwrap_or_default(),
+        };
+
+        db.insert_server_template(&template).await?;
+        Ok(template)
+    }
+
+    /// Create a new server owned by the given user from this template
+    ///
+    /// Everything is given a new id, with roles and channels
+    /// referred to by their new ids throughout the server.
+    pub async fn instantiate(
+        &self,
+        db: &Database,
+        data: v0::DataCreateServer,
+        owner: &User,
+    ) -> Result<(Server, Vec<Channel>)> {
+        let config = config().await;
+        let limits = config.features.server_limits(0);
+
+        if self.channels.len() > limits.server_channels {
+            return Err(create_error!(TooManyChannels {
+                max: limits.server_channels,
+            }));
+        }
+
+        // The default role does not count towards the limit
+        if self
+            .roles
+            .keys()
+            .filter(|id| id.as_str() != DEFAULT_ROLE_ID)
+            .count()
+            > limits.server_roles
+        {
+            return Err(create_error!(TooManyRoles {
+                max: limits.server_roles,
+            }));
+        }
+
+        if self.categories.len() > limits.server_categories {
+            return Err(create_error!(TooManyCategories {
+                max: limits.server_categories,
+            }));
+        }
+
+        let (mut server, _) = Server::create(db, data, owner, false).await?;
+
+        // The default role keeps its id so every member still has it
+        let role_ids: HashMap<&str, String> = self
+            .roles
+            .keys()
+            .map(|id| {
+                if id == DEFAULT_ROLE_ID {
+                    (id.as_str(), id.to_string())
+                } else {
+                    (id.as_str(), Ulid::new().to_string())
+                }
+            })
+            .collect();
+
+        let channel_ids: HashMap<&str, String> = self
+            .channels
+            .iter()
+            .map(|channel| (channel.id.as_str(), Ulid::new().to_string()))
+            .collect();
+
+        let remap_overrides = |overrides: &HashMap<String, OverrideField>| {
+            overrides
+                .iter()
+                .filter_map(|(id, permissions)| {
+                    role_ids
+                        .get(id.as_str())
+                        .map(|id| (id.to_string(), *permissions))
+                })
+                .collect::<HashMap<String, OverrideField>>()
+        };
+
+        let mut channels = vec![];
+        for channel in &self.channels {
+            let id = channel_ids[channel.id.as_str()].to_string();
+            let channel = match channel.channel_type {
+                TemplateChannelType::Text => Channel::TextChannel {
+                    id,
+                    server: server.id.to_string(),
+                    name: channel.name.to_string(),
+                    description: channel.description.clone(),
+                    icon: None,
+                    last_message_id: None,
+                    default_permissions: channel.default_permissions,
+                    role_permissions: remap_overrides(&channel.role_permissions),
+                    nsfw: channel.nsfw,
+                    slowmode_seconds: None,
+                    slowmode_applies_to_bots: false,
+                    slowmode_exempt_roles: vec![],
+                    reactions_only: false,
+                    disable_embeds: false,
+                    announcement: false,
+                    parent: None,
+                    root_message: None,
+                    archived: false,
+                    pin_order: vec![],
+                    last_thread_message_id: None,
+                    pins: None,
+                },
+                TemplateChannelType::Voice => Channel::VoiceChannel {
+                    id,
+                    server: server.id.to_string(),
+                    name: channel.name.to_string(),
+                    description: channel.description.clone(),
+                    icon: None,
+                    default_permissions: channel.default_permissions,
+                    role_permissions: remap_overrides(&channel.role_permissions),
+                    nsfw: channel.nsfw,
+                    pins: None,
+                },
+            };
+
+            db.insert_channel(&channel).await?;
+            channels.push(channel);
+        }
+
+        // Each channel may only appear in one category
+        let mut categorised = HashSet::new();
+        let categories: Vec<Category> = self
+            .categories
+            .iter()
+            .map(|category| Category {
+                id: Ulid::new().to_string(),
+                title: category.title.to_string(),
+                channels: category
+                    .channels
+                    .iter()
+                    .filter_map(|id| channel_ids.get(id.as_str()))
+                    .filter(|id| categorised.insert(id.to_string()))
+                    .cloned()
+                    .collect(),
+                default_permissions: category.default_permissions,
+                role_permissions: remap_overrides(&category.role_permissions),
+            })
+            .collect();
+
+        let partial = PartialServer {
+            channels: Some(channels.iter().map(|channel| channel.id()).collect()),
+            categories: if categories.is_empty() {
+                None
+            } else {
+                Some(categories)
+            },
+            roles: Some(
+                self.roles
+                    .iter()
+                    .map(|(id, role)| (role_ids[id.as_str()].to_string(), role.clone()))
+                    .collect(),
+            ),
+            default_permissions: Some(self.default_permissions),
+            ..Default::default()
+        };
+
+        db.update_server(&server.id, &partial, vec![]).await?;
+        server.apply_options(partial);
+
+        Ok((server, channels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use onechatsocial_models::v0;
+    use onechatsocial_permissions::OverrideField;
+
+    use crate::{fixture, Category, Channel, ServerTemplate};
+
+    #[async_std::test]
+    async fn instantiate() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                owner user 0
+                user user 2
+                channel channel 3
+                server server 4);
+
+            let role_id = server.roles.keys().next().unwrap().clone();
+            let permissions = OverrideField { a: 1, d: 2 };
+            db.set_channel_role_permission(&channel.id(), &role_id, permissions)
+                .await
+                .unwrap();
+
+            let mut server = server;
+            server.categories = Some(vec![Category {
+                id: "category".to_string(),
+                title: "Category".to_string(),
+                channels: vec![channel.id()],
+                default_permissions: None,
+                role_permissions: HashMap::from([(role_id.clone(), permissions)]),
+            }]);
+
+            let template = ServerTemplate::create(&db, &server, &owner.id)
+                .await
+                .unwrap();
+
+            assert_eq!(
+                template,
+                db.fetch_server_template(&template.code).await.unwrap()
+            );
+
+            let (created, channels) = template
+                .instantiate(
+                    &db,
+                    v0::DataCreateServer {
+                        name: "Copy".to_string(),
+                        ..Default::default()
+                    },
+                    &user,
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(user.id, created.owner);
+            assert_eq!("Copy", created.name);
+            assert_eq!(server.default_permissions, created.default_permissions);
+            assert_eq!(server.roles.len(), created.roles.len());
+            assert_eq!(server.channels.len(), channels.len());
+
+            // Everything is given a new id within the new server
+            let (new_role_id, role) = created
+                .roles
+                .iter()
+                .find(|(_, role)| role.name == server.roles[&role_id].name)
+                .unwrap();
+
+            assert_ne!(&role_id, new_role_id);
+            assert_eq!(server.roles[&role_id].rank, role.rank);
+
+            let new_channel = channels
+                .iter()
+                .find(|new_channel| match new_channel {
+                    Channel::TextChannel { name, .. } => {
+                        matches!(&channel, Channel::TextChannel { name: original, .. } if original == name)
+                    }
+                    _ => false,
+                })
+                .unwrap();
+
+            assert_ne!(channel.id(), new_channel.id());
+            assert!(created.channels.contains(&new_channel.id()));
+            assert!(matches!(
+                new_channel,
+                Channel::TextChannel { server, role_permissions, .. }
+                    if server == &created.id && role_permissions.get(new_role_id) == Some(&permissions)
+            ));
+
+            // Categories keep their channels and role overrides
+            let category = &created.categories.as_ref().unwrap()[0];
+            assert_eq!(vec![new_channel.id()], category.channels);
+            assert_eq!(
+                Some(&permissions),
+                category.role_permissions.get(new_role_id)
+            );
+        });
+    }
+}
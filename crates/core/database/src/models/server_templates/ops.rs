@@ -0,0 +1,15 @@
+use onechatsocial_result::Result;
+
+use crate::ServerTemplate;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractServerTemplates: Sync + Send {
+    /// Insert a new server template into the database
+    async fn insert_server_template(&self, template: &ServerTemplate) -> Result<()>;
+
+    /// Fetch a server template by its code
+    async fn fetch_server_template(&self, code: &str) -> Result<ServerTemplate>;
+}
@@ -0,0 +1,31 @@
+use std::collections::hash_map::Entry;
+
+use onechatsocial_result::Result;
+
+use crate::ReferenceDb;
+use crate::ServerTemplate;
+
+use super::AbstractServerTemplates;
+
+#[async_trait]
+impl AbstractServerTemplates for ReferenceDb {
+    /// Insert a new server template into the database
+    async fn insert_server_template(&self, template: &ServerTemplate) -> Result<()> {
+        let mut server_templates = self.server_templates.lock().await;
+        if let Entry::Vacant(entry) = server_templates.entry(template.code.to_string()) {
+            entry.insert(template.clone());
+            Ok(())
+        } else {
+            Err(create_database_error!("insert", "server_template"))
+        }
+    }
+
+    /// Fetch a server template by its code
+    async fn fetch_server_template(&self, code: &str) -> Result<ServerTemplate> {
+        let server_templates = self.server_templates.lock().await;
+        server_templates
+            .get(code)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+}
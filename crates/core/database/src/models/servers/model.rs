@@ -1,11 +1,20 @@
 use std::collections::{HashMap, HashSet};
 
+use onechatsocial_config::config;
 use onechatsocial_models::v0::{self, DataCreateServerChannel};
 use onechatsocial_permissions::{OverrideField, DEFAULT_PERMISSION_SERVER};
 use onechatsocial_result::Result;
 use ulid::Ulid;
 
-use crate::{events::client::EventV1, Channel, Database, File, User};
+use crate::{
+    events::client::EventV1, util::colour::is_valid_colour, Channel, Database, File, User,
+};
+
+/// Id of the role every member of a server implicitly has
+pub const DEFAULT_ROLE_ID: &str = "default";
+
+/// Rank of the default role, which no other role may take
+pub const DEFAULT_ROLE_RANK: i64 = i64::MAX;
 
 auto_derived_partial!(
     /// Server
@@ -39,6 +48,8 @@ auto_derived_partial!(
         )]
         pub roles: HashMap<String, Role>,
         /// Default set of server and channel permissions
+        ///
+        /// Superseded by the default role where one exists.
         pub default_permissions: i64,
 
         /// Icon attachment
@@ -61,6 +72,13 @@ auto_derived_partial!(
         /// Whether this server should be publicly discoverable
         #[serde(skip_serializing_if = "crate::if_false", default)]
         pub discoverable: bool,
+        /// Default colour of text embeds which do not specify one
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub embed_colour: Option<String>,
+
+        /// Outbound webhook notified of member events
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub event_webhook: Option<ServerEventWebhook>,
     },
     "PartialServer"
 );
@@ -83,6 +101,9 @@ auto_derived_partial!(
         /// Ranking of this role
         #[serde(default)]
         pub rank: i64,
+        /// Icon shown next to the names of members with this role
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub icon: Option<File>,
     },
     "PartialRole"
 );
@@ -96,6 +117,15 @@ auto_derived!(
         pub title: String,
         /// Channels in this category
         pub channels: Vec<String>,
+        /// Default permissions inherited by channels in this category
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub default_permissions: Option<OverrideField>,
+        /// Role permissions inherited by channels in this category
+        #[serde(
+            default = "HashMap::<String, OverrideField>::new",
+            skip_serializing_if = "HashMap::<String, OverrideField>::is_empty"
+        )]
+        pub role_permissions: HashMap<String, OverrideField>,
     }
 
     /// System message channel assignments
@@ -112,6 +142,18 @@ auto_derived!(
         /// ID of channel to send user banned messages in
         #[serde(skip_serializing_if = "Option::is_none")]
         pub user_banned: Option<String>,
+        /// ID of channel to send role expiry messages in
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub role_expired: Option<String>,
+    }
+
+    /// Outbound webhook notified of member events
+    pub struct ServerEventWebhook {
+        /// URL which events are delivered to
+        pub url: String,
+        /// Secret used to sign deliveries
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub secret: Option<String>,
     }
 
     /// Optional fields on server object
@@ -121,11 +163,13 @@ auto_derived!(
         SystemMessages,
         Icon,
         Banner,
+        EmbedColour,
     }
 
     /// Optional fields on server object
     pub enum FieldsRole {
         Colour,
+        Icon,
     }
 );
 
@@ -151,9 +195,14 @@ impl Server {
             banner: None,
             categories: None,
             discoverable: false,
+            embed_colour: None,
+            event_webhook: None,
             flags: None,
             icon: None,
-            roles: HashMap::new(),
+            roles: HashMap::from([(
+                DEFAULT_ROLE_ID.to_string(),
+                Role::default_role(*DEFAULT_PERMISSION_SERVER as i64),
+            )]),
             system_messages: None,
         };
 
@@ -225,9 +274,35 @@ impl Server {
             FieldsServer::SystemMessages => self.system_messages = None,
             FieldsServer::Icon => self.icon = None,
             FieldsServer::Banner => self.banner = None,
+            FieldsServer::EmbedColour => self.embed_colour = None,
         }
     }
 
+    /// Get the permissions every member of this server starts with
+    pub fn base_permissions(&self) -> u64 {
+        self.roles
+            .get(DEFAULT_ROLE_ID)
+            .map(|role| role.permissions.a & !role.permissions.d)
+            .unwrap_or(self.default_permissions) as u64
+    }
+
+    /// Count the roles of this server, not including the default role
+    pub fn role_count(&self) -> usize {
+        self.roles
+            .keys()
+            .filter(|id| id.as_str() != DEFAULT_ROLE_ID)
+            .count()
+    }
+
+    /// Find the category a channel is in
+    pub fn category_of(&self, channel_id: &str) -> Option<&Category> {
+        self.categories.as_ref().and_then(|categories| {
+            categories
+                .iter()
+                .find(|category| category.channels.iter().any(|id| id == channel_id))
+        })
+    }
+
     /// Set role permission on a server
     pub async fn set_role_permission(
         &mut self,
@@ -321,6 +396,21 @@ impl Server {
 }
 
 impl Role {
+    /// Create the default role of a server, ranked below every other role
+    pub fn default_role(permissions: i64) -> Role {
+        Role {
+            name: "Default".to_string(),
+            permissions: OverrideField {
+                a: permissions,
+                d: 0,
+            },
+            colour: None,
+            hoist: false,
+            rank: DEFAULT_ROLE_RANK,
+            icon: None,
+        }
+    }
+
     /// Into optional struct
     pub fn into_optional(self) -> PartialRole {
         PartialRole {
@@ -329,6 +419,17 @@ impl Role {
             colour: self.colour,
             hoist: Some(self.hoist),
             rank: Some(self.rank),
+            icon: self.icon,
+        }
+    }
+
+    /// Ensure a role colour is a CSS colour which clients can render
+    pub async fn validate_colour(colour: &str) -> Result<()> {
+        let config = config().await;
+        if is_valid_colour(colour, config.features.role_colour_gradients) {
+            Ok(())
+        } else {
+            Err(create_error!(InvalidProperty))
         }
     }
 
@@ -383,6 +484,7 @@ impl Role {
     pub fn remove_field(&mut self, field: &FieldsRole) {
         match field {
             FieldsRole::Colour => self.colour = None,
+            FieldsRole::Icon => self.icon = None,
         }
     }
 
@@ -395,7 +497,14 @@ impl Role {
         .p(server_id.to_string())
         .await;
 
-        db.delete_role(server_id, role_id).await
+        db.delete_role(server_id, role_id).await?;
+
+        // The role holds the only reference to its icon
+        if let Some(icon) = self.icon {
+            db.mark_attachment_as_deleted(&icon.id).await.ok();
+        }
+
+        Ok(())
     }
 }
 
@@ -419,15 +528,22 @@ impl SystemMessageChannels {
             ids.insert(id);
         }
 
+        if let Some(id) = self.role_expired {
+            ids.insert(id);
+        }
+
         ids
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+    use onechatsocial_permissions::{
+        calculate_channel_permissions, calculate_server_permissions, ChannelPermission,
+        OverrideField,
+    };
 
-    use crate::{fixture, util::permissions::DatabasePermissionQuery};
+    use crate::{fixture, util::permissions::DatabasePermissionQuery, Role, DEFAULT_ROLE_ID};
 
     #[async_std::test]
     async fn permissions() {
@@ -454,4 +570,74 @@ mod tests {
                 .has_channel_permission(ChannelPermission::BanMembers));
         });
     }
+
+    #[async_std::test]
+    async fn default_role() {
+        database_test!(|db| async move {
+            fixture!(db, "server_with_roles",
+                user user 2
+                channel channel 3
+                server server 4);
+
+            let mut query = DatabasePermissionQuery::new(&db, &user).server(&server);
+            assert!(calculate_server_permissions(&mut query)
+                .await
+                .has_channel_permission(ChannelPermission::SendMessage));
+
+            // The default role replaces the server's default permissions
+            let mut server = server;
+            let role = Role::default_role(0);
+            db.insert_role(&server.id, DEFAULT_ROLE_ID, &role)
+                .await
+                .unwrap();
+            server.roles.insert(DEFAULT_ROLE_ID.to_string(), role);
+
+            let mut query = DatabasePermissionQuery::new(&db, &user).server(&server);
+            assert!(!calculate_server_permissions(&mut query)
+                .await
+                .has_channel_permission(ChannelPermission::SendMessage));
+
+            // The default role does not count as one of the server's roles
+            assert_eq!(server.role_count(), server.roles.len() - 1);
+
+            // Anything the default role denies is taken away from what it allows
+            let mut denying = server.clone();
+            denying.roles.insert(
+                DEFAULT_ROLE_ID.to_string(),
+                Role {
+                    permissions: OverrideField {
+                        a: (ChannelPermission::ViewChannel as u64
+                            | ChannelPermission::SendMessage as u64)
+                            as i64,
+                        d: ChannelPermission::SendMessage as i64,
+                    },
+                    ..Role::default_role(0)
+                },
+            );
+
+            assert_eq!(
+                denying.base_permissions(),
+                ChannelPermission::ViewChannel as u64
+            );
+
+            // Channels can override the default role like any other role
+            db.set_channel_role_permission(
+                &channel.id(),
+                DEFAULT_ROLE_ID,
+                OverrideField {
+                    a: (ChannelPermission::ViewChannel as u64
+                        | ChannelPermission::SendMessage as u64) as i64,
+                    d: 0,
+                },
+            )
+            .await
+            .unwrap();
+
+            let channel = db.fetch_channel(&channel.id()).await.unwrap();
+            let mut query = DatabasePermissionQuery::new(&db, &user).channel(&channel);
+            assert!(calculate_channel_permissions(&mut query)
+                .await
+                .has_channel_permission(ChannelPermission::SendMessage));
+        });
+    }
 }
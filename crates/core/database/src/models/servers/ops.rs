@@ -1,6 +1,8 @@
 use onechatsocial_result::Result;
 
-use crate::{FieldsRole, FieldsServer, PartialRole, PartialServer, Role, Server};
+use crate::{
+    FieldsRole, FieldsServer, PartialRole, PartialServer, Role, Server, ServerEventWebhook,
+};
 
 mod mongodb;
 mod reference;
@@ -27,6 +29,13 @@ pub trait AbstractServers: Sync + Send {
     /// Delete a server by its id
     async fn delete_server(&self, id: &str) -> Result<()>;
 
+    /// Set or clear the event webhook of a server
+    async fn set_server_event_webhook(
+        &self,
+        id: &str,
+        webhook: Option<&ServerEventWebhook>,
+    ) -> Result<()>;
+
     /// Insert a new role into server object
     async fn insert_role(&self, server_id: &str, role_id: &str, role: &Role) -> Result<()>;
 
@@ -2,7 +2,9 @@ use bson::{to_document, Document};
 use futures::StreamExt;
 use onechatsocial_result::Result;
 
-use crate::{FieldsRole, FieldsServer, PartialRole, PartialServer, Role, Server};
+use crate::{
+    FieldsRole, FieldsServer, PartialRole, PartialServer, Role, Server, ServerEventWebhook,
+};
 use crate::{IntoDocumentPath, MongoDb};
 
 use super::AbstractServers;
@@ -70,6 +72,40 @@ impl AbstractServers for MongoDb {
         query!(self, delete_one_by_id, COL, id).map(|_| ())
     }
 
+    /// Set or clear the event webhook of a server
+    async fn set_server_event_webhook(
+        &self,
+        id: &str,
+        webhook: Option<&ServerEventWebhook>,
+    ) -> Result<()> {
+        let update = if let Some(webhook) = webhook {
+            doc! {
+                "$set": {
+                    "event_webhook": to_document(webhook)
+                        .map_err(|_| create_database_error!("to_document", "event_webhook"))?
+                }
+            }
+        } else {
+            doc! {
+                "$unset": {
+                    "event_webhook": 1_i32
+                }
+            }
+        };
+
+        self.col::<Document>(COL)
+            .update_one(
+                doc! {
+                    "_id": id
+                },
+                update,
+                None,
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| create_database_error!("update_one", "server"))
+    }
+
     /// Insert a new role into server object
     async fn insert_role(&self, server_id: &str, role_id: &str, role: &Role) -> Result<()> {
         self.col::<Document>(COL)
@@ -168,6 +204,7 @@ impl IntoDocumentPath for FieldsServer {
             FieldsServer::Banner => "banner",
             FieldsServer::Categories => "categories",
             FieldsServer::Description => "description",
+            FieldsServer::EmbedColour => "embed_colour",
             FieldsServer::Icon => "icon",
             FieldsServer::SystemMessages => "system_messages",
         })
@@ -178,6 +215,7 @@ impl IntoDocumentPath for FieldsRole {
     fn as_path(&self) -> Option<&'static str> {
         Some(match self {
             FieldsRole::Colour => "colour",
+            FieldsRole::Icon => "icon",
         })
     }
 }
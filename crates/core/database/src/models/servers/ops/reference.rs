@@ -1,7 +1,9 @@
 use onechatsocial_result::Result;
 
 use crate::ReferenceDb;
-use crate::{FieldsRole, FieldsServer, PartialRole, PartialServer, Role, Server};
+use crate::{
+    FieldsRole, FieldsServer, PartialRole, PartialServer, Role, Server, ServerEventWebhook,
+};
 
 use super::AbstractServers;
 
@@ -71,6 +73,21 @@ impl AbstractServers for ReferenceDb {
         }
     }
 
+    /// Set or clear the event webhook of a server
+    async fn set_server_event_webhook(
+        &self,
+        id: &str,
+        webhook: Option<&ServerEventWebhook>,
+    ) -> Result<()> {
+        let mut servers = self.servers.lock().await;
+        if let Some(server) = servers.get_mut(id) {
+            server.event_webhook = webhook.cloned();
+            Ok(())
+        } else {
+            Err(create_error!(NotFound))
+        }
+    }
+
     /// Insert a new role into server object
     async fn insert_role(&self, server_id: &str, role_id: &str, role: &Role) -> Result<()> {
         let mut servers = self.servers.lock().await;
@@ -0,0 +1,55 @@
+use std::collections::HashSet;
+
+use onechatsocial_result::Result;
+
+use crate::Database;
+
+auto_derived!(
+    /// Sticker
+    pub struct Sticker {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the server whose sticker pack this sticker belongs to
+        pub server: String,
+        /// Uploader user id
+        pub creator_id: String,
+        /// Sticker name
+        pub name: String,
+        /// Whether the sticker is animated
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub animated: bool,
+    }
+);
+
+impl Sticker {
+    /// Check whether all of the given stickers exist and can be used
+    ///
+    /// Stickers from the server being sent to are always available,
+    /// otherwise the user must be a member of the server the sticker pack belongs to.
+    pub async fn can_use_all(
+        db: &Database,
+        ids: &[String],
+        user_id: &str,
+        server_id: Option<&str>,
+    ) -> Result<()> {
+        let stickers = db.fetch_stickers(ids).await?;
+        if stickers.len() != ids.len() {
+            return Err(create_error!(UnknownSticker));
+        }
+
+        let servers: HashSet<&str> = stickers
+            .iter()
+            .map(|sticker| sticker.server.as_str())
+            .filter(|server| Some(*server) != server_id)
+            .collect();
+
+        for server in servers {
+            if db.fetch_member(server, user_id).await.is_err() {
+                return Err(create_error!(UnknownSticker));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use onechatsocial_result::Result;
+
+use crate::Sticker;
+
+mod mongodb;
+mod reference;
+
+#[async_trait]
+pub trait AbstractStickers: Sync + Send {
+    /// Insert sticker into database.
+    async fn insert_sticker(&self, sticker: &Sticker) -> Result<()>;
+
+    /// Fetch a sticker by its id
+    async fn fetch_sticker(&self, id: &str) -> Result<Sticker>;
+
+    /// Fetch stickers by their ids
+    async fn fetch_stickers(&self, ids: &[String]) -> Result<Vec<Sticker>>;
+}
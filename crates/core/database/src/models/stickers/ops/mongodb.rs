@@ -0,0 +1,35 @@
+use onechatsocial_result::Result;
+
+use crate::MongoDb;
+use crate::Sticker;
+
+use super::AbstractStickers;
+
+static COL: &str = "stickers";
+
+#[async_trait]
+impl AbstractStickers for MongoDb {
+    /// Insert sticker into database.
+    async fn insert_sticker(&self, sticker: &Sticker) -> Result<()> {
+        query!(self, insert_one, COL, &sticker).map(|_| ())
+    }
+
+    /// Fetch a sticker by its id
+    async fn fetch_sticker(&self, id: &str) -> Result<Sticker> {
+        query!(self, find_one_by_id, COL, id)?.ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch stickers by their ids
+    async fn fetch_stickers(&self, ids: &[String]) -> Result<Vec<Sticker>> {
+        query!(
+            self,
+            find,
+            COL,
+            doc! {
+                "_id": {
+                    "$in": ids
+                }
+            }
+        )
+    }
+}
@@ -0,0 +1,38 @@
+use onechatsocial_result::Result;
+
+use crate::ReferenceDb;
+use crate::Sticker;
+
+use super::AbstractStickers;
+
+#[async_trait]
+impl AbstractStickers for ReferenceDb {
+    /// Insert sticker into database.
+    async fn insert_sticker(&self, sticker: &Sticker) -> Result<()> {
+        let mut stickers = self.stickers.lock().await;
+        if stickers.contains_key(&sticker.id) {
+            Err(create_database_error!("insert", "sticker"))
+        } else {
+            stickers.insert(sticker.id.to_string(), sticker.clone());
+            Ok(())
+        }
+    }
+
+    /// Fetch a sticker by its id
+    async fn fetch_sticker(&self, id: &str) -> Result<Sticker> {
+        let stickers = self.stickers.lock().await;
+        stickers
+            .get(id)
+            .cloned()
+            .ok_or_else(|| create_error!(NotFound))
+    }
+
+    /// Fetch stickers by their ids
+    async fn fetch_stickers(&self, ids: &[String]) -> Result<Vec<Sticker>> {
+        let stickers = self.stickers.lock().await;
+        Ok(ids
+            .iter()
+            .filter_map(|id| stickers.get(id).cloned())
+            .collect())
+    }
+}
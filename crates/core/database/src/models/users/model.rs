@@ -47,6 +47,9 @@ auto_derived_partial!(
         /// Bot information
         #[serde(skip_serializing_if = "Option::is_none")]
         pub bot: Option<BotInformation>,
+        /// Whether this user's read position is hidden from other users
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub hide_read_receipts: bool,
     },
     "PartialUser"
 );
@@ -150,6 +153,7 @@ impl Default for User {
             flags: Default::default(),
             privileged: Default::default(),
             bot: Default::default(),
+            hide_read_receipts: Default::default(),
         }
     }
 }
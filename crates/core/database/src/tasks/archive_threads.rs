@@ -0,0 +1,43 @@
+// Queue Type: Polled
+use onechatsocial_config::config;
+use std::time::{Duration, SystemTime};
+use ulid::Ulid;
+
+use crate::{Database, PartialChannel};
+
+/// Maximum number of threads to archive at once
+const BATCH_SIZE: i64 = 100;
+
+/// Start a new worker
+pub async fn worker(db: Database) {
+    loop {
+        let idle = config().await.features.thread_archive_after;
+        if idle > 0 {
+            // Threads without any messages since this point have gone idle
+            let cutoff = Ulid::from_datetime(SystemTime::now() - Duration::from_secs(idle));
+            match db.fetch_idle_threads(&cutoff.to_string(), BATCH_SIZE).await {
+                Ok(threads) => {
+                    for mut thread in threads {
+                        if let Err(err) = thread
+                            .update(
+                                &db,
+                                PartialChannel {
+                                    archived: Some(true),
+                                    ..Default::default()
+                                },
+                                vec![],
+                            )
+                            .await
+                        {
+                            error!("Failed to archive thread {} with {err:?}!", thread.id());
+                        }
+                    }
+                }
+                Err(err) => error!("Failed to fetch idle threads with {err:?}!"),
+            }
+        }
+
+        // Sleep for an arbitrary amount of time.
+        async_std::task::sleep(Duration::from_secs(60)).await;
+    }
+}
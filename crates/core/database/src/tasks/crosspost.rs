@@ -0,0 +1,167 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use deadqueue::limited::Queue;
+use once_cell::sync::Lazy;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+
+use crate::{
+    util::{permissions::DatabasePermissionQuery, ulid_generator},
+    ChannelFollow, Database, Message,
+};
+
+/// Task information
+#[derive(Debug)]
+struct CrosspostTask {
+    /// Message to cross-post into the followers of its channel
+    message: Message,
+}
+
+/// One queue per worker, messages from a channel always go to the same queue
+/// so they are cross-posted in the order they were sent
+static QUEUES: Lazy<Vec<Queue<CrosspostTask>>> = Lazy::new(|| {
+    (0..super::WORKER_COUNT)
+        .map(|_| Queue::new(10_000))
+        .collect()
+});
+
+/// Pick the queue for messages from a given channel
+fn shard(channel: &str) -> usize {
+    let mut hasher = DefaultHasher::new();
+    channel.hash(&mut hasher);
+    (hasher.finish() % super::WORKER_COUNT as u64) as usize
+}
+
+/// Queue a new task for a worker
+pub async fn queue(message: Message) {
+    let q = &QUEUES[shard(&message.channel)];
+    q.try_push(CrosspostTask { message }).ok();
+    info!("Queue is using {} slots from {}.", q.len(), q.capacity());
+}
+
+/// Start a new worker for the given queue
+pub async fn worker(db: Database, shard: usize) {
+    loop {
+        let task = QUEUES[shard].pop().await;
+        let Ok(follows) = db.fetch_channel_followers(&task.message.channel).await else {
+            continue;
+        };
+
+        for follow in follows {
+            if !can_crosspost(&db, &follow).await {
+                info!(
+                    "Skipping cross-post of {} into {}, creator may no longer send messages.",
+                    task.message.id, follow.follower
+                );
+                continue;
+            }
+
+            let mut copy = crosspost_copy(&task.message, follow.follower, follow.creator);
+            if let Err(err) = copy.send_without_notifications(&db, false, false).await {
+                error!("Failed to cross-post {}: {err:?}", task.message.id);
+            }
+        }
+    }
+}
+
+/// Check whether the creator of a follow may still send messages in the follower channel
+async fn can_crosspost(db: &Database, follow: &ChannelFollow) -> bool {
+    let (Ok(channel), Ok(creator)) = (
+        db.fetch_channel(&follow.follower).await,
+        db.fetch_user(&follow.creator).await,
+    ) else {
+        return false;
+    };
+
+    let mut query = DatabasePermissionQuery::new(db, &creator).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .has_channel_permission(ChannelPermission::SendMessage)
+}
+
+/// Create a copy of a message to be sent in a follower channel
+///
+/// Attachments are referenced as they are when forwarding, they remain owned by
+/// the original message. Mentions, replies and interactions are not carried over
+/// as they only make sense in the original channel. The copy is authored by the
+/// creator of the follow, the original author is only kept in `forwarded_from`.
+fn crosspost_copy(message: &Message, channel: String, creator: String) -> Message {
+    Message {
+        id: ulid_generator::generate().to_string(),
+        channel,
+        author: creator,
+        content: message.content.clone(),
+        attachments: message.attachments.clone(),
+        embeds: message.embeds.clone(),
+        has_spoilers: message.has_spoilers,
+        language: message.language.clone(),
+        forwarded_from: Some(message.original_reference()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use onechatsocial_models::v0::MessageWebhook;
+
+    use crate::{File, Message, MessageReference};
+
+    use super::{crosspost_copy, shard};
+
+    #[test]
+    fn copy_records_original() {
+        let message = Message {
+            id: "message".to_string(),
+            channel: "announcements".to_string(),
+            author: "author".to_string(),
+            webhook: Some(MessageWebhook {
+                name: "Announcer".to_string(),
+                avatar: None,
+            }),
+            content: Some("Hello!".to_string()),
+            attachments: Some(vec![File {
+                id: "file".to_string(),
+                tag: "attachments".to_string(),
+                filename: "file.txt".to_string(),
+                metadata: Default::default(),
+                content_type: "text/plain".to_string(),
+                size: 0,
+                description: None,
+                is_voice: false,
+                waveform: None,
+                duration: None,
+                deleted: None,
+                reported: None,
+                message_id: Some("message".to_string()),
+                user_id: None,
+                server_id: None,
+                object_id: None,
+            }]),
+            ..Default::default()
+        };
+
+        let copy = crosspost_copy(&message, "follower".to_string(), "creator".to_string());
+        assert_eq!("follower", copy.channel);
+        assert_eq!("creator", copy.author);
+        assert!(copy.webhook.is_none());
+        assert_eq!(message.content, copy.content);
+        assert_eq!(message.attachments, copy.attachments);
+        assert_eq!(
+            Some(MessageReference {
+                channel: "announcements".to_string(),
+                message: "message".to_string(),
+                author: "author".to_string(),
+            }),
+            copy.forwarded_from
+        );
+    }
+
+    #[test]
+    fn channels_keep_their_queue() {
+        // Every message from a channel goes through the same worker
+        assert_eq!(shard("announcements"), shard("announcements"));
+        assert!(shard("announcements") < super::super::WORKER_COUNT);
+    }
+}
@@ -0,0 +1,43 @@
+// Queue Type: Polled
+use iso8601_timestamp::Timestamp;
+use std::time::Duration;
+
+use crate::Database;
+
+/// Maximum number of deletions to finalise at once
+const BATCH_SIZE: i64 = 100;
+
+/// Start a new worker
+pub async fn worker(db: Database) {
+    loop {
+        match db
+            .fetch_due_pending_message_deletions(Timestamp::now_utc(), BATCH_SIZE)
+            .await
+        {
+            Ok(due) => {
+                for deletion in due {
+                    let id = deletion.id.to_string();
+
+                    // Another worker may have already taken this deletion, or it may have been undone
+                    match db.claim_pending_message_deletion(&id).await {
+                        Ok(true) => {}
+                        Ok(false) => continue,
+                        Err(err) => {
+                            error!("Failed to claim deletion of message {id} with {err:?}!");
+                            continue;
+                        }
+                    }
+
+                    match deletion.message.finalise_delete(&db).await {
+                        Ok(_) => info!("Finalised deletion of message {id}."),
+                        Err(err) => error!("Failed to finalise deletion of {id} with {err:?}!"),
+                    }
+                }
+            }
+            Err(err) => error!("Failed to fetch due message deletions with {err:?}!"),
+        }
+
+        // Sleep for an arbitrary amount of time.
+        async_std::task::sleep(Duration::from_secs(1)).await;
+    }
+}
@@ -0,0 +1,18 @@
+// Queue Type: Polled
+use std::time::Duration;
+
+use crate::{AuditLogEntry, Database};
+
+/// Start a new worker
+pub async fn worker(db: Database) {
+    loop {
+        if let Some(retention) = AuditLogEntry::retention().await {
+            if let Err(err) = db.delete_expired_audit_log_entries(retention).await {
+                error!("Failed to delete expired audit log entries with {err:?}!");
+            }
+        }
+
+        // Sleep for an arbitrary amount of time.
+        async_std::task::sleep(Duration::from_secs(60)).await;
+    }
+}
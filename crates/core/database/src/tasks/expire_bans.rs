@@ -0,0 +1,30 @@
+// Queue Type: Polled
+use iso8601_timestamp::Timestamp;
+use std::time::Duration;
+
+use crate::Database;
+
+/// Maximum number of expired bans to lift at once
+const BATCH_SIZE: i64 = 100;
+
+/// Start a new worker
+pub async fn worker(db: Database) {
+    loop {
+        let now = Timestamp::now_utc();
+        match db.fetch_expired_bans(now, BATCH_SIZE).await {
+            Ok(bans) => {
+                for ban in bans {
+                    // Skip bans which were replaced after we fetched them
+                    let id = ban.id.clone();
+                    if let Err(err) = ban.lift_expired(&db, now).await {
+                        error!("Failed to lift expired ban {id:?} with {err:?}!");
+                    }
+                }
+            }
+            Err(err) => error!("Failed to fetch expired bans with {err:?}!"),
+        }
+
+        // Sleep for an arbitrary amount of time.
+        async_std::task::sleep(Duration::from_secs(5)).await;
+    }
+}
@@ -0,0 +1,42 @@
+// Queue Type: Polled
+use iso8601_timestamp::Timestamp;
+use std::time::Duration;
+
+use crate::Database;
+
+/// Maximum number of expired messages to delete at once
+const BATCH_SIZE: i64 = 100;
+
+/// Start a new worker
+pub async fn worker(db: Database) {
+    loop {
+        match db
+            .fetch_expired_messages(Timestamp::now_utc(), BATCH_SIZE)
+            .await
+        {
+            Ok(expired) => {
+                for message in expired {
+                    let id = message.id.to_string();
+
+                    // Another worker may have already taken this message
+                    match db.claim_expired_message(&id).await {
+                        Ok(true) => {}
+                        Ok(false) => continue,
+                        Err(err) => {
+                            error!("Failed to claim expired message {id} with {err:?}!");
+                            continue;
+                        }
+                    }
+
+                    if let Err(err) = message.expire(&db).await {
+                        error!("Failed to delete expired message {id} with {err:?}!");
+                    }
+                }
+            }
+            Err(err) => error!("Failed to fetch expired messages with {err:?}!"),
+        }
+
+        // Sleep for an arbitrary amount of time.
+        async_std::task::sleep(Duration::from_secs(1)).await;
+    }
+}
@@ -0,0 +1,31 @@
+// Queue Type: Polled
+use iso8601_timestamp::Timestamp;
+use std::time::Duration;
+
+use crate::Database;
+
+/// Maximum number of members to remove expired roles from at once
+const BATCH_SIZE: i64 = 100;
+
+/// Start a new worker
+pub async fn worker(db: Database) {
+    loop {
+        let now = Timestamp::now_utc();
+        match db.fetch_members_with_expired_roles(now, BATCH_SIZE).await {
+            Ok(members) => {
+                for mut member in members {
+                    if let Err(err) = member.expire_roles(&db, now).await {
+                        error!(
+                            "Failed to remove expired roles from {:?} with {err:?}!",
+                            member.id
+                        );
+                    }
+                }
+            }
+            Err(err) => error!("Failed to fetch members with expired roles with {err:?}!"),
+        }
+
+        // Sleep for an arbitrary amount of time.
+        async_std::task::sleep(Duration::from_secs(5)).await;
+    }
+}
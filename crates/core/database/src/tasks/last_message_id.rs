@@ -15,6 +15,8 @@ struct Data {
     id: String,
     /// Whether the channel is a DM
     is_dm: bool,
+    /// Whether the message was sent in a thread started in the channel
+    is_thread: bool,
 }
 
 /// Task information
@@ -30,13 +32,31 @@ static Q: Lazy<Queue<Data>> = Lazy::new(|| Queue::new(10_000));
 
 /// Queue a new task for a worker
 pub async fn queue(channel: String, id: String, is_dm: bool) {
-    Q.try_push(Data { channel, id, is_dm }).ok();
+    Q.try_push(Data {
+        channel,
+        id,
+        is_dm,
+        is_thread: false,
+    })
+    .ok();
+    info!("Queue is using {} slots from {}.", Q.len(), Q.capacity());
+}
+
+/// Queue a new task for a worker, recording a message sent in a thread of the given channel
+pub async fn queue_thread(channel: String, id: String) {
+    Q.try_push(Data {
+        channel,
+        id,
+        is_dm: false,
+        is_thread: true,
+    })
+    .ok();
     info!("Queue is using {} slots from {}.", Q.len(), Q.capacity());
 }
 
 /// Start a new worker
 pub async fn worker(db: Database) {
-    let mut tasks = HashMap::<String, DelayedTask<Task>>::new();
+    let mut tasks = HashMap::<(String, bool), DelayedTask<Task>>::new();
     let mut keys = vec![];
 
     loop {
@@ -51,10 +71,20 @@ pub async fn worker(db: Database) {
         for key in &keys {
             if let Some(task) = tasks.remove(key) {
                 let Task { id, is_dm, .. } = task.data;
+                let (key, is_thread) = key;
 
-                let mut channel = PartialChannel {
-                    last_message_id: Some(id.to_string()),
-                    ..Default::default()
+                // Thread activity is tracked separately so the channel's own
+                // last message always belongs to it
+                let mut channel = if *is_thread {
+                    PartialChannel {
+                        last_thread_message_id: Some(id.to_string()),
+                        ..Default::default()
+                    }
+                } else {
+                    PartialChannel {
+                        last_message_id: Some(id.to_string()),
+                        ..Default::default()
+                    }
                 };
 
                 if is_dm {
@@ -62,7 +92,7 @@ pub async fn worker(db: Database) {
                 }
 
                 match db.update_channel(key, &channel, vec![]).await {
-                    Ok(_) => info!("Updated last_message_id for {key} to {id}."),
+                    Ok(_) => info!("Updated last message for {key} to {id}."),
                     Err(err) => error!("Failed to update last_message_id with {err:?}!"),
                 }
             }
@@ -72,12 +102,19 @@ pub async fn worker(db: Database) {
         keys.clear();
 
         // Queue incoming tasks.
-        while let Some(Data { channel, id, is_dm }) = Q.try_pop() {
-            if let Some(task) = tasks.get_mut(&channel) {
+        while let Some(Data {
+            channel,
+            id,
+            is_dm,
+            is_thread,
+        }) = Q.try_pop()
+        {
+            let key = (channel, is_thread);
+            if let Some(task) = tasks.get_mut(&key) {
                 task.data.id = id;
                 task.delay();
             } else {
-                tasks.insert(channel, DelayedTask::new(Task { id, is_dm }));
+                tasks.insert(key, DelayedTask::new(Task { id, is_dm }));
             }
         }
 
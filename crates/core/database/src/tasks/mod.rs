@@ -8,8 +8,19 @@ use std::time::Instant;
 const WORKER_COUNT: usize = 5;
 
 pub mod ack;
+pub mod archive_threads;
+pub mod crosspost;
+pub mod delete_message;
+pub mod expire_audit_log;
+pub mod expire_bans;
+pub mod expire_messages;
+pub mod expire_roles;
 pub mod last_message_id;
+pub mod permission_cache;
 pub mod process_embeds;
+pub mod reaction_batch;
+pub mod scheduled_messages;
+pub mod server_webhook;
 pub mod web_push;
 
 /// Spawn background workers
@@ -20,6 +31,32 @@ pub async fn start_workers(db: Database, authifier_db: authifier::Database) {
         task::spawn(process_embeds::worker(db.clone()));
         task::spawn(web_push::worker(authifier_db.clone()));
     }
+
+    // Server webhook deliveries are made by a fixed pool of workers
+    for _ in 0..server_webhook::WORKER_COUNT {
+        task::spawn(server_webhook::worker());
+    }
+
+    // Cross-posts from each channel must be sent in order, so each worker owns its own queue
+    for shard in 0..WORKER_COUNT {
+        task::spawn(crosspost::worker(db.clone(), shard));
+    }
+
+    // Scheduled, deleted and expiring messages, roles, bans and audit log entries, and idle
+    // threads are polled for, so a single worker is enough
+    task::spawn(archive_threads::worker(db.clone()));
+    task::spawn(delete_message::worker(db.clone()));
+    task::spawn(expire_audit_log::worker(db.clone()));
+    task::spawn(expire_bans::worker(db.clone()));
+    task::spawn(expire_messages::worker(db.clone()));
+    task::spawn(expire_roles::worker(db.clone()));
+    task::spawn(scheduled_messages::worker(db));
+
+    // Reaction batches must be published in order, so a single worker is used
+    task::spawn(reaction_batch::worker());
+
+    // Permission cache invalidations are received once per instance
+    task::spawn(permission_cache::worker());
 }
 
 /// Task with additional information on when it should run
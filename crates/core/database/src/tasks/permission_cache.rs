@@ -0,0 +1,45 @@
+// Queue Type: Pub/Sub
+use futures::StreamExt;
+use std::time::Duration;
+
+use crate::util::permission_cache::{self, INVALIDATION_CHANNEL};
+
+/// Start a new worker
+///
+/// Applies permission cache invalidations made by any instance.
+pub async fn worker() {
+    loop {
+        match redis_kiss::open_pubsub_connection().await {
+            Ok(mut sub) => match sub.subscribe(INVALIDATION_CHANNEL).await {
+                Ok(_) => {
+                    permission_cache::set_listening(true);
+
+                    let mut stream = sub.on_message();
+                    while let Some(item) = stream.next().await {
+                        match redis_kiss::decode_payload::<String>(&item) {
+                            Ok(server) => permission_cache::receive_invalidation(&server),
+                            Err(err) => {
+                                error!(
+                                    "Failed to decode permission cache invalidation with {err:?}!"
+                                )
+                            }
+                        }
+                    }
+
+                    // Invalidations may be missed until we are subscribed again
+                    permission_cache::set_listening(false);
+                    warn!("Lost subscription to permission cache invalidations!");
+                }
+                Err(err) => {
+                    error!("Failed to subscribe to permission cache invalidations with {err:?}!")
+                }
+            },
+            Err(err) => {
+                error!("Failed to connect for permission cache invalidations with {err:?}!")
+            }
+        }
+
+        // Sleep for an arbitrary amount of time.
+        async_std::task::sleep(Duration::from_secs(1)).await;
+    }
+}
@@ -11,7 +11,14 @@ use async_std::task::spawn;
 use deadqueue::limited::Queue;
 use once_cell::sync::Lazy;
 use onechatsocial_models::v0::Embed;
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 use isahc::prelude::*;
 
@@ -28,6 +35,75 @@ struct EmbedTask {
 
 static Q: Lazy<Queue<EmbedTask>> = Lazy::new(|| Queue::new(10_000));
 
+/// Consecutive failures to reach January before embed generation is paused
+const BREAKER_THRESHOLD: usize = 5;
+
+/// How long embed generation is paused for once the breaker trips
+const BREAKER_COOLDOWN: Duration = Duration::from_secs(60);
+
+static FAILURES: AtomicUsize = AtomicUsize::new(0);
+static TRIPS: AtomicUsize = AtomicUsize::new(0);
+static OPEN_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// State of the embed generation circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerState {
+    /// Whether embed generation is currently paused
+    pub open: bool,
+    /// Number of consecutive failures to reach January
+    pub consecutive_failures: usize,
+    /// Number of times the breaker has tripped since startup
+    pub trips: usize,
+}
+
+/// Get the current state of the circuit breaker
+pub fn breaker_state() -> BreakerState {
+    BreakerState {
+        open: OPEN_UNTIL
+            .lock()
+            .unwrap()
+            .is_some_and(|until| Instant::now() < until),
+        consecutive_failures: FAILURES.load(Ordering::Relaxed),
+        trips: TRIPS.load(Ordering::Relaxed),
+    }
+}
+
+/// Check whether embed generation is paused, resuming it once the cooldown has passed
+pub fn is_paused() -> bool {
+    let mut open_until = OPEN_UNTIL.lock().unwrap();
+    match *open_until {
+        Some(until) if Instant::now() < until => true,
+        Some(_) => {
+            *open_until = None;
+            info!("Resuming embed generation.");
+            false
+        }
+        None => false,
+    }
+}
+
+/// Record whether January could be reached
+///
+/// After the breaker resumes, a single failure is enough to pause it again.
+pub fn record_result(reachable: bool) {
+    if reachable {
+        FAILURES.store(0, Ordering::Relaxed);
+        return;
+    }
+
+    if FAILURES.fetch_add(1, Ordering::Relaxed) + 1 >= BREAKER_THRESHOLD {
+        let mut open_until = OPEN_UNTIL.lock().unwrap();
+        if open_until.is_none() {
+            *open_until = Some(Instant::now() + BREAKER_COOLDOWN);
+            TRIPS.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "January appears to be unavailable, pausing embed generation for {}s.",
+                BREAKER_COOLDOWN.as_secs()
+            );
+        }
+    }
+}
+
 /// Queue a new task for a worker
 pub async fn queue(channel: String, id: String, content: String) {
     Q.try_push(EmbedTask {
@@ -48,6 +124,12 @@ pub async fn worker(db: Database) {
 
     loop {
         let task = Q.pop().await;
+
+        // Messages are sent without embeds while January is unavailable
+        if is_paused() {
+            continue;
+        }
+
         let db = db.clone();
         let semaphore = semaphore.clone();
 
@@ -62,6 +144,15 @@ pub async fn worker(db: Database) {
             .await;
 
             if let Ok(embeds) = embeds {
+                // Link previews may have been suppressed in the meantime
+                if db
+                    .fetch_message(&task.id)
+                    .await
+                    .is_ok_and(|message| message.embeds_suppressed())
+                {
+                    return;
+                }
+
                 if let Err(err) = Message::append(
                     &db,
                     task.id,
@@ -141,24 +232,27 @@ pub async fn generate(
         tasks.push(spawn(async move {
             let guard = semaphore.acquire().await;
 
-            if let Ok(mut response) = isahc::get_async(format!(
+            match isahc::get_async(format!(
                 "{host}/embed?url={}",
                 url_escape::encode_component(&link)
             ))
             .await
             {
-                drop(guard);
-                response.json::<Embed>().await.ok()
-            } else {
-                None
+                Ok(mut response) if !response.status().is_server_error() => {
+                    drop(guard);
+                    (true, response.json::<Embed>().await.ok())
+                }
+                _ => (false, None),
             }
         }));
     }
 
-    let embeds = join_all(tasks)
-        .await
+    let results = join_all(tasks).await;
+    record_result(results.iter().any(|(reachable, _)| *reachable));
+
+    let embeds = results
         .into_iter()
-        .flatten()
+        .filter_map(|(_, embed)| embed)
         .collect::<Vec<Embed>>();
 
     // Prevent database update when no embeds are found.
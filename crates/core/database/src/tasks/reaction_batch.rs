@@ -0,0 +1,228 @@
+// Queue Type: Debounced
+use deadqueue::limited::Queue;
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use onechatsocial_config::config;
+use onechatsocial_models::v0::ReactionDelta;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::events::client::EventV1;
+
+/// Task information
+enum Data {
+    /// A user added or removed a reaction
+    Change {
+        /// Message the reaction is on
+        message: String,
+        /// Channel the message is in
+        channel: String,
+        /// User who reacted
+        user: String,
+        /// Emoji used
+        emoji: String,
+        /// Whether the reaction was added
+        added: bool,
+    },
+    /// Event which must not overtake pending changes to its message
+    Event {
+        /// Message the event is about
+        message: String,
+        /// Channel the message is in
+        channel: String,
+        /// Event to publish
+        event: EventV1,
+    },
+}
+
+/// Reaction changes to a single message waiting to be published
+struct Batch {
+    /// Channel the message is in
+    channel: String,
+    /// When the first change was made
+    started: Instant,
+    /// Whether each user had reacted with each emoji before and after the batch
+    changes: IndexMap<(String, String), (bool, bool)>,
+}
+
+impl Batch {
+    /// Create a new empty batch
+    fn new(channel: String) -> Self {
+        Batch {
+            channel,
+            started: Instant::now(),
+            changes: IndexMap::new(),
+        }
+    }
+
+    /// Record a reaction being added or removed
+    fn push(&mut self, user: String, emoji: String, added: bool) {
+        self.changes
+            .entry((user, emoji))
+            .or_insert((!added, added))
+            .1 = added;
+    }
+
+    /// Find the net change to each reaction, in the order they first changed
+    fn deltas(self) -> Vec<ReactionDelta> {
+        self.changes
+            .into_iter()
+            .filter(|(_, (before, after))| before != after)
+            .map(|((user_id, emoji_id), (_, added))| ReactionDelta {
+                user_id,
+                emoji_id,
+                added,
+            })
+            .collect()
+    }
+
+    /// Publish the net changes of this batch, if there are any
+    async fn publish(self, message: String) {
+        let channel = self.channel.clone();
+        let changes = self.deltas();
+        if changes.is_empty() {
+            return;
+        }
+
+        EventV1::MessageReactionBulk {
+            id: message,
+            channel_id: channel.clone(),
+            changes,
+        }
+        .p(channel)
+        .await;
+    }
+}
+
+static Q: Lazy<Queue<Data>> = Lazy::new(|| Queue::new(10_000));
+
+/// Check whether reaction events in a channel should be batched
+pub async fn is_batched(channel: &str) -> bool {
+    config()
+        .await
+        .features
+        .reaction_batch_channels
+        .iter()
+        .any(|id| id == channel)
+}
+
+/// Queue a reaction change to be published with the next batch for its message
+pub async fn queue_change(
+    message: String,
+    channel: String,
+    user: String,
+    emoji: String,
+    added: bool,
+) {
+    Q.try_push(Data::Change {
+        message,
+        channel,
+        user,
+        emoji,
+        added,
+    })
+    .ok();
+
+    info!("Queue is using {} slots from {}.", Q.len(), Q.capacity());
+}
+
+/// Queue an event to be published after any pending reaction changes to its message
+pub async fn queue_event(message: String, channel: String, event: EventV1) {
+    Q.try_push(Data::Event {
+        message,
+        channel,
+        event,
+    })
+    .ok();
+
+    info!("Queue is using {} slots from {}.", Q.len(), Q.capacity());
+}
+
+/// Start a new worker
+///
+/// Only one worker may run, otherwise batches for a message could be published out of order.
+pub async fn worker() {
+    let window = Duration::from_millis(config().await.features.reaction_batch_window);
+    let mut batches = HashMap::<String, Batch>::new();
+    let mut keys = vec![];
+
+    loop {
+        // Queue incoming tasks, in the order they were made.
+        while let Some(data) = Q.try_pop() {
+            match data {
+                Data::Change {
+                    message,
+                    channel,
+                    user,
+                    emoji,
+                    added,
+                } => batches
+                    .entry(message)
+                    .or_insert_with(|| Batch::new(channel))
+                    .push(user, emoji, added),
+                Data::Event {
+                    message,
+                    channel,
+                    event,
+                } => {
+                    // Flush pending changes first so they can't overtake this event
+                    if let Some(batch) = batches.remove(&message) {
+                        batch.publish(message).await;
+                    }
+
+                    event.p(channel).await;
+                }
+            }
+        }
+
+        // Find due batches.
+        for (key, batch) in &batches {
+            if batch.started.elapsed() >= window {
+                keys.push(key.clone());
+            }
+        }
+
+        // Publish any due batches.
+        for key in keys.drain(..) {
+            if let Some(batch) = batches.remove(&key) {
+                batch.publish(key).await;
+            }
+        }
+
+        // Sleep for a fraction of the window.
+        async_std::task::sleep((window / 4).max(Duration::from_millis(10))).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Batch;
+
+    #[test]
+    fn changes_are_coalesced() {
+        let mut batch = Batch::new("channel".to_string());
+        batch.push("a".to_string(), "👍".to_string(), true);
+        batch.push("b".to_string(), "🎉".to_string(), true);
+        batch.push("a".to_string(), "👍".to_string(), false);
+        batch.push("c".to_string(), "👍".to_string(), false);
+        batch.push("b".to_string(), "🎉".to_string(), false);
+        batch.push("b".to_string(), "🎉".to_string(), true);
+
+        // Adding and removing the same reaction cancels out
+        let deltas: Vec<(String, String, bool)> = batch
+            .deltas()
+            .into_iter()
+            .map(|delta| (delta.user_id, delta.emoji_id, delta.added))
+            .collect();
+
+        assert_eq!(
+            deltas,
+            vec![
+                ("b".to_string(), "🎉".to_string(), true),
+                ("c".to_string(), "👍".to_string(), false),
+            ]
+        );
+    }
+}
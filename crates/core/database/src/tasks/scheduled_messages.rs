@@ -0,0 +1,43 @@
+// Queue Type: Polled
+use iso8601_timestamp::Timestamp;
+use std::time::Duration;
+
+use crate::Database;
+
+/// Maximum number of scheduled messages to send at once
+const BATCH_SIZE: i64 = 100;
+
+/// Start a new worker
+pub async fn worker(db: Database) {
+    loop {
+        match db
+            .fetch_due_scheduled_messages(Timestamp::now_utc(), BATCH_SIZE)
+            .await
+        {
+            Ok(due) => {
+                for scheduled in due {
+                    let id = scheduled.id.to_string();
+
+                    // Another worker may have already taken this message
+                    match db.claim_scheduled_message(&id).await {
+                        Ok(true) => {}
+                        Ok(false) => continue,
+                        Err(err) => {
+                            error!("Failed to claim scheduled message {id} with {err:?}!");
+                            continue;
+                        }
+                    }
+
+                    match scheduled.send(&db).await {
+                        Ok(_) => info!("Sent scheduled message {id}."),
+                        Err(err) => error!("Failed to send scheduled message {id} with {err:?}!"),
+                    }
+                }
+            }
+            Err(err) => error!("Failed to fetch due scheduled messages with {err:?}!"),
+        }
+
+        // Sleep for an arbitrary amount of time.
+        async_std::task::sleep(Duration::from_secs(1)).await;
+    }
+}
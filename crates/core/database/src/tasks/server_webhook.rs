@@ -0,0 +1,345 @@
+use std::{
+    future::Future,
+    net::{IpAddr, SocketAddr},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use async_std::{net::ToSocketAddrs, task::sleep};
+use deadqueue::limited::Queue;
+use isahc::{
+    config::{Configurable, ResolveMap},
+    http::Uri,
+    Request,
+};
+use once_cell::sync::Lazy;
+use onechatsocial_models::v0::ServerWebhookEvent;
+use onechatsocial_result::{create_error, Result};
+
+use crate::{Database, ServerEventWebhook, Webhook};
+
+/// Maximum number of delivery attempts per event
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry, doubled after every failed attempt
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Time to wait for the remote server to respond
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of workers to start, each delivers a single event at a time
+///
+/// Deliveries can wait on retries for a while, so this bounds how many are in flight at once.
+pub const WORKER_COUNT: usize = 32;
+
+/// Task information
+#[derive(Debug)]
+struct WebhookTask {
+    /// Webhook to deliver to
+    webhook: ServerEventWebhook,
+    /// Event to deliver
+    event: ServerWebhookEvent,
+}
+
+static Q: Lazy<Queue<WebhookTask>> = Lazy::new(|| Queue::new(10_000));
+
+static DELIVERED: AtomicUsize = AtomicUsize::new(0);
+static RETRIED: AtomicUsize = AtomicUsize::new(0);
+static FAILED: AtomicUsize = AtomicUsize::new(0);
+
+/// Delivery statistics since startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerWebhookStats {
+    /// Number of events delivered
+    pub delivered: usize,
+    /// Number of failed attempts which were retried
+    pub retried: usize,
+    /// Number of events dropped after running out of attempts
+    pub failed: usize,
+}
+
+/// Get delivery statistics since startup
+pub fn stats() -> ServerWebhookStats {
+    ServerWebhookStats {
+        delivered: DELIVERED.load(Ordering::Relaxed),
+        retried: RETRIED.load(Ordering::Relaxed),
+        failed: FAILED.load(Ordering::Relaxed),
+    }
+}
+
+/// Queue a new task for a worker
+pub async fn queue(webhook: ServerEventWebhook, event: ServerWebhookEvent) {
+    Q.try_push(WebhookTask { webhook, event }).ok();
+    info!("Queue is using {} slots from {}.", Q.len(), Q.capacity());
+}
+
+/// Queue an event for a server's event webhook, if it has one
+pub async fn dispatch(db: &Database, server_id: &str, event: ServerWebhookEvent) {
+    if let Ok(server) = db.fetch_server(server_id).await {
+        if let Some(webhook) = server.event_webhook {
+            queue(webhook, event).await;
+        }
+    }
+}
+
+/// Check whether an address is reachable on the public internet
+///
+/// Loopback, link-local, private and otherwise internal addresses are refused
+/// so that webhooks can not be used to reach services behind the server.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && (b & 0xc0) == 64))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(ip));
+            }
+
+            let segment = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // Unique local, fc00::/7
+                || (segment & 0xfe00) == 0xfc00
+                // Link-local, fe80::/10
+                || (segment & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Resolve the host of a webhook URL to a public address
+///
+/// Fails if the URL is invalid or if the host resolves to any address which is not public.
+pub async fn resolve_public_address(url: &str) -> Result<(String, SocketAddr)> {
+    let uri: Uri = url.parse().map_err(|_| create_error!(InvalidProperty))?;
+    let port = match uri.scheme_str() {
+        Some("https") => uri.port_u16().unwrap_or(443),
+        Some("http") => uri.port_u16().unwrap_or(80),
+        _ => return Err(create_error!(InvalidProperty)),
+    };
+
+    // IPv6 hosts are bracketed in URLs
+    let host = uri
+        .host()
+        .ok_or_else(|| create_error!(InvalidProperty))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+
+    let addresses: Vec<SocketAddr> = (host.as_str(), port)
+        .to_socket_addrs()
+        .await
+        .map_err(|_| create_error!(InvalidProperty))?
+        .collect();
+
+    if addresses.is_empty()
+        || addresses
+            .iter()
+            .any(|address| !is_public_address(address.ip()))
+    {
+        return Err(create_error!(InvalidProperty));
+    }
+
+    Ok((host, addresses[0]))
+}
+
+/// Outcome of a single delivery attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Attempt {
+    /// The remote server accepted the event
+    Delivered,
+    /// The attempt failed but may succeed later
+    Retry,
+    /// The event can not be delivered
+    Abort,
+}
+
+/// Start a new worker
+pub async fn worker() {
+    loop {
+        let task = Q.pop().await;
+        deliver(task).await;
+    }
+}
+
+/// Deliver an event, retrying with exponential backoff
+async fn deliver(task: WebhookTask) {
+    let Ok(body) = serde_json::to_vec(&task.event) else {
+        return;
+    };
+
+    let signature = match &task.webhook.secret {
+        Some(secret) => match Webhook::sign_payload(secret, &body) {
+            Ok(signature) => Some(signature),
+            Err(err) => {
+                error!("Failed to sign server webhook payload: {err:?}");
+                return;
+            }
+        },
+        None => None,
+    };
+
+    let url = &task.webhook.url;
+    run_attempts(INITIAL_BACKOFF, |attempt| {
+        let body = body.clone();
+        let signature = signature.clone();
+        async move {
+            // Resolve on every attempt and connect to the checked address only,
+            // so the host can not be pointed at an internal address after saving
+            let Ok((host, address)) = resolve_public_address(url).await else {
+                info!("Refusing to deliver to server webhook {url}, it is not public");
+                return Attempt::Abort;
+            };
+
+            let mut request = Request::post(url)
+                .timeout(REQUEST_TIMEOUT)
+                .dns_resolve(ResolveMap::new().add(host, address.port(), address.ip()))
+                .header("Content-Type", "application/json");
+
+            if let Some(signature) = &signature {
+                request = request.header("X-Signature-256", signature);
+            }
+
+            match request.body(body) {
+                Ok(request) => match isahc::send_async(request).await {
+                    Ok(response) if response.status().is_success() => Attempt::Delivered,
+                    Ok(response) => {
+                        info!(
+                            "Server webhook {url} responded with {} (attempt {attempt})",
+                            response.status()
+                        );
+                        Attempt::Retry
+                    }
+                    Err(err) => {
+                        info!("Failed to reach server webhook {url} (attempt {attempt}): {err}");
+                        Attempt::Retry
+                    }
+                },
+                Err(err) => {
+                    error!("Failed to build server webhook request: {err}");
+                    Attempt::Abort
+                }
+            }
+        }
+    })
+    .await;
+}
+
+/// Make up to `MAX_ATTEMPTS` attempts, doubling the backoff after each failure
+///
+/// Returns whether the event was delivered and the number of attempts made.
+async fn run_attempts<F, Fut>(mut backoff: Duration, mut attempt: F) -> (bool, u32)
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Attempt>,
+{
+    for n in 1..=MAX_ATTEMPTS {
+        match attempt(n).await {
+            Attempt::Delivered => {
+                DELIVERED.fetch_add(1, Ordering::Relaxed);
+                return (true, n);
+            }
+            Attempt::Abort => {
+                FAILED.fetch_add(1, Ordering::Relaxed);
+                return (false, n);
+            }
+            Attempt::Retry => {}
+        }
+
+        if n < MAX_ATTEMPTS {
+            RETRIED.fetch_add(1, Ordering::Relaxed);
+            sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+
+    FAILED.fetch_add(1, Ordering::Relaxed);
+    (false, MAX_ATTEMPTS)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::IpAddr, time::Duration};
+
+    use super::{is_public_address, resolve_public_address, run_attempts, Attempt, MAX_ATTEMPTS};
+
+    #[async_std::test]
+    async fn retries_until_delivered() {
+        let (delivered, attempts) = run_attempts(Duration::ZERO, |attempt| async move {
+            if attempt < 3 {
+                Attempt::Retry
+            } else {
+                Attempt::Delivered
+            }
+        })
+        .await;
+
+        assert!(delivered);
+        assert_eq!(attempts, 3);
+    }
+
+    #[async_std::test]
+    async fn gives_up_after_max_attempts() {
+        let (delivered, attempts) =
+            run_attempts(Duration::ZERO, |_| async { Attempt::Retry }).await;
+
+        assert!(!delivered);
+        assert_eq!(attempts, MAX_ATTEMPTS);
+
+        // Aborting stops without any further attempts
+        let (delivered, attempts) =
+            run_attempts(Duration::ZERO, |_| async { Attempt::Abort }).await;
+
+        assert!(!delivered);
+        assert_eq!(attempts, 1);
+    }
+
+    #[async_std::test]
+    async fn refuses_internal_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fe80::1",
+            "fd00::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_address(ip.parse::<IpAddr>().unwrap()), "{ip}");
+        }
+
+        for ip in ["1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(is_public_address(ip.parse::<IpAddr>().unwrap()), "{ip}");
+        }
+
+        for url in [
+            "http://127.0.0.1/hook",
+            "https://localhost:8443/hook",
+            "http://[::1]/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "ftp://1.1.1.1/hook",
+            "not a url",
+        ] {
+            assert!(resolve_public_address(url).await.is_err(), "{url}");
+        }
+
+        let (host, address) = resolve_public_address("https://1.1.1.1/hook")
+            .await
+            .unwrap();
+        assert_eq!(host, "1.1.1.1");
+        assert_eq!(address.port(), 443);
+    }
+}
@@ -1,4 +1,8 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
 use authifier::Database;
 use base64::{
@@ -27,16 +31,20 @@ struct PushTask {
 
 static Q: Lazy<Queue<PushTask>> = Lazy::new(|| Queue::new(10_000));
 
+/// Number of recent notifications sent to each user per tag, along with when the last one was sent
+static RECENT: Lazy<Mutex<HashMap<(String, String), (usize, Instant)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 /// Queue a new task for a worker
 pub async fn queue(recipients: Vec<String>, payload: PushNotification) {
     if recipients.is_empty() {
         return;
     }
 
-    let online_ids = filter_online(&recipients).await;
-    let recipients = (&recipients.into_iter().collect::<HashSet<String>>() - &online_ids)
-        .into_iter()
-        .collect::<Vec<String>>();
+    let recipients = prune_online(recipients).await;
+    if recipients.is_empty() {
+        return;
+    }
 
     Q.try_push(PushTask {
         recipients,
@@ -47,6 +55,61 @@ pub async fn queue(recipients: Vec<String>, payload: PushNotification) {
     info!("Queue is using {} slots from {}.", Q.len(), Q.capacity());
 }
 
+/// Remove any recipients connected to the gateway, as they will receive the live event anyway
+async fn prune_online(recipients: Vec<String>) -> Vec<String> {
+    if !config().await.features.suppress_push_for_online {
+        return recipients;
+    }
+
+    let online_ids = filter_online(&recipients).await;
+    without_online(recipients, &online_ids)
+}
+
+/// Remove any recipients who currently have an active session
+fn without_online(recipients: Vec<String>, online_ids: &HashSet<String>) -> Vec<String> {
+    recipients
+        .into_iter()
+        .filter(|id| !online_ids.contains(id))
+        .collect()
+}
+
+/// Count a notification towards the collapse window of a given user and tag
+///
+/// Returns how many notifications have been sent within the current window, including this one.
+fn collapse_count(
+    recent: &mut HashMap<(String, String), (usize, Instant)>,
+    user_id: &str,
+    tag: &str,
+    window: Duration,
+    now: Instant,
+) -> usize {
+    let entry = recent
+        .entry((user_id.to_string(), tag.to_string()))
+        .or_insert((0, now));
+
+    if now.duration_since(entry.1) > window {
+        entry.0 = 0;
+    }
+
+    entry.0 += 1;
+    entry.1 = now;
+    entry.0
+}
+
+/// Replace the body of a notification with a summary if it has been collapsed
+fn collapse(payload: &PushNotification, count: usize) -> PushNotification {
+    let mut payload = payload.clone();
+    if count > 1 {
+        payload.body = match (&payload.channel_name, &payload.server_name) {
+            (Some(channel), Some(_)) => format!("{count} new messages in #{channel}"),
+            (Some(group), None) => format!("{count} new messages in {group}"),
+            _ => format!("{count} new messages"),
+        };
+    }
+
+    payload
+}
+
 /// Start a new worker
 pub async fn worker(db: Database) {
     let config = config().await;
@@ -62,27 +125,60 @@ pub async fn worker(db: Database) {
         .decode(config.api.vapid.private_key)
         .expect("valid `VAPID_PRIVATE_KEY`");
 
+    let collapse_window = Duration::from_secs(config.features.push_collapse_window);
+
     loop {
         let task = Q.pop().await;
 
+        // Rapid notifications in the same channel replace each other, so
+        // summarise how many messages each recipient has missed
+        let payloads: HashMap<String, PushNotification> = {
+            let mut recent = RECENT.lock().unwrap();
+            let now = Instant::now();
+
+            // Forget anything that is no longer within the window
+            recent.retain(|_, (_, last)| now.duration_since(*last) <= collapse_window);
+
+            task.recipients
+                .iter()
+                .map(|user_id| {
+                    let count = collapse_count(
+                        &mut recent,
+                        user_id,
+                        &task.payload.tag,
+                        collapse_window,
+                        now,
+                    );
+
+                    (user_id.to_string(), collapse(&task.payload, count))
+                })
+                .collect()
+        };
+
         if let Ok(sessions) = db.find_sessions_with_subscription(&task.recipients).await {
             for session in sessions {
+                let payload = payloads.get(&session.user_id).unwrap_or(&task.payload);
+
                 if let Some(sub) = session.subscription {
                     if sub.endpoint == "fcm" {
                         // Use Firebase Cloud Messaging
                         if let Some(client) = &fcm_client {
                             let PushNotification {
-                                author,
+                                author: _,
+                                title,
+                                channel_name: _,
+                                server_name: _,
+                                reply: _,
                                 icon,
                                 image: _,
                                 body,
                                 tag,
                                 timestamp: _,
                                 url: _,
-                            } = &task.payload;
+                            } = payload;
 
                             let mut notification = fcm::NotificationBuilder::new();
-                            notification.title(author);
+                            notification.title(title);
                             notification.icon(icon);
                             notification.body(body);
                             notification.tag(tag);
@@ -92,6 +188,7 @@ pub async fn worker(db: Database) {
                             let mut message_builder =
                                 fcm::MessageBuilder::new(&config.api.fcm.api_key, &sub.auth);
                             message_builder.notification(notification);
+                            message_builder.collapse_key(tag);
 
                             if let Err(err) = client.send(message_builder.finalize()).await {
                                 error!("Failed to send FCM notification! {:?}", err);
@@ -120,7 +217,7 @@ pub async fn worker(db: Database) {
                                     let mut builder = WebPushMessageBuilder::new(&subscription);
                                     builder.set_vapid_signature(signature);
 
-                                    let payload = json!(task.payload).to_string();
+                                    let payload = json!(payload).to_string();
                                     builder
                                         .set_payload(ContentEncoding::AesGcm, payload.as_bytes());
 
@@ -160,3 +257,82 @@ pub async fn worker(db: Database) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        time::{Duration, Instant},
+    };
+
+    use onechatsocial_presence::{create_session, delete_session};
+
+    use super::{collapse_count, prune_online, without_online};
+
+    #[test]
+    fn online_mentioned_user_is_skipped() {
+        let online = HashSet::from(["online".to_string()]);
+        let recipients = without_online(vec!["online".to_string()], &online);
+        assert!(recipients.is_empty());
+    }
+
+    #[test]
+    fn offline_mentioned_user_is_pushed() {
+        let online = HashSet::from(["online".to_string()]);
+        let recipients = without_online(vec!["online".to_string(), "offline".to_string()], &online);
+        assert_eq!(recipients, vec!["offline".to_string()]);
+    }
+
+    #[async_std::test]
+    async fn recipients_with_sessions_are_pruned() {
+        let online = ulid::Ulid::new().to_string();
+        let offline = ulid::Ulid::new().to_string();
+        let (_, session_id) = create_session(&online, 0).await;
+
+        assert_eq!(
+            prune_online(vec![online.clone(), offline.clone()]).await,
+            vec![offline.clone()]
+        );
+
+        // Once their last session closes, the user is pushed again
+        delete_session(&online, session_id).await;
+        assert_eq!(
+            prune_online(vec![online.clone(), offline.clone()]).await,
+            vec![online, offline]
+        );
+    }
+
+    #[test]
+    fn rapid_notifications_are_collapsed() {
+        let mut recent = HashMap::new();
+        let window = Duration::from_secs(60);
+        let now = Instant::now();
+
+        assert_eq!(
+            collapse_count(&mut recent, "user", "channel", window, now),
+            1
+        );
+        assert_eq!(
+            collapse_count(&mut recent, "user", "channel", window, now),
+            2
+        );
+        assert_eq!(
+            collapse_count(&mut recent, "user", "channel", window, now),
+            3
+        );
+
+        // Other channels and users are counted separately
+        assert_eq!(collapse_count(&mut recent, "user", "other", window, now), 1);
+        assert_eq!(
+            collapse_count(&mut recent, "other", "channel", window, now),
+            1
+        );
+
+        // Counting starts over once the window has passed
+        let later = now + Duration::from_secs(61);
+        assert_eq!(
+            collapse_count(&mut recent, "user", "channel", window, later),
+            1
+        );
+    }
+}
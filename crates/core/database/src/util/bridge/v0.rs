@@ -110,6 +110,7 @@ impl From<crate::Webhook> for Webhook {
             channel_id: value.channel_id,
             token: value.token,
             permissions: value.permissions,
+            template: value.template.map(|template| template.into()),
         }
     }
 }
@@ -123,31 +124,65 @@ impl From<crate::PartialWebhook> for PartialWebhook {
             channel_id: value.channel_id,
             token: value.token,
             permissions: value.permissions,
+            template: value.template.map(|template| template.into()),
         }
     }
 }
 
 impl From<FieldsWebhook> for crate::FieldsWebhook {
-    fn from(_value: FieldsWebhook) -> Self {
-        Self::Avatar
+    fn from(value: FieldsWebhook) -> Self {
+        match value {
+            FieldsWebhook::Avatar => crate::FieldsWebhook::Avatar,
+            FieldsWebhook::Template => crate::FieldsWebhook::Template,
+        }
     }
 }
 
 impl From<crate::FieldsWebhook> for FieldsWebhook {
-    fn from(_value: crate::FieldsWebhook) -> Self {
-        Self::Avatar
+    fn from(value: crate::FieldsWebhook) -> Self {
+        match value {
+            crate::FieldsWebhook::Avatar => FieldsWebhook::Avatar,
+            crate::FieldsWebhook::Template => FieldsWebhook::Template,
+        }
+    }
+}
+
+impl From<crate::WebhookTemplate> for WebhookTemplate {
+    fn from(value: crate::WebhookTemplate) -> Self {
+        WebhookTemplate {
+            content: value.content,
+            title: value.title,
+            description: value.description,
+            url: value.url,
+            icon_url: value.icon_url,
+            colour: value.colour,
+        }
+    }
+}
+
+impl From<WebhookTemplate> for crate::WebhookTemplate {
+    fn from(value: WebhookTemplate) -> Self {
+        crate::WebhookTemplate {
+            content: value.content,
+            title: value.title,
+            description: value.description,
+            url: value.url,
+            icon_url: value.icon_url,
+            colour: value.colour,
+        }
     }
 }
 
 impl From<crate::Channel> for Channel {
     fn from(value: crate::Channel) -> Self {
         match value {
-            crate::Channel::SavedMessages { id, user } => Channel::SavedMessages { id, user },
+            crate::Channel::SavedMessages { id, user, .. } => Channel::SavedMessages { id, user },
             crate::Channel::DirectMessage {
                 id,
                 active,
                 recipients,
                 last_message_id,
+                ..
             } => Channel::DirectMessage {
                 id,
                 active,
@@ -164,6 +199,7 @@ impl From<crate::Channel> for Channel {
                 last_message_id,
                 permissions,
                 nsfw,
+                ..
             } => Channel::Group {
                 id,
                 name,
@@ -185,6 +221,18 @@ impl From<crate::Channel> for Channel {
                 default_permissions,
                 role_permissions,
                 nsfw,
+                slowmode_seconds,
+                slowmode_applies_to_bots,
+                slowmode_exempt_roles,
+                reactions_only,
+                disable_embeds,
+                announcement,
+                parent,
+                root_message,
+                archived,
+                pin_order,
+                last_thread_message_id,
+                ..
             } => Channel::TextChannel {
                 id,
                 server,
@@ -195,6 +243,17 @@ impl From<crate::Channel> for Channel {
                 default_permissions,
                 role_permissions,
                 nsfw,
+                slowmode_seconds,
+                slowmode_applies_to_bots,
+                slowmode_exempt_roles,
+                reactions_only,
+                disable_embeds,
+                announcement,
+                parent,
+                root_message,
+                archived,
+                pin_order,
+                last_thread_message_id,
             },
             crate::Channel::VoiceChannel {
                 id,
@@ -205,6 +264,7 @@ impl From<crate::Channel> for Channel {
                 default_permissions,
                 role_permissions,
                 nsfw,
+                ..
             } => Channel::VoiceChannel {
                 id,
                 server,
@@ -232,6 +292,15 @@ impl From<crate::PartialChannel> for PartialChannel {
             role_permissions: value.role_permissions,
             default_permissions: value.default_permissions,
             last_message_id: value.last_message_id,
+            slowmode_exempt_roles: value.slowmode_exempt_roles,
+            slowmode_seconds: value.slowmode_seconds,
+            slowmode_applies_to_bots: value.slowmode_applies_to_bots,
+            reactions_only: value.reactions_only,
+            disable_embeds: value.disable_embeds,
+            announcement: value.announcement,
+            archived: value.archived,
+            pin_order: value.pin_order,
+            last_thread_message_id: value.last_thread_message_id,
         }
     }
 }
@@ -242,6 +311,7 @@ impl From<FieldsChannel> for crate::FieldsChannel {
             FieldsChannel::Description => crate::FieldsChannel::Description,
             FieldsChannel::Icon => crate::FieldsChannel::Icon,
             FieldsChannel::DefaultPermissions => crate::FieldsChannel::DefaultPermissions,
+            FieldsChannel::SlowmodeSeconds => crate::FieldsChannel::SlowmodeSeconds,
         }
     }
 }
@@ -252,6 +322,152 @@ impl From<crate::FieldsChannel> for FieldsChannel {
             crate::FieldsChannel::Description => FieldsChannel::Description,
             crate::FieldsChannel::Icon => FieldsChannel::Icon,
             crate::FieldsChannel::DefaultPermissions => FieldsChannel::DefaultPermissions,
+            crate::FieldsChannel::SlowmodeSeconds => FieldsChannel::SlowmodeSeconds,
+        }
+    }
+}
+
+impl From<NotificationLevel> for crate::NotificationLevel {
+    fn from(value: NotificationLevel) -> Self {
+        match value {
+            NotificationLevel::All => crate::NotificationLevel::All,
+            NotificationLevel::Mentions => crate::NotificationLevel::Mentions,
+            NotificationLevel::Muted => crate::NotificationLevel::Muted,
+            NotificationLevel::Nothing => crate::NotificationLevel::Nothing,
+        }
+    }
+}
+
+impl From<crate::NotificationLevel> for NotificationLevel {
+    fn from(value: crate::NotificationLevel) -> Self {
+        match value {
+            crate::NotificationLevel::All => NotificationLevel::All,
+            crate::NotificationLevel::Mentions => NotificationLevel::Mentions,
+            crate::NotificationLevel::Muted => NotificationLevel::Muted,
+            crate::NotificationLevel::Nothing => NotificationLevel::Nothing,
+        }
+    }
+}
+
+impl From<crate::NotificationLevel> for ChannelNotificationPreference {
+    fn from(value: crate::NotificationLevel) -> Self {
+        ChannelNotificationPreference {
+            level: value.into(),
+        }
+    }
+}
+
+impl From<crate::ChannelFollow> for ChannelFollow {
+    fn from(value: crate::ChannelFollow) -> Self {
+        ChannelFollow {
+            id: value.id,
+            channel: value.channel,
+            follower: value.follower,
+            creator: value.creator,
+        }
+    }
+}
+
+impl From<crate::ChannelPins> for ChannelPins {
+    fn from(value: crate::ChannelPins) -> Self {
+        ChannelPins {
+            pin_count: value.count,
+            last_pinned_at: value.last_pinned_at,
+        }
+    }
+}
+
+impl From<crate::ReactionRole> for ReactionRole {
+    fn from(value: crate::ReactionRole) -> Self {
+        ReactionRole {
+            id: value.id,
+            message: value.message,
+            emoji: value.emoji,
+            role: value.role,
+        }
+    }
+}
+
+impl From<crate::MessageTombstone> for MessageTombstone {
+    fn from(value: crate::MessageTombstone) -> Self {
+        MessageTombstone {
+            message: value.message,
+            channel: value.channel,
+            author: value.author,
+            deleted_by: value.deleted_by,
+            deleted_at: value.deleted_at,
+        }
+    }
+}
+
+impl From<crate::MessageRevision> for MessageRevision {
+    fn from(value: crate::MessageRevision) -> Self {
+        MessageRevision {
+            content: value.content,
+            embeds: value.embeds,
+            edited_at: value.edited_at,
+        }
+    }
+}
+
+impl From<crate::MessageReference> for MessageReference {
+    fn from(value: crate::MessageReference) -> Self {
+        MessageReference {
+            channel: value.channel,
+            message: value.message,
+            author: value.author,
+        }
+    }
+}
+
+impl From<crate::MessageSnapshot> for MessageSnapshot {
+    fn from(value: crate::MessageSnapshot) -> Self {
+        MessageSnapshot {
+            message: value.message,
+            author: value.author,
+            content: value.content,
+        }
+    }
+}
+
+impl From<crate::Poll> for Poll {
+    fn from(value: crate::Poll) -> Self {
+        Poll {
+            options: value
+                .options
+                .into_iter()
+                .map(|option| option.into())
+                .collect(),
+            multi_select: value.multi_select,
+            closes_at: value.closes_at,
+        }
+    }
+}
+
+impl From<crate::PollOption> for PollOption {
+    fn from(value: crate::PollOption) -> Self {
+        PollOption {
+            name: value.name,
+            voters: value.voters,
+        }
+    }
+}
+
+impl From<crate::ScheduledMessage> for ScheduledMessage {
+    fn from(value: crate::ScheduledMessage) -> Self {
+        ScheduledMessage {
+            send_at: value.send_at,
+            message: value.message.into(),
+        }
+    }
+}
+
+impl From<crate::EmojiReactionCount> for EmojiLeaderboardEntry {
+    fn from(value: crate::EmojiReactionCount) -> Self {
+        EmojiLeaderboardEntry {
+            emoji: value.emoji,
+            reactions: value.count,
+            users: value.users,
         }
     }
 }
@@ -269,6 +485,17 @@ impl From<crate::Emoji> for Emoji {
     }
 }
 
+impl From<crate::Emoji> for EmojiDefinition {
+    fn from(value: crate::Emoji) -> Self {
+        EmojiDefinition {
+            id: value.id,
+            name: value.name,
+            animated: value.animated,
+            nsfw: value.nsfw,
+        }
+    }
+}
+
 impl From<crate::EmojiParent> for EmojiParent {
     fn from(value: crate::EmojiParent) -> Self {
         match value {
@@ -296,6 +523,10 @@ impl From<crate::File> for File {
             metadata: value.metadata.into(),
             content_type: value.content_type,
             size: value.size,
+            description: value.description,
+            is_voice: value.is_voice,
+            waveform: value.waveform,
+            duration: value.duration,
             deleted: value.deleted,
             reported: value.reported,
             message_id: value.message_id,
@@ -324,8 +555,20 @@ impl From<crate::Metadata> for Metadata {
     }
 }
 
+impl From<crate::FieldsMessage> for FieldsMessage {
+    fn from(value: crate::FieldsMessage) -> Self {
+        match value {
+            crate::FieldsMessage::EditedBy => FieldsMessage::EditedBy,
+        }
+    }
+}
+
 impl From<crate::Message> for Message {
     fn from(value: crate::Message) -> Self {
+        let embeds = value.visible_embeds();
+        let masquerade: Option<Masquerade> = value.masquerade.map(|masq| masq.into());
+        let display_author = DisplayAuthor::resolve(masquerade.as_ref(), value.webhook.as_ref());
+
         Message {
             id: value.id,
             nonce: value.nonce,
@@ -337,13 +580,30 @@ impl From<crate::Message> for Message {
             attachments: value
                 .attachments
                 .map(|v| v.into_iter().map(|f| f.into()).collect()),
+            stickers: value.stickers,
             edited: value.edited,
-            embeds: value.embeds,
+            edited_by: value.edited_by,
+            embeds,
             mentions: value.mentions,
+            role_mentions: value.role_mentions,
             replies: value.replies,
+            reply_snapshots: value
+                .reply_snapshots
+                .map(|snapshots| snapshots.into_iter().map(|s| s.into()).collect()),
             reactions: value.reactions,
+            reaction_counts: None,
             interactions: value.interactions.into(),
-            masquerade: value.masquerade.map(|masq| masq.into()),
+            masquerade,
+            has_spoilers: value.has_spoilers,
+            language: value.language,
+            reply_count: value.reply_count,
+            thread_participant_count: value.thread_participant_count,
+            pinned: value.pinned,
+            poll: value.poll.map(|poll| poll.into()),
+            expires_at: value.expires_at,
+            forwarded_from: value.forwarded_from.map(|reference| reference.into()),
+            flags: value.flags,
+            display_author,
         }
     }
 }
@@ -361,13 +621,30 @@ impl From<crate::PartialMessage> for PartialMessage {
             attachments: value
                 .attachments
                 .map(|v| v.into_iter().map(|f| f.into()).collect()),
+            stickers: value.stickers,
             edited: value.edited,
+            edited_by: value.edited_by,
             embeds: value.embeds,
             mentions: value.mentions,
+            role_mentions: value.role_mentions,
             replies: value.replies,
+            reply_snapshots: value
+                .reply_snapshots
+                .map(|snapshots| snapshots.into_iter().map(|s| s.into()).collect()),
             reactions: value.reactions,
+            reaction_counts: None,
             interactions: value.interactions.map(|interactions| interactions.into()),
             masquerade: value.masquerade.map(|masq| masq.into()),
+            has_spoilers: value.has_spoilers,
+            language: value.language,
+            reply_count: value.reply_count,
+            thread_participant_count: value.thread_participant_count,
+            pinned: value.pinned,
+            poll: value.poll.map(|poll| poll.into()),
+            expires_at: value.expires_at,
+            forwarded_from: value.forwarded_from.map(|reference| reference.into()),
+            flags: value.flags,
+            display_author: None,
         }
     }
 }
@@ -383,11 +660,26 @@ impl From<crate::SystemMessage> for SystemMessage {
                 Self::ChannelOwnershipChanged { from, to }
             }
             crate::SystemMessage::ChannelRenamed { name, by } => Self::ChannelRenamed { name, by },
+            crate::SystemMessage::MessagePinned { id, by } => Self::MessagePinned { id, by },
+            crate::SystemMessage::MessageUnpinned { id, by } => Self::MessageUnpinned { id, by },
+            crate::SystemMessage::Poll {
+                question,
+                options,
+                by,
+            } => Self::Poll {
+                question,
+                options,
+                by,
+            },
+            crate::SystemMessage::RoleExpired { id, role } => Self::RoleExpired { id, role },
+            crate::SystemMessage::ThreadCreated { id, name, by } => {
+                Self::ThreadCreated { id, name, by }
+            }
             crate::SystemMessage::Text { content } => Self::Text { content },
             crate::SystemMessage::UserAdded { id, by } => Self::UserAdded { id, by },
-            crate::SystemMessage::UserBanned { id } => Self::UserBanned { id },
+            crate::SystemMessage::UserBanned { id, by } => Self::UserBanned { id, by },
             crate::SystemMessage::UserJoined { id } => Self::UserJoined { id },
-            crate::SystemMessage::UserKicked { id } => Self::UserKicked { id },
+            crate::SystemMessage::UserKicked { id, by } => Self::UserKicked { id, by },
             crate::SystemMessage::UserLeft { id } => Self::UserLeft { id },
             crate::SystemMessage::UserRemove { id, by } => Self::UserRemove { id, by },
         }
@@ -401,6 +693,7 @@ impl From<crate::Interactions> for Interactions {
                 .reactions
                 .map(|reactions| reactions.into_iter().collect()),
             restrict_reactions: value.restrict_reactions,
+            reaction_cooldowns: value.reaction_cooldowns,
         }
     }
 }
@@ -412,6 +705,7 @@ impl From<Interactions> for crate::Interactions {
                 .reactions
                 .map(|reactions| reactions.into_iter().collect()),
             restrict_reactions: value.restrict_reactions,
+            reaction_cooldowns: value.reaction_cooldowns,
         }
     }
 }
@@ -444,11 +738,24 @@ impl From<Masquerade> for crate::Masquerade {
     }
 }
 
+impl From<crate::User> for BannedUser {
+    fn from(value: crate::User) -> Self {
+        BannedUser {
+            id: value.id,
+            username: value.username,
+            discriminator: value.discriminator,
+            avatar: value.avatar.map(|file| file.into()),
+        }
+    }
+}
+
 impl From<crate::ServerBan> for ServerBan {
     fn from(value: crate::ServerBan) -> Self {
         ServerBan {
             id: value.id.into(),
             reason: value.reason,
+            moderator: value.moderator,
+            expires_at: value.expires_at,
         }
     }
 }
@@ -462,6 +769,9 @@ impl From<crate::Member> for Member {
             avatar: value.avatar.map(|f| f.into()),
             roles: value.roles,
             timeout: value.timeout,
+            timed_roles: value
+                .timed_roles
+                .map(|timed_roles| timed_roles.into_iter().map(|t| t.into()).collect()),
         }
     }
 }
@@ -475,6 +785,18 @@ impl From<crate::PartialMember> for PartialMember {
             avatar: value.avatar.map(|f| f.into()),
             roles: value.roles,
             timeout: value.timeout,
+            timed_roles: value
+                .timed_roles
+                .map(|timed_roles| timed_roles.into_iter().map(|t| t.into()).collect()),
+        }
+    }
+}
+
+impl From<crate::TimedRole> for TimedRole {
+    fn from(value: crate::TimedRole) -> Self {
+        TimedRole {
+            role: value.role,
+            expires_at: value.expires_at,
         }
     }
 }
@@ -495,6 +817,7 @@ impl From<crate::FieldsMember> for FieldsMember {
             crate::FieldsMember::Nickname => FieldsMember::Nickname,
             crate::FieldsMember::Roles => FieldsMember::Roles,
             crate::FieldsMember::Timeout => FieldsMember::Timeout,
+            crate::FieldsMember::TimedRoles => FieldsMember::TimedRoles,
         }
     }
 }
@@ -533,10 +856,17 @@ impl From<crate::Server> for Server {
             nsfw: value.nsfw,
             analytics: value.analytics,
             discoverable: value.discoverable,
+            embed_colour: value.embed_colour,
         }
     }
 }
 
+impl From<crate::ServerNameFilter> for ServerNameFilter {
+    fn from(value: crate::ServerNameFilter) -> Self {
+        ServerNameFilter { words: value.words }
+    }
+}
+
 impl From<crate::PartialServer> for PartialServer {
     fn from(value: crate::PartialServer) -> Self {
         PartialServer {
@@ -559,6 +889,7 @@ impl From<crate::PartialServer> for PartialServer {
             nsfw: value.nsfw,
             analytics: value.analytics,
             discoverable: value.discoverable,
+            embed_colour: value.embed_colour,
         }
     }
 }
@@ -569,6 +900,7 @@ impl From<crate::FieldsServer> for FieldsServer {
             crate::FieldsServer::Banner => FieldsServer::Banner,
             crate::FieldsServer::Categories => FieldsServer::Categories,
             crate::FieldsServer::Description => FieldsServer::Description,
+            crate::FieldsServer::EmbedColour => FieldsServer::EmbedColour,
             crate::FieldsServer::Icon => FieldsServer::Icon,
             crate::FieldsServer::SystemMessages => FieldsServer::SystemMessages,
         }
@@ -581,6 +913,44 @@ impl From<crate::Category> for Category {
             id: value.id,
             title: value.title,
             channels: value.channels,
+            default_permissions: value.default_permissions,
+            role_permissions: value.role_permissions,
+        }
+    }
+}
+
+impl From<crate::ServerTemplate> for ServerTemplate {
+    fn from(value: crate::ServerTemplate) -> Self {
+        ServerTemplate {
+            code: value.code,
+            server: value.server,
+            creator: value.creator,
+            name: value.name,
+            default_permissions: value.default_permissions,
+            roles: value
+                .roles
+                .into_iter()
+                .map(|(id, role)| (id, role.into()))
+                .collect(),
+            channels: value.channels.into_iter().map(|c| c.into()).collect(),
+            categories: value.categories.into_iter().map(|c| c.into()).collect(),
+        }
+    }
+}
+
+impl From<crate::TemplateChannel> for TemplateChannel {
+    fn from(value: crate::TemplateChannel) -> Self {
+        TemplateChannel {
+            id: value.id,
+            channel_type: match value.channel_type {
+                crate::TemplateChannelType::Text => LegacyServerChannelType::Text,
+                crate::TemplateChannelType::Voice => LegacyServerChannelType::Voice,
+            },
+            name: value.name,
+            description: value.description,
+            nsfw: value.nsfw,
+            default_permissions: value.default_permissions,
+            role_permissions: value.role_permissions,
         }
     }
 }
@@ -592,6 +962,7 @@ impl From<crate::SystemMessageChannels> for SystemMessageChannels {
             user_left: value.user_left,
             user_kicked: value.user_kicked,
             user_banned: value.user_banned,
+            role_expired: value.role_expired,
         }
     }
 }
@@ -604,6 +975,7 @@ impl From<crate::Role> for Role {
             colour: value.colour,
             hoist: value.hoist,
             rank: value.rank,
+            icon: value.icon.map(|f| f.into()),
         }
     }
 }
@@ -616,6 +988,7 @@ impl From<crate::PartialRole> for PartialRole {
             colour: value.colour,
             hoist: value.hoist,
             rank: value.rank,
+            icon: value.icon.map(|f| f.into()),
         }
     }
 }
@@ -624,6 +997,79 @@ impl From<crate::FieldsRole> for FieldsRole {
     fn from(value: crate::FieldsRole) -> Self {
         match value {
             crate::FieldsRole::Colour => FieldsRole::Colour,
+            crate::FieldsRole::Icon => FieldsRole::Icon,
+        }
+    }
+}
+
+impl From<crate::AuditLogEntry> for AuditLogEntry {
+    fn from(value: crate::AuditLogEntry) -> Self {
+        AuditLogEntry {
+            id: value.id,
+            server: value.server,
+            actor: value.actor,
+            action: value.action.into(),
+            target: value.target,
+            changes: value.changes.into_iter().map(|c| c.into()).collect(),
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<crate::AuditLogAction> for AuditLogAction {
+    fn from(value: crate::AuditLogAction) -> Self {
+        match value {
+            crate::AuditLogAction::RoleCreate => AuditLogAction::RoleCreate,
+            crate::AuditLogAction::RoleUpdate => AuditLogAction::RoleUpdate,
+            crate::AuditLogAction::RoleDelete => AuditLogAction::RoleDelete,
+            crate::AuditLogAction::RolePermissionsUpdate => AuditLogAction::RolePermissionsUpdate,
+            crate::AuditLogAction::DefaultPermissionsUpdate => {
+                AuditLogAction::DefaultPermissionsUpdate
+            }
+            crate::AuditLogAction::ChannelUpdate => AuditLogAction::ChannelUpdate,
+            crate::AuditLogAction::ChannelPermissionsUpdate => {
+                AuditLogAction::ChannelPermissionsUpdate
+            }
+            crate::AuditLogAction::CategoryPermissionsUpdate => {
+                AuditLogAction::CategoryPermissionsUpdate
+            }
+            crate::AuditLogAction::MemberKick => AuditLogAction::MemberKick,
+            crate::AuditLogAction::MemberBan => AuditLogAction::MemberBan,
+            crate::AuditLogAction::MemberUnban => AuditLogAction::MemberUnban,
+        }
+    }
+}
+
+impl From<AuditLogAction> for crate::AuditLogAction {
+    fn from(value: AuditLogAction) -> Self {
+        match value {
+            AuditLogAction::RoleCreate => crate::AuditLogAction::RoleCreate,
+            AuditLogAction::RoleUpdate => crate::AuditLogAction::RoleUpdate,
+            AuditLogAction::RoleDelete => crate::AuditLogAction::RoleDelete,
+            AuditLogAction::RolePermissionsUpdate => crate::AuditLogAction::RolePermissionsUpdate,
+            AuditLogAction::DefaultPermissionsUpdate => {
+                crate::AuditLogAction::DefaultPermissionsUpdate
+            }
+            AuditLogAction::ChannelUpdate => crate::AuditLogAction::ChannelUpdate,
+            AuditLogAction::ChannelPermissionsUpdate => {
+                crate::AuditLogAction::ChannelPermissionsUpdate
+            }
+            AuditLogAction::CategoryPermissionsUpdate => {
+                crate::AuditLogAction::CategoryPermissionsUpdate
+            }
+            AuditLogAction::MemberKick => crate::AuditLogAction::MemberKick,
+            AuditLogAction::MemberBan => crate::AuditLogAction::MemberBan,
+            AuditLogAction::MemberUnban => crate::AuditLogAction::MemberUnban,
+        }
+    }
+}
+
+impl From<crate::AuditLogChange> for AuditLogChange {
+    fn from(value: crate::AuditLogChange) -> Self {
+        AuditLogChange {
+            key: value.key,
+            before: value.before,
+            after: value.after,
         }
     }
 }
@@ -663,6 +1109,8 @@ impl crate::User {
             (RelationshipStatus::None, false)
         };
 
+        let is_self = perspective.map_or(false, |perspective| perspective.id == self.id);
+
         User {
             username: self.username,
             discriminator: self.discriminator,
@@ -695,6 +1143,7 @@ impl crate::User {
             flags: self.flags.unwrap_or_default() as u32,
             privileged: self.privileged,
             bot: self.bot.map(|bot| bot.into()),
+            hide_read_receipts: is_self && self.hide_read_receipts,
             relationship,
             online: can_see_profile && onechatsocial_presence::is_online(&self.id).await,
             id: self.id,
@@ -722,6 +1171,7 @@ impl crate::User {
             flags: self.flags.unwrap_or_default() as u32,
             privileged: self.privileged,
             bot: self.bot.map(|bot| bot.into()),
+            hide_read_receipts: self.hide_read_receipts,
             relationship: RelationshipStatus::User,
             online: onechatsocial_presence::is_online(&self.id).await,
             id: self.id,
@@ -748,6 +1198,7 @@ impl From<crate::PartialUser> for PartialUser {
             flags: value.flags.map(|flags| flags as u32),
             privileged: value.privileged,
             bot: value.bot.map(|bot| bot.into()),
+            hide_read_receipts: value.hide_read_receipts,
             relationship: None,
             online: None,
             id: value.id,
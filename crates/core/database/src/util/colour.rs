@@ -0,0 +1,343 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Named CSS colours and colour keywords
+static NAMED_COLOURS: &[&str] = &[
+    "aliceblue",
+    "antiquewhite",
+    "aqua",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanchedalmond",
+    "blue",
+    "blueviolet",
+    "brown",
+    "burlywood",
+    "cadetblue",
+    "chartreuse",
+    "chocolate",
+    "coral",
+    "cornflowerblue",
+    "cornsilk",
+    "crimson",
+    "currentcolor",
+    "cyan",
+    "darkblue",
+    "darkcyan",
+    "darkgoldenrod",
+    "darkgray",
+    "darkgreen",
+    "darkgrey",
+    "darkkhaki",
+    "darkmagenta",
+    "darkolivegreen",
+    "darkorange",
+    "darkorchid",
+    "darkred",
+    "darksalmon",
+    "darkseagreen",
+    "darkslateblue",
+    "darkslategray",
+    "darkslategrey",
+    "darkturquoise",
+    "darkviolet",
+    "deeppink",
+    "deepskyblue",
+    "dimgray",
+    "dimgrey",
+    "dodgerblue",
+    "firebrick",
+    "floralwhite",
+    "forestgreen",
+    "fuchsia",
+    "gainsboro",
+    "ghostwhite",
+    "gold",
+    "goldenrod",
+    "gray",
+    "green",
+    "greenyellow",
+    "grey",
+    "honeydew",
+    "hotpink",
+    "indianred",
+    "indigo",
+    "ivory",
+    "khaki",
+    "lavender",
+    "lavenderblush",
+    "lawngreen",
+    "lemonchiffon",
+    "lightblue",
+    "lightcoral",
+    "lightcyan",
+    "lightgoldenrodyellow",
+    "lightgray",
+    "lightgreen",
+    "lightgrey",
+    "lightpink",
+    "lightsalmon",
+    "lightseagreen",
+    "lightskyblue",
+    "lightslategray",
+    "lightslategrey",
+    "lightsteelblue",
+    "lightyellow",
+    "lime",
+    "limegreen",
+    "linen",
+    "magenta",
+    "maroon",
+    "mediumaquamarine",
+    "mediumblue",
+    "mediumorchid",
+    "mediumpurple",
+    "mediumseagreen",
+    "mediumslateblue",
+    "mediumspringgreen",
+    "mediumturquoise",
+    "mediumvioletred",
+    "midnightblue",
+    "mintcream",
+    "mistyrose",
+    "moccasin",
+    "navajowhite",
+    "navy",
+    "oldlace",
+    "olive",
+    "olivedrab",
+    "orange",
+    "orangered",
+    "orchid",
+    "palegoldenrod",
+    "palegreen",
+    "paleturquoise",
+    "palevioletred",
+    "papayawhip",
+    "peachpuff",
+    "peru",
+    "pink",
+    "plum",
+    "powderblue",
+    "purple",
+    "rebeccapurple",
+    "red",
+    "rosybrown",
+    "royalblue",
+    "saddlebrown",
+    "salmon",
+    "sandybrown",
+    "seagreen",
+    "seashell",
+    "sienna",
+    "silver",
+    "skyblue",
+    "slateblue",
+    "slategray",
+    "slategrey",
+    "snow",
+    "springgreen",
+    "steelblue",
+    "tan",
+    "teal",
+    "thistle",
+    "tomato",
+    "transparent",
+    "turquoise",
+    "violet",
+    "wheat",
+    "white",
+    "whitesmoke",
+    "yellow",
+    "yellowgreen",
+];
+
+/// Hex colour with 3, 4, 6 or 8 digits
+static RE_HEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^#([0-9a-f]{3,4}|[0-9a-f]{6}|[0-9a-f]{8})$").unwrap());
+
+/// Theme variable provided by clients
+static RE_VARIABLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^var\(--[a-z0-9-]+\)$").unwrap());
+
+/// Colour function, its arguments are checked separately
+static RE_FUNCTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(rgba?|hsla?)\(([^()]*)\)$").unwrap());
+
+/// Gradient function, its arguments are checked separately
+static RE_GRADIENT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(repeating-)?(linear|radial|conic)-gradient\((.*)\)$").unwrap());
+
+/// Angle, direction or shape which may lead a gradient
+static RE_GRADIENT_DIRECTION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(-?\d+(\.\d+)?(deg|grad|rad|turn)|to (top|bottom|left|right)( (top|bottom|left|right))?|from -?\d+(\.\d+)?deg|circle|ellipse)$").unwrap()
+});
+
+/// Position of a gradient colour stop
+static RE_GRADIENT_STOP: Lazy<Regex> = Lazy::new(|| Regex::new(r"^-?\d+(\.\d+)?(%|px)?$").unwrap());
+
+/// Check whether the given string is a CSS colour which clients can safely render
+///
+/// Accepts hex, rgb(a), hsl(a), named colours and theme variables,
+/// as well as linear, radial and conic gradients if allowed.
+pub fn is_valid_colour(colour: &str, allow_gradients: bool) -> bool {
+    let colour = colour.trim().to_lowercase();
+    if is_valid_solid_colour(&colour) {
+        return true;
+    }
+
+    if !allow_gradients {
+        return false;
+    }
+
+    let Some(captures) = RE_GRADIENT.captures(&colour) else {
+        return false;
+    };
+
+    let mut arguments = split_top_level(&captures[3], ',');
+    if arguments
+        .first()
+        .is_some_and(|argument| RE_GRADIENT_DIRECTION.is_match(argument))
+    {
+        arguments.remove(0);
+    }
+
+    arguments.len() >= 2
+        && arguments.iter().all(|stop| {
+            let parts = split_top_level(stop, ' ');
+            (1..=3).contains(&parts.len())
+                && is_valid_solid_colour(parts[0])
+                && parts[1..]
+                    .iter()
+                    .all(|part| RE_GRADIENT_STOP.is_match(part))
+        })
+}
+
+/// Check whether the given lowercase string is a single colour
+fn is_valid_solid_colour(colour: &str) -> bool {
+    if NAMED_COLOURS.contains(&colour) || RE_HEX.is_match(colour) || RE_VARIABLE.is_match(colour) {
+        return true;
+    }
+
+    let Some(captures) = RE_FUNCTION.captures(colour) else {
+        return false;
+    };
+
+    // Accept both the legacy comma and modern space separated syntax
+    let arguments: Vec<&str> = captures[2]
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|argument| !argument.is_empty())
+        .collect();
+
+    if !(3..=4).contains(&arguments.len()) {
+        return false;
+    }
+
+    let channels_valid = if captures[1].starts_with("rgb") {
+        arguments[..3]
+            .iter()
+            .all(|value| is_in_range(value, 255.0) || is_percentage(value))
+    } else {
+        is_in_range(arguments[0].trim_end_matches("deg"), 360.0)
+            && arguments[1..3].iter().all(|value| is_percentage(value))
+    };
+
+    channels_valid
+        && arguments.get(3).map_or(true, |alpha| {
+            is_in_range(alpha, 1.0) || is_percentage(alpha)
+        })
+}
+
+/// Check whether the value is a number between zero and the maximum
+fn is_in_range(value: &str, max: f64) -> bool {
+    value
+        .parse::<f64>()
+        .is_ok_and(|value| (0.0..=max).contains(&value))
+}
+
+/// Check whether the value is a percentage between 0% and 100%
+fn is_percentage(value: &str) -> bool {
+    value
+        .strip_suffix('%')
+        .is_some_and(|value| is_in_range(value, 100.0))
+}
+
+/// Split a string by the separator, ignoring separators within parentheses
+fn split_top_level(value: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0_usize;
+    let mut start = 0;
+
+    for (index, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 => {
+                parts.push(value[start..index].trim());
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(value[start..].trim());
+    parts.into_iter().filter(|part| !part.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_valid_colour;
+
+    #[test]
+    fn valid_colours() {
+        for colour in [
+            "red",
+            "RebeccaPurple",
+            "transparent",
+            "#fff",
+            "#A1B2C3",
+            "#a1b2c3d4",
+            "rgb(255, 0, 0)",
+            "rgba(0,0,0,0.5)",
+            "rgb(255 0 0 / 50%)",
+            "hsl(120, 100%, 50%)",
+            "hsla(120deg, 100%, 50%, 0.25)",
+            "var(--accent)",
+        ] {
+            assert!(is_valid_colour(colour, false), "{colour} should be valid");
+        }
+
+        for colour in [
+            "linear-gradient(90deg, red, #00f 50%)",
+            "linear-gradient(to top right, rgb(0, 0, 0) 0%, var(--accent) 100%)",
+            "repeating-radial-gradient(circle, red 0, blue 10%)",
+            "conic-gradient(from 45deg, red, orange, yellow)",
+        ] {
+            assert!(is_valid_colour(colour, true), "{colour} should be valid");
+            assert!(!is_valid_colour(colour, false), "{colour} is a gradient");
+        }
+    }
+
+    #[test]
+    fn invalid_colours() {
+        for colour in [
+            "",
+            "banana",
+            "light blue",
+            "#ggg",
+            "#12345",
+            "rgb(256, 0, 0)",
+            "rgb(1, 2)",
+            "rgba(0, 0, 0, 2)",
+            "hsl(120, 50, 50)",
+            "red; background: url(x)",
+            "url(https://example.com)",
+            "linear-gradient(red)",
+            "linear-gradient(90deg, red, nope)",
+            "linear-gradient(90deg, red 10% 20% 30%, blue)",
+        ] {
+            assert!(!is_valid_colour(colour, true), "{colour} should be invalid");
+        }
+    }
+}
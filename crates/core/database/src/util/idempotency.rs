@@ -1,37 +1,134 @@
-use std::num::NonZeroUsize;
+use std::{num::NonZeroUsize, sync::Arc, time::Instant};
 
+use onechatsocial_config::config;
 use onechatsocial_result::{create_error, Result};
 
 #[cfg(feature = "rocket-impl")]
 use onechatsocial_result::Error;
 
-use async_std::sync::Mutex;
+use async_std::sync::{Mutex, MutexGuardArc};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
 pub struct IdempotencyKey {
     key: String,
+    /// Whether the key was supplied by the client, only these are deduplicated
+    #[serde(skip)]
+    supplied: bool,
 }
 
-static TOKEN_CACHE: Lazy<Mutex<lru::LruCache<String, ()>>> =
+/// Message sent with a client supplied key
+struct SentMessage {
+    /// Fingerprint of the payload the message was sent with
+    fingerprint: u64,
+    /// Id of the created message
+    id: String,
+    /// When the message was created
+    sent_at: Instant,
+}
+
+/// Messages sent with each key, locked while a message is being sent
+type SentMessageSlot = Arc<Mutex<Option<SentMessage>>>;
+
+static MESSAGE_CACHE: Lazy<Mutex<lru::LruCache<String, SentMessageSlot>>> =
     Lazy::new(|| Mutex::new(lru::LruCache::new(NonZeroUsize::new(1000).unwrap())));
 
+/// Exclusive claim on an idempotency key while a message is sent with it
+///
+/// Any other sends with the same key wait until this claim is completed or dropped,
+/// dropping it without completing allows the next send to try again.
+pub struct MessageClaim {
+    slot: Option<MutexGuardArc<Option<SentMessage>>>,
+    fingerprint: u64,
+}
+
 impl IdempotencyKey {
-    // Backwards compatibility.
-    // Issue #109
-    pub async fn consume_nonce(&mut self, v: Option<String>) -> Result<()> {
-        if let Some(v) = v {
-            let mut cache = TOKEN_CACHE.lock().await;
-            if cache.get(&v).is_some() {
+    /// Create a key from a given string without checking it against the cache
+    pub fn unchecked_from_string(key: String) -> Self {
+        Self {
+            key,
+            supplied: false,
+        }
+    }
+
+    /// Create a key from a string supplied by the client
+    pub fn from_string(key: String) -> Self {
+        Self {
+            key,
+            supplied: true,
+        }
+    }
+
+    /// Claim this key for a message sent by the given author
+    ///
+    /// A legacy `nonce` takes the place of the key if present. Returns the id of the
+    /// message previously sent with the same key and payload within the replay window,
+    /// waiting for it to be sent if it is still in-flight. Reusing a key for a different
+    /// payload is rejected.
+    pub async fn claim_message(
+        &mut self,
+        nonce: Option<String>,
+        author: &str,
+        fingerprint: u64,
+    ) -> Result<(MessageClaim, Option<String>)> {
+        if let Some(nonce) = nonce {
+            self.key = nonce;
+            self.supplied = true;
+        }
+
+        if !self.supplied {
+            return Ok((
+                MessageClaim {
+                    slot: None,
+                    fingerprint,
+                },
+                None,
+            ));
+        }
+
+        let cache_key = format!("{author}:{}", self.key);
+        let slot = {
+            let mut cache = MESSAGE_CACHE.lock().await;
+            if let Some(slot) = cache.get(&cache_key) {
+                slot.clone()
+            } else {
+                let slot = SentMessageSlot::default();
+                cache.put(cache_key, slot.clone());
+                slot
+            }
+        };
+
+        let slot = slot.lock_arc().await;
+        if let Some(sent) = &*slot {
+            // A window of zero rejects all duplicates
+            let window = config().await.features.message_idempotency_window;
+            if window == 0 {
                 return Err(create_error!(DuplicateNonce));
             }
 
-            cache.put(v.clone(), ());
-            self.key = v;
+            if sent.sent_at.elapsed().as_secs() < window {
+                if sent.fingerprint != fingerprint {
+                    return Err(create_error!(DuplicateNonce));
+                }
+
+                return Ok((
+                    MessageClaim {
+                        slot: None,
+                        fingerprint,
+                    },
+                    Some(sent.id.clone()),
+                ));
+            }
         }
 
-        Ok(())
+        Ok((
+            MessageClaim {
+                slot: Some(slot),
+                fingerprint,
+            },
+            None,
+        ))
     }
 
     pub fn into_key(self) -> String {
@@ -39,6 +136,19 @@ impl IdempotencyKey {
     }
 }
 
+impl MessageClaim {
+    /// Record the message sent with this claim, releasing it
+    pub fn complete(mut self, message_id: &str) {
+        if let Some(mut slot) = self.slot.take() {
+            *slot = Some(SentMessage {
+                fingerprint: self.fingerprint,
+                id: message_id.to_string(),
+                sent_at: Instant::now(),
+            });
+        }
+    }
+}
+
 #[cfg(feature = "rocket-impl")]
 use revolt_rocket_okapi::{
     gen::OpenApiGenerator,
@@ -106,18 +216,66 @@ impl<'r> FromRequest<'r> for IdempotencyKey {
                 ));
             }
 
-            let idempotency = IdempotencyKey { key };
-            let mut cache = TOKEN_CACHE.lock().await;
-            if cache.get(&idempotency.key).is_some() {
-                return Outcome::Failure((Status::Conflict, create_error!(DuplicateNonce)));
-            }
-
-            cache.put(idempotency.key.clone(), ());
-            return Outcome::Success(idempotency);
+            // Duplicates are resolved when the message is sent,
+            // so that identical retries can be given the original message
+            return Outcome::Success(IdempotencyKey::from_string(key));
         }
 
-        Outcome::Success(IdempotencyKey {
-            key: ulid::Ulid::new().to_string(),
-        })
+        Outcome::Success(IdempotencyKey::unchecked_from_string(
+            ulid::Ulid::new().to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use onechatsocial_result::ErrorType;
+
+    use super::IdempotencyKey;
+
+    #[async_std::test]
+    async fn message_claims_are_scoped_to_author() {
+        let mut key = IdempotencyKey::from_string("scoped".to_string());
+        let (claim, existing) = key.claim_message(None, "alice", 1).await.unwrap();
+        assert_eq!(existing, None);
+        claim.complete("message");
+
+        let (_, existing) = key.claim_message(None, "alice", 1).await.unwrap();
+        assert_eq!(existing, Some("message".to_string()));
+
+        // Other authors may use the same key
+        let (_, existing) = key.claim_message(None, "bob", 2).await.unwrap();
+        assert_eq!(existing, None);
+
+        // Generated keys are never deduplicated
+        let mut key = IdempotencyKey::unchecked_from_string("scoped".to_string());
+        let (_, existing) = key.claim_message(None, "alice", 2).await.unwrap();
+        assert_eq!(existing, None);
+    }
+
+    #[async_std::test]
+    async fn conflicting_payload_is_rejected() {
+        let mut key = IdempotencyKey::from_string("conflict".to_string());
+        let (claim, _) = key.claim_message(None, "alice", 1).await.unwrap();
+        claim.complete("message");
+
+        assert!(matches!(
+            key.claim_message(None, "alice", 2)
+                .await
+                .unwrap_err()
+                .error_type,
+            ErrorType::DuplicateNonce
+        ));
+    }
+
+    #[async_std::test]
+    async fn failed_send_can_be_retried() {
+        let mut key = IdempotencyKey::from_string("failed".to_string());
+        let (claim, existing) = key.claim_message(None, "webhook", 1).await.unwrap();
+        assert_eq!(existing, None);
+        drop(claim);
+
+        let (_, existing) = key.claim_message(None, "webhook", 1).await.unwrap();
+        assert_eq!(existing, None);
     }
 }
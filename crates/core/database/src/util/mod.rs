@@ -1,7 +1,11 @@
 pub mod bridge;
+pub mod colour;
 pub mod idempotency;
+pub mod permission_cache;
 pub mod permissions;
 pub mod reference;
+pub mod token_bucket;
+pub mod ulid_generator;
 
 #[cfg(test)]
 pub mod test_fixtures;
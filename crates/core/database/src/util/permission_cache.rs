@@ -0,0 +1,375 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+use onechatsocial_permissions::OverrideField;
+
+/// Channel permissions recently calculated for each user
+pub(crate) static PERMISSION_CACHE: Lazy<PermissionCache> =
+    Lazy::new(|| PermissionCache::new(10_000));
+
+/// Redis channel used to tell every instance which servers to invalidate
+pub const INVALIDATION_CHANNEL: &str = "internal:permission_cache";
+
+/// Whether this instance is currently receiving invalidations from other instances
+///
+/// Cached permissions can not be trusted otherwise, so the cache is only used while set.
+static LISTENING: AtomicBool = AtomicBool::new(false);
+
+/// How long an invalidation is remembered for
+///
+/// Calculations which started before a server was invalidated are
+/// discarded, so this only needs to outlive any in-flight calculation.
+const INVALIDATION_GRACE: Duration = Duration::from_secs(60);
+
+/// Permissions calculated for a user in a server channel
+struct Entry {
+    server: String,
+    fingerprint: u64,
+    permissions: u64,
+    calculated: Instant,
+    /// Time after which the permissions lapse regardless of the TTL
+    expires: Option<Instant>,
+}
+
+/// Cached entries alongside the servers recently invalidated
+struct Entries {
+    entries: lru::LruCache<(String, String), Entry>,
+    invalidated: HashMap<String, Instant>,
+}
+
+/// Current state of the permission cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PermissionCacheState {
+    /// Number of calculations answered from the cache
+    pub hits: u64,
+    /// Number of calculations which had to be done again
+    pub misses: u64,
+    /// Number of entries currently cached
+    pub entries: usize,
+}
+
+/// Cache of server channel permissions keyed by user and channel
+///
+/// Entries are tied to the channel's overrides through a fingerprint and
+/// are dropped whenever anything else they depend on changes in the server.
+pub struct PermissionCache {
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl PermissionCache {
+    /// Create a new cache tracking at most the given number of entries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(Entries {
+                entries: lru::LruCache::new(
+                    NonZeroUsize::new(capacity).expect("must track at least one entry"),
+                ),
+                invalidated: HashMap::new(),
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Find permissions calculated within `ttl` for the given user and channel
+    pub fn get(
+        &self,
+        user: &str,
+        channel: &str,
+        server: &str,
+        fingerprint: u64,
+        ttl: Duration,
+    ) -> Option<u64> {
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let Entries {
+            entries,
+            invalidated,
+        } = &mut *entries;
+
+        let permissions = entries
+            .get(&(user.to_string(), channel.to_string()))
+            .filter(|entry| {
+                entry.server == server
+                    && entry.fingerprint == fingerprint
+                    && entry.calculated.elapsed() < ttl
+                    && entry.expires.map_or(true, |at| Instant::now() < at)
+                    && invalidated
+                        .get(server)
+                        .map_or(true, |at| *at < entry.calculated)
+            })
+            .map(|entry| entry.permissions);
+
+        if permissions.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+
+        permissions
+    }
+
+    /// Store permissions for the given user and channel
+    ///
+    /// `calculated` must be taken before any data was read for the calculation,
+    /// `expires` caps how long the entry may be used for if anything it was
+    /// calculated from lapses by itself.
+    #[allow(clippy::too_many_arguments)]
+    pub fn insert(
+        &self,
+        user: &str,
+        channel: &str,
+        server: &str,
+        fingerprint: u64,
+        permissions: u64,
+        calculated: Instant,
+        expires: Option<Instant>,
+    ) {
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        // Discard calculations which may have read data from before an invalidation
+        if entries
+            .invalidated
+            .get(server)
+            .is_some_and(|at| *at >= calculated)
+        {
+            return;
+        }
+
+        entries.entries.put(
+            (user.to_string(), channel.to_string()),
+            Entry {
+                server: server.to_string(),
+                fingerprint,
+                permissions,
+                calculated,
+                expires,
+            },
+        );
+    }
+
+    /// Drop all cached permissions for channels in the given server
+    pub fn invalidate_server(&self, server: &str) {
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let now = Instant::now();
+        entries
+            .invalidated
+            .retain(|_, at| now.duration_since(*at) < INVALIDATION_GRACE);
+        entries.invalidated.insert(server.to_string(), now);
+
+        let keys: Vec<(String, String)> = entries
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.server == server)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in keys {
+            entries.entries.pop(&key);
+        }
+    }
+
+    /// Drop all cached permissions
+    pub fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entries
+            .clear();
+    }
+
+    /// Get the current state of the cache
+    pub fn state(&self) -> PermissionCacheState {
+        PermissionCacheState {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self
+                .entries
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .entries
+                .len(),
+        }
+    }
+}
+
+/// Fingerprint the permission overrides of a channel
+pub fn fingerprint_overrides(
+    default_permissions: &Option<OverrideField>,
+    role_permissions: &HashMap<String, OverrideField>,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    default_permissions
+        .map(|field| (field.a, field.d))
+        .hash(&mut hasher);
+
+    let mut roles: Vec<(&String, i64, i64)> = role_permissions
+        .iter()
+        .map(|(id, field)| (id, field.a, field.d))
+        .collect();
+
+    roles.sort();
+    roles.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Drop all cached permissions for channels in the given server on every instance
+pub async fn invalidate_server(server: &str) {
+    PERMISSION_CACHE.invalidate_server(server);
+
+    if let Err(err) =
+        redis_kiss::publish(INVALIDATION_CHANNEL.to_string(), server.to_string()).await
+    {
+        error!("Failed to publish permission cache invalidation for {server} with {err:?}!");
+    }
+}
+
+/// Drop all cached permissions for channels in a server invalidated by any instance
+pub fn receive_invalidation(server: &str) {
+    PERMISSION_CACHE.invalidate_server(server);
+}
+
+/// Check whether cached permissions may be used
+pub fn is_listening() -> bool {
+    LISTENING.load(Ordering::Acquire)
+}
+
+/// Mark whether invalidations from other instances are being received
+///
+/// Anything cached is dropped once we stop listening, as invalidations may have been missed.
+pub fn set_listening(listening: bool) {
+    LISTENING.store(listening, Ordering::Release);
+    if !listening {
+        PERMISSION_CACHE.clear();
+    }
+}
+
+/// Get the current state of the permission cache
+pub fn cache_state() -> PermissionCacheState {
+    PERMISSION_CACHE.state()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        time::{Duration, Instant},
+    };
+
+    use onechatsocial_permissions::OverrideField;
+
+    use super::{fingerprint_overrides, PermissionCache};
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn invalidation() {
+        let cache = PermissionCache::new(10);
+        let fingerprint = fingerprint_overrides(&None, &HashMap::new());
+
+        cache.insert(
+            "user",
+            "channel",
+            "server",
+            fingerprint,
+            1,
+            Instant::now(),
+            None,
+        );
+        cache.insert(
+            "user",
+            "other",
+            "elsewhere",
+            fingerprint,
+            2,
+            Instant::now(),
+            None,
+        );
+        assert_eq!(
+            Some(1),
+            cache.get("user", "channel", "server", fingerprint, TTL)
+        );
+
+        // Changing the channel's overrides changes its fingerprint
+        let changed = fingerprint_overrides(&Some(OverrideField { a: 1, d: 0 }), &HashMap::new());
+        assert_ne!(fingerprint, changed);
+        assert_eq!(None, cache.get("user", "channel", "server", changed, TTL));
+
+        // Invalidating a server only drops entries in that server
+        let started = Instant::now();
+        cache.invalidate_server("server");
+        assert_eq!(
+            None,
+            cache.get("user", "channel", "server", fingerprint, TTL)
+        );
+        assert_eq!(
+            Some(2),
+            cache.get("user", "other", "elsewhere", fingerprint, TTL)
+        );
+
+        // Calculations which started before the invalidation are not stored
+        cache.insert("user", "channel", "server", fingerprint, 1, started, None);
+        assert_eq!(
+            None,
+            cache.get("user", "channel", "server", fingerprint, TTL)
+        );
+
+        cache.insert(
+            "user",
+            "channel",
+            "server",
+            fingerprint,
+            3,
+            Instant::now(),
+            None,
+        );
+        assert_eq!(
+            Some(3),
+            cache.get("user", "channel", "server", fingerprint, TTL)
+        );
+
+        // Entries expire after the given time
+        assert_eq!(
+            None,
+            cache.get("user", "channel", "server", fingerprint, Duration::ZERO)
+        );
+
+        // Entries calculated from something which lapses expire along with it
+        let now = Instant::now();
+        cache.insert("user", "channel", "server", fingerprint, 4, now, Some(now));
+        assert_eq!(
+            None,
+            cache.get("user", "channel", "server", fingerprint, TTL)
+        );
+
+        let state = cache.state();
+        assert_eq!(3, state.hits);
+        assert_eq!(5, state.misses);
+        assert_eq!(2, state.entries);
+
+        // Everything is dropped if invalidations may have been missed
+        cache.clear();
+        assert_eq!(0, cache.state().entries);
+    }
+}
@@ -1,11 +1,19 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    time::{Duration, Instant},
+};
 
+use iso8601_timestamp::Timestamp;
+use onechatsocial_config::config;
 use onechatsocial_permissions::{
     calculate_user_permissions, ChannelType, Override, PermissionQuery, PermissionValue,
     RelationshipStatus, DEFAULT_PERMISSION_DIRECT_MESSAGE,
 };
 
-use crate::{Channel, Database, Member, Server, User};
+use crate::{
+    util::permission_cache::{self, fingerprint_overrides, PERMISSION_CACHE},
+    Channel, Database, Member, Server, User, DEFAULT_ROLE_ID,
+};
 
 /// Permissions calculator
 #[derive(Clone)]
@@ -16,6 +24,7 @@ pub struct DatabasePermissionQuery<'a> {
     perspective: &'a User,
     user: Option<Cow<'a, User>>,
     channel: Option<Cow<'a, Channel>>,
+    parent_channel: Option<Channel>,
     server: Option<Cow<'a, Server>>,
     member: Option<Cow<'a, Member>>,
 
@@ -23,6 +32,9 @@ pub struct DatabasePermissionQuery<'a> {
     cached_user_permission: Option<PermissionValue>,
     cached_mutual_connection: Option<bool>,
     cached_permission: Option<u64>,
+
+    // Time at which a cacheable channel permission calculation started
+    cache_started: Option<Instant>,
 }
 
 #[async_trait]
@@ -138,7 +150,7 @@ impl PermissionQuery for DatabasePermissionQuery<'_> {
     /// Get default server permission
     async fn get_default_server_permissions(&mut self) -> u64 {
         if let Some(server) = &self.server {
-            server.default_permissions as u64
+            server.base_permissions()
         } else {
             0
         }
@@ -183,6 +195,11 @@ impl PermissionQuery for DatabasePermissionQuery<'_> {
 
     /// Get the type of the channel
     async fn get_channel_type(&mut self) -> ChannelType {
+        // Threads whose parent channel is gone have no permissions to take
+        if !self.load_parent_channel().await {
+            return ChannelType::Unknown;
+        }
+
         if let Some(channel) = &self.channel {
             match channel {
                 Cow::Borrowed(Channel::DirectMessage { .. })
@@ -205,58 +222,37 @@ impl PermissionQuery for DatabasePermissionQuery<'_> {
     /// Get the default channel permissions
     /// Group channel defaults should be mapped to an allow-only override
     async fn get_default_channel_permissions(&mut self) -> Override {
-        if let Some(channel) = &self.channel {
-            match channel {
-                Cow::Borrowed(Channel::Group { permissions, .. })
-                | Cow::Owned(Channel::Group { permissions, .. }) => Override {
-                    allow: permissions.unwrap_or(*DEFAULT_PERMISSION_DIRECT_MESSAGE as i64) as u64,
-                    deny: 0,
-                },
-                Cow::Borrowed(Channel::TextChannel {
+        match self.overriding_channel() {
+            Some(Channel::Group { permissions, .. }) => Override {
+                allow: permissions.unwrap_or(*DEFAULT_PERMISSION_DIRECT_MESSAGE as i64) as u64,
+                deny: 0,
+            },
+            Some(
+                Channel::TextChannel {
                     default_permissions,
                     ..
-                })
-                | Cow::Owned(Channel::TextChannel {
-                    default_permissions,
-                    ..
-                })
-                | Cow::Borrowed(Channel::VoiceChannel {
-                    default_permissions,
-                    ..
-                })
-                | Cow::Owned(Channel::VoiceChannel {
+                }
+                | Channel::VoiceChannel {
                     default_permissions,
                     ..
-                }) => default_permissions.unwrap_or_default().into(),
-                _ => Default::default(),
-            }
-        } else {
-            Default::default()
+                },
+            ) => default_permissions.unwrap_or_default().into(),
+            _ => Default::default(),
         }
     }
 
     /// Get the ordered role overrides (from lowest to highest) for this member in this channel
     async fn get_our_channel_role_overrides(&mut self) -> Vec<Override> {
-        if let Some(channel) = &self.channel {
+        if let Some(channel) = self.overriding_channel() {
             match channel {
-                Cow::Borrowed(Channel::TextChannel {
-                    role_permissions, ..
-                })
-                | Cow::Owned(Channel::TextChannel {
-                    role_permissions, ..
-                })
-                | Cow::Borrowed(Channel::VoiceChannel {
+                Channel::TextChannel {
                     role_permissions, ..
-                })
-                | Cow::Owned(Channel::VoiceChannel {
+                }
+                | Channel::VoiceChannel {
                     role_permissions, ..
-                }) => {
+                } => {
                     if let Some(server) = &self.server {
-                        let member_roles = self
-                            .member
-                            .as_ref()
-                            .map(|member| member.roles.clone())
-                            .unwrap_or_default();
+                        let member_roles = self.our_override_role_ids();
 
                         let mut roles = role_permissions
                             .iter()
@@ -282,6 +278,45 @@ impl PermissionQuery for DatabasePermissionQuery<'_> {
         }
     }
 
+    /// Get the default permissions of the category this channel is in
+    async fn get_default_category_permissions(&mut self) -> Override {
+        if let (Some(channel), Some(server)) = (self.overriding_channel(), &self.server) {
+            server
+                .category_of(&channel.id())
+                .and_then(|category| category.default_permissions)
+                .unwrap_or_default()
+                .into()
+        } else {
+            Default::default()
+        }
+    }
+
+    /// Get the ordered role overrides (from lowest to highest) for this member in this channel's category
+    async fn get_our_category_role_overrides(&mut self) -> Vec<Override> {
+        if let (Some(channel), Some(server)) = (self.overriding_channel(), &self.server) {
+            if let Some(category) = server.category_of(&channel.id()) {
+                let member_roles = self.our_override_role_ids();
+
+                let mut roles = category
+                    .role_permissions
+                    .iter()
+                    .filter(|(id, _)| member_roles.contains(id))
+                    .filter_map(|(id, permission)| {
+                        server.roles.get(id).map(|role| {
+                            let v: Override = (*permission).into();
+                            (role.rank, v)
+                        })
+                    })
+                    .collect::<Vec<(i64, Override)>>();
+
+                roles.sort_by(|a, b| b.0.cmp(&a.0));
+                return roles.into_iter().map(|(_, v)| v).collect();
+            }
+        }
+
+        vec![]
+    }
+
     /// Do we own this group or saved messages channel if it is one of those?
     async fn do_we_own_the_channel(&mut self) -> bool {
         if let Some(channel) = &self.channel {
@@ -338,6 +373,8 @@ impl PermissionQuery for DatabasePermissionQuery<'_> {
     /// Set the current server as the server owning this channel
     /// (this will only ever be called for server channels, use unimplemented!() for other code paths)
     async fn set_server_from_channel(&mut self) {
+        self.load_parent_channel().await;
+
         if let Some(channel) = &self.channel {
             match channel {
                 Cow::Borrowed(Channel::TextChannel { server, .. })
@@ -369,6 +406,74 @@ impl PermissionQuery for DatabasePermissionQuery<'_> {
             }
         }
     }
+
+    // * For caching channel permission
+
+    /// Get previously calculated permissions for this server channel, if still valid
+    async fn get_cached_channel_permissions(&mut self) -> Option<u64> {
+        // Only calculations which fetch everything themselves may be cached
+        if self.server.is_some() || self.member.is_some() {
+            return None;
+        }
+
+        let ttl = config().await.features.permission_cache_ttl;
+        if ttl == 0 || !permission_cache::is_listening() {
+            return None;
+        }
+
+        let (channel, server, fingerprint) = self.cache_key()?;
+        let permissions = PERMISSION_CACHE.get(
+            &self.perspective.id,
+            channel,
+            server,
+            fingerprint,
+            Duration::from_secs(ttl),
+        );
+
+        if permissions.is_none() {
+            self.cache_started = Some(Instant::now());
+        }
+
+        permissions
+    }
+
+    /// Store calculated permissions for this server channel
+    async fn set_cached_channel_permissions(&mut self, permissions: u64) {
+        // Timeouts and timed roles lapse without notice, so entries must not outlive them
+        let now = Timestamp::now_utc();
+        let expires = self
+            .member
+            .as_ref()
+            .and_then(|member| {
+                member
+                    .timeout
+                    .iter()
+                    .chain(
+                        member
+                            .timed_roles
+                            .iter()
+                            .flatten()
+                            .map(|timed_role| &timed_role.expires_at),
+                    )
+                    .filter_map(|at| Duration::try_from(**at - *now).ok())
+                    .min()
+            })
+            .map(|remaining| Instant::now() + remaining);
+
+        if let Some(calculated) = self.cache_started.take() {
+            if let Some((channel, server, fingerprint)) = self.cache_key() {
+                PERMISSION_CACHE.insert(
+                    &self.perspective.id,
+                    channel,
+                    server,
+                    fingerprint,
+                    permissions,
+                    calculated,
+                    expires,
+                );
+            }
+        }
+    }
 }
 
 impl<'a> DatabasePermissionQuery<'a> {
@@ -379,12 +484,87 @@ impl<'a> DatabasePermissionQuery<'a> {
             perspective,
             user: None,
             channel: None,
+            parent_channel: None,
             server: None,
             member: None,
 
             cached_mutual_connection: None,
             cached_user_permission: None,
             cached_permission: None,
+
+            cache_started: None,
+        }
+    }
+
+    /// Fetch the channel a thread was started in, as threads take their overrides from it
+    ///
+    /// Returns false if the current channel is a thread whose parent no longer exists.
+    async fn load_parent_channel(&mut self) -> bool {
+        let Some(Channel::TextChannel {
+            parent: Some(parent),
+            ..
+        }) = self.channel.as_deref()
+        else {
+            return true;
+        };
+
+        if self
+            .parent_channel
+            .as_ref()
+            .map_or(true, |channel| &channel.id() != parent)
+        {
+            self.parent_channel = self.database.fetch_channel(parent).await.ok();
+        }
+
+        self.parent_channel.is_some()
+    }
+
+    /// Get the channel whose overrides apply, which is the parent channel for threads
+    fn overriding_channel(&self) -> Option<&Channel> {
+        self.parent_channel.as_ref().or(self.channel.as_deref())
+    }
+
+    /// Get the ids of roles whose channel and category overrides apply to this member
+    ///
+    /// Every member has the default role, which always ranks the lowest.
+    fn our_override_role_ids(&self) -> Vec<String> {
+        let mut roles = self
+            .member
+            .as_ref()
+            .map(|member| member.roles.clone())
+            .unwrap_or_default();
+
+        roles.push(DEFAULT_ROLE_ID.to_string());
+        roles
+    }
+
+    /// Get the channel id, server id and overrides fingerprint used to cache permissions
+    ///
+    /// Threads are never cached as their overrides live on the parent channel.
+    fn cache_key(&self) -> Option<(&str, &str, u64)> {
+        match self.channel.as_deref()? {
+            Channel::TextChannel {
+                parent: Some(_), ..
+            } => None,
+            Channel::TextChannel {
+                id,
+                server,
+                default_permissions,
+                role_permissions,
+                ..
+            }
+            | Channel::VoiceChannel {
+                id,
+                server,
+                default_permissions,
+                role_permissions,
+                ..
+            } => Some((
+                id.as_str(),
+                server.as_str(),
+                fingerprint_overrides(default_permissions, role_permissions),
+            )),
+            _ => None,
         }
     }
 
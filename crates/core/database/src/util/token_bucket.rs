@@ -0,0 +1,98 @@
+use std::{
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Tokens remaining for a single key
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Set of token buckets keyed by an arbitrary id, such as a user id
+///
+/// Only the most recently used keys are tracked, evicted keys start again with a full bucket.
+pub struct TokenBuckets {
+    buckets: Mutex<lru::LruCache<String, Bucket>>,
+}
+
+impl TokenBuckets {
+    /// Create a new set of buckets tracking at most the given number of keys
+    pub fn new(keys: usize) -> Self {
+        Self {
+            buckets: Mutex::new(lru::LruCache::new(
+                NonZeroUsize::new(keys).expect("must track at least one key"),
+            )),
+        }
+    }
+
+    /// Take a token from the bucket for the given key
+    ///
+    /// Buckets hold `capacity` tokens and are refilled completely over `period`.
+    /// Returns how long to wait until a token is available if the bucket is empty.
+    pub fn take(&self, key: &str, capacity: u32, period: Duration) -> Result<(), Duration> {
+        self.take_at(key, capacity, period, Instant::now())
+    }
+
+    fn take_at(
+        &self,
+        key: &str,
+        capacity: u32,
+        period: Duration,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let capacity = capacity as f64;
+        let rate = capacity / period.as_secs_f64().max(f64::EPSILON);
+
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let bucket = buckets.get_or_insert_mut(key.to_string(), || Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::TokenBuckets;
+
+    #[test]
+    fn refills_over_time() {
+        let buckets = TokenBuckets::new(10);
+        let period = Duration::from_secs(10);
+        let now = Instant::now();
+
+        for _ in 0..5 {
+            assert!(buckets.take_at("user", 5, period, now).is_ok());
+        }
+
+        // Bucket is empty, one token is refilled every two seconds
+        let retry_after = buckets.take_at("user", 5, period, now).unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(2));
+
+        // Other keys are unaffected
+        assert!(buckets.take_at("other", 5, period, now).is_ok());
+
+        let later = now + Duration::from_secs(2);
+        assert!(buckets.take_at("user", 5, period, later).is_ok());
+        assert!(buckets.take_at("user", 5, period, later).is_err());
+    }
+}
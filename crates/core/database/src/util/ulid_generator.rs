@@ -0,0 +1,79 @@
+use std::{sync::Mutex, time::SystemTime};
+
+use once_cell::sync::Lazy;
+use ulid::Ulid;
+
+/// Last id handed out by this process
+static PREVIOUS: Lazy<Mutex<Ulid>> = Lazy::new(|| Mutex::new(Ulid::nil()));
+
+/// Generate a new ULID which is strictly greater than any previously generated
+///
+/// Ids which determine ordering, such as message ids, must use this over `Ulid::new`
+/// so that pagination stays correct if the system clock moves backwards.
+pub fn generate() -> Ulid {
+    let mut previous = PREVIOUS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    generate_after(&mut previous, SystemTime::now())
+}
+
+/// Generate a ULID for the given time which is strictly greater than the previous one
+fn generate_after(previous: &mut Ulid, now: SystemTime) -> Ulid {
+    let candidate = Ulid::from_datetime(now);
+    let next = if candidate > *previous {
+        candidate
+    } else {
+        // Clock stood still or went backwards, continue from the previous id
+        previous
+            .increment()
+            .unwrap_or_else(|| Ulid::from_parts(previous.timestamp_ms() + 1, 0))
+    };
+
+    *previous = next;
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use ulid::Ulid;
+
+    use super::{generate, generate_after};
+
+    #[test]
+    fn monotonic_under_clock_regression() {
+        let now = SystemTime::now();
+        let mut previous = Ulid::nil();
+
+        let first = generate_after(&mut previous, now);
+        let same_time = generate_after(&mut previous, now);
+        let went_back = generate_after(&mut previous, now - Duration::from_secs(5));
+        let recovered = generate_after(&mut previous, now + Duration::from_secs(1));
+
+        assert!(first < same_time);
+        assert!(same_time < went_back);
+        assert!(went_back < recovered);
+
+        // Ids generated while the clock was behind keep the latest timestamp seen
+        assert_eq!(went_back.timestamp_ms(), first.timestamp_ms());
+    }
+
+    #[test]
+    fn monotonic_on_overflow() {
+        let mut previous = Ulid::from_parts(1_000, u128::MAX);
+        let next = generate_after(&mut previous, SystemTime::UNIX_EPOCH);
+
+        assert_eq!(next, Ulid::from_parts(1_001, 0));
+    }
+
+    #[test]
+    fn strictly_increasing() {
+        let mut last = generate();
+        for _ in 0..1_000 {
+            let next = generate();
+            assert!(next > last);
+            last = next;
+        }
+    }
+}
@@ -24,6 +24,10 @@ auto_derived_partial!(
 
         /// The private token for the webhook
         pub token: Option<String>,
+
+        /// Template used to transform incoming payloads into messages
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub template: Option<WebhookTemplate>,
     },
     "PartialWebhook"
 );
@@ -52,6 +56,10 @@ auto_derived!(
         /// Webhook permissions
         pub permissions: Option<u64>,
 
+        /// Template used to transform incoming payloads into messages
+        #[cfg_attr(feature = "validator", validate)]
+        pub template: Option<WebhookTemplate>,
+
         /// Fields to remove from webhook
         #[cfg_attr(feature = "serde", serde(default))]
         pub remove: Vec<FieldsWebhook>,
@@ -78,6 +86,38 @@ auto_derived!(
     /// Optional fields on webhook object
     pub enum FieldsWebhook {
         Avatar,
+        Template,
+    }
+
+    /// Template used to transform incoming payloads into messages
+    ///
+    /// Each field may reference values from the payload using `{{ path.to.field }}`
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct WebhookTemplate {
+        /// Message content
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 2000)))]
+        pub content: Option<String>,
+        /// Embed title
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 100)))]
+        pub title: Option<String>,
+        /// Embed description
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 2000)))]
+        pub description: Option<String>,
+        /// Embed URL
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        pub url: Option<String>,
+        /// Embed icon URL
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 256)))]
+        pub icon_url: Option<String>,
+        /// Embed colour
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        pub colour: Option<String>,
     }
 
     /// Information for the webhook
@@ -88,6 +128,17 @@ auto_derived!(
 
         #[validate(length(min = 1, max = 128))]
         pub avatar: Option<String>,
+
+        /// Secret used to sign incoming requests
+        ///
+        /// When set, requests must carry a valid `X-Signature-256` header
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        #[validate(length(min = 8, max = 128))]
+        pub secret: Option<String>,
+
+        /// Template used to transform incoming payloads into messages
+        #[validate]
+        pub template: Option<WebhookTemplate>,
     }
 );
 
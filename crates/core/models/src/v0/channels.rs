@@ -1,6 +1,7 @@
 use super::File;
 
-use onechatsocial_permissions::OverrideField;
+use iso8601_timestamp::Timestamp;
+use onechatsocial_permissions::{Override, OverrideField};
 use std::collections::{HashMap, HashSet};
 
 auto_derived!(
@@ -104,6 +105,68 @@ auto_derived!(
                 serde(skip_serializing_if = "crate::if_false", default)
             )]
             nsfw: bool,
+
+            /// Minimum number of seconds between messages from each member
+            #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+            slowmode_seconds: Option<u16>,
+            /// Whether slowmode also applies to webhooks and system messages
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "crate::if_false", default)
+            )]
+            slowmode_applies_to_bots: bool,
+            /// Ids of roles whose members are exempt from slowmode
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Vec::is_empty", default)
+            )]
+            slowmode_exempt_roles: Vec<String>,
+
+            /// Whether only members who can manage messages may send messages,
+            /// everyone else may only read and react
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "crate::if_false", default)
+            )]
+            reactions_only: bool,
+
+            /// Whether link previews should not be generated for messages in this channel
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "crate::if_false", default)
+            )]
+            disable_embeds: bool,
+
+            /// Whether other channels may follow this channel to have its messages cross-posted
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "crate::if_false", default)
+            )]
+            announcement: bool,
+
+            /// Id of the channel this thread was started in
+            #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+            parent: Option<String>,
+            /// Id of the message this thread was started from
+            #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+            root_message: Option<String>,
+            /// Whether this thread was archived after going idle
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "crate::if_false", default)
+            )]
+            archived: bool,
+
+            /// Ids of pinned messages in their manually arranged order,
+            /// pinned messages are listed by pin time if empty
+            #[cfg_attr(
+                feature = "serde",
+                serde(skip_serializing_if = "Vec::is_empty", default)
+            )]
+            pin_order: Vec<String>,
+            /// Id of the last message sent in any thread started in this channel
+            #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+            last_thread_message_id: Option<String>,
         },
         /// Voice channel belonging to a server
         VoiceChannel {
@@ -167,6 +230,24 @@ auto_derived!(
         pub default_permissions: Option<OverrideField>,
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         pub last_message_id: Option<String>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub slowmode_exempt_roles: Option<Vec<String>>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub slowmode_seconds: Option<u16>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub slowmode_applies_to_bots: Option<bool>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub reactions_only: Option<bool>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub disable_embeds: Option<bool>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub announcement: Option<bool>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub archived: Option<bool>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub pin_order: Option<Vec<String>>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub last_thread_message_id: Option<String>,
     }
 
     /// Optional fields on channel object
@@ -174,6 +255,7 @@ auto_derived!(
         Description,
         Icon,
         DefaultPermissions,
+        SlowmodeSeconds,
     }
 
     /// New webhook information
@@ -257,6 +339,94 @@ auto_derived!(
         /// Whether this channel is age restricted
         #[serde(skip_serializing_if = "Option::is_none")]
         pub nsfw: Option<bool>,
+        /// Initial permission overrides for roles in this channel
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub role_permissions: Option<HashMap<String, Override>>,
+    }
+
+    /// Create new thread
+    #[cfg_attr(feature = "validator", derive(validator::Validate))]
+    pub struct DataCreateThread {
+        /// Thread name
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 32)))]
+        pub name: String,
+    }
+
+    /// New order of pinned messages
+    pub struct DataEditPinOrder {
+        /// Ids of every pinned message in the order they should be listed,
+        /// leave empty to list pinned messages by pin time
+        pub order: Vec<String>,
+    }
+
+    /// Follow relationship where new messages in one channel are cross-posted into another
+    pub struct ChannelFollow {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the channel being followed
+        pub channel: String,
+        /// Id of the channel receiving cross-posted messages
+        pub follower: String,
+        /// Id of the user who created this follow
+        pub creator: String,
+    }
+
+    /// Follow channel details
+    pub struct DataFollowChannel {
+        /// Id of the channel to cross-post new messages into
+        pub follower: String,
+    }
+
+    /// Which messages in a channel a user is notified of
+    pub enum NotificationLevel {
+        /// Push every message
+        All,
+        /// Only push messages which mention the user
+        Mentions,
+        /// Never push messages, mentions are still tracked
+        Muted,
+        /// Never push messages or track mentions
+        Nothing,
+    }
+
+    /// Notification preference of a user for a channel
+    pub struct ChannelNotificationPreference {
+        /// Which messages the user is notified of
+        pub level: NotificationLevel,
+    }
+
+    /// New notification preference for a channel
+    pub struct DataSetNotificationPreference {
+        /// Which messages to be notified of
+        pub level: NotificationLevel,
+    }
+
+    /// Permissions resolved for a user in a channel or server
+    pub struct EffectivePermissions {
+        /// Resolved permission bitfield
+        pub permissions: u64,
+        /// Names of each granted permission
+        pub granted: Vec<String>,
+    }
+
+    /// Summary of the messages pinned in a channel
+    pub struct ChannelPins {
+        /// Number of messages pinned in the channel
+        pub pin_count: usize,
+        /// Time at which the most recently pinned message was pinned
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub last_pinned_at: Option<Timestamp>,
+    }
+
+    /// Channel along with a summary of its pinned messages
+    pub struct ChannelWithPins {
+        /// Channel
+        #[serde(flatten)]
+        pub channel: Channel,
+        /// Summary of the messages pinned in the channel
+        #[serde(flatten)]
+        pub pins: ChannelPins,
     }
 );
 
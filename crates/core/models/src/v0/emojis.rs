@@ -54,4 +54,76 @@ auto_derived!(
         #[serde(default)]
         pub nsfw: bool,
     }
+
+    /// Emoji definition used to copy emoji between servers
+    pub struct EmojiDefinition {
+        /// Id of the emoji in the server it was exported from
+        pub id: String,
+        /// Emoji name
+        pub name: String,
+        /// Whether the emoji is animated
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub animated: bool,
+        /// Whether the emoji is marked as nsfw
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub nsfw: bool,
+    }
+
+    /// Emoji to import into a server
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct EmojiImport {
+        /// Id of the emoji in the server it was exported from
+        pub id: String,
+        /// Autumn upload id of the emoji image
+        ///
+        /// Uploads belong to a single emoji, so images must be uploaded again.
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        pub file: String,
+        /// Emoji name
+        #[validate(length(min = 1, max = 32), regex = "RE_EMOJI")]
+        pub name: String,
+        /// Whether the emoji is mature
+        #[serde(default)]
+        pub nsfw: bool,
+    }
+
+    /// Emoji to import into a server
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataImportEmoji {
+        /// Emoji definitions, as exported from another server
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 100)))]
+        pub emojis: Vec<EmojiImport>,
+    }
+
+    /// Emoji to delete from a server
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataBulkDeleteEmoji {
+        /// Ids of the emoji to delete
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 100)))]
+        pub ids: Vec<String>,
+    }
+
+    /// Result of deleting emoji in bulk
+    pub struct BulkDeleteEmojiResponse {
+        /// Ids of the emoji which were deleted
+        pub deleted: Vec<String>,
+        /// Ids which could not be deleted as they were not found in the server
+        pub failed: Vec<String>,
+    }
+
+    /// Emoji reaction statistics
+    pub struct EmojiLeaderboardEntry {
+        /// Emoji id or unicode emoji
+        pub emoji: String,
+        /// Total number of reactions using this emoji
+        pub reactions: usize,
+        /// Number of distinct users who reacted with this emoji
+        pub users: usize,
+    }
 );
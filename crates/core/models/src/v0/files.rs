@@ -14,6 +14,21 @@ auto_derived!(
         pub content_type: String,
         /// Size of this file (in bytes)
         pub size: isize,
+        /// Alternative text describing this file
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub description: Option<String>,
+        /// Whether this file is a recorded voice message
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub is_voice: bool,
+        /// Waveform of a voice message, one amplitude sample per byte
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub waveform: Option<Vec<u8>>,
+        /// Length of a voice message in milliseconds
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub duration: Option<u32>,
 
         /// Whether this file was deleted
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
@@ -15,6 +15,59 @@ use super::{Embed, File, MessageWebhook, User, Webhook, RE_COLOUR};
 pub static RE_MENTION: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"<@([0-9A-HJKMNP-TV-Z]{26})>").unwrap());
 
+pub static RE_ROLE_MENTION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"<%([0-9A-HJKMNP-TV-Z]{26})>").unwrap());
+
+pub static RE_MASS_MENTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"@(everyone|here)\b").unwrap());
+
+/// Find the first capture of every match which is not preceded by a backslash
+fn parse_unescaped<'a>(regex: &'a Regex, content: &'a str) -> impl Iterator<Item = &'a str> {
+    regex.captures_iter(content).filter_map(move |capture| {
+        let mention = capture.get(0)?;
+        if content[..mention.start()].ends_with('\\') {
+            None
+        } else {
+            capture.get(1).map(|id| id.as_str())
+        }
+    })
+}
+
+/// Find all user ids mentioned in the given content
+///
+/// Mentions preceded by a backslash (`\<@id>`) are escaped and ignored.
+pub fn parse_mentions(content: &str) -> impl Iterator<Item = &str> {
+    parse_unescaped(&RE_MENTION, content)
+}
+
+/// Find all role ids mentioned in the given content
+///
+/// Role mentions are written as `<%id>` and may be escaped like user mentions.
+pub fn parse_role_mentions(content: &str) -> impl Iterator<Item = &str> {
+    parse_unescaped(&RE_ROLE_MENTION, content)
+}
+
+/// Find all uses of `@everyone` and `@here` in the given content
+///
+/// Yields either `everyone` or `here` for each use.
+pub fn parse_mass_mentions(content: &str) -> impl Iterator<Item = &str> {
+    parse_unescaped(&RE_MASS_MENTION, content)
+}
+
+/// Escape all mentions in the given content so they are displayed but never parsed
+pub fn escape_mentions(content: &str) -> String {
+    let content = RE_MENTION.replace_all(content, r"\$0");
+    let content = RE_ROLE_MENTION.replace_all(&content, r"\$0");
+    RE_MASS_MENTION.replace_all(&content, r"\$0").into_owned()
+}
+
+/// Regex for valid BCP-47 language tags
+///
+/// Language, optionally followed by script, region and variants
+pub static RE_LANGUAGE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[a-zA-Z]{2,3}(-[a-zA-Z]{4})?(-([a-zA-Z]{2}|[0-9]{3}))?(-([a-zA-Z0-9]{5,8}|[0-9][a-zA-Z0-9]{3}))*$")
+        .unwrap()
+});
+
 auto_derived_partial!(
     /// Message
     pub struct Message {
@@ -40,27 +93,74 @@ auto_derived_partial!(
         /// Array of attachments
         #[serde(skip_serializing_if = "Option::is_none")]
         pub attachments: Option<Vec<File>>,
+        /// Array of sticker ids included in this message
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub stickers: Option<Vec<String>>,
         /// Time at which this message was last edited
         #[serde(skip_serializing_if = "Option::is_none")]
         pub edited: Option<Timestamp>,
+        /// Id of the user who last edited this message, if not the author
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub edited_by: Option<String>,
         /// Attached embeds to this message
         #[serde(skip_serializing_if = "Option::is_none")]
         pub embeds: Option<Vec<Embed>>,
         /// Array of user ids mentioned in this message
         #[serde(skip_serializing_if = "Option::is_none")]
         pub mentions: Option<Vec<String>>,
+        /// Array of role ids mentioned in this message
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub role_mentions: Option<Vec<String>>,
         /// Array of message ids this message is replying to
         #[serde(skip_serializing_if = "Option::is_none")]
         pub replies: Option<Vec<String>>,
+        /// Copies of the replied messages taken when this message was sent
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub reply_snapshots: Option<Vec<MessageSnapshot>>,
         /// Hashmap of emoji IDs to array of user IDs
         #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
         pub reactions: IndexMap<String, IndexSet<String>>,
+        /// Hashmap of emoji IDs to number of reactions
+        ///
+        /// Sent in place of `reactions` for messages with a large number of reactions
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub reaction_counts: Option<IndexMap<String, usize>>,
         /// Information about how this message should be interacted with
         #[serde(skip_serializing_if = "Interactions::is_default", default)]
         pub interactions: Interactions,
         /// Name and / or avatar overrides for this message
         #[serde(skip_serializing_if = "Option::is_none")]
         pub masquerade: Option<Masquerade>,
+        /// Whether this message contains spoilers in its content or attachments
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub has_spoilers: bool,
+        /// BCP-47 language tag of this message's content, if known
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub language: Option<String>,
+        /// Number of replies to this message
+        #[serde(skip_serializing_if = "crate::if_zero_u32", default)]
+        pub reply_count: u32,
+        /// Number of distinct users who replied to this message
+        #[serde(skip_serializing_if = "crate::if_zero_u32", default)]
+        pub thread_participant_count: u32,
+        /// Whether this message is pinned in its channel
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub pinned: Option<bool>,
+        /// Poll attached to this message
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub poll: Option<Poll>,
+        /// Time at which this message will be deleted
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub expires_at: Option<Timestamp>,
+        /// Message this message was forwarded from
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub forwarded_from: Option<MessageReference>,
+        /// Bitfield of message flags
+        #[serde(skip_serializing_if = "crate::if_zero_u32", default)]
+        pub flags: u32,
+        /// Effective name and avatar to display for the author of this message
+        #[serde(default)]
+        pub display_author: DisplayAuthor,
     },
     "PartialMessage"
 );
@@ -80,9 +180,17 @@ auto_derived!(
         #[serde(rename = "user_left")]
         UserLeft { id: String },
         #[serde(rename = "user_kicked")]
-        UserKicked { id: String },
+        UserKicked {
+            id: String,
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            by: Option<String>,
+        },
         #[serde(rename = "user_banned")]
-        UserBanned { id: String },
+        UserBanned {
+            id: String,
+            #[serde(skip_serializing_if = "Option::is_none", default)]
+            by: Option<String>,
+        },
         #[serde(rename = "channel_renamed")]
         ChannelRenamed { name: String, by: String },
         #[serde(rename = "channel_description_changed")]
@@ -91,6 +199,24 @@ auto_derived!(
         ChannelIconChanged { by: String },
         #[serde(rename = "channel_ownership_changed")]
         ChannelOwnershipChanged { from: String, to: String },
+        #[serde(rename = "message_pinned")]
+        MessagePinned { id: String, by: String },
+        #[serde(rename = "message_unpinned")]
+        MessageUnpinned { id: String, by: String },
+        #[serde(rename = "poll")]
+        Poll {
+            question: String,
+            options: Vec<String>,
+            by: String,
+        },
+        #[serde(rename = "role_expired")]
+        RoleExpired { id: String, role: String },
+        #[serde(rename = "thread_created")]
+        ThreadCreated {
+            id: String,
+            name: String,
+            by: String,
+        },
     }
 
     /// Name and / or avatar override information
@@ -112,6 +238,40 @@ auto_derived!(
         pub colour: Option<String>,
     }
 
+    /// Name and avatar to display for the author of a message
+    ///
+    /// Masquerade overrides take priority over webhook details,
+    /// any field which is not set should fall back to the author's own profile.
+    #[derive(Default)]
+    pub struct DisplayAuthor {
+        /// Name to display
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub name: Option<String>,
+        /// Avatar to display
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub avatar: Option<DisplayAvatar>,
+        /// Role colour to display
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub colour: Option<String>,
+        /// Autumn file id of the author's hoisted role icon
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub role_icon: Option<String>,
+    }
+
+    /// Avatar to display for the author of a message
+    #[serde(tag = "type")]
+    pub enum DisplayAvatar {
+        /// Image URL given by a masquerade
+        Url { url: String },
+        /// Autumn file id of a webhook avatar
+        File { id: String },
+    }
+
+    /// Optional fields on message object
+    pub enum FieldsMessage {
+        EditedBy,
+    }
+
     /// Information to guide interactions on this message
     #[derive(Default)]
     pub struct Interactions {
@@ -123,6 +283,80 @@ auto_derived!(
         /// Can only be set to true if reactions list is of at least length 1
         #[serde(skip_serializing_if = "crate::if_false", default)]
         pub restrict_reactions: bool,
+        /// Seconds a user must wait before adding a given reaction again
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        pub reaction_cooldowns: Option<IndexMap<String, u64>>,
+    }
+
+    /// Message flag bitfield
+    #[repr(u32)]
+    pub enum MessageFlags {
+        /// Link previews are neither generated nor shown for this message
+        SuppressEmbeds = 1,
+    }
+
+    /// Net change to a user's reaction on a message
+    pub struct ReactionDelta {
+        /// Id of the user who reacted
+        pub user_id: String,
+        /// Id of the emoji
+        pub emoji_id: String,
+        /// Whether the reaction was added, otherwise it was removed
+        pub added: bool,
+    }
+
+    /// Reference to a message in another channel
+    pub struct MessageReference {
+        /// Id of the channel the message was sent in
+        pub channel: String,
+        /// Id of the message
+        pub message: String,
+        /// Id of the user or webhook that sent the message
+        pub author: String,
+    }
+
+    /// Copy of a replied message taken when the reply was sent
+    pub struct MessageSnapshot {
+        /// Id of the message
+        pub message: String,
+        /// Id of the user or webhook that sent the message
+        pub author: String,
+        /// Message content, truncated to the configured length
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub content: Option<String>,
+    }
+
+    /// Poll which users can vote on
+    pub struct Poll {
+        /// Options which can be voted for
+        pub options: Vec<PollOption>,
+        /// Whether users may vote for more than one option
+        #[serde(skip_serializing_if = "crate::if_false", default)]
+        pub multi_select: bool,
+        /// Time after which votes are no longer accepted
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub closes_at: Option<Timestamp>,
+    }
+
+    /// Option of a poll
+    pub struct PollOption {
+        /// Name of this option
+        pub name: String,
+        /// Ids of users who voted for this option
+        #[serde(default)]
+        pub voters: IndexSet<String>,
+    }
+
+    /// Previous version of an edited message
+    pub struct MessageRevision {
+        /// Message content
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub content: Option<String>,
+        /// Attached embeds
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub embeds: Option<Vec<Embed>>,
+        /// Time at which this version was replaced by an edit
+        pub edited_at: Timestamp,
     }
 
     /// Appended Information
@@ -146,10 +380,43 @@ auto_derived!(
         Oldest,
     }
 
+    /// Message Has
+    ///
+    /// Content a message must have to be matched
+    pub enum MessageHas {
+        /// Message has at least one attachment
+        Attachment,
+        /// Message has at least one embed
+        Embed,
+        /// Message content contains a link
+        Link,
+        /// Message has at least one reaction
+        Reaction,
+    }
+
+    /// Message being replied to, shown in push notifications
+    pub struct PushNotificationReply {
+        /// Known author name of the replied message
+        pub author: String,
+        /// Snippet of the replied message content
+        pub snippet: String,
+    }
+
     /// Push Notification
     pub struct PushNotification {
         /// Known author name
         pub author: String,
+        /// Summary of who sent this and where
+        pub title: String,
+        /// Name of the channel or group this was sent in
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub channel_name: Option<String>,
+        /// Name of the server this was sent in
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub server_name: Option<String>,
+        /// Message being replied to
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub reply: Option<PushNotificationReply>,
         /// URL to author avatar
         pub icon: String,
         /// URL to first matching attachment
@@ -190,6 +457,36 @@ auto_derived!(
         pub mention: bool,
     }
 
+    /// Type of mention which can be parsed from message content
+    pub enum MentionType {
+        /// Users mentioned by id
+        Users,
+        /// Roles mentioned by id
+        Roles,
+        /// Everyone who can see the channel
+        Everyone,
+    }
+
+    /// Which mentions in the message content should notify
+    ///
+    /// A type of mention may either be parsed in full or limited to an
+    /// explicit list of ids, but not both at once.
+    #[derive(Default)]
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct AllowedMentions {
+        /// Types of mentions to parse from the content
+        #[serde(default)]
+        pub parse: Vec<MentionType>,
+        /// Users which may be mentioned
+        #[serde(default)]
+        #[validate(length(max = 100))]
+        pub users: Vec<String>,
+        /// Roles which may be mentioned
+        #[serde(default)]
+        #[validate(length(max = 100))]
+        pub roles: Vec<String>,
+    }
+
     /// Message to send
     #[cfg_attr(feature = "validator", derive(Validate))]
     pub struct DataMessageSend {
@@ -200,10 +497,17 @@ auto_derived!(
         pub nonce: Option<String>,
 
         /// Message content to send
-        #[validate(length(min = 0, max = 2000))]
+        ///
+        /// Maximum length depends on the server's tier
         pub content: Option<String>,
         /// Attachments to include in message
         pub attachments: Option<Vec<String>>,
+        /// Alternative text for each attachment, in the same order
+        pub attachment_descriptions: Option<Vec<Option<String>>>,
+        /// Stickers to include in message
+        ///
+        /// A message may consist of only stickers.
+        pub stickers: Option<Vec<String>>,
         /// Messages to reply to
         pub replies: Option<Vec<ReplyIntent>>,
         /// Embeds to include in message
@@ -216,6 +520,148 @@ auto_derived!(
         pub masquerade: Option<Masquerade>,
         /// Information about how this message should be interacted with
         pub interactions: Option<Interactions>,
+        /// Message to quote, a snapshot of its content is included as an embed
+        #[validate(length(min = 26, max = 26))]
+        pub quote: Option<String>,
+        /// BCP-47 language tag of the message content
+        ///
+        /// Detected from the content if not given.
+        #[validate(length(min = 2, max = 35), regex = "RE_LANGUAGE")]
+        pub language: Option<String>,
+        /// Only send if this is still the latest message in the channel
+        ///
+        /// Sending fails with a conflict if another message was sent in the meantime.
+        #[validate(length(min = 26, max = 26))]
+        pub if_last_message: Option<String>,
+        /// Time at which this message should be sent
+        ///
+        /// May be up to 30 days ahead, times in the past are sent immediately.
+        pub send_at: Option<Timestamp>,
+        /// Number of seconds after sending until this message is deleted
+        ///
+        /// Must be between 10 seconds and 7 days.
+        #[validate(range(min = 10, max = 604800))]
+        pub expires_in: Option<u32>,
+        /// Which mentions in the content should notify
+        ///
+        /// All mentions notify if not given.
+        #[validate]
+        pub allowed_mentions: Option<AllowedMentions>,
+        /// Send the only attachment as a voice message
+        ///
+        /// The attachment must be an audio file and no content may be given.
+        #[validate]
+        pub voice: Option<VoiceMessage>,
+    }
+
+    /// Recording details of a voice message
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct VoiceMessage {
+        /// Waveform of the recording, one amplitude sample per byte
+        #[validate(length(min = 1, max = 256))]
+        pub waveform: Vec<u8>,
+        /// Length of the recording in milliseconds
+        pub duration: u32,
+    }
+
+    /// Poll to create
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataCreatePoll {
+        /// Question being asked
+        #[validate(length(min = 1, max = 256))]
+        pub question: String,
+        /// Options which can be voted for
+        #[validate(length(min = 2, max = 10))]
+        pub options: Vec<String>,
+        /// Whether users may vote for more than one option
+        #[serde(default)]
+        pub multi_select: bool,
+        /// Time after which votes are no longer accepted
+        pub closes_at: Option<Timestamp>,
+    }
+
+    /// New flags for a message
+    pub struct DataEditMessageFlags {
+        /// Bitfield of message flags
+        pub flags: u32,
+    }
+
+    /// Changes to a message
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataEditMessage {
+        /// New message content
+        ///
+        /// Maximum length depends on the server's tier
+        #[validate(length(min = 1))]
+        pub content: Option<String>,
+        /// Embeds to replace the message's embeds with
+        ///
+        /// Text embed content contributes to the content length cap
+        #[validate]
+        #[validate(length(max = 10))]
+        pub embeds: Option<Vec<SendableEmbed>>,
+        /// Ids of the message's attachments in their new order
+        ///
+        /// Must contain exactly the attachments already on the message.
+        #[validate(length(min = 1))]
+        pub attachments: Option<Vec<String>>,
+    }
+
+    /// Message forwarding details
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataForwardMessage {
+        /// Id of the channel to forward the message to
+        #[validate(length(min = 26, max = 26))]
+        pub target: String,
+    }
+
+    /// Vote to cast on a poll
+    pub struct DataPollVote {
+        /// Index of the option to vote for
+        ///
+        /// Voting for an option again removes the vote.
+        pub option: usize,
+    }
+
+    /// Role granted to users who react to a message with a given emoji
+    pub struct ReactionRole {
+        /// Unique Id
+        #[serde(rename = "_id")]
+        pub id: String,
+        /// Id of the message to react to
+        pub message: String,
+        /// Emoji which grants the role
+        pub emoji: String,
+        /// Id of the role to grant
+        pub role: String,
+    }
+
+    /// Reaction role details
+    pub struct DataSetReactionRole {
+        /// Id of the role to grant
+        pub role: String,
+    }
+
+    /// Record of a deleted message, without any of its content
+    pub struct MessageTombstone {
+        /// Id of the deleted message
+        pub message: String,
+        /// Id of the channel the message was sent in
+        pub channel: String,
+        /// Id of the user or webhook that sent the message
+        pub author: String,
+        /// Id of the user that deleted the message
+        pub deleted_by: String,
+        /// Time at which the message was deleted
+        pub deleted_at: Timestamp,
+    }
+
+    /// Message which will be sent at a later time
+    pub struct ScheduledMessage {
+        /// Time at which the message will be sent
+        pub send_at: Timestamp,
+        /// Message which will be sent
+        pub message: Message,
     }
 );
 
@@ -226,9 +672,47 @@ pub enum MessageAuthor<'a> {
     System {
         username: &'a str,
         avatar: Option<&'a str>,
+        /// Name of the user whose action caused this system message
+        by: Option<String>,
     },
 }
 
+impl Message {
+    /// Replace reactions with per-emoji counts if there are more than `threshold` entries
+    pub fn summarise_reactions(&mut self, threshold: usize) {
+        let entries: usize = self.reactions.values().map(IndexSet::len).sum();
+        if entries > threshold {
+            self.reaction_counts = Some(
+                std::mem::take(&mut self.reactions)
+                    .into_iter()
+                    .map(|(emoji, users)| (emoji, users.len()))
+                    .collect(),
+            );
+        }
+    }
+}
+
+impl DisplayAuthor {
+    /// Resolve the display details from a message's masquerade and webhook
+    pub fn resolve(masquerade: Option<&Masquerade>, webhook: Option<&MessageWebhook>) -> Self {
+        DisplayAuthor {
+            name: masquerade
+                .and_then(|masquerade| masquerade.name.clone())
+                .or_else(|| webhook.map(|webhook| webhook.name.clone())),
+            avatar: masquerade
+                .and_then(|masquerade| masquerade.avatar.clone())
+                .map(|url| DisplayAvatar::Url { url })
+                .or_else(|| {
+                    webhook
+                        .and_then(|webhook| webhook.avatar.clone())
+                        .map(|id| DisplayAvatar::File { id })
+                }),
+            colour: masquerade.and_then(|masquerade| masquerade.colour.clone()),
+            role_icon: None,
+        }
+    }
+}
+
 impl Interactions {
     /// Check if default initialisation of fields
     pub fn is_default(&self) -> bool {
@@ -236,6 +720,41 @@ impl Interactions {
     }
 }
 
+impl AllowedMentions {
+    /// Allow every type of mention to be parsed
+    pub fn all() -> Self {
+        AllowedMentions {
+            parse: vec![
+                MentionType::Users,
+                MentionType::Roles,
+                MentionType::Everyone,
+            ],
+            ..Default::default()
+        }
+    }
+
+    /// Check whether a type of mention is both parsed and restricted to a list
+    pub fn is_ambiguous(&self) -> bool {
+        (self.parse.contains(&MentionType::Users) && !self.users.is_empty())
+            || (self.parse.contains(&MentionType::Roles) && !self.roles.is_empty())
+    }
+
+    /// Check whether the given user may be mentioned
+    pub fn allows_user(&self, id: &str) -> bool {
+        self.parse.contains(&MentionType::Users) || self.users.iter().any(|user| user == id)
+    }
+
+    /// Check whether the given role may be mentioned
+    pub fn allows_role(&self, id: &str) -> bool {
+        self.parse.contains(&MentionType::Roles) || self.roles.iter().any(|role| role == id)
+    }
+
+    /// Check whether `@everyone` and `@here` may be used
+    pub fn allows_everyone(&self) -> bool {
+        self.parse.contains(&MentionType::Everyone)
+    }
+}
+
 impl<'a> MessageAuthor<'a> {
     pub fn id(&self) -> &str {
         match self {
@@ -262,6 +781,29 @@ impl<'a> MessageAuthor<'a> {
     }
 }
 
+impl SystemMessage {
+    /// Describe this system message for notifications
+    ///
+    /// The subject is the name of the user this message is about,
+    /// `by` is the name of the user who caused it, if known.
+    pub fn describe(&self, subject: &str, by: Option<&str>) -> String {
+        let action = match self {
+            SystemMessage::UserAdded { .. } => "was added",
+            SystemMessage::UserRemove { .. } => "was removed",
+            SystemMessage::UserKicked { .. } => "was kicked",
+            SystemMessage::UserBanned { .. } => "was banned",
+            SystemMessage::UserJoined { .. } => return format!("{subject} joined"),
+            SystemMessage::UserLeft { .. } => return format!("{subject} left"),
+            _ => return self.clone().into(),
+        };
+
+        match by {
+            Some(by) => format!("{subject} {action} by {by}"),
+            None => format!("{subject} {action}"),
+        }
+    }
+}
+
 impl From<SystemMessage> for String {
     fn from(s: SystemMessage) -> String {
         match s {
@@ -280,13 +822,45 @@ impl From<SystemMessage> for String {
             SystemMessage::ChannelOwnershipChanged { .. } => {
                 "Channel ownership changed.".to_string()
             }
+            SystemMessage::MessagePinned { .. } => "Message pinned.".to_string(),
+            SystemMessage::MessageUnpinned { .. } => "Message unpinned.".to_string(),
+            SystemMessage::Poll { question, .. } => format!("Poll: {question}"),
+            SystemMessage::RoleExpired { .. } => "Role expired.".to_string(),
+            SystemMessage::ThreadCreated { name, .. } => format!("Thread started: {name}"),
         }
     }
 }
 
+/// Maximum length of the notification body, keeps us within push provider payload limits
+const PUSH_BODY_LENGTH: usize = 512;
+
+/// Maximum length of names and reply snippets in push notifications
+const PUSH_SNIPPET_LENGTH: usize = 64;
+
+/// Truncate text to a given number of characters, adding an ellipsis if it was cut
+fn truncate(text: String, length: usize) -> String {
+    if text.chars().count() > length {
+        let mut text: String = text.chars().take(length - 1).collect();
+        text.push('…');
+        text
+    } else {
+        text
+    }
+}
+
 impl PushNotification {
     /// Create a new notification from a given message, author and channel ID
-    pub async fn from(msg: Message, author: Option<MessageAuthor<'_>>, channel_id: &str) -> Self {
+    ///
+    /// Channel and server names are used to describe where the message was sent,
+    /// group and direct message channels should not provide a server name.
+    pub async fn from(
+        msg: &Message,
+        author: Option<MessageAuthor<'_>>,
+        channel_id: &str,
+        channel_name: Option<String>,
+        server_name: Option<String>,
+        reply: Option<PushNotificationReply>,
+    ) -> Self {
         let config = config().await;
 
         let icon = if let Some(author) = &author {
@@ -299,16 +873,21 @@ impl PushNotification {
             format!("{}/assets/logo.png", config.hosts.app)
         };
 
-        let image = msg.attachments.and_then(|attachments| {
+        let image = msg.attachments.as_ref().and_then(|attachments| {
             attachments
                 .first()
                 .map(|v| format!("{}/attachments/{}", config.hosts.autumn, v.id))
         });
 
-        let body = if let Some(sys) = msg.system {
-            sys.into()
-        } else if let Some(text) = msg.content {
-            text
+        let body = if let Some(sys) = &msg.system {
+            match &author {
+                Some(MessageAuthor::System { username, by, .. }) => {
+                    sys.describe(username, by.as_deref())
+                }
+                _ => sys.clone().into(),
+            }
+        } else if let Some(text) = &msg.content {
+            text.clone()
         } else {
             "Empty Message".to_string()
         };
@@ -318,13 +897,41 @@ impl PushNotification {
             .expect("Time went backwards")
             .as_secs();
 
+        let author = match author {
+            Some(MessageAuthor::System { .. }) => "System".to_string(),
+            Some(author) => author.username().to_string(),
+            None => "Revolt".to_string(),
+        };
+
+        let channel_name = channel_name.map(|name| truncate(name, PUSH_SNIPPET_LENGTH));
+        let server_name = server_name.map(|name| truncate(name, PUSH_SNIPPET_LENGTH));
+        let reply = reply.map(|reply| PushNotificationReply {
+            author: truncate(reply.author, PUSH_SNIPPET_LENGTH),
+            snippet: truncate(reply.snippet, PUSH_SNIPPET_LENGTH),
+        });
+
+        let mut title = if let Some(reply) = &reply {
+            format!("{author} replied to {}", reply.author)
+        } else {
+            author.clone()
+        };
+
+        // Server channels are prefixed with a hash, groups are shown by name
+        match (&channel_name, &server_name) {
+            (Some(channel), Some(_)) => title.push_str(&format!(" in #{channel}")),
+            (Some(group), None) => title.push_str(&format!(" in {group}")),
+            _ => {}
+        }
+
         Self {
-            author: author
-                .map(|x| x.username().to_string())
-                .unwrap_or_else(|| "Revolt".to_string()),
+            author,
+            title,
+            channel_name,
+            server_name,
+            reply,
             icon,
             image,
-            body,
+            body: truncate(body, PUSH_BODY_LENGTH),
             tag: channel_id.to_string(),
             timestamp,
             url: format!("{}/channel/{}/{}", config.hosts.app, channel_id, msg.id),
@@ -1,4 +1,6 @@
-use super::MemberCompositeKey;
+use iso8601_timestamp::Timestamp;
+
+use super::{File, MemberCompositeKey};
 
 auto_derived!(
     /// Server Ban
@@ -8,5 +10,34 @@ auto_derived!(
         pub id: MemberCompositeKey,
         /// Reason for ban creation
         pub reason: Option<String>,
+        /// Id of the user who issued the ban
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub moderator: Option<String>,
+        /// Time at which the ban is lifted
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub expires_at: Option<Timestamp>,
+    }
+
+    /// Just enough user information to list bans
+    pub struct BannedUser {
+        /// Id of the banned user
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Username of the banned user
+        pub username: String,
+        /// Discriminator of the banned user
+        pub discriminator: String,
+        /// Avatar of the banned user
+        pub avatar: Option<File>,
+    }
+
+    /// Page of bans in a server
+    pub struct BanListResult {
+        /// Users objects
+        pub users: Vec<BannedUser>,
+        /// Ban objects
+        pub bans: Vec<ServerBan>,
+        /// Total number of bans matching the query, counted up to 1000
+        pub count: usize,
     }
 );
@@ -1,4 +1,4 @@
-use super::File;
+use super::{File, User};
 
 use iso8601_timestamp::Timestamp;
 use once_cell::sync::Lazy;
@@ -49,6 +49,9 @@ auto_derived_partial!(
         /// Timestamp this member is timed out until
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         pub timeout: Option<Timestamp>,
+        /// Roles which are removed from this member once they expire
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub timed_roles: Option<Vec<TimedRole>>,
     },
     "PartialMember"
 );
@@ -63,12 +66,21 @@ auto_derived!(
         pub user: String,
     }
 
+    /// Role assigned to a member until a given time
+    pub struct TimedRole {
+        /// Role id
+        pub role: String,
+        /// Time at which the role is removed
+        pub expires_at: Timestamp,
+    }
+
     /// Optional fields on server member object
     pub enum FieldsMember {
         Nickname,
         Avatar,
         Roles,
         Timeout,
+        TimedRoles,
     }
 
     /// Member removal intention
@@ -77,4 +89,12 @@ auto_derived!(
         Kick,
         Ban,
     }
+
+    /// Member list response
+    pub struct AllMemberResponse {
+        /// List of members
+        pub members: Vec<Member>,
+        /// List of users
+        pub users: Vec<User>,
+    }
 );
@@ -1,5 +1,7 @@
-use super::{Channel, File};
+use super::{Channel, File, LegacyServerChannelType};
 
+use iso8601_timestamp::Timestamp;
+use onechatsocial_config::FeaturesLimits;
 use onechatsocial_permissions::OverrideField;
 use std::collections::HashMap;
 
@@ -75,6 +77,9 @@ auto_derived_partial!(
             serde(skip_serializing_if = "crate::if_false", default)
         )]
         pub discoverable: bool,
+        /// Default colour of text embeds which do not specify one
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub embed_colour: Option<String>,
     },
     "PartialServer"
 );
@@ -100,6 +105,9 @@ auto_derived_partial!(
         /// Ranking of this role
         #[cfg_attr(feature = "serde", serde(default))]
         pub rank: i64,
+        /// Icon shown next to the names of members with this role
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub icon: Option<File>,
     },
     "PartialRole"
 );
@@ -112,11 +120,13 @@ auto_derived!(
         SystemMessages,
         Icon,
         Banner,
+        EmbedColour,
     }
 
     /// Optional fields on server object
     pub enum FieldsRole {
         Colour,
+        Icon,
     }
 
     /// Channel category
@@ -127,6 +137,18 @@ auto_derived!(
         pub title: String,
         /// Channels in this category
         pub channels: Vec<String>,
+        /// Default permissions inherited by channels in this category
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub default_permissions: Option<OverrideField>,
+        /// Role permissions inherited by channels in this category
+        #[cfg_attr(
+            feature = "serde",
+            serde(
+                default = "HashMap::<String, OverrideField>::new",
+                skip_serializing_if = "HashMap::<String, OverrideField>::is_empty"
+            )
+        )]
+        pub role_permissions: HashMap<String, OverrideField>,
     }
 
     /// System message channel assignments
@@ -143,6 +165,9 @@ auto_derived!(
         /// ID of channel to send user banned messages in
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         pub user_banned: Option<String>,
+        /// ID of channel to send role expiry messages in
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub role_expired: Option<String>,
     }
 
     /// Information about new server to create
@@ -167,4 +192,256 @@ auto_derived!(
         /// Default channels
         pub channels: Vec<Channel>,
     }
+
+    /// Effective limits applied to a server
+    pub struct ServerLimits {
+        /// Maximum number of emoji
+        pub emoji: usize,
+        /// Maximum number of roles
+        pub roles: usize,
+        /// Maximum number of channels
+        pub channels: usize,
+        /// Maximum number of categories
+        pub categories: usize,
+        /// Maximum length of a message
+        pub message_length: usize,
+        /// Maximum number of attachments on a message
+        pub message_attachments: usize,
+        /// Maximum size of an attachment (in bytes)
+        pub attachment_size: usize,
+        /// Maximum size of an emoji (in bytes)
+        pub emoji_size: usize,
+        /// Maximum size of a role icon (in bytes)
+        pub role_icon_size: usize,
+    }
+
+    /// Current number of objects in a server
+    pub struct ServerCounts {
+        /// Number of channels
+        pub channels: usize,
+        /// Number of categories
+        pub categories: usize,
+        /// Number of roles
+        pub roles: usize,
+    }
+
+    /// Words which may not appear in channel names, role names or nicknames
+    pub struct ServerNameFilter {
+        /// Disallowed words
+        pub words: Vec<String>,
+    }
+
+    /// Outbound webhook notified when members join or leave a server
+    pub struct ServerEventWebhook {
+        /// URL which events are delivered to
+        pub url: String,
+    }
+
+    /// New event webhook configuration
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataEditServerEventWebhook {
+        /// URL which events are delivered to
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 512)))]
+        pub url: String,
+        /// Secret used to sign deliveries
+        ///
+        /// Signatures are sent in the `X-Signature-256` header as `sha256=<hex digest>`.
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        pub secret: Option<String>,
+    }
+
+    /// Event delivered to a server's event webhook
+    #[cfg_attr(feature = "serde", serde(tag = "type"))]
+    pub enum ServerWebhookEvent {
+        /// User joined the server
+        MemberJoin { server: String, user: String },
+        /// Member left or was removed from the server
+        MemberLeave { server: String, user: String },
+        /// User was banned from the server
+        MemberBan {
+            server: String,
+            user: String,
+            reason: Option<String>,
+        },
+    }
+
+    /// New list of disallowed words
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataEditNameFilter {
+        /// Disallowed words
+        #[cfg_attr(feature = "validator", validate(length(min = 0, max = 200)))]
+        pub words: Vec<String>,
+    }
+
+    /// Role definition used to copy roles between servers
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct RoleDefinition {
+        /// Id of the role in the server it was exported from
+        pub id: String,
+        /// Role name
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 32)))]
+        pub name: String,
+        /// Permissions available to this role
+        pub permissions: OverrideField,
+        /// Colour used for this role
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 128)))]
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub colour: Option<String>,
+        /// Whether this role should be shown separately on the member sidebar
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub hoist: bool,
+        /// Ranking of this role
+        #[cfg_attr(feature = "serde", serde(default))]
+        pub rank: i64,
+    }
+
+    /// Roles to import into a server
+    #[cfg_attr(feature = "validator", derive(Validate))]
+    pub struct DataImportRoles {
+        /// Role definitions, as exported from another server
+        #[cfg_attr(feature = "validator", validate(length(min = 1, max = 200)))]
+        pub roles: Vec<RoleDefinition>,
+    }
+
+    /// Result of importing definitions into a server
+    pub struct ImportResponse {
+        /// Map of exported ids to their ids in this server
+        pub ids: HashMap<String, String>,
+        /// Exported ids whose name is already in use, these map to the existing object
+        pub collisions: Vec<String>,
+    }
+
+    /// Snapshot of a server's structure which new servers can be created from
+    pub struct ServerTemplate {
+        /// Code used to share this template
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub code: String,
+        /// Id of the server this template was taken from
+        pub server: String,
+        /// Id of the user who created this template
+        pub creator: String,
+        /// Name of the server when this template was taken
+        pub name: String,
+        /// Default set of server and channel permissions
+        pub default_permissions: i64,
+        /// Roles, keyed by their id in the template
+        #[cfg_attr(
+            feature = "serde",
+            serde(
+                default = "HashMap::<String, Role>::new",
+                skip_serializing_if = "HashMap::<String, Role>::is_empty"
+            )
+        )]
+        pub roles: HashMap<String, Role>,
+        /// Channels, in the order they appeared in the server
+        pub channels: Vec<TemplateChannel>,
+        /// Categories, referring to channels by their id in the template
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Vec::is_empty", default)
+        )]
+        pub categories: Vec<Category>,
+    }
+
+    /// Channel within a server template
+    pub struct TemplateChannel {
+        /// Id of the channel in the template
+        pub id: String,
+        /// Channel type
+        #[cfg_attr(feature = "serde", serde(rename = "type"))]
+        pub channel_type: LegacyServerChannelType,
+        /// Display name of the channel
+        pub name: String,
+        /// Channel description
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub description: Option<String>,
+        /// Whether this channel is marked as not safe for work
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub nsfw: bool,
+        /// Default permissions assigned to users in this channel
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub default_permissions: Option<OverrideField>,
+        /// Permissions assigned to roles in this channel, keyed by their id in the template
+        #[cfg_attr(
+            feature = "serde",
+            serde(
+                default = "HashMap::<String, OverrideField>::new",
+                skip_serializing_if = "HashMap::<String, OverrideField>::is_empty"
+            )
+        )]
+        pub role_permissions: HashMap<String, OverrideField>,
+    }
+
+    /// Record of a moderation or configuration action taken in a server
+    pub struct AuditLogEntry {
+        /// Unique Id
+        #[cfg_attr(feature = "serde", serde(rename = "_id"))]
+        pub id: String,
+        /// Id of the server the action was taken in
+        pub server: String,
+        /// Id of the user who took the action
+        pub actor: String,
+        /// Type of action taken
+        pub action: AuditLogAction,
+        /// Id of the role, channel, category or user the action was taken on
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub target: Option<String>,
+        /// Values changed by the action
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "Vec::is_empty", default)
+        )]
+        pub changes: Vec<AuditLogChange>,
+        /// Time at which the action was taken
+        pub created_at: Timestamp,
+    }
+
+    /// Type of action recorded in the audit log
+    pub enum AuditLogAction {
+        RoleCreate,
+        RoleUpdate,
+        RoleDelete,
+        RolePermissionsUpdate,
+        DefaultPermissionsUpdate,
+        ChannelUpdate,
+        ChannelPermissionsUpdate,
+        CategoryPermissionsUpdate,
+        MemberKick,
+        MemberBan,
+        MemberUnban,
+    }
+
+    /// Change to a single value, with both sides encoded as JSON
+    pub struct AuditLogChange {
+        /// Name of the changed value
+        pub key: String,
+        /// Value before the action, if it was set
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub before: Option<String>,
+        /// Value after the action, if it is set
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        pub after: Option<String>,
+    }
 );
+
+impl From<&FeaturesLimits> for ServerLimits {
+    fn from(limits: &FeaturesLimits) -> Self {
+        ServerLimits {
+            emoji: limits.server_emoji,
+            roles: limits.server_roles,
+            channels: limits.server_channels,
+            categories: limits.server_categories,
+            message_length: limits.message_length,
+            message_attachments: limits.message_attachments,
+            attachment_size: limits.attachment_size,
+            emoji_size: limits.emoji_size,
+            role_icon_size: limits.role_icon_size,
+        }
+    }
+}
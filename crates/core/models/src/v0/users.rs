@@ -60,6 +60,14 @@ auto_derived_partial!(
         /// Bot information
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
         pub bot: Option<BotInformation>,
+        /// Whether this user's read position is hidden from other users
+        ///
+        /// Only present on the current user.
+        #[cfg_attr(
+            feature = "serde",
+            serde(skip_serializing_if = "crate::if_false", default)
+        )]
+        pub hide_read_receipts: bool,
 
         /// Current session user's relationship with this user
         pub relationship: RelationshipStatus,
@@ -108,12 +108,22 @@ pub async fn calculate_channel_permissions<P: PermissionQuery>(query: &mut P) ->
             }
         }
         ChannelType::ServerChannel => {
+            if let Some(permissions) = query.get_cached_channel_permissions().await {
+                return permissions.into();
+            }
+
             query.set_server_from_channel().await;
 
-            if query.are_we_server_owner().await {
+            let permissions = if query.are_we_server_owner().await {
                 ChannelPermission::GrantAllSafe.into()
             } else if query.are_we_a_member().await {
                 let mut permissions = calculate_server_permissions(query).await;
+                permissions.apply(query.get_default_category_permissions().await);
+
+                for role_override in query.get_our_category_role_overrides().await {
+                    permissions.apply(role_override);
+                }
+
                 permissions.apply(query.get_default_channel_permissions().await);
 
                 for role_override in query.get_our_channel_role_overrides().await {
@@ -131,7 +141,13 @@ pub async fn calculate_channel_permissions<P: PermissionQuery>(query: &mut P) ->
                 permissions
             } else {
                 0_u64.into()
-            }
+            };
+
+            query
+                .set_cached_channel_permissions(permissions.clone().into())
+                .await;
+
+            permissions
         }
         ChannelType::Unknown => 0_u64.into(),
     }
@@ -31,8 +31,8 @@ pub enum ChannelPermission {
     ManageRole = 1 << 3,
     /// Manage server customisation (includes emoji)
     ManageCustomisation = 1 << 4,
-
-    // % 1 bit reserved
+    /// View the server audit log
+    ViewAuditLog = 1 << 5,
 
     // * Member permissions
     /// Kick other members below their ranking
@@ -91,7 +91,10 @@ pub enum ChannelPermission {
     MoveMembers = 1 << 35,
 
     // * Misc. permissions
-    // % Bits 36 to 52: free area
+    /// Mention roles, everyone or everyone online in a channel
+    MentionEveryone = 1 << 36,
+
+    // % Bits 37 to 52: free area
     // % Bits 53 to 64: do not use
 
     // * Grant all permissions
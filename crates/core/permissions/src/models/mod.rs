@@ -63,6 +63,40 @@ impl PermissionValue {
             }))
         }
     }
+
+    /// Throw if we cannot grant the permissions changed going from the current to the next override
+    ///
+    /// We need to check any:
+    /// - allows added (permissions now granted)
+    /// - denies removed (permissions now neutral or granted)
+    pub fn throw_permission_override(
+        &self,
+        current_value: Option<&Override>,
+        next_value: &Override,
+    ) -> Result<()> {
+        let changed = if let Some(current_value) = current_value {
+            (!current_value.allows() & next_value.allows())
+                | (current_value.denies() & !next_value.denies())
+        } else {
+            next_value.allows()
+        };
+
+        if self.has(changed) {
+            Ok(())
+        } else {
+            Err(create_error!(CannotGiveMissingPermissions))
+        }
+    }
+
+    /// List each channel permission which has been granted
+    #[cfg(feature = "try-from-primitive")]
+    pub fn granted_channel_permissions(&self) -> Vec<ChannelPermission> {
+        (0..u64::BITS)
+            .map(|bit| 1_u64 << bit)
+            .filter(|flag| self.has(*flag))
+            .filter_map(|flag| ChannelPermission::try_from(flag).ok())
+            .collect()
+    }
 }
 
 impl From<i64> for PermissionValue {
@@ -1,7 +1,7 @@
 use crate::{
     calculate_channel_permissions, calculate_user_permissions, ChannelPermission, ChannelType,
-    Override, PermissionQuery, RelationshipStatus, DEFAULT_PERMISSION_DIRECT_MESSAGE,
-    DEFAULT_PERMISSION_SERVER, DEFAULT_PERMISSION_VIEW_ONLY,
+    Override, PermissionQuery, PermissionValue, RelationshipStatus,
+    DEFAULT_PERMISSION_DIRECT_MESSAGE, DEFAULT_PERMISSION_SERVER, DEFAULT_PERMISSION_VIEW_ONLY,
 };
 
 #[async_std::test]
@@ -76,6 +76,14 @@ async fn validate_user_permissions() {
             unreachable!()
         }
 
+        async fn get_default_category_permissions(&mut self) -> Override {
+            unreachable!()
+        }
+
+        async fn get_our_category_role_overrides(&mut self) -> Vec<Override> {
+            unreachable!()
+        }
+
         async fn do_we_own_the_channel(&mut self) -> bool {
             unreachable!()
         }
@@ -168,6 +176,14 @@ async fn validate_group_permissions() {
             unreachable!()
         }
 
+        async fn get_default_category_permissions(&mut self) -> Override {
+            unreachable!()
+        }
+
+        async fn get_our_category_role_overrides(&mut self) -> Vec<Override> {
+            unreachable!()
+        }
+
         async fn do_we_own_the_channel(&mut self) -> bool {
             false
         }
@@ -272,6 +288,14 @@ async fn validate_server_permissions() {
             }]
         }
 
+        async fn get_default_category_permissions(&mut self) -> Override {
+            Override { allow: 0, deny: 0 }
+        }
+
+        async fn get_our_category_role_overrides(&mut self) -> Vec<Override> {
+            vec![]
+        }
+
         async fn do_we_own_the_channel(&mut self) -> bool {
             unreachable!()
         }
@@ -358,6 +382,14 @@ async fn validate_timed_out_member() {
             vec![]
         }
 
+        async fn get_default_category_permissions(&mut self) -> Override {
+            Override { allow: 0, deny: 0 }
+        }
+
+        async fn get_our_category_role_overrides(&mut self) -> Vec<Override> {
+            vec![]
+        }
+
         async fn do_we_own_the_channel(&mut self) -> bool {
             unreachable!()
         }
@@ -375,3 +407,187 @@ async fn validate_timed_out_member() {
         }
     }
 }
+
+#[async_std::test]
+async fn validate_category_permissions() {
+    /// Scenario in which we are in a server channel where:
+    /// - the server grants viewing, sending messages and reading history by default
+    /// - the channel's category denies sending messages and reading history
+    /// - but lets our role send messages in the category
+    /// - and the channel itself grants reading history back
+    struct Scenario {}
+    let mut query = Scenario {};
+
+    let perms = calculate_channel_permissions(&mut query).await;
+    let value: u64 = perms.into();
+    assert_eq!(
+        value,
+        ChannelPermission::ViewChannel as u64
+            | ChannelPermission::SendMessage as u64
+            | ChannelPermission::ReadMessageHistory as u64
+    );
+
+    #[async_trait]
+    impl PermissionQuery for Scenario {
+        async fn are_we_privileged(&mut self) -> bool {
+            false
+        }
+
+        async fn are_we_a_bot(&mut self) -> bool {
+            unreachable!()
+        }
+
+        async fn are_the_users_same(&mut self) -> bool {
+            unreachable!()
+        }
+
+        async fn user_relationship(&mut self) -> RelationshipStatus {
+            unreachable!()
+        }
+
+        async fn user_is_bot(&mut self) -> bool {
+            unreachable!()
+        }
+
+        async fn have_mutual_connection(&mut self) -> bool {
+            unreachable!()
+        }
+
+        async fn are_we_server_owner(&mut self) -> bool {
+            false
+        }
+
+        async fn are_we_a_member(&mut self) -> bool {
+            true
+        }
+
+        async fn get_default_server_permissions(&mut self) -> u64 {
+            ChannelPermission::ViewChannel as u64
+                | ChannelPermission::SendMessage as u64
+                | ChannelPermission::ReadMessageHistory as u64
+        }
+
+        async fn get_our_server_role_overrides(&mut self) -> Vec<Override> {
+            vec![]
+        }
+
+        async fn are_we_timed_out(&mut self) -> bool {
+            false
+        }
+
+        async fn get_channel_type(&mut self) -> ChannelType {
+            ChannelType::ServerChannel
+        }
+
+        async fn get_default_channel_permissions(&mut self) -> Override {
+            Override {
+                allow: ChannelPermission::ReadMessageHistory as u64,
+                deny: 0,
+            }
+        }
+
+        async fn get_our_channel_role_overrides(&mut self) -> Vec<Override> {
+            vec![]
+        }
+
+        async fn get_default_category_permissions(&mut self) -> Override {
+            Override {
+                allow: 0,
+                deny: ChannelPermission::SendMessage as u64
+                    | ChannelPermission::ReadMessageHistory as u64,
+            }
+        }
+
+        async fn get_our_category_role_overrides(&mut self) -> Vec<Override> {
+            vec![Override {
+                allow: ChannelPermission::SendMessage as u64,
+                deny: 0,
+            }]
+        }
+
+        async fn do_we_own_the_channel(&mut self) -> bool {
+            unreachable!()
+        }
+
+        async fn are_we_part_of_the_channel(&mut self) -> bool {
+            unreachable!()
+        }
+
+        async fn set_recipient_as_user(&mut self) {
+            unreachable!()
+        }
+
+        async fn set_server_from_channel(&mut self) {
+            // no-op
+        }
+    }
+}
+
+#[test]
+fn validate_permission_override() {
+    let value: PermissionValue =
+        (ChannelPermission::ViewChannel as u64 | ChannelPermission::SendMessage as u64).into();
+
+    // Granting permissions we have
+    assert!(value
+        .throw_permission_override(
+            None,
+            &Override {
+                allow: ChannelPermission::SendMessage as u64,
+                deny: ChannelPermission::ManageChannel as u64,
+            },
+        )
+        .is_ok());
+
+    // Granting permissions we don't have
+    assert!(value
+        .throw_permission_override(
+            None,
+            &Override {
+                allow: ChannelPermission::ManageChannel as u64,
+                deny: 0,
+            },
+        )
+        .is_err());
+
+    // Lifting a deny on a permission we don't have
+    assert!(value
+        .throw_permission_override(
+            Some(&Override {
+                allow: 0,
+                deny: ChannelPermission::ManageChannel as u64,
+            }),
+            &Override::default(),
+        )
+        .is_err());
+
+    // Keeping an existing grant we don't have
+    assert!(value
+        .throw_permission_override(
+            Some(&Override {
+                allow: ChannelPermission::ManageChannel as u64,
+                deny: 0,
+            }),
+            &Override {
+                allow: ChannelPermission::ManageChannel as u64,
+                deny: 0,
+            },
+        )
+        .is_ok());
+}
+
+#[test]
+#[cfg(feature = "try-from-primitive")]
+fn list_granted_channel_permissions() {
+    let value: PermissionValue =
+        (ChannelPermission::ViewChannel + ChannelPermission::SendMessage + (1 << 51)).into();
+
+    // Unassigned bits are left out
+    assert_eq!(
+        value.granted_channel_permissions(),
+        vec![
+            ChannelPermission::ViewChannel,
+            ChannelPermission::SendMessage
+        ]
+    );
+}
@@ -54,6 +54,8 @@ pub enum ErrorType {
     // ? Channel related errors
     UnknownChannel,
     UnknownAttachment,
+    UnknownSticker,
+    AttachmentQuarantined,
     UnknownMessage,
     CannotEditMessage,
     CannotJoinCall,
@@ -63,13 +65,30 @@ pub enum ErrorType {
     TooManyEmbeds {
         max: usize,
     },
+    TooManyStickers {
+        max: usize,
+    },
     TooManyReplies {
         max: usize,
     },
+    TooManyMentions {
+        max: usize,
+    },
+    TooManyReactions {
+        max: usize,
+    },
+    TooManyPins {
+        max: usize,
+    },
     TooManyChannels {
         max: usize,
     },
     EmptyMessage,
+    LastMessageMismatch,
+    MessagesTooOld {
+        ids: Vec<String>,
+    },
+    PollClosed,
     PayloadTooLarge,
     CannotRemoveYourself,
     GroupTooLarge {
@@ -91,6 +110,9 @@ pub enum ErrorType {
     TooManyRoles {
         max: usize,
     },
+    TooManyCategories {
+        max: usize,
+    },
     AlreadyInServer,
 
     // ? Bot related errors
@@ -129,6 +151,9 @@ pub enum ErrorType {
     FailedValidation {
         error: String,
     },
+    RateLimited {
+        retry_after: u64,
+    },
 
     // ? Legacy errors
     VosoUnavailable,
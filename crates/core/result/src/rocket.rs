@@ -29,11 +29,20 @@ impl<'r> Responder<'r, 'static> for Error {
             ErrorType::UnknownChannel => Status::NotFound,
             ErrorType::UnknownMessage => Status::NotFound,
             ErrorType::UnknownAttachment => Status::BadRequest,
+            ErrorType::UnknownSticker => Status::BadRequest,
+            ErrorType::AttachmentQuarantined => Status::UnprocessableEntity,
             ErrorType::CannotEditMessage => Status::Forbidden,
             ErrorType::CannotJoinCall => Status::BadRequest,
             ErrorType::TooManyAttachments { .. } => Status::BadRequest,
+            ErrorType::TooManyStickers { .. } => Status::BadRequest,
             ErrorType::TooManyReplies { .. } => Status::BadRequest,
+            ErrorType::TooManyMentions { .. } => Status::BadRequest,
+            ErrorType::TooManyReactions { .. } => Status::BadRequest,
+            ErrorType::TooManyPins { .. } => Status::BadRequest,
             ErrorType::EmptyMessage => Status::UnprocessableEntity,
+            ErrorType::LastMessageMismatch => Status::Conflict,
+            ErrorType::MessagesTooOld { .. } => Status::BadRequest,
+            ErrorType::PollClosed => Status::Forbidden,
             ErrorType::PayloadTooLarge => Status::UnprocessableEntity,
             ErrorType::CannotRemoveYourself => Status::BadRequest,
             ErrorType::GroupTooLarge { .. } => Status::Forbidden,
@@ -50,6 +59,7 @@ impl<'r> Responder<'r, 'static> for Error {
             ErrorType::TooManyEmoji { .. } => Status::BadRequest,
             ErrorType::TooManyChannels { .. } => Status::BadRequest,
             ErrorType::TooManyRoles { .. } => Status::BadRequest,
+            ErrorType::TooManyCategories { .. } => Status::BadRequest,
 
             ErrorType::ReachedMaximumBots => Status::BadRequest,
             ErrorType::IsBot => Status::BadRequest,
@@ -75,6 +85,7 @@ impl<'r> Responder<'r, 'static> for Error {
             ErrorType::NotFound => Status::NotFound,
             ErrorType::NoEffect => Status::Ok,
             ErrorType::FailedValidation { .. } => Status::BadRequest,
+            ErrorType::RateLimited { .. } => Status::TooManyRequests,
         };
 
         // Serialize the error data structure into JSON.
@@ -6,8 +6,10 @@ use rocket::serde::json::Json;
 /// # Query Stats
 ///
 /// Fetch various technical statistics.
+///
+/// Collection sizes are cached for a few minutes, pass `refresh` to recompute them.
 #[openapi(tag = "Admin")]
-#[get("/stats")]
-pub async fn stats(db: &Db) -> Result<Json<Stats>> {
-    Ok(Json(db.generate_stats().await?))
+#[get("/stats?<refresh>")]
+pub async fn stats(db: &Db, refresh: Option<bool>) -> Result<Json<Stats>> {
+    Ok(Json(db.generate_stats(refresh.unwrap_or_default()).await?))
 }
@@ -1,8 +1,9 @@
+use onechatsocial_database::{AuditLogAction, AuditLogChange, ServerNameFilter};
 use onechatsocial_quark::{
     models::{
         channel::{Channel, FieldsChannel, PartialChannel},
         message::SystemMessage,
-        File, User,
+        File, Server, User,
     },
     perms, Database, Error, Permission, Ref, Result,
 };
@@ -31,6 +32,32 @@ pub struct DataEditChannel {
     nsfw: Option<bool>,
     /// Whether this channel is archived
     archived: Option<bool>,
+    /// Minimum number of seconds between messages from each member
+    ///
+    /// Only applicable to text channels.
+    #[validate(range(min = 1, max = 21600))]
+    slowmode_seconds: Option<u16>,
+    /// Whether slowmode also applies to webhooks and system messages
+    ///
+    /// Only applicable to text channels.
+    slowmode_applies_to_bots: Option<bool>,
+    /// Ids of roles whose members are exempt from slowmode
+    ///
+    /// Only applicable to text channels.
+    #[validate(length(max = 100))]
+    slowmode_exempt_roles: Option<Vec<String>>,
+    /// Whether only members who can manage messages may send messages
+    ///
+    /// Only applicable to text channels.
+    reactions_only: Option<bool>,
+    /// Whether link previews should not be generated for new messages
+    ///
+    /// Only applicable to text channels.
+    disable_embeds: Option<bool>,
+    /// Whether other channels may follow this channel
+    ///
+    /// Only applicable to text channels.
+    announcement: Option<bool>,
     #[validate(length(min = 1))]
     remove: Option<Vec<FieldsChannel>>,
 }
@@ -42,6 +69,7 @@ pub struct DataEditChannel {
 #[patch("/<target>", data = "<data>")]
 pub async fn req(
     db: &State<Database>,
+    core_db: &State<onechatsocial_database::Database>,
     user: User,
     target: Ref,
     data: Json<DataEditChannel>,
@@ -61,13 +89,108 @@ pub async fn req(
         && data.icon.is_none()
         && data.nsfw.is_none()
         && data.owner.is_none()
+        && data.slowmode_seconds.is_none()
+        && data.slowmode_applies_to_bots.is_none()
+        && data.slowmode_exempt_roles.is_none()
+        && data.reactions_only.is_none()
+        && data.disable_embeds.is_none()
+        && data.announcement.is_none()
         && data.remove.is_none()
     {
         return Ok(Json(channel));
     }
 
+    let before = channel.clone();
+
+    // Ensure the new name is allowed in this server
+    if let Some(name) = &data.name {
+        if let Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } = &channel
+        {
+            ServerNameFilter::validate_name(core_db, server, name)
+                .await
+                .map_err(Error::from_core)?;
+        }
+    }
+
     let mut partial: PartialChannel = Default::default();
 
+    // Update slowmode interval
+    if let Some(new_slowmode_seconds) = data.slowmode_seconds {
+        if let Channel::TextChannel {
+            slowmode_seconds, ..
+        } = &mut channel
+        {
+            slowmode_seconds.replace(new_slowmode_seconds);
+            partial.slowmode_seconds = Some(new_slowmode_seconds);
+        } else {
+            return Err(Error::InvalidOperation);
+        }
+    }
+
+    // Toggle whether slowmode applies to webhooks and system messages
+    if let Some(new_applies_to_bots) = data.slowmode_applies_to_bots {
+        if let Channel::TextChannel {
+            slowmode_applies_to_bots,
+            ..
+        } = &mut channel
+        {
+            *slowmode_applies_to_bots = new_applies_to_bots;
+            partial.slowmode_applies_to_bots = Some(new_applies_to_bots);
+        } else {
+            return Err(Error::InvalidOperation);
+        }
+    }
+
+    // Update roles exempt from slowmode
+    if let Some(roles) = data.slowmode_exempt_roles {
+        if let Channel::TextChannel {
+            server,
+            slowmode_exempt_roles,
+            ..
+        } = &mut channel
+        {
+            let server = db.fetch_server(server).await?;
+            if roles.iter().any(|role| !server.roles.contains_key(role)) {
+                return Err(Error::InvalidRole);
+            }
+
+            *slowmode_exempt_roles = roles.clone();
+            partial.slowmode_exempt_roles = Some(roles);
+        } else {
+            return Err(Error::InvalidOperation);
+        }
+    }
+
+    // Toggle whether regular members may only react
+    if let Some(new_reactions_only) = data.reactions_only {
+        if let Channel::TextChannel { reactions_only, .. } = &mut channel {
+            *reactions_only = new_reactions_only;
+            partial.reactions_only = Some(new_reactions_only);
+        } else {
+            return Err(Error::InvalidOperation);
+        }
+    }
+
+    // Toggle link preview generation
+    if let Some(new_disable_embeds) = data.disable_embeds {
+        if let Channel::TextChannel { disable_embeds, .. } = &mut channel {
+            *disable_embeds = new_disable_embeds;
+            partial.disable_embeds = Some(new_disable_embeds);
+        } else {
+            return Err(Error::InvalidOperation);
+        }
+    }
+
+    // Toggle whether other channels may follow this one
+    if let Some(new_announcement) = data.announcement {
+        if let Channel::TextChannel { announcement, .. } = &mut channel {
+            *announcement = new_announcement;
+            partial.announcement = Some(new_announcement);
+        } else {
+            return Err(Error::InvalidOperation);
+        }
+    }
+
     // Transfer group ownership
     if let Some(new_owner) = data.owner {
         if let Channel::Group {
@@ -191,11 +314,13 @@ pub async fn req(
                 }
 
                 if partial.icon.is_some() {
-                    SystemMessage::ChannelIconChanged { by: user.id }
-                        .into_message(channel.id().to_string())
-                        .create(db, &channel, None)
-                        .await
-                        .ok();
+                    SystemMessage::ChannelIconChanged {
+                        by: user.id.clone(),
+                    }
+                    .into_message(channel.id().to_string())
+                    .create(db, &channel, None)
+                    .await
+                    .ok();
                 }
             }
 
@@ -206,5 +331,17 @@ pub async fn req(
         _ => return Err(Error::InvalidOperation),
     };
 
+    if let Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } = &channel {
+        Server::record_audit_log(
+            db,
+            server,
+            &user.id,
+            AuditLogAction::ChannelUpdate,
+            Some(channel.id()),
+            AuditLogChange::diff(&before, &channel),
+        )
+        .await;
+    }
+
     Ok(Json(channel))
 }
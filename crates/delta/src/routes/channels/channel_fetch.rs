@@ -10,14 +10,14 @@ use rocket::{serde::json::Json, State};
 
 /// # Fetch Channel
 ///
-/// Fetch channel by its id.
+/// Fetch channel by its id, along with a summary of its pinned messages.
 #[openapi(tag = "Channel Information")]
 #[get("/<target>")]
 pub async fn fetch_channel(
     db: &State<Database>,
     user: User,
     target: Reference,
-) -> Result<Json<v0::Channel>> {
+) -> Result<Json<v0::ChannelWithPins>> {
     let channel = target.as_channel(db).await?;
 
     let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
@@ -25,13 +25,17 @@ pub async fn fetch_channel(
         .await
         .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
 
-    Ok(Json(channel.into()))
+    let pins = channel.pins();
+    Ok(Json(v0::ChannelWithPins {
+        channel: channel.into(),
+        pins: pins.into(),
+    }))
 }
 
 #[cfg(test)]
 mod test {
     use crate::{rocket, util::test::TestHarness};
-    use onechatsocial_database::Channel;
+    use onechatsocial_database::{Channel, Message};
     use onechatsocial_models::v0;
     use rocket::http::{Header, Status};
 
@@ -63,4 +67,58 @@ mod test {
         let channel: v0::Channel = response.into_json().await.expect("`Channel`");
         assert_eq!(channel, group.into());
     }
+
+    #[rocket::async_test]
+    async fn fetch_channel_pins() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let group = Channel::create_group(
+            &harness.db,
+            v0::DataCreateGroup {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            user.id.to_string(),
+        )
+        .await
+        .unwrap();
+
+        let message = Message {
+            id: ulid::Ulid::new().to_string(),
+            channel: group.id(),
+            author: user.id.to_string(),
+            content: Some("Pin me".to_string()),
+            ..Default::default()
+        };
+
+        #[allow(clippy::disallowed_methods)]
+        harness.db.insert_message(&message).await.unwrap();
+
+        let response = harness
+            .client
+            .put(format!(
+                "/channels/{}/messages/{}/pin",
+                group.id(),
+                message.id
+            ))
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NoContent);
+
+        let response = harness
+            .client
+            .get(format!("/channels/{}", group.id()))
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let channel: v0::ChannelWithPins = response.into_json().await.expect("`Channel`");
+        assert_eq!(channel.pins.pin_count, 1);
+        assert!(channel.pins.last_pinned_at.is_some());
+    }
 }
@@ -0,0 +1,55 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Channel, ChannelFollow, Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+
+use rocket::{serde::json::Json, State};
+
+/// # Follow Channel
+///
+/// Have one of your channels follow this announcement channel, new messages sent here will be cross-posted into it.
+///
+/// Requires `ManageChannel` and `SendMessage` in the follower channel.
+/// Cross-posting stops once you can no longer send messages there.
+#[openapi(tag = "Channel Information")]
+#[post("/<target>/follow", data = "<data>")]
+pub async fn follow_channel(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataFollowChannel>,
+) -> Result<Json<v0::ChannelFollow>> {
+    let data = data.into_inner();
+
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    let follower = Reference::from_unchecked(data.follower)
+        .as_channel(db)
+        .await?;
+
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&follower);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageChannel)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::SendMessage)?;
+
+    // Only announcement channels can be followed, by other server text channels
+    if !channel.is_announcement() || !matches!(follower, Channel::TextChannel { .. }) {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    ChannelFollow::create(
+        db,
+        channel.id().to_string(),
+        follower.id().to_string(),
+        user.id,
+    )
+    .await
+    .map(|follow| Json(follow.into()))
+}
@@ -0,0 +1,35 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::Result;
+
+use rocket::{serde::json::Json, State};
+use rocket_empty::EmptyResponse;
+
+/// # Reorder Pinned Messages
+///
+/// Manually arrange the pinned messages of a text channel.
+///
+/// Requires `ManageMessages` permission.
+#[openapi(tag = "Messaging")]
+#[put("/<target>/pins/order", data = "<data>")]
+pub async fn set_pin_order(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataEditPinOrder>,
+) -> Result<EmptyResponse> {
+    let mut channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+    channel
+        .set_pin_order(db, data.into_inner().order)
+        .await
+        .map(|_| EmptyResponse)
+}
@@ -0,0 +1,65 @@
+use onechatsocial_config::config;
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, MessageFilter, MessageQuery, MessageTimePeriod, User,
+};
+use onechatsocial_models::v0::{self, MessageSort};
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Pinned Messages
+///
+/// Fetch all messages pinned in a channel, most recently pinned first
+/// unless the channel's pins have been manually ordered.
+///
+/// Messages with a large number of reactions will only include reaction counts unless `expand_reactions` is set.
+#[openapi(tag = "Messaging")]
+#[get("/<target>/pins?<expand_reactions>")]
+pub async fn fetch_pins(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    expand_reactions: Option<bool>,
+) -> Result<Json<Vec<v0::Message>>> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ReadMessageHistory)?;
+
+    let mut messages = db
+        .fetch_messages(MessageQuery {
+            limit: Some(config().await.features.limits.default.channel_pins as i64),
+            filter: MessageFilter {
+                channel: Some(channel.id()),
+                pinned: Some(true),
+                ..Default::default()
+            },
+            time_period: MessageTimePeriod::Absolute {
+                before: None,
+                after: None,
+                sort: Some(MessageSort::Latest),
+            },
+        })
+        .await?;
+
+    channel.sort_pins(&mut messages);
+
+    let expand_reactions = expand_reactions.unwrap_or_default();
+    let threshold = config().await.features.reaction_summary_threshold;
+
+    Ok(Json(
+        messages
+            .into_iter()
+            .map(|message| {
+                let mut message: v0::Message = message.into();
+                if !expand_reactions {
+                    message.summarise_reactions(threshold);
+                }
+
+                message
+            })
+            .collect(),
+    ))
+}
@@ -0,0 +1,35 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::Result;
+
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Unfollow Channel
+///
+/// Stop cross-posting new messages from this channel into one of your channels.
+///
+/// Requires `ManageChannel` in the follower channel.
+#[openapi(tag = "Channel Information")]
+#[delete("/<target>/follow/<follower>")]
+pub async fn unfollow_channel(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    follower: Reference,
+) -> Result<EmptyResponse> {
+    let follow = db.fetch_channel_follow(&target.id, &follower.id).await?;
+
+    let follower = follower.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&follower);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageChannel)?;
+
+    db.delete_channel_follow(&follow.id)
+        .await
+        .map(|_| EmptyResponse)
+}
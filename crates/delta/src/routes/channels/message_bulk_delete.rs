@@ -1,9 +1,11 @@
-use chrono::Utc;
-use onechatsocial_quark::{
-    models::{Message, User},
-    perms, Db, EmptyResponse, Error, Permission, Ref, Result,
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, Message, User,
 };
-use rocket::serde::json::Json;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use rocket_empty::EmptyResponse;
 use serde::Deserialize;
 use validator::Validate;
 
@@ -21,39 +23,43 @@ pub struct OptionsBulkDelete {
 ///
 /// This will always require `ManageMessages` permission regardless of whether you own the message or not.
 ///
-/// Messages must have been sent within the past 1 week.
+/// Messages must have been sent within the past 2 weeks.
 #[openapi(tag = "Messaging")]
-#[delete("/<target>/messages/bulk", data = "<options>", rank = 1)]
-pub async fn req(
-    db: &Db,
+#[post("/<target>/messages/bulk-delete", data = "<options>")]
+pub async fn bulk_delete_messages(
+    db: &State<Database>,
     user: User,
-    target: Ref,
+    target: Reference,
     options: Json<OptionsBulkDelete>,
 ) -> Result<EmptyResponse> {
     let options = options.into_inner();
-    options
-        .validate()
-        .map_err(|error| Error::FailedValidation { error })?;
-
-    for id in &options.ids {
-        if ulid::Ulid::from_string(id)
-            .map_err(|_| Error::InvalidOperation)?
-            .datetime()
-            .signed_duration_since(Utc::now())
-            .num_days()
-            .abs()
-            > 7
-        {
-            return Err(Error::InvalidOperation);
-        }
-    }
+    options.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
 
-    perms(&user)
-        .channel(&target.as_channel(db).await?)
-        .throw_permission(db, Permission::ManageMessages)
-        .await?;
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
 
-    Message::bulk_delete(db, &target.id, options.ids)
+    Message::bulk_delete(db, &channel.id(), options.ids, &user.id)
         .await
         .map(|_| EmptyResponse)
 }
+
+/// # Bulk Delete Messages (Deprecated)
+///
+/// Same as `POST /channels/{target}/messages/bulk-delete`.
+#[openapi(tag = "Messaging")]
+#[delete("/<target>/messages/bulk", data = "<options>", rank = 1)]
+pub async fn req(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    options: Json<OptionsBulkDelete>,
+) -> Result<EmptyResponse> {
+    bulk_delete_messages(db, user, target, options).await
+}
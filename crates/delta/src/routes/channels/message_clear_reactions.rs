@@ -1,34 +1,40 @@
-use onechatsocial_quark::{
-    models::{message::PartialMessage, User},
-    perms, Db, EmptyResponse, Permission, Ref, Result,
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
 };
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+
+use rocket::State;
+use rocket_empty::EmptyResponse;
 
 /// # Remove All Reactions from Message
 ///
-/// Remove your own, someone else's or all of a given reaction.
+/// Remove all reactions from a message.
 ///
 /// Requires `ManageMessages` permission.
 #[openapi(tag = "Interactions")]
 #[delete("/<target>/messages/<msg>/reactions")]
-pub async fn clear_reactions(db: &Db, user: User, target: Ref, msg: Ref) -> Result<EmptyResponse> {
+pub async fn clear_reactions(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+) -> Result<EmptyResponse> {
     let channel = target.as_channel(db).await?;
-    perms(&user)
-        .channel(&channel)
-        .throw_permission_and_view_channel(db, Permission::ManageMessages)
-        .await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
 
     // Fetch relevant message
-    let mut message = msg.as_message_in(db, channel.id()).await?;
+    let mut message = msg.as_message(db).await?;
+    if message.channel != channel.id() {
+        return Err(create_error!(NotFound));
+    }
 
     // Clear reactions
     message
-        .update(
-            db,
-            PartialMessage {
-                reactions: Some(Default::default()),
-                ..Default::default()
-            },
-        )
+        .remove_all_reactions(db, &permissions)
         .await
         .map(|_| EmptyResponse)
 }
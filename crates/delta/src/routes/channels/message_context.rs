@@ -0,0 +1,56 @@
+use onechatsocial_config::config;
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Message Context
+///
+/// Fetch a message along with the messages surrounding it, useful for jumping to a message.
+///
+/// Up to `radius` messages are returned either side of the target, ordered from oldest to newest.
+///
+/// Messages with a large number of reactions will only include reaction counts unless `expand_reactions` is set.
+#[openapi(tag = "Messaging")]
+#[get("/<target>/messages/<msg>/context?<radius>&<expand_reactions>")]
+pub async fn fetch_message_context(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+    radius: Option<i64>,
+    expand_reactions: Option<bool>,
+) -> Result<Json<Vec<v0::Message>>> {
+    let radius = radius.unwrap_or(25);
+    if !(1..=50).contains(&radius) {
+        return Err(create_error!(InvalidProperty));
+    }
+
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ReadMessageHistory)?;
+
+    let expand_reactions = expand_reactions.unwrap_or_default();
+    let threshold = config().await.features.reaction_summary_threshold;
+
+    Ok(Json(
+        db.fetch_message_context(&channel.id(), &msg.id, radius)
+            .await?
+            .into_iter()
+            .map(|message| {
+                let mut message: v0::Message = message.into();
+                if !expand_reactions {
+                    message.summarise_reactions(threshold);
+                }
+
+                message
+            })
+            .collect(),
+    ))
+}
@@ -1,22 +1,38 @@
-use onechatsocial_quark::{models::User, perms, Db, EmptyResponse, Error, Permission, Ref, Result};
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+
+use rocket::State;
+use rocket_empty::EmptyResponse;
 
 /// # Delete Message
 ///
 /// Delete a message you've sent or one you have permission to delete.
+///
+/// The deletion may be undone for a few seconds afterwards.
 #[openapi(tag = "Messaging")]
 #[delete("/<target>/messages/<msg>", rank = 2)]
-pub async fn req(db: &Db, user: User, target: Ref, msg: Ref) -> Result<EmptyResponse> {
+pub async fn req(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+) -> Result<EmptyResponse> {
     let message = msg.as_message(db).await?;
     if message.channel != target.id {
-        return Err(Error::NotFound);
+        return Err(create_error!(NotFound));
     }
 
     if message.author != user.id {
-        perms(&user)
-            .channel(&target.as_channel(db).await?)
-            .throw_permission(db, Permission::ManageMessages)
-            .await?;
+        let channel = target.as_channel(db).await?;
+        let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+        calculate_channel_permissions(&mut query)
+            .await
+            .throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
     }
 
-    message.delete(db).await.map(|_| EmptyResponse)
+    message.delete(db, &user.id).await.map(|_| EmptyResponse)
 }
@@ -1,113 +1,252 @@
-use onechatsocial_quark::{
-    models::message::{PartialMessage, SendableEmbed},
-    models::{Message, User},
-    perms,
-    types::january::Embed,
-    Db, Error, Permission, Ref, Result, Timestamp,
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
 };
-
-use rocket::serde::json::Json;
-use serde::{Deserialize, Serialize};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
 use validator::Validate;
 
-/// # Message Details
-#[derive(Validate, Serialize, Deserialize, JsonSchema)]
-pub struct DataEditMessage {
-    /// New message content
-    #[validate(length(min = 1, max = 2000))]
-    content: Option<String>,
-    /// Embeds to include in the message
-    #[validate(length(min = 0, max = 10))]
-    embeds: Option<Vec<SendableEmbed>>,
-}
-
 /// # Edit Message
 ///
 /// Edits a message that you've previously sent.
+///
+/// Messages sent by other users may be edited if you have `ManageMessages`.
+///
+/// The previous version of the message is kept in its edit history.
 #[openapi(tag = "Messaging")]
 #[patch("/<target>/messages/<msg>", data = "<edit>")]
 pub async fn req(
-    db: &Db,
+    db: &State<Database>,
     user: User,
-    target: Ref,
-    msg: Ref,
-    edit: Json<DataEditMessage>,
-) -> Result<Json<Message>> {
+    target: Reference,
+    msg: Reference,
+    edit: Json<v0::DataEditMessage>,
+) -> Result<Json<v0::Message>> {
     let edit = edit.into_inner();
-    edit.validate()
-        .map_err(|error| Error::FailedValidation { error })?;
+    edit.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
 
     // Ensure we have permissions to send a message
     let channel = target.as_channel(db).await?;
-    let mut permissions = perms(&user).channel(&channel);
-    permissions
-        .throw_permission_and_view_channel(db, Permission::SendMessage)
-        .await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::SendMessage)?;
 
     let mut message = msg.as_message(db).await?;
     if message.channel != channel.id() {
-        return Err(Error::NotFound);
+        return Err(create_error!(NotFound));
     }
 
-    if message.author != user.id {
-        return Err(Error::CannotEditMessage);
+    // Forwarded messages must stay as their original author wrote them
+    if message.forwarded_from.is_some() {
+        return Err(create_error!(CannotEditMessage));
     }
 
-    Message::validate_sum(&edit.content, edit.embeds.as_deref().unwrap_or_default())?;
-
-    message.edited = Some(Timestamp::now_utc());
-    let mut partial = PartialMessage {
-        edited: message.edited,
-        ..Default::default()
-    };
-
-    // 1. Handle content update
-    if let Some(content) = &edit.content {
-        partial.content = Some(content.clone());
+    // Moderators may edit messages sent by other users
+    if message.author != user.id
+        && !permissions.has_channel_permission(ChannelPermission::ManageMessages)
+    {
+        return Err(create_error!(CannotEditMessage));
     }
 
-    // 2. Clear any auto generated embeds
-    let mut new_embeds: Vec<Embed> = vec![];
-    if let Some(embeds) = &message.embeds {
-        for embed in embeds {
-            if let Embed::Text(embed) = embed {
-                new_embeds.push(Embed::Text(embed.clone()))
-            }
-        }
+    // Ensure we have permissions to send embeds
+    if edit.embeds.is_some() {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::SendEmbeds)?;
     }
 
-    // 3. Replace if we are given new embeds
-    if let Some(embeds) = edit.embeds {
-        // Ensure we have permissions to send embeds
-        permissions
-            .throw_permission_and_view_channel(db, Permission::SendEmbeds)
-            .await?;
+    message
+        .edit(
+            db,
+            &channel,
+            edit,
+            &user.id,
+            permissions.has_channel_permission(ChannelPermission::SendEmbeds),
+        )
+        .await?;
 
-        new_embeds.clear();
+    Ok(Json(message.into()))
+}
 
-        for embed in embeds {
-            new_embeds.push(embed.clone().into_embed(db, &message.id).await?);
-        }
-    }
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::{rocket, util::test::TestHarness};
+    use onechatsocial_database::{Channel, File, Message, Metadata};
+    use onechatsocial_models::v0;
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn moderator_edit_is_attributed() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+        let (_, other_session, other_user) = harness.new_user().await;
+
+        let group = Channel::create_group(
+            &harness.db,
+            v0::DataCreateGroup {
+                name: TestHarness::rand_string(),
+                users: HashSet::from([other_user.id.to_string()]),
+                ..Default::default()
+            },
+            user.id.to_string(),
+        )
+        .await
+        .unwrap();
+
+        let message = Message {
+            id: ulid::Ulid::new().to_string(),
+            channel: group.id(),
+            author: other_user.id.to_string(),
+            content: Some("Hello!".to_string()),
+            ..Default::default()
+        };
+
+        #[allow(clippy::disallowed_methods)]
+        harness.db.insert_message(&message).await.unwrap();
+
+        // Authors editing their own messages are not attributed
+        let response = harness
+            .client
+            .patch(format!("/channels/{}/messages/{}", group.id(), message.id))
+            .header(ContentType::JSON)
+            .body(json!({ "content": "Hello there!" }).to_string())
+            .header(Header::new(
+                "x-session-token",
+                other_session.token.to_string(),
+            ))
+            .dispatch()
+            .await;
 
-    partial.embeds = Some(new_embeds);
+        assert_eq!(response.status(), Status::Ok);
 
-    message.update(db, partial).await?;
+        let edited: v0::Message = response.into_json().await.expect("`Message`");
+        assert!(edited.edited.is_some());
+        assert_eq!(edited.edited_by, None);
 
-    // Queue up a task for processing embeds if the we have sufficient permissions
-    if permissions
-        .has_permission(db, Permission::SendEmbeds)
-        .await?
-    {
-        if let Some(content) = edit.content {
-            onechatsocial_quark::tasks::process_embeds::queue(
-                message.channel.to_string(),
-                message.id.to_string(),
-                content,
-            )
+        // The group owner may edit messages on behalf of other users
+        let response = harness
+            .client
+            .patch(format!("/channels/{}/messages/{}", group.id(), message.id))
+            .header(ContentType::JSON)
+            .body(json!({ "content": "[redacted]" }).to_string())
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let edited: v0::Message = response.into_json().await.expect("`Message`");
+        assert_eq!(edited.content, Some("[redacted]".to_string()));
+        assert_eq!(edited.edited_by, Some(user.id.to_string()));
+
+        // The author editing it again is no longer attributed to the moderator
+        let response = harness
+            .client
+            .patch(format!("/channels/{}/messages/{}", group.id(), message.id))
+            .header(ContentType::JSON)
+            .body(json!({ "content": "Hello again!" }).to_string())
+            .header(Header::new(
+                "x-session-token",
+                other_session.token.to_string(),
+            ))
+            .dispatch()
             .await;
-        }
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let edited: v0::Message = response.into_json().await.expect("`Message`");
+        assert_eq!(edited.edited_by, None);
+
+        let stored = harness.db.fetch_message(&message.id).await.unwrap();
+        assert_eq!(stored.edited_by, None);
     }
 
-    Ok(Json(message))
+    #[rocket::async_test]
+    async fn reorder_attachments() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let group = Channel::create_group(
+            &harness.db,
+            v0::DataCreateGroup {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            user.id.to_string(),
+        )
+        .await
+        .unwrap();
+
+        let attachment = |filename: &str| File {
+            id: ulid::Ulid::new().to_string(),
+            tag: "attachments".to_string(),
+            filename: filename.to_string(),
+            metadata: Metadata::File,
+            content_type: "text/plain".to_string(),
+            size: 1,
+            description: None,
+            is_voice: false,
+            waveform: None,
+            duration: None,
+            deleted: None,
+            reported: None,
+            message_id: None,
+            user_id: None,
+            server_id: None,
+            object_id: None,
+        };
+
+        let first = attachment("first.txt");
+        let second = attachment("second.txt");
+        let message = Message {
+            id: ulid::Ulid::new().to_string(),
+            channel: group.id(),
+            author: user.id.to_string(),
+            attachments: Some(vec![first.clone(), second.clone()]),
+            ..Default::default()
+        };
+
+        #[allow(clippy::disallowed_methods)]
+        harness.db.insert_message(&message).await.unwrap();
+
+        // Attachments may not be removed through reordering
+        let response = harness
+            .client
+            .patch(format!("/channels/{}/messages/{}", group.id(), message.id))
+            .header(ContentType::JSON)
+            .body(json!({ "attachments": [second.id] }).to_string())
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let response = harness
+            .client
+            .patch(format!("/channels/{}/messages/{}", group.id(), message.id))
+            .header(ContentType::JSON)
+            .body(json!({ "attachments": [second.id, first.id] }).to_string())
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+
+        let edited: v0::Message = response.into_json().await.expect("`Message`");
+        assert_eq!(
+            vec![second.id, first.id],
+            edited
+                .attachments
+                .unwrap_or_default()
+                .into_iter()
+                .map(|file| file.id)
+                .collect::<Vec<String>>()
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use onechatsocial_config::config;
 use onechatsocial_database::{
     util::{permissions::DatabasePermissionQuery, reference::Reference},
     Database, User,
@@ -10,13 +11,16 @@ use rocket::{serde::json::Json, State};
 /// # Fetch Message
 ///
 /// Retrieves a message by its id.
+///
+/// Messages with a large number of reactions will only include reaction counts unless `expand_reactions` is set.
 #[openapi(tag = "Messaging")]
-#[get("/<target>/messages/<msg>")]
+#[get("/<target>/messages/<msg>?<expand_reactions>")]
 pub async fn req(
     db: &State<Database>,
     user: User,
     target: Reference,
     msg: Reference,
+    expand_reactions: Option<bool>,
 ) -> Result<Json<v0::Message>> {
     let channel = target.as_channel(db).await?;
 
@@ -30,5 +34,13 @@ pub async fn req(
         return Err(create_error!(NotFound));
     }
 
-    Ok(Json(message.into()))
+    let role_icon = message.fetch_role_icon(db, &channel).await;
+    let mut message: v0::Message = message.into();
+    message.display_author.role_icon = role_icon;
+
+    if !expand_reactions.unwrap_or_default() {
+        message.summarise_reactions(config().await.features.reaction_summary_threshold);
+    }
+
+    Ok(Json(message))
 }
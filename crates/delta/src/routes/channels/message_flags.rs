@@ -0,0 +1,41 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Edit Message Flags
+///
+/// Change the flags of a message, such as to suppress its link previews.
+///
+/// Requires `ManageMessages` permission for messages sent by other users.
+#[openapi(tag = "Messaging")]
+#[patch("/<target>/messages/<msg>/flags", data = "<data>")]
+pub async fn edit_message_flags(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+    data: Json<v0::DataEditMessageFlags>,
+) -> Result<Json<v0::Message>> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    // Fetch relevant message
+    let mut message = msg.as_message(db).await?;
+    if message.channel != channel.id() {
+        return Err(create_error!(NotFound));
+    }
+
+    let flags = data.into_inner().flags;
+    message
+        .set_flags(db, &channel, &permissions, flags, &user.id)
+        .await?;
+
+    Ok(Json(message.into()))
+}
@@ -0,0 +1,153 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Forward Message
+///
+/// Forward a message into another channel, attributed to its original author.
+///
+/// Requires `ViewChannel` and `ReadMessageHistory` in the channel the message
+/// is in and `SendMessage` in the channel it is forwarded to, along with
+/// `SendEmbeds` and `UploadFiles` if the message has embeds or attachments.
+#[openapi(tag = "Messaging")]
+#[post("/<target>/messages/<msg>/forward", data = "<data>")]
+pub async fn forward_message(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+    data: Json<v0::DataForwardMessage>,
+) -> Result<Json<v0::Message>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ReadMessageHistory)?;
+
+    // Fetch relevant message
+    let message = msg.as_message(db).await?;
+    if message.channel != channel.id() {
+        return Err(create_error!(NotFound));
+    }
+
+    // Ensure we have permissions to send the message in the target channel
+    let target = db.fetch_channel(&data.target).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&target);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::SendMessage)?;
+
+    if target.is_reactions_only() {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+    }
+
+    if message.embeds.as_ref().is_some_and(|v| !v.is_empty()) {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::SendEmbeds)?;
+    }
+
+    if message.attachments.as_ref().is_some_and(|v| !v.is_empty()) {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::UploadFiles)?;
+    }
+
+    let author: v0::User = user.clone().into(db, Some(&user)).await;
+    message
+        .forward(db, &target, &author)
+        .await
+        .map(|message| Json(message.into()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use onechatsocial_database::{Channel, Member, Message, PartialChannel, Server};
+    use onechatsocial_models::v0;
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn reactions_only_refuses_forwards() {
+        let harness = TestHarness::new().await;
+        let (_, _, owner) = harness.new_user().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let (mut server, channels) = Server::create(
+            &harness.db,
+            v0::DataCreateServer {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            &owner,
+            true,
+        )
+        .await
+        .unwrap();
+
+        let mut target = Channel::create_server_channel(
+            &harness.db,
+            &mut server,
+            v0::DataCreateServerChannel {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .unwrap();
+
+        target
+            .update(
+                &harness.db,
+                PartialChannel {
+                    reactions_only: Some(true),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        Member::create(&harness.db, &server, &user, None)
+            .await
+            .unwrap();
+
+        let message = Message {
+            id: ulid::Ulid::new().to_string(),
+            channel: channels[0].id(),
+            author: owner.id.to_string(),
+            content: Some("Forward me".to_string()),
+            ..Default::default()
+        };
+
+        #[allow(clippy::disallowed_methods)]
+        harness.db.insert_message(&message).await.unwrap();
+
+        let response = harness
+            .client
+            .post(format!(
+                "/channels/{}/messages/{}/forward",
+                channels[0].id(),
+                message.id
+            ))
+            .header(ContentType::JSON)
+            .body(json!({ "target": target.id() }).to_string())
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let error: serde_json::Value = response.into_json().await.expect("`Error`");
+        assert_eq!(error["type"], "MissingPermission");
+    }
+}
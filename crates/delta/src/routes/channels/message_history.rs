@@ -0,0 +1,42 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Message Edit History
+///
+/// Fetch previous versions of an edited message, oldest first.
+///
+/// Requires `ManageMessages` permission.
+#[openapi(tag = "Messaging")]
+#[get("/<target>/messages/<msg>/history")]
+pub async fn fetch_message_history(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+) -> Result<Json<Vec<v0::MessageRevision>>> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+    let message = msg.as_message(db).await?;
+    if message.channel != channel.id() {
+        return Err(create_error!(NotFound));
+    }
+
+    Ok(Json(
+        message
+            .edit_history
+            .unwrap_or_default()
+            .into_iter()
+            .map(|revision| revision.into())
+            .collect(),
+    ))
+}
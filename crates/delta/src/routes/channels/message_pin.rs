@@ -0,0 +1,39 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Pin Message
+///
+/// Pin a message in its channel.
+///
+/// Requires `ManageMessages` permission.
+#[openapi(tag = "Messaging")]
+#[put("/<target>/messages/<msg>/pin")]
+pub async fn pin_message(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+) -> Result<EmptyResponse> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    // Fetch relevant message
+    let mut message = msg.as_message(db).await?;
+    if message.channel != channel.id() {
+        return Err(create_error!(NotFound));
+    }
+
+    message
+        .set_pinned(db, &channel, &permissions, true, &user.id)
+        .await
+        .map(|_| EmptyResponse)
+}
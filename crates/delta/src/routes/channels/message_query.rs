@@ -1,7 +1,12 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use onechatsocial_config::config;
+use onechatsocial_database::MessageTimePeriod as TimePeriod;
 use onechatsocial_quark::{
     models::{
         message::{
-            BulkMessageResponse, MessageFilter, MessageQuery, MessageSort, MessageTimePeriod,
+            BulkMessageResponse, MessageFilter, MessageHas, MessageQuery, MessageSort,
+            MessageTimePeriod,
         },
         User,
     },
@@ -29,18 +34,37 @@ pub struct OptionsQueryMessages {
     sort: Option<MessageSort>,
     /// Message id to search around
     ///
-    /// Specifying 'nearby' ignores 'before', 'after' and 'sort'.
+    /// Specifying 'nearby' ignores 'before', 'after' and 'sort'
+    /// and cannot be combined with 'has' or 'pinned'.
     /// It will also take half of limit rounded as the limits to each side.
     /// It also fetches the message ID specified.
     #[validate(length(min = 26, max = 26))]
     nearby: Option<String>,
+    /// Unix timestamp (in milliseconds) from which messages should be fetched
+    ///
+    /// Can be combined with 'before' and 'after'.
+    #[validate(range(max = 281_474_976_710_655))]
+    from_time: Option<u64>,
+    /// Unix timestamp (in milliseconds) before which messages should be fetched
+    ///
+    /// Can be combined with 'before' and 'after'.
+    #[validate(range(max = 281_474_976_710_655))]
+    to_time: Option<u64>,
+    /// Only match messages which have all of the given content
+    has: Option<Vec<MessageHas>>,
+    /// Only match messages which are (or are not) pinned
+    pinned: Option<bool>,
     /// Whether to include user (and member, if server channel) objects
     include_users: Option<bool>,
+    /// Whether to include full reactions for messages with a large number of reactions
+    expand_reactions: Option<bool>,
 }
 
 /// # Fetch Messages
 ///
 /// Fetch multiple messages.
+///
+/// Messages with a large number of reactions will only include reaction counts unless `expand_reactions` is set.
 #[openapi(tag = "Messaging")]
 #[get("/<target>/messages?<options..>")]
 pub async fn req(
@@ -57,6 +81,10 @@ pub async fn req(
         return Err(Error::InvalidOperation);
     }
 
+    if options.nearby.is_some() && (options.has.is_some() || options.pinned.is_some()) {
+        return Err(Error::InvalidOperation);
+    }
+
     let channel = target.as_channel(db).await?;
     perms(&user)
         .channel(&channel)
@@ -69,28 +97,52 @@ pub async fn req(
         after,
         sort,
         nearby,
+        from_time,
+        to_time,
+        has,
+        pinned,
         include_users,
+        expand_reactions,
     } = options;
 
-    let messages = db
+    let mut messages = db
         .fetch_messages(MessageQuery {
             filter: MessageFilter {
                 channel: Some(channel.id().to_string()),
+                pinned,
+                has,
                 ..Default::default()
             },
             time_period: if let Some(nearby) = nearby {
                 MessageTimePeriod::Relative { nearby }
             } else {
-                MessageTimePeriod::Absolute {
+                // Narrow the id bounds to the requested time range
+                match TimePeriod::from_time_range(
                     before,
                     after,
-                    sort,
+                    from_time.map(|ms| UNIX_EPOCH + Duration::from_millis(ms)),
+                    to_time.map(|ms| UNIX_EPOCH + Duration::from_millis(ms)),
+                    None,
+                ) {
+                    TimePeriod::Absolute { before, after, .. } => MessageTimePeriod::Absolute {
+                        before,
+                        after,
+                        sort,
+                    },
+                    TimePeriod::Relative { nearby } => MessageTimePeriod::Relative { nearby },
                 }
             },
             limit,
         })
         .await?;
 
+    if !expand_reactions.unwrap_or_default() {
+        let threshold = config().await.features.reaction_summary_threshold;
+        for message in &mut messages {
+            message.summarise_reactions(threshold);
+        }
+    }
+
     BulkMessageResponse::transform(db, Some(&channel), messages, &user, include_users)
         .await
         .map(Json)
@@ -1,4 +1,12 @@
-use onechatsocial_quark::{models::User, perms, Db, EmptyResponse, Permission, Ref, Result};
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+
+use rocket::State;
+use rocket_empty::EmptyResponse;
 
 /// # Add Reaction to Message
 ///
@@ -6,24 +14,27 @@ use onechatsocial_quark::{models::User, perms, Db, EmptyResponse, Permission, Re
 #[openapi(tag = "Interactions")]
 #[put("/<target>/messages/<msg>/reactions/<emoji>")]
 pub async fn react_message(
-    db: &Db,
+    db: &State<Database>,
     user: User,
-    target: Ref,
-    msg: Ref,
-    emoji: Ref,
+    target: Reference,
+    msg: Reference,
+    emoji: Reference,
 ) -> Result<EmptyResponse> {
     let channel = target.as_channel(db).await?;
-    perms(&user)
-        .channel(&channel)
-        .throw_permission_and_view_channel(db, Permission::React)
-        .await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::React)?;
 
     // Fetch relevant message
-    let message = msg.as_message_in(db, channel.id()).await?;
+    let message = msg.as_message(db).await?;
+    if message.channel != channel.id() {
+        return Err(create_error!(NotFound));
+    }
 
     // Add the reaction
     message
-        .add_reaction(db, &user, &emoji.id)
+        .add_reaction(db, &user.id, &emoji.id)
         .await
         .map(|_| EmptyResponse)
 }
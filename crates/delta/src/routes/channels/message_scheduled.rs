@@ -0,0 +1,37 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Scheduled Messages
+///
+/// Fetch messages in a channel which are yet to be sent, soonest first.
+///
+/// Only includes your own messages unless you have `ManageMessages`.
+#[openapi(tag = "Messaging")]
+#[get("/<target>/scheduled")]
+pub async fn fetch_scheduled_messages(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<Vec<v0::ScheduledMessage>>> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    let manage_messages = permissions.has_channel_permission(ChannelPermission::ManageMessages);
+
+    Ok(Json(
+        db.fetch_scheduled_messages(&channel.id())
+            .await?
+            .into_iter()
+            .filter(|scheduled| manage_messages || scheduled.message.author == user.id)
+            .map(|scheduled| scheduled.into())
+            .collect(),
+    ))
+}
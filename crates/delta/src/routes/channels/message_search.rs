@@ -1,7 +1,9 @@
+use onechatsocial_config::config;
 use onechatsocial_quark::{
     models::{
         message::{
-            BulkMessageResponse, MessageFilter, MessageQuery, MessageSort, MessageTimePeriod,
+            BulkSearchResponse, MessageFilter, MessageHas, MessageQuery, MessageSort,
+            MessageTimePeriod, SearchCursor,
         },
         User,
     },
@@ -25,23 +27,40 @@ pub struct OptionsMessageSearch {
     #[validate(range(min = 1, max = 100))]
     limit: Option<i64>,
     /// Message id before which messages should be fetched
+    ///
+    /// Ignored when sorting by relevance.
     #[validate(length(min = 26, max = 26))]
     before: Option<String>,
     /// Message id after which messages should be fetched
+    ///
+    /// Ignored when sorting by relevance.
     #[validate(length(min = 26, max = 26))]
     after: Option<String>,
     /// Message sort direction
     ///
-    /// By default, it will be sorted by latest.
+    /// By default, it will be sorted by relevance.
     #[serde(default = "MessageSort::default")]
     sort: MessageSort,
+    /// Last result of the previous page when sorting by relevance
+    #[validate]
+    cursor: Option<SearchCursor>,
+    /// Only match messages which have all of the given content
+    has: Option<Vec<MessageHas>>,
+    /// Only match messages which are (or are not) pinned
+    pinned: Option<bool>,
     /// Whether to include user (and member, if server channel) objects
     include_users: Option<bool>,
+    /// Whether to include full reactions for messages with a large number of reactions
+    expand_reactions: Option<bool>,
 }
 
 /// # Search for Messages
 ///
 /// This route searches for messages within the given parameters.
+///
+/// Each result includes where the search terms appear in its content.
+///
+/// Messages with a large number of reactions will only include reaction counts unless `expand_reactions` is set.
 #[openapi(tag = "Messaging")]
 #[post("/<target>/search", data = "<options>")]
 pub async fn req(
@@ -49,7 +68,7 @@ pub async fn req(
     user: User,
     target: Ref,
     options: Json<OptionsMessageSearch>,
-) -> Result<Json<BulkMessageResponse>> {
+) -> Result<Json<BulkSearchResponse>> {
     if user.bot.is_some() {
         return Err(Error::IsBot);
     }
@@ -71,26 +90,42 @@ pub async fn req(
         before,
         after,
         sort,
+        cursor,
+        has,
+        pinned,
         include_users,
+        expand_reactions,
     } = options;
 
-    let messages = db
-        .fetch_messages(MessageQuery {
-            filter: MessageFilter {
-                channel: Some(channel.id().to_string()),
-                query: Some(query),
-                ..Default::default()
+    let mut results = db
+        .search_messages(
+            MessageQuery {
+                filter: MessageFilter {
+                    channel: Some(channel.id().to_string()),
+                    query: Some(query),
+                    pinned,
+                    has,
+                    ..Default::default()
+                },
+                time_period: MessageTimePeriod::Absolute {
+                    before,
+                    after,
+                    sort: Some(sort),
+                },
+                limit,
             },
-            time_period: MessageTimePeriod::Absolute {
-                before,
-                after,
-                sort: Some(sort),
-            },
-            limit,
-        })
+            cursor,
+        )
         .await?;
 
-    BulkMessageResponse::transform(db, Some(&channel), messages, &user, include_users)
+    if !expand_reactions.unwrap_or_default() {
+        let threshold = config().await.features.reaction_summary_threshold;
+        for result in &mut results {
+            result.message.summarise_reactions(threshold);
+        }
+    }
+
+    BulkSearchResponse::transform(db, Some(&channel), results, &user, include_users)
         .await
         .map(Json)
 }
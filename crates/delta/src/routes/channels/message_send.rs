@@ -3,9 +3,11 @@ use onechatsocial_database::util::permissions::DatabasePermissionQuery;
 use onechatsocial_database::{
     util::idempotency::IdempotencyKey, util::reference::Reference, Database, User,
 };
-use onechatsocial_database::{Interactions, Message};
+use onechatsocial_database::{Channel, Interactions, Message};
 use onechatsocial_models::v0;
-use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_permissions::{
+    calculate_channel_permissions, ChannelPermission, PermissionQuery,
+};
 use onechatsocial_result::{create_error, Result};
 use rocket::serde::json::Json;
 use rocket::State;
@@ -14,6 +16,9 @@ use validator::Validate;
 /// # Send Message
 ///
 /// Sends a message to the given channel.
+///
+/// Retrying with the same `Idempotency-Key` returns the original message,
+/// reusing a key for a different message is rejected.
 #[openapi(tag = "Messaging")]
 #[post("/<target>/messages", data = "<data>")]
 pub async fn message_send(
@@ -37,6 +42,11 @@ pub async fn message_send(
     let permissions = calculate_channel_permissions(&mut query).await;
     permissions.throw_if_lacking_channel_permission(ChannelPermission::SendMessage)?;
 
+    // Reactions only channels may only be posted in by those who can manage messages
+    if channel.is_reactions_only() {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+    }
+
     // Verify permissions for masquerade
     if let Some(masq) = &data.masquerade {
         permissions.throw_if_lacking_channel_permission(ChannelPermission::Masquerade)?;
@@ -46,8 +56,8 @@ pub async fn message_send(
         }
     }
 
-    // Check permissions for embeds
-    if data.embeds.as_ref().is_some_and(|v| !v.is_empty()) {
+    // Check permissions for embeds, quotes are included as an embed
+    if data.embeds.as_ref().is_some_and(|v| !v.is_empty()) || data.quote.is_some() {
         permissions.throw_if_lacking_channel_permission(ChannelPermission::SendEmbeds)?;
     }
 
@@ -59,7 +69,15 @@ pub async fn message_send(
     // Ensure interactions information is correct
     if let Some(interactions) = &data.interactions {
         let interactions: Interactions = interactions.clone().into();
-        interactions.validate(db, &permissions).await?;
+        interactions.validate(db, &user.id, &permissions).await?;
+    }
+
+    // Permissions may have come from the cache, so ensure we know the server
+    if matches!(
+        channel,
+        Channel::TextChannel { .. } | Channel::VoiceChannel { .. }
+    ) {
+        query.set_server_from_channel().await;
     }
 
     // Disallow mentions for new users (TRUST-0: <12 hours age) in public servers
@@ -90,3 +108,180 @@ pub async fn message_send(
         .into(),
     ))
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use onechatsocial_database::{Member, Message, PartialChannel, Server};
+    use onechatsocial_models::v0;
+    use onechatsocial_permissions::ChannelPermission;
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn reactions_only_refuses_members() {
+        let harness = TestHarness::new().await;
+        let (_, owner_session, owner) = harness.new_user().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let (server, mut channels) = Server::create(
+            &harness.db,
+            v0::DataCreateServer {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            &owner,
+            true,
+        )
+        .await
+        .unwrap();
+
+        Member::create(&harness.db, &server, &user, None)
+            .await
+            .unwrap();
+
+        let channel = &mut channels[0];
+        channel
+            .update(
+                &harness.db,
+                PartialChannel {
+                    reactions_only: Some(true),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        // Members without ManageMessages may not post
+        let response = harness
+            .client
+            .post(format!("/channels/{}/messages", channel.id()))
+            .header(ContentType::JSON)
+            .body(json!({ "content": "Hello!" }).to_string())
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let error: serde_json::Value = response.into_json().await.expect("`Error`");
+        assert_eq!(error["type"], "MissingPermission");
+
+        // The owner still can
+        let response = harness
+            .client
+            .post(format!("/channels/{}/messages", channel.id()))
+            .header(ContentType::JSON)
+            .body(json!({ "content": "Announcement" }).to_string())
+            .header(Header::new(
+                "x-session-token",
+                owner_session.token.to_string(),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+        let message: v0::Message = response.into_json().await.expect("`Message`");
+
+        // Members may still react to messages
+        let response = harness
+            .client
+            .put(format!(
+                "/channels/{}/messages/{}/reactions/%F0%9F%91%8D",
+                channel.id(),
+                message.id
+            ))
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NoContent);
+
+        let message: Message = harness.db.fetch_message(&message.id).await.unwrap();
+        assert!(message
+            .reactions
+            .get("👍")
+            .is_some_and(|users| users.contains(&user.id)));
+    }
+
+    #[rocket::async_test]
+    async fn quote_requires_send_embeds() {
+        let harness = TestHarness::new().await;
+        let (_, owner_session, owner) = harness.new_user().await;
+        let (_, session, user) = harness.new_user().await;
+
+        let (server, channels) = Server::create(
+            &harness.db,
+            v0::DataCreateServer {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            &owner,
+            true,
+        )
+        .await
+        .unwrap();
+
+        Member::create(&harness.db, &server, &user, None)
+            .await
+            .unwrap();
+
+        let channel = channels[0].id();
+        let send = |body: serde_json::Value, token: String| {
+            harness
+                .client
+                .post(format!("/channels/{channel}/messages"))
+                .header(ContentType::JSON)
+                .body(body.to_string())
+                .header(Header::new("x-session-token", token))
+                .dispatch()
+        };
+
+        let response = send(
+            json!({ "content": "Quote me" }),
+            owner_session.token.to_string(),
+        )
+        .await;
+
+        assert_eq!(response.status(), Status::Ok);
+        let quoted: v0::Message = response.into_json().await.expect("`Message`");
+
+        let response = harness
+            .client
+            .put(format!("/channels/{channel}/permissions/default"))
+            .header(ContentType::JSON)
+            .body(
+                json!({
+                    "permissions": {
+                        "allow": 0,
+                        "deny": ChannelPermission::SendEmbeds as u64
+                    }
+                })
+                .to_string(),
+            )
+            .header(Header::new(
+                "x-session-token",
+                owner_session.token.to_string(),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+        drop(response);
+
+        // Quoting attaches an embed, so it needs the same permission
+        let response = send(
+            json!({ "content": "Look", "quote": quoted.id }),
+            session.token.to_string(),
+        )
+        .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let error: serde_json::Value = response.into_json().await.expect("`Error`");
+        assert_eq!(error["type"], "MissingPermission");
+
+        let response = send(json!({ "content": "Look" }), session.token.to_string()).await;
+        assert_eq!(response.status(), Status::Ok);
+    }
+}
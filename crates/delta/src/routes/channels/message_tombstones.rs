@@ -0,0 +1,56 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, MessageTombstone, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// # Query Parameters
+#[derive(Validate, Serialize, Deserialize, JsonSchema, FromForm)]
+pub struct OptionsFetchTombstones {
+    /// Maximum number of tombstones to return
+    #[validate(range(min = 1, max = 100))]
+    limit: Option<i64>,
+}
+
+/// # Fetch Deleted Messages
+///
+/// Fetch records of recently deleted messages in a channel, newest first.
+///
+/// Records only include who sent and deleted each message, never its content.
+#[openapi(tag = "Messaging")]
+#[get("/<target>/messages/tombstones?<options..>", rank = 1)]
+pub async fn fetch_message_tombstones(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    options: OptionsFetchTombstones,
+) -> Result<Json<Vec<v0::MessageTombstone>>> {
+    options.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+    let Some(retention) = MessageTombstone::retention().await else {
+        return Ok(Json(vec![]));
+    };
+
+    Ok(Json(
+        db.fetch_message_tombstones(&channel.id(), retention, options.limit.unwrap_or(50))
+            .await?
+            .into_iter()
+            .map(|tombstone| tombstone.into())
+            .collect(),
+    ))
+}
@@ -0,0 +1,31 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, Message, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::Result;
+
+use rocket::{serde::json::Json, State};
+
+/// # Undo Message Deletion
+///
+/// Restore a message you've deleted, this is only possible for a few seconds after deletion.
+#[openapi(tag = "Messaging")]
+#[post("/<target>/messages/<msg>/undo_delete")]
+pub async fn undo_delete_message(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+) -> Result<Json<v0::Message>> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    Message::undo_delete(db, &channel.id(), &msg.id, &user.id)
+        .await
+        .map(|message| Json(message.into()))
+}
@@ -1,4 +1,12 @@
-use onechatsocial_quark::{models::User, perms, Db, EmptyResponse, Permission, Ref, Result};
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+
+use rocket::State;
+use rocket_empty::EmptyResponse;
 use serde::{Deserialize, Serialize};
 
 /// # Query Parameters
@@ -18,29 +26,30 @@ pub struct OptionsUnreact {
 #[openapi(tag = "Interactions")]
 #[delete("/<target>/messages/<msg>/reactions/<emoji>?<options..>")]
 pub async fn unreact_message(
-    db: &Db,
+    db: &State<Database>,
     user: User,
-    target: Ref,
-    msg: Ref,
-    emoji: Ref,
+    target: Reference,
+    msg: Reference,
+    emoji: Reference,
     options: OptionsUnreact,
 ) -> Result<EmptyResponse> {
     let channel = target.as_channel(db).await?;
-    let mut permissions = perms(&user).channel(&channel);
-    permissions
-        .throw_permission_and_view_channel(db, Permission::React)
-        .await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::React)?;
 
     // Check if we need to escalate permissions
     let remove_all = options.remove_all.unwrap_or_default();
     if options.user_id.is_some() || remove_all {
-        permissions
-            .throw_permission(db, Permission::ManageMessages)
-            .await?;
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
     }
 
     // Fetch relevant message
-    let message = msg.as_message_in(db, channel.id()).await?;
+    let message = msg.as_message(db).await?;
+    if message.channel != channel.id() {
+        return Err(create_error!(NotFound));
+    }
 
     // Check if we should wipe all of this reaction
     if remove_all {
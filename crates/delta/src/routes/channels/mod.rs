@@ -5,6 +5,10 @@ mod channel_ack;
 mod channel_delete;
 mod channel_edit;
 mod channel_fetch;
+mod channel_follow;
+mod channel_pin_order;
+mod channel_pins;
+mod channel_unfollow;
 mod group_add_member;
 mod group_create;
 mod group_remove_member;
@@ -12,16 +16,34 @@ mod invite_create;
 mod members_fetch;
 mod message_bulk_delete;
 mod message_clear_reactions;
+mod message_context;
 mod message_delete;
 mod message_edit;
 mod message_fetch;
+mod message_flags;
+mod message_forward;
+mod message_history;
+mod message_pin;
 mod message_query;
 mod message_react;
+mod message_scheduled;
 mod message_search;
 mod message_send;
+mod message_tombstones;
+mod message_undo_delete;
+mod message_unpin;
 mod message_unreact;
+mod notification_fetch;
+mod notification_set;
+mod permissions_fetch;
 mod permissions_set;
 mod permissions_set_default;
+mod poll_create;
+mod poll_vote;
+mod reaction_role_remove;
+mod reaction_role_set;
+mod reaction_roles_fetch;
+mod thread_create;
 mod voice_join;
 mod webhook_create;
 mod webhook_fetch_all;
@@ -33,23 +55,46 @@ pub fn routes() -> (Vec<Route>, OpenApi) {
         members_fetch::req,
         channel_delete::req,
         channel_edit::req,
+        channel_follow::follow_channel,
+        channel_unfollow::unfollow_channel,
+        notification_fetch::fetch_notification_preference,
+        notification_set::set_notification_preference,
         invite_create::req,
         message_send::message_send,
         message_query::req,
         message_search::req,
         message_fetch::req,
+        message_forward::forward_message,
+        message_context::fetch_message_context,
         message_edit::req,
+        message_flags::edit_message_flags,
         message_bulk_delete::req,
+        message_bulk_delete::bulk_delete_messages,
         message_delete::req,
+        message_undo_delete::undo_delete_message,
+        message_tombstones::fetch_message_tombstones,
+        message_pin::pin_message,
+        message_unpin::unpin_message,
+        channel_pins::fetch_pins,
+        channel_pin_order::set_pin_order,
+        message_scheduled::fetch_scheduled_messages,
+        message_history::fetch_message_history,
+        thread_create::create_thread,
+        poll_create::create_poll,
+        poll_vote::vote_poll,
         group_create::create_group,
         group_add_member::req,
         group_remove_member::req,
         voice_join::req,
+        permissions_fetch::fetch_effective_permissions,
         permissions_set::req,
         permissions_set_default::req,
         message_react::react_message,
         message_unreact::unreact_message,
         message_clear_reactions::clear_reactions,
+        reaction_roles_fetch::fetch_reaction_roles,
+        reaction_role_set::set_reaction_role,
+        reaction_role_remove::remove_reaction_role,
         webhook_create::req,
         webhook_fetch_all::req,
     ]
@@ -0,0 +1,29 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    ChannelNotificationPreference, Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Notification Preference
+///
+/// Fetch which messages in this channel you are notified of.
+#[openapi(tag = "Channel Information")]
+#[get("/<target>/notifications")]
+pub async fn fetch_notification_preference(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<v0::ChannelNotificationPreference>> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    ChannelNotificationPreference::fetch_level(db, &channel, &user.id)
+        .await
+        .map(|level| Json(level.into()))
+}
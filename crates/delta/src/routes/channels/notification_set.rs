@@ -0,0 +1,35 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    ChannelNotificationPreference, Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Set Notification Preference
+///
+/// Set which messages in this channel you are notified of.
+///
+/// Text channels in large servers only ever push mentions, regardless of this preference.
+#[openapi(tag = "Channel Information")]
+#[put("/<target>/notifications", data = "<data>")]
+pub async fn set_notification_preference(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataSetNotificationPreference>,
+) -> Result<Json<v0::ChannelNotificationPreference>> {
+    let data = data.into_inner();
+
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    let level = data.level.into();
+    ChannelNotificationPreference::set_level(db, &channel, &user.id, level).await?;
+
+    Ok(Json(level.into()))
+}
@@ -0,0 +1,138 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Effective Permissions
+///
+/// Fetch the permissions a user has in this channel, after applying the server's
+/// default role, their roles and any category and channel overrides.
+///
+/// Use `@me` to fetch your own permissions, fetching another user's permissions
+/// requires the `ManagePermissions` permission.
+#[openapi(tag = "Channel Permissions")]
+#[get("/<target>/permissions/<member>")]
+pub async fn fetch_effective_permissions(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    member: Reference,
+) -> Result<Json<v0::EffectivePermissions>> {
+    let channel = target.as_channel(db).await?;
+
+    let subject = if member.id == "@me" || member.id == user.id {
+        None
+    } else {
+        Some(member.as_user(db).await?)
+    };
+
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let mut permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    if let Some(subject) = &subject {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::ManagePermissions)?;
+
+        let mut query = DatabasePermissionQuery::new(db, subject).channel(&channel);
+        permissions = calculate_channel_permissions(&mut query).await;
+    }
+
+    Ok(Json(v0::EffectivePermissions {
+        granted: permissions
+            .granted_channel_permissions()
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+        permissions: permissions.into(),
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use onechatsocial_database::{Member, Server};
+    use onechatsocial_models::v0::{self, DataCreateServer};
+    use onechatsocial_permissions::ChannelPermission;
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn reflects_override_changes() {
+        let harness = TestHarness::new().await;
+        let (_, session, user) = harness.new_user().await;
+        let (_, other_session, other_user) = harness.new_user().await;
+
+        let (server, channels) = Server::create(
+            &harness.db,
+            DataCreateServer {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            &user,
+            true,
+        )
+        .await
+        .unwrap();
+
+        Member::create(&harness.db, &server, &other_user, None)
+            .await
+            .unwrap();
+
+        let channel = channels[0].id();
+        let fetch = |member: String, token: String| {
+            harness
+                .client
+                .get(format!("/channels/{channel}/permissions/{member}"))
+                .header(Header::new("x-session-token", token))
+                .dispatch()
+        };
+
+        let response = fetch("@me".to_string(), other_session.token.to_string()).await;
+        assert_eq!(response.status(), Status::Ok);
+
+        let permissions: v0::EffectivePermissions =
+            response.into_json().await.expect("`EffectivePermissions`");
+        assert!(permissions.granted.contains(&"SendMessage".to_string()));
+        assert_ne!(
+            permissions.permissions & ChannelPermission::SendMessage as u64,
+            0
+        );
+
+        // Members may not look up other users' permissions
+        let response = fetch(user.id.to_string(), other_session.token.to_string()).await;
+        assert_eq!(response.status(), Status::Forbidden);
+        drop(response);
+
+        let response = harness
+            .client
+            .put(format!("/channels/{channel}/permissions/default"))
+            .header(ContentType::JSON)
+            .body(
+                json!({
+                    "permissions": {
+                        "allow": 0,
+                        "deny": ChannelPermission::SendMessage as u64
+                    }
+                })
+                .to_string(),
+            )
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+        drop(response);
+
+        // The channel override applies straight away
+        let response = fetch(other_user.id.to_string(), session.token.to_string()).await;
+        assert_eq!(response.status(), Status::Ok);
+
+        let permissions: v0::EffectivePermissions =
+            response.into_json().await.expect("`EffectivePermissions`");
+        assert!(!permissions.granted.contains(&"SendMessage".to_string()));
+        assert!(permissions.granted.contains(&"ViewChannel".to_string()));
+    }
+}
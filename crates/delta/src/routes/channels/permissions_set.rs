@@ -1,9 +1,10 @@
 use rocket::serde::json::Json;
 use serde::Deserialize;
 
+use onechatsocial_database::{AuditLogAction, AuditLogChange};
 use onechatsocial_quark::{
-    models::{Channel, User},
-    perms, Db, Error, Override, Permission, Ref, Result,
+    models::{Channel, Server, User},
+    perms, Db, Error, Override, OverrideField, Permission, Ref, Result,
 };
 
 /// # Permission Value
@@ -50,7 +51,7 @@ pub async fn req(
     let perm_clone = perm.clone();
     if let Some(channel) = perm_clone.channel.get() {
         match channel {
-            Channel::TextChannel { server: server_id, .. } | Channel::VoiceChannel { server: server_id, .. } => {
+            Channel::TextChannel { server: server_id, role_permissions, .. } | Channel::VoiceChannel { server: server_id, role_permissions, .. } => {
                 let server = match Ref::from_unchecked(server_id.to_string()).as_server(db).await {
                     Ok(s) => s,
                     Err(e) => {
@@ -72,7 +73,18 @@ pub async fn req(
                     return Err(Error::NotElevated);
                 }
     
-                let current_value: Override = role.permissions.into();
+                // Every permission the override allows must be held by the editor,
+                // not only those newly allowed, so an override can never grant more
+                // than the editor's own effective permissions
+                match perm.throw_permission_value(db, data.permissions.allows()).await {
+                    Ok(_) => {},
+                    Err(e) => {
+                        eprintln!("Error Code 7: {:?}", e);
+                        return Err(e);
+                    }
+                }
+
+                let current_value: Option<Override> = role_permissions.get(&role_id).map(|value| (*value).into());
     
                 match perm.throw_permission_override(db, current_value, data.permissions).await {
                     Ok(_) => {},
@@ -89,6 +101,22 @@ pub async fn req(
                         return Err(e);
                     }
                 }
+
+                let before = role_permissions.get(&role_id).copied();
+                let after: Option<OverrideField> = Some(data.permissions.into());
+                Server::record_audit_log(
+                    db,
+                    server_id,
+                    &user.id,
+                    AuditLogAction::ChannelPermissionsUpdate,
+                    Some(channel.id()),
+                    vec![AuditLogChange::new(
+                        &format!("role_permissions.{role_id}"),
+                        &before,
+                        &after,
+                    )],
+                )
+                .await;
     
                 Ok(Json(channel_clone))
 
@@ -99,3 +127,200 @@ pub async fn req(
         Err(Error::InvalidOperation)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use onechatsocial_database::{Bot, Channel, Member, PartialMember, Role, Server};
+    use onechatsocial_models::v0::DataCreateServer;
+    use onechatsocial_permissions::{ChannelPermission, OverrideField};
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn bot_cannot_edit_own_role_at_equal_rank() {
+        let harness = TestHarness::new().await;
+        let (_, _, user) = harness.new_user().await;
+
+        let bot = Bot::create(&harness.db, TestHarness::rand_string(), &user, None)
+            .await
+            .expect("`Bot`");
+
+        let bot_user = harness.db.fetch_user(&bot.id).await.unwrap();
+
+        let (server, channels) = Server::create(
+            &harness.db,
+            DataCreateServer {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            &user,
+            true,
+        )
+        .await
+        .unwrap();
+
+        let role_id = Role {
+            name: TestHarness::rand_string(),
+            permissions: OverrideField {
+                a: ChannelPermission::ManagePermissions as i64,
+                d: 0,
+            },
+            colour: None,
+            hoist: false,
+            rank: 1,
+            icon: None,
+        }
+        .create(&harness.db, &server.id)
+        .await
+        .unwrap();
+
+        Member::create(&harness.db, &server, &bot_user, None)
+            .await
+            .unwrap();
+
+        let mut member = harness.db.fetch_member(&server.id, &bot.id).await.unwrap();
+        member
+            .update(
+                &harness.db,
+                PartialMember {
+                    roles: Some(vec![role_id.to_string()]),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        // The bot's highest role ranks equal to the role being edited
+        let response = harness
+            .client
+            .put(format!(
+                "/channels/{}/permissions/{}",
+                channels[0].id(),
+                role_id
+            ))
+            .header(ContentType::JSON)
+            .body(
+                json!({
+                    "permissions": {
+                        "allow": ChannelPermission::ManageChannel as u64,
+                        "deny": 0
+                    }
+                })
+                .to_string(),
+            )
+            .header(Header::new("x-bot-token", bot.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let error: serde_json::Value = response.into_json().await.expect("`Error`");
+        assert_eq!(error["type"], "NotElevated");
+
+        match harness.db.fetch_channel(&channels[0].id()).await.unwrap() {
+            Channel::TextChannel {
+                role_permissions, ..
+            } => assert!(!role_permissions.contains_key(&role_id)),
+            _ => unreachable!(),
+        }
+    }
+
+    #[rocket::async_test]
+    async fn bot_cannot_allow_permissions_it_lacks() {
+        let harness = TestHarness::new().await;
+        let (_, _, user) = harness.new_user().await;
+
+        let bot = Bot::create(&harness.db, TestHarness::rand_string(), &user, None)
+            .await
+            .expect("`Bot`");
+
+        let bot_user = harness.db.fetch_user(&bot.id).await.unwrap();
+
+        let (server, channels) = Server::create(
+            &harness.db,
+            DataCreateServer {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            &user,
+            true,
+        )
+        .await
+        .unwrap();
+
+        let mut role_ids = vec![];
+        for rank in [1, 2] {
+            role_ids.push(
+                Role {
+                    name: TestHarness::rand_string(),
+                    permissions: OverrideField {
+                        a: if rank == 1 {
+                            ChannelPermission::ManagePermissions as i64
+                        } else {
+                            0
+                        },
+                        d: 0,
+                    },
+                    colour: None,
+                    hoist: false,
+                    rank,
+                    icon: None,
+                }
+                .create(&harness.db, &server.id)
+                .await
+                .unwrap(),
+            );
+        }
+
+        Member::create(&harness.db, &server, &bot_user, None)
+            .await
+            .unwrap();
+
+        let mut member = harness.db.fetch_member(&server.id, &bot.id).await.unwrap();
+        member
+            .update(
+                &harness.db,
+                PartialMember {
+                    roles: Some(vec![role_ids[0].to_string()]),
+                    ..Default::default()
+                },
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        let set = |allow: u64| {
+            harness
+                .client
+                .put(format!(
+                    "/channels/{}/permissions/{}",
+                    channels[0].id(),
+                    role_ids[1]
+                ))
+                .header(ContentType::JSON)
+                .body(
+                    json!({
+                        "permissions": {
+                            "allow": allow,
+                            "deny": 0
+                        }
+                    })
+                    .to_string(),
+                )
+                .header(Header::new("x-bot-token", bot.token.to_string()))
+                .dispatch()
+        };
+
+        // The bot outranks the role but does not hold ManageChannel itself
+        let response = set(ChannelPermission::ManageChannel as u64).await;
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let error: serde_json::Value = response.into_json().await.expect("`Error`");
+        assert_eq!(error["type"], "CannotGiveMissingPermissions");
+
+        // Permissions the bot holds may be granted
+        let response = set(ChannelPermission::ManagePermissions as u64).await;
+        assert_eq!(response.status(), Status::Ok);
+    }
+}
@@ -1,8 +1,9 @@
 use rocket::serde::json::Json;
 use serde::Deserialize;
 
+use onechatsocial_database::{AuditLogAction, AuditLogChange};
 use onechatsocial_quark::{
-    models::{channel::PartialChannel, Channel, User},
+    models::{channel::PartialChannel, Channel, Server, User},
     perms, Db, Error, Override, Permission, Ref, Result,
 };
 
@@ -59,10 +60,12 @@ pub async fn req(
             }
         }
         Channel::TextChannel {
+            server,
             default_permissions,
             ..
         }
         | Channel::VoiceChannel {
+            server,
             default_permissions,
             ..
         } => {
@@ -74,16 +77,30 @@ pub async fn req(
                 )
                 .await?;
 
+                let server = server.clone();
+                let before = *default_permissions;
+                let after = Some(permissions.into());
+
                 channel
                     .update(
                         db,
                         PartialChannel {
-                            default_permissions: Some(permissions.into()),
+                            default_permissions: after,
                             ..Default::default()
                         },
                         vec![],
                     )
                     .await?;
+
+                Server::record_audit_log(
+                    db,
+                    &server,
+                    &user.id,
+                    AuditLogAction::ChannelPermissionsUpdate,
+                    Some(channel.id()),
+                    vec![AuditLogChange::new("default_permissions", &before, &after)],
+                )
+                .await;
             } else {
                 return Err(Error::InvalidOperation);
             }
@@ -0,0 +1,39 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, Message, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Create Poll
+///
+/// Start a new poll in a channel which members can vote on.
+///
+/// Requires `SendMessage` permission.
+#[openapi(tag = "Messaging")]
+#[post("/<target>/polls", data = "<data>")]
+pub async fn create_poll(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataCreatePoll>,
+) -> Result<Json<v0::Message>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::SendMessage)?;
+
+    Message::create_poll(db, &channel, data, &user.id)
+        .await
+        .map(|message| Json(message.into()))
+}
@@ -0,0 +1,40 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Vote on Poll
+///
+/// Vote for an option of a message's poll, voting for the same option again removes the vote.
+///
+/// Votes on single-select polls replace any previous vote.
+#[openapi(tag = "Messaging")]
+#[post("/<target>/messages/<msg>/poll/vote", data = "<data>")]
+pub async fn vote_poll(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+    data: Json<v0::DataPollVote>,
+) -> Result<Json<v0::Poll>> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    // Fetch relevant message
+    let mut message = msg.as_message(db).await?;
+    if message.channel != channel.id() {
+        return Err(create_error!(NotFound));
+    }
+
+    message.vote_poll(db, data.option, &user.id).await?;
+    message
+        .poll
+        .map(|poll| Json(poll.into()))
+        .ok_or_else(|| create_error!(NotFound))
+}
@@ -0,0 +1,41 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Remove Reaction Role
+///
+/// Stop granting a role to users who react to a message with a given emoji.
+///
+/// Roles which have already been granted are left as they are.
+///
+/// Requires `ManageRole` and `ManageMessages`.
+#[openapi(tag = "Interactions")]
+#[delete("/<target>/messages/<msg>/reaction_roles/<emoji>")]
+pub async fn remove_reaction_role(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+    emoji: Reference,
+) -> Result<EmptyResponse> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageRole)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+    let reaction_role = db.fetch_reaction_role(&msg.id, &emoji.id).await?;
+    if reaction_role.channel != channel.id() {
+        return Err(create_error!(NotFound));
+    }
+
+    db.delete_reaction_role(&reaction_role.id)
+        .await
+        .map(|_| EmptyResponse)
+}
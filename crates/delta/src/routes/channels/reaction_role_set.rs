@@ -0,0 +1,80 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Channel, Database, Emoji, ReactionRole, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+
+use rocket::{serde::json::Json, State};
+
+/// # Set Reaction Role
+///
+/// Grant a role to anyone who reacts to a message with a given emoji,
+/// the role is removed again when they remove their reaction.
+///
+/// Requires `ManageRole` and `ManageMessages`, you must also rank above the given role.
+#[openapi(tag = "Interactions")]
+#[put("/<target>/messages/<msg>/reaction_roles/<emoji>", data = "<data>")]
+pub async fn set_reaction_role(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+    emoji: Reference,
+    data: Json<v0::DataSetReactionRole>,
+) -> Result<Json<v0::ReactionRole>> {
+    let data = data.into_inner();
+
+    let channel = target.as_channel(db).await?;
+    let server_id = match &channel {
+        Channel::TextChannel { server, .. } | Channel::VoiceChannel { server, .. } => server,
+        _ => return Err(create_error!(InvalidOperation)),
+    };
+
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    let permissions = calculate_channel_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageRole)?;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+    // Ensure the role exists and that we rank above it
+    let server = db.fetch_server(server_id).await?;
+    let role = server
+        .roles
+        .get(&data.role)
+        .ok_or_else(|| create_error!(NotFound))?;
+
+    if user.id != server.owner {
+        let member = db.fetch_member(&server.id, &user.id).await?;
+        if role.rank <= member.get_ranking(&server) {
+            return Err(create_error!(NotElevated));
+        }
+    }
+
+    // Ensure the reaction can actually be used
+    let message = msg.as_message(db).await?;
+    if message.channel != channel.id() {
+        return Err(create_error!(NotFound));
+    }
+
+    if !message.interactions.can_use(&emoji.id) || !Emoji::can_use(db, &user.id, &emoji.id).await? {
+        return Err(create_error!(InvalidOperation));
+    }
+
+    // Replace any existing role for this reaction
+    if let Ok(existing) = db.fetch_reaction_role(&message.id, &emoji.id).await {
+        db.delete_reaction_role(&existing.id).await?;
+    }
+
+    let reaction_role = ReactionRole {
+        id: ulid::Ulid::new().to_string(),
+        server: server.id,
+        channel: channel.id(),
+        message: message.id,
+        emoji: emoji.id,
+        role: data.role,
+    };
+
+    db.insert_reaction_role(&reaction_role).await?;
+    Ok(Json(reaction_role.into()))
+}
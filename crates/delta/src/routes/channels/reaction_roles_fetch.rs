@@ -0,0 +1,36 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::Result;
+
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Reaction Roles
+///
+/// Fetch all roles granted by reacting to a given message.
+#[openapi(tag = "Interactions")]
+#[get("/<target>/messages/<msg>/reaction_roles")]
+pub async fn fetch_reaction_roles(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    msg: Reference,
+) -> Result<Json<Vec<v0::ReactionRole>>> {
+    let channel = target.as_channel(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).channel(&channel);
+    calculate_channel_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    Ok(Json(
+        db.fetch_reaction_roles(&msg.id)
+            .await?
+            .into_iter()
+            .filter(|reaction_role| reaction_role.channel == channel.id())
+            .map(|reaction_role| reaction_role.into())
+            .collect(),
+    ))
+}
@@ -57,6 +57,8 @@ pub async fn req(
         channel_id: channel.id(),
         permissions: *DEFAULT_WEBHOOK_PERMISSIONS,
         token: Some(nanoid::nanoid!(64)),
+        secret: data.secret,
+        template: data.template.map(|template| template.into()),
     };
 
     webhook.create(db).await?;
@@ -44,10 +44,14 @@ pub async fn create_emoji(
                 .throw_if_lacking_channel_permission(ChannelPermission::ManageCustomisation)?;
 
             // Check that we haven't hit the emoji limit
+            let limits = config
+                .features
+                .server_limits(server.flags.unwrap_or_default() as u32);
+
             let emojis = db.fetch_emoji_by_parent_id(&server.id).await?;
-            if emojis.len() >= config.features.limits.default.server_emoji {
+            if emojis.len() >= limits.server_emoji {
                 return Err(create_error!(TooManyEmoji {
-                    max: config.features.limits.default.server_emoji,
+                    max: limits.server_emoji,
                 }));
             }
         }
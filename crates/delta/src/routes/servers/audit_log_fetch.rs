@@ -0,0 +1,76 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// # Query Parameters
+#[derive(Validate, Serialize, Deserialize, JsonSchema, FromForm)]
+pub struct OptionsFetchAuditLog {
+    /// Maximum number of entries to fetch
+    #[validate(range(min = 1, max = 100))]
+    limit: Option<i64>,
+
+    /// Only fetch entries created before the entry with this id
+    #[validate(length(min = 26, max = 26))]
+    before: Option<String>,
+
+    /// Only fetch entries of this action type
+    action: Option<String>,
+
+    /// Only fetch entries for actions taken by this user
+    #[validate(length(min = 26, max = 26))]
+    actor: Option<String>,
+}
+
+/// # Fetch Audit Log
+///
+/// Fetch moderation and configuration actions taken in a server, newest first.
+#[openapi(tag = "Server Information")]
+#[get("/<target>/audit-log?<options..>")]
+pub async fn fetch_audit_log(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    options: OptionsFetchAuditLog,
+) -> Result<Json<Vec<v0::AuditLogEntry>>> {
+    options.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let action: Option<onechatsocial_database::AuditLogAction> = options
+        .action
+        .map(|action| {
+            serde_json::from_value::<v0::AuditLogAction>(serde_json::Value::String(action))
+                .map(|action| action.into())
+                .map_err(|_| create_error!(InvalidProperty))
+        })
+        .transpose()?;
+
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ViewAuditLog)?;
+
+    Ok(Json(
+        db.fetch_audit_log_entries(
+            &server.id,
+            action.as_ref(),
+            options.actor.as_deref(),
+            options.before.as_deref(),
+            options.limit.unwrap_or(50),
+        )
+        .await?
+        .into_iter()
+        .map(|entry| entry.into())
+        .collect(),
+    ))
+}
@@ -1,6 +1,9 @@
+use std::time::{Duration, SystemTime};
+
+use onechatsocial_database::{AuditLogAction, AuditLogChange};
 use onechatsocial_quark::{
-    models::{server_member::MemberCompositeKey, ServerBan, User},
-    perms, Db, Error, Permission, Ref, Result,
+    models::{server_member::MemberCompositeKey, Server, ServerBan, User},
+    perms, Db, Error, Permission, Ref, Result, Timestamp,
 };
 
 use rocket::serde::json::Json;
@@ -13,11 +16,18 @@ pub struct DataBanCreate {
     /// Ban reason
     #[validate(length(min = 1, max = 1024))]
     reason: Option<String>,
+    /// Number of seconds until the ban is lifted
+    ///
+    /// Must be at most a year, the ban is kept until removed manually if not given.
+    #[validate(range(min = 1, max = 31536000))]
+    duration: Option<u64>,
 }
 
 /// # Ban User
 ///
 /// Ban a user by their id.
+///
+/// Banning a user who is already banned updates the reason and expiry of their ban.
 #[openapi(tag = "Server Members")]
 #[put("/<server>/bans/<target>", data = "<data>")]
 pub async fn req(
@@ -31,6 +41,10 @@ pub async fn req(
     data.validate()
         .map_err(|error| Error::FailedValidation { error })?;
 
+    let expires_at = data
+        .duration
+        .map(|duration| Timestamp::from(SystemTime::now() + Duration::from_secs(duration)));
+
     let server = server.as_server(db).await?;
 
     if target.id == user.id {
@@ -48,14 +62,16 @@ pub async fn req(
         .await?;
 
     // If member exists, check privileges against them
-    if let Ok(member) = target.as_member(db, &server.id).await {
+    let ban = if let Ok(member) = target.as_member(db, &server.id).await {
         if member.get_ranking(permissions.server.get().unwrap())
             <= permissions.get_member_rank().unwrap_or(i64::MIN)
         {
             return Err(Error::NotElevated);
         }
 
-        server.ban_member(db, member, data.reason).await.map(Json)
+        server
+            .ban_member(db, member, data.reason, &user.id, expires_at)
+            .await?
     } else {
         let server_id = server.id.to_string();
         server
@@ -66,8 +82,29 @@ pub async fn req(
                     user: target.id,
                 },
                 data.reason,
+                &user.id,
+                expires_at,
             )
-            .await
-            .map(Json)
-    }
+            .await?
+    };
+
+    let changes = vec![
+        AuditLogChange::new("reason", &None, &ban.reason),
+        AuditLogChange::new("expires_at", &None, &ban.expires_at),
+    ]
+    .into_iter()
+    .filter(|change| change.after.is_some())
+    .collect();
+
+    Server::record_audit_log(
+        db,
+        &server.id,
+        &user.id,
+        AuditLogAction::MemberBan,
+        Some(&ban.id.user),
+        changes,
+    )
+    .await;
+
+    Ok(Json(ban))
 }
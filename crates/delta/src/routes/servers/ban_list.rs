@@ -1,70 +1,89 @@
-use onechatsocial_quark::models::{File, ServerBan, User};
-use onechatsocial_quark::{perms, Db, Permission, Ref, Result};
-
-use rocket::serde::json::Json;
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
 use serde::{Deserialize, Serialize};
+use validator::Validate;
 
-/// # Banned User
-///
-/// Just enoguh user information to list bans.
-#[derive(Serialize, Deserialize, JsonSchema)]
-struct BannedUser {
-    /// Id of the banned user
-    #[serde(rename = "_id")]
-    pub id: String,
-    /// Username of the banned user
-    pub username: String,
-    /// Discriminator of the banned user
-    pub discriminator: String,
-    /// Avatar of the banned user
-    pub avatar: Option<File>,
-}
+/// Most bans counted for a single request
+const MAX_BAN_COUNT: u64 = 1000;
 
-/// # Ban List Result
-#[derive(Serialize, Deserialize, JsonSchema)]
-pub struct BanListResult {
-    /// Users objects
-    users: Vec<BannedUser>,
-    /// Ban objects
-    bans: Vec<ServerBan>,
-}
+/// # Query Parameters
+#[derive(Validate, Serialize, Deserialize, JsonSchema, FromForm)]
+pub struct OptionsFetchBans {
+    /// Maximum number of bans to fetch
+    #[validate(range(min = 1, max = 100))]
+    limit: Option<i64>,
+
+    /// Only fetch bans of users with an id before this one
+    #[validate(length(min = 26, max = 26))]
+    before: Option<String>,
+
+    /// Only fetch bans of users with an id after this one
+    #[validate(length(min = 26, max = 26))]
+    after: Option<String>,
 
-impl From<User> for BannedUser {
-    fn from(user: User) -> Self {
-        BannedUser {
-            id: user.id,
-            username: user.username,
-            discriminator: user.discriminator,
-            avatar: user.avatar,
-        }
-    }
+    /// Text to match against the start of the banned user's username or the ban reason
+    #[validate(length(min = 1, max = 64))]
+    query: Option<String>,
 }
 
 /// # Fetch Bans
 ///
-/// Fetch all bans on a server.
+/// Fetch a page of bans on a server, ordered by the banned user's id.
 #[openapi(tag = "Server Members")]
-#[get("/<target>/bans")]
-pub async fn req(db: &Db, user: User, target: Ref) -> Result<Json<BanListResult>> {
+#[get("/<target>/bans?<options..>")]
+pub async fn req(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    options: OptionsFetchBans,
+) -> Result<Json<v0::BanListResult>> {
+    options.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
     let server = target.as_server(db).await?;
-    perms(&user)
-        .server(&server)
-        .throw_permission(db, Permission::BanMembers)
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::BanMembers)?;
+
+    let bans = db
+        .fetch_bans_page(
+            &server.id,
+            options.limit.unwrap_or(100),
+            options.before.as_deref(),
+            options.after.as_deref(),
+            options.query.as_deref(),
+        )
+        .await?;
+
+    let count = db
+        .count_bans(&server.id, options.query.as_deref(), MAX_BAN_COUNT)
         .await?;
 
-    let bans = db.fetch_bans(&server.id).await?;
     let users = db
         .fetch_users(
             &bans
                 .iter()
-                .map(|x| &x.id.user)
-                .cloned()
+                .map(|ban| ban.id.user.clone())
                 .collect::<Vec<String>>(),
         )
         .await?
         .into_iter()
-        .map(|x| x.into())
+        .map(|user| user.into())
         .collect();
 
-    Ok(Json(BanListResult { users, bans }))
+    Ok(Json(v0::BanListResult {
+        users,
+        bans: bans.into_iter().map(|ban| ban.into()).collect(),
+        count,
+    }))
 }
@@ -1,4 +1,8 @@
-use onechatsocial_quark::{models::User, perms, Db, EmptyResponse, Permission, Ref, Result};
+use onechatsocial_database::AuditLogAction;
+use onechatsocial_quark::{
+    models::{Server, User},
+    perms, Db, EmptyResponse, Permission, Ref, Result,
+};
 
 /// # Unban user
 ///
@@ -13,5 +17,18 @@ pub async fn req(db: &Db, user: User, server: Ref, target: Ref) -> Result<EmptyR
         .await?;
 
     let ban = target.as_ban(db, &server.id).await?;
-    db.delete_ban(&ban.id).await.map(|_| EmptyResponse)
+    let user_id = ban.id.user.clone();
+    ban.lift(db).await?;
+
+    Server::record_audit_log(
+        db,
+        &server.id,
+        &user.id,
+        AuditLogAction::MemberUnban,
+        Some(&user_id),
+        vec![],
+    )
+    .await;
+
+    Ok(EmptyResponse)
 }
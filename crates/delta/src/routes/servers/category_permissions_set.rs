@@ -0,0 +1,97 @@
+use rocket::serde::json::Json;
+use serde::Deserialize;
+
+use onechatsocial_database::{AuditLogAction, AuditLogChange};
+use onechatsocial_quark::{
+    models::{server::PartialServer, Server, User},
+    perms, Db, Error, Override, Permission, Ref, Result,
+};
+
+/// # Permission Value
+#[derive(Deserialize, JsonSchema)]
+pub struct DataSetCategoryRolePermission {
+    /// Allow / deny values for the role in this category.
+    permissions: Override,
+}
+
+/// # Set Category Role Permission
+///
+/// Sets permissions for the specified role in a category, inherited by its channels.
+#[openapi(tag = "Server Permissions")]
+#[put(
+    "/<target>/categories/<category_id>/permissions/<role_id>",
+    data = "<data>",
+    rank = 2
+)]
+pub async fn set_category_role_permission(
+    db: &Db,
+    user: User,
+    target: Ref,
+    category_id: String,
+    role_id: String,
+    data: Json<DataSetCategoryRolePermission>,
+) -> Result<Json<Server>> {
+    let data = data.into_inner();
+
+    let mut server = target.as_server(db).await?;
+    let rank = server.roles.get(&role_id).ok_or(Error::NotFound)?.rank;
+
+    let mut categories = server.categories.clone().unwrap_or_default();
+    let category = categories
+        .iter_mut()
+        .find(|category| category.id == category_id)
+        .ok_or(Error::NotFound)?;
+
+    let mut permissions = perms(&user).server(&server);
+    permissions
+        .throw_permission(db, Permission::ManagePermissions)
+        .await?;
+
+    if rank <= permissions.get_member_rank().unwrap_or(i64::MIN) {
+        return Err(Error::NotElevated);
+    }
+
+    permissions
+        .throw_permission_override(
+            db,
+            category
+                .role_permissions
+                .get(&role_id)
+                .map(|x| Override::from(*x)),
+            data.permissions,
+        )
+        .await?;
+
+    let before = category
+        .role_permissions
+        .insert(role_id.clone(), data.permissions.into());
+
+    let after = category.role_permissions.get(&role_id).copied();
+
+    server
+        .update(
+            db,
+            PartialServer {
+                categories: Some(categories),
+                ..Default::default()
+            },
+            vec![],
+        )
+        .await?;
+
+    Server::record_audit_log(
+        db,
+        &server.id,
+        &user.id,
+        AuditLogAction::CategoryPermissionsUpdate,
+        Some(&category_id),
+        vec![AuditLogChange::new(
+            &format!("role_permissions.{role_id}"),
+            &before,
+            &after,
+        )],
+    )
+    .await;
+
+    Ok(Json(server))
+}
@@ -0,0 +1,81 @@
+use rocket::serde::json::Json;
+use serde::Deserialize;
+
+use onechatsocial_database::{AuditLogAction, AuditLogChange};
+use onechatsocial_quark::{
+    models::{server::PartialServer, Server, User},
+    perms, Db, Error, Override, Permission, Ref, Result,
+};
+
+/// # Permission Value
+#[derive(Deserialize, JsonSchema)]
+pub struct DataSetCategoryDefaultPermission {
+    /// Allow / deny values for members in this category.
+    permissions: Override,
+}
+
+/// # Set Category Default Permission
+///
+/// Sets permissions for the default role in a category, inherited by its channels.
+#[openapi(tag = "Server Permissions")]
+#[put(
+    "/<target>/categories/<category_id>/permissions/default",
+    data = "<data>",
+    rank = 1
+)]
+pub async fn set_category_default_permission(
+    db: &Db,
+    user: User,
+    target: Ref,
+    category_id: String,
+    data: Json<DataSetCategoryDefaultPermission>,
+) -> Result<Json<Server>> {
+    let data = data.into_inner();
+
+    let mut server = target.as_server(db).await?;
+    let mut categories = server.categories.clone().unwrap_or_default();
+    let category = categories
+        .iter_mut()
+        .find(|category| category.id == category_id)
+        .ok_or(Error::NotFound)?;
+
+    let mut permissions = perms(&user).server(&server);
+    permissions
+        .throw_permission(db, Permission::ManagePermissions)
+        .await?;
+
+    permissions
+        .throw_permission_override(
+            db,
+            category.default_permissions.map(|x| x.into()),
+            data.permissions,
+        )
+        .await?;
+
+    let before = category.default_permissions;
+    category.default_permissions = Some(data.permissions.into());
+    let after = category.default_permissions;
+
+    server
+        .update(
+            db,
+            PartialServer {
+                categories: Some(categories),
+                ..Default::default()
+            },
+            vec![],
+        )
+        .await?;
+
+    Server::record_audit_log(
+        db,
+        &server.id,
+        &user.id,
+        AuditLogAction::CategoryPermissionsUpdate,
+        Some(&category_id),
+        vec![AuditLogChange::new("default_permissions", &before, &after)],
+    )
+    .await;
+
+    Ok(Json(server))
+}
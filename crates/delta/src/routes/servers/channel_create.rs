@@ -1,5 +1,7 @@
 use onechatsocial_database::util::permissions::DatabasePermissionQuery;
-use onechatsocial_database::{util::reference::Reference, Channel, Database, User};
+use onechatsocial_database::{
+    util::reference::Reference, Channel, Database, ServerNameFilter, User,
+};
 use onechatsocial_models::v0;
 use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
 use onechatsocial_result::{create_error, Result};
@@ -11,6 +13,9 @@ use validator::Validate;
 /// # Create Channel
 ///
 /// Create a new Text or Voice channel.
+///
+/// Initial role permission overrides require `ManagePermissions`, you must rank above
+/// each role and may only grant permissions you have yourself.
 #[openapi(tag = "Server Information")]
 #[post("/<server>/channels", data = "<data>")]
 pub async fn create_server_channel(
@@ -28,9 +33,39 @@ pub async fn create_server_channel(
 
     let mut server = server.as_server(db).await?;
     let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
-    calculate_server_permissions(&mut query)
-        .await
-        .throw_if_lacking_channel_permission(ChannelPermission::ManageChannel)?;
+    let permissions = calculate_server_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageChannel)?;
+
+    ServerNameFilter::validate_name(db, &server.id, &data.name).await?;
+
+    // Initial overrides are subject to the same checks as setting them afterwards
+    if let Some(role_permissions) = &data.role_permissions {
+        if !role_permissions.is_empty() {
+            permissions
+                .throw_if_lacking_channel_permission(ChannelPermission::ManagePermissions)?;
+
+            let member_rank = if server.owner == user.id {
+                i64::MIN
+            } else {
+                db.fetch_member(&server.id, &user.id)
+                    .await?
+                    .get_ranking(&server)
+            };
+
+            for (role_id, value) in role_permissions {
+                let role = server
+                    .roles
+                    .get(role_id)
+                    .ok_or_else(|| create_error!(NotFound))?;
+
+                if role.rank <= member_rank {
+                    return Err(create_error!(NotElevated));
+                }
+
+                permissions.throw_permission_override(None, value)?;
+            }
+        }
+    }
 
     Channel::create_server_channel(db, &mut server, data, true)
         .await
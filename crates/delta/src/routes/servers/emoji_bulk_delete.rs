@@ -0,0 +1,50 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, Emoji, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Bulk Delete Emoji
+///
+/// Delete multiple emoji from a server.
+///
+/// Ids which could not be deleted are reported back rather than failing the whole request.
+#[openapi(tag = "Emojis")]
+#[delete("/<target>/emoji/bulk", data = "<data>")]
+pub async fn bulk_delete_emoji(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataBulkDeleteEmoji>,
+) -> Result<Json<v0::BulkDeleteEmojiResponse>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    // Bots cannot manage emojis
+    if user.bot.is_some() {
+        return Err(create_error!(IsBot));
+    }
+
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageCustomisation)?;
+
+    let deleted = Emoji::delete_many(db, &server.id, &data.ids).await?;
+    let failed = data
+        .ids
+        .into_iter()
+        .filter(|id| !deleted.contains(id))
+        .collect();
+
+    Ok(Json(v0::BulkDeleteEmojiResponse { deleted, failed }))
+}
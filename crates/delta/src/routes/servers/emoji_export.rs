@@ -0,0 +1,33 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Export Emoji
+///
+/// Export emoji definitions so they can be imported into another server.
+#[openapi(tag = "Emojis")]
+#[get("/<target>/export/emoji")]
+pub async fn export_emoji(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<Vec<v0::EmojiDefinition>>> {
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageCustomisation)?;
+
+    Ok(Json(
+        db.fetch_emoji_by_parent_id(&server.id)
+            .await?
+            .into_iter()
+            .map(|emoji| emoji.into())
+            .collect(),
+    ))
+}
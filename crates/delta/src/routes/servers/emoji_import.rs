@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use onechatsocial_config::config;
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, Emoji, EmojiParent, File, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Import Emoji
+///
+/// Create emoji from definitions exported from another server.
+///
+/// Emoji whose name is already in use are not created and are reported as collisions.
+#[openapi(tag = "Emojis")]
+#[post("/<target>/import/emoji", data = "<data>")]
+pub async fn import_emoji(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataImportEmoji>,
+) -> Result<Json<v0::ImportResponse>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    for emoji in &data.emojis {
+        emoji.validate().map_err(|error| {
+            create_error!(FailedValidation {
+                error: error.to_string()
+            })
+        })?;
+    }
+
+    // Bots cannot manage emojis
+    if user.bot.is_some() {
+        return Err(create_error!(IsBot));
+    }
+
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageCustomisation)?;
+
+    let existing = db.fetch_emoji_by_parent_id(&server.id).await?;
+
+    let mut ids = HashMap::new();
+    let mut collisions = vec![];
+    let mut definitions = vec![];
+    for definition in data.emojis {
+        if let Some(emoji) = existing.iter().find(|emoji| emoji.name == definition.name) {
+            ids.insert(definition.id.clone(), emoji.id.clone());
+            collisions.push(definition.id);
+        } else {
+            definitions.push(definition);
+        }
+    }
+
+    let config = config().await;
+    let limits = config
+        .features
+        .server_limits(server.flags.unwrap_or_default() as u32);
+
+    if existing.len() + definitions.len() > limits.server_emoji {
+        return Err(create_error!(TooManyEmoji {
+            max: limits.server_emoji,
+        }));
+    }
+
+    for definition in definitions {
+        let attachment = File::use_emoji(db, &definition.file, &definition.file).await?;
+
+        let emoji = Emoji {
+            id: definition.file,
+            parent: EmojiParent::Server {
+                id: server.id.clone(),
+            },
+            creator_id: user.id.clone(),
+            name: definition.name,
+            animated: "image/gif" == &attachment.content_type,
+            nsfw: definition.nsfw,
+        };
+
+        emoji.create(db).await?;
+        ids.insert(definition.id, emoji.id);
+    }
+
+    Ok(Json(v0::ImportResponse { ids, collisions }))
+}
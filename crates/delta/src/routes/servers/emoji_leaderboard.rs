@@ -0,0 +1,80 @@
+use std::time::{Duration, SystemTime};
+
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{
+    calculate_channel_permissions, calculate_server_permissions, ChannelPermission,
+};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Maximum number of recent messages to consider
+const SCAN_LIMIT: i64 = 10_000;
+
+/// Number of emoji to return
+const LEADERBOARD_SIZE: i64 = 25;
+
+/// # Query Parameters
+#[derive(Validate, Serialize, Deserialize, JsonSchema, FromForm)]
+pub struct OptionsEmojiLeaderboard {
+    /// Number of days to look back over
+    #[validate(range(min = 1, max = 90))]
+    window: Option<u64>,
+}
+
+/// # Fetch Emoji Leaderboard
+///
+/// Fetch the most used reactions in a server over a given period of time.
+///
+/// Only channels you can read are counted.
+#[openapi(tag = "Server Customisation")]
+#[get("/<target>/emoji/leaderboard?<options..>")]
+pub async fn emoji_leaderboard(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    options: OptionsEmojiLeaderboard,
+) -> Result<Json<Vec<v0::EmojiLeaderboardEntry>>> {
+    options.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ViewChannel)?;
+
+    // Only count reactions in channels we are allowed to read
+    let channels = db.fetch_channels(&server.channels).await?;
+    let mut readable_channels = vec![];
+    for channel in &channels {
+        let mut query = query.clone().channel(channel);
+        if calculate_channel_permissions(&mut query)
+            .await
+            .has_channel_permission(ChannelPermission::ReadMessageHistory)
+        {
+            readable_channels.push(channel.id());
+        }
+    }
+
+    if readable_channels.is_empty() {
+        return Ok(Json(vec![]));
+    }
+
+    let since = SystemTime::now() - Duration::from_secs(options.window.unwrap_or(7) * 86_400);
+    Ok(Json(
+        db.fetch_reaction_leaderboard(&readable_channels, since, SCAN_LIMIT, LEADERBOARD_SIZE)
+            .await?
+            .into_iter()
+            .map(|entry| entry.into())
+            .collect(),
+    ))
+}
@@ -0,0 +1,29 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::Result;
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Delete Event Webhook
+///
+/// Stop sending member join, leave and ban events to a webhook.
+#[openapi(tag = "Server Information")]
+#[delete("/<target>/event_webhook")]
+pub async fn delete_event_webhook(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<EmptyResponse> {
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    db.set_server_event_webhook(&server.id, None)
+        .await
+        .map(|_| EmptyResponse)
+}
@@ -0,0 +1,30 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Event Webhook
+///
+/// Fetch the webhook which receives member join, leave and ban events.
+#[openapi(tag = "Server Information")]
+#[get("/<target>/event_webhook")]
+pub async fn fetch_event_webhook(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<v0::ServerEventWebhook>> {
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    server
+        .event_webhook
+        .map(|webhook| Json(v0::ServerEventWebhook { url: webhook.url }))
+        .ok_or_else(|| create_error!(NotFound))
+}
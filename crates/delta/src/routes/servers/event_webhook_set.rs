@@ -0,0 +1,53 @@
+use onechatsocial_database::{
+    tasks::server_webhook,
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, ServerEventWebhook, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Set Event Webhook
+///
+/// Set the webhook which receives member join, leave and ban events.
+///
+/// Events are delivered as JSON in a `POST` request and retried with
+/// backoff if the remote server fails to respond successfully.
+///
+/// The URL must point at a publicly reachable address.
+#[openapi(tag = "Server Information")]
+#[put("/<target>/event_webhook", data = "<data>")]
+pub async fn set_event_webhook(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataEditServerEventWebhook>,
+) -> Result<Json<v0::ServerEventWebhook>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    // Refuse loopback, link-local and private addresses
+    server_webhook::resolve_public_address(&data.url).await?;
+
+    let webhook = ServerEventWebhook {
+        url: data.url,
+        secret: data.secret,
+    };
+
+    db.set_server_event_webhook(&server.id, Some(&webhook))
+        .await?;
+
+    Ok(Json(v0::ServerEventWebhook { url: webhook.url }))
+}
@@ -1,14 +1,16 @@
 use std::collections::HashSet;
 
+use onechatsocial_database::ServerNameFilter;
 use onechatsocial_quark::{
     models::{
+        server::DEFAULT_ROLE_ID,
         server_member::{FieldsMember, PartialMember},
         File, Member, User,
     },
     perms, Db, Error, Permission, Ref, Result, Timestamp,
 };
 
-use rocket::serde::json::Json;
+use rocket::{serde::json::Json, State};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
@@ -36,6 +38,7 @@ pub struct DataMemberEdit {
 #[patch("/<server>/members/<target>", data = "<data>")]
 pub async fn req(
     db: &Db,
+    core_db: &State<onechatsocial_database::Database>,
     user: User,
     server: Ref,
     target: Ref,
@@ -123,6 +126,11 @@ pub async fn req(
         let added_roles: Vec<&&String> = new_roles.difference(&current_roles).collect();
 
         for role_id in added_roles {
+            // Every member already has the default role
+            if role_id.as_str() == DEFAULT_ROLE_ID {
+                return Err(Error::InvalidRole);
+            }
+
             if let Some(role) = server.roles.remove(*role_id) {
                 if role.rank <= our_ranking {
                     return Err(Error::NotElevated);
@@ -133,6 +141,13 @@ pub async fn req(
         }
     }
 
+    // Ensure the new nickname is allowed in this server
+    if let Some(nickname) = &data.nickname {
+        ServerNameFilter::validate_name(core_db, &server.id, nickname)
+            .await
+            .map_err(Error::from_core)?;
+    }
+
     // Apply edits to the member object
     let DataMemberEdit {
         nickname,
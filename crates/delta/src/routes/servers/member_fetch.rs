@@ -5,7 +5,7 @@ use rocket::serde::json::Json;
 ///
 /// Retrieve a member.
 #[openapi(tag = "Server Members")]
-#[get("/<target>/members/<member>?<roles>")]
+#[get("/<target>/members/<member>?<roles>", rank = 2)]
 pub async fn req(
     db: &Db,
     user: User,
@@ -1,62 +1,97 @@
-use onechatsocial_quark::{
-    models::{Member, User},
-    perms, Db, Ref, Result,
-};
+use std::collections::HashMap;
 
-use rocket::serde::json::Json;
+use onechatsocial_database::{util::reference::Reference, Database, User};
+use onechatsocial_models::v0;
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
 use serde::{Deserialize, Serialize};
+use validator::Validate;
 
 /// # Query Parameters
-#[derive(Deserialize, JsonSchema, FromForm)]
+#[derive(Validate, Serialize, Deserialize, JsonSchema, FromForm)]
 pub struct OptionsFetchAllMembers {
     /// Whether to exclude offline users
+    ///
+    /// Pages may contain fewer members than the limit when set.
     exclude_offline: Option<bool>,
-}
-
-/// # Member List
-///
-/// Both lists are sorted by ID.
-#[derive(Serialize, JsonSchema)]
-pub struct AllMemberResponse {
-    /// List of members
-    members: Vec<Member>,
-    /// List of users
-    users: Vec<User>,
+    /// Maximum number of members to return, all members are returned if not set
+    #[validate(range(min = 1, max = 1000))]
+    limit: Option<usize>,
+    /// User id after which members should be fetched
+    #[validate(length(min = 26, max = 26))]
+    after: Option<String>,
+    /// Only include members with this role
+    #[validate(length(min = 26, max = 26))]
+    role: Option<String>,
 }
 
 /// # Fetch Members
 ///
-/// Fetch all server members.
+/// Fetch server members, sorted by user id.
+///
+/// Use `after` with the last user id of the previous page to fetch the next one.
 #[openapi(tag = "Server Members")]
 #[get("/<target>/members?<options..>")]
-pub async fn req(
-    db: &Db,
+pub async fn fetch_all_members(
+    db: &State<Database>,
     user: User,
-    target: Ref,
+    target: Reference,
     options: OptionsFetchAllMembers,
-) -> Result<Json<AllMemberResponse>> {
-    let server = target.as_server(db).await?;
-    perms(&user).server(&server).calc(db).await?;
+) -> Result<Json<v0::AllMemberResponse>> {
+    options.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
 
-    let mut members = db.fetch_all_members(&server.id).await?;
+    let server = target.as_server(db).await?;
 
-    let mut user_ids = vec![];
-    for member in &members {
-        user_ids.push(member.id.user.clone());
+    // Only members may view the member list
+    if db.fetch_member(&server.id, &user.id).await.is_err() {
+        return Err(create_error!(NotFound));
     }
 
-    let mut users = User::fetch_foreign_users(db, &user_ids).await?;
+    let members = db
+        .fetch_members_page(
+            &server.id,
+            options.after.as_deref(),
+            options.role.as_deref(),
+            options.limit,
+        )
+        .await?;
+
+    let user_ids: Vec<String> = members
+        .iter()
+        .map(|member| member.id.user.clone())
+        .collect();
+
+    let mut users: HashMap<String, User> = db
+        .fetch_users(&user_ids)
+        .await?
+        .into_iter()
+        .map(|user| (user.id.clone(), user))
+        .collect();
+
+    // Ensure the lists match up exactly
+    let mut response = v0::AllMemberResponse {
+        members: Vec::with_capacity(members.len()),
+        users: Vec::with_capacity(members.len()),
+    };
+
+    let exclude_offline = options.exclude_offline.unwrap_or_default();
+    for member in members {
+        if let Some(found) = users.remove(&member.id.user) {
+            let found = found.into(db, &user).await;
 
-    // Ensure the lists match up exactly.
-    members.sort_by(|a, b| a.id.user.cmp(&b.id.user));
-    users.sort_by(|a, b| a.id.cmp(&b.id));
+            // Optionally, remove all offline user entries
+            if exclude_offline && !found.online {
+                continue;
+            }
 
-    // Optionally, remove all offline user entries.
-    if let Some(true) = options.exclude_offline {
-        let mut iter = users.iter();
-        members.retain(|_| iter.next().unwrap().online.unwrap_or(false));
-        users.retain(|user| user.online.unwrap_or(false));
+            response.users.push(found);
+            response.members.push(member.into());
+        }
     }
 
-    Ok(Json(AllMemberResponse { members, users }))
+    Ok(Json(response))
 }
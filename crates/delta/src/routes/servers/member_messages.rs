@@ -0,0 +1,197 @@
+use onechatsocial_config::config;
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, MessageFilter, MessageQuery, MessageTimePeriod, User,
+};
+use onechatsocial_models::v0::{self, MessageSort};
+use onechatsocial_permissions::{
+    calculate_channel_permissions, calculate_server_permissions, ChannelPermission,
+};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// # Query Parameters
+#[derive(Validate, Serialize, Deserialize, JsonSchema, FromForm)]
+pub struct OptionsFetchMemberMessages {
+    /// Maximum number of messages to fetch
+    #[validate(range(min = 1, max = 100))]
+    limit: Option<i64>,
+    /// Message id before which messages should be fetched
+    #[validate(length(min = 26, max = 26))]
+    before: Option<String>,
+    /// Message id after which messages should be fetched
+    #[validate(length(min = 26, max = 26))]
+    after: Option<String>,
+    /// Whether to include full reactions for messages with a large number of reactions
+    expand_reactions: Option<bool>,
+}
+
+/// # Fetch Member Messages
+///
+/// Fetch messages sent by a given user across all channels of a server that you can read.
+///
+/// Messages with a large number of reactions will only include reaction counts unless `expand_reactions` is set.
+///
+/// Requires `ManageMessages` on the server.
+#[openapi(tag = "Server Members")]
+#[get("/<target>/members/<member>/messages?<options..>")]
+pub async fn fetch_member_messages(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    member: Reference,
+    options: OptionsFetchMemberMessages,
+) -> Result<Json<Vec<v0::Message>>> {
+    options.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageMessages)?;
+
+    // Only search channels we are allowed to read
+    let channels = db.fetch_channels(&server.channels).await?;
+    let mut readable_channels = vec![];
+    for channel in &channels {
+        let mut query = query.clone().channel(channel);
+        if calculate_channel_permissions(&mut query)
+            .await
+            .has_channel_permission(ChannelPermission::ReadMessageHistory)
+        {
+            readable_channels.push(channel.id());
+        }
+    }
+
+    if readable_channels.is_empty() {
+        return Ok(Json(vec![]));
+    }
+
+    let OptionsFetchMemberMessages {
+        limit,
+        before,
+        after,
+        expand_reactions,
+    } = options;
+
+    let expand_reactions = expand_reactions.unwrap_or_default();
+    let threshold = config().await.features.reaction_summary_threshold;
+
+    Ok(Json(
+        db.fetch_messages(MessageQuery {
+            limit: Some(limit.unwrap_or(50)),
+            filter: MessageFilter {
+                channel: None,
+                channels: Some(readable_channels),
+                author: Some(member.id),
+                mentions: None,
+                query: None,
+                pinned: None,
+                has: None,
+            },
+            time_period: MessageTimePeriod::Absolute {
+                before,
+                after,
+                sort: Some(MessageSort::Latest),
+            },
+        })
+        .await?
+        .into_iter()
+        .map(|message| {
+            let mut message: v0::Message = message.into();
+            if !expand_reactions {
+                message.summarise_reactions(threshold);
+            }
+
+            message
+        })
+        .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use onechatsocial_database::{Member, Server};
+    use onechatsocial_models::v0;
+    use rocket::http::{ContentType, Header, Status};
+
+    #[rocket::async_test]
+    async fn fetch_member_messages() {
+        let harness = TestHarness::new().await;
+        let (_, owner_session, owner) = harness.new_user().await;
+        let (_, session, user) = harness.new_user().await;
+        let (_, other_session, other_user) = harness.new_user().await;
+
+        let (server, channels) = Server::create(
+            &harness.db,
+            v0::DataCreateServer {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            &owner,
+            true,
+        )
+        .await
+        .unwrap();
+
+        for user in [&user, &other_user] {
+            Member::create(&harness.db, &server, user, None)
+                .await
+                .unwrap();
+        }
+
+        let channel = channels[0].id();
+        for (content, token) in [
+            ("First", session.token.to_string()),
+            ("Unrelated", other_session.token.to_string()),
+            ("Second", session.token.to_string()),
+        ] {
+            let response = harness
+                .client
+                .post(format!("/channels/{channel}/messages"))
+                .header(ContentType::JSON)
+                .body(json!({ "content": content }).to_string())
+                .header(Header::new("x-session-token", token))
+                .dispatch()
+                .await;
+
+            assert_eq!(response.status(), Status::Ok);
+        }
+
+        let fetch = |token: String| {
+            harness
+                .client
+                .get(format!(
+                    "/servers/{}/members/{}/messages",
+                    server.id, user.id
+                ))
+                .header(Header::new("x-session-token", token))
+                .dispatch()
+        };
+
+        // Only the given member's messages are returned, newest first
+        let response = fetch(owner_session.token.to_string()).await;
+        assert_eq!(response.status(), Status::Ok);
+
+        let messages: Vec<v0::Message> = response.into_json().await.expect("`Vec<Message>`");
+        assert_eq!(
+            messages
+                .iter()
+                .map(|message| message.content.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("Second"), Some("First")]
+        );
+        assert!(messages.iter().all(|message| message.author == user.id));
+
+        // Members without ManageMessages may not look
+        let response = fetch(other_session.token.to_string()).await;
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+}
@@ -1,5 +1,6 @@
+use onechatsocial_database::AuditLogAction;
 use onechatsocial_quark::{
-    models::{server_member::RemovalIntention, User},
+    models::{server_member::RemovalIntention, Server, User},
     perms, Db, EmptyResponse, Error, Permission, Ref, Result,
 };
 
@@ -33,8 +34,20 @@ pub async fn req(db: &Db, user: User, target: Ref, member: Ref) -> Result<EmptyR
         return Err(Error::NotElevated);
     }
 
+    let member_id = member.id.user.clone();
     server
-        .remove_member(db, member, RemovalIntention::Kick, false)
-        .await
-        .map(|_| EmptyResponse)
+        .remove_member(db, member, RemovalIntention::Kick, Some(&user.id), false)
+        .await?;
+
+    Server::record_audit_log(
+        db,
+        &server.id,
+        &user.id,
+        AuditLogAction::MemberKick,
+        Some(&member_id),
+        vec![],
+    )
+    .await;
+
+    Ok(EmptyResponse)
 }
@@ -0,0 +1,103 @@
+use onechatsocial_quark::{
+    models::{
+        server::DEFAULT_ROLE_ID,
+        server_member::{FieldsMember, PartialMember, TimedRole},
+        Member, User,
+    },
+    perms, Db, Error, Permission, Ref, Result, Timestamp,
+};
+
+use rocket::serde::json::Json;
+use serde::Deserialize;
+
+/// # Role Assignment Data
+#[derive(Deserialize, JsonSchema)]
+pub struct DataAssignRole {
+    /// Time at which the role is removed again
+    ///
+    /// The role is kept until removed manually if not given.
+    expires_at: Option<Timestamp>,
+}
+
+/// # Assign Role
+///
+/// Assign a role to a member, optionally only until a given time.
+///
+/// Assigning a role the member already has replaces its expiry.
+#[openapi(tag = "Server Members")]
+#[put("/<server>/members/<target>/roles/<role_id>", data = "<data>")]
+pub async fn assign_role(
+    db: &Db,
+    user: User,
+    server: Ref,
+    target: Ref,
+    role_id: String,
+    data: Json<DataAssignRole>,
+) -> Result<Json<Member>> {
+    let data = data.into_inner();
+
+    let server = server.as_server(db).await?;
+    let mut member = target.as_member(db, &server.id).await?;
+    let mut permissions = perms(&user).server(&server);
+
+    permissions
+        .throw_permission(db, Permission::AssignRoles)
+        .await?;
+
+    let our_ranking = permissions.get_member_rank().unwrap_or(i64::MIN);
+
+    // Check that we have permissions to act against this member
+    if member.id.user != user.id && member.get_ranking(&server) <= our_ranking {
+        return Err(Error::NotElevated);
+    }
+
+    // Every member already has the default role
+    if role_id == DEFAULT_ROLE_ID {
+        return Err(Error::InvalidRole);
+    }
+
+    // Roles can only be granted if they rank below our own
+    let role = server.roles.get(&role_id).ok_or(Error::InvalidRole)?;
+    if role.rank <= our_ranking {
+        return Err(Error::NotElevated);
+    }
+
+    if let Some(expires_at) = data.expires_at {
+        if *expires_at <= *Timestamp::now_utc() {
+            return Err(Error::InvalidProperty);
+        }
+    }
+
+    let mut timed_roles: Vec<TimedRole> = member
+        .timed_roles
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|timed_role| timed_role.role != role_id)
+        .collect();
+
+    if let Some(expires_at) = data.expires_at {
+        timed_roles.push(TimedRole {
+            role: role_id.clone(),
+            expires_at,
+        });
+    }
+
+    let mut partial = PartialMember::default();
+    let mut remove = vec![];
+
+    if !member.roles.contains(&role_id) {
+        let mut roles = member.roles.clone();
+        roles.push(role_id);
+        partial.roles = Some(roles);
+    }
+
+    if !timed_roles.is_empty() {
+        partial.timed_roles = Some(timed_roles);
+    } else if member.timed_roles.is_some() {
+        remove.push(FieldsMember::TimedRoles);
+    }
+
+    member.update(db, partial, remove).await?;
+    Ok(Json(member))
+}
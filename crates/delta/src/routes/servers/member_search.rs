@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use onechatsocial_database::{util::reference::Reference, Database, User};
+use onechatsocial_models::v0;
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// # Query Parameters
+#[derive(Validate, Serialize, Deserialize, JsonSchema, FromForm)]
+pub struct OptionsSearchMembers {
+    /// Username or nickname prefix to search for
+    #[validate(length(min = 1, max = 32))]
+    q: String,
+
+    /// Maximum number of members to return
+    #[validate(range(min = 1, max = 100))]
+    limit: Option<usize>,
+}
+
+/// # Search Members
+///
+/// Search for members whose username or nickname starts with a given prefix.
+///
+/// Members matched by nickname are listed first, users are in the same order as members.
+#[openapi(tag = "Server Members")]
+#[get("/<target>/members/search?<options..>", rank = 1)]
+pub async fn search_members(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    options: OptionsSearchMembers,
+) -> Result<Json<v0::AllMemberResponse>> {
+    options.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let server = target.as_server(db).await?;
+
+    // Only members may search the member list
+    if db.fetch_member(&server.id, &user.id).await.is_err() {
+        return Err(create_error!(NotFound));
+    }
+
+    let members = db
+        .search_members(&server.id, &options.q, options.limit.unwrap_or(10))
+        .await?;
+
+    let user_ids: Vec<String> = members
+        .iter()
+        .map(|member| member.id.user.clone())
+        .collect();
+
+    let mut users: HashMap<String, User> = db
+        .fetch_users(&user_ids)
+        .await?
+        .into_iter()
+        .map(|user| (user.id.clone(), user))
+        .collect();
+
+    // Ensure the lists match up exactly
+    let mut response = v0::AllMemberResponse {
+        members: Vec::with_capacity(members.len()),
+        users: Vec::with_capacity(members.len()),
+    };
+
+    for member in members {
+        if let Some(found) = users.remove(&member.id.user) {
+            response.users.push(found.into(db, &user).await);
+            response.members.push(member.into());
+        }
+    }
+
+    Ok(Json(response))
+}
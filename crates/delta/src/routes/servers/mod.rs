@@ -1,52 +1,92 @@
 use revolt_rocket_okapi::revolt_okapi::openapi3::OpenApi;
 use rocket::Route;
 
+mod audit_log_fetch;
 mod ban_create;
 mod ban_list;
 mod ban_remove;
+mod category_permissions_set;
+mod category_permissions_set_default;
 mod channel_create;
+mod emoji_bulk_delete;
+mod emoji_export;
+mod emoji_import;
+mod emoji_leaderboard;
 mod emoji_list;
+mod event_webhook_delete;
+mod event_webhook_fetch;
+mod event_webhook_set;
 mod invites_fetch;
 mod member_edit;
 mod member_experimental_query;
 mod member_fetch;
 mod member_fetch_all;
+mod member_messages;
 mod member_remove;
+mod member_role_assign;
+mod member_search;
+mod name_filter_fetch;
+mod name_filter_set;
+mod permissions_fetch;
 mod permissions_set;
 mod permissions_set_default;
 mod roles_create;
 mod roles_delete;
 mod roles_edit;
+mod roles_export;
 mod roles_fetch;
+mod roles_import;
 mod server_ack;
 mod server_create;
+mod server_create_from_template;
 mod server_delete;
 mod server_edit;
 mod server_fetch;
+mod template_create;
 
 pub fn routes() -> (Vec<Route>, OpenApi) {
     openapi_get_routes_spec![
         server_create::create_server,
+        server_create_from_template::create_server_from_template,
+        template_create::create_template,
         server_delete::req,
         server_fetch::req,
         server_edit::req,
         server_ack::req,
         channel_create::create_server_channel,
-        member_fetch_all::req,
+        member_fetch_all::fetch_all_members,
         member_remove::req,
         member_fetch::req,
         member_edit::req,
         member_experimental_query::member_experimental_query,
+        member_messages::fetch_member_messages,
+        member_search::search_members,
+        member_role_assign::assign_role,
         ban_create::req,
         ban_remove::req,
         ban_list::req,
+        audit_log_fetch::fetch_audit_log,
         invites_fetch::req,
         roles_create::req,
         roles_edit::req,
         roles_fetch::req,
         roles_delete::req,
+        permissions_fetch::fetch_effective_permissions,
         permissions_set::req,
         permissions_set_default::req,
-        emoji_list::list_emoji
+        category_permissions_set::set_category_role_permission,
+        category_permissions_set_default::set_category_default_permission,
+        emoji_list::list_emoji,
+        emoji_leaderboard::emoji_leaderboard,
+        name_filter_fetch::fetch_name_filter,
+        name_filter_set::set_name_filter,
+        event_webhook_fetch::fetch_event_webhook,
+        event_webhook_set::set_event_webhook,
+        event_webhook_delete::delete_event_webhook,
+        roles_export::export_roles,
+        roles_import::import_roles,
+        emoji_export::export_emoji,
+        emoji_import::import_emoji,
+        emoji_bulk_delete::bulk_delete_emoji
     ]
 }
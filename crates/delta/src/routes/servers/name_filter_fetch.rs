@@ -0,0 +1,34 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::{Error, ErrorType, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Name Filter
+///
+/// Fetch the words which may not be used in channel names, role names or nicknames.
+#[openapi(tag = "Server Information")]
+#[get("/<target>/name_filter")]
+pub async fn fetch_name_filter(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<v0::ServerNameFilter>> {
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    match db.fetch_server_name_filter(&server.id).await {
+        Ok(filter) => Ok(Json(filter.into())),
+        Err(Error {
+            error_type: ErrorType::NotFound,
+            ..
+        }) => Ok(Json(v0::ServerNameFilter { words: vec![] })),
+        Err(error) => Err(error),
+    }
+}
@@ -0,0 +1,60 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, ServerNameFilter, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Set Name Filter
+///
+/// Replace the words which may not be used in channel names, role names or nicknames.
+///
+/// Words are matched case-insensitively, ignoring diacritics, common
+/// character substitutions and separators.
+#[openapi(tag = "Server Information")]
+#[put("/<target>/name_filter", data = "<data>")]
+pub async fn set_name_filter(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataEditNameFilter>,
+) -> Result<Json<v0::ServerNameFilter>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    let mut words: Vec<String> = data
+        .words
+        .into_iter()
+        .map(|word| word.trim().to_string())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    words.sort();
+    words.dedup();
+
+    let filter = ServerNameFilter {
+        id: server.id,
+        words,
+    };
+
+    if filter.words.is_empty() {
+        db.delete_server_name_filter(&filter.id).await?;
+    } else {
+        db.upsert_server_name_filter(&filter).await?;
+    }
+
+    Ok(Json(filter.into()))
+}
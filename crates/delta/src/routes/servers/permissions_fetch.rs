@@ -0,0 +1,106 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Fetch Effective Permissions
+///
+/// Fetch the permissions a user has across this server, after applying the
+/// server's default role and their roles.
+///
+/// Use `@me` to fetch your own permissions, fetching another user's permissions
+/// requires the `ManagePermissions` permission.
+#[openapi(tag = "Server Permissions")]
+#[get("/<target>/permissions/<member>")]
+pub async fn fetch_effective_permissions(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    member: Reference,
+) -> Result<Json<v0::EffectivePermissions>> {
+    let server = target.as_server(db).await?;
+
+    // Only members may look up permissions in a server
+    if db.fetch_member(&server.id, &user.id).await.is_err() {
+        return Err(create_error!(NotFound));
+    }
+
+    let subject = if member.id == "@me" || member.id == user.id {
+        None
+    } else {
+        Some(member.as_user(db).await?)
+    };
+
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    let mut permissions = calculate_server_permissions(&mut query).await;
+
+    if let Some(subject) = &subject {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::ManagePermissions)?;
+
+        let mut query = DatabasePermissionQuery::new(db, subject).server(&server);
+        permissions = calculate_server_permissions(&mut query).await;
+    }
+
+    Ok(Json(v0::EffectivePermissions {
+        granted: permissions
+            .granted_channel_permissions()
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+        permissions: permissions.into(),
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use onechatsocial_database::{Member, Server};
+    use onechatsocial_models::v0::{self, DataCreateServer};
+    use rocket::http::{Header, Status};
+
+    #[rocket::async_test]
+    async fn only_members_may_fetch() {
+        let harness = TestHarness::new().await;
+        let (_, _, user) = harness.new_user().await;
+        let (_, member_session, member) = harness.new_user().await;
+        let (_, outsider_session, _) = harness.new_user().await;
+
+        let (server, _) = Server::create(
+            &harness.db,
+            DataCreateServer {
+                name: TestHarness::rand_string(),
+                ..Default::default()
+            },
+            &user,
+            true,
+        )
+        .await
+        .unwrap();
+
+        Member::create(&harness.db, &server, &member, None)
+            .await
+            .unwrap();
+
+        let fetch = |token: String| {
+            harness
+                .client
+                .get(format!("/servers/{}/permissions/@me", server.id))
+                .header(Header::new("x-session-token", token))
+                .dispatch()
+        };
+
+        let response = fetch(member_session.token.to_string()).await;
+        assert_eq!(response.status(), Status::Ok);
+
+        let permissions: v0::EffectivePermissions =
+            response.into_json().await.expect("`EffectivePermissions`");
+        assert!(permissions.granted.contains(&"SendMessage".to_string()));
+
+        let response = fetch(outsider_session.token.to_string()).await;
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
@@ -1,9 +1,10 @@
 use rocket::serde::json::Json;
 use serde::Deserialize;
 
+use onechatsocial_database::{AuditLogAction, AuditLogChange};
 use onechatsocial_quark::{
     models::{Server, User},
-    perms, Db, Error, Override, Permission, Ref, Result,
+    perms, Db, Error, Override, OverrideField, Permission, Ref, Result,
 };
 
 /// # Permission Value
@@ -40,14 +41,24 @@ pub async fn req(
             return Err(Error::NotElevated);
         }
 
+        let before = current_value;
         let current_value: Override = current_value.into();
         permissions
             .throw_permission_override(db, current_value, data.permissions)
             .await?;
 
-        server
-            .set_role_permission(db, &role_id, data.permissions.into())
-            .await?;
+        let after: OverrideField = data.permissions.into();
+        server.set_role_permission(db, &role_id, after).await?;
+
+        Server::record_audit_log(
+            db,
+            &server.id,
+            &user.id,
+            AuditLogAction::RolePermissionsUpdate,
+            Some(&role_id),
+            vec![AuditLogChange::new("permissions", &before, &after)],
+        )
+        .await;
 
         Ok(Json(server))
     } else {
@@ -1,7 +1,11 @@
+use onechatsocial_database::{AuditLogAction, AuditLogChange};
 use onechatsocial_permissions::DataPermissionsValue;
 use onechatsocial_quark::{
-    models::{server::PartialServer, Server, User},
-    perms, Db, Permission, Ref, Result,
+    models::{
+        server::{PartialServer, DEFAULT_ROLE_ID},
+        Server, User,
+    },
+    perms, Db, Override, Permission, Ref, Result,
 };
 use rocket::serde::json::Json;
 /// # Set Default Permission
@@ -28,6 +32,7 @@ pub async fn req(
         .throw_permission_value(db, data.permissions)
         .await?;
 
+    let before = server.base_permissions();
     server
         .update(
             db,
@@ -39,5 +44,29 @@ pub async fn req(
         )
         .await?;
 
+    if server.roles.contains_key(DEFAULT_ROLE_ID) {
+        server
+            .set_role_permission(
+                db,
+                DEFAULT_ROLE_ID,
+                Override::allow_only(data.permissions).into(),
+            )
+            .await?;
+    }
+
+    Server::record_audit_log(
+        db,
+        &server.id,
+        &user.id,
+        AuditLogAction::DefaultPermissionsUpdate,
+        None,
+        vec![AuditLogChange::new(
+            "default_permissions",
+            &before,
+            &data.permissions,
+        )],
+    )
+    .await;
+
     Ok(Json(server))
 }
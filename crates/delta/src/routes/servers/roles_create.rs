@@ -1,9 +1,13 @@
+use onechatsocial_database::{AuditLogAction, AuditLogChange, ServerNameFilter};
 use onechatsocial_quark::{
-    models::{server::Role, User},
+    models::{
+        server::{Role, DEFAULT_ROLE_RANK},
+        Server, User,
+    },
     perms, Db, Error, Permission, Ref, Result, variables::delta::MAX_ROLE_COUNT,
 };
 
-use rocket::serde::json::Json;
+use rocket::{serde::json::Json, State};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
@@ -13,6 +17,9 @@ pub struct DataCreateRole {
     /// Role name
     #[validate(length(min = 1, max = 32))]
     name: String,
+    /// Role colour
+    #[validate(length(min = 1, max = 128))]
+    colour: Option<String>,
     /// Ranking position
     ///
     /// Smaller values take priority.
@@ -35,6 +42,7 @@ pub struct NewRoleResponse {
 #[post("/<target>/roles", data = "<data>")]
 pub async fn req(
     db: &Db,
+    core_db: &State<onechatsocial_database::Database>,
     user: User,
     target: Ref,
     data: Json<DataCreateRole>,
@@ -50,29 +58,56 @@ pub async fn req(
         .throw_permission(db, Permission::ManageRole)
         .await?;
 
-    if server.roles.len() > *MAX_ROLE_COUNT {
+    if server.role_count() > *MAX_ROLE_COUNT {
         return Err(Error::TooManyRoles { max: *MAX_ROLE_COUNT })
     };
 
+    ServerNameFilter::validate_name(core_db, &server.id, &data.name)
+        .await
+        .map_err(Error::from_core)?;
+
+    if let Some(colour) = &data.colour {
+        onechatsocial_database::Role::validate_colour(colour)
+            .await
+            .map_err(|_| Error::InvalidProperty)?;
+    }
+
     let member_rank = permissions.get_member_rank();
     let rank = if let Some(given_rank) = data.rank {
         if given_rank <= member_rank.unwrap_or(i64::MIN) {
             return Err(Error::NotElevated);
         }
 
+        // The default role always ranks the lowest
+        if given_rank == DEFAULT_ROLE_RANK {
+            return Err(Error::InvalidProperty);
+        }
+
         given_rank
     } else {
-        member_rank.unwrap_or(0).saturating_add(1)
+        member_rank
+            .unwrap_or(0)
+            .saturating_add(1)
+            .min(DEFAULT_ROLE_RANK - 1)
     };
 
     let role = Role {
         name: data.name,
+        colour: data.colour,
         rank,
         ..Default::default()
     };
 
-    Ok(Json(NewRoleResponse {
-        id: role.create(db, &server.id).await?,
-        role,
-    }))
+    let id = role.create(db, &server.id).await?;
+    Server::record_audit_log(
+        db,
+        &server.id,
+        &user.id,
+        AuditLogAction::RoleCreate,
+        Some(&id),
+        AuditLogChange::diff(&Role::default(), &role),
+    )
+    .await;
+
+    Ok(Json(NewRoleResponse { id, role }))
 }
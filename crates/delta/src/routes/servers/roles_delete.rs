@@ -1,11 +1,24 @@
-use onechatsocial_quark::{models::User, perms, Db, EmptyResponse, Error, Permission, Ref, Result};
+use onechatsocial_database::{AuditLogAction, AuditLogChange};
+use onechatsocial_quark::{
+    models::{
+        server::{Role, DEFAULT_ROLE_ID},
+        Server, User,
+    },
+    perms, Db, EmptyResponse, Error, Permission, Ref, Result,
+};
 
 /// # Delete Role
 ///
 /// Delete a server role by its id.
+///
+/// The default role cannot be deleted.
 #[openapi(tag = "Server Permissions")]
 #[delete("/<target>/roles/<role_id>")]
 pub async fn req(db: &Db, user: User, target: Ref, role_id: String) -> Result<EmptyResponse> {
+    if role_id == DEFAULT_ROLE_ID {
+        return Err(Error::InvalidOperation);
+    }
+
     let mut server = target.as_server(db).await?;
     let mut permissions = perms(&user).server(&server);
 
@@ -20,9 +33,20 @@ pub async fn req(db: &Db, user: User, target: Ref, role_id: String) -> Result<Em
             return Err(Error::NotElevated);
         }
 
-        role.delete(db, &server.id, &role_id)
-            .await
-            .map(|_| EmptyResponse)
+        let changes = AuditLogChange::diff(&role, &Role::default());
+        role.delete(db, &server.id, &role_id).await?;
+
+        Server::record_audit_log(
+            db,
+            &server.id,
+            &user.id,
+            AuditLogAction::RoleDelete,
+            Some(&role_id),
+            changes,
+        )
+        .await;
+
+        Ok(EmptyResponse)
     } else {
         Err(Error::NotFound)
     }
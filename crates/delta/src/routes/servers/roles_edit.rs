@@ -1,14 +1,14 @@
+use onechatsocial_config::config;
+use onechatsocial_database::{AuditLogAction, AuditLogChange, ServerNameFilter};
 use onechatsocial_quark::{
     models::{
-        server::{FieldsRole, PartialRole, Role},
-        User,
+        server::{FieldsRole, PartialRole, Role, DEFAULT_ROLE_ID, DEFAULT_ROLE_RANK},
+        File, Server, User,
     },
-    perms,
-    util::regex::RE_COLOUR,
-    Db, Error, Permission, Ref, Result,
+    perms, Db, Error, Permission, Ref, Result,
 };
 
-use rocket::serde::json::Json;
+use rocket::{serde::json::Json, State};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
@@ -19,8 +19,10 @@ pub struct DataEditRole {
     #[validate(length(min = 1, max = 32))]
     name: Option<String>,
     /// Role colour
-    #[validate(length(min = 1, max = 128), regex = "RE_COLOUR")]
+    #[validate(length(min = 1, max = 128))]
     colour: Option<String>,
+    /// Attachment Id for icon
+    icon: Option<String>,
     /// Whether this role should be displayed separately
     hoist: Option<bool>,
     /// Ranking position
@@ -39,6 +41,7 @@ pub struct DataEditRole {
 #[patch("/<target>/roles/<role_id>", data = "<data>")]
 pub async fn req(
     db: &Db,
+    core_db: &State<onechatsocial_database::Database>,
     user: User,
     target: Ref,
     role_id: String,
@@ -61,18 +64,40 @@ pub async fn req(
         let DataEditRole {
             name,
             colour,
+            icon,
             hoist,
             rank,
             remove,
         } = data;
 
         if let Some(rank) = &rank {
+            // The default role always ranks the lowest
+            if role_id == DEFAULT_ROLE_ID {
+                return Err(Error::InvalidOperation);
+            }
+
+            if *rank == DEFAULT_ROLE_RANK {
+                return Err(Error::InvalidProperty);
+            }
+
             if rank <= &member_rank {
                 return Err(Error::NotElevated);
             }
         }
 
-        let partial = PartialRole {
+        if let Some(name) = &name {
+            ServerNameFilter::validate_name(core_db, &server.id, name)
+                .await
+                .map_err(Error::from_core)?;
+        }
+
+        if let Some(colour) = &colour {
+            onechatsocial_database::Role::validate_colour(colour)
+                .await
+                .map_err(|_| Error::InvalidProperty)?;
+        }
+
+        let mut partial = PartialRole {
             name,
             colour,
             hoist,
@@ -80,6 +105,34 @@ pub async fn req(
             ..Default::default()
         };
 
+        if let Some(icon) = icon {
+            let file = File::use_role_icon(db, &icon, &role_id).await?;
+
+            let config = config().await;
+            let limits = config
+                .features
+                .server_limits(server.flags.unwrap_or_default() as u32);
+
+            if file.size as usize > limits.role_icon_size {
+                db.mark_attachment_as_deleted(&file.id).await?;
+                return Err(Error::PayloadTooLarge);
+            }
+
+            partial.icon = Some(file);
+        }
+
+        // Release the previous icon once it is removed or replaced
+        if partial.icon.is_some()
+            || remove
+                .as_ref()
+                .is_some_and(|fields| fields.contains(&FieldsRole::Icon))
+        {
+            if let Some(icon) = &role.icon {
+                db.mark_attachment_as_deleted(&icon.id).await?;
+            }
+        }
+
+        let before = role.clone();
         role.update(
             db,
             &server.id,
@@ -89,6 +142,16 @@ pub async fn req(
         )
         .await?;
 
+        Server::record_audit_log(
+            db,
+            &server.id,
+            &user.id,
+            AuditLogAction::RoleUpdate,
+            Some(&role_id),
+            AuditLogChange::diff(&before, &role),
+        )
+        .await;
+
         Ok(Json(role))
     } else {
         Err(Error::NotFound)
@@ -0,0 +1,41 @@
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, User,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::Result;
+use rocket::{serde::json::Json, State};
+
+/// # Export Roles
+///
+/// Export role definitions so they can be imported into another server.
+#[openapi(tag = "Server Permissions")]
+#[get("/<target>/export/roles")]
+pub async fn export_roles(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<Vec<v0::RoleDefinition>>> {
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    calculate_server_permissions(&mut query)
+        .await
+        .throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    let mut roles: Vec<v0::RoleDefinition> = server
+        .roles
+        .into_iter()
+        .map(|(id, role)| v0::RoleDefinition {
+            id,
+            name: role.name,
+            permissions: role.permissions,
+            colour: role.colour,
+            hoist: role.hoist,
+            rank: role.rank,
+        })
+        .collect();
+
+    roles.sort_by_key(|role| role.rank);
+    Ok(Json(roles))
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use onechatsocial_config::config;
+use onechatsocial_database::{
+    util::{permissions::DatabasePermissionQuery, reference::Reference},
+    Database, Role, ServerNameFilter, User, DEFAULT_ROLE_RANK,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{calculate_server_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use validator::Validate;
+
+/// # Import Roles
+///
+/// Create roles from definitions exported from another server.
+///
+/// Roles whose name is already in use are not created and are reported as collisions.
+#[openapi(tag = "Server Permissions")]
+#[post("/<target>/import/roles", data = "<data>")]
+pub async fn import_roles(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+    data: Json<v0::DataImportRoles>,
+) -> Result<Json<v0::ImportResponse>> {
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    for role in &data.roles {
+        role.validate().map_err(|error| {
+            create_error!(FailedValidation {
+                error: error.to_string()
+            })
+        })?;
+    }
+
+    let server = target.as_server(db).await?;
+    let mut query = DatabasePermissionQuery::new(db, &user).server(&server);
+    let permissions = calculate_server_permissions(&mut query).await;
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageServer)?;
+
+    // Imported roles may not grant anything we don't have ourselves
+    if data
+        .roles
+        .iter()
+        .any(|role| !permissions.has(role.permissions.a as u64))
+    {
+        return Err(create_error!(NotElevated));
+    }
+
+    // Imported roles are always placed below our own ranking
+    let member_rank = if server.owner == user.id {
+        i64::MIN
+    } else {
+        db.fetch_member(&server.id, &user.id)
+            .await?
+            .get_ranking(&server)
+    };
+
+    let mut ids = HashMap::new();
+    let mut collisions = vec![];
+    let mut definitions = vec![];
+    for definition in data.roles {
+        if let Some((id, _)) = server
+            .roles
+            .iter()
+            .find(|(_, role)| role.name.to_lowercase() == definition.name.to_lowercase())
+        {
+            ids.insert(definition.id.clone(), id.clone());
+            collisions.push(definition.id);
+        } else {
+            ServerNameFilter::validate_name(db, &server.id, &definition.name).await?;
+            if let Some(colour) = &definition.colour {
+                Role::validate_colour(colour).await?;
+            }
+
+            definitions.push(definition);
+        }
+    }
+
+    let config = config().await;
+    let limits = config
+        .features
+        .server_limits(server.flags.unwrap_or_default() as u32);
+
+    if server.role_count() + definitions.len() > limits.server_roles {
+        return Err(create_error!(TooManyRoles {
+            max: limits.server_roles,
+        }));
+    }
+
+    for definition in definitions {
+        let role = Role {
+            name: definition.name,
+            permissions: definition.permissions,
+            colour: definition.colour,
+            hoist: definition.hoist,
+            rank: definition
+                .rank
+                .max(member_rank.saturating_add(1))
+                .min(DEFAULT_ROLE_RANK - 1),
+            icon: None,
+        };
+
+        ids.insert(definition.id, role.create(db, &server.id).await?);
+    }
+
+    Ok(Json(v0::ImportResponse { ids, collisions }))
+}
@@ -0,0 +1,42 @@
+use onechatsocial_database::{Database, Member, User};
+use onechatsocial_models::v0;
+use onechatsocial_result::{create_error, Result};
+
+use rocket::serde::json::Json;
+use rocket::State;
+use validator::Validate;
+
+/// # Create Server From Template
+///
+/// Create a new server with the channels, categories, roles and
+/// default permissions of a template.
+#[openapi(tag = "Server Information")]
+#[post("/create-from-template/<code>", data = "<data>", rank = 1)]
+pub async fn create_server_from_template(
+    db: &State<Database>,
+    user: User,
+    code: String,
+    data: Json<v0::DataCreateServer>,
+) -> Result<Json<v0::CreateServerLegacyResponse>> {
+    if user.bot.is_some() {
+        return Err(create_error!(IsBot));
+    }
+
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    user.can_acquire_server(db).await?;
+
+    let template = db.fetch_server_template(&code).await?;
+    let (server, channels) = template.instantiate(db, data, &user).await?;
+    let channels = Member::create(db, &server, &user, Some(channels)).await?;
+
+    Ok(Json(v0::CreateServerLegacyResponse {
+        server: server.into(),
+        channels: channels.into_iter().map(|channel| channel.into()).collect(),
+    }))
+}
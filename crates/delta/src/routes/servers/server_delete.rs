@@ -34,6 +34,7 @@ pub async fn req(
                 db,
                 member,
                 RemovalIntention::Leave,
+                None,
                 options.leave_silently.unwrap_or_default(),
             )
             .await
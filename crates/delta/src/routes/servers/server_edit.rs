@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use onechatsocial_config::config;
 use onechatsocial_quark::{
     models::{
         server::{Category, FieldsServer, PartialServer, SystemMessageChannels},
@@ -45,6 +46,9 @@ pub struct DataEditServer {
     ///
     /// Must be enabled in order to show up on [Revolt Discover](https://rvlt.gg).
     analytics: Option<bool>,
+    /// Default colour of text embeds which do not specify one
+    #[validate(length(min = 1, max = 128))]
+    embed_colour: Option<String>,
 
     /// Fields to remove from server object
     #[validate(length(min = 1))]
@@ -81,6 +85,7 @@ pub async fn req(
         && data.flags.is_none()
         && data.analytics.is_none()
         && data.discoverable.is_none()
+        && data.embed_colour.is_none()
         && data.remove.is_none()
     {
         return Ok(Json(server));
@@ -90,6 +95,7 @@ pub async fn req(
         || data.banner.is_some()
         || data.system_messages.is_some()
         || data.analytics.is_some()
+        || data.embed_colour.is_some()
         || data.remove.is_some()
     {
         permissions
@@ -122,9 +128,15 @@ pub async fn req(
         // nsfw,
         discoverable,
         analytics,
+        embed_colour,
         remove,
     } = data;
 
+    if let Some(colour) = &embed_colour {
+        onechatsocial_database::Message::validate_embed_colour(colour)
+            .map_err(|_| Error::InvalidProperty)?;
+    }
+
     let mut partial = PartialServer {
         name,
         description,
@@ -134,6 +146,7 @@ pub async fn req(
         // nsfw,
         discoverable,
         analytics,
+        embed_colour,
         ..Default::default()
     };
 
@@ -162,6 +175,17 @@ pub async fn req(
     }
 
     if let Some(categories) = &mut partial.categories {
+        let config = config().await;
+        let limits = config
+            .features
+            .server_limits(server.flags.unwrap_or_default() as u32);
+
+        if categories.len() > limits.server_categories {
+            return Err(Error::TooManyCategories {
+                max: limits.server_categories,
+            });
+        }
+
         let mut channel_ids = HashSet::new();
         for category in categories {
             for channel in &category.channels {
@@ -175,6 +199,17 @@ pub async fn req(
             category
                 .channels
                 .retain(|item| server.channels.contains(item));
+
+            // Permission overrides can only be changed through their own routes
+            let existing = server
+                .categories
+                .as_ref()
+                .and_then(|existing| existing.iter().find(|c| c.id == category.id));
+
+            category.default_permissions = existing.and_then(|c| c.default_permissions);
+            category.role_permissions = existing
+                .map(|c| c.role_permissions.clone())
+                .unwrap_or_default();
         }
     }
 
@@ -1,3 +1,5 @@
+use onechatsocial_config::config;
+use onechatsocial_models::v0::{ServerCounts, ServerLimits};
 use onechatsocial_quark::{
     models::{Channel, Server, User},
     perms, Db, Ref, Result,
@@ -10,18 +12,28 @@ use serde::{Deserialize, Serialize};
 pub struct OptionsFetchServer {
     /// Whether to include channels
     include_channels: Option<bool>,
+    /// Whether to include member counts for the server and each role
+    include_counts: Option<bool>,
 }
 
 /// # Fetch server route response
 #[derive(Serialize, JsonSchema)]
-#[serde(untagged)]
-pub enum FetchServerResponse {
-    JustServer(Server),
-    ServerWithChannels {
-        #[serde(flatten)]
-        server: Server,
-        channels: Vec<Channel>,
-    },
+pub struct FetchServerResponse {
+    #[serde(flatten)]
+    server: Server,
+    /// Channels visible to the user, if requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channels: Option<Vec<Channel>>,
+    /// Effective limits for this server
+    limits: ServerLimits,
+    /// Current usage counted against the limits
+    counts: ServerCounts,
+    /// Total number of members, if requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_members: Option<u64>,
+    /// Number of members currently online, if requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    online_members: Option<u64>,
 }
 
 /// # Fetch Server
@@ -35,11 +47,26 @@ pub async fn req(
     target: Ref,
     options: OptionsFetchServer,
 ) -> Result<Json<FetchServerResponse>> {
-    let server = target.as_server(db).await?;
+    let mut server = target.as_server(db).await?;
     let mut perms = perms(&user).server(&server);
     perms.calc(db).await?;
 
-    if let Some(true) = options.include_channels {
+    let config = config().await;
+    let limits = config
+        .features
+        .server_limits(server.flags.unwrap_or_default() as u32)
+        .into();
+
+    let counts = ServerCounts {
+        channels: server.channels.len(),
+        categories: server
+            .categories
+            .as_ref()
+            .map_or(0, |categories| categories.len()),
+        roles: server.role_count(),
+    };
+
+    let channels = if let Some(true) = options.include_channels {
         let all_channels = db.fetch_channels(&server.channels).await?;
         let mut visible_channels = vec![];
 
@@ -55,11 +82,27 @@ pub async fn req(
             }
         }
 
-        Ok(Json(FetchServerResponse::ServerWithChannels {
-            server,
-            channels: visible_channels,
-        }))
+        Some(visible_channels)
     } else {
-        Ok(Json(FetchServerResponse::JustServer(server)))
-    }
+        None
+    };
+
+    let (total_members, online_members) = if let Some(true) = options.include_counts {
+        server.count_role_members(db).await?;
+        (
+            Some(db.fetch_member_count(&server.id).await? as u64),
+            Some(server.count_online_members(db).await?),
+        )
+    } else {
+        (None, None)
+    };
+
+    Ok(Json(FetchServerResponse {
+        server,
+        channels,
+        limits,
+        counts,
+        total_members,
+        online_members,
+    }))
 }
@@ -0,0 +1,27 @@
+use onechatsocial_database::{util::reference::Reference, Database, ServerTemplate, User};
+use onechatsocial_models::v0;
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+/// # Create Server Template
+///
+/// Take a snapshot of this server's channels, categories, roles and
+/// default permissions which new servers can be created from.
+///
+/// Only the server owner may create templates.
+#[openapi(tag = "Server Information")]
+#[post("/<target>/template")]
+pub async fn create_template(
+    db: &State<Database>,
+    user: User,
+    target: Reference,
+) -> Result<Json<v0::ServerTemplate>> {
+    let server = target.as_server(db).await?;
+    if server.owner != user.id {
+        return Err(create_error!(NotOwner));
+    }
+
+    ServerTemplate::create(db, &server, &user.id)
+        .await
+        .map(|template| Json(template.into()))
+}
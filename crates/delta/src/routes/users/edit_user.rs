@@ -55,6 +55,10 @@ pub struct DataEditUser {
     #[serde(skip_serializing_if = "Option::is_none")]
     flags: Option<i32>,
 
+    /// Whether to hide your read position from other users
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hide_read_receipts: Option<bool>,
+
     /// Fields to remove from user object
     #[validate(length(min = 1))]
     remove: Option<Vec<FieldsUser>>,
@@ -101,6 +105,7 @@ pub async fn req(
         && data.avatar.is_none()
         && data.badges.is_none()
         && data.flags.is_none()
+        && data.hide_read_receipts.is_none()
         && data.remove.is_none()
     {
         return Ok(Json(user));
@@ -131,6 +136,7 @@ pub async fn req(
         display_name: data.display_name,
         badges: data.badges,
         flags: data.flags,
+        hide_read_receipts: data.hide_read_receipts,
         ..Default::default()
     };
 
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use onechatsocial_config::config;
+use onechatsocial_database::{
+    util::permissions::DatabasePermissionQuery, Channel, Database, MessageFilter, MessageQuery,
+    MessageTimePeriod, User,
+};
+use onechatsocial_models::v0::{self, MessageSort};
+use onechatsocial_permissions::{calculate_channel_permissions, ChannelPermission};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Maximum number of pages of mentions to look through for readable messages
+const MAX_PAGES: usize = 5;
+
+/// # Query Parameters
+#[derive(Validate, Serialize, Deserialize, JsonSchema, FromForm)]
+pub struct OptionsFetchMentions {
+    /// Only include mentions from this server
+    #[validate(length(min = 26, max = 26))]
+    server: Option<String>,
+    /// Maximum number of messages to fetch
+    #[validate(range(min = 1, max = 100))]
+    limit: Option<i64>,
+    /// Message id before which messages should be fetched
+    #[validate(length(min = 26, max = 26))]
+    before: Option<String>,
+    /// Whether to include full reactions for messages with a large number of reactions
+    expand_reactions: Option<bool>,
+}
+
+/// # Fetch Mentions
+///
+/// Fetch recent messages mentioning you across all channels you can read, newest first.
+///
+/// Messages with a large number of reactions will only include reaction counts unless `expand_reactions` is set.
+#[openapi(tag = "User Information")]
+#[get("/@me/mentions?<options..>")]
+pub async fn fetch_mentions(
+    db: &State<Database>,
+    user: User,
+    options: OptionsFetchMentions,
+) -> Result<Json<Vec<v0::Message>>> {
+    options.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let OptionsFetchMentions {
+        server,
+        limit,
+        before,
+        expand_reactions,
+    } = options;
+
+    // Collect every channel the mentions could be in
+    let channels = if let Some(server) = server {
+        // Only members may look for mentions in a server
+        if db.fetch_member(&server, &user.id).await.is_err() {
+            return Err(create_error!(NotFound));
+        }
+
+        let server = db.fetch_server(&server).await?;
+        db.fetch_channels(&server.channels).await?
+    } else {
+        let server_ids: Vec<String> = db
+            .fetch_all_memberships(&user.id)
+            .await?
+            .into_iter()
+            .map(|member| member.id.server)
+            .collect();
+
+        let channel_ids: Vec<String> = db
+            .fetch_servers(&server_ids)
+            .await?
+            .into_iter()
+            .flat_map(|server| server.channels)
+            .collect();
+
+        let mut channels = db.find_direct_messages(&user.id).await?;
+        channels.append(&mut db.fetch_channels(&channel_ids).await?);
+        channels
+    };
+
+    if channels.is_empty() {
+        return Ok(Json(vec![]));
+    }
+
+    let limit = limit.unwrap_or(50);
+    let channel_ids: Vec<String> = channels.iter().map(|channel| channel.id()).collect();
+    let channels: HashMap<String, Channel> = channels
+        .into_iter()
+        .map(|channel| (channel.id(), channel))
+        .collect();
+
+    // Only check permissions for channels which actually appear in the results,
+    // rather than for every channel the user could have been mentioned in
+    let mut readable: HashMap<String, bool> = HashMap::new();
+    let mut messages: Vec<v0::Message> = Vec::with_capacity(limit as usize);
+    let mut before = before;
+    let expand_reactions = expand_reactions.unwrap_or_default();
+    let threshold = config().await.features.reaction_summary_threshold;
+
+    'pages: for _ in 0..MAX_PAGES {
+        let page = db
+            .fetch_messages(MessageQuery {
+                limit: Some(limit),
+                filter: MessageFilter {
+                    channels: Some(channel_ids.clone()),
+                    mentions: Some(user.id.to_string()),
+                    ..Default::default()
+                },
+                time_period: MessageTimePeriod::Absolute {
+                    before: before.clone(),
+                    after: None,
+                    sort: Some(MessageSort::Latest),
+                },
+            })
+            .await?;
+
+        let exhausted = (page.len() as i64) < limit;
+        before = page.last().map(|message| message.id.to_string());
+
+        for message in page {
+            let can_read = match readable.get(&message.channel) {
+                Some(can_read) => *can_read,
+                None => {
+                    let can_read = match channels.get(&message.channel) {
+                        Some(channel) => {
+                            let mut query =
+                                DatabasePermissionQuery::new(db, &user).channel(channel);
+                            let permissions = calculate_channel_permissions(&mut query).await;
+                            permissions.has_channel_permission(ChannelPermission::ViewChannel)
+                                && permissions
+                                    .has_channel_permission(ChannelPermission::ReadMessageHistory)
+                        }
+                        None => false,
+                    };
+
+                    readable.insert(message.channel.to_string(), can_read);
+                    can_read
+                }
+            };
+
+            if can_read {
+                let mut message: v0::Message = message.into();
+                if !expand_reactions {
+                    message.summarise_reactions(threshold);
+                }
+
+                messages.push(message);
+                if messages.len() as i64 == limit {
+                    break 'pages;
+                }
+            }
+        }
+
+        if exhausted {
+            break;
+        }
+    }
+
+    Ok(Json(messages))
+}
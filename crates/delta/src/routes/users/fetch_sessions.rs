@@ -0,0 +1,80 @@
+use onechatsocial_quark::{
+    authifier::{models::Session, Authifier},
+    Error, Result,
+};
+
+use rocket::{serde::json::Json, State};
+use serde::{Deserialize, Serialize};
+
+/// # Session Information
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct SessionInfo {
+    /// Session Id
+    #[serde(rename = "_id")]
+    id: String,
+    /// Device name given when logging in
+    name: String,
+    /// Whether this is the session making the request
+    current: bool,
+}
+
+/// # Fetch Sessions
+///
+/// Retrieve all active sessions of your account.
+#[openapi(tag = "User Information")]
+#[get("/@me/sessions")]
+pub async fn req(authifier: &State<Authifier>, session: Session) -> Result<Json<Vec<SessionInfo>>> {
+    let sessions = authifier
+        .database
+        .find_sessions(&session.user_id)
+        .await
+        .map_err(|_| Error::DatabaseError {
+            operation: "find",
+            with: "sessions",
+        })?;
+
+    Ok(Json(
+        sessions
+            .into_iter()
+            .map(|item| SessionInfo {
+                current: item.id == session.id,
+                id: item.id,
+                name: item.name,
+            })
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use rocket::http::{Header, Status};
+
+    use super::SessionInfo;
+
+    #[rocket::async_test]
+    async fn fetch_sessions() {
+        let harness = TestHarness::new().await;
+        let (account, session, _) = harness.new_user().await;
+        let other_session = harness.new_session(&account).await;
+        let (_, unrelated_session, _) = harness.new_user().await;
+
+        let response = harness
+            .client
+            .get("/users/@me/sessions")
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+        let sessions: Vec<SessionInfo> = response.into_json().await.expect("`Vec<SessionInfo>`");
+        assert_eq!(sessions.len(), 2);
+        assert!(sessions
+            .iter()
+            .any(|item| item.id == session.id && item.current));
+        assert!(sessions
+            .iter()
+            .any(|item| item.id == other_session.id && !item.current));
+        assert!(!sessions.iter().any(|item| item.id == unrelated_session.id));
+    }
+}
@@ -6,14 +6,18 @@ mod block_user;
 mod change_username;
 mod edit_user;
 mod fetch_dms;
+mod fetch_mentions;
 mod fetch_profile;
 mod fetch_self;
+mod fetch_sessions;
 mod fetch_user;
 mod fetch_user_flags;
 mod find_mutual;
 mod get_default_avatar;
 mod open_dm;
 mod remove_friend;
+mod revoke_other_sessions;
+mod revoke_session;
 mod send_friend_request;
 mod unblock_user;
 
@@ -27,6 +31,10 @@ pub fn routes() -> (Vec<Route>, OpenApi) {
         change_username::req,
         get_default_avatar::req,
         fetch_profile::req,
+        fetch_sessions::req,
+        fetch_mentions::fetch_mentions,
+        revoke_session::req,
+        revoke_other_sessions::req,
         // Direct Messaging
         fetch_dms::req,
         open_dm::req,
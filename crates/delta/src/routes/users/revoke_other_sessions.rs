@@ -0,0 +1,80 @@
+use onechatsocial_quark::{
+    authifier::{models::Session, Authifier},
+    EmptyResponse, Error, Result,
+};
+
+use rocket::State;
+
+/// # Revoke Other Sessions
+///
+/// Log out every session of your account except the current one.
+#[openapi(tag = "User Information")]
+#[delete("/@me/sessions")]
+pub async fn req(authifier: &State<Authifier>, session: Session) -> Result<EmptyResponse> {
+    let sessions = authifier
+        .database
+        .find_sessions(&session.user_id)
+        .await
+        .map_err(|_| Error::DatabaseError {
+            operation: "find",
+            with: "sessions",
+        })?;
+
+    for target in sessions {
+        if target.id != session.id {
+            target
+                .delete(authifier)
+                .await
+                .map_err(|_| Error::DatabaseError {
+                    operation: "delete_one",
+                    with: "session",
+                })?;
+        }
+    }
+
+    Ok(EmptyResponse)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use rocket::http::{Header, Status};
+
+    #[rocket::async_test]
+    async fn revoke_other_sessions() {
+        let harness = TestHarness::new().await;
+        let (account, session, _) = harness.new_user().await;
+        let first = harness.new_session(&account).await;
+        let second = harness.new_session(&account).await;
+
+        let response = harness
+            .client
+            .delete("/users/@me/sessions")
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NoContent);
+        drop(response);
+
+        for revoked in [first, second] {
+            let response = harness
+                .client
+                .get("/users/@me")
+                .header(Header::new("x-session-token", revoked.token.to_string()))
+                .dispatch()
+                .await;
+
+            assert_eq!(response.status(), Status::Unauthorized);
+        }
+
+        let response = harness
+            .client
+            .get("/users/@me")
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+}
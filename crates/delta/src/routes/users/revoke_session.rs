@@ -0,0 +1,100 @@
+use onechatsocial_quark::{
+    authifier::{models::Session, Authifier},
+    EmptyResponse, Error, Result,
+};
+
+use rocket::State;
+
+/// # Revoke Session
+///
+/// Log out a session of your account, disconnecting it from the events server.
+#[openapi(tag = "User Information")]
+#[delete("/@me/sessions/<id>")]
+pub async fn req(
+    authifier: &State<Authifier>,
+    session: Session,
+    id: String,
+) -> Result<EmptyResponse> {
+    let target = authifier
+        .database
+        .find_session(&id)
+        .await
+        .map_err(|_| Error::DatabaseError {
+            operation: "find_one",
+            with: "session",
+        })?
+        .ok_or(Error::NotFound)?;
+
+    // Sessions of other accounts are indistinguishable from missing ones
+    if target.user_id != session.user_id {
+        return Err(Error::NotFound);
+    }
+
+    target
+        .delete(authifier)
+        .await
+        .map(|_| EmptyResponse)
+        .map_err(|_| Error::DatabaseError {
+            operation: "delete_one",
+            with: "session",
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rocket, util::test::TestHarness};
+    use rocket::http::{Header, Status};
+
+    #[rocket::async_test]
+    async fn revoke_session() {
+        let harness = TestHarness::new().await;
+        let (account, session, _) = harness.new_user().await;
+        let other_session = harness.new_session(&account).await;
+        let (_, unrelated_session, _) = harness.new_user().await;
+
+        let response = harness
+            .client
+            .delete(format!("/users/@me/sessions/{}", unrelated_session.id))
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NotFound);
+        drop(response);
+
+        let response = harness
+            .client
+            .delete(format!("/users/@me/sessions/{}", other_session.id))
+            .header(Header::new("x-session-token", session.token.to_string()))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::NoContent);
+        drop(response);
+
+        let response = harness
+            .client
+            .get("/users/@me")
+            .header(Header::new(
+                "x-session-token",
+                other_session.token.to_string(),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Unauthorized);
+        drop(response);
+
+        let response = harness
+            .client
+            .get("/users/@me")
+            .header(Header::new(
+                "x-session-token",
+                unrelated_session.token.to_string(),
+            ))
+            .dispatch()
+            .await;
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+}
@@ -9,6 +9,9 @@ mod webhook_execute;
 mod webhook_fetch_token;
 mod webhook_fetch;
 mod webhook_execute_github;
+mod webhook_execute_template;
+mod webhook_message_delete;
+mod webhook_message_edit;
 
 pub fn routes() -> (Vec<Route>, OpenApi) {
     openapi_get_routes_spec![
@@ -18,7 +21,10 @@ pub fn routes() -> (Vec<Route>, OpenApi) {
         webhook_edit::webhook_edit,
         webhook_execute_github::webhook_execute_github,
         webhook_execute::webhook_execute,
+        webhook_execute_template::webhook_execute_template,
         webhook_fetch_token::webhook_fetch_token,
         webhook_fetch::webhook_fetch,
+        webhook_message_edit::webhook_message_edit,
+        webhook_message_delete::webhook_message_delete,
     ]
 }
@@ -34,7 +34,11 @@ pub async fn webhook_edit(
         .await
         .throw_if_lacking_channel_permission(ChannelPermission::ManageWebhooks)?;
 
-    if data.name.is_none() && data.avatar.is_none() && data.remove.is_empty() {
+    if data.name.is_none()
+        && data.avatar.is_none()
+        && data.template.is_none()
+        && data.remove.is_empty()
+    {
         return Ok(Json(webhook.into()));
     };
 
@@ -42,12 +46,14 @@ pub async fn webhook_edit(
         name,
         avatar,
         permissions,
+        template,
         remove,
     } = data;
 
     let mut partial = PartialWebhook {
         name,
         permissions,
+        template: template.map(|template| template.into()),
         ..Default::default()
     };
 
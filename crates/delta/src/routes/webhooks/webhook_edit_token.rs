@@ -26,7 +26,11 @@ pub async fn webhook_edit_token(
     let mut webhook = webhook_id.as_webhook(db).await?;
     webhook.assert_token(&token)?;
 
-    if data.name.is_none() && data.avatar.is_none() && data.remove.is_empty() {
+    if data.name.is_none()
+        && data.avatar.is_none()
+        && data.template.is_none()
+        && data.remove.is_empty()
+    {
         return Ok(Json(webhook.into()));
     };
 
@@ -34,12 +38,14 @@ pub async fn webhook_edit_token(
         name,
         avatar,
         permissions,
+        template,
         remove
     } = data;
 
     let mut partial = PartialWebhook {
         name,
         permissions,
+        template: template.map(|template| template.into()),
         ..Default::default()
     };
 
@@ -9,28 +9,36 @@ use rocket::{serde::json::Json, State};
 
 use validator::Validate;
 
+use crate::util::signature::{Signature, SignedJson};
+
 /// # Executes a webhook
 ///
 /// Executes a webhook and sends a message
+///
+/// If the webhook has a secret, the request body must be signed.
+///
+/// Retrying with the same `Idempotency-Key` header returns the original message.
 #[openapi(tag = "Webhooks")]
 #[post("/<webhook_id>/<token>", data = "<data>")]
 pub async fn webhook_execute(
     db: &State<Database>,
     webhook_id: Reference,
     token: String,
-    data: Json<v0::DataMessageSend>,
+    signature: Signature,
+    data: SignedJson<v0::DataMessageSend>,
     idempotency: IdempotencyKey,
 ) -> Result<Json<v0::Message>> {
-    let data = data.into_inner();
+    let webhook = webhook_id.as_webhook(db).await?;
+    webhook.assert_token(&token)?;
+    webhook.assert_signature(&data.raw, signature.as_deref())?;
+
+    let data = data.value;
     data.validate().map_err(|error| {
         create_error!(FailedValidation {
             error: error.to_string()
         })
     })?;
 
-    let webhook = webhook_id.as_webhook(db).await?;
-    webhook.assert_token(&token)?;
-
     let permissions: PermissionValue = webhook.permissions.into();
     permissions.throw_if_lacking_channel_permission(ChannelPermission::SendMessage)?;
 
@@ -38,12 +46,17 @@ pub async fn webhook_execute(
         permissions.throw_if_lacking_channel_permission(ChannelPermission::UploadFiles)?;
     }
 
-    if data.embeds.as_ref().map_or(false, |v| !v.is_empty()) {
+    // Quotes are included as an embed
+    if data.embeds.as_ref().map_or(false, |v| !v.is_empty()) || data.quote.is_some() {
         permissions.throw_if_lacking_channel_permission(ChannelPermission::SendEmbeds)?;
     }
 
-    if data.masquerade.is_some() {
+    if let Some(masq) = &data.masquerade {
         permissions.throw_if_lacking_channel_permission(ChannelPermission::Masquerade)?;
+
+        if masq.colour.is_some() {
+            permissions.throw_if_lacking_channel_permission(ChannelPermission::ManageRole)?;
+        }
     }
 
     if data.interactions.is_some() {
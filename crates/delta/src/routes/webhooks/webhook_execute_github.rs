@@ -1,4 +1,7 @@
-use onechatsocial_database::{util::reference::Reference, Database, Message};
+use onechatsocial_database::{
+    util::{reference::Reference, ulid_generator},
+    Database, Message,
+};
 use onechatsocial_models::v0::{MessageAuthor, SendableEmbed, Webhook};
 use onechatsocial_result::{create_error, Error, Result};
 use revolt_rocket_okapi::{
@@ -10,9 +13,10 @@ use rocket::{http::Status, request::FromRequest, Request, State};
 use schemars::schema::SchemaObject;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use ulid::Ulid;
 use validator::Validate;
 
+use crate::util::signature::Signature;
+
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct GithubUser {
     name: Option<String>,
@@ -750,10 +754,12 @@ pub async fn webhook_execute_github(
     webhook_id: Reference,
     token: String,
     event: EventHeader<'_>,
+    signature: Signature,
     data: String,
 ) -> Result<()> {
     let webhook = webhook_id.as_webhook(db).await?;
     webhook.assert_token(&token)?;
+    webhook.assert_signature(data.as_bytes(), signature.as_deref())?;
 
     let channel = db.fetch_channel(&webhook.channel_id).await?;
     let event = convert_event(&data, &event)?;
@@ -1059,7 +1065,7 @@ pub async fn webhook_execute_github(
         })
     })?;
 
-    let message_id = Ulid::new().to_string();
+    let message_id = ulid_generator::generate().to_string();
 
     let mut message = Message {
         id: message_id,
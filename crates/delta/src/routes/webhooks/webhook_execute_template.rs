@@ -0,0 +1,67 @@
+use onechatsocial_database::{
+    util::{idempotency::IdempotencyKey, reference::Reference},
+    Database, Message,
+};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{ChannelPermission, PermissionValue};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+use serde_json::Value;
+use validator::Validate;
+
+use crate::util::signature::{Signature, SignedJson};
+
+/// # Executes a webhook using its template
+///
+/// Transforms an arbitrary JSON payload into a message using the webhook's template and sends it
+///
+/// Retrying with the same `Idempotency-Key` header returns the original message.
+#[openapi(tag = "Webhooks")]
+#[post("/<webhook_id>/<token>/template", data = "<data>")]
+pub async fn webhook_execute_template(
+    db: &State<Database>,
+    webhook_id: Reference,
+    token: String,
+    signature: Signature,
+    data: SignedJson<Value>,
+    idempotency: IdempotencyKey,
+) -> Result<Json<v0::Message>> {
+    let webhook = webhook_id.as_webhook(db).await?;
+    webhook.assert_token(&token)?;
+    webhook.assert_signature(&data.raw, signature.as_deref())?;
+
+    let Some(template) = &webhook.template else {
+        return Err(create_error!(InvalidOperation));
+    };
+
+    let data = template.render(&data.value);
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    let permissions: PermissionValue = webhook.permissions.into();
+    permissions.throw_if_lacking_channel_permission(ChannelPermission::SendMessage)?;
+
+    // Quotes are included as an embed
+    if data.embeds.as_ref().map_or(false, |v| !v.is_empty()) || data.quote.is_some() {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::SendEmbeds)?;
+    }
+
+    let channel = db.fetch_channel(&webhook.channel_id).await?;
+
+    Ok(Json(
+        Message::create_from_api(
+            db,
+            channel,
+            data,
+            v0::MessageAuthor::Webhook(&webhook.into()),
+            idempotency,
+            true,
+            true,
+        )
+        .await?
+        .into(),
+    ))
+}
@@ -0,0 +1,33 @@
+use onechatsocial_database::{util::reference::Reference, Database};
+use onechatsocial_result::{create_error, Result};
+use rocket::State;
+use rocket_empty::EmptyResponse;
+
+/// # Deletes a webhook message
+///
+/// Deletes a message previously sent by this webhook
+#[openapi(tag = "Webhooks")]
+#[delete("/<webhook_id>/<token>/messages/<message_id>")]
+pub async fn webhook_message_delete(
+    db: &State<Database>,
+    webhook_id: Reference,
+    token: String,
+    message_id: Reference,
+) -> Result<EmptyResponse> {
+    let webhook = webhook_id.as_webhook(db).await?;
+    webhook.assert_token(&token)?;
+
+    // Webhooks may only delete their own messages
+    let message = message_id.as_message(db).await?;
+    if message.webhook.is_none()
+        || message.author != webhook.id
+        || message.channel != webhook.channel_id
+    {
+        return Err(create_error!(NotFound));
+    }
+
+    message
+        .delete_now(db, &webhook.id)
+        .await
+        .map(|_| EmptyResponse)
+}
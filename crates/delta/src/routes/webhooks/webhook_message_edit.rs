@@ -0,0 +1,57 @@
+use onechatsocial_database::{util::reference::Reference, Database};
+use onechatsocial_models::v0;
+use onechatsocial_permissions::{ChannelPermission, PermissionValue};
+use onechatsocial_result::{create_error, Result};
+use rocket::{serde::json::Json, State};
+
+use validator::Validate;
+
+/// # Edits a webhook message
+///
+/// Edits a message previously sent by this webhook
+#[openapi(tag = "Webhooks")]
+#[patch("/<webhook_id>/<token>/messages/<message_id>", data = "<data>")]
+pub async fn webhook_message_edit(
+    db: &State<Database>,
+    webhook_id: Reference,
+    token: String,
+    message_id: Reference,
+    data: Json<v0::DataEditMessage>,
+) -> Result<Json<v0::Message>> {
+    let webhook = webhook_id.as_webhook(db).await?;
+    webhook.assert_token(&token)?;
+
+    let data = data.into_inner();
+    data.validate().map_err(|error| {
+        create_error!(FailedValidation {
+            error: error.to_string()
+        })
+    })?;
+
+    // Webhooks may only edit their own messages
+    let mut message = message_id.as_message(db).await?;
+    if message.webhook.is_none()
+        || message.author != webhook.id
+        || message.channel != webhook.channel_id
+    {
+        return Err(create_error!(NotFound));
+    }
+
+    let permissions: PermissionValue = webhook.permissions.into();
+    if data.embeds.as_ref().map_or(false, |v| !v.is_empty()) {
+        permissions.throw_if_lacking_channel_permission(ChannelPermission::SendEmbeds)?;
+    }
+
+    let channel = db.fetch_channel(&webhook.channel_id).await?;
+    message
+        .edit(
+            db,
+            &channel,
+            data,
+            &webhook.id,
+            permissions.has_channel_permission(ChannelPermission::SendEmbeds),
+        )
+        .await?;
+
+    Ok(Json(message.into()))
+}
@@ -1,2 +1,3 @@
 pub mod ratelimiter;
+pub mod signature;
 pub mod test;
@@ -0,0 +1,120 @@
+use onechatsocial_result::{create_error, Error};
+use revolt_rocket_okapi::{
+    gen::OpenApiGenerator,
+    request::{OpenApiFromData, OpenApiFromRequest, RequestHeaderInput},
+    revolt_okapi::openapi3::{Parameter, ParameterValue, RequestBody},
+};
+use rocket::{
+    data::{self, FromData, Limits},
+    http::Status,
+    request::{self, FromRequest},
+    serde::json::Json,
+    Data, Request,
+};
+use schemars::{
+    schema::{InstanceType, SchemaObject, SingleOrVec},
+    JsonSchema,
+};
+use serde::de::DeserializeOwned;
+
+/// Signature header attached to inbound webhook requests
+pub struct Signature(pub Option<String>);
+
+impl Signature {
+    /// Get the signature as a string, if present
+    pub fn as_deref(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+}
+
+#[async_trait]
+impl<'r> FromRequest<'r> for Signature {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let headers = request.headers();
+        request::Outcome::Success(Signature(
+            headers
+                .get_one("X-Signature-256")
+                .or_else(|| headers.get_one("X-Hub-Signature-256"))
+                .map(|signature| signature.to_string()),
+        ))
+    }
+}
+
+impl<'r> OpenApiFromRequest<'r> for Signature {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> revolt_rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::Parameter(Parameter {
+            name: "X-Signature-256".to_string(),
+            location: "header".to_string(),
+            required: false,
+            description: Some("HMAC-SHA256 signature of the request body".to_string()),
+            deprecated: false,
+            allow_empty_value: false,
+            value: ParameterValue::Schema {
+                style: None,
+                explode: None,
+                allow_reserved: false,
+                schema: SchemaObject {
+                    instance_type: Some(SingleOrVec::Single(Box::new(InstanceType::String))),
+                    ..Default::default()
+                },
+                example: None,
+                examples: None,
+            },
+            extensions: schemars::Map::new(),
+        }))
+    }
+}
+
+/// JSON body which keeps hold of the raw payload for signature verification
+pub struct SignedJson<T> {
+    /// Raw request body
+    pub raw: Vec<u8>,
+    /// Deserialised request body
+    pub value: T,
+}
+
+#[async_trait]
+impl<'r, T: DeserializeOwned> FromData<'r> for SignedJson<T> {
+    type Error = Error;
+
+    async fn from_data(request: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let limit = request.limits().get("json").unwrap_or(Limits::JSON);
+        let raw = match data.open(limit).into_bytes().await {
+            Ok(bytes) if bytes.is_complete() => bytes.into_inner(),
+            Ok(_) => {
+                return data::Outcome::Failure((
+                    Status::PayloadTooLarge,
+                    create_error!(PayloadTooLarge),
+                ))
+            }
+            Err(_) => {
+                return data::Outcome::Failure((
+                    Status::BadRequest,
+                    create_error!(InvalidOperation),
+                ))
+            }
+        };
+
+        match serde_json::from_slice(&raw) {
+            Ok(value) => data::Outcome::Success(SignedJson { raw, value }),
+            Err(error) => data::Outcome::Failure((
+                Status::UnprocessableEntity,
+                create_error!(FailedValidation {
+                    error: error.to_string()
+                }),
+            )),
+        }
+    }
+}
+
+impl<'r, T: JsonSchema + DeserializeOwned> OpenApiFromData<'r> for SignedJson<T> {
+    fn request_body(gen: &mut OpenApiGenerator) -> revolt_rocket_okapi::Result<RequestBody> {
+        Json::<T>::request_body(gen)
+    }
+}
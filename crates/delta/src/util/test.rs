@@ -88,6 +88,13 @@ impl TestHarness {
         (account, session, user)
     }
 
+    pub async fn new_session(&self, account: &Account) -> Session {
+        account
+            .create_session(&self.authifier, TestHarness::rand_string())
+            .await
+            .expect("`Session`")
+    }
+
     pub async fn wait_for_event<F>(&mut self, topic: &str, predicate: F) -> EventV1
     where
         F: Fn(&EventV1) -> bool,
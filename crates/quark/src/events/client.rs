@@ -1,5 +1,6 @@
 use authifier::AuthifierEvent;
-use onechatsocial_models::v0::{FieldsWebhook, PartialWebhook, Webhook};
+use iso8601_timestamp::Timestamp;
+use onechatsocial_models::v0::{FieldsWebhook, PartialWebhook, ReactionDelta, Webhook};
 use serde::{Deserialize, Serialize};
 
 use crate::models::channel::{FieldsChannel, PartialChannel};
@@ -60,6 +61,9 @@ pub enum EventV1 {
     /// Ping response
     Pong { data: Ping },
 
+    /// Missed events are no longer available, state must be fetched again
+    ResyncRequired,
+
     /// New message
     Message(Message),
 
@@ -103,6 +107,16 @@ pub enum EventV1 {
         emoji_id: String,
     },
 
+    /// Remove all reactions from message
+    MessageReactionRemoveAll { id: String, channel_id: String },
+
+    /// Batch of reaction changes to a message
+    MessageReactionBulk {
+        id: String,
+        channel_id: String,
+        changes: Vec<ReactionDelta>,
+    },
+
     /// Bulk delete messages
     BulkMessageDelete { channel: String, ids: Vec<String> },
 
@@ -138,6 +152,13 @@ pub enum EventV1 {
         message_id: String,
     },
 
+    /// Messages pinned in a channel changed
+    ChannelPinsUpdate {
+        id: String,
+        pin_count: usize,
+        last_pinned_at: Option<Timestamp>,
+    },
+
     /// New server
     ServerCreate {
         id: String,
@@ -169,6 +190,9 @@ pub enum EventV1 {
     /// User left server
     ServerMemberLeave { id: String, user: String },
 
+    /// User was unbanned from server
+    ServerMemberUnban { id: String, user: String },
+
     /// Server role created or updated
     ServerRoleUpdate {
         id: String,
@@ -235,3 +259,17 @@ pub enum EventV1 {
     /// Auth events
     Auth(AuthifierEvent),
 }
+
+/// Event as published to subscribers
+///
+/// Every published event is given a ULID, which clients can pass back
+/// as `last_event` when reconnecting to replay the events they missed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublishedEvent {
+    /// Unique id assigned when the event was published
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<String>,
+    /// Published event
+    #[serde(flatten)]
+    pub event: EventV1,
+}
@@ -7,14 +7,16 @@ use crate::{
         user::{PartialUser, Presence, RelationshipStatus},
         Channel, Member, User,
     },
-    perms, Database, Permission, Result,
+    perms, Database, Error, Permission, Result,
 };
 
+use onechatsocial_database::util::permission_cache::invalidate_server;
 use onechatsocial_presence::filter_online;
+use ulid::Ulid;
 
 use super::{
-    client::EventV1,
-    state::{Cache, State},
+    client::{EventV1, PublishedEvent},
+    state::{Cache, State, MAX_PRESENCE_SUBSCRIPTIONS},
 };
 
 /// Cache Manager
@@ -287,6 +289,32 @@ impl State {
         }
     }
 
+    /// Watch presence for a set of server members, replacing any previous set
+    ///
+    /// Users who are not members of the given server are ignored.
+    pub async fn subscribe_presence(
+        &mut self,
+        db: &Database,
+        server: &str,
+        users: Vec<String>,
+    ) -> Result<()> {
+        if users.len() > MAX_PRESENCE_SUBSCRIPTIONS {
+            return Err(Error::InvalidOperation);
+        }
+
+        if !self.cache.servers.contains_key(server) {
+            return Err(Error::UnknownServer);
+        }
+
+        let members = db.fetch_members(server, &users).await?;
+        self.replace_presence_subscriptions(
+            Some(server.to_string()),
+            members.into_iter().map(|member| member.id.user).collect(),
+        );
+
+        Ok(())
+    }
+
     /// Push presence change to the user and all associated server topics
     pub async fn broadcast_presence_change(&self, target: bool) {
         if if let Some(status) = &self.cache.users.get(&self.cache.user_id).unwrap().status {
@@ -392,7 +420,7 @@ impl State {
             EventV1::ChannelGroupLeave { id, user, .. } => {
                 if user == &self.cache.user_id {
                     self.remove_subscription(id);
-                } else if !self.cache.can_subscribe_to_user(user) {
+                } else if !self.should_subscribe_to_user(user) {
                     self.remove_subscription(user);
                 }
             }
@@ -427,7 +455,7 @@ impl State {
                     server.apply_options(data.clone());
                 }
 
-                if data.default_permissions.is_some() {
+                if data.default_permissions.is_some() || data.categories.is_some() {
                     queue_server = Some(id.clone());
                 }
             }
@@ -436,6 +464,10 @@ impl State {
             }
             EventV1::ServerMemberLeave { id, user } => {
                 if user == &self.cache.user_id {
+                    if self.presence_server() == Some(id.as_str()) {
+                        self.replace_presence_subscriptions(None, HashSet::new());
+                    }
+
                     self.remove_subscription(id);
 
                     if let Some(server) = self.cache.servers.remove(id) {
@@ -445,9 +477,15 @@ impl State {
                         }
                     }
                     self.cache.members.remove(id);
+                } else if self.presence_server() == Some(id.as_str()) {
+                    self.remove_presence_subscription(user);
                 }
             }
             EventV1::ServerDelete { id } => {
+                if self.presence_server() == Some(id.as_str()) {
+                    self.replace_presence_subscriptions(None, HashSet::new());
+                }
+
                 self.remove_subscription(id);
 
                 if let Some(server) = self.cache.servers.remove(id) {
@@ -524,7 +562,7 @@ impl State {
             EventV1::UserRelationship { id, user, .. } => {
                 self.cache.users.insert(id.clone(), user.clone());
 
-                if self.cache.can_subscribe_to_user(id) {
+                if self.should_subscribe_to_user(id) {
                     self.insert_subscription(id.clone());
                 } else {
                     self.remove_subscription(id);
@@ -555,14 +593,24 @@ impl State {
 impl EventV1 {
     /// Publish helper wrapper
     pub async fn p(self, channel: String) {
+        // Permissions calculated for this server may no longer be valid
+        if let Some(server) = self.permission_server() {
+            invalidate_server(server).await;
+        }
+
+        let event = PublishedEvent {
+            event_id: Some(Ulid::new().to_string()),
+            event: self,
+        };
+
         #[cfg(not(debug_assertions))]
-        redis_kiss::p(channel, self).await;
+        redis_kiss::p(channel, event).await;
 
         #[cfg(debug_assertions)]
-        info!("Publishing event to {channel}: {self:?}");
+        info!("Publishing event to {channel}: {event:?}");
 
         #[cfg(debug_assertions)]
-        redis_kiss::publish(channel, self).await.unwrap();
+        redis_kiss::publish(channel, event).await.unwrap();
     }
 
     /// Publish user event
@@ -586,4 +634,18 @@ impl EventV1 {
     pub async fn global(self) {
         self.p("global".to_string()).await;
     }
+
+    /// Find the server whose permissions may be affected by this event
+    fn permission_server(&self) -> Option<&str> {
+        match self {
+            EventV1::ServerUpdate { id, .. }
+            | EventV1::ServerDelete { id }
+            | EventV1::ServerMemberJoin { id, .. }
+            | EventV1::ServerMemberLeave { id, .. }
+            | EventV1::ServerRoleUpdate { id, .. }
+            | EventV1::ServerRoleDelete { id, .. } => Some(id),
+            EventV1::ServerMemberUpdate { id, .. } => Some(&id.server),
+            _ => None,
+        }
+    }
 }
@@ -9,4 +9,5 @@ pub enum ClientMessage {
     BeginTyping { channel: String },
     EndTyping { channel: String },
     Ping { data: Ping, responded: Option<()> },
+    SubscribePresence { server: String, users: Vec<String> },
 }
@@ -4,6 +4,9 @@ use lru::LruCache;
 
 use crate::models::{Channel, Member, Server, User};
 
+/// Maximum number of users whose presence may be watched at once
+pub const MAX_PRESENCE_SUBSCRIPTIONS: usize = 200;
+
 /// Enumeration representing some change in subscriptions
 pub enum SubscriptionStateChange {
     /// No change
@@ -62,6 +65,9 @@ pub struct State {
     pub private_topic: String,
     subscribed: HashSet<String>,
     state: SubscriptionStateChange,
+
+    presence_server: Option<String>,
+    presence: HashSet<String>,
 }
 
 impl State {
@@ -84,6 +90,9 @@ impl State {
             subscribed,
             private_topic,
             state: SubscriptionStateChange::Reset,
+
+            presence_server: None,
+            presence: HashSet::new(),
         }
     }
 
@@ -117,6 +126,8 @@ impl State {
     pub fn reset_state(&mut self) {
         self.state = SubscriptionStateChange::Reset;
         self.subscribed.clear();
+        self.presence_server = None;
+        self.presence.clear();
     }
 
     /// Add a new subscription
@@ -162,4 +173,96 @@ impl State {
 
         self.subscribed.remove(subscription);
     }
+
+    /// Replace the users whose presence is being watched
+    ///
+    /// Subscriptions which are still required for other reasons are kept.
+    pub fn replace_presence_subscriptions(
+        &mut self,
+        server: Option<String>,
+        users: HashSet<String>,
+    ) {
+        let stale: Vec<String> = self.presence.difference(&users).cloned().collect();
+        self.presence_server = server;
+        self.presence = users;
+
+        for id in stale {
+            if !self.should_subscribe_to_user(&id) {
+                self.remove_subscription(&id);
+            }
+        }
+
+        for id in self.presence.clone() {
+            self.insert_subscription(id);
+        }
+    }
+
+    /// Stop watching presence for a given user
+    pub fn remove_presence_subscription(&mut self, user_id: &str) {
+        if self.presence.remove(user_id) && !self.should_subscribe_to_user(user_id) {
+            self.remove_subscription(user_id);
+        }
+    }
+
+    /// Check whether we should remain subscribed to another user
+    pub fn should_subscribe_to_user(&self, user_id: &str) -> bool {
+        self.presence.contains(user_id) || self.cache.can_subscribe_to_user(user_id)
+    }
+
+    /// Get the server whose member presence is being watched, if any
+    pub fn presence_server(&self) -> Option<&str> {
+        self.presence_server.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::models::user::{Relationship, RelationshipStatus};
+    use crate::models::User;
+
+    use super::State;
+
+    fn subscribed(state: &State, id: &str) -> bool {
+        state.iter_subscriptions().any(|topic| topic == id)
+    }
+
+    #[test]
+    fn presence_subscriptions() {
+        let mut state = State::from(User {
+            id: "me".to_string(),
+            relations: Some(vec![Relationship {
+                id: "friend".to_string(),
+                status: RelationshipStatus::Friend,
+            }]),
+            ..Default::default()
+        });
+
+        state.apply_state();
+
+        let users: HashSet<String> = ["member", "friend"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        state.replace_presence_subscriptions(Some("server".to_string()), users);
+        assert_eq!(state.presence_server(), Some("server"));
+        assert!(subscribed(&state, "member"));
+        assert!(subscribed(&state, "friend"));
+
+        // Friends remain subscribed after presence is no longer watched
+        state.replace_presence_subscriptions(None, HashSet::new());
+        assert_eq!(state.presence_server(), None);
+        assert!(!subscribed(&state, "member"));
+        assert!(subscribed(&state, "friend"));
+
+        state.replace_presence_subscriptions(
+            Some("server".to_string()),
+            HashSet::from(["member".to_string()]),
+        );
+        state.remove_presence_subscription("member");
+        assert!(!subscribed(&state, "member"));
+        assert!(!state.should_subscribe_to_user("member"));
+    }
 }
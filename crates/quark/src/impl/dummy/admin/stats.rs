@@ -4,7 +4,7 @@ use super::super::DummyDb;
 
 #[async_trait]
 impl AbstractStats for DummyDb {
-    async fn generate_stats(&self) -> Result<Stats> {
+    async fn generate_stats(&self, _refresh: bool) -> Result<Stats> {
         todo!()
     }
 }
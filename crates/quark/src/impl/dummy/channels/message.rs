@@ -1,4 +1,6 @@
-use crate::models::message::{AppendMessage, Message, MessageQuery, PartialMessage};
+use crate::models::message::{
+    AppendMessage, Message, MessageQuery, PartialMessage, SearchCursor, SearchResult,
+};
 use crate::{AbstractMessage, Result};
 
 use super::super::DummyDb;
@@ -45,6 +47,14 @@ impl AbstractMessage for DummyDb {
         Ok(vec![])
     }
 
+    async fn search_messages(
+        &self,
+        _query: MessageQuery,
+        _cursor: Option<SearchCursor>,
+    ) -> Result<Vec<SearchResult>> {
+        Ok(vec![])
+    }
+
     /// Add a new reaction to a message
     async fn add_reaction(&self, id: &str, emoji: &str, user: &str) -> Result<()> {
         info!("Add to {id} with {emoji} and {user}");
@@ -28,6 +28,7 @@ impl AbstractServer for DummyDb {
             nsfw: false,
             analytics: true,
             discoverable: true,
+            embed_colour: None,
         })
     }
 
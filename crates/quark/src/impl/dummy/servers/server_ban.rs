@@ -13,6 +13,8 @@ impl AbstractServerBan for DummyDb {
                 user: user.into(),
             },
             reason: Some("ban reason".into()),
+            moderator: None,
+            expires_at: None,
         })
     }
 
@@ -25,6 +27,11 @@ impl AbstractServerBan for DummyDb {
         Ok(())
     }
 
+    async fn upsert_ban(&self, ban: &ServerBan) -> Result<()> {
+        info!("Upsert {ban:?}");
+        Ok(())
+    }
+
     async fn delete_ban(&self, id: &MemberCompositeKey) -> Result<()> {
         info!("Delete {id:?}");
         Ok(())
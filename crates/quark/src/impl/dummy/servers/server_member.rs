@@ -3,6 +3,8 @@ use crate::models::server_member::{
 };
 use crate::{AbstractServerMember, Result};
 
+use std::collections::HashMap;
+
 use super::super::DummyDb;
 
 #[async_trait]
@@ -53,4 +55,12 @@ impl AbstractServerMember for DummyDb {
     async fn fetch_server_count(&self, _user: &str) -> Result<usize> {
         Ok(5)
     }
+
+    async fn fetch_role_member_counts(&self, _server: &str) -> Result<HashMap<String, u64>> {
+        Ok(HashMap::new())
+    }
+
+    async fn fetch_member_ids(&self, _server: &str) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
 }
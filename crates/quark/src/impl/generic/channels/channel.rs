@@ -1,13 +1,12 @@
 use std::collections::HashSet;
 
-use onechatsocial_database::util::idempotency::IdempotencyKey;
-use ulid::Ulid;
+use onechatsocial_database::util::{idempotency::IdempotencyKey, ulid_generator};
 
 use crate::{
     events::client::EventV1,
     models::{
         channel::{FieldsChannel, PartialChannel},
-        message::{DataMessageSend, Message, Reply, SystemMessage, RE_MENTION},
+        message::{DataMessageSend, Message, Reply, SystemMessage},
         Channel,
     },
     tasks::{ack::AckEvent, process_embeds},
@@ -94,8 +93,21 @@ impl Channel {
         Ok(())
     }
 
-    /// Delete a channel
+    /// Delete a channel along with any threads started in it
     pub async fn delete(self, db: &Database) -> Result<()> {
+        // Threads take their permissions from this channel, so they must not outlive it
+        let rvdb: onechatsocial_database::Database = db.clone().into();
+        for thread in rvdb
+            .fetch_threads(self.id())
+            .await
+            .map_err(Error::from_core)?
+        {
+            let thread = db.fetch_channel(&thread.id()).await?;
+            let id = thread.id().to_string();
+            EventV1::ChannelDelete { id: id.clone() }.p(id).await;
+            db.delete_channel(&thread).await?;
+        }
+
         let id = self.id().to_string();
         EventV1::ChannelDelete { id: id.clone() }.p(id).await;
         db.delete_channel(&self).await
@@ -133,11 +145,84 @@ impl Channel {
                 }
                 _ => {}
             },
+            FieldsChannel::SlowmodeSeconds => {
+                if let Self::TextChannel {
+                    slowmode_seconds, ..
+                } = self
+                {
+                    slowmode_seconds.take();
+                }
+            }
         }
     }
 
     /// Apply partial channel to channel
     pub fn apply_options(&mut self, partial: PartialChannel) {
+        if let Self::TextChannel {
+            slowmode_seconds,
+            slowmode_applies_to_bots,
+            ..
+        } = self
+        {
+            if let Some(v) = partial.slowmode_seconds {
+                slowmode_seconds.replace(v);
+            }
+
+            if let Some(v) = partial.slowmode_applies_to_bots {
+                *slowmode_applies_to_bots = v;
+            }
+        }
+
+        if let Self::TextChannel {
+            slowmode_exempt_roles,
+            ..
+        } = self
+        {
+            if let Some(v) = partial.slowmode_exempt_roles {
+                *slowmode_exempt_roles = v;
+            }
+        }
+
+        if let Self::TextChannel { reactions_only, .. } = self {
+            if let Some(v) = partial.reactions_only {
+                *reactions_only = v;
+            }
+        }
+
+        if let Self::TextChannel { disable_embeds, .. } = self {
+            if let Some(v) = partial.disable_embeds {
+                *disable_embeds = v;
+            }
+        }
+
+        if let Self::TextChannel { announcement, .. } = self {
+            if let Some(v) = partial.announcement {
+                *announcement = v;
+            }
+        }
+
+        if let Self::TextChannel { archived, .. } = self {
+            if let Some(v) = partial.archived {
+                *archived = v;
+            }
+        }
+
+        if let Self::TextChannel { pin_order, .. } = self {
+            if let Some(v) = partial.pin_order {
+                *pin_order = v;
+            }
+        }
+
+        if let Self::TextChannel {
+            last_thread_message_id,
+            ..
+        } = self
+        {
+            if let Some(v) = partial.last_thread_message_id {
+                last_thread_message_id.replace(v);
+            }
+        }
+
         // ! FIXME: maybe flatten channel object?
         match self {
             Self::DirectMessage { active, .. } => {
@@ -411,13 +496,29 @@ impl Channel {
         mut idempotency: IdempotencyKey,
         generate_embeds: bool,
     ) -> Result<Message> {
-        Message::validate_sum(&data.content, data.embeds.as_deref().unwrap_or_default())?;
+        Message::validate_sum(
+            &data.content,
+            data.embeds.as_deref().unwrap_or_default(),
+            2000,
+            2000,
+            2000,
+        )?;
+
+        let (author_id, webhook) = match &author {
+            MessageAuthor::User(user) => (user.id.clone(), None),
+            MessageAuthor::Webhook(webhook) => (webhook.id.clone(), Some((*webhook).clone())),
+        };
 
-        idempotency
-            .consume_nonce(data.nonce)
+        // Duplicate nonces are rejected outright
+        let (claim, existing) = idempotency
+            .claim_message(data.nonce.clone(), &author_id, 0)
             .await
             .map_err(|_| Error::InvalidOperation)?;
 
+        if existing.is_some() {
+            return Err(Error::InvalidOperation);
+        }
+
         // Check the message is not empty
         if (data.content.as_ref().map_or(true, |v| v.is_empty()))
             && (data.attachments.as_ref().map_or(true, |v| v.is_empty()))
@@ -441,13 +542,8 @@ impl Channel {
             }
         }
 
-        let (author_id, webhook) = match &author {
-            MessageAuthor::User(user) => (user.id.clone(), None),
-            MessageAuthor::Webhook(webhook) => (webhook.id.clone(), Some((*webhook).clone())),
-        };
-
         // Start constructing the message
-        let message_id = Ulid::new().to_string();
+        let message_id = ulid_generator::generate().to_string();
         let mut message = Message {
             id: message_id.clone(),
             channel: self.id().to_string(),
@@ -461,11 +557,8 @@ impl Channel {
         // Parse mentions in message.
         let mut mentions = HashSet::new();
         if let Some(content) = &data.content {
-            for capture in RE_MENTION.captures_iter(content) {
-                if let Some(mention) = capture.get(1) {
-                    mentions.insert(mention.as_str().to_string());
-                }
-            }
+            mentions
+                .extend(onechatsocial_models::v0::parse_mentions(content).map(|id| id.to_string()));
         }
 
         // Verify replies are valid.
@@ -548,6 +641,7 @@ impl Channel {
         message.nonce = Some(idempotency.into_key());
 
         message.create(db, self, Some(author)).await?;
+        claim.complete(&message.id);
 
         // Queue up a task for processing embeds
         if generate_embeds {
@@ -1,18 +1,18 @@
 use std::collections::HashSet;
 
+use onechatsocial_database::util::ulid_generator;
 use onechatsocial_presence::filter_online;
 use serde_json::json;
-use ulid::Ulid;
 use validator::Validate;
 
 use crate::{
     events::client::EventV1,
     models::{
         message::{
-            AppendMessage, BulkMessageResponse, Interactions, PartialMessage, SendableEmbed,
-            SystemMessage, DataMessageSend,
+            AppendMessage, BulkMessageResponse, BulkSearchResponse, Interactions, PartialMessage,
+            SearchResult, SendableEmbed, SystemMessage, DataMessageSend,
         },
-        Channel, Emoji, Message, User,
+        Channel, Emoji, Member, Message, Server, User,
     },
     permissions::PermissionCalculator,
     tasks::ack::AckEvent,
@@ -24,6 +24,21 @@ use crate::{
 };
 
 impl Message {
+    /// Fill in the icon of each author's hoisted role, unless they are masquerading
+    pub fn resolve_role_icons(messages: &mut [Message], server: &Server, members: &[Member]) {
+        for message in messages {
+            if message.is_webhook() || message.masquerade.is_some() {
+                continue;
+            }
+
+            message.role_icon = members
+                .iter()
+                .find(|member| member.id.user == message.author)
+                .and_then(|member| member.get_role_icon(server))
+                .map(|icon| icon.id.clone());
+        }
+    }
+
     /// Create a message
     pub async fn create_no_web_push(
         &mut self,
@@ -148,6 +163,15 @@ impl Message {
 
         db.delete_message(&self.id).await?;
 
+        let rvdb: onechatsocial_database::Database = db.clone().into();
+        for id in self.replies.iter().flatten() {
+            rvdb.remove_thread_reply(id, &self.author).await.ok();
+        }
+
+        if self.pinned.unwrap_or_default() {
+            rvdb.remove_channel_pins(&self.channel, 1).await.ok();
+        }
+
         EventV1::MessageDelete {
             id: self.id,
             channel: self.channel.clone(),
@@ -158,8 +182,50 @@ impl Message {
     }
 
     /// Bulk delete messages
-    pub async fn bulk_delete(db: &Database, channel: &str, ids: Vec<String>) -> Result<()> {
+    pub async fn bulk_delete(
+        db: &Database,
+        channel: &str,
+        ids: Vec<String>,
+        deleted_by: &str,
+    ) -> Result<()> {
+        // Keep note of the messages so their threads can be updated and tombstones recorded
+        let mut messages = vec![];
+        for id in &ids {
+            if let Ok(message) = db.fetch_message(id).await {
+                if message.channel == channel {
+                    messages.push(message);
+                }
+            }
+        }
+
+        // Record the deletions first so a failure here leaves the messages untouched
+        let rvdb: onechatsocial_database::Database = db.clone().into();
+        let deleted: Vec<(String, String)> = messages
+            .iter()
+            .map(|message| (message.id.to_string(), message.author.to_string()))
+            .collect();
+
+        onechatsocial_database::MessageTombstone::create_many(&rvdb, channel, &deleted, deleted_by)
+            .await
+            .map_err(Error::from_core)?;
+
         db.delete_messages(channel, ids.clone()).await?;
+
+        let pinned = messages
+            .iter()
+            .filter(|message| message.pinned.unwrap_or_default())
+            .count();
+
+        if pinned > 0 {
+            rvdb.remove_channel_pins(channel, pinned).await.ok();
+        }
+
+        for message in messages {
+            for id in message.replies.iter().flatten() {
+                rvdb.remove_thread_reply(id, &message.author).await.ok();
+            }
+        }
+
         EventV1::BulkMessageDelete {
             channel: channel.to_string(),
             ids,
@@ -170,19 +236,48 @@ impl Message {
     }
 
     /// Validate the sum of content of a message is under threshold
-    pub fn validate_sum(content: &Option<String>, embeds: &[SendableEmbed]) -> Result<()> {
+    ///
+    /// Embeds count towards the total as well as their own separate total,
+    /// and each description is capped.
+    pub fn validate_sum(
+        content: &Option<String>,
+        embeds: &[SendableEmbed],
+        max_length: usize,
+        max_embed_length: usize,
+        max_description_length: usize,
+    ) -> Result<()> {
         let mut running_total = 0;
         if let Some(content) = content {
             running_total += content.len();
         }
 
+        let mut embed_total = 0;
         for embed in embeds {
             if let Some(desc) = &embed.description {
-                running_total += desc.len();
+                if desc.len() > max_description_length {
+                    return Err(Error::PayloadTooLarge);
+                }
             }
+
+            // Destructured so that any new fields have to be accounted for here
+            let SendableEmbed {
+                icon_url,
+                url,
+                title,
+                description,
+                media: _,
+                colour: _,
+            } = embed;
+
+            embed_total += [icon_url, url, title, description]
+                .into_iter()
+                .flatten()
+                .map(String::len)
+                .sum::<usize>();
         }
 
-        if running_total <= 2000 {
+        running_total += embed_total;
+        if running_total <= max_length && embed_total <= max_embed_length {
             Ok(())
         } else {
             Err(Error::PayloadTooLarge)
@@ -270,6 +365,19 @@ impl Message {
     pub fn is_webhook(&self) -> bool {
         self.webhook.is_some()
     }
+
+    /// Replace reactions with per-emoji counts if there are more than `threshold` entries
+    pub fn summarise_reactions(&mut self, threshold: usize) {
+        let entries: usize = self.reactions.values().map(|users| users.len()).sum();
+        if entries > threshold {
+            self.reaction_counts = Some(
+                std::mem::take(&mut self.reactions)
+                    .into_iter()
+                    .map(|(emoji, users)| (emoji, users.len()))
+                    .collect(),
+            );
+        }
+    }
 }
 
 pub trait IntoUsers {
@@ -291,13 +399,20 @@ impl IntoUsers for Message {
                     ids.push(id.clone());
                     ids.push(by.clone());
                 }
+                SystemMessage::UserKicked { id, by } | SystemMessage::UserBanned { id, by } => {
+                    ids.push(id.clone());
+                    ids.extend(by.clone());
+                }
                 SystemMessage::UserJoined { id, .. }
                 | SystemMessage::UserLeft { id, .. }
-                | SystemMessage::UserKicked { id, .. }
-                | SystemMessage::UserBanned { id, .. } => ids.push(id.clone()),
+                | SystemMessage::RoleExpired { id, .. } => ids.push(id.clone()),
                 SystemMessage::ChannelRenamed { by, .. }
                 | SystemMessage::ChannelDescriptionChanged { by, .. }
-                | SystemMessage::ChannelIconChanged { by, .. } => ids.push(by.clone()),
+                | SystemMessage::ChannelIconChanged { by, .. }
+                | SystemMessage::MessagePinned { by, .. }
+                | SystemMessage::MessageUnpinned { by, .. }
+                | SystemMessage::Poll { by, .. }
+                | SystemMessage::ThreadCreated { by, .. } => ids.push(by.clone()),
                 _ => {}
             }
         }
@@ -320,7 +435,7 @@ impl IntoUsers for Vec<Message> {
 impl SystemMessage {
     pub fn into_message(self, channel: String) -> Message {
         Message {
-            id: Ulid::new().to_string(),
+            id: ulid_generator::generate().to_string(),
             channel,
             author: "00000000000000000000000000".to_string(),
             system: Some(self),
@@ -348,6 +463,11 @@ impl From<SystemMessage> for String {
             SystemMessage::ChannelOwnershipChanged { .. } => {
                 "Channel ownership changed.".to_string()
             }
+            SystemMessage::MessagePinned { .. } => "Message pinned.".to_string(),
+            SystemMessage::MessageUnpinned { .. } => "Message unpinned.".to_string(),
+            SystemMessage::Poll { question, .. } => format!("Poll: {question}"),
+            SystemMessage::RoleExpired { .. } => "Role expired.".to_string(),
+            SystemMessage::ThreadCreated { name, .. } => format!("Thread started: {name}"),
         }
     }
 }
@@ -357,6 +477,11 @@ impl SendableEmbed {
         self.validate()
             .map_err(|error| Error::FailedValidation { error })?;
 
+        if let Some(colour) = &self.colour {
+            onechatsocial_database::Message::validate_embed_colour(colour)
+                .map_err(|_| Error::InvalidProperty)?;
+        }
+
         let media = if let Some(id) = self.media {
             Some(
                 db.find_and_use_attachment(&id, "attachments", "message", message_id)
@@ -381,32 +506,35 @@ impl BulkMessageResponse {
     pub async fn transform(
         db: &Database,
         channel: Option<&Channel>,
-        messages: Vec<Message>,
+        mut messages: Vec<Message>,
         user: &User,
         include_users: Option<bool>,
     ) -> Result<BulkMessageResponse> {
+        let user_ids = messages.get_user_ids();
+
+        // Role icons are resolved now so they follow changes to the authors' roles
+        let members = match channel {
+            Some(Channel::TextChannel { server, .. })
+            | Some(Channel::VoiceChannel { server, .. }) => {
+                let server = db.fetch_server(server).await?;
+                let members = db.fetch_members(&server.id, &user_ids).await?;
+                Message::resolve_role_icons(&mut messages, &server, &members);
+                Some(members)
+            }
+            _ => None,
+        };
+
         if let Some(true) = include_users {
-            let user_ids = messages.get_user_ids();
             let users = User::fetch_foreign_users(db, &user_ids)
                 .await?
                 .into_iter()
                 .map(|x| x.with_relationship(user))
                 .collect();
 
-            Ok(match channel {
-                Some(Channel::TextChannel { server, .. })
-                | Some(Channel::VoiceChannel { server, .. }) => {
-                    BulkMessageResponse::MessagesAndUsers {
-                        messages,
-                        users,
-                        members: Some(db.fetch_members(server, &user_ids).await?),
-                    }
-                }
-                _ => BulkMessageResponse::MessagesAndUsers {
-                    messages,
-                    users,
-                    members: None,
-                },
+            Ok(BulkMessageResponse::MessagesAndUsers {
+                messages,
+                users,
+                members,
             })
         } else {
             Ok(BulkMessageResponse::JustMessages(messages))
@@ -414,6 +542,75 @@ impl BulkMessageResponse {
     }
 }
 
+impl SearchResult {
+    /// Wrap a matched message, finding where the search terms appear in its content
+    pub fn new(message: Message, score: f64, query: &str) -> SearchResult {
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .filter(|term| !term.starts_with('-'))
+            .map(|term| {
+                term.trim_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase()
+            })
+            .filter(|term| !term.is_empty())
+            .collect();
+
+        let mut highlights = vec![];
+        if let Some(content) = &message.content {
+            let mut start = None;
+            for (index, c) in content
+                .char_indices()
+                .chain(std::iter::once((content.len(), ' ')))
+            {
+                if c.is_alphanumeric() {
+                    start.get_or_insert(index);
+                } else if let Some(start) = start.take() {
+                    // Text search matches on word stems, so treat terms as prefixes
+                    let word = content[start..index].to_lowercase();
+                    if terms.iter().any(|term| word.starts_with(term.as_str())) {
+                        highlights.push((start, index));
+                    }
+                }
+            }
+        }
+
+        SearchResult {
+            message,
+            highlights,
+            score,
+        }
+    }
+}
+
+impl BulkSearchResponse {
+    pub async fn transform(
+        db: &Database,
+        channel: Option<&Channel>,
+        results: Vec<SearchResult>,
+        user: &User,
+        include_users: Option<bool>,
+    ) -> Result<BulkSearchResponse> {
+        let messages = results
+            .iter()
+            .map(|result| result.message.clone())
+            .collect();
+
+        let response =
+            BulkMessageResponse::transform(db, channel, messages, user, include_users).await?;
+
+        Ok(match response {
+            BulkMessageResponse::JustMessages(_) => BulkSearchResponse::JustResults(results),
+            BulkMessageResponse::MessagesAndUsers { users, members, .. } => {
+                BulkSearchResponse::ResultsAndUsers {
+                    results,
+                    users,
+                    members,
+                }
+            }
+        })
+    }
+}
+
 impl Interactions {
     /// Validate interactions info is correct
     pub async fn validate(
@@ -453,7 +650,7 @@ impl Interactions {
 
     /// Check if default initialisation of fields
     pub fn is_default(&self) -> bool {
-        !self.restrict_reactions && self.reactions.is_none()
+        !self.restrict_reactions && self.reactions.is_none() && self.reaction_cooldowns.is_none()
     }
 }
 
@@ -481,8 +678,12 @@ impl DataMessageSend {
             throw_permission(permissions, Permission::SendEmbeds)?;
         };
 
-        if self.masquerade.is_some() {
+        if let Some(masquerade) = &self.masquerade {
             throw_permission(permissions, Permission::Masquerade)?;
+
+            if masquerade.colour.is_some() {
+                throw_permission(permissions, Permission::ManageRole)?;
+            }
         };
 
         if self.interactions.is_some() {
@@ -1,6 +1,11 @@
 use crate::{models::File, Database, Result};
 
 impl File {
+    /// Whether this file was marked as a spoiler by its uploader
+    pub fn is_spoiler(&self) -> bool {
+        self.filename.starts_with("SPOILER_")
+    }
+
     pub async fn use_attachment(db: &Database, id: &str, parent: &str) -> Result<File> {
         db.find_and_use_attachment(id, "attachments", "message", parent)
             .await
@@ -31,6 +36,11 @@ impl File {
             .await
     }
 
+    pub async fn use_role_icon(db: &Database, id: &str, parent: &str) -> Result<File> {
+        db.find_and_use_attachment(id, "icons", "object", parent)
+            .await
+    }
+
     pub async fn use_emoji(db: &Database, id: &str, parent: &str) -> Result<File> {
         db.find_and_use_attachment(id, "emojis", "object", parent)
             .await
@@ -1,5 +1,8 @@
 use std::collections::HashSet;
 
+use onechatsocial_database::tasks::server_webhook;
+use onechatsocial_models::v0::ServerWebhookEvent;
+use onechatsocial_presence::filter_online;
 use ulid::Ulid;
 
 use crate::{
@@ -8,11 +11,12 @@ use crate::{
         message::SystemMessage,
         server::{
             FieldsRole, FieldsServer, PartialRole, PartialServer, Role, SystemMessageChannels,
+            DEFAULT_ROLE_ID,
         },
         server_member::{MemberCompositeKey, RemovalIntention},
         Channel, Member, Server, ServerBan, User,
     },
-    perms, Database, Error, OverrideField, Permission, Result,
+    perms, Database, Error, Override, OverrideField, Permission, Result, Timestamp,
 };
 
 impl Role {
@@ -24,6 +28,8 @@ impl Role {
             colour: self.colour,
             hoist: Some(self.hoist),
             rank: Some(self.rank),
+            icon: self.icon,
+            member_count: self.member_count,
         }
     }
 
@@ -83,13 +89,21 @@ impl Role {
         .p(server_id.to_string())
         .await;
 
-        db.delete_role(server_id, role_id).await
+        db.delete_role(server_id, role_id).await?;
+
+        // The role holds the only reference to its icon
+        if let Some(icon) = self.icon {
+            db.mark_attachment_as_deleted(&icon.id).await.ok();
+        }
+
+        Ok(())
     }
 
     /// Remove field from Role
     pub fn remove(&mut self, field: &FieldsRole) {
         match field {
             FieldsRole::Colour => self.colour = None,
+            FieldsRole::Icon => self.icon = None,
         }
     }
 }
@@ -100,6 +114,28 @@ impl Server {
         db.insert_server(self).await
     }
 
+    /// Record an action taken in a server to its audit log
+    ///
+    /// The action has already been saved by the time it is recorded,
+    /// so failing to record it is logged rather than returned.
+    pub async fn record_audit_log(
+        db: &Database,
+        server_id: &str,
+        actor: &str,
+        action: onechatsocial_database::AuditLogAction,
+        target: Option<&str>,
+        changes: Vec<onechatsocial_database::AuditLogChange>,
+    ) {
+        let rvdb: onechatsocial_database::Database = db.clone().into();
+        if let Err(err) = onechatsocial_database::AuditLogEntry::record(
+            &rvdb, server_id, actor, action, target, changes,
+        )
+        .await
+        {
+            error!("Failed to record audit log entry in {server_id}: {err:?}");
+        }
+    }
+
     /// Update server data
     pub async fn update<'a>(
         &mut self,
@@ -137,6 +173,25 @@ impl Server {
         db.delete_server(&self).await
     }
 
+    /// Get the permissions every member of this server starts with
+    pub fn base_permissions(&self) -> u64 {
+        self.roles
+            .get(DEFAULT_ROLE_ID)
+            .map(|role| {
+                let v = Override::from(role.permissions);
+                v.allows() & !v.denies()
+            })
+            .unwrap_or(self.default_permissions as u64)
+    }
+
+    /// Count the roles of this server, not including the default role
+    pub fn role_count(&self) -> usize {
+        self.roles
+            .keys()
+            .filter(|id| id.as_str() != DEFAULT_ROLE_ID)
+            .count()
+    }
+
     /// Remove a field from Server
     pub fn remove(&mut self, field: &FieldsServer) {
         match field {
@@ -145,9 +200,26 @@ impl Server {
             FieldsServer::SystemMessages => self.system_messages = None,
             FieldsServer::Icon => self.icon = None,
             FieldsServer::Banner => self.banner = None,
+            FieldsServer::EmbedColour => self.embed_colour = None,
         }
     }
 
+    /// Count the members holding each role in this server
+    pub async fn count_role_members(&mut self, db: &Database) -> Result<()> {
+        let counts = db.fetch_role_member_counts(&self.id).await?;
+        for (id, role) in self.roles.iter_mut() {
+            role.member_count = Some(counts.get(id).copied().unwrap_or_default());
+        }
+
+        Ok(())
+    }
+
+    /// Count how many members of this server are currently online
+    pub async fn count_online_members(&self, db: &Database) -> Result<u64> {
+        let ids = db.fetch_member_ids(&self.id).await?;
+        Ok(filter_online(&ids).await.len() as u64)
+    }
+
     /// Set role permission on a server
     pub async fn set_role_permission(
         &mut self,
@@ -215,6 +287,16 @@ impl Server {
         .p(self.id.clone())
         .await;
 
+        server_webhook::dispatch(
+            &db.clone().into(),
+            &self.id,
+            ServerWebhookEvent::MemberJoin {
+                server: self.id.clone(),
+                user: user.id.clone(),
+            },
+        )
+        .await;
+
         EventV1::ServerCreate {
             id: self.id.clone(),
             server: self.clone(),
@@ -247,6 +329,7 @@ impl Server {
         db: &Database,
         member: Member,
         intention: RemovalIntention,
+        by: Option<&str>,
         silent: bool,
     ) -> Result<()> {
         db.delete_member(&member.id).await?;
@@ -255,9 +338,21 @@ impl Server {
             id: self.id.to_string(),
             user: member.id.user.clone(),
         }
-        .p(member.id.server)
+        .p(member.id.server.clone())
         .await;
 
+        if !matches!(intention, RemovalIntention::Ban) {
+            server_webhook::dispatch(
+                &db.clone().into(),
+                &self.id,
+                ServerWebhookEvent::MemberLeave {
+                    server: self.id.clone(),
+                    user: member.id.user.clone(),
+                },
+            )
+            .await;
+        }
+
         if !silent {
             if let Some(id) = self.system_messages.as_ref().and_then(|x| match intention {
                 RemovalIntention::Leave => x.user_left.as_ref(),
@@ -266,8 +361,14 @@ impl Server {
             }) {
                 match intention {
                     RemovalIntention::Leave => SystemMessage::UserLeft { id: member.id.user },
-                    RemovalIntention::Kick => SystemMessage::UserKicked { id: member.id.user },
-                    RemovalIntention::Ban => SystemMessage::UserBanned { id: member.id.user },
+                    RemovalIntention::Kick => SystemMessage::UserKicked {
+                        id: member.id.user,
+                        by: by.map(str::to_string),
+                    },
+                    RemovalIntention::Ban => SystemMessage::UserBanned {
+                        id: member.id.user,
+                        by: by.map(str::to_string),
+                    },
                 }
                 .into_message(id.to_string())
                 .create_no_web_push(db, id, false)
@@ -280,14 +381,37 @@ impl Server {
     }
 
     /// Create ban
+    ///
+    /// Banning a user who is already banned replaces the existing ban.
     pub async fn ban_user(
         self,
         db: &Database,
         id: MemberCompositeKey,
         reason: Option<String>,
+        moderator: &str,
+        expires_at: Option<Timestamp>,
     ) -> Result<ServerBan> {
-        let ban = ServerBan { id, reason };
-        db.insert_ban(&ban).await?;
+        let ban = ServerBan {
+            id,
+            reason,
+            moderator: Some(moderator.to_string()),
+            expires_at,
+        };
+
+        // Banning a user again replaces their existing ban
+        db.upsert_ban(&ban).await?;
+
+        server_webhook::dispatch(
+            &db.clone().into(),
+            &self.id,
+            ServerWebhookEvent::MemberBan {
+                server: self.id.clone(),
+                user: ban.id.user.clone(),
+                reason: ban.reason.clone(),
+            },
+        )
+        .await;
+
         Ok(ban)
     }
 
@@ -297,11 +421,13 @@ impl Server {
         db: &Database,
         member: Member,
         reason: Option<String>,
+        by: &str,
+        expires_at: Option<Timestamp>,
     ) -> Result<ServerBan> {
-        self.remove_member(db, member.clone(), RemovalIntention::Ban, false)
+        self.remove_member(db, member.clone(), RemovalIntention::Ban, Some(by), false)
             .await?;
 
-        self.ban_user(db, member.id, reason).await
+        self.ban_user(db, member.id, reason, by, expires_at).await
     }
 }
 
@@ -325,6 +451,10 @@ impl SystemMessageChannels {
             ids.insert(id);
         }
 
+        if let Some(id) = self.role_expired {
+            ids.insert(id);
+        }
+
         ids
     }
 }
@@ -1 +1,17 @@
+use crate::{events::client::EventV1, models::ServerBan, Database, Result};
 
+impl ServerBan {
+    /// Lift this ban, allowing the user to join the server again
+    pub async fn lift(self, db: &Database) -> Result<()> {
+        db.delete_ban(&self.id).await?;
+
+        EventV1::ServerMemberUnban {
+            id: self.id.server.clone(),
+            user: self.id.user,
+        }
+        .p(self.id.server)
+        .await;
+
+        Ok(())
+    }
+}
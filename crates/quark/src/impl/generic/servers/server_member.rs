@@ -6,7 +6,7 @@ use crate::{
     events::client::EventV1,
     models::{
         server_member::{FieldsMember, MemberCompositeKey, MemberWithRoles, PartialMember},
-        Member, Server,
+        File, Member, Server,
     },
     Database, Result,
 };
@@ -23,6 +23,7 @@ impl Member {
             avatar: None,
             roles: vec![],
             timeout: None,
+            timed_roles: None,
         }
     }
 
@@ -66,6 +67,16 @@ impl Member {
         value
     }
 
+    /// Get the icon of this member's highest ranking hoisted role which has one
+    pub fn get_role_icon<'a>(&self, server: &'a Server) -> Option<&'a File> {
+        self.roles
+            .iter()
+            .filter_map(|role| server.roles.get(role))
+            .filter(|role| role.hoist && role.icon.is_some())
+            .min_by_key(|role| role.rank)
+            .and_then(|role| role.icon.as_ref())
+    }
+
     /// Check whether this member is in timeout
     pub fn in_timeout(&self) -> bool {
         if let Some(timeout) = self.timeout {
@@ -81,6 +92,7 @@ impl Member {
             FieldsMember::Nickname => self.nickname = None,
             FieldsMember::Roles => self.roles.clear(),
             FieldsMember::Timeout => self.timeout = None,
+            FieldsMember::TimedRoles => self.timed_roles = None,
         }
     }
 }
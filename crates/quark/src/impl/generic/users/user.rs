@@ -23,7 +23,7 @@ impl User {
     pub async fn update<'a>(
         &mut self,
         db: &Database,
-        partial: PartialUser,
+        mut partial: PartialUser,
         remove: Vec<FieldsUser>,
     ) -> Result<()> {
         for field in &remove {
@@ -34,6 +34,21 @@ impl User {
 
         db.update_user(&self.id, &partial, remove.clone()).await?;
 
+        // Privacy settings are only sent to the user themselves
+        if let Some(hide_read_receipts) = partial.hide_read_receipts.take() {
+            EventV1::UserUpdate {
+                id: self.id.clone(),
+                data: PartialUser {
+                    hide_read_receipts: Some(hide_read_receipts),
+                    ..Default::default()
+                },
+                clear: vec![],
+                event_id: Some(ulid::Ulid::new().to_string()),
+            }
+            .private(self.id.clone())
+            .await;
+        }
+
         EventV1::UserUpdate {
             id: self.id.clone(),
             data: partial,
@@ -113,6 +128,7 @@ impl User {
             .into_iter()
             .map(|mut user| {
                 user.online = Some(online_ids.contains(&user.id));
+                user.hide_read_receipts = false;
                 user.foreign()
             })
             .collect::<Vec<User>>())
@@ -127,6 +143,10 @@ impl User {
             user.relationship = Some(get_relationship(perspective, &user.id));
         }
 
+        if user.id != perspective.id {
+            user.hide_read_receipts = false;
+        }
+
         user
     }
 
@@ -1,18 +1,98 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
-use bson::{from_document, Document};
+use bson::{from_document, Bson, Document};
 use futures::StreamExt;
+use iso8601_timestamp::Timestamp;
+use once_cell::sync::Lazy;
+use onechatsocial_database::{
+    tasks::{process_embeds, server_webhook},
+    util::permission_cache,
+};
 
 use crate::{
-    models::stats::{Index, Stats},
+    models::stats::{CollectionSize, CollectionSizes, Index, Stats},
     AbstractStats, Error, Result,
 };
 
 use super::super::MongoDb;
 
+/// Collections whose size is reported
+static SIZED_COLLECTIONS: [&str; 5] = ["messages", "users", "servers", "channels", "attachments"];
+
+/// How long computed collection sizes are reused for
+static COLLECTION_SIZES_TTL: Duration = Duration::from_secs(300);
+
+/// Most recently computed collection sizes
+static COLLECTION_SIZES: Lazy<Mutex<Option<(Instant, CollectionSizes)>>> =
+    Lazy::new(|| Mutex::new(None));
+
+/// Read a numeric field which MongoDB may return as any number type
+fn number(document: &Document, key: &str) -> u64 {
+    match document.get(key) {
+        Some(Bson::Int32(value)) => *value as u64,
+        Some(Bson::Int64(value)) => *value as u64,
+        Some(Bson::Double(value)) => *value as u64,
+        _ => 0,
+    }
+}
+
+impl MongoDb {
+    /// Fetch collection sizes, using the cached sizes unless stale or `refresh` is set
+    async fn collection_sizes(&self, refresh: bool) -> Result<CollectionSizes> {
+        if !refresh {
+            let cached = COLLECTION_SIZES
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            if let Some((computed, sizes)) = cached.as_ref() {
+                if computed.elapsed() < COLLECTION_SIZES_TTL {
+                    return Ok(sizes.clone());
+                }
+            }
+        }
+
+        let mut collections = HashMap::new();
+        for collection in SIZED_COLLECTIONS {
+            let stats = self
+                .db()
+                .run_command(doc! { "collStats": collection }, None)
+                .await
+                .map_err(|_| Error::DatabaseError {
+                    operation: "run_command",
+                    with: "collStats",
+                })?;
+
+            collections.insert(
+                collection.to_string(),
+                CollectionSize {
+                    count: number(&stats, "count"),
+                    size: number(&stats, "size"),
+                    storage_size: number(&stats, "storageSize"),
+                },
+            );
+        }
+
+        let sizes = CollectionSizes {
+            collections,
+            generated_at: Timestamp::now_utc(),
+        };
+
+        COLLECTION_SIZES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .replace((Instant::now(), sizes.clone()));
+
+        Ok(sizes)
+    }
+}
+
 #[async_trait]
 impl AbstractStats for MongoDb {
-    async fn generate_stats(&self) -> Result<Stats> {
+    async fn generate_stats(&self, refresh: bool) -> Result<Stats> {
         let mut indices = HashMap::new();
         let mut coll_stats = HashMap::new();
 
@@ -85,6 +165,10 @@ impl AbstractStats for MongoDb {
         Ok(Stats {
             indices,
             coll_stats,
+            collection_sizes: self.collection_sizes(refresh).await?,
+            embed_breaker: process_embeds::breaker_state().into(),
+            permission_cache: permission_cache::cache_state().into(),
+            server_webhooks: server_webhook::stats().into(),
         })
     }
 }
@@ -54,6 +54,24 @@ impl MongoDb {
                 operation: "delete_many",
                 with: "webhooks",
             })
+            .map(|_| ())?;
+
+        // Delete all follows to or from these channels.
+        self.col::<Document>("channel_follows")
+            .delete_many(
+                doc! {
+                    "$or": [
+                        { "channel": &id },
+                        { "follower": &id }
+                    ]
+                },
+                None,
+            )
+            .await
+            .map_err(|_| Error::DatabaseError {
+                operation: "delete_many",
+                with: "channel_follows",
+            })
             .map(|_| ())
     }
 }
@@ -152,6 +170,12 @@ impl AbstractChannel for MongoDb {
                     }
                 }
 
+                if let Some(cid) = &sys.role_expired {
+                    if &id == cid {
+                        unset.insert("system_messages.role_expired", 1_i32);
+                    }
+                }
+
                 if !unset.is_empty() {
                     update.insert("$unset", unset);
                 }
@@ -312,6 +336,7 @@ impl IntoDocumentPath for FieldsChannel {
             FieldsChannel::DefaultPermissions => "default_permissions",
             FieldsChannel::Description => "description",
             FieldsChannel::Icon => "icon",
+            FieldsChannel::SlowmodeSeconds => "slowmode_seconds",
         })
     }
 }
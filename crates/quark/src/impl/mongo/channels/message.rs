@@ -1,9 +1,10 @@
 use bson::{to_bson, Document};
-use futures::try_join;
+use futures::{try_join, StreamExt};
 use mongodb::options::FindOptions;
 
 use crate::models::message::{
-    AppendMessage, Message, MessageQuery, MessageSort, MessageTimePeriod, PartialMessage,
+    AppendMessage, Message, MessageFilter, MessageHas, MessageQuery, MessageSort,
+    MessageTimePeriod, PartialMessage, SearchCursor, SearchResult,
 };
 use crate::r#impl::mongo::DocumentId;
 use crate::{AbstractMessage, Error, Result};
@@ -12,6 +13,83 @@ use super::super::MongoDb;
 
 static COL: &str = "messages";
 
+/// Build the document matching messages for the given filter
+fn build_filter(filter: MessageFilter) -> Document {
+    let mut document = doc! {};
+
+    if let Some(channel) = filter.channel {
+        document.insert("channel", channel);
+    }
+
+    if let Some(author) = filter.author {
+        document.insert("author", author);
+    }
+
+    if let Some(pinned) = filter.pinned {
+        if pinned {
+            document.insert("pinned", true);
+        } else {
+            document.insert(
+                "pinned",
+                doc! {
+                    "$ne": true
+                },
+            );
+        }
+    }
+
+    if let Some(has) = filter.has {
+        for has in has {
+            match has {
+                MessageHas::Attachment => {
+                    document.insert(
+                        "attachments.0",
+                        doc! {
+                            "$exists": true
+                        },
+                    );
+                }
+                MessageHas::Embed => {
+                    document.insert(
+                        "embeds.0",
+                        doc! {
+                            "$exists": true
+                        },
+                    );
+                }
+                MessageHas::Link => {
+                    document.insert(
+                        "content",
+                        doc! {
+                            "$regex": "https?://"
+                        },
+                    );
+                }
+                MessageHas::Reaction => {
+                    document.insert(
+                        "reactions",
+                        doc! {
+                            "$exists": true,
+                            "$ne": {}
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(query) = filter.query {
+        document.insert(
+            "$text",
+            doc! {
+                "$search": query
+            },
+        );
+    }
+
+    document
+}
+
 impl MongoDb {
     pub async fn delete_bulk_messages(&self, projection: Document) -> Result<()> {
         let mut for_attachments = projection.clone();
@@ -142,29 +220,9 @@ impl AbstractMessage for MongoDb {
     }
 
     async fn fetch_messages(&self, query: MessageQuery) -> Result<Vec<Message>> {
-        let mut filter = doc! {};
-
         // 1. Apply message filters
-        if let Some(channel) = query.filter.channel {
-            filter.insert("channel", channel);
-        }
-
-        if let Some(author) = query.filter.author {
-            filter.insert("author", author);
-        }
-
-        let is_search_query = if let Some(query) = query.filter.query {
-            filter.insert(
-                "$text",
-                doc! {
-                    "$search": query
-                },
-            );
-
-            true
-        } else {
-            false
-        };
+        let mut filter = build_filter(query.filter);
+        let is_search_query = filter.contains_key("$text");
 
         // 2. Find query limit
         let limit = query.limit.unwrap_or(50);
@@ -275,6 +333,123 @@ impl AbstractMessage for MongoDb {
         }
     }
 
+    async fn search_messages(
+        &self,
+        query: MessageQuery,
+        cursor: Option<SearchCursor>,
+    ) -> Result<Vec<SearchResult>> {
+        let Some(search) = query.filter.query.clone() else {
+            return Err(Error::InvalidOperation);
+        };
+
+        let MessageTimePeriod::Absolute {
+            before,
+            after,
+            sort,
+        } = query.time_period
+        else {
+            return Err(Error::InvalidOperation);
+        };
+
+        // 1. Apply message filters and find text match score
+        let mut pipeline = vec![
+            doc! {
+                "$match": build_filter(query.filter)
+            },
+            doc! {
+                "$addFields": {
+                    "score": {
+                        "$meta": "textScore"
+                    }
+                }
+            },
+        ];
+
+        // 2. Apply pagination and sort
+        match sort.unwrap_or(MessageSort::Latest) {
+            MessageSort::Relevance => {
+                // Continue after the last result of the previous page
+                if let Some(SearchCursor { score, id }) = cursor {
+                    pipeline.push(doc! {
+                        "$match": {
+                            "$or": [
+                                {
+                                    "score": {
+                                        "$lt": score
+                                    }
+                                },
+                                {
+                                    "score": score,
+                                    "_id": {
+                                        "$lt": id
+                                    }
+                                }
+                            ]
+                        }
+                    });
+                }
+
+                pipeline.push(doc! {
+                    "$sort": {
+                        "score": -1_i32,
+                        "_id": -1_i32
+                    }
+                });
+            }
+            sort => {
+                if let Some(doc) = match (before, after) {
+                    (Some(before), Some(after)) => Some(doc! {
+                        "$lt": before,
+                        "$gt": after
+                    }),
+                    (Some(before), _) => Some(doc! {
+                        "$lt": before
+                    }),
+                    (_, Some(after)) => Some(doc! {
+                        "$gt": after
+                    }),
+                    _ => None,
+                } {
+                    pipeline.push(doc! {
+                        "$match": {
+                            "_id": doc
+                        }
+                    });
+                }
+
+                pipeline.push(doc! {
+                    "$sort": {
+                        "_id": if let MessageSort::Oldest = sort { 1_i32 } else { -1_i32 }
+                    }
+                });
+            }
+        }
+
+        pipeline.push(doc! {
+            "$limit": query.limit.unwrap_or(50)
+        });
+
+        // 3. Execute and find highlights
+        Ok(self
+            .col::<Document>(COL)
+            .aggregate(pipeline, None)
+            .await
+            .map_err(|_| Error::DatabaseError {
+                operation: "aggregate",
+                with: "messages",
+            })?
+            .filter_map(|s| async { s.ok() })
+            .collect::<Vec<Document>>()
+            .await
+            .into_iter()
+            .filter_map(|document| {
+                let score = document.get_f64("score").ok()?;
+                let message = bson::from_document::<Message>(document).ok()?;
+                Some(SearchResult::new(message, score, &search))
+            })
+            .collect())
+    }
+
     /// Add a new reaction to a message
     async fn add_reaction(&self, id: &str, emoji: &str, user: &str) -> Result<()> {
         self.col::<Document>(COL)
@@ -66,6 +66,20 @@ impl MongoDb {
                 })?;
         }
 
+        // Delete the server's name filter.
+        self.col::<Document>("server_name_filters")
+            .delete_one(
+                doc! {
+                    "_id": &server.id
+                },
+                None,
+            )
+            .await
+            .map_err(|_| Error::DatabaseError {
+                operation: "delete_one",
+                with: "server_name_filters",
+            })?;
+
         // Update many attachments with parent id.
         self.delete_many_attachments(doc! {
             "object_id": &server.id
@@ -227,6 +241,7 @@ impl IntoDocumentPath for FieldsServer {
             FieldsServer::Banner => "banner",
             FieldsServer::Categories => "categories",
             FieldsServer::Description => "description",
+            FieldsServer::EmbedColour => "embed_colour",
             FieldsServer::Icon => "icon",
             FieldsServer::SystemMessages => "system_messages",
         })
@@ -237,6 +252,7 @@ impl IntoDocumentPath for FieldsRole {
     fn as_path(&self) -> Option<&'static str> {
         Some(match self {
             FieldsRole::Colour => "colour",
+            FieldsRole::Icon => "icon",
         })
     }
 }
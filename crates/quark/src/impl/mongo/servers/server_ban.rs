@@ -1,6 +1,8 @@
+use mongodb::options::ReplaceOptions;
+
 use crate::models::server_member::MemberCompositeKey;
 use crate::models::ServerBan;
-use crate::{AbstractServerBan, Result};
+use crate::{AbstractServerBan, Error, Result};
 
 use super::super::MongoDb;
 
@@ -33,6 +35,24 @@ impl AbstractServerBan for MongoDb {
         self.insert_one(COL, ban).await.map(|_| ())
     }
 
+    async fn upsert_ban(&self, ban: &ServerBan) -> Result<()> {
+        self.col::<ServerBan>(COL)
+            .replace_one(
+                doc! {
+                    "_id.server": &ban.id.server,
+                    "_id.user": &ban.id.user
+                },
+                ban,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map(|_| ())
+            .map_err(|_| Error::DatabaseError {
+                operation: "replace_one",
+                with: "server_bans",
+            })
+    }
+
     async fn delete_ban(&self, id: &MemberCompositeKey) -> Result<()> {
         self.delete_one(
             COL,
@@ -1,4 +1,8 @@
+use std::collections::HashMap;
+
 use bson::Document;
+use futures::StreamExt;
+use mongodb::options::FindOptions;
 
 use super::super::MongoDb;
 use crate::models::server_member::MemberWithRoles;
@@ -132,6 +136,77 @@ impl AbstractServerMember for MongoDb {
                 with: "server_members",
             })
     }
+
+    async fn fetch_role_member_counts(&self, server: &str) -> Result<HashMap<String, u64>> {
+        Ok(self
+            .col::<Document>(COL)
+            .aggregate(
+                vec![
+                    doc! {
+                        "$match": {
+                            "_id.server": server
+                        }
+                    },
+                    doc! {
+                        "$unwind": "$roles"
+                    },
+                    doc! {
+                        "$group": {
+                            "_id": "$roles",
+                            "count": {
+                                "$sum": 1_i64
+                            }
+                        }
+                    },
+                ],
+                None,
+            )
+            .await
+            .map_err(|_| Error::DatabaseError {
+                operation: "aggregate",
+                with: "server_members",
+            })?
+            .filter_map(|s| async { s.ok() })
+            .collect::<Vec<Document>>()
+            .await
+            .into_iter()
+            .filter_map(|x| {
+                let id = x.get_str("_id").ok()?.to_string();
+                let count = x.get_i64("count").ok()?;
+                Some((id, count as u64))
+            })
+            .collect())
+    }
+
+    async fn fetch_member_ids(&self, server: &str) -> Result<Vec<String>> {
+        Ok(self
+            .col::<Document>(COL)
+            .find(
+                doc! {
+                    "_id.server": server
+                },
+                FindOptions::builder()
+                    .projection(doc! { "_id.user": 1_i32 })
+                    .build(),
+            )
+            .await
+            .map_err(|_| Error::DatabaseError {
+                operation: "find",
+                with: "server_members",
+            })?
+            .filter_map(|s| async { s.ok() })
+            .collect::<Vec<Document>>()
+            .await
+            .into_iter()
+            .filter_map(|x| {
+                x.get_document("_id")
+                    .ok()?
+                    .get_str("user")
+                    .ok()
+                    .map(|id| id.to_string())
+            })
+            .collect())
+    }
 }
 
 impl IntoDocumentPath for FieldsMember {
@@ -141,6 +216,7 @@ impl IntoDocumentPath for FieldsMember {
             FieldsMember::Nickname => "nickname",
             FieldsMember::Roles => "roles",
             FieldsMember::Timeout => "timeout",
+            FieldsMember::TimedRoles => "timed_roles",
         })
     }
 }
@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 
 use iso8601_timestamp::Timestamp;
+use onechatsocial_database::{
+    tasks::{process_embeds::BreakerState, server_webhook::ServerWebhookStats},
+    util::permission_cache::PermissionCacheState,
+};
 use serde::{Deserialize, Serialize};
 
 /// Index access information
@@ -111,6 +115,99 @@ pub struct CollectionStats {
     count: u64,
 }
 
+/// Approximate size of a collection
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionSize {
+    /// Number of documents in collection
+    pub count: u64,
+
+    /// Uncompressed data size
+    pub size: u64,
+
+    /// Data size on disk
+    pub storage_size: u64,
+}
+
+/// Document counts and storage sizes of the largest collections
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct CollectionSizes {
+    /// Size of each collection
+    pub collections: HashMap<String, CollectionSize>,
+
+    /// Time at which these sizes were computed
+    pub generated_at: Timestamp,
+}
+
+/// Embed generation circuit breaker state
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct EmbedBreakerStats {
+    /// Whether embed generation is currently paused
+    open: bool,
+
+    /// Number of consecutive failures to reach January
+    consecutive_failures: usize,
+
+    /// Number of times the breaker has tripped since startup
+    trips: usize,
+}
+
+impl From<BreakerState> for EmbedBreakerStats {
+    fn from(state: BreakerState) -> Self {
+        EmbedBreakerStats {
+            open: state.open,
+            consecutive_failures: state.consecutive_failures,
+            trips: state.trips,
+        }
+    }
+}
+
+/// Channel permission cache usage
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct PermissionCacheStats {
+    /// Number of calculations answered from the cache
+    hits: u64,
+
+    /// Number of calculations which had to be done again
+    misses: u64,
+
+    /// Number of entries currently cached
+    entries: usize,
+}
+
+impl From<PermissionCacheState> for PermissionCacheStats {
+    fn from(state: PermissionCacheState) -> Self {
+        PermissionCacheStats {
+            hits: state.hits,
+            misses: state.misses,
+            entries: state.entries,
+        }
+    }
+}
+
+/// Server event webhook deliveries since startup
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+pub struct ServerWebhookDeliveryStats {
+    /// Number of events delivered
+    delivered: usize,
+
+    /// Number of failed attempts which were retried
+    retried: usize,
+
+    /// Number of events dropped after running out of attempts
+    failed: usize,
+}
+
+impl From<ServerWebhookStats> for ServerWebhookDeliveryStats {
+    fn from(stats: ServerWebhookStats) -> Self {
+        ServerWebhookDeliveryStats {
+            delivered: stats.delivered,
+            retried: stats.retried,
+            failed: stats.failed,
+        }
+    }
+}
+
 /// Server Stats
 #[derive(Serialize, JsonSchema, Debug)]
 pub struct Stats {
@@ -119,4 +216,16 @@ pub struct Stats {
 
     /// Collection stats
     pub coll_stats: HashMap<String, CollectionStats>,
+
+    /// Cached document counts and storage sizes
+    pub collection_sizes: CollectionSizes,
+
+    /// Embed generation circuit breaker state
+    pub embed_breaker: EmbedBreakerStats,
+
+    /// Channel permission cache usage
+    pub permission_cache: PermissionCacheStats,
+
+    /// Server event webhook deliveries
+    pub server_webhooks: ServerWebhookDeliveryStats,
 }
@@ -101,6 +101,47 @@ pub enum Channel {
         /// Whether this channel is marked as not safe for work
         #[serde(skip_serializing_if = "if_false", default)]
         nsfw: bool,
+
+        /// Minimum number of seconds between messages from each member
+        #[serde(skip_serializing_if = "Option::is_none")]
+        slowmode_seconds: Option<u16>,
+        /// Whether slowmode also applies to webhooks and system messages
+        #[serde(skip_serializing_if = "if_false", default)]
+        slowmode_applies_to_bots: bool,
+        /// Ids of roles whose members are exempt from slowmode
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        slowmode_exempt_roles: Vec<String>,
+
+        /// Whether only members who can manage messages may send messages,
+        /// everyone else may only read and react
+        #[serde(skip_serializing_if = "if_false", default)]
+        reactions_only: bool,
+
+        /// Whether link previews should not be generated for messages in this channel
+        #[serde(skip_serializing_if = "if_false", default)]
+        disable_embeds: bool,
+
+        /// Whether other channels may follow this channel to have its messages cross-posted
+        #[serde(skip_serializing_if = "if_false", default)]
+        announcement: bool,
+
+        /// Id of the channel this thread was started in
+        #[serde(skip_serializing_if = "Option::is_none")]
+        parent: Option<String>,
+        /// Id of the message this thread was started from
+        #[serde(skip_serializing_if = "Option::is_none")]
+        root_message: Option<String>,
+        /// Whether this thread was archived after going idle
+        #[serde(skip_serializing_if = "if_false", default)]
+        archived: bool,
+
+        /// Ids of pinned messages in their manually arranged order,
+        /// pinned messages are listed by pin time if empty
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pin_order: Vec<String>,
+        /// Id of the last message sent in any thread started in this channel
+        #[serde(skip_serializing_if = "Option::is_none")]
+        last_thread_message_id: Option<String>,
     },
     /// Voice channel belonging to a server
     VoiceChannel {
@@ -158,6 +199,24 @@ pub struct PartialChannel {
     pub default_permissions: Option<OverrideField>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_message_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slowmode_exempt_roles: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slowmode_seconds: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slowmode_applies_to_bots: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reactions_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_embeds: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub announcement: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin_order: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_thread_message_id: Option<String>,
 }
 
 /// Optional fields on channel object
@@ -166,4 +225,5 @@ pub enum FieldsChannel {
     Description,
     Icon,
     DefaultPermissions,
+    SlowmodeSeconds,
 }
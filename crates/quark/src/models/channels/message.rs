@@ -21,6 +21,11 @@ pub fn if_false(t: &bool) -> bool {
     !t
 }
 
+/// Utility function to check if an u32 is zero
+pub fn if_zero_u32(t: &u32) -> bool {
+    t == &0
+}
+
 pub static RE_MENTION: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"<@([0-9A-HJKMNP-TV-Z]{26})>").unwrap());
 
@@ -66,9 +71,17 @@ pub enum SystemMessage {
     #[serde(rename = "user_left")]
     UserLeft { id: String },
     #[serde(rename = "user_kicked")]
-    UserKicked { id: String },
+    UserKicked {
+        id: String,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        by: Option<String>,
+    },
     #[serde(rename = "user_banned")]
-    UserBanned { id: String },
+    UserBanned {
+        id: String,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        by: Option<String>,
+    },
     #[serde(rename = "channel_renamed")]
     ChannelRenamed { name: String, by: String },
     #[serde(rename = "channel_description_changed")]
@@ -77,6 +90,24 @@ pub enum SystemMessage {
     ChannelIconChanged { by: String },
     #[serde(rename = "channel_ownership_changed")]
     ChannelOwnershipChanged { from: String, to: String },
+    #[serde(rename = "message_pinned")]
+    MessagePinned { id: String, by: String },
+    #[serde(rename = "message_unpinned")]
+    MessageUnpinned { id: String, by: String },
+    #[serde(rename = "poll")]
+    Poll {
+        question: String,
+        options: Vec<String>,
+        by: String,
+    },
+    #[serde(rename = "role_expired")]
+    RoleExpired { id: String, role: String },
+    #[serde(rename = "thread_created")]
+    ThreadCreated {
+        id: String,
+        name: String,
+        by: String,
+    },
 }
 
 /// Name and / or avatar override information
@@ -109,6 +140,55 @@ pub struct Interactions {
     /// Can only be set to true if reactions list is of at least length 1
     #[serde(skip_serializing_if = "if_false", default)]
     pub restrict_reactions: bool,
+    /// Seconds a user must wait before adding a given reaction again
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub reaction_cooldowns: Option<IndexMap<String, u64>>,
+}
+
+/// Reference to a message in another channel
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct MessageReference {
+    /// Id of the channel the message was sent in
+    pub channel: String,
+    /// Id of the message
+    pub message: String,
+    /// Id of the user or webhook that sent the message
+    pub author: String,
+}
+
+/// Copy of a replied message taken when the reply was sent
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct MessageSnapshot {
+    /// Id of the message
+    pub message: String,
+    /// Id of the user or webhook that sent the message
+    pub author: String,
+    /// Message content, truncated to the configured length
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// Poll which users can vote on
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct Poll {
+    /// Options which can be voted for
+    pub options: Vec<PollOption>,
+    /// Whether users may vote for more than one option
+    #[serde(skip_serializing_if = "if_false", default)]
+    pub multi_select: bool,
+    /// Time after which votes are no longer accepted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closes_at: Option<Timestamp>,
+}
+
+/// Option of a poll
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct PollOption {
+    /// Name of this option
+    pub name: String,
+    /// Ids of users who voted for this option
+    #[serde(default)]
+    pub voters: IndexSet<String>,
 }
 
 /// Representation of a Message on Revolt
@@ -140,27 +220,74 @@ pub struct Message {
     /// Array of attachments
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attachments: Option<Vec<File>>,
+    /// Array of sticker ids included in this message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stickers: Option<Vec<String>>,
     /// Time at which this message was last edited
     #[serde(skip_serializing_if = "Option::is_none")]
     pub edited: Option<Timestamp>,
+    /// Id of the user who last edited this message, if not the author
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edited_by: Option<String>,
     /// Attached embeds to this message
     #[serde(skip_serializing_if = "Option::is_none")]
     pub embeds: Option<Vec<Embed>>,
     /// Array of user ids mentioned in this message
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mentions: Option<Vec<String>>,
+    /// Array of role ids mentioned in this message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role_mentions: Option<Vec<String>>,
     /// Array of message ids this message is replying to
     #[serde(skip_serializing_if = "Option::is_none")]
     pub replies: Option<Vec<String>>,
+    /// Copies of the replied messages taken when this message was sent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_snapshots: Option<Vec<MessageSnapshot>>,
     /// Hashmap of emoji IDs to array of user IDs
     #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
     pub reactions: IndexMap<String, IndexSet<String>>,
+    /// Hashmap of emoji IDs to number of reactions
+    ///
+    /// Sent in place of `reactions` for messages with a large number of reactions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reaction_counts: Option<IndexMap<String, usize>>,
     /// Information about how this message should be interacted with
     #[serde(skip_serializing_if = "Interactions::is_default", default)]
     pub interactions: Interactions,
     /// Name and / or avatar overrides for this message
     #[serde(skip_serializing_if = "Option::is_none")]
     pub masquerade: Option<Masquerade>,
+    /// Id of the icon of the author's hoisted role, resolved when messages are fetched
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub role_icon: Option<String>,
+    /// Whether this message contains spoilers in its content or attachments
+    #[serde(skip_serializing_if = "if_false", default)]
+    pub has_spoilers: bool,
+    /// BCP-47 language tag of this message's content, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Number of replies to this message
+    #[serde(skip_serializing_if = "if_zero_u32", default)]
+    pub reply_count: u32,
+    /// Number of distinct users who replied to this message
+    #[serde(skip_serializing_if = "if_zero_u32", default)]
+    pub thread_participant_count: u32,
+    /// Whether this message is pinned in its channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<bool>,
+    /// Poll attached to this message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll: Option<Poll>,
+    /// Time at which this message will be deleted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<Timestamp>,
+    /// Message this message was forwarded from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forwarded_from: Option<MessageReference>,
+    /// Bitfield of message flags
+    #[serde(skip_serializing_if = "if_zero_u32", default)]
+    pub flags: u32,
 }
 
 /// # Message Sort
@@ -178,6 +305,22 @@ pub enum MessageSort {
     Oldest,
 }
 
+/// # Message Has
+///
+/// Content a message must have to be matched
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "rocket_impl", derive(FromFormField))]
+pub enum MessageHas {
+    /// Message has at least one attachment
+    Attachment,
+    /// Message has at least one embed
+    Embed,
+    /// Message content contains a link
+    Link,
+    /// Message has at least one reaction
+    Reaction,
+}
+
 /// # Message Time Period
 ///
 /// Filter and sort messages by time
@@ -211,6 +354,10 @@ pub struct MessageFilter {
     pub author: Option<String>,
     /// Search query
     pub query: Option<String>,
+    /// Whether the message is pinned
+    pub pinned: Option<bool>,
+    /// Content the message must have
+    pub has: Option<Vec<MessageHas>>,
 }
 
 /// # Message Query
@@ -249,6 +396,50 @@ pub enum BulkMessageResponse {
     },
 }
 
+/// # Search Cursor
+///
+/// Last result of the previous page of a relevance sorted search
+#[derive(Validate, Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct SearchCursor {
+    /// Text match score of the last result
+    pub score: f64,
+    /// Id of the last result
+    #[validate(length(min = 26, max = 26))]
+    pub id: String,
+}
+
+/// # Search Result
+#[derive(Serialize, JsonSchema, Debug, Clone)]
+pub struct SearchResult {
+    /// Matched message
+    pub message: Message,
+    /// Byte offsets (start, end) of matched terms in the message content
+    pub highlights: Vec<(usize, usize)>,
+    /// Text match score, higher is more relevant
+    pub score: f64,
+}
+
+/// # Bulk Search Response
+///
+/// Response used when searching for messages
+#[derive(Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum BulkSearchResponse {
+    JustResults(
+        /// List of results
+        Vec<SearchResult>,
+    ),
+    ResultsAndUsers {
+        /// List of results
+        results: Vec<SearchResult>,
+        /// List of users
+        users: Vec<User>,
+        /// List of members
+        #[serde(skip_serializing_if = "Option::is_none")]
+        members: Option<Vec<Member>>,
+    },
+}
+
 /// # Appended Information
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AppendMessage {
@@ -1,5 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+/// Utility function to check if a boolean value is false
+pub fn if_false(t: &bool) -> bool {
+    !t
+}
+
 /// Metadata associated with file
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Default)]
 #[serde(tag = "type")]
@@ -34,6 +39,18 @@ pub struct File {
     pub content_type: String,
     /// Size of this file (in bytes)
     pub size: isize,
+    /// Alternative text describing this file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether this file is a recorded voice message
+    #[serde(skip_serializing_if = "if_false", default)]
+    pub is_voice: bool,
+    /// Waveform of a voice message, one amplitude sample per byte
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub waveform: Option<Vec<u8>>,
+    /// Length of a voice message in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u32>,
 
     /// Whether this file was deleted
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -6,6 +6,12 @@ use validator::Validate;
 
 use crate::{models::attachment::File, OverrideField};
 
+/// Id of the role every member of a server implicitly has
+pub const DEFAULT_ROLE_ID: &str = "default";
+
+/// Rank of the default role, which no other role may take
+pub const DEFAULT_ROLE_RANK: i64 = i64::MAX;
+
 /// Utility function to check if a boolean value is false
 pub fn if_false(t: &bool) -> bool {
     !t
@@ -33,6 +39,12 @@ pub struct Role {
     /// Ranking of this role
     #[serde(default)]
     pub rank: i64,
+    /// Icon shown next to the names of members with this role
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<File>,
+    /// Number of members with this role, if requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member_count: Option<u64>,
 }
 
 /// Channel category
@@ -46,6 +58,15 @@ pub struct Category {
     pub title: String,
     /// Channels in this category
     pub channels: Vec<String>,
+    /// Default permissions inherited by channels in this category
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_permissions: Option<OverrideField>,
+    /// Role permissions inherited by channels in this category
+    #[serde(
+        default = "HashMap::<String, OverrideField>::new",
+        skip_serializing_if = "HashMap::<String, OverrideField>::is_empty"
+    )]
+    pub role_permissions: HashMap<String, OverrideField>,
 }
 
 /// System message channel assignments
@@ -63,6 +84,9 @@ pub struct SystemMessageChannels {
     /// ID of channel to send user banned messages in
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user_banned: Option<String>,
+    /// ID of channel to send role expiry messages in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role_expired: Option<String>,
 }
 
 /// Server flag enum
@@ -108,6 +132,8 @@ pub struct Server {
     )]
     pub roles: HashMap<String, Role>,
     /// Default set of server and channel permissions
+    ///
+    /// Superseded by the default role where one exists.
     pub default_permissions: i64,
 
     /// Icon attachment
@@ -130,6 +156,9 @@ pub struct Server {
     /// Whether this server should be publicly discoverable
     #[serde(skip_serializing_if = "if_false", default)]
     pub discoverable: bool,
+    /// Default colour of text embeds which do not specify one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embed_colour: Option<String>,
 }
 
 /// Optional fields on server object
@@ -140,10 +169,12 @@ pub enum FieldsServer {
     SystemMessages,
     Icon,
     Banner,
+    EmbedColour,
 }
 
 /// Optional fields on server object
 #[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
 pub enum FieldsRole {
     Colour,
+    Icon,
 }
@@ -1,3 +1,4 @@
+use iso8601_timestamp::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use super::server_member::MemberCompositeKey;
@@ -10,4 +11,10 @@ pub struct ServerBan {
     pub id: MemberCompositeKey,
     /// Reason for ban creation
     pub reason: Option<String>,
+    /// Id of the user who issued the ban
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moderator: Option<String>,
+    /// Time at which the ban is lifted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<Timestamp>,
 }
@@ -44,6 +44,18 @@ pub struct Member {
     /// Timestamp this member is timed out until
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout: Option<Timestamp>,
+    /// Roles which are removed from this member once they expire
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timed_roles: Option<Vec<TimedRole>>,
+}
+
+/// Role assigned to a member until a given time
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct TimedRole {
+    /// Role id
+    pub role: String,
+    /// Time at which the role is removed
+    pub expires_at: Timestamp,
 }
 
 /// Representation of a member of a server on Revolt With Role Data
@@ -77,6 +89,7 @@ pub enum FieldsMember {
     Avatar,
     Roles,
     Timeout,
+    TimedRoles,
 }
 
 /// Member removal intention
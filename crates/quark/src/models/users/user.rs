@@ -159,6 +159,9 @@ pub struct User {
     /// Bot information
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bot: Option<BotInformation>,
+    /// Whether this user's read position is hidden from other users
+    #[serde(skip_serializing_if = "if_false", default)]
+    pub hide_read_receipts: bool,
 
     // ? Entries below should never be pushed to the database
     /// Current session user's relationship with this user
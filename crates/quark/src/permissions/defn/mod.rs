@@ -31,6 +31,11 @@ pub struct OverrideField {
 }
 
 impl Override {
+    /// Create an override which only allows the given permissions
+    pub fn allow_only(allow: u64) -> Override {
+        Override { allow, deny: 0 }
+    }
+
     /// Into allows
     pub fn allows(&self) -> u64 {
         self.allow
@@ -24,8 +24,8 @@ pub enum Permission {
     ManageRole = 1 << 3,
     /// Manage server customisation (includes emoji)
     ManageCustomisation = 1 << 4,
-
-    // % 1 bit reserved
+    /// View the server audit log
+    ViewAuditLog = 1 << 5,
 
     // * Member permissions
     /// Kick other members below their ranking
@@ -84,7 +84,10 @@ pub enum Permission {
     MoveMembers = 1 << 35,
 
     // * Misc. permissions
-    // % Bits 36 to 52: free area
+    /// Mention roles, everyone or everyone online in a channel
+    MentionEveryone = 1 << 36,
+
+    // % Bits 37 to 52: free area
     // % Bits 53 to 64: do not use
 
     // * Grant all permissions
@@ -123,6 +126,7 @@ bitfield! {
     pub can_manage_permissions, _: 61;
     pub can_manage_roles, _: 60;
     pub can_manage_customisation, _: 59;
+    pub can_view_audit_log, _: 58;
 
     // * Member permissions
     pub can_kick_members, _: 57;
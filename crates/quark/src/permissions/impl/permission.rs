@@ -1,9 +1,11 @@
 use std::collections::HashSet;
 
 use crate::{
-    models::Channel, permissions::PermissionCalculator, Override, Permission, PermissionValue,
-    Permissions, Perms, Result, ALLOW_IN_TIMEOUT, DEFAULT_PERMISSION_DIRECT_MESSAGE,
-    DEFAULT_PERMISSION_SAVED_MESSAGES, DEFAULT_PERMISSION_VIEW_ONLY,
+    models::{server::DEFAULT_ROLE_ID, Channel},
+    permissions::PermissionCalculator,
+    Override, Permission, PermissionValue, Permissions, Perms, Result, ALLOW_IN_TIMEOUT,
+    DEFAULT_PERMISSION_DIRECT_MESSAGE, DEFAULT_PERMISSION_SAVED_MESSAGES,
+    DEFAULT_PERMISSION_VIEW_ONLY,
 };
 
 use super::super::Permission::GrantAllSafe;
@@ -51,8 +53,8 @@ async fn calculate_server_permission(
 
     let member = data.member.get().expect("Member should be present by now.");
 
-    // 3. Apply allows from default_permissions.
-    let mut permissions: PermissionValue = server.default_permissions.into();
+    // 3. Apply allows from the default role.
+    let mut permissions: PermissionValue = server.base_permissions().into();
 
     // 4. Resolve each role in order.
     let member_roles: HashSet<&String> = member.roles.iter().collect();
@@ -106,8 +108,19 @@ async fn calculate_channel_permission(
         0_u64.into()
     };
 
+    // Threads take their overrides from the channel they were started in.
+    let parent = match data.channel.get().unwrap() {
+        Channel::TextChannel {
+            parent: Some(parent),
+            ..
+        } => Some(db.fetch_channel(parent).await?),
+        _ => None,
+    };
+
     // Borrow the channel now and continue as normal.
-    let channel = data.channel.get().unwrap();
+    let channel = parent
+        .as_ref()
+        .unwrap_or_else(|| data.channel.get().unwrap());
 
     // 1. Check channel type.
     let value: PermissionValue = match channel {
@@ -166,11 +179,13 @@ async fn calculate_channel_permission(
             }
         }
         Channel::TextChannel {
+            id,
             default_permissions,
             role_permissions,
             ..
         }
         | Channel::VoiceChannel {
+            id,
             default_permissions,
             role_permissions,
             ..
@@ -188,16 +203,28 @@ async fn calculate_channel_permission(
                     return Ok((Permission::GrantAllSafe as u64).into());
                 }
 
-                // 3. Apply default allows and denies for channel.
-                if let Some(default) = default_permissions {
-                    permissions.apply((*default).into());
-                }
+                // Every member has the default role, which always ranks the lowest.
+                let default_role = DEFAULT_ROLE_ID.to_string();
+                let member_roles: HashSet<&String> = member
+                    .roles
+                    .iter()
+                    .chain(std::iter::once(&default_role))
+                    .collect();
+
+                // 3. Apply overrides inherited from the channel's category.
+                let category = server.categories.as_ref().and_then(|categories| {
+                    categories
+                        .iter()
+                        .find(|category| category.channels.contains(id))
+                });
 
-                // 4. Resolve each role in order.
-                let member_roles: HashSet<&String> = member.roles.iter().collect();
+                if let Some(category) = category {
+                    if let Some(default) = category.default_permissions {
+                        permissions.apply(default.into());
+                    }
 
-                if !member_roles.is_empty() {
-                    let mut roles = role_permissions
+                    let mut roles = category
+                        .role_permissions
                         .iter()
                         .filter(|(id, _)| member_roles.contains(id))
                         .filter_map(|(id, permission)| {
@@ -210,13 +237,36 @@ async fn calculate_channel_permission(
 
                     roles.sort_by(|a, b| b.0.cmp(&a.0));
 
-                    // 5. Apply allows and denies from roles.
                     for (_, v) in roles {
                         permissions.apply(v);
                     }
                 }
 
-                // 5. Revoke permissions if member is timed out.
+                // 4. Apply default allows and denies for channel.
+                if let Some(default) = default_permissions {
+                    permissions.apply((*default).into());
+                }
+
+                // 5. Resolve each role in order.
+                let mut roles = role_permissions
+                    .iter()
+                    .filter(|(id, _)| member_roles.contains(id))
+                    .filter_map(|(id, permission)| {
+                        server.roles.get(id).map(|role| {
+                            let v: Override = (*permission).into();
+                            (role.rank, v)
+                        })
+                    })
+                    .collect::<Vec<(i64, Override)>>();
+
+                roles.sort_by(|a, b| b.0.cmp(&a.0));
+
+                // 6. Apply allows and denies from roles.
+                for (_, v) in roles {
+                    permissions.apply(v);
+                }
+
+                // 7. Revoke permissions if member is timed out.
                 if member.in_timeout() {
                     permissions.restrict(*ALLOW_IN_TIMEOUT);
                 }
@@ -43,6 +43,12 @@ pub async fn worker(db: Database) {
 
     loop {
         let task = Q.pop().await;
+
+        // Messages are sent without embeds while January is unavailable
+        if onechatsocial_database::tasks::process_embeds::is_paused() {
+            continue;
+        }
+
         let db = db.clone();
         let semaphore = semaphore.clone();
 
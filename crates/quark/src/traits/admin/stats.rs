@@ -2,5 +2,6 @@ use crate::{models::stats::Stats, Result};
 
 #[async_trait]
 pub trait AbstractStats: Sync + Send {
-    async fn generate_stats(&self) -> Result<Stats>;
+    /// Generate stats, recomputing cached collection sizes if `refresh` is set
+    async fn generate_stats(&self, refresh: bool) -> Result<Stats>;
 }
@@ -1,4 +1,6 @@
-use crate::models::message::{AppendMessage, Message, MessageQuery, PartialMessage};
+use crate::models::message::{
+    AppendMessage, Message, MessageQuery, PartialMessage, SearchCursor, SearchResult,
+};
 use crate::Result;
 
 #[async_trait]
@@ -24,6 +26,15 @@ pub trait AbstractMessage: Sync + Send {
     /// Fetch multiple messages by given query
     async fn fetch_messages(&self, query: MessageQuery) -> Result<Vec<Message>>;
 
+    /// Search for messages matching the query's text search
+    ///
+    /// Relevance sorted searches ignore `before` and `after` and continue from the cursor instead.
+    async fn search_messages(
+        &self,
+        query: MessageQuery,
+        cursor: Option<SearchCursor>,
+    ) -> Result<Vec<SearchResult>>;
+
     /// Add a new reaction to a message
     async fn add_reaction(&self, id: &str, emoji: &str, user: &str) -> Result<()>;
 
@@ -13,6 +13,9 @@ pub trait AbstractServerBan: Sync + Send {
     /// Insert new ban into database
     async fn insert_ban(&self, ban: &ServerBan) -> Result<()>;
 
+    /// Insert a ban or replace an existing one in a single write
+    async fn upsert_ban(&self, ban: &ServerBan) -> Result<()>;
+
     /// Delete a ban from the database
     async fn delete_ban(&self, id: &MemberCompositeKey) -> Result<()>;
 }
@@ -3,6 +3,8 @@ use crate::models::server_member::{
 };
 use crate::Result;
 
+use std::collections::HashMap;
+
 #[async_trait]
 pub trait AbstractServerMember: Sync + Send {
     /// Fetch a server member by their id
@@ -38,4 +40,10 @@ pub trait AbstractServerMember: Sync + Send {
 
     /// Fetch server count of a user
     async fn fetch_server_count(&self, user: &str) -> Result<usize>;
+
+    /// Fetch the number of members holding each role in a server
+    async fn fetch_role_member_counts(&self, server: &str) -> Result<HashMap<String, u64>>;
+
+    /// Fetch the user ids of all members in a server
+    async fn fetch_member_ids(&self, server: &str) -> Result<Vec<String>>;
 }
@@ -3,6 +3,7 @@ use async_std::task::spawn;
 use futures::future::join_all;
 use linkify::{LinkFinder, LinkKind};
 use once_cell::sync::Lazy;
+use onechatsocial_database::tasks::process_embeds;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashSet, sync::Arc};
@@ -244,22 +245,27 @@ impl Embed {
             tasks.push(spawn(async move {
                 let guard = semaphore.acquire().await;
 
-                let response = client.get(url).query(&[("url", link)]).send().await.ok()?;
+                let response = match client.get(url).query(&[("url", link)]).send().await {
+                    Ok(response) if !response.status().is_server_error() => response,
+                    _ => return (false, None),
+                };
 
                 drop(guard);
 
                 if response.status().is_success() {
-                    response.json::<Embed>().await.ok()
+                    (true, response.json::<Embed>().await.ok())
                 } else {
-                    None
+                    (true, None)
                 }
             }));
         }
 
-        let embeds = join_all(tasks)
-            .await
+        let results = join_all(tasks).await;
+        process_embeds::record_result(results.iter().any(|(reachable, _)| *reachable));
+
+        let embeds = results
             .into_iter()
-            .flatten()
+            .filter_map(|(_, embed)| embed)
             .collect::<Vec<Embed>>();
 
         // Prevent database update when no embeds are found.
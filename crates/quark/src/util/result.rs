@@ -79,6 +79,9 @@ pub enum Error {
     TooManyRoles {
         max: usize,
     },
+    TooManyCategories {
+        max: usize,
+    },
 
     // ? Bot related errors
     ReachedMaximumBots,
@@ -147,8 +150,13 @@ impl Error {
     }
 
     /// Create a error from core error
+    ///
+    /// Errors caused by the request itself keep their meaning rather than becoming a server error.
     pub fn from_core(error: onechatsocial_result::Error) -> Error {
-        Error::Core { error }
+        match error.error_type {
+            onechatsocial_result::ErrorType::InvalidProperty => Error::InvalidProperty,
+            _ => Error::Core { error },
+        }
     }
 }
 
@@ -196,6 +204,7 @@ impl<'r> Responder<'r, 'static> for Error {
             Error::TooManyEmoji { .. } => Status::BadRequest,
             Error::TooManyChannels { .. } => Status::BadRequest,
             Error::TooManyRoles { .. } => Status::BadRequest,
+            Error::TooManyCategories { .. } => Status::BadRequest,
             Error::TooManyEmbeds { .. } => Status::BadRequest,
 
             Error::ReachedMaximumBots => Status::BadRequest,